@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::error;
+
+// Limits of each dumped frame, enough to spot a wrong channel index
+pub const FRAME_DUMP_ROWS: usize = 16;
+pub const FRAME_DUMP_COLUMNS: usize = 8;
+
+// Number of frames kept in the dump file
+const FRAME_DUMP_CAPACITY: usize = 64;
+
+/// Ring-buffer file with the last raw frames returned by `get_board_data`.
+///
+/// The file is rewritten on every frame and only contains the most recent
+/// `FRAME_DUMP_CAPACITY` frames, so it can be left enabled during long sessions.
+pub struct FrameDumper {
+    path: String,
+    frames: VecDeque<String>,
+}
+
+impl FrameDumper {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            frames: VecDeque::with_capacity(FRAME_DUMP_CAPACITY),
+        }
+    }
+
+    /// Records a frame and rewrites the dump file.
+    ///
+    /// # Arguments
+    /// * `label`: Where the frame comes from, e.g. `impedance` or `raw`.
+    /// * `shape`: The full shape of the returned array (rows, samples).
+    /// * `rows`: The first rows of the array, already truncated to the dump limits.
+    pub fn record(&mut self, label: &str, shape: (usize, usize), rows: &[Vec<f64>]) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+
+        let mut frame = format!("[{}] {} shape=({}, {})\n", timestamp, label, shape.0, shape.1);
+        for (index, row) in rows.iter().enumerate() {
+            frame.push_str(&format!("  row {:>2}: {:?}\n", index, row));
+        }
+
+        if self.frames.len() >= FRAME_DUMP_CAPACITY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+
+        let content = self.frames.iter().cloned().collect::<String>();
+        if let Err(e) = std::fs::write(&self.path, content) {
+            error!("Could not write BrainFlow frame dump {}: {}", self.path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_frame_dump_keeps_last_frames() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("frames.log");
+        let mut dumper = FrameDumper::new(path.to_str().unwrap());
+
+        for i in 0..FRAME_DUMP_CAPACITY + 1 {
+            dumper.record("raw", (10, 62), &[vec![i as f64, 1.0]]);
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("raw shape=(10, 62)").count(), FRAME_DUMP_CAPACITY);
+        assert!(!content.contains("row  0: [0.0, 1.0]"));
+        assert!(content.contains(&format!("row  0: [{:?}, 1.0]", FRAME_DUMP_CAPACITY as f64)));
+    }
+}
\ No newline at end of file
@@ -1 +1,2 @@
 pub mod brainbit_headset;
+pub mod frame_dump;
\ No newline at end of file
@@ -0,0 +1,179 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use neural_analytics_domain::domain::{models::bulb_state::BulbState, ports::output::smart_bulb::SmartBulbPort};
+
+/// Failures and latencies injected by the `FlakyBulbAdapter`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlakyBulbConfig {
+    /// Fraction of the actuations failing with a transient error, from 0 to 1.
+    pub failure_rate: f64,
+    pub min_latency: Duration,
+    pub max_latency: Duration,
+    /// Seed of the injected failures and latencies, so a failing run can be replayed.
+    pub seed: u64,
+}
+
+impl Default for FlakyBulbConfig {
+    /// A bulb on a congested WiFi network, failing a third of the time.
+    fn default() -> Self {
+        Self {
+            failure_rate: 0.3,
+            min_latency: Duration::from_millis(5),
+            max_latency: Duration::from_millis(50),
+            seed: 0,
+        }
+    }
+}
+
+/// Actuation of the bulb attempted by the core, as recorded by the `FlakyBulbAdapter`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActuationAttempt {
+    /// When the bulb answered, once its latency elapsed.
+    pub at: Instant,
+    pub state: BulbState,
+    pub is_failed: bool,
+}
+
+/// Bulb failing and answering late on purpose, to exercise the retries of the core
+/// under the conditions of a real network.
+///
+/// Failures are reported as timeouts, which the core retries. Clones share the
+/// recorded attempts, so a test keeps a handle on the adapter it gives the core.
+#[derive(Clone)]
+pub struct FlakyBulbAdapter {
+    config: FlakyBulbConfig,
+    rng: Arc<Mutex<StdRng>>,
+    attempts: Arc<Mutex<Vec<ActuationAttempt>>>,
+}
+
+impl FlakyBulbAdapter {
+    pub fn new(config: FlakyBulbConfig) -> Self {
+        Self {
+            config,
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(config.seed))),
+            attempts: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Every actuation attempted so far, oldest first.
+    pub fn attempts(&self) -> Vec<ActuationAttempt> {
+        self.attempts.lock().map(|attempts| attempts.clone()).unwrap_or_default()
+    }
+
+    /// State of the bulb after the last successful actuation, `None` before the first one.
+    pub fn state(&self) -> Option<BulbState> {
+        self.attempts()
+            .iter()
+            .rev()
+            .find(|attempt| !attempt.is_failed)
+            .map(|attempt| attempt.state)
+    }
+
+    /// Most successful actuations within any span of the given length, to check the
+    /// bulb is never driven faster than it can follow.
+    pub fn max_actuations_within(&self, span: Duration) -> usize {
+        let actuations: Vec<Instant> = self
+            .attempts()
+            .iter()
+            .filter(|attempt| !attempt.is_failed)
+            .map(|attempt| attempt.at)
+            .collect();
+
+        actuations
+            .iter()
+            .enumerate()
+            .map(|(index, start)| {
+                actuations[index..]
+                    .iter()
+                    .take_while(|at| at.duration_since(*start) < span)
+                    .count()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Waits for the injected latency, then fails or records the new state.
+    async fn actuate(&self, state: BulbState) -> Result<(), String> {
+        let (latency, is_failed) = {
+            let mut rng = self.rng.lock().map_err(|e| e.to_string())?;
+            let min_micros = self.config.min_latency.as_micros() as u64;
+            let max_micros = (self.config.max_latency.as_micros() as u64).max(min_micros);
+
+            (
+                Duration::from_micros(rng.gen_range(min_micros..=max_micros)),
+                rng.gen_bool(self.config.failure_rate.clamp(0.0, 1.0)),
+            )
+        };
+
+        tokio::time::sleep(latency).await;
+
+        self.attempts.lock().map_err(|e| e.to_string())?.push(ActuationAttempt {
+            at: Instant::now(),
+            state,
+            is_failed,
+        });
+
+        if is_failed {
+            return Err(format!("Request timed out after {} ms (injected failure)", latency.as_millis()));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SmartBulbPort for FlakyBulbAdapter {
+    async fn change_state(&self, state: BulbState) -> Result<(), String> {
+        self.actuate(state).await
+    }
+
+    async fn change_color(&self, _red: u8, _green: u8, _blue: u8) -> Result<(), String> {
+        self.actuate(BulbState::BulbOn).await
+    }
+
+    fn is_simulated(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_injected_failures_follow_the_rate_and_the_seed() {
+        let config = FlakyBulbConfig {
+            failure_rate: 0.5,
+            min_latency: Duration::ZERO,
+            max_latency: Duration::from_millis(1),
+            seed: 42,
+        };
+
+        let bulb = FlakyBulbAdapter::new(config);
+        let replayed = FlakyBulbAdapter::new(config);
+
+        for _ in 0..100 {
+            let result = bulb.change_state(BulbState::BulbOn).await;
+            let _ = replayed.change_state(BulbState::BulbOn).await;
+
+            if let Err(e) = result {
+                assert!(e.contains("timed out"));
+            }
+        }
+
+        let failures = |bulb: &FlakyBulbAdapter| -> Vec<bool> {
+            bulb.attempts().iter().map(|attempt| attempt.is_failed).collect()
+        };
+        let failed = failures(&bulb).iter().filter(|is_failed| **is_failed).count();
+
+        assert_eq!(failures(&bulb), failures(&replayed));
+        assert!((30..=70).contains(&failed));
+        assert_eq!(bulb.state(), Some(BulbState::BulbOn));
+        assert_eq!(bulb.max_actuations_within(Duration::ZERO), 0);
+        assert_eq!(bulb.max_actuations_within(Duration::from_secs(60)), 100 - failed);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod flaky_bulb;
+pub mod null_bulb;
+pub mod synthetic_headset;
+
+#[cfg(test)]
+mod soak;
@@ -0,0 +1,32 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use neural_analytics_domain::domain::{models::bulb_state::BulbState, ports::output::smart_bulb::SmartBulbPort};
+
+/// Bulb without a device behind it, remembering its last state.
+///
+/// Clones share the same state, so a test can check the bulb it handed to the core.
+#[derive(Debug, Clone, Default)]
+pub struct NullBulbAdapter {
+    state: Arc<Mutex<Option<BulbState>>>,
+}
+
+impl NullBulbAdapter {
+    /// Last state the bulb was changed to, `None` before the first change.
+    pub fn state(&self) -> Option<BulbState> {
+        self.state.lock().ok().and_then(|state| *state)
+    }
+}
+
+#[async_trait]
+impl SmartBulbPort for NullBulbAdapter {
+    async fn change_state(&self, state: BulbState) -> Result<(), String> {
+        *self.state.lock().map_err(|e| e.to_string())? = Some(state);
+        Ok(())
+    }
+
+    fn is_simulated(&self) -> bool {
+        true
+    }
+}
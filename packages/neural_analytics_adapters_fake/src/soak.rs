@@ -0,0 +1,64 @@
+//! Soak of the pipeline against the synthetic headset, which streams hours of
+//! recording in minutes.
+
+use std::time::Duration;
+
+use neural_analytics_domain::domain::{
+    models::{core_config::CoreConfig, soak_report::SoakThresholds},
+    services::soak_service::run_soak_test,
+};
+
+use crate::synthetic_headset::SyntheticHeadsetAdapter;
+
+// Seconds the soak runs for when `NEURAL_ANALYTICS_SOAK_SECS` is not set
+const DEFAULT_SOAK_SECS: u64 = 600;
+
+#[tokio::test]
+async fn test_soak_report_flags_buffers_growth() {
+    // A short run is too noisy for the memory and latency thresholds
+    let thresholds = SoakThresholds {
+        max_memory_growth_bytes: i64::MAX,
+        max_latency_drift_ms: f64::INFINITY,
+        ..SoakThresholds::default()
+    };
+    let mut report = run_soak_test(
+        Box::new(SyntheticHeadsetAdapter::default()),
+        Duration::from_millis(200),
+        CoreConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    assert!(report.windows > 0);
+    assert!(report.simulated_secs > 0.0);
+    assert!(report.violations(&thresholds).is_empty());
+
+    report.buffers_growth_bytes = thresholds.max_buffers_growth_bytes + 1;
+    assert_eq!(report.violations(&thresholds).len(), 1);
+}
+
+/// Nightly soak of the pipeline, for `NEURAL_ANALYTICS_SOAK_SECS` seconds:
+///
+/// ```sh
+/// NEURAL_ANALYTICS_SOAK_SECS=3600 cargo test --release -p neural_analytics_adapters_fake soak -- --ignored --nocapture
+/// ```
+#[tokio::test]
+#[ignore = "runs for minutes, meant for the nightly soak"]
+async fn test_soak_pipeline_stays_within_thresholds() {
+    let secs = std::env::var("NEURAL_ANALYTICS_SOAK_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(DEFAULT_SOAK_SECS);
+
+    let report = run_soak_test(
+        Box::new(SyntheticHeadsetAdapter::default()),
+        Duration::from_secs(secs),
+        CoreConfig::default(),
+    )
+    .await
+    .unwrap();
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+
+    let violations = report.violations(&SoakThresholds::default());
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use neural_analytics_domain::domain::{
+    models::{eeg_work_modes::WorkMode, signal_unit::SignalUnit, synthetic_signal::SyntheticSignalConfig},
+    ports::input::eeg_headset::{EegHeadsetPort, SAMPLE_RATE_HZ},
+    services::{
+        eeg_synthesizer_service::EegSynthesizer,
+        window_assembler_service::WINDOW_SAMPLES,
+    },
+};
+
+// Electrodes of the BrainBit headset, in the hardware order
+const ELECTRODES: [&str; 4] = ["T3", "T4", "O1", "O2"];
+
+// Impedance of a good contact, in kOhm, reported until a tester changes it
+const DEFAULT_IMPEDANCE: u16 = 500;
+
+/// Impedances reported by the synthetic headset, shared with the frontends that change them.
+///
+/// Clones share the same values, so a slider can drive the calibration screen
+/// through the whole quality range without touching hardware.
+#[derive(Debug, Clone)]
+pub struct SimulatedImpedance(Arc<RwLock<HashMap<String, u16>>>);
+
+impl Default for SimulatedImpedance {
+    fn default() -> Self {
+        let impedances = ELECTRODES
+            .iter()
+            .map(|electrode| (electrode.to_string(), DEFAULT_IMPEDANCE))
+            .collect();
+
+        Self(Arc::new(RwLock::new(impedances)))
+    }
+}
+
+impl SimulatedImpedance {
+    /// Changes the impedance reported for an electrode.
+    ///
+    /// # Arguments
+    /// * `electrode`: The hardware name of the electrode, such as `O1`.
+    /// * `impedance`: The impedance, in kOhm.
+    ///
+    /// # Returns
+    /// * `Result<(), String>`: An error if the headset has no such electrode.
+    pub fn set(&self, electrode: &str, impedance: u16) -> Result<(), String> {
+        let mut impedances = self
+            .0
+            .write()
+            .map_err(|e| format!("Failed to update the simulated impedance: {}", e))?;
+
+        match impedances.get_mut(electrode) {
+            Some(current) => {
+                *current = impedance;
+                Ok(())
+            }
+            None => Err(format!("Unknown electrode: {}", electrode)),
+        }
+    }
+
+    /// Impedance of every electrode, in kOhm.
+    pub fn values(&self) -> HashMap<String, u16> {
+        self.0.read().map(|impedances| impedances.clone()).unwrap_or_default()
+    }
+}
+
+// Start of the stream paced in real time, and the samples streamed since
+struct StreamPace {
+    started: Instant,
+    samples: u64,
+}
+
+/// Headset streaming synthetic EEG, so the pipeline can be run without hardware.
+///
+/// The signal comes from an `EegSynthesizer`, with the spectrum of a real recording.
+/// By default every window is returned right away, so the benchmark measures the
+/// processing of the machine and not the pace of a real device. In real time, the
+/// windows are paced against the connection rather than by a sleep per window, so the
+/// time spent processing them does not lower the sampling rate. The impedances are
+/// the ones set through its `SimulatedImpedance`.
+pub struct SyntheticHeadsetAdapter {
+    is_connected: AtomicBool,
+    // Whether windows are streamed at the pace of the real headset
+    is_real_time: bool,
+    // Synthesizer of the samples, so consecutive windows continue the same signal
+    synthesizer: Mutex<EegSynthesizer>,
+    // Pace of the real-time stream, restarted by every connection
+    pace: Mutex<Option<StreamPace>>,
+    // Samples returned by each extraction, a whole window unless the windows overlap
+    hop_samples: AtomicUsize,
+    work_mode: WorkMode,
+    impedance: SimulatedImpedance,
+}
+
+impl Default for SyntheticHeadsetAdapter {
+    fn default() -> Self {
+        Self {
+            is_connected: AtomicBool::new(false),
+            is_real_time: false,
+            synthesizer: Mutex::new(EegSynthesizer::new(&ELECTRODES, &SyntheticSignalConfig::default())),
+            pace: Mutex::new(None),
+            hop_samples: AtomicUsize::new(WINDOW_SAMPLES),
+            work_mode: WorkMode::Initialized,
+            impedance: SimulatedImpedance::default(),
+        }
+    }
+}
+
+impl SyntheticHeadsetAdapter {
+    /// Headset streaming its windows at the pace of the real one, to run the application in mock mode.
+    pub fn real_time() -> Self {
+        Self { is_real_time: true, ..Self::default() }
+    }
+
+    /// Streams the signal of the given configuration instead of the default one.
+    pub fn with_signal(self, signal: &SyntheticSignalConfig) -> Self {
+        Self {
+            synthesizer: Mutex::new(EegSynthesizer::new(&ELECTRODES, signal)),
+            ..self
+        }
+    }
+
+    /// Waits until the samples are due at the pace of the real headset.
+    ///
+    /// A consumer falling behind by more than a window, such as a paused capture, does not
+    /// get the samples it missed in a burst: the pace restarts from the current time.
+    fn wait_for_samples(&self, samples: usize) {
+        let Ok(mut pace) = self.pace.lock() else {
+            return;
+        };
+
+        let now = Instant::now();
+        let pace = pace.get_or_insert(StreamPace { started: now, samples: 0 });
+        pace.samples += samples as u64;
+
+        let due = pace.started + Duration::from_secs_f64(pace.samples as f64 / SAMPLE_RATE_HZ as f64);
+        let window = Duration::from_secs_f64(WINDOW_SAMPLES as f64 / SAMPLE_RATE_HZ as f64);
+
+        if now > due + window {
+            pace.started = now - Duration::from_secs_f64(pace.samples as f64 / SAMPLE_RATE_HZ as f64);
+        } else if let Some(wait) = due.checked_duration_since(now) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// Handle to change the impedances the headset reports.
+    pub fn simulated_impedance(&self) -> SimulatedImpedance {
+        self.impedance.clone()
+    }
+}
+
+impl EegHeadsetPort for SyntheticHeadsetAdapter {
+    fn connect(&self) -> Result<(), String> {
+        if let Ok(mut pace) = self.pace.lock() {
+            *pace = None;
+        }
+
+        self.is_connected.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.is_connected.load(Ordering::SeqCst)
+    }
+
+    fn disconnect(&mut self) -> Result<(), String> {
+        self.is_connected.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String> {
+        Ok(self.impedance.values())
+    }
+
+    fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String> {
+        let samples = self.hop_samples.load(Ordering::SeqCst);
+
+        if self.is_real_time {
+            self.wait_for_samples(samples);
+        }
+
+        self.synthesizer
+            .lock()
+            .map(|mut synthesizer| synthesizer.next_samples(samples))
+            .map_err(|e| format!("Failed to synthesize the EEG: {}", e))
+    }
+
+    fn signal_unit(&self) -> SignalUnit {
+        SignalUnit::Microvolts
+    }
+
+    fn set_window_hop(&self, samples: usize) {
+        self.hop_samples.store(samples.clamp(1, WINDOW_SAMPLES), Ordering::SeqCst);
+    }
+
+    fn change_work_mode(&mut self, mode: WorkMode) {
+        self.work_mode = mode;
+    }
+
+    fn get_work_mode(&self) -> WorkMode {
+        self.work_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthetic_headset_streams_continuous_windows() {
+        let headset = SyntheticHeadsetAdapter::default();
+        headset.connect().unwrap();
+
+        let first = headset.extract_raw_data().unwrap();
+        let second = headset.extract_raw_data().unwrap();
+
+        assert!(headset.is_connected());
+        assert_eq!(first.len(), 4);
+        assert_eq!(first["O1"].len(), WINDOW_SAMPLES);
+        assert_ne!(first["O1"], second["O1"]);
+    }
+
+    #[test]
+    fn test_simulated_impedance_drives_the_reported_impedances() {
+        let headset = SyntheticHeadsetAdapter::default();
+        let impedance = headset.simulated_impedance();
+
+        impedance.set("O1", 2_500).unwrap();
+        assert!(impedance.set("Fp1", 100).is_err());
+
+        let reported = headset.extract_impedance_data().unwrap();
+        assert_eq!(reported["O1"], 2_500);
+        assert_eq!(reported["T3"], DEFAULT_IMPEDANCE);
+    }
+}
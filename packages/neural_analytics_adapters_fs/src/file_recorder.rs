@@ -0,0 +1,523 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::mpsc::{channel, Sender};
+use std::thread::JoinHandle;
+
+use log::{error, info};
+use neural_analytics_domain::domain::{
+    models::{
+        recording_config::{RecordingConfig, RecordingFormat},
+        signal_unit::SignalUnit,
+    },
+    ports::{input::eeg_headset::SAMPLE_RATE_HZ, output::recorder::RecorderPort},
+    services::window_labeler_service::{ProtocolWindowLabeler, WindowInfo, WindowLabeler},
+};
+
+// Milliseconds between two samples of the headset
+const SAMPLE_PERIOD_MS: f64 = 1000.0 / SAMPLE_RATE_HZ as f64;
+
+// Name of the recording in the directory of a session
+const SESSION_RECORDING_NAME: &str = "eeg";
+
+// Columns of the CSV recordings before the one of each channel
+const CSV_COLUMNS: [&str; 5] = ["window_id", "sample", "timestamp", "sample_timestamp", "unit"];
+
+// Line of the recording file, timestamps in Unix epoch milliseconds
+#[derive(serde::Serialize)]
+struct RecordedWindow<'a> {
+    timestamp: i64,
+    // Acquisition time of the last sample, from the board clock corrected for drift
+    sample_timestamp: Option<i64>,
+    window_id: u64,
+    unit: SignalUnit,
+    // Training label given by the labeler of the recording, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    headset_data: &'a HashMap<String, Vec<f32>>,
+}
+
+// Writes the lines of a recording on a thread of its own, so recording a window does not
+// wait for the disk while the context is locked
+struct RecordingWriter {
+    sender: Sender<(u64, String)>,
+    thread: JoinHandle<File>,
+}
+
+impl RecordingWriter {
+    fn spawn(mut file: File) -> Self {
+        let (sender, receiver) = channel::<(u64, String)>();
+        let thread = std::thread::spawn(move || {
+            for (window_id, lines) in receiver {
+                if let Err(e) = file.write_all(lines.as_bytes()) {
+                    error!("Could not record window {}: {}", window_id, e);
+                }
+            }
+
+            file
+        });
+
+        Self { sender, thread }
+    }
+
+    fn write(&self, window_id: u64, lines: String) {
+        // The thread only stops once the sender is dropped
+        let _ = self.sender.send((window_id, lines));
+    }
+
+    /// Waits for the lines still queued, then syncs the file.
+    fn close(self) {
+        drop(self.sender);
+
+        match self.thread.join() {
+            Ok(file) => {
+                if let Err(e) = file.sync_all() {
+                    error!("Could not sync the recording: {}", e);
+                }
+            }
+            Err(_) => error!("The writer of the recording panicked"),
+        }
+    }
+}
+
+/// Records the extracted EEG windows to a JSON Lines or a CSV file, between `start` and
+/// `stop`, or to a directory per session with a `RecordingConfig`.
+///
+/// The CSV recordings hold a row per sample, with the estimated acquisition time of each
+/// and a column per channel, fixed by the first window of the file. The lines are written
+/// to the file in the background, and `stop` waits for them.
+///
+/// The windows of the JSON Lines recordings carry the `label` of the window labeler, the
+/// guided protocol of the configuration by default, so they can train the model as is.
+#[derive(Default)]
+pub struct FileRecorderAdapter {
+    file: Option<RecordingWriter>,
+    path: Option<String>,
+    // Path given to `start`, the rotated sessions are recorded next to it
+    base_path: Option<String>,
+    // Unit of the samples of the headset, written on every line
+    unit: SignalUnit,
+    format: RecordingFormat,
+    // Channels of the columns of a CSV recording, in order
+    columns: Vec<String>,
+    config: RecordingConfig,
+    // File of the session recorded by the configuration, opened with its first window
+    session_path: Option<String>,
+    // Labels the windows of the recording in progress
+    labeler: Option<Box<dyn WindowLabeler>>,
+}
+
+impl FileRecorderAdapter {
+    /// Creates the recorder, recording every session if the configuration sets a directory.
+    pub fn new(config: RecordingConfig) -> Self {
+        FileRecorderAdapter {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Labels the windows recorded from now on, replacing the guided protocol of the
+    /// configuration until the recording stops. `None` leaves them unlabeled.
+    pub fn set_labeler(&mut self, labeler: Option<Box<dyn WindowLabeler>>) {
+        self.labeler = labeler;
+    }
+
+    // Labeler of the guided protocol of the configuration, started now
+    fn protocol_labeler(&self) -> Option<Box<dyn WindowLabeler>> {
+        self.config.guided_protocol.clone().map(|protocol| {
+            Box::new(ProtocolWindowLabeler::new(protocol, chrono::Utc::now().timestamp_millis())) as Box<dyn WindowLabeler>
+        })
+    }
+
+    fn open(&mut self, path: &str) -> Result<(), String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(path)
+            .map_err(|e| format!("Could not open recording {}: {}", path, e))?;
+
+        self.format = RecordingFormat::of_path(path);
+        // Appending to a CSV recording keeps the columns of its header
+        self.columns = match self.format {
+            RecordingFormat::Csv => csv_channels(&file),
+            RecordingFormat::JsonLines => Vec::new(),
+        };
+
+        info!("Recording EEG windows to {}", path);
+        self.file = Some(RecordingWriter::spawn(file));
+        self.path = Some(path.to_string());
+
+        Ok(())
+    }
+
+    /// Syncs the file being recorded to before closing it, so its last windows are on disk.
+    fn close_file(&mut self) {
+        if let Some(writer) = self.file.take() {
+            writer.close();
+        }
+    }
+
+    // Opens the file of the session recorded by the configuration, with the directory of the session
+    fn open_session(&mut self) {
+        let Some(session_path) = self.session_path.clone() else {
+            return;
+        };
+
+        let opened = Path::new(&session_path)
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .map_err(|e| format!("Could not create the directory of recording {}: {}", session_path, e))
+            .and_then(|_| self.open(&session_path));
+
+        if let Err(e) = opened {
+            // Not retried on every window, the next session tries again
+            error!("{}", e);
+            self.session_path = None;
+        }
+    }
+}
+
+impl RecorderPort for FileRecorderAdapter {
+    /// Starts recording to the given file, appending to it if it exists.
+    ///
+    /// The file is a CSV one if its extension is `.csv`, a JSON Lines one otherwise.
+    /// A recording in progress is stopped first.
+    ///
+    /// # Returns
+    /// * `Result<(), String>`: An error if the file cannot be opened.
+    fn start(&mut self, path: &str) -> Result<(), String> {
+        self.stop();
+        self.open(path)?;
+        self.base_path = Some(path.to_string());
+        self.labeler = self.protocol_labeler();
+
+        Ok(())
+    }
+
+    /// Starts recording a session to its own directory, if the configuration sets one
+    /// and nothing is being recorded.
+    ///
+    /// The directory and the file are created with the first window of the session, so
+    /// a session without data leaves nothing behind.
+    ///
+    /// # Returns
+    /// * `Option<String>`: The file the session will be recorded to, if any.
+    fn start_session(&mut self, session_id: u64) -> Option<String> {
+        let directory = self.config.directory.as_ref()?;
+
+        if self.path.is_some() {
+            return None;
+        }
+
+        let session_dir = format!("session-{}-{}", session_id, chrono::Utc::now().format("%Y%m%dT%H%M%S"));
+        let path = Path::new(directory)
+            .join(session_dir)
+            .join(format!("{}.{}", SESSION_RECORDING_NAME, self.config.format.extension()))
+            .to_string_lossy()
+            .into_owned();
+
+        self.session_path = Some(path.clone());
+        self.labeler = self.protocol_labeler();
+        Some(path)
+    }
+
+    /// Stops the recording of the session started by `start_session`, if it is the one in progress.
+    fn end_session(&mut self) {
+        let Some(session_path) = self.session_path.take() else {
+            return;
+        };
+
+        if self.path.as_deref() == Some(session_path.as_str()) {
+            self.close_file();
+            self.path = None;
+            self.labeler = None;
+            info!("Stopped recording to {}", session_path);
+        }
+    }
+
+    /// Continues the recording in progress in a new file for the given session.
+    ///
+    /// The file is named after the one given to `start`, e.g. `eeg.session-2.jsonl`.
+    ///
+    /// # Returns
+    /// * `Result<Option<String>, String>`: The new file, `None` when not recording, or an error if it cannot be opened.
+    fn rotate(&mut self, session_id: u64) -> Result<Option<String>, String> {
+        let Some(base_path) = self.base_path.clone() else {
+            return Ok(None);
+        };
+
+        let path = session_path(&base_path, session_id);
+        self.close_file();
+        self.path = None;
+
+        self.open(&path).map(|_| Some(path))
+    }
+
+    /// Sets the unit of the samples, as reported by the headset.
+    fn set_unit(&mut self, unit: SignalUnit) {
+        self.unit = unit;
+    }
+
+    /// Stops the recording in progress, returning the file it was written to.
+    fn stop(&mut self) -> Option<String> {
+        self.close_file();
+        self.base_path = None;
+        self.session_path = None;
+        self.labeler = None;
+        let path = self.path.take();
+
+        if let Some(path) = &path {
+            info!("Stopped recording to {}", path);
+        }
+
+        path
+    }
+
+    /// Returns the file being recorded to, if any.
+    fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Appends a window to the recording, if one is in progress.
+    ///
+    /// # Arguments
+    /// * `window_id`: The id of the window.
+    /// * `headset_data`: The samples of each channel.
+    /// * `sample_timestamp`: The acquisition time of the last sample, if the headset reports it.
+    fn record_window(
+        &mut self,
+        window_id: u64,
+        headset_data: &HashMap<String, Vec<f32>>,
+        sample_timestamp: Option<i64>,
+    ) {
+        if self.file.is_none() {
+            self.open_session();
+        }
+
+        let Some(writer) = self.file.as_ref() else {
+            return;
+        };
+
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let lines = match self.format {
+            RecordingFormat::JsonLines => {
+                let label = self.labeler.as_ref().and_then(|labeler| {
+                    labeler.label(&WindowInfo {
+                        window_id,
+                        window_timestamp: timestamp,
+                    })
+                });
+                let window = RecordedWindow {
+                    timestamp,
+                    sample_timestamp,
+                    window_id,
+                    unit: self.unit,
+                    label,
+                    headset_data,
+                };
+
+                serde_json::to_string(&window).map(|line| line + "\n").map_err(|e| e.to_string())
+            }
+            RecordingFormat::Csv => {
+                let mut rows = String::new();
+
+                if self.columns.is_empty() {
+                    self.columns = headset_data.keys().cloned().collect();
+                    self.columns.sort_unstable();
+                    let header: Vec<&str> = CSV_COLUMNS.iter().copied().chain(self.columns.iter().map(String::as_str)).collect();
+                    rows.push_str(&header.join(","));
+                    rows.push('\n');
+                }
+
+                rows.push_str(&csv_rows(window_id, timestamp, sample_timestamp, self.unit, &self.columns, headset_data));
+                Ok(rows)
+            }
+        };
+
+        match lines {
+            Ok(lines) => writer.write(window_id, lines),
+            Err(e) => error!("Could not record window {}: {}", window_id, e),
+        }
+    }
+}
+
+/// Inserts the session before the extension of the path
+fn session_path(base_path: &str, session_id: u64) -> String {
+    let path = Path::new(base_path);
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("recording");
+    let file_name = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{}.session-{}.{}", stem, session_id, extension),
+        None => format!("{}.session-{}", stem, session_id),
+    };
+
+    path.with_file_name(file_name).to_string_lossy().into_owned()
+}
+
+/// Channels of the header of a CSV recording, none if the file is empty
+fn csv_channels(file: &File) -> Vec<String> {
+    let mut header = String::new();
+
+    match BufReader::new(file).read_line(&mut header) {
+        Ok(_) => header.trim_end().split(',').skip(CSV_COLUMNS.len()).map(str::to_string).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Rows of the samples of a window, the acquisition time of each estimated back from the last
+fn csv_rows(
+    window_id: u64,
+    timestamp: i64,
+    sample_timestamp: Option<i64>,
+    unit: SignalUnit,
+    columns: &[String],
+    headset_data: &HashMap<String, Vec<f32>>,
+) -> String {
+    let samples = columns
+        .iter()
+        .filter_map(|channel| headset_data.get(channel).map(Vec::len))
+        .max()
+        .unwrap_or_default();
+    let mut rows = String::new();
+
+    for sample in 0..samples {
+        let acquired_at = sample_timestamp
+            .map(|last| (last as f64 - (samples - 1 - sample) as f64 * SAMPLE_PERIOD_MS).round().to_string())
+            .unwrap_or_default();
+        let values = columns.iter().map(|channel| {
+            headset_data
+                .get(channel)
+                .and_then(|values| values.get(sample))
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        });
+
+        let row: Vec<String> = [
+            window_id.to_string(),
+            sample.to_string(),
+            timestamp.to_string(),
+            acquired_at,
+            unit.symbol().to_string(),
+        ]
+        .into_iter()
+        .chain(values)
+        .collect();
+        rows.push_str(&row.join(","));
+        rows.push('\n');
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neural_analytics_domain::domain::models::guided_protocol::GuidedProtocol;
+
+    #[test]
+    fn test_records_windows_only_while_started() {
+        let path = std::env::temp_dir().join(format!("neural_analytics_recording_{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+        let headset_data = [("T3".to_string(), vec![1.0, 2.0])].into_iter().collect();
+        let mut recording = FileRecorderAdapter::default();
+        recording.set_unit(SignalUnit::Microvolts);
+
+        recording.record_window(1, &headset_data, None);
+        recording.start(path).unwrap();
+        recording.record_window(2, &headset_data, Some(1_000));
+        assert_eq!(recording.stop().as_deref(), Some(path));
+        recording.record_window(3, &headset_data, None);
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["window_id"], 2);
+        assert_eq!(lines[0]["sample_timestamp"], 1_000);
+        assert_eq!(lines[0]["unit"], "microvolts");
+        assert_eq!(recording.path(), None);
+    }
+
+    #[test]
+    fn test_labels_the_windows_with_the_guided_protocol() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("labeled.jsonl");
+        let path = path.to_str().unwrap();
+        let headset_data = [("T3".to_string(), vec![1.0, 2.0])].into_iter().collect();
+        let mut recording = FileRecorderAdapter::new(RecordingConfig {
+            guided_protocol: Some(GuidedProtocol::default()),
+            ..Default::default()
+        });
+
+        recording.start(path).unwrap();
+        recording.record_window(1, &headset_data, None);
+        recording.set_labeler(None);
+        recording.record_window(2, &headset_data, None);
+        recording.stop();
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        // The protocol starts with a rest period
+        assert_eq!(lines[0]["label"], "trash");
+        assert!(lines[1].get("label").is_none());
+    }
+
+    #[test]
+    fn test_records_sessions_to_their_own_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recording = FileRecorderAdapter::new(RecordingConfig {
+            directory: Some(dir.path().to_string_lossy().into_owned()),
+            format: RecordingFormat::Csv,
+            ..Default::default()
+        });
+        recording.set_unit(SignalUnit::Microvolts);
+
+        let path = recording.start_session(3).unwrap();
+        assert!(path.ends_with("eeg.csv"));
+        assert!(path.contains("session-3-"));
+        // Nothing is written until the first window
+        assert_eq!(recording.path(), None);
+        assert!(!Path::new(&path).exists());
+
+        let headset_data = [("T3".to_string(), vec![1.0, 2.0]), ("O1".to_string(), vec![-0.5, 0.25])].into_iter().collect();
+        recording.record_window(7, &headset_data, Some(1_004));
+        recording.record_window(8, &headset_data, None);
+        assert_eq!(recording.path(), Some(path.as_str()));
+
+        recording.end_session();
+        assert_eq!(recording.path(), None);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "window_id,sample,timestamp,sample_timestamp,unit,O1,T3");
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("7,0,") && lines[1].ends_with(",1000,µV,-0.5,1"));
+        assert!(lines[2].ends_with(",1004,µV,0.25,2"));
+        assert!(lines[4].starts_with("8,1,") && lines[4].ends_with(",,µV,0.25,2"));
+
+        // A recording in progress is not replaced by the one of the session
+        recording.start(&path).unwrap();
+        assert_eq!(recording.start_session(4), None);
+        recording.record_window(9, &headset_data, None);
+        recording.stop();
+        assert!(std::fs::read_to_string(&path).unwrap().lines().nth(5).unwrap().starts_with("9,0,"));
+    }
+
+    #[test]
+    fn test_session_path_keeps_extension() {
+        assert_eq!(session_path("/tmp/eeg.jsonl", 2), "/tmp/eeg.session-2.jsonl");
+        assert_eq!(session_path("eeg", 3), "eeg.session-3");
+        assert_eq!(FileRecorderAdapter::default().rotate(2), Ok(None));
+        assert_eq!(RecordingFormat::of_path("eeg.CSV"), RecordingFormat::Csv);
+        assert_eq!(RecordingFormat::of_path("eeg.session-2.jsonl"), RecordingFormat::JsonLines);
+    }
+}
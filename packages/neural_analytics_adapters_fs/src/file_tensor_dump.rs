@@ -0,0 +1,229 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use log::warn;
+use neural_analytics_domain::domain::ports::output::tensor_dump::TensorDumpPort;
+
+/// Environment variable holding the file the tensors of the last predictions are dumped to
+pub const TENSOR_DUMP_VARIABLE: &str = "NEURAL_ANALYTICS_TENSOR_DUMP";
+
+/// Environment variable holding how many predictions the dump keeps, `DEFAULT_DUMPED_PREDICTIONS` when not set
+pub const TENSOR_DUMP_COUNT_VARIABLE: &str = "NEURAL_ANALYTICS_TENSOR_DUMP_COUNT";
+
+/// Predictions kept in the dump when `NEURAL_ANALYTICS_TENSOR_DUMP_COUNT` is not set
+pub const DEFAULT_DUMPED_PREDICTIONS: usize = 32;
+
+// First bytes of a dump, followed by the version of its layout
+const DUMP_MAGIC: &[u8; 4] = b"NATD";
+const DUMP_VERSION: u32 = 1;
+
+/// Input tensor and raw output of a prediction of the model
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensorRecord {
+    /// When the prediction was made, in milliseconds since the Unix epoch.
+    pub timestamp_ms: i64,
+    /// Shape of the input tensor, e.g. `[1, 62, 4]`.
+    pub input_shape: Vec<u32>,
+    /// Input tensor fed to the model, after the preprocessing, in row-major order.
+    pub input: Vec<f32>,
+    /// Logits returned by the model, before the softmax.
+    pub output: Vec<f32>,
+}
+
+/// Dump of the tensors of the last predictions, for the model developers to compare the
+/// preprocessing of the core with the one of the training pipeline offline.
+///
+/// The file is rewritten after every prediction, so it holds the last ones even when the
+/// application crashes. Its layout, every number little-endian, is:
+///
+/// ```text
+/// "NATD"  u32 version  u32 record count
+/// per record: i64 timestamp_ms  u32 rank  u32 dims[rank]  u32 input length  f32 input[..]
+///             u32 output length  f32 output[..]
+/// ```
+pub struct FileTensorDumpAdapter {
+    path: PathBuf,
+    capacity: usize,
+    records: Mutex<VecDeque<TensorRecord>>,
+}
+
+impl FileTensorDumpAdapter {
+    pub fn new(path: PathBuf, capacity: usize) -> Self {
+        Self {
+            path,
+            capacity: capacity.max(1),
+            records: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Dump configured by `NEURAL_ANALYTICS_TENSOR_DUMP`, `None` when the debug mode is off
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var_os(TENSOR_DUMP_VARIABLE).filter(|path| !path.is_empty())?;
+        let capacity = std::env::var(TENSOR_DUMP_COUNT_VARIABLE)
+            .ok()
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(DEFAULT_DUMPED_PREDICTIONS);
+
+        Some(Self::new(PathBuf::from(path), capacity))
+    }
+
+    /// Records kept in the dump, the oldest first
+    pub fn records(&self) -> Vec<TensorRecord> {
+        let records = self.records.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        records.iter().cloned().collect()
+    }
+}
+
+impl TensorDumpPort for FileTensorDumpAdapter {
+    /// Records the tensors of a prediction and rewrites the dump
+    ///
+    /// A dump that cannot be written is only warned about, the predictions go on.
+    fn record(&self, input_shape: &[usize], input: &[f32], output: &[f32]) {
+        let record = TensorRecord {
+            timestamp_ms: Utc::now().timestamp_millis(),
+            input_shape: input_shape.iter().map(|&dim| dim as u32).collect(),
+            input: input.to_vec(),
+            output: output.to_vec(),
+        };
+
+        let bytes = {
+            let mut records = self.records.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if records.len() == self.capacity {
+                records.pop_front();
+            }
+            records.push_back(record);
+
+            encode_dump(records.iter())
+        };
+
+        // Written aside and renamed, so a reader never sees a dump half written
+        let partial_path = self.path.with_extension("partial");
+        let result = std::fs::write(&partial_path, bytes).and_then(|_| std::fs::rename(&partial_path, &self.path));
+
+        if let Err(e) = result {
+            warn!("Failed to write the tensor dump {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Encodes the records in the layout documented on `FileTensorDumpAdapter`
+fn encode_dump<'a>(records: impl ExactSizeIterator<Item = &'a TensorRecord>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(DUMP_MAGIC);
+    bytes.extend_from_slice(&DUMP_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(records.len() as u32).to_le_bytes());
+
+    for record in records {
+        bytes.extend_from_slice(&record.timestamp_ms.to_le_bytes());
+        bytes.extend_from_slice(&(record.input_shape.len() as u32).to_le_bytes());
+        for dim in &record.input_shape {
+            bytes.extend_from_slice(&dim.to_le_bytes());
+        }
+
+        for values in [&record.input, &record.output] {
+            bytes.extend_from_slice(&(values.len() as u32).to_le_bytes());
+            for value in values {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+
+    bytes
+}
+
+/// Decodes a dump written by `FileTensorDumpAdapter`
+///
+/// # Returns
+/// - `Result<Vec<TensorRecord>, String>`: The records of the dump, the oldest first, or an
+///   error message if the file is not a dump of this version.
+pub fn decode_dump(bytes: &[u8]) -> Result<Vec<TensorRecord>, String> {
+    let mut reader = DumpReader { bytes, offset: 0 };
+
+    if reader.take(4)? != DUMP_MAGIC {
+        return Err("Not a tensor dump".to_string());
+    }
+
+    let version = reader.u32()?;
+    if version != DUMP_VERSION {
+        return Err(format!("Unsupported tensor dump version {}", version));
+    }
+
+    let count = reader.u32()?;
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let timestamp_ms = i64::from_le_bytes(reader.take(8)?.try_into().unwrap_or_default());
+        let rank = reader.u32()?;
+        let input_shape = (0..rank).map(|_| reader.u32()).collect::<Result<_, _>>()?;
+        let input = reader.f32s()?;
+        let output = reader.f32s()?;
+
+        records.push(TensorRecord { timestamp_ms, input_shape, input, output });
+    }
+
+    Ok(records)
+}
+
+struct DumpReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DumpReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end = self.offset + length;
+        let bytes = self.bytes.get(self.offset..end).ok_or("Truncated tensor dump")?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap_or_default()))
+    }
+
+    fn f32s(&mut self) -> Result<Vec<f32>, String> {
+        let length = self.u32()? as usize;
+        let bytes = self.take(length * 4)?;
+        Ok(bytes.chunks_exact(4).map(|value| f32::from_le_bytes(value.try_into().unwrap_or_default())).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_keeps_the_last_predictions_in_the_dump() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tensors.bin");
+        let dump = FileTensorDumpAdapter::new(path.clone(), 2);
+
+        for prediction in 0..3 {
+            dump.record(&[1, 2, 1], &[prediction as f32, -1.5], &[0.25, prediction as f32]);
+        }
+
+        let records = decode_dump(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(records, dump.records());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].input_shape, vec![1, 2, 1]);
+        assert_eq!(records[0].input, vec![1.0, -1.5]);
+        assert_eq!(records[1].output, vec![0.25, 2.0]);
+    }
+
+    #[test]
+    fn test_decode_dump_rejects_other_files() {
+        assert_eq!(decode_dump(b"PK\x03\x04").unwrap_err(), "Not a tensor dump");
+
+        let mut truncated = encode_dump([TensorRecord {
+            timestamp_ms: 0,
+            input_shape: vec![1],
+            input: vec![1.0],
+            output: vec![],
+        }]
+        .iter());
+        truncated.pop();
+        assert_eq!(decode_dump(&truncated).unwrap_err(), "Truncated tensor dump");
+    }
+}
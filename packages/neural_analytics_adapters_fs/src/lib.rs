@@ -0,0 +1,5 @@
+pub mod file_recorder;
+pub mod file_tensor_dump;
+pub mod model_file_watcher;
+pub mod replay_headset;
+pub mod zip_bundle_archive;
@@ -0,0 +1,94 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use log::error;
+use neural_analytics_domain::domain::ports::input::model_watcher::ModelWatcherPort;
+use notify::{Event as FileEvent, EventKind, RecursiveMode, Watcher};
+
+// Time to wait for the training pipeline to finish writing the file
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches the model file through the notifications of the file system.
+///
+/// The parent directory is watched, so files replaced through a rename are detected
+/// too. The burst of events produced by a single write is coalesced into one change.
+#[derive(Default)]
+pub struct ModelFileWatcherAdapter;
+
+impl ModelWatcherPort for ModelFileWatcherAdapter {
+    fn watch(&self, model_path: &str, on_change: Box<dyn Fn() + Send>) -> Result<(), String> {
+        let path = PathBuf::from(model_path);
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| format!("Invalid model path: {}", model_path))?
+            .to_os_string();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (sender, receiver) = channel();
+        let mut watcher =
+            notify::recommended_watcher(sender).map_err(|e| format!("Error creating model watcher: {}", e))?;
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Error watching {}: {}", directory.display(), e))?;
+
+        std::thread::spawn(move || {
+            // The watcher stops when dropped, so it lives as long as this thread
+            let _watcher = watcher;
+
+            while let Ok(event) = receiver.recv() {
+                match event {
+                    Ok(event) if is_model_change(&event, &file_name) => {
+                        // Coalesce the burst of events produced by a single write
+                        std::thread::sleep(RELOAD_DEBOUNCE);
+                        while receiver.try_recv().is_ok() {}
+
+                        on_change();
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("Model watcher error: {}", e),
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Checks if a file system event creates or modifies the watched model file
+fn is_model_change(event: &FileEvent, file_name: &OsString) -> bool {
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == Some(file_name.as_os_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    #[test]
+    fn test_is_model_change_filters_by_file_and_kind() {
+        let file_name = OsString::from("neural_analytics.onnx");
+
+        let modified = FileEvent::new(EventKind::Modify(ModifyKind::Any))
+            .add_path(PathBuf::from("assets/neural_analytics.onnx"));
+        let created = FileEvent::new(EventKind::Create(CreateKind::File))
+            .add_path(PathBuf::from("assets/neural_analytics.onnx"));
+        let other_file = FileEvent::new(EventKind::Modify(ModifyKind::Any))
+            .add_path(PathBuf::from("assets/other.onnx"));
+        let accessed = FileEvent::new(EventKind::Access(AccessKind::Any))
+            .add_path(PathBuf::from("assets/neural_analytics.onnx"));
+
+        assert!(is_model_change(&modified, &file_name));
+        assert!(is_model_change(&created, &file_name));
+        assert!(!is_model_change(&other_file, &file_name));
+        assert!(!is_model_change(&accessed, &file_name));
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use neural_analytics_domain::domain::{
+    models::{eeg_work_modes::WorkMode, recording_config::RecordingFormat, signal_unit::SignalUnit},
+    ports::input::eeg_headset::EegHeadsetPort,
+    services::window_assembler_service::WINDOW_SAMPLES,
+};
+
+// Impedance of a good contact, in kOhm, reported for every electrode of the recording
+const REPLAYED_IMPEDANCE: u16 = 500;
+
+// Columns of the CSV recordings before the one of each channel
+const CSV_COLUMNS: usize = 5;
+
+// Window of a recording written by `FileRecorderAdapter`, the rest of it is not replayed
+#[derive(serde::Deserialize)]
+struct RecordedWindow {
+    // Unix epoch milliseconds when the window was extracted
+    timestamp: i64,
+    unit: SignalUnit,
+    headset_data: HashMap<String, Vec<f32>>,
+}
+
+/// Headset streaming a recording of `FileRecorderAdapter`, so the whole pipeline can be run
+/// against a known dataset.
+///
+/// The windows of the recording are streamed one after the other, as fast as they are
+/// extracted, or at their original pace when opened with `real_time`. The extraction
+/// fails once the recording is over. Recordings of a capture without overlap are
+/// replayed window for window. Every electrode reports a good contact.
+pub struct ReplayHeadsetAdapter {
+    is_connected: AtomicBool,
+    // Samples of each channel, the windows of the recording one after the other
+    samples: HashMap<String, Vec<f32>>,
+    // Samples streamed at the end of each window, with the time it was extracted
+    window_times: Vec<(usize, i64)>,
+    unit: SignalUnit,
+    // Whether windows are streamed at the pace they were recorded
+    is_real_time: bool,
+    // When the first window was streamed, the origin of the recorded timestamps
+    started_at: Mutex<Option<Instant>>,
+    // Samples streamed so far
+    position: AtomicUsize,
+    // Samples returned by each extraction, a whole window unless the windows overlap
+    hop_samples: AtomicUsize,
+    work_mode: WorkMode,
+}
+
+impl ReplayHeadsetAdapter {
+    /// Opens a recording to replay.
+    ///
+    /// # Arguments
+    /// * `path`: The JSON Lines or CSV recording, as written by `FileRecorderAdapter`, the
+    ///   format told by its extension.
+    ///
+    /// # Returns
+    /// * `Result<Self, String>`: The headset, or an error naming the first line that
+    ///   cannot be read or whose channels differ from the first one.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Could not open the recording {}: {}", path, e))?;
+        let windows = match RecordingFormat::of_path(path) {
+            RecordingFormat::JsonLines => read_json_lines(file),
+            RecordingFormat::Csv => read_csv(file),
+        }
+        .map_err(|(line, e)| format!("Invalid recording {}, line {}: {}", path, line, e))?;
+
+        let mut samples: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut window_times = Vec::with_capacity(windows.len());
+        let mut unit = SignalUnit::default();
+
+        for (index, window) in windows.into_iter().enumerate() {
+            if !samples.is_empty() && (samples.len() != window.headset_data.len()
+                || window.headset_data.keys().any(|channel| !samples.contains_key(channel)))
+            {
+                return Err(format!("Invalid recording {}, window {}: the channels changed", path, index + 1));
+            }
+
+            unit = window.unit;
+            for (channel, values) in window.headset_data {
+                samples.entry(channel).or_default().extend(values);
+            }
+            window_times.push((samples.values().map(Vec::len).min().unwrap_or(0), window.timestamp));
+        }
+
+        if samples.is_empty() {
+            return Err(format!("The recording {} has no window", path));
+        }
+
+        Ok(Self {
+            is_connected: AtomicBool::new(false),
+            samples,
+            window_times,
+            unit,
+            is_real_time: false,
+            started_at: Mutex::new(None),
+            position: AtomicUsize::new(0),
+            hop_samples: AtomicUsize::new(WINDOW_SAMPLES),
+            work_mode: WorkMode::Initialized,
+        })
+    }
+
+    /// Opens a recording to replay at its original pace, each window streamed as long
+    /// after the first one as it was recorded, to run the application without hardware.
+    pub fn real_time(path: &str) -> Result<Self, String> {
+        Self::open(path).map(|headset| Self { is_real_time: true, ..headset })
+    }
+
+    /// Samples of each channel in the recording.
+    pub fn len(&self) -> usize {
+        self.samples.values().map(Vec::len).min().unwrap_or(0)
+    }
+
+    /// Whether the recording holds no sample.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Waits until the recorded time the samples up to `end` had been extracted.
+    fn wait_for(&self, end: usize) {
+        let window = self.window_times.partition_point(|(streamed, _)| *streamed < end);
+        let (Some((_, timestamp)), Some((_, first_timestamp))) = (self.window_times.get(window), self.window_times.first())
+        else {
+            return;
+        };
+
+        let started_at = *self
+            .started_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_or_insert_with(Instant::now);
+        let due = Duration::from_millis((timestamp - first_timestamp).max(0) as u64);
+
+        if let Some(wait) = due.checked_sub(started_at.elapsed()) {
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+impl EegHeadsetPort for ReplayHeadsetAdapter {
+    fn connect(&self) -> Result<(), String> {
+        self.is_connected.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.is_connected.load(Ordering::SeqCst)
+    }
+
+    fn disconnect(&mut self) -> Result<(), String> {
+        self.is_connected.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String> {
+        Ok(self
+            .samples
+            .keys()
+            .map(|channel| (channel.clone(), REPLAYED_IMPEDANCE))
+            .collect())
+    }
+
+    fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String> {
+        let samples = self.hop_samples.load(Ordering::SeqCst);
+        let first = self.position.load(Ordering::SeqCst);
+
+        if first + samples > self.len() {
+            return Err(format!("The recording is over, after {} samples", first));
+        }
+
+        if self.is_real_time {
+            self.wait_for(first + samples);
+        }
+
+        self.position.store(first + samples, Ordering::SeqCst);
+
+        Ok(self
+            .samples
+            .iter()
+            .map(|(channel, values)| (channel.clone(), values[first..first + samples].to_vec()))
+            .collect())
+    }
+
+    fn signal_unit(&self) -> SignalUnit {
+        self.unit
+    }
+
+    fn set_window_hop(&self, samples: usize) {
+        self.hop_samples.store(samples.clamp(1, WINDOW_SAMPLES), Ordering::SeqCst);
+    }
+
+    fn change_work_mode(&mut self, mode: WorkMode) {
+        self.work_mode = mode;
+    }
+
+    fn get_work_mode(&self) -> WorkMode {
+        self.work_mode
+    }
+}
+
+/// Windows of a JSON Lines recording, or the line that cannot be read
+fn read_json_lines(file: File) -> Result<Vec<RecordedWindow>, (usize, String)> {
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            line.map_err(|e| e.to_string())
+                .and_then(|line| serde_json::from_str(&line).map_err(|e| e.to_string()))
+                .map_err(|e| (index + 1, e))
+        })
+        .collect()
+}
+
+/// Windows of a CSV recording, their rows grouped by window id, or the line that cannot be read
+fn read_csv(file: File) -> Result<Vec<RecordedWindow>, (usize, String)> {
+    let mut lines = BufReader::new(file).lines();
+    let header = lines.next().unwrap_or(Ok(String::new())).map_err(|e| (1, e.to_string()))?;
+    let channels: Vec<String> = header.trim_end().split(',').skip(CSV_COLUMNS).map(str::to_string).collect();
+
+    let mut windows: Vec<(String, RecordedWindow)> = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line_number = index + 2;
+        let line = line.map_err(|e| (line_number, e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.trim_end().split(',').collect();
+        if columns.len() != CSV_COLUMNS + channels.len() {
+            return Err((line_number, format!("expected {} columns", CSV_COLUMNS + channels.len())));
+        }
+
+        let unit = [SignalUnit::Microvolts, SignalUnit::Normalized]
+            .into_iter()
+            .find(|unit| unit.symbol() == columns[4])
+            .ok_or_else(|| (line_number, format!("unknown unit '{}'", columns[4])))?;
+        let timestamp = columns[2]
+            .parse()
+            .map_err(|e| (line_number, format!("invalid timestamp '{}': {}", columns[2], e)))?;
+
+        if windows.last().is_none_or(|(window_id, _)| window_id != columns[0]) {
+            let headset_data = channels.iter().map(|channel| (channel.clone(), Vec::new())).collect();
+            windows.push((columns[0].to_string(), RecordedWindow { timestamp, unit, headset_data }));
+        }
+
+        let (_, window) = windows.last_mut().expect("BUG: No window for the row");
+        for (channel, value) in channels.iter().zip(&columns[CSV_COLUMNS..]) {
+            // A channel missing from the window is left blank, replayed as a lost sample
+            let value = if value.is_empty() {
+                f32::NAN
+            } else {
+                value.parse().map_err(|e| (line_number, format!("invalid sample '{}': {}", value, e)))?
+            };
+            window.headset_data.get_mut(channel).expect("BUG: Unknown channel").push(value);
+        }
+    }
+
+    Ok(windows.into_iter().map(|(_, window)| window).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_recorder::FileRecorderAdapter;
+    use neural_analytics_domain::domain::models::recording_config::RecordingConfig;
+    use neural_analytics_domain::domain::ports::output::recorder::RecorderPort;
+
+    #[test]
+    fn test_replays_the_windows_of_a_recording() {
+        let path = std::env::temp_dir().join(format!("neural_analytics_replay_{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut recording = FileRecorderAdapter::new(RecordingConfig::default());
+        recording.set_unit(SignalUnit::Microvolts);
+        recording.start(path).unwrap();
+        for window_id in 0..2 {
+            let headset_data = [
+                ("T3".to_string(), vec![window_id as f32; WINDOW_SAMPLES]),
+                ("O1".to_string(), vec![-(window_id as f32); WINDOW_SAMPLES]),
+            ]
+            .into_iter()
+            .collect();
+            recording.record_window(window_id, &headset_data, None);
+        }
+        recording.stop();
+
+        let headset = ReplayHeadsetAdapter::open(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(headset.len(), 2 * WINDOW_SAMPLES);
+        assert_eq!(headset.signal_unit(), SignalUnit::Microvolts);
+        assert_eq!(headset.extract_impedance_data().unwrap()["O1"], REPLAYED_IMPEDANCE);
+
+        assert_eq!(headset.extract_raw_data().unwrap()["T3"], vec![0.0; WINDOW_SAMPLES]);
+        assert_eq!(headset.extract_raw_data().unwrap()["O1"], vec![-1.0; WINDOW_SAMPLES]);
+        assert!(headset.extract_raw_data().unwrap_err().contains("over"));
+    }
+
+    #[test]
+    fn test_replays_csv_recordings_at_their_pace() {
+        let path = std::env::temp_dir().join(format!("neural_analytics_replay_{}.csv", std::process::id()));
+        let mut csv = "window_id,sample,timestamp,sample_timestamp,unit,O1,T3\n".to_string();
+        for (window_id, timestamp) in [(1, 10_000), (2, 10_150)] {
+            for sample in 0..WINDOW_SAMPLES {
+                csv.push_str(&format!("{},{},{},,µV,{},\n", window_id, sample, timestamp, window_id));
+            }
+        }
+        std::fs::write(&path, csv).unwrap();
+
+        let headset = ReplayHeadsetAdapter::real_time(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(headset.len(), 2 * WINDOW_SAMPLES);
+        assert_eq!(headset.signal_unit(), SignalUnit::Microvolts);
+
+        let started_at = Instant::now();
+        let first = headset.extract_raw_data().unwrap();
+        assert!(started_at.elapsed() < Duration::from_millis(100));
+        assert_eq!(first["O1"], vec![1.0; WINDOW_SAMPLES]);
+        assert!(first["T3"].iter().all(|value| value.is_nan()));
+
+        // The second window was recorded 150 ms after the first one
+        let second = headset.extract_raw_data().unwrap();
+        assert!(started_at.elapsed() >= Duration::from_millis(150));
+        assert_eq!(second["O1"], vec![2.0; WINDOW_SAMPLES]);
+    }
+}
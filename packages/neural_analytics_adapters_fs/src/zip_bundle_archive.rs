@@ -0,0 +1,53 @@
+use std::fs::File;
+use std::io::Write;
+
+use neural_analytics_domain::domain::ports::output::bundle_archive::BundleArchivePort;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+/// Writes the diagnostic bundles as deflated zip archives.
+#[derive(Default)]
+pub struct ZipBundleArchiveAdapter;
+
+impl BundleArchivePort for ZipBundleArchiveAdapter {
+    fn write(&self, path: &str, files: &[(&str, String)]) -> Result<(), String> {
+        let error = |e: &dyn std::fmt::Display| format!("Could not write the diagnostic bundle {}: {}", path, e);
+
+        let file = File::create(path).map_err(|e| error(&e))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, content) in files {
+            zip.start_file(*name, options).map_err(|e| error(&e))?;
+            zip.write_all(content.as_bytes()).map_err(|e| error(&e))?;
+        }
+
+        zip.finish().map_err(|e| error(&e))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_archives_every_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bundle.zip").to_str().unwrap().to_string();
+        let files = [("version.json", "{}".to_string()), ("journal.jsonl", "{\"kind\":\"a\"}\n".to_string())];
+
+        ZipBundleArchiveAdapter.write(&path, &files).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, ["journal.jsonl", "version.json"]);
+
+        let mut journal = String::new();
+        archive.by_name("journal.jsonl").unwrap().read_to_string(&mut journal).unwrap();
+        assert_eq!(journal, "{\"kind\":\"a\"}\n");
+    }
+}
@@ -0,0 +1 @@
+pub mod mqtt_prediction_sink;
\ No newline at end of file
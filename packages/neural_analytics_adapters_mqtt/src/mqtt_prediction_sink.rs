@@ -0,0 +1,170 @@
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::{debug, info, warn};
+use neural_analytics_domain::domain::{
+    models::{payload_policy::PayloadPolicy, published_prediction::PublishedPrediction},
+    ports::output::prediction_sink::PredictionSinkPort,
+    services::payload_policy_service::to_wire,
+};
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+
+/// Environment variable holding the MQTT broker the predictions are published to, as `host` or `host:port`
+pub const MQTT_BROKER_VARIABLE: &str = "NEURAL_ANALYTICS_MQTT_BROKER";
+
+/// Environment variable holding the topic of the predictions, `DEFAULT_TOPIC` when not set
+pub const MQTT_TOPIC_VARIABLE: &str = "NEURAL_ANALYTICS_MQTT_TOPIC";
+
+/// Environment variables holding the credentials of the broker, when it requires them
+pub const MQTT_USERNAME_VARIABLE: &str = "NEURAL_ANALYTICS_MQTT_USERNAME";
+pub const MQTT_PASSWORD_VARIABLE: &str = "NEURAL_ANALYTICS_MQTT_PASSWORD";
+
+/// Topic of the predictions when `NEURAL_ANALYTICS_MQTT_TOPIC` is not set
+pub const DEFAULT_TOPIC: &str = "neural_analytics/prediction";
+
+// Port of the brokers given without one, the standard unencrypted MQTT port
+const DEFAULT_PORT: u16 = 1883;
+
+// Predictions waiting for the connection before the next ones are dropped
+const QUEUE_CAPACITY: usize = 16;
+
+// Wait before connecting again to a broker that went away
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Publishes the predictions as JSON to a topic of an MQTT broker, so home-automation
+/// systems other than the Tapo bulb, such as Home Assistant or Node-RED, can react to them.
+///
+/// The predictions are retained, so a subscriber joining later gets the last one. The
+/// connection is kept in the background, reconnecting to a broker that went away, and
+/// the predictions made while it is down are queued up to 16, the next ones dropped.
+///
+/// The predictions are serialized with the payload policy of the `mqtt` destination.
+pub struct MqttPredictionSinkAdapter {
+    client: AsyncClient,
+    topic: String,
+    payload_policy: PayloadPolicy,
+}
+
+impl MqttPredictionSinkAdapter {
+    /// Connects to the broker in the background. Returns immediately.
+    pub fn new(host: &str, port: u16, topic: String, credentials: Option<(String, String)>) -> Self {
+        let mut options = MqttOptions::new(format!("neural-analytics-{}", std::process::id()), host, port);
+        options.set_keep_alive(KEEP_ALIVE);
+
+        if let Some((username, password)) = credentials {
+            options.set_credentials(username, password);
+        }
+
+        let (client, mut event_loop) = AsyncClient::new(options, QUEUE_CAPACITY);
+        let broker = format!("{}:{}", host, port);
+
+        // The event loop drives the connection and sends the queued predictions
+        tokio::spawn(async move {
+            loop {
+                match event_loop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => info!("Connected to the MQTT broker {}", broker),
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!("MQTT broker {} unreachable, retrying in {} s: {}", broker, RECONNECT_DELAY.as_secs(), e);
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        });
+
+        Self {
+            client,
+            topic,
+            payload_policy: PayloadPolicy::default(),
+        }
+    }
+
+    /// Serializes the predictions in the shape of the policy, the full ones by default.
+    pub fn with_payload_policy(mut self, payload_policy: PayloadPolicy) -> Self {
+        self.payload_policy = payload_policy;
+        self
+    }
+
+    /// Creates the adapter from the broker in `NEURAL_ANALYTICS_MQTT_BROKER`.
+    ///
+    /// # Returns
+    /// - `Option<MqttPredictionSinkAdapter>`: `None` when no broker is configured or its
+    ///   address is invalid.
+    pub fn from_env() -> Option<Self> {
+        let broker = env::var(MQTT_BROKER_VARIABLE).ok().filter(|broker| !broker.trim().is_empty())?;
+
+        let (host, port) = match parse_broker(&broker) {
+            Ok(address) => address,
+            Err(e) => {
+                warn!("Ignoring {}: {}", MQTT_BROKER_VARIABLE, e);
+                return None;
+            }
+        };
+
+        let topic = env::var(MQTT_TOPIC_VARIABLE).unwrap_or_else(|_| DEFAULT_TOPIC.to_string());
+        let credentials = env::var(MQTT_USERNAME_VARIABLE)
+            .ok()
+            .map(|username| (username, env::var(MQTT_PASSWORD_VARIABLE).unwrap_or_default()));
+
+        info!("Publishing the predictions to {} on the MQTT broker {}:{}", topic, host, port);
+        Some(Self::new(&host, port, topic, credentials))
+    }
+}
+
+/// Splits a broker address into its host and port, `1883` when it has none.
+fn parse_broker(broker: &str) -> Result<(String, u16), String> {
+    let broker = broker.trim();
+
+    match broker.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => port
+            .parse()
+            .map(|port| (host.to_string(), port))
+            .map_err(|_| format!("invalid port '{}' in '{}'", port, broker)),
+        Some(_) => Err(format!("missing host in '{}'", broker)),
+        None => Ok((broker.to_string(), DEFAULT_PORT)),
+    }
+}
+
+#[async_trait]
+impl PredictionSinkPort for MqttPredictionSinkAdapter {
+    /// Queues the prediction for the event loop, without waiting for the broker.
+    async fn publish(&self, prediction: &PublishedPrediction) -> Result<(), String> {
+        let payload = to_wire(prediction, &self.payload_policy)?;
+
+        self.client
+            .try_publish(&self.topic, QoS::AtLeastOnce, true, payload.into_bytes())
+            .map_err(|e| format!("Prediction not queued for the MQTT topic {}: {}", self.topic, e))?;
+
+        debug!("Queued the prediction '{}' for the MQTT topic {}", prediction.prediction, self.topic);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_broker_defaults_to_the_mqtt_port() {
+        assert_eq!(parse_broker("192.168.1.10"), Ok(("192.168.1.10".to_string(), 1883)));
+        assert_eq!(parse_broker(" broker.local:8883 "), Ok(("broker.local".to_string(), 8883)));
+        assert!(parse_broker("broker.local:mqtt").unwrap_err().contains("invalid port"));
+        assert!(parse_broker(":1883").unwrap_err().contains("missing host"));
+    }
+
+    #[tokio::test]
+    async fn test_publish_does_not_wait_for_an_unreachable_broker() {
+        let adapter = MqttPredictionSinkAdapter::new("127.0.0.1", 9, DEFAULT_TOPIC.to_string(), None);
+        let prediction = PublishedPrediction {
+            prediction: "red".to_string(),
+            window_id: Some(3),
+            timestamp: 0,
+        };
+
+        let published = tokio::time::timeout(Duration::from_secs(1), adapter.publish(&prediction)).await;
+        assert_eq!(published, Ok(Ok(())));
+    }
+}
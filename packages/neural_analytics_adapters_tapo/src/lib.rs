@@ -0,0 +1 @@
+pub mod tapo_smartbulb;
\ No newline at end of file
@@ -0,0 +1,318 @@
+use async_trait::async_trait;
+use log::{debug, error, warn};
+use std::env;
+use std::sync::{Arc, RwLock};
+use tapo::{ApiClient, ColorLightHandler, LightHandler};
+use tokio::sync::Mutex;
+
+use neural_analytics_domain::domain::models::bulb_connection::{BulbConnectionPolicy, BulbConnectionState};
+use neural_analytics_domain::domain::models::bulb_state::BulbState;
+use neural_analytics_domain::domain::ports::output::smart_bulb::SmartBulbPort;
+
+/// Handler of the connected bulb, of the model set in `TAPO_BULB_MODEL`.
+enum TapoBulb {
+    /// Dimmable white bulb, such as the L510, which only turns on and off.
+    Light(LightHandler),
+    /// Color bulb, such as the L530, which also takes the color of each prediction class.
+    ColorLight(ColorLightHandler),
+}
+
+/// Adapter for interacting with a Tapo smart bulb using environment variables.
+/// Connection is initiated in the background when `new` is called.
+pub struct TapoSmartBulbAdapter {
+    // Stores the handler after background connection. Needs Arc<Mutex> for sharing.
+    device_client: Arc<Mutex<Option<TapoBulb>>>,
+    // Keep config details for potential retries or reference
+    ip_address: String,
+    // Progress of the background connection, polled by the core
+    connection_state: Arc<RwLock<BulbConnectionState>>,
+}
+
+impl Default for TapoSmartBulbAdapter {
+    /// Creates a new instance and initiates connection in the background,
+    /// with the default retry schedule.
+    fn default() -> Self {
+        Self::with_policy(BulbConnectionPolicy::default())
+    }
+}
+
+impl TapoSmartBulbAdapter {
+    /// Creates a new instance and initiates connection in the background.
+    /// Returns immediately. The adapter might not be connected yet.
+    /// Does not panic if environment variables are not set - will just use placeholder values
+    /// and log a warning. This ensures tests can run without environment variables set.
+    ///
+    /// Failed attempts are retried following `policy`, and the bulb is reported
+    /// unavailable once its attempts are exhausted.
+    pub fn with_policy(policy: BulbConnectionPolicy) -> Self {
+        debug!("Creating TapoSmartBulbAdapter config and spawning connection task...");
+
+        // Usamos valores por defecto si las variables de entorno no están configuradas
+        // para que los tests no fallen, pero logueamos un warning
+        let ip_address = env::var("TAPO_IP_ADDRESS").unwrap_or_else(|_| {
+            log::warn!("TAPO_IP_ADDRESS environment variable not set. Using dummy value for tests");
+            "127.0.0.1".to_string()
+        });
+
+        let username = env::var("TAPO_USERNAME").unwrap_or_else(|_| {
+            log::warn!("TAPO_USERNAME environment variable not set. Using dummy value for tests");
+            "test_user".to_string()
+        });
+
+        let password = env::var("TAPO_PASSWORD").unwrap_or_else(|_| {
+            log::warn!("TAPO_PASSWORD environment variable not set. Using dummy value for tests");
+            "test_password".to_string()
+        });
+
+        // Color bulbs are reached with their own handler, white ones with the L510 one
+        let is_color_bulb = env::var("TAPO_BULB_MODEL").is_ok_and(|model| model.eq_ignore_ascii_case("l530"));
+
+        let device_client_arc = Arc::new(Mutex::new(None));
+        let max_attempts = policy.max_attempts.max(1);
+        let connection_state = Arc::new(RwLock::new(BulbConnectionState::Connecting { attempt: 1, max_attempts }));
+
+        // Clone data needed for the background task
+        let ip_clone = ip_address.clone();
+        let user_clone = username.clone();
+        let pass_clone = password.clone();
+        let client_arc_clone = Arc::clone(&device_client_arc);
+        let state_clone = Arc::clone(&connection_state);
+        let set_state = move |state: BulbConnectionState| match state_clone.write() {
+            Ok(mut guard) => *guard = state,
+            Err(e) => error!("Failed to update the Tapo connection state: {}", e),
+        };
+
+        // Spawn the connection logic in a background task
+        tokio::spawn(async move {
+            debug!(
+                "Background task: Attempting connection to Tapo device at {}",
+                ip_clone
+            );
+
+            // Si estamos usando valores dummy para tests, no intentamos conectar realmente
+            if ip_clone == "127.0.0.1" && user_clone == "test_user" && pass_clone == "test_password"
+            {
+                debug!("Using dummy values for tests - not attempting actual connection");
+                set_state(BulbConnectionState::Connected);
+                return;
+            }
+
+            let mut last_error = String::new();
+
+            for attempt in 1..=max_attempts {
+                set_state(BulbConnectionState::Connecting { attempt, max_attempts });
+
+                // The client is consumed by each connection attempt
+                let api_client = ApiClient::new(user_clone.clone(), pass_clone.clone());
+
+                let connection = if is_color_bulb {
+                    api_client.l530(ip_clone.clone()).await.map(TapoBulb::ColorLight)
+                } else {
+                    api_client.l510(ip_clone.clone()).await.map(TapoBulb::Light)
+                };
+
+                match connection {
+                    Ok(handler) => {
+                        debug!(
+                            "Background task: Successfully connected to Tapo device at {}. Updating adapter state.",
+                            ip_clone
+                        );
+
+                        // Lock the tokio mutex asynchronously
+                        let mut client_guard = client_arc_clone.lock().await;
+                        *client_guard = Some(handler);
+                        set_state(BulbConnectionState::Connected);
+                        return;
+                    }
+                    Err(e) => {
+                        // Log the error; the Option remains None until an attempt succeeds
+                        warn!(
+                            "Background task: Attempt {} of {} to connect to Tapo device {} failed: {}",
+                            attempt, max_attempts, ip_clone, e
+                        );
+                        last_error = e.to_string();
+                    }
+                }
+
+                if attempt < max_attempts {
+                    tokio::time::sleep(policy.delay_after(attempt)).await;
+                }
+            }
+
+            error!(
+                "Background task: Giving up connecting to Tapo device {} after {} attempts",
+                ip_clone, max_attempts
+            );
+            set_state(BulbConnectionState::Unavailable {
+                reason: format!("No connection after {} attempts: {}", max_attempts, last_error),
+            });
+        });
+
+        debug!(
+            "TapoSmartBulbAdapter::new returning for IP: {}. Connection proceeds in background.",
+            ip_address
+        );
+
+        Self {
+            device_client: device_client_arc,
+            ip_address,
+            connection_state,
+        }
+    }
+}
+
+#[async_trait]
+impl SmartBulbPort for TapoSmartBulbAdapter {
+    /// Changes the state of the smart bulb (on or off).
+    /// Returns an error if the background connection hasn't completed successfully yet.
+    async fn change_state(&self, state: BulbState) -> Result<(), String> {
+        debug!(
+            "Adapter: Requesting state change for bulb {} to {:?}",
+            self.ip_address, state
+        );
+
+        // Lock the tokio mutex asynchronously
+        let maybe_client_guard = self.device_client.lock().await;
+
+        // Si estamos en un test con valores dummy, simular éxito sin llamar al API real
+        if self.ip_address == "127.0.0.1" {
+            debug!(
+                "Test environment detected. Simulating successful bulb state change to {:?}",
+                state
+            );
+            return Ok(());
+        }
+
+        // Check if the client is available (connection successful)
+        let client = maybe_client_guard.as_ref().ok_or_else(|| {
+            format!(
+                "Cannot change state for Tapo device {}: Not connected yet or connection failed.",
+                self.ip_address
+            )
+        })?;
+
+        // Proceed with the command using the handler from the Option
+        let result = match (client, state) {
+            (TapoBulb::Light(handler), BulbState::BulbOn) => handler.on().await,
+            (TapoBulb::Light(handler), BulbState::BulbOff) => handler.off().await,
+            (TapoBulb::ColorLight(handler), BulbState::BulbOn) => handler.on().await,
+            (TapoBulb::ColorLight(handler), BulbState::BulbOff) => handler.off().await,
+        };
+
+        result.map_err(|e| {
+            let error_msg = format!(
+                "Failed to change Tapo bulb state to {:?} for device {}: {}",
+                state, self.ip_address, e
+            );
+            error!("{}", error_msg);
+            error_msg
+        })
+    }
+
+    /// Changes the color of an L530 bulb, black turning it off.
+    /// Returns an error for the white bulbs, or before the background connection completed.
+    async fn change_color(&self, red: u8, green: u8, blue: u8) -> Result<(), String> {
+        debug!(
+            "Adapter: Requesting color ({}, {}, {}) for bulb {}",
+            red, green, blue, self.ip_address
+        );
+
+        let maybe_client_guard = self.device_client.lock().await;
+
+        if self.ip_address == "127.0.0.1" {
+            debug!("Test environment detected. Simulating successful bulb color change");
+            return Ok(());
+        }
+
+        let handler = match maybe_client_guard.as_ref() {
+            Some(TapoBulb::ColorLight(handler)) => handler,
+            Some(TapoBulb::Light(_)) => {
+                return Err(format!(
+                    "Tapo device {} has no color support, set TAPO_BULB_MODEL=l530 for color bulbs",
+                    self.ip_address
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "Cannot change color for Tapo device {}: Not connected yet or connection failed.",
+                    self.ip_address
+                ))
+            }
+        };
+
+        let result = match rgb_to_hue_saturation_brightness(red, green, blue) {
+            None => handler.off().await,
+            Some((hue, saturation, brightness)) => {
+                handler
+                    .set()
+                    .hue_saturation(hue, saturation)
+                    .brightness(brightness)
+                    .send(handler)
+                    .await
+            }
+        };
+
+        result.map_err(|e| {
+            let error_msg = format!(
+                "Failed to change Tapo bulb color to ({}, {}, {}) for device {}: {}",
+                red, green, blue, self.ip_address, e
+            );
+            error!("{}", error_msg);
+            error_msg
+        })
+    }
+
+    fn connection_state(&self) -> BulbConnectionState {
+        self.connection_state
+            .read()
+            .map(|state| state.clone())
+            .unwrap_or_else(|e| BulbConnectionState::Unavailable { reason: e.to_string() })
+    }
+
+    /// The adapter falls back to dummy credentials when no Tapo device is configured,
+    /// in which case every state change is simulated.
+    fn is_simulated(&self) -> bool {
+        self.ip_address == "127.0.0.1"
+    }
+}
+
+/// Converts an RGB color to the hue (0-360), saturation and brightness (1-100) taken by
+/// the Tapo color bulbs, `None` for black, which the bulbs can only show turned off.
+fn rgb_to_hue_saturation_brightness(red: u8, green: u8, blue: u8) -> Option<(u16, u8, u8)> {
+    let (red, green, blue) = (red as f32 / 255.0, green as f32 / 255.0, blue as f32 / 255.0);
+    let max = red.max(green).max(blue);
+    let delta = max - red.min(green).min(blue);
+
+    if max <= 0.0 {
+        return None;
+    }
+
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == red {
+        60.0 * ((green - blue) / delta).rem_euclid(6.0)
+    } else if max == green {
+        60.0 * ((blue - red) / delta + 2.0)
+    } else {
+        60.0 * ((red - green) / delta + 4.0)
+    };
+
+    let saturation = (delta / max * 100.0).round().clamp(1.0, 100.0);
+    let brightness = (max * 100.0).round().clamp(1.0, 100.0);
+
+    Some((hue.round() as u16 % 360, saturation as u8, brightness as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgb_to_hue_saturation_brightness() {
+        assert_eq!(rgb_to_hue_saturation_brightness(255, 0, 0), Some((0, 100, 100)));
+        assert_eq!(rgb_to_hue_saturation_brightness(0, 255, 0), Some((120, 100, 100)));
+        assert_eq!(rgb_to_hue_saturation_brightness(0, 0, 128), Some((240, 100, 50)));
+        assert_eq!(rgb_to_hue_saturation_brightness(255, 255, 255), Some((0, 1, 100)));
+        assert_eq!(rgb_to_hue_saturation_brightness(0, 0, 0), None);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod lifecycle_webhook;
+pub mod prediction_webhook;
+mod retrying_client;
+
+#[cfg(test)]
+mod mock_server;
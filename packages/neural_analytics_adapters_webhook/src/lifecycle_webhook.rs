@@ -0,0 +1,123 @@
+use std::env;
+
+use async_trait::async_trait;
+use log::{debug, info};
+use neural_analytics_domain::domain::{
+    models::lifecycle_event::LifecycleEvent, ports::output::lifecycle_notifier::LifecycleNotifierPort,
+};
+
+use crate::retrying_client::RetryingClient;
+
+/// Environment variable holding the comma-separated URLs notified of the lifecycle events
+pub const WEBHOOK_URLS_VARIABLE: &str = "NEURAL_ANALYTICS_WEBHOOK_URLS";
+
+/// Posts the lifecycle events of the headset sessions as JSON to user-provided URLs,
+/// so lab management tools can track the usage of an installation without polling.
+///
+/// Each URL is retried with an exponential backoff, independently of the others.
+pub struct LifecycleWebhookAdapter {
+    client: RetryingClient,
+    urls: Vec<String>,
+}
+
+impl LifecycleWebhookAdapter {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            client: RetryingClient::default(),
+            urls,
+        }
+    }
+
+    /// Creates the adapter from the URLs in `NEURAL_ANALYTICS_WEBHOOK_URLS`.
+    ///
+    /// # Returns
+    /// - `Option<LifecycleWebhookAdapter>`: `None` when no URL is configured.
+    pub fn from_env() -> Option<Self> {
+        let urls: Vec<String> = env::var(WEBHOOK_URLS_VARIABLE)
+            .ok()?
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        if urls.is_empty() {
+            return None;
+        }
+
+        info!("Notifying the session lifecycle to {} webhook(s)", urls.len());
+        Some(Self::new(urls))
+    }
+}
+
+#[async_trait]
+impl LifecycleNotifierPort for LifecycleWebhookAdapter {
+    async fn notify(&self, event: &LifecycleEvent) -> Result<(), String> {
+        let mut errors = Vec::new();
+
+        for url in &self.urls {
+            match self.client.post(url, event).await {
+                Ok(()) => debug!("Notified the {:?} lifecycle event to {}", event.kind, url),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+    use neural_analytics_domain::domain::models::lifecycle_event::LifecycleEventKind;
+    use std::time::Duration;
+
+    fn event() -> LifecycleEvent {
+        LifecycleEvent {
+            kind: LifecycleEventKind::SessionStarted,
+            timestamp: 0,
+            session_id: 0,
+            device_id: None,
+            firmware_version: None,
+            user_profile: None,
+            summary: None,
+        }
+    }
+
+    fn adapter(urls: Vec<String>) -> LifecycleWebhookAdapter {
+        let mut adapter = LifecycleWebhookAdapter::new(urls);
+        adapter.client.initial_backoff = Duration::from_millis(1);
+        adapter
+    }
+
+    #[tokio::test]
+    async fn test_notify_retries_until_the_endpoint_accepts_the_event() {
+        let server = MockServer::start(vec![500, 200]).await;
+
+        adapter(vec![server.url.clone()]).notify(&event()).await.unwrap();
+
+        let bodies = server.bodies();
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies[1].contains("\"session_id\":0"), "{}", bodies[1]);
+    }
+
+    #[tokio::test]
+    async fn test_notify_reports_failing_urls_after_retrying() {
+        let failing = MockServer::start(vec![500]).await;
+        let working = MockServer::start(vec![200]).await;
+
+        let error = adapter(vec![failing.url.clone(), working.url.clone()])
+            .notify(&event())
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("after 4 attempts"), "{}", error);
+        assert_eq!(failing.bodies().len(), 4);
+        assert_eq!(working.bodies().len(), 1);
+    }
+}
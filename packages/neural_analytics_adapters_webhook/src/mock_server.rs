@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Local HTTP endpoint for the tests, answering the requests with the given statuses in
+/// order, the last one repeated, and keeping the bodies it received.
+pub(crate) struct MockServer {
+    pub url: String,
+    bodies: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    pub async fn start(statuses: Vec<u16>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let received = bodies.clone();
+
+        tokio::spawn(async move {
+            let mut answered = 0;
+
+            while let Ok((mut stream, _)) = listener.accept().await {
+                received.lock().unwrap().push(read_body(&mut stream).await);
+
+                let status = statuses[answered.min(statuses.len() - 1)];
+                answered += 1;
+
+                let response = format!("HTTP/1.1 {} Mock\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        Self { url, bodies }
+    }
+
+    /// Bodies of the requests received so far.
+    pub fn bodies(&self) -> Vec<String> {
+        self.bodies.lock().unwrap().clone()
+    }
+}
+
+/// Reads a request up to the end of its body, returning the body.
+async fn read_body(stream: &mut TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+
+    loop {
+        let read = stream.read(&mut buffer).await.unwrap_or(0);
+        if read == 0 {
+            return String::new();
+        }
+        request.extend_from_slice(&buffer[..read]);
+
+        let text = String::from_utf8_lossy(&request);
+        if let Some(headers_end) = text.find("\r\n\r\n") {
+            let body_start = headers_end + 4;
+            let length = text[..headers_end]
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+
+            if request.len() >= body_start + length {
+                return String::from_utf8_lossy(&request[body_start..body_start + length]).into_owned();
+            }
+        }
+    }
+}
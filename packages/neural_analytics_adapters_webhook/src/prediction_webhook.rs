@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use log::{debug, warn};
+use neural_analytics_domain::domain::{
+    models::webhook_notification::WebhookNotification, ports::output::prediction_webhook::PredictionWebhookPort,
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::retrying_client::RetryingClient;
+
+// Notifications waiting for the delivery of the previous ones before the next ones are dropped
+const QUEUE_CAPACITY: usize = 16;
+
+/// Posts the predictions mapped to a `Webhook` action as JSON to the URL of the action.
+///
+/// The notifications are delivered in the background, one at a time and in order, each
+/// retried with an exponential backoff up to 4 attempts. The capture loop only queues
+/// them, up to 16 waiting for an endpoint that is down, the next ones dropped.
+pub struct PredictionWebhookAdapter {
+    sender: mpsc::Sender<(String, WebhookNotification)>,
+}
+
+impl PredictionWebhookAdapter {
+    /// Starts the delivery task in the background. Returns immediately.
+    pub fn new() -> Self {
+        Self::with_client(RetryingClient::default())
+    }
+
+    fn with_client(client: RetryingClient) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<(String, WebhookNotification)>(QUEUE_CAPACITY);
+
+        tokio::spawn(async move {
+            while let Some((url, notification)) = receiver.recv().await {
+                match client.post(&url, &notification).await {
+                    Ok(()) => debug!("Notified the prediction '{}' to {}", notification.prediction, url),
+                    Err(e) => warn!("Prediction '{}' not notified: {}", notification.prediction, e),
+                }
+            }
+        });
+
+        Self { sender }
+    }
+}
+
+impl Default for PredictionWebhookAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PredictionWebhookPort for PredictionWebhookAdapter {
+    /// Queues the notification for the delivery task, without waiting for the endpoint.
+    async fn notify(&self, url: &str, notification: &WebhookNotification) -> Result<(), String> {
+        self.sender
+            .try_send((url.to_string(), notification.clone()))
+            .map_err(|e| match e {
+                TrySendError::Full(_) => format!("{} notifications already waiting for delivery", QUEUE_CAPACITY),
+                TrySendError::Closed(_) => "The webhook delivery task stopped".to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+    use std::time::Duration;
+
+    fn notification(prediction: &str) -> WebhookNotification {
+        WebhookNotification {
+            prediction: prediction.to_string(),
+            timestamp: "2025-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    fn adapter() -> PredictionWebhookAdapter {
+        PredictionWebhookAdapter::with_client(RetryingClient {
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        })
+    }
+
+    /// Waits for the server to receive the given number of requests.
+    async fn wait_for_requests(server: &MockServer, requests: usize) -> Vec<String> {
+        for _ in 0..200 {
+            let bodies = server.bodies();
+            if bodies.len() >= requests {
+                return bodies;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        server.bodies()
+    }
+
+    #[tokio::test]
+    async fn test_notify_posts_the_prediction_in_the_background() {
+        let server = MockServer::start(vec![200]).await;
+
+        adapter().notify(&server.url, &notification("green")).await.unwrap();
+
+        let bodies = wait_for_requests(&server, 1).await;
+        assert_eq!(
+            bodies,
+            vec![r#"{"prediction":"green","timestamp":"2025-01-01T00:00:00+00:00"}"#.to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notify_gives_up_after_the_last_attempt() {
+        let server = MockServer::start(vec![500]).await;
+        let adapter = adapter();
+
+        adapter.notify(&server.url, &notification("red")).await.unwrap();
+        adapter.notify(&server.url, &notification("green")).await.unwrap();
+
+        // Each notification is retried up to 4 times, then the next one is delivered
+        let bodies = wait_for_requests(&server, 8).await;
+        assert_eq!(bodies.len(), 8);
+        assert!(bodies[3].contains("red") && bodies[4].contains("green"));
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use log::warn;
+use serde::Serialize;
+
+// Attempts per request, the first one included
+const MAX_ATTEMPTS: u32 = 4;
+
+// Wait before the first retry, doubled on every following one
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+// Longest wait for an endpoint to answer, so a hung one does not hold the following requests
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts JSON bodies, retrying each request with an exponential backoff until it succeeds
+/// or runs out of attempts.
+#[derive(Clone)]
+pub(crate) struct RetryingClient {
+    client: reqwest::Client,
+    pub(crate) max_attempts: u32,
+    pub(crate) initial_backoff: Duration,
+}
+
+impl Default for RetryingClient {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            max_attempts: MAX_ATTEMPTS,
+            initial_backoff: INITIAL_BACKOFF,
+        }
+    }
+}
+
+impl RetryingClient {
+    /// Wait before the given retry, starting at 1.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff * 2u32.saturating_pow(retry - 1)
+    }
+
+    pub(crate) async fn post<T: Serialize + ?Sized>(&self, url: &str, body: &T) -> Result<(), String> {
+        let mut attempt = 1;
+
+        loop {
+            let result = self
+                .client
+                .post(url)
+                .json(body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if attempt < self.max_attempts => {
+                    warn!("Webhook {} failed (attempt {}/{}): {}", url, attempt, self.max_attempts, e);
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(format!("Webhook {} failed after {} attempts: {}", url, attempt, e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_on_every_retry() {
+        let client = RetryingClient::default();
+
+        assert_eq!(client.backoff(1), Duration::from_millis(500));
+        assert_eq!(client.backoff(2), Duration::from_millis(1000));
+        assert_eq!(client.backoff(3), Duration::from_millis(2000));
+    }
+}
@@ -0,0 +1,468 @@
+//! Debug frontend of Neural Analytics.
+//!
+//! A single egui window with the raw signals, the state machine state, the
+//! metrics derived from the core events and the core logs. It consumes the same
+//! typed bridge as the Slint GUI, for developers that need more introspection
+//! than the polished UI exposes.
+//!
+//! Run with `cargo run -p neural_analytics_bridge --features debug-frontend`.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints};
+use log::{LevelFilter, Log, Metadata, Record};
+use neural_analytics_bridge::domain::models::{
+    band_powers::{BandPowers, EEG_BANDS}, bulb_state::BulbState, channel_config::ChannelConfig, core_health::CoreHealth, signal_unit::SignalUnit,
+    window_prediction::WindowPrediction,
+};
+use neural_analytics_bridge::domain::models::context_snapshot::ContextSnapshot;
+use neural_analytics_bridge::config_file::load_config_from_env;
+use neural_analytics_bridge::{
+    core_event_channel, set_simulated_impedance, simulated_impedance, spawn_core, CoreClient, CoreEvent, CoreEventReceiver,
+};
+
+// Samples kept per channel, about 4 seconds at the BrainBit sampling rate
+const SIGNAL_HISTORY: usize = 1024;
+const LOG_HISTORY: usize = 500;
+const REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+// Impedance range of the sliders, in kOhm, covering every contact quality
+const SIMULATED_IMPEDANCE_RANGE: std::ops::RangeInclusive<u16> = 0..=3000;
+
+type LogLines = Arc<Mutex<VecDeque<String>>>;
+
+/// Logger keeping the last records in memory, so they can be shown in the window
+struct MemoryLogger {
+    lines: LogLines,
+}
+
+impl Log for MemoryLogger {
+    // Follows the maximum level, which `set_log_level` changes at runtime
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == LOG_HISTORY {
+            lines.pop_front();
+        }
+        lines.push_back(format!("[{}] {}: {}", record.level(), record.target(), record.args()));
+    }
+
+    fn flush(&self) {}
+}
+
+/// State of the debug window, built only from the bridge events
+struct DebugApp {
+    receiver: CoreEventReceiver,
+    core: CoreClient,
+    logs: LogLines,
+    signals: BTreeMap<String, VecDeque<f32>>,
+    event_counts: BTreeMap<&'static str, u64>,
+    // Events waiting in the channel when the last frame started, the frontend stage of the queues
+    event_backlog: usize,
+    core_health: Option<CoreHealth>,
+    last_window_id: Option<u64>,
+    color_thinking: Option<String>,
+    window_prediction: Option<WindowPrediction>,
+    last_bulb: Option<(BulbState, bool, Option<u64>)>,
+    band_powers: BTreeMap<String, BandPowers>,
+    memory_usage: HashMap<String, usize>,
+    memory_budget_bytes: usize,
+    diagnostics_issues: Vec<String>,
+    channel_config: ChannelConfig,
+    signal_unit: SignalUnit,
+    last_snapshot_path: Option<String>,
+    test_signal_issues: Option<Vec<String>>,
+}
+
+impl DebugApp {
+    fn new(receiver: CoreEventReceiver, core: CoreClient, logs: LogLines) -> Self {
+        Self {
+            receiver,
+            core,
+            logs,
+            signals: BTreeMap::new(),
+            event_counts: BTreeMap::new(),
+            event_backlog: 0,
+            core_health: None,
+            last_window_id: None,
+            color_thinking: None,
+            window_prediction: None,
+            last_bulb: None,
+            band_powers: BTreeMap::new(),
+            memory_usage: HashMap::new(),
+            memory_budget_bytes: 0,
+            diagnostics_issues: Vec::new(),
+            channel_config: ChannelConfig::default(),
+            signal_unit: SignalUnit::default(),
+            last_snapshot_path: None,
+            test_signal_issues: None,
+        }
+    }
+
+    /// Applies every pending core event to the window state
+    fn drain_events(&mut self) {
+        self.event_backlog = self.receiver.len();
+
+        while let Ok(event) = self.receiver.try_recv() {
+            *self.event_counts.entry(event_kind(&event)).or_default() += 1;
+
+            match event {
+                CoreEvent::Initialized { diagnostics_issues, channel_config, signal_unit, .. } => {
+                    self.diagnostics_issues = diagnostics_issues;
+                    self.channel_config = channel_config;
+                    self.signal_unit = signal_unit;
+                }
+                CoreEvent::PredictionActed {
+                    window_id,
+                    headset_data,
+                    color_thinking,
+                    actuation,
+                    band_powers,
+                    window_prediction,
+                } => {
+                    self.push_signals(headset_data);
+                    self.band_powers = band_powers.unwrap_or_default().into_iter().collect();
+                    self.last_window_id = window_id;
+                    self.color_thinking = color_thinking.or(self.color_thinking.take());
+                    self.window_prediction = window_prediction;
+
+                    if let Some(actuation) = actuation {
+                        self.last_bulb = Some((actuation.bulb_state, actuation.is_confirmed, actuation.latency_ms));
+                    }
+                }
+                CoreEvent::BulbStateChanged { bulb_state, is_confirmed, latency_ms, .. } => {
+                    self.last_bulb = Some((bulb_state, is_confirmed, latency_ms));
+                }
+                CoreEvent::MemoryPressure { memory_usage, memory_budget_bytes } => {
+                    self.memory_usage = memory_usage;
+                    self.memory_budget_bytes = memory_budget_bytes;
+                }
+                CoreEvent::Heartbeat { core_health } => {
+                    self.core_health = Some(core_health);
+                }
+                CoreEvent::ContextSnapshot { snapshot } => {
+                    self.last_snapshot_path = save_snapshot(&snapshot);
+                }
+                CoreEvent::DiagnosticBundleCreated { result } => match result {
+                    Ok(path) => self.last_snapshot_path = Some(path),
+                    Err(e) => log::error!("Failed to create the diagnostic bundle: {}", e),
+                },
+                CoreEvent::TestSignalVerified { headset_data, diagnostics_issues } => {
+                    self.push_signals(headset_data);
+                    self.test_signal_issues = Some(diagnostics_issues);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Appends a window to the plotted history of each channel
+    fn push_signals(&mut self, headset_data: HashMap<String, Vec<f32>>) {
+        for (channel, values) in headset_data {
+            let history = self.signals.entry(channel).or_default();
+            history.extend(values);
+
+            let overflow = history.len().saturating_sub(SIGNAL_HISTORY);
+            history.drain(..overflow);
+        }
+    }
+
+    fn show_state(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            match &self.core_health {
+                Some(health) => {
+                    ui.label(format!("State: {}", health.state));
+                    ui.separator();
+                    ui.label(format!("Headset connected: {}", health.is_headset_connected));
+                    ui.separator();
+                    ui.label(format!("Bulb reachable: {:?}", health.is_bulb_reachable));
+                    ui.separator();
+                    ui.label(format!("Last tick: {:?}", health.last_tick_timestamp));
+                }
+                None => {
+                    ui.label("State: waiting for the first heartbeat...");
+                }
+            }
+
+            ui.separator();
+            if ui.button("Dump state").clicked() {
+                self.core.request_context_snapshot();
+            }
+            if ui.button("Diagnostic bundle").clicked() {
+                self.core.create_diagnostic_bundle(None);
+            }
+            if ui.button("Test signal").clicked() {
+                self.core.request_test_signal();
+            }
+            if let Some(path) = &self.last_snapshot_path {
+                ui.label(format!("Saved to {}", path));
+            }
+        });
+    }
+
+    fn show_metrics(&self, ui: &mut egui::Ui) {
+        ui.heading("Metrics");
+
+        egui::Grid::new("metrics").striped(true).show(ui, |ui| {
+            for (kind, count) in &self.event_counts {
+                ui.label(*kind);
+                ui.label(count.to_string());
+                ui.end_row();
+            }
+
+            ui.label("Event backlog");
+            ui.label(self.event_backlog.to_string());
+            ui.end_row();
+
+            ui.label("Last window");
+            ui.label(format!("{:?}", self.last_window_id));
+            ui.end_row();
+
+            ui.label("Color thinking");
+            ui.label(self.color_thinking.as_deref().unwrap_or("-"));
+            ui.end_row();
+
+            if let Some(prediction) = &self.window_prediction {
+                ui.label("Last window prediction");
+                ui.label(format!(
+                    "{} confidence={} latency={:.1}ms",
+                    prediction.label,
+                    prediction
+                        .confidence
+                        .map_or("-".to_string(), |confidence| format!("{:.0}%", confidence * 100.0)),
+                    prediction.latency_ms
+                ));
+                ui.end_row();
+            }
+
+            if let Some((bulb_state, is_confirmed, latency_ms)) = &self.last_bulb {
+                ui.label("Last bulb actuation");
+                ui.label(format!("{:?} confirmed={} latency={:?}ms", bulb_state, is_confirmed, latency_ms));
+                ui.end_row();
+            }
+        });
+
+        if !self.band_powers.is_empty() {
+            ui.separator();
+            ui.label("Band powers");
+
+            for (channel, band_powers) in &self.band_powers {
+                let bands = EEG_BANDS
+                    .iter()
+                    .map(|(band, _, _)| format!("{}={:.2}", band, band_powers.of(band).unwrap_or_default()))
+                    .collect::<Vec<_>>();
+                ui.label(format!("  {}: {}", channel, bands.join(" ")));
+            }
+        }
+
+        if !self.memory_usage.is_empty() {
+            ui.separator();
+            ui.label(format!("Memory budget: {} bytes", self.memory_budget_bytes));
+
+            for (buffer, bytes) in &self.memory_usage {
+                ui.label(format!("  {}: {} bytes", buffer, bytes));
+            }
+        }
+
+        if let Some(issues) = &self.test_signal_issues {
+            ui.separator();
+
+            if issues.is_empty() {
+                ui.colored_label(egui::Color32::GREEN, "Test signal verified");
+            }
+
+            for issue in issues {
+                ui.colored_label(egui::Color32::RED, issue);
+            }
+        }
+
+        if !self.diagnostics_issues.is_empty() {
+            ui.separator();
+            ui.label("Diagnostics issues");
+
+            for issue in &self.diagnostics_issues {
+                ui.colored_label(egui::Color32::YELLOW, issue);
+            }
+        }
+    }
+
+    /// Sliders for the impedances of the synthetic headset, only shown in mock mode
+    fn show_simulated_impedance(&self, ui: &mut egui::Ui) {
+        let Some(impedances) = simulated_impedance() else {
+            return;
+        };
+
+        ui.separator();
+        ui.heading("Simulated impedance");
+
+        let mut electrodes: Vec<_> = impedances.into_iter().collect();
+        electrodes.sort();
+
+        for (electrode, mut impedance) in electrodes {
+            let slider = egui::Slider::new(&mut impedance, SIMULATED_IMPEDANCE_RANGE)
+                .suffix(" kOhm")
+                .text(electrode.as_str());
+
+            if ui.add(slider).changed() {
+                if let Err(e) = set_simulated_impedance(&electrode, impedance) {
+                    log::warn!("{}", e);
+                }
+            }
+        }
+    }
+
+    fn show_logs(&self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+            for line in self.logs.lock().unwrap().iter() {
+                ui.monospace(line);
+            }
+        });
+    }
+
+    fn show_signals(&self, ui: &mut egui::Ui) {
+        if self.signals.is_empty() {
+            ui.label("No EEG data received yet.");
+            return;
+        }
+
+        let plot_height = ui.available_height() / self.signals.len() as f32;
+
+        for (channel, values) in &self.signals {
+            let points: PlotPoints = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| [i as f64, *value as f64])
+                .collect();
+
+            ui.label(format!(
+                "{} ({}, {})",
+                self.channel_config.display_name_of(channel),
+                self.channel_config.position_of(channel),
+                channel
+            ));
+            Plot::new(channel)
+                .height(plot_height - 24.0)
+                .allow_scroll(false)
+                .y_axis_label(self.signal_unit.symbol())
+                .show(ui, |plot_ui| plot_ui.line(Line::new(points)));
+        }
+    }
+}
+
+impl eframe::App for DebugApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.drain_events();
+
+        egui::TopBottomPanel::top("state").show(ctx, |ui| self.show_state(ui));
+        egui::SidePanel::left("metrics").min_width(260.0).show(ctx, |ui| {
+            self.show_metrics(ui);
+            self.show_simulated_impedance(ui);
+        });
+        egui::TopBottomPanel::bottom("logs")
+            .resizable(true)
+            .default_height(180.0)
+            .show(ctx, |ui| self.show_logs(ui));
+        egui::CentralPanel::default().show(ctx, |ui| self.show_signals(ui));
+
+        // The events arrive from another thread, so keep polling the channel
+        ctx.request_repaint_after(REPAINT_INTERVAL);
+    }
+}
+
+/// Short name of the event, used as metric key
+fn event_kind(event: &CoreEvent) -> &'static str {
+    match event {
+        CoreEvent::Initialized { .. } => "initialized",
+        CoreEvent::HeadsetConnected { .. } => "headset_connected",
+        CoreEvent::HeadsetDisconnected { .. } => "headset_disconnected",
+        CoreEvent::HeadsetCalibrating { .. } => "headset_calibrating",
+        CoreEvent::CheckingSignalQuality { .. } => "checking_signal_quality",
+        CoreEvent::HeadsetCalibrated { .. } => "headset_calibrated",
+        CoreEvent::PredictionActed { .. } => "prediction_acted",
+        CoreEvent::BulbStateChanged { .. } => "bulb_state_changed",
+        CoreEvent::BulbConnection { .. } => "bulb_connection",
+        CoreEvent::MemoryPressure { .. } => "memory_pressure",
+        CoreEvent::Heartbeat { .. } => "heartbeat",
+        CoreEvent::ModelReloaded { .. } => "model_reloaded",
+        CoreEvent::SuggestedThresholds { .. } => "suggested_thresholds",
+        CoreEvent::ContextSnapshot { .. } => "context_snapshot",
+        CoreEvent::TestSignalVerified { .. } => "test_signal_verified",
+        CoreEvent::PredictionExplanation { .. } => "prediction_explanation",
+        CoreEvent::SessionRotated { .. } => "session_rotated",
+        CoreEvent::CalibrationReused { .. } => "calibration_reused",
+        CoreEvent::ClockDriftWarning { .. } => "clock_drift_warning",
+        CoreEvent::StaleDataSkipped { .. } => "stale_data_skipped",
+        CoreEvent::InferencePaused { .. } => "inference_paused",
+        CoreEvent::ExhibitionCue { .. } => "exhibition_cue",
+        CoreEvent::OperationProgress { .. } => "operation_progress",
+        CoreEvent::UntestedFirmware { .. } => "untested_firmware",
+        CoreEvent::CommandPanicked { .. } => "command_panicked",
+        CoreEvent::IntegrityViolation { .. } => "integrity_violation",
+        CoreEvent::DiagnosticBundleCreated { .. } => "diagnostic_bundle_created",
+        CoreEvent::ShutdownRequested => "shutdown_requested",
+    }
+}
+
+/// Writes the snapshot in the working directory, so it can be attached to an issue report
+fn save_snapshot(snapshot: &ContextSnapshot) -> Option<String> {
+    let path = format!("neural_analytics_snapshot_{}.json", snapshot.timestamp);
+
+    let result = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+
+    match result {
+        Ok(()) => {
+            log::info!("Context snapshot saved to {}", path);
+            Some(path)
+        }
+        Err(e) => {
+            log::error!("Failed to save the context snapshot: {}", e);
+            None
+        }
+    }
+}
+
+fn main() -> eframe::Result {
+    let logs = LogLines::default();
+
+    log::set_boxed_logger(Box::new(MemoryLogger { lines: logs.clone() }))
+        .expect("BUG: Failed to install the debug logger");
+    log::set_max_level(LevelFilter::Info);
+
+    // The core runs in the tokio workers while egui owns the main thread
+    let runtime = tokio::runtime::Runtime::new().expect("BUG: Failed to create the tokio runtime");
+    let _guard = runtime.enter();
+
+    let config = load_config_from_env().unwrap_or_else(|e| {
+        eprintln!("Invalid configuration file {}", e);
+        std::process::exit(1);
+    });
+
+    let (sender, receiver) = core_event_channel();
+    let core = runtime
+        .block_on(spawn_core(config, sender))
+        .expect("BUG: The core initialization panicked")
+        .expect("BUG: Failed to initialize core");
+    let client = core.client();
+
+    let result = eframe::run_native(
+        "Neural Analytics - Debug",
+        eframe::NativeOptions::default(),
+        Box::new(|_creation_context| Ok(Box::new(DebugApp::new(receiver, client, logs)))),
+    );
+
+    // Leave the devices idle once the window is closed
+    runtime.block_on(core.shutdown());
+
+    result
+}
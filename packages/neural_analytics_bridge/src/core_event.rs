@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use neural_analytics_core::domain::events::NeuralAnalyticsEvents;
+use neural_analytics_core::domain::models::{
+    band_powers::BandPowers, bulb_connection::BulbConnectionState, bulb_state::BulbState, calibration_thresholds::ThresholdSuggestion, causality_record::CausalityRecord,
+    channel_config::ChannelConfig, context_snapshot::ContextSnapshot, core_health::CoreHealth,
+    event_data::EventData, exhibition::ExhibitionCue, impedance_snapshot::ImpedanceSnapshot, integrity_report::IntegrityReport, operation_progress::OperationProgress,
+    signal_quality::SignalQuality, signal_unit::SignalUnit, ui_hint::UiHint, window_prediction::WindowPrediction,
+};
+
+/// Actuation attempt of the bulb caused by a prediction; when `is_confirmed` is false the bulb kept its previous state.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "event-trace", derive(serde::Serialize, serde::Deserialize))]
+pub struct BulbActuation {
+    pub bulb_state: BulbState,
+    pub is_confirmed: bool,
+    pub is_simulated: bool,
+    pub latency_ms: Option<u64>,
+    pub causality: Option<CausalityRecord>,
+}
+
+/// External event of the core, with only the data that event carries
+///
+/// The events of a state transition carry the `UiHint` of the state they enter, telling
+/// frontends which screen to show.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "event-trace", derive(serde::Serialize, serde::Deserialize), serde(tag = "kind"))]
+pub enum CoreEvent {
+    /// The core finished its initialization, with the issues found by the startup diagnostics,
+    /// the labels to present the channels with, the unit of their samples and the classes the
+    /// model predicts, in the order of its output.
+    Initialized {
+        diagnostics_issues: Vec<String>,
+        channel_config: ChannelConfig,
+        signal_unit: SignalUnit,
+        class_labels: Vec<String>,
+        ui_hint: Option<UiHint>,
+    },
+    HeadsetConnected { ui_hint: Option<UiHint> },
+    HeadsetDisconnected { ui_hint: Option<UiHint> },
+    /// Impedance of each electrode while the headset is being calibrated.
+    HeadsetCalibrating { impedance_data: HashMap<String, u16> },
+    /// Quality of the signal of each electrode while calibrating a headset that cannot measure impedance.
+    CheckingSignalQuality { signal_quality: HashMap<String, SignalQuality> },
+    /// The headset is calibrated, with the final impedance of its electrodes when it was measured.
+    HeadsetCalibrated {
+        impedance: Option<ImpedanceSnapshot>,
+        ui_hint: Option<UiHint>,
+    },
+    /// A predicted window of EEG data, with the actuation the prediction caused if it drives the bulb.
+    ///
+    /// Sent once both are known, so the color shown and the state of the bulb never disagree.
+    PredictionActed {
+        window_id: Option<u64>,
+        headset_data: HashMap<String, Vec<f32>>,
+        color_thinking: Option<String>,
+        actuation: Option<BulbActuation>,
+        band_powers: Option<HashMap<String, BandPowers>>,
+        /// Prediction of the model for the window before smoothing, with its confidence.
+        window_prediction: Option<WindowPrediction>,
+    },
+    /// A bulb actuation; when `is_confirmed` is false the bulb kept its previous state.
+    BulbStateChanged {
+        bulb_state: BulbState,
+        is_confirmed: bool,
+        is_simulated: bool,
+        latency_ms: Option<u64>,
+        causality: Option<CausalityRecord>,
+    },
+    /// The connection to the smart bulb changed, e.g. it is unavailable after every retry failed.
+    BulbConnection { connection_state: BulbConnectionState },
+    MemoryPressure {
+        memory_usage: HashMap<String, usize>,
+        memory_budget_bytes: usize,
+    },
+    Heartbeat { core_health: CoreHealth },
+    /// The model was replaced on disk and reloaded, with the classes of the new one.
+    ModelReloaded { model_path: String, class_labels: Vec<String> },
+    /// Thresholds adapted to the noise floor observed while calibrating, see `apply_calibration_thresholds`.
+    SuggestedThresholds { suggestion: ThresholdSuggestion },
+    /// Summary of the core context, answering `request_context_snapshot`.
+    ContextSnapshot { snapshot: Box<ContextSnapshot> },
+    /// Result of `request_test_signal`, the pipeline works when there are no issues.
+    TestSignalVerified {
+        headset_data: HashMap<String, Vec<f32>>,
+        diagnostics_issues: Vec<String>,
+    },
+    /// Contribution of each channel to the prediction of a window, when `explain_predictions` is enabled.
+    PredictionExplanation {
+        window_id: u64,
+        color_thinking: String,
+        channel_contributions: HashMap<String, f32>,
+    },
+    /// A new session started because the headset or the user profile changed.
+    SessionRotated {
+        session_id: u64,
+        reason: String,
+        recording_path: Option<String>,
+    },
+    /// The calibration was skipped, reusing the one of the same user and headset; see `CoreClient::request_recalibration`.
+    CalibrationReused {
+        calibration_timestamp: i64,
+        max_impedance: u16,
+    },
+    /// The board clock drifted from the host clock more than `clock_drift_threshold_ms`.
+    ClockDriftWarning { drift_ms: f64 },
+    /// The window was buffered by the board during a stall, it was recorded without a prediction.
+    StaleDataSkipped { window_id: u64, data_age_ms: i64 },
+    /// The inference was paused after `streak` garbage predictions in a row, the electrodes
+    /// likely lost contact; see `CoreClient::request_recalibration`.
+    InferencePaused { window_id: u64, streak: u64 },
+    /// A change of the prediction shown in exhibition mode, on the bulb and on a fullscreen
+    /// overlay, held for `hold_ms` of the cue.
+    ExhibitionCue { cue: ExhibitionCue },
+    /// Progress of a long-running operation, such as the headset connection; see `cancel_operation`.
+    OperationProgress { progress: OperationProgress },
+    /// The headset runs a firmware version the pipeline was never tested with.
+    UntestedFirmware { firmware_version: String },
+    /// The handler of a command panicked, the command failed but the core keeps running.
+    CommandPanicked { command: String, message: String },
+    /// The session was aborted in strict mode, its data lost its integrity; see `CoreConfig::strict_mode`.
+    /// The headset search stays paused until `resume_headset_search`.
+    IntegrityViolation { report: IntegrityReport },
+    /// A diagnostic bundle asked with `create_diagnostic_bundle` was written to the
+    /// archive, or the reason it could not be.
+    DiagnosticBundleCreated { result: Result<String, String> },
+    /// Shutdown asked through the remote API, the frontend should drop its `CoreHandle` and exit.
+    /// Never sent by the core itself.
+    ShutdownRequested,
+}
+
+impl CoreEvent {
+    /// Builds the typed event from the name and data sent by the core
+    ///
+    /// # Arguments
+    /// - `event_name`: The name of the external event.
+    /// - `data`: The `EventData` sent along with the event.
+    ///
+    /// # Returns
+    /// - `Option<CoreEvent>`: `None` if the event is unknown or misses its required data.
+    pub fn from_event_data(event_name: &str, data: &EventData) -> Option<Self> {
+        let event = match NeuralAnalyticsEvents::from_string(event_name)? {
+            NeuralAnalyticsEvents::InitializedCoreEvent => CoreEvent::Initialized {
+                diagnostics_issues: data.diagnostics_issues.clone().unwrap_or_default(),
+                channel_config: data.channel_config.clone().unwrap_or_default(),
+                signal_unit: data.signal_unit.unwrap_or_default(),
+                class_labels: data.class_labels.clone().unwrap_or_default(),
+                ui_hint: data.ui_hint.clone(),
+            },
+            NeuralAnalyticsEvents::HeadsetConnectedEvent => CoreEvent::HeadsetConnected {
+                ui_hint: data.ui_hint.clone(),
+            },
+            NeuralAnalyticsEvents::HeadsetDisconnectedEvent => CoreEvent::HeadsetDisconnected {
+                ui_hint: data.ui_hint.clone(),
+            },
+            NeuralAnalyticsEvents::HeadsetCalibratingEvent => match (&data.impedance_data, &data.signal_quality) {
+                (Some(impedance_data), _) => CoreEvent::HeadsetCalibrating {
+                    impedance_data: impedance_data.clone(),
+                },
+                (None, Some(signal_quality)) => CoreEvent::CheckingSignalQuality {
+                    signal_quality: signal_quality.clone(),
+                },
+                (None, None) => return None,
+            },
+            NeuralAnalyticsEvents::HeadsetCalibratedEvent => CoreEvent::HeadsetCalibrated {
+                impedance: data.calibrated_impedance.clone(),
+                ui_hint: data.ui_hint.clone(),
+            },
+            NeuralAnalyticsEvents::PredictionActedEvent => CoreEvent::PredictionActed {
+                window_id: data.window_id,
+                headset_data: data.headset_data.clone()?,
+                color_thinking: data.color_thinking.clone(),
+                actuation: data.bulb_state.map(|bulb_state| BulbActuation {
+                    bulb_state,
+                    is_confirmed: data.is_bulb_confirmed.unwrap_or(false),
+                    is_simulated: data.is_bulb_simulated.unwrap_or(false),
+                    latency_ms: data.bulb_latency_ms,
+                    causality: data.causality.clone(),
+                }),
+                band_powers: data.band_powers.clone(),
+                window_prediction: data.window_prediction.clone(),
+            },
+            NeuralAnalyticsEvents::BulbStateChangedEvent => CoreEvent::BulbStateChanged {
+                bulb_state: data.bulb_state?,
+                is_confirmed: data.is_bulb_confirmed.unwrap_or(false),
+                is_simulated: data.is_bulb_simulated.unwrap_or(false),
+                latency_ms: data.bulb_latency_ms,
+                causality: data.causality.clone(),
+            },
+            NeuralAnalyticsEvents::MemoryPressureEvent => CoreEvent::MemoryPressure {
+                memory_usage: data.memory_usage.clone().unwrap_or_default(),
+                memory_budget_bytes: data.memory_budget_bytes.unwrap_or_default(),
+            },
+            NeuralAnalyticsEvents::HeartbeatEvent => CoreEvent::Heartbeat {
+                core_health: data.core_health.clone()?,
+            },
+            NeuralAnalyticsEvents::ModelReloadedEvent => CoreEvent::ModelReloaded {
+                model_path: data.model_path.clone().unwrap_or_default(),
+                class_labels: data.class_labels.clone().unwrap_or_default(),
+            },
+            NeuralAnalyticsEvents::SuggestedThresholdsEvent => CoreEvent::SuggestedThresholds {
+                suggestion: data.suggested_thresholds?,
+            },
+            NeuralAnalyticsEvents::ContextSnapshotEvent => CoreEvent::ContextSnapshot {
+                snapshot: Box::new(data.context_snapshot.clone()?),
+            },
+            NeuralAnalyticsEvents::TestSignalVerifiedEvent => CoreEvent::TestSignalVerified {
+                headset_data: data.headset_data.clone().unwrap_or_default(),
+                diagnostics_issues: data.diagnostics_issues.clone().unwrap_or_default(),
+            },
+            NeuralAnalyticsEvents::PredictionExplanationEvent => CoreEvent::PredictionExplanation {
+                window_id: data.window_id?,
+                color_thinking: data.color_thinking.clone()?,
+                channel_contributions: data.channel_contributions.clone()?,
+            },
+            NeuralAnalyticsEvents::SessionRotatedEvent => CoreEvent::SessionRotated {
+                session_id: data.session_id?,
+                reason: data.session_rotation_reason.clone().unwrap_or_default(),
+                recording_path: data.recording_path.clone(),
+            },
+            NeuralAnalyticsEvents::CalibrationReusedEvent => CoreEvent::CalibrationReused {
+                calibration_timestamp: data.calibration_timestamp?,
+                max_impedance: data.max_impedance.unwrap_or_default(),
+            },
+            NeuralAnalyticsEvents::ClockDriftWarningEvent => CoreEvent::ClockDriftWarning {
+                drift_ms: data.clock_drift_ms?,
+            },
+            NeuralAnalyticsEvents::StaleDataSkippedEvent => CoreEvent::StaleDataSkipped {
+                window_id: data.window_id?,
+                data_age_ms: data.data_age_ms?,
+            },
+            NeuralAnalyticsEvents::InferencePausedEvent => CoreEvent::InferencePaused {
+                window_id: data.window_id?,
+                streak: data.trash_streak?,
+            },
+            NeuralAnalyticsEvents::ExhibitionCueEvent => CoreEvent::ExhibitionCue {
+                cue: data.exhibition_cue.clone()?,
+            },
+            NeuralAnalyticsEvents::BulbConnectionStateEvent => CoreEvent::BulbConnection {
+                connection_state: data.bulb_connection.clone()?,
+            },
+            NeuralAnalyticsEvents::UntestedFirmwareEvent => CoreEvent::UntestedFirmware {
+                firmware_version: data.firmware_version.clone()?,
+            },
+            NeuralAnalyticsEvents::OperationProgressEvent => CoreEvent::OperationProgress {
+                progress: data.operation_progress?,
+            },
+            NeuralAnalyticsEvents::CommandPanickedEvent => CoreEvent::CommandPanicked {
+                command: data.command_name.clone()?,
+                message: data.error_message.clone().unwrap_or_default(),
+            },
+            NeuralAnalyticsEvents::IntegrityViolationEvent => CoreEvent::IntegrityViolation {
+                report: data.integrity_report.clone()?,
+            },
+            NeuralAnalyticsEvents::DiagnosticBundleCreatedEvent => CoreEvent::DiagnosticBundleCreated {
+                result: match data.error_message.clone() {
+                    Some(e) => Err(e),
+                    None => Ok(data.bundle_path.clone()?),
+                },
+            },
+        };
+
+        Some(event)
+    }
+
+    /// Presentation hints of the state the event enters, `None` for the events within a state
+    pub fn ui_hint(&self) -> Option<&UiHint> {
+        match self {
+            CoreEvent::Initialized { ui_hint, .. }
+            | CoreEvent::HeadsetConnected { ui_hint }
+            | CoreEvent::HeadsetDisconnected { ui_hint }
+            | CoreEvent::HeadsetCalibrated { ui_hint, .. } => ui_hint.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_event_data_maps_bulb_state_changed() {
+        let mut data = EventData::default();
+        data.bulb_state = Some(BulbState::BulbOn);
+        data.is_bulb_confirmed = Some(true);
+        data.bulb_latency_ms = Some(42);
+
+        let event = CoreEvent::from_event_data(
+            &NeuralAnalyticsEvents::BulbStateChangedEvent.to_string(),
+            &data,
+        );
+
+        assert_eq!(
+            event,
+            Some(CoreEvent::BulbStateChanged {
+                bulb_state: BulbState::BulbOn,
+                is_confirmed: true,
+                is_simulated: false,
+                latency_ms: Some(42),
+                causality: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_event_data_maps_prediction_acted_with_its_actuation() {
+        let mut data = EventData::default();
+        data.headset_data = Some(HashMap::from([("O1".to_string(), vec![1.0])]));
+        data.color_thinking = Some("green".to_string());
+        data.window_id = Some(7);
+        data.bulb_state = Some(BulbState::BulbOn);
+        data.is_bulb_confirmed = Some(true);
+
+        let event = CoreEvent::from_event_data(&NeuralAnalyticsEvents::PredictionActedEvent.to_string(), &data);
+
+        let Some(CoreEvent::PredictionActed { window_id, color_thinking, actuation, .. }) = event else {
+            panic!("Unexpected event: {:?}", event);
+        };
+        assert_eq!(window_id, Some(7));
+        assert_eq!(color_thinking.as_deref(), Some("green"));
+        assert_eq!(actuation.map(|actuation| (actuation.bulb_state, actuation.is_confirmed)), Some((BulbState::BulbOn, true)));
+    }
+
+    #[test]
+    fn test_from_event_data_rejects_unknown_or_incomplete_events() {
+        assert_eq!(CoreEvent::from_event_data("unknown_event", &EventData::default()), None);
+        assert_eq!(
+            CoreEvent::from_event_data(
+                &NeuralAnalyticsEvents::PredictionActedEvent.to_string(),
+                &EventData::default(),
+            ),
+            None
+        );
+    }
+}
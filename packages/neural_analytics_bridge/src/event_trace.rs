@@ -0,0 +1,185 @@
+//! Recording and replay of the typed event stream, for reproducible frontend bug reports.
+//!
+//! A trace is a JSON Lines file with one `TracedEvent` per line, as the frontend
+//! received it. Replaying the trace sends the same events through a bridge channel
+//! with the same pacing, so the views go through the same states without a headset,
+//! a bulb or the core.
+//!
+//! The windows of the events are traced in the shape of the `event_trace` payload policy
+//! of the configuration, so a long trace can be kept small. The decimated and summarized
+//! traces replay with fewer samples, or none, in the signal plots.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::time::{Duration, Instant};
+
+use log::{error, info};
+
+use crate::domain::models::payload_policy::PayloadPolicy;
+use crate::domain::services::payload_policy_service::to_wire;
+use crate::{CoreEvent, CoreEventSender};
+
+/// Environment variable holding the file the received events are recorded to
+pub const EVENT_TRACE_VARIABLE: &str = "NEURAL_ANALYTICS_EVENT_TRACE";
+
+/// Line of a trace, an event and when it was received
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TracedEvent {
+    /// Milliseconds since the recording started.
+    pub offset_ms: u64,
+    pub event: CoreEvent,
+}
+
+/// Records the events a frontend receives to a trace file.
+pub struct EventTraceRecorder {
+    file: File,
+    path: String,
+    started: Instant,
+    payload_policy: PayloadPolicy,
+}
+
+impl EventTraceRecorder {
+    /// Starts a trace in the given file, replacing it if it exists.
+    ///
+    /// # Returns
+    /// - `Result<EventTraceRecorder, String>`: An error if the file cannot be created.
+    pub fn create(path: &str) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Could not create event trace {}: {}", path, e))?;
+
+        info!("Recording the core events to {}", path);
+
+        Ok(Self {
+            file,
+            path: path.to_string(),
+            started: Instant::now(),
+            payload_policy: PayloadPolicy::default(),
+        })
+    }
+
+    /// Starts a trace in the file set in `NEURAL_ANALYTICS_EVENT_TRACE`
+    ///
+    /// # Returns
+    /// - `Option<EventTraceRecorder>`: `None` when the variable is not set or the file cannot be created.
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var(EVENT_TRACE_VARIABLE).ok().filter(|path| !path.is_empty())?;
+
+        Self::create(&path).inspect_err(|e| error!("{}", e)).ok()
+    }
+
+    /// Traces the windows of the events in the shape of the policy, instead of in full.
+    pub fn with_payload_policy(mut self, payload_policy: PayloadPolicy) -> Self {
+        self.payload_policy = payload_policy;
+        self
+    }
+
+    /// Appends an event to the trace
+    ///
+    /// Every line is written as soon as the event is received, so the trace keeps the
+    /// events that led to a crash of the frontend.
+    pub fn record(&mut self, event: &CoreEvent) {
+        let line = TracedEvent {
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            event: event.clone(),
+        };
+
+        let written = to_wire(&line, &self.payload_policy)
+            .and_then(|line| writeln!(self.file, "{}", line).map_err(|e| e.to_string()));
+
+        if let Err(e) = written {
+            error!("Could not record the event to {}: {}", self.path, e);
+        }
+    }
+}
+
+/// Reads a trace recorded by `EventTraceRecorder`
+///
+/// # Returns
+/// - `Result<Vec<TracedEvent>, String>`: The events in the order they were received, or
+///   an error naming the first line that cannot be read.
+pub fn read_event_trace(path: &str) -> Result<Vec<TracedEvent>, String> {
+    let file = File::open(path).map_err(|e| format!("Could not open event trace {}: {}", path, e))?;
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            line.map_err(|e| e.to_string())
+                .and_then(|line| serde_json::from_str(&line).map_err(|e| e.to_string()))
+                .map_err(|e| format!("Invalid event trace {}, line {}: {}", path, index + 1, e))
+        })
+        .collect()
+}
+
+/// Sends the events of a trace to `sender`, with the pacing they were recorded with
+///
+/// Stands in for `spawn_core`, so the frontend handles the events as if the core sent them.
+/// The replay stops early if the receiver is dropped.
+///
+/// # Arguments
+/// - `trace`: The events read by `read_event_trace`.
+/// - `sender`: The sender the events are replayed to.
+pub async fn replay_event_trace(trace: Vec<TracedEvent>, sender: CoreEventSender) {
+    let started = tokio::time::Instant::now();
+    let total = trace.len();
+
+    for traced in trace {
+        tokio::time::sleep_until(started + Duration::from_millis(traced.offset_ms)).await;
+
+        if sender.send(traced.event).is_err() {
+            error!("The core event receiver was dropped, the replay stopped");
+            return;
+        }
+    }
+
+    info!("Replayed the {} events of the trace", total);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::domain::events::NeuralAnalyticsEvents;
+    use crate::domain::models::bulb_state::BulbState;
+    use crate::BulbActuation;
+
+    #[test]
+    fn test_recorded_trace_reads_back_the_same_events() {
+        let path = std::env::temp_dir().join(format!("neural_analytics_event_trace_{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+        let events = vec![
+            CoreEvent::HeadsetConnected {
+                ui_hint: NeuralAnalyticsEvents::HeadsetConnectedEvent.ui_hint(),
+            },
+            CoreEvent::PredictionActed {
+                window_id: Some(3),
+                headset_data: HashMap::from([("T3".to_string(), vec![0.1, -2.5])]),
+                color_thinking: Some("red".to_string()),
+                actuation: Some(BulbActuation {
+                    bulb_state: BulbState::BulbOff,
+                    is_confirmed: false,
+                    is_simulated: true,
+                    latency_ms: None,
+                    causality: None,
+                }),
+                band_powers: None,
+                window_prediction: None,
+            },
+        ];
+
+        let mut recorder = EventTraceRecorder::create(path).unwrap();
+        events.iter().for_each(|event| recorder.record(event));
+        drop(recorder);
+
+        let trace = read_event_trace(path).unwrap();
+        assert_eq!(trace.into_iter().map(|traced| traced.event).collect::<Vec<_>>(), events);
+
+        // A line without its event is reported, instead of replaying part of the trace
+        std::fs::write(path, std::fs::read_to_string(path).unwrap() + "{\"offset_ms\": 1}\n").unwrap();
+        let error = read_event_trace(path).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+
+        assert!(error.contains("line 3"), "{}", error);
+    }
+}
@@ -0,0 +1,134 @@
+//! Typed bridge between the Neural Analytics core and its frontends.
+//!
+//! The core reports everything through an event name and a loosely filled
+//! `EventData`; this crate turns that pair into a `CoreEvent` and forwards it
+//! through a tokio channel, so every frontend (Slint, egui, tauri...) consumes
+//! the same typed stream instead of matching event names on its own.
+//!
+//! Code running next to a frontend, such as a plugin, subscribes to the same typed
+//! events on the `CoreHandle` with `SubscribeCoreEvents::subscribe`.
+
+use neural_analytics_core::domain::models::{core_config::CoreConfig, event_data::EventData};
+use neural_analytics_core::initialize_core_with_config;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+pub mod core_event;
+#[cfg(feature = "event-trace")]
+pub mod event_trace;
+#[cfg(feature = "remote-api")]
+pub mod remote_api;
+
+pub use core_event::{BulbActuation, CoreEvent};
+pub use neural_analytics_core::config_file;
+pub use neural_analytics_core::{
+    domain, send_headset_command, set_headset_frame_dump, set_simulated_impedance, simulated_impedance, CoreClient,
+    CoreHandle, EventSubscription,
+};
+
+// Drive the last core initialized, kept for the frontends written before `CoreClient`
+#[allow(deprecated)]
+pub use neural_analytics_core::{
+    apply_calibration_thresholds, cancel_operation, core_health, core_metrics, create_diagnostic_bundle, end_session,
+    get_session_stats, record_operator_action, request_context_snapshot, request_recalibration, request_test_signal,
+    resume_headset_search, set_log_level, set_user_profile, start_recording, start_session, stop_recording,
+};
+
+/// Sending half of the channel the core events are forwarded to
+pub type CoreEventSender = UnboundedSender<CoreEvent>;
+
+/// Receiving half of the channel the core events are forwarded to
+pub type CoreEventReceiver = UnboundedReceiver<CoreEvent>;
+
+/// Creates the channel used to receive the core events
+///
+/// # Returns
+/// - `(CoreEventSender, CoreEventReceiver)`: The sender is given to `spawn_core`, the receiver to the frontend.
+pub fn core_event_channel() -> (CoreEventSender, CoreEventReceiver) {
+    mpsc::unbounded_channel()
+}
+
+/// Initializes the core in the background and forwards its events to `sender`
+///
+/// Events unknown to the bridge are dropped, so an older frontend keeps working
+/// against a newer core.
+///
+/// # Arguments
+/// - `config`: The `CoreConfig` used by the state machine and its services.
+/// - `sender`: The sender the typed events are forwarded to.
+///
+/// # Returns
+/// - `JoinHandle<Result<CoreHandle, String>>`: Resolves with the result of the core initialization.
+///   The `CoreHandle` must be kept while the core runs, dropping it shuts the core down.
+pub fn spawn_core(
+    config: CoreConfig,
+    sender: CoreEventSender,
+) -> JoinHandle<Result<CoreHandle, String>> {
+    tokio::spawn(async move {
+        let event_handler = typed_event_handler(move |core_event| {
+            sender
+                .send(core_event)
+                .map_err(|_| "The core event receiver was dropped".to_string())
+        });
+
+        initialize_core_with_config(config, event_handler).await
+    })
+}
+
+/// Typed subscription to the events of a running core
+pub trait SubscribeCoreEvents {
+    /// Calls `on_event` with every event of the core known to the bridge, after the
+    /// handler the core was started with, until the subscription is dropped
+    ///
+    /// `on_event` is called one event at a time and must not block, the core waits for it.
+    fn subscribe<F>(&self, on_event: F) -> EventSubscription
+    where
+        F: Fn(CoreEvent) + 'static + Send;
+}
+
+impl SubscribeCoreEvents for CoreHandle {
+    fn subscribe<F>(&self, on_event: F) -> EventSubscription
+    where
+        F: Fn(CoreEvent) + 'static + Send,
+    {
+        self.add_event_handler(typed_event_handler(move |core_event| {
+            on_event(core_event);
+            Ok(())
+        }))
+    }
+}
+
+/// Event handler of the core passing the events known to the bridge on to `on_event`, typed
+fn typed_event_handler<F>(on_event: F) -> impl Fn(&String, &EventData) -> Result<(), String> + 'static + Send
+where
+    F: Fn(CoreEvent) -> Result<(), String> + 'static + Send,
+{
+    move |event, data| match CoreEvent::from_event_data(event, data) {
+        Some(core_event) => on_event(core_event),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neural_analytics_core::domain::events::NeuralAnalyticsEvents;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_typed_event_handler_passes_on_the_known_events() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let event_handler = typed_event_handler(move |core_event| {
+            sink.lock().unwrap().push(core_event);
+            Ok(())
+        });
+
+        let mut data = EventData::default();
+        data.clock_drift_ms = Some(120.0);
+        event_handler(&NeuralAnalyticsEvents::ClockDriftWarningEvent.to_string(), &data).unwrap();
+        event_handler(&"unknown_event".to_string(), &data).unwrap();
+
+        assert_eq!(*received.lock().unwrap(), vec![CoreEvent::ClockDriftWarning { drift_ms: 120.0 }]);
+    }
+}
@@ -0,0 +1,337 @@
+//! Token-protected REST API to operate the core remotely.
+//!
+//! Lets a lab operator check and drive a Raspberry Pi installation from another
+//! machine, without access to its screen. Every route requires the
+//! `Authorization: Bearer <token>` header:
+//!
+//! - `GET /status`: The `CoreHealth` of the running core.
+//! - `GET /metrics`: The metrics of the core in the Prometheus text format, such as the
+//!   transitions between its states, the failed commands and the connection attempts.
+//! - `POST /recording/start`: Starts recording the EEG windows to `{"path": "..."}`.
+//! - `POST /recording/stop`: Stops the recording in progress.
+//! - `POST /session/start`: Opens a session spanning the reconnects of the headset,
+//!   recorded to the optional `{"recording_path": "..."}`.
+//!
+//!   The JSON responses are serialized with the payload policy of the `remote_api`
+//!   destination, like the events of the other bridges.
+//!
+//!   The recording paths are file names, resolved inside the recording directory of
+//!   the API, so a client cannot write anywhere else on the machine.
+//! - `POST /session/end`: Closes the session and its recording.
+//! - `GET /sessions/{id}/stats`: The `SessionStats` of a session, such as its windows, the
+//!   share of each class in its predictions and the duration of its calibrations.
+//! - `POST /recalibration`: Goes back to the calibration of the headset.
+//! - `POST /log-level`: Changes the level of the logs to `{"level": "debug"}`, until it
+//!   is changed again, to debug a session without restarting it.
+//! - `POST /shutdown`: Asks the frontend to shut the core down and exit.
+
+use std::net::SocketAddr;
+use std::path::{Component, PathBuf};
+use std::sync::Arc;
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+use crate::domain::models::{operator_action::OperatorAction, payload_policy::PayloadPolicy};
+use crate::domain::services::payload_policy_service::to_wire;
+use crate::{CoreClient, CoreEvent, CoreEventSender};
+
+/// Environment variable holding the token required by the API, which is disabled without it
+pub const API_TOKEN_VARIABLE: &str = "NEURAL_ANALYTICS_API_TOKEN";
+
+/// Environment variable holding the address the API listens on
+pub const API_ADDRESS_VARIABLE: &str = "NEURAL_ANALYTICS_API_ADDRESS";
+
+/// Address the API listens on by default, only reachable from the same machine
+pub const DEFAULT_API_ADDRESS: &str = "127.0.0.1:7878";
+
+/// Environment variable holding the directory the recordings requested through the API are written to
+pub const API_RECORDINGS_VARIABLE: &str = "NEURAL_ANALYTICS_API_RECORDINGS";
+
+/// Directory the recordings requested through the API are written to by default
+pub const DEFAULT_API_RECORDINGS: &str = "recordings";
+
+/// Configuration of the remote API
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteApiConfig {
+    pub bind_address: SocketAddr,
+    pub token: String,
+    /// Directory the recordings are written to, the clients only choose the file names
+    pub recording_directory: PathBuf,
+    /// Shape of the JSON responses, the full ones by default
+    pub payload_policy: PayloadPolicy,
+}
+
+impl RemoteApiConfig {
+    /// Reads the configuration from the environment
+    ///
+    /// # Returns
+    /// - `Option<RemoteApiConfig>`: `None` when no token is set or the address is invalid,
+    ///   so the API is never exposed unprotected.
+    pub fn from_env() -> Option<Self> {
+        let token = std::env::var(API_TOKEN_VARIABLE).ok().filter(|token| !token.is_empty())?;
+        let address = std::env::var(API_ADDRESS_VARIABLE).unwrap_or_else(|_| DEFAULT_API_ADDRESS.to_string());
+        let recording_directory =
+            PathBuf::from(std::env::var(API_RECORDINGS_VARIABLE).unwrap_or_else(|_| DEFAULT_API_RECORDINGS.to_string()));
+
+        match address.parse() {
+            Ok(bind_address) => Some(Self {
+                bind_address,
+                token,
+                recording_directory,
+                payload_policy: PayloadPolicy::default(),
+            }),
+            Err(e) => {
+                error!("Invalid remote API address {}: {}", address, e);
+                None
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ApiState {
+    token: Arc<String>,
+    recording_directory: Arc<PathBuf>,
+    payload_policy: PayloadPolicy,
+    core: CoreClient,
+    sender: CoreEventSender,
+}
+
+#[derive(serde::Deserialize)]
+struct StartRecordingRequest {
+    path: String,
+}
+
+#[derive(serde::Deserialize)]
+struct StartSessionRequest {
+    #[serde(default)]
+    recording_path: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct LogLevelRequest {
+    level: String,
+}
+
+/// Serves the remote API in the background
+///
+/// # Arguments
+/// - `config`: The address to listen on and the token to require.
+/// - `core`: The core the API operates, taken from its `CoreHandle`.
+/// - `sender`: The sender of the frontend, used to forward the shutdown requests.
+///
+/// # Returns
+/// - `JoinHandle<Result<(), String>>`: Resolves when the server stops, with an error if it could not start.
+pub fn spawn_remote_api(
+    config: RemoteApiConfig,
+    core: CoreClient,
+    sender: CoreEventSender,
+) -> JoinHandle<Result<(), String>> {
+    tokio::spawn(async move {
+        let state = ApiState {
+            token: Arc::new(config.token),
+            recording_directory: Arc::new(config.recording_directory),
+            payload_policy: config.payload_policy,
+            core,
+            sender,
+        };
+
+        let router = Router::new()
+            .route("/status", get(status))
+            .route("/metrics", get(metrics))
+            .route("/recording/start", post(recording_start))
+            .route("/recording/stop", post(recording_stop))
+            .route("/session/start", post(session_start))
+            .route("/session/end", post(session_end))
+            .route("/sessions/:id/stats", get(session_stats))
+            .route("/recalibration", post(recalibration))
+            .route("/log-level", post(log_level))
+            .route("/shutdown", post(shutdown))
+            .layer(middleware::from_fn_with_state(state.clone(), authorize))
+            .with_state(state);
+
+        let listener = tokio::net::TcpListener::bind(config.bind_address)
+            .await
+            .map_err(|e| format!("Failed to bind the remote API to {}: {}", config.bind_address, e))?;
+
+        info!("Remote API listening on {}", config.bind_address);
+
+        axum::serve(listener, router)
+            .await
+            .map_err(|e| format!("The remote API stopped: {}", e))
+    })
+}
+
+/// Checks the `Authorization` header against the expected token
+fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let provided = match authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+        Some(provided) => provided.as_bytes(),
+        None => return false,
+    };
+
+    // Compare every byte, so the response time does not leak the matching prefix
+    provided.len() == token.len()
+        && provided
+            .iter()
+            .zip(token.as_bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+async fn authorize(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    if !is_authorized(authorization, &state.token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Serializes a response in the shape of the payload policy of the remote API
+fn json_response<T: serde::Serialize>(body: &T, policy: &PayloadPolicy) -> Response {
+    match to_wire(body, policy) {
+        Ok(wire) => ([(header::CONTENT_TYPE, "application/json")], wire).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+async fn status(State(state): State<ApiState>) -> Response {
+    match state.core.health() {
+        Some(health) => json_response(&health, &state.payload_policy),
+        None => (StatusCode::SERVICE_UNAVAILABLE, "The core is not running").into_response(),
+    }
+}
+
+async fn metrics(State(state): State<ApiState>) -> Response {
+    match state.core.metrics() {
+        Some(metrics) => (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            metrics.to_prometheus(),
+        )
+            .into_response(),
+        None => (StatusCode::SERVICE_UNAVAILABLE, "The core is not running").into_response(),
+    }
+}
+
+/// Resolves the file name of a recording inside the recording directory
+///
+/// # Returns
+/// - `Result<String, String>`: The path to record to, or an error when the name is not a
+///   plain file name, such as an absolute path or one going up with `..`.
+fn resolve_recording_path(directory: &std::path::Path, name: &str) -> Result<String, String> {
+    let mut components = std::path::Path::new(name).components();
+
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(file_name)), None) => {
+            std::fs::create_dir_all(directory)
+                .map_err(|e| format!("Could not create the recording directory {}: {}", directory.display(), e))?;
+
+            Ok(directory.join(file_name).to_string_lossy().into_owned())
+        }
+        _ => Err(format!("'{}' is not a file name, recordings are written to the recording directory", name)),
+    }
+}
+
+async fn recording_start(State(state): State<ApiState>, Json(request): Json<StartRecordingRequest>) -> Response {
+    match resolve_recording_path(&state.recording_directory, &request.path) {
+        Ok(path) => {
+            state.core.start_recording(&path);
+            StatusCode::ACCEPTED.into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+async fn recording_stop(State(state): State<ApiState>) -> StatusCode {
+    state.core.stop_recording();
+    StatusCode::ACCEPTED
+}
+
+async fn session_start(State(state): State<ApiState>, Json(request): Json<StartSessionRequest>) -> Response {
+    let recording_path = match request.recording_path {
+        Some(name) => match resolve_recording_path(&state.recording_directory, &name) {
+            Ok(path) => Some(path),
+            Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+        },
+        None => None,
+    };
+
+    state.core.start_session(recording_path.as_deref());
+    StatusCode::ACCEPTED.into_response()
+}
+
+async fn session_end(State(state): State<ApiState>) -> StatusCode {
+    state.core.end_session();
+    StatusCode::ACCEPTED
+}
+
+async fn session_stats(State(state): State<ApiState>, Path(session_id): Path<u64>) -> Response {
+    match state.core.session_stats(session_id).await {
+        Ok(Some(stats)) => json_response(&stats, &state.payload_policy),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("Session {} is unknown", session_id)).into_response(),
+        Err(e) => (StatusCode::SERVICE_UNAVAILABLE, e).into_response(),
+    }
+}
+
+async fn recalibration(State(state): State<ApiState>) -> StatusCode {
+    // Journaled like the recalibrations asked from the GUI
+    state.core.record_operator_action(OperatorAction::Recalibrate);
+
+    // Queued right away, the outcome is reported through the calibration events
+    let _ = state.core.request_recalibration();
+    StatusCode::ACCEPTED
+}
+
+async fn log_level(State(state): State<ApiState>, Json(request): Json<LogLevelRequest>) -> Response {
+    match state.core.set_log_level(&request.level) {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+async fn shutdown(State(state): State<ApiState>) -> StatusCode {
+    match state.sender.send(CoreEvent::ShutdownRequested) {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_authorized_requires_bearer_token() {
+        assert!(is_authorized(Some("Bearer secret"), "secret"));
+        assert!(!is_authorized(Some("Bearer secreT"), "secret"));
+        assert!(!is_authorized(Some("Bearer secret2"), "secret"));
+        assert!(!is_authorized(Some("secret"), "secret"));
+        assert!(!is_authorized(None, "secret"));
+    }
+
+    #[test]
+    fn test_recording_paths_stay_in_the_recording_directory() {
+        let directory = std::env::temp_dir().join("neural_analytics_api_recordings");
+
+        assert_eq!(
+            resolve_recording_path(&directory, "session.csv"),
+            Ok(directory.join("session.csv").to_string_lossy().into_owned())
+        );
+        assert!(resolve_recording_path(&directory, "../session.csv").is_err());
+        assert!(resolve_recording_path(&directory, "..").is_err());
+        assert!(resolve_recording_path(&directory, "nested/session.csv").is_err());
+        assert!(resolve_recording_path(&directory, "/etc/session.csv").is_err());
+        assert!(resolve_recording_path(&directory, "").is_err());
+    }
+}
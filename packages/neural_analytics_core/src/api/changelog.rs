@@ -0,0 +1,195 @@
+//! Changelog of the stable API, generated from the annotations of its items.
+//!
+//! The `Added in` and `Changed in` lines of the documentation of an item, and its
+//! `#[deprecated]` attribute, are read from the source of each API module, so the
+//! changelog cannot drift from the code it describes.
+
+use std::collections::BTreeMap;
+
+// Source of each version of the API, read at build time
+const API_SOURCES: [(&str, &str); 1] = [("v1", include_str!("v1.rs"))];
+
+/// What happened to an item in a release, in the order the changelog lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ChangeKind {
+    Added,
+    Changed,
+    Deprecated,
+}
+
+/// Change of an item of the API, as annotated in its source
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ApiChange {
+    version: String,
+    kind: ChangeKind,
+    /// Path of the item within the API, such as `v1::CoreBuilder::start`.
+    item: String,
+    note: Option<String>,
+}
+
+/// Changelog of every version of the API, newest release first, in Markdown
+pub fn changelog() -> String {
+    let mut releases: BTreeMap<Vec<u32>, Vec<ApiChange>> = BTreeMap::new();
+
+    for (module, source) in API_SOURCES {
+        for change in annotated_changes(module, source) {
+            releases.entry(version_key(&change.version)).or_default().push(change);
+        }
+    }
+
+    let mut changelog = String::from(
+        "# API changelog\n\n\
+         Changes of the stable API of `neural_analytics_core`, the `api` modules, generated from\n\
+         the annotations of their items:\n\n\
+         ```sh\n\
+         cargo run -p neural_analytics_core --bin neural_analytics_changelog > packages/neural_analytics_core/API_CHANGELOG.md\n\
+         ```\n",
+    );
+
+    for changes in releases.values_mut().rev() {
+        changes.sort_by(|first, second| (first.kind, &first.item).cmp(&(second.kind, &second.item)));
+        changelog.push_str(&format!("\n## {}\n", changes[0].version));
+
+        let mut section = None;
+        for change in changes.iter() {
+            if section != Some(change.kind) {
+                section = Some(change.kind);
+                changelog.push_str(&format!("\n### {:?}\n\n", change.kind));
+            }
+
+            match &change.note {
+                Some(note) => changelog.push_str(&format!("- `{}`: {}\n", change.item, note)),
+                None => changelog.push_str(&format!("- `{}`\n", change.item)),
+            }
+        }
+    }
+
+    changelog
+}
+
+/// Newest release annotated in the API, which `API_VERSION` must match
+pub fn newest_annotated_version() -> Option<String> {
+    API_SOURCES
+        .iter()
+        .flat_map(|(module, source)| annotated_changes(module, source))
+        .max_by_key(|change| version_key(&change.version))
+        .map(|change| change.version)
+}
+
+/// Numbers of a `major.minor.patch` version, so `1.10.0` sorts after `1.9.0`
+fn version_key(version: &str) -> Vec<u32> {
+    version.split('.').map(|number| number.parse().unwrap_or_default()).collect()
+}
+
+/// Reads the changes annotated on the public items of the source of an API module
+///
+/// The annotations apply to the next `pub` item, and the methods are named after the
+/// type of their `impl` block.
+fn annotated_changes(module: &str, source: &str) -> Vec<ApiChange> {
+    let mut changes = Vec::new();
+    let mut pending: Vec<(ChangeKind, String, Option<String>)> = Vec::new();
+    let mut owner: Option<String> = None;
+
+    for raw_line in source.lines() {
+        let line = raw_line.trim();
+
+        if let Some(version) = line.strip_prefix("/// Added in ") {
+            pending.push((ChangeKind::Added, version.trim_end_matches('.').to_string(), None));
+        } else if let Some((version, note)) = line.strip_prefix("/// Changed in ").and_then(|rest| rest.split_once(": ")) {
+            pending.push((ChangeKind::Changed, version.to_string(), Some(note.to_string())));
+        } else if let Some(arguments) = line.strip_prefix("#[deprecated(") {
+            if let Some(version) = quoted_argument(arguments, "since") {
+                pending.push((ChangeKind::Deprecated, version, quoted_argument(arguments, "note")));
+            }
+        } else if let Some(declaration) = line.strip_prefix("impl") {
+            owner = declaration.trim_end_matches('{').split_whitespace().last().map(str::to_string);
+        } else if raw_line == "}" {
+            owner = None;
+        } else if line.starts_with("pub ") {
+            let Some(name) = item_name(line) else {
+                continue;
+            };
+
+            // Items of the module are not indented, the methods of a type are
+            let item = match &owner {
+                Some(owner) if raw_line.starts_with(' ') => format!("{}::{}::{}", module, owner, name),
+                _ => format!("{}::{}", module, name),
+            };
+
+            for (kind, version, note) in pending.drain(..) {
+                changes.push(ApiChange { version, kind, item: item.clone(), note });
+            }
+        }
+    }
+
+    changes
+}
+
+/// Value of a `name = "value"` argument of an attribute
+fn quoted_argument(arguments: &str, name: &str) -> Option<String> {
+    let start = arguments.find(&format!("{} = \"", name))? + name.len() + 4;
+    let length = arguments[start..].find('"')?;
+
+    Some(arguments[start..start + length].to_string())
+}
+
+/// Name of the item declared by a `pub` line, the alias of a re-export if it has one
+fn item_name(declaration: &str) -> Option<String> {
+    let declaration = declaration.strip_prefix("pub ")?.trim_end_matches(';');
+
+    let name = match declaration.strip_prefix("use ") {
+        Some(path) => path.rsplit([':', ' ']).next()?,
+        None => declaration.split_whitespace().find(|word| {
+            !matches!(*word, "async" | "const" | "unsafe" | "fn" | "struct" | "enum" | "trait" | "type" | "static" | "mod")
+        })?,
+    };
+
+    let length = name.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(name.len());
+    (length > 0).then(|| name[..length].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::API_VERSION;
+
+    #[test]
+    fn test_annotated_changes_of_items_and_methods() {
+        let source = "/// Added in 1.0.0.\n\
+                      pub use crate::Handle as CoreHandle;\n\n\
+                      impl Builder {\n    \
+                          /// Added in 1.1.0.\n    \
+                          /// Changed in 1.2.0: Waits for the headset.\n    \
+                          pub async fn start<F>(self) {}\n\
+                      }\n\n\
+                      /// Added in 1.0.0.\n\
+                      #[deprecated(since = \"1.1.0\", note = \"Use `Builder::start`\")]\n\
+                      pub fn start() {}\n";
+
+        let changes: Vec<(ChangeKind, String, String)> = annotated_changes("v1", source)
+            .into_iter()
+            .map(|change| (change.kind, change.version, change.item))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                (ChangeKind::Added, "1.0.0".to_string(), "v1::CoreHandle".to_string()),
+                (ChangeKind::Added, "1.1.0".to_string(), "v1::Builder::start".to_string()),
+                (ChangeKind::Changed, "1.2.0".to_string(), "v1::Builder::start".to_string()),
+                (ChangeKind::Added, "1.0.0".to_string(), "v1::start".to_string()),
+                (ChangeKind::Deprecated, "1.1.0".to_string(), "v1::start".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changelog_is_up_to_date() {
+        assert_eq!(newest_annotated_version().as_deref(), Some(API_VERSION));
+        assert_eq!(
+            changelog(),
+            include_str!("../../API_CHANGELOG.md"),
+            "API_CHANGELOG.md is outdated, regenerate it with `cargo run -p neural_analytics_core --bin neural_analytics_changelog`"
+        );
+    }
+}
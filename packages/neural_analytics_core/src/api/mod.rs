@@ -0,0 +1,30 @@
+//! Stable API of the core, for the applications embedding it.
+//!
+//! The rest of the crate, and the domain it re-exports, changes with the needs of the
+//! frontends of this repository. The `api` modules are the part kept compatible:
+//!
+//! * Each major version lives in a module of its own, `v1` for `1.x`, so an application
+//!   upgrades by changing its imports once, when it chooses to.
+//! * Within a major version, `API_VERSION` follows semantic versioning: a minor release
+//!   only adds items, a patch release only fixes their behaviour.
+//! * The structs with public fields are `#[non_exhaustive]`, built from their `Default` or
+//!   their constructor, so a minor release can add fields to them.
+//! * An item renamed or replaced is kept as a `#[deprecated]` shim calling its successor
+//!   for at least one minor release, and only removed with the next major version.
+//! * Every item carries the release it was added in, as a `/// Added in <version>.` line
+//!   of its documentation, and the release of each change, as a
+//!   `/// Changed in <version>: <what changed>.` line. The `API_CHANGELOG.md` of the
+//!   crate is generated from those lines and the `#[deprecated]` attributes:
+//!
+//! ```sh
+//! cargo run -p neural_analytics_core --bin neural_analytics_changelog > packages/neural_analytics_core/API_CHANGELOG.md
+//! ```
+//!
+//! The layout of the configuration file is versioned too, by the `version` key checked
+//! against `CONFIG_VERSION`.
+
+pub mod changelog;
+pub mod v1;
+
+/// Version of the newest API, `v1` while its major version is `1`
+pub const API_VERSION: &str = "1.16.0";
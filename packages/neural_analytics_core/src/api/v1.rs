@@ -0,0 +1,325 @@
+//! Version 1 of the stable API, see the `api` module for its guarantees.
+//!
+//! ```no_run
+//! use neural_analytics_core::api::v1::{CoreBuilder, EventKind};
+//!
+//! # async fn run() -> Result<(), String> {
+//! let core = CoreBuilder::new()
+//!     .start(|kind, data| {
+//!         if kind == EventKind::PredictionActed {
+//!             println!("Thinking of {:?}", data.color_thinking);
+//!         }
+//!         Ok(())
+//!     })
+//!     .await?;
+//!
+//! core.pause_capture().await?;
+//! core.shutdown().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use neural_analytics_domain::domain::events::NeuralAnalyticsEvents;
+
+use crate::{initialize_core_with_config, start_core_with_config};
+
+/// Configuration of the core, every field defaulting to the behaviour of the GUI.
+///
+/// Added in 1.0.0.
+/// Changed in 1.1.0: Adds the `calibration` section, with the thresholds of each electrode.
+/// Changed in 1.3.0: Adds the `band_powers` section.
+/// Changed in 1.4.0: Adds `bulb_auto_off_ms`, turning off a bulb the core stopped driving.
+/// Changed in 1.7.0: Adds the `payload_policies` section, shaping the windows of the events per destination.
+/// Changed in 1.9.0: Adds the `exhibition` section, holding each change of the prediction on the bulb and an overlay.
+/// Changed in 1.10.0: Adds the `inference` section, selecting the runtime the model is loaded in.
+/// Changed in 1.11.0: Adds the `synthetic_headset` section, shaping the EEG of the mock mode.
+/// Changed in 1.15.0: Marked `#[non_exhaustive]`, built from `CoreConfig::default()` so the sections of later releases do not break the callers.
+pub use neural_analytics_domain::domain::models::core_config::CoreConfig;
+
+/// Layout of `CoreConfig` this core reads, the `version` key of the configuration files.
+///
+/// Added in 1.0.0.
+pub use neural_analytics_domain::domain::models::core_config::CONFIG_VERSION;
+
+/// Payload of the events, each kind filling the fields it documents.
+///
+/// Added in 1.0.0.
+/// Changed in 1.3.0: Adds `band_powers`, sent with the predictions.
+/// Changed in 1.8.0: Adds `window_prediction`, with the confidence and class probabilities of the model.
+/// Changed in 1.9.0: Adds `exhibition_cue`, sent with the `ExhibitionCue` events.
+/// Changed in 1.15.0: Marked `#[non_exhaustive]`, built from `EventData::default()` so the fields of later releases do not break the callers.
+pub use neural_analytics_domain::domain::models::event_data::EventData;
+
+/// Prediction of a window with its confidence, class probabilities and latency.
+///
+/// Added in 1.8.0.
+pub use neural_analytics_domain::domain::models::window_prediction::WindowPrediction;
+
+/// Adapters of the devices, for the applications plugging hardware of their own.
+///
+/// Added in 1.0.0.
+/// Changed in 1.5.0: Adds `prediction_sink`, publishing the predictions beside the smart bulb.
+/// Changed in 1.12.0: Adds `prediction_webhook`, calling the URLs of the `Webhook` actions.
+/// Changed in 1.14.0: Adds `model_watcher` and `bundle_archive`, watching the model file and writing the diagnostic bundles.
+/// Changed in 1.15.0: Marked `#[non_exhaustive]`, built with `CoreAdapters::new` and its `with_*` methods so the adapters of later releases do not break the callers.
+/// Changed in 1.16.0: Adds `recorder`, `tensor_dump` and `thread_scheduler`, recording the extracted windows, dumping the tensors of the predictions and applying the `scheduling` hints.
+pub use neural_analytics_domain::CoreAdapters;
+
+/// Handle of the running core, driving it and shutting it down.
+///
+/// Added in 1.0.0.
+/// Changed in 1.2.0: Adds `add_event_handler`, see `subscribe` for the typed events.
+/// Changed in 1.10.1: Shutting the core down closes the session in progress and syncs its journal and recording.
+/// Changed in 1.13.0: Adds `client`, for the tasks driving the core without owning it.
+pub use neural_analytics_domain::CoreHandle;
+
+/// Client of a running core, driving it without keeping it running.
+///
+/// Added in 1.13.0.
+pub use neural_analytics_domain::CoreClient;
+
+/// Handler added to a running core, removed when dropped.
+///
+/// Added in 1.2.0.
+pub use neural_analytics_domain::EventSubscription;
+
+/// Statistics of a session, served by `get_session_stats`.
+///
+/// Added in 1.6.0.
+pub use neural_analytics_domain::domain::models::session_stats::SessionStats;
+
+/// Statistics of a session of the running core, `None` when the session is unknown or
+/// was forgotten, the last 64 finished sessions being kept.
+///
+/// Added in 1.6.0.
+#[deprecated(since = "1.13.0", note = "Use `CoreClient::session_stats`, which reads the core it was taken from")]
+#[allow(deprecated)]
+pub fn get_session_stats(
+    session_id: u64,
+) -> impl std::future::Future<Output = Result<Option<SessionStats>, String>> + Send + 'static {
+    neural_analytics_domain::get_session_stats(session_id)
+}
+
+/// Parses a TOML configuration file.
+///
+/// Added in 1.0.0.
+pub use crate::config_file::load_config;
+
+/// Parses the content of a TOML configuration file.
+///
+/// Added in 1.0.0.
+pub use crate::config_file::parse_config;
+
+/// Kind of an event sent by the core
+///
+/// New kinds are added in minor releases, so matches on it need a wildcard arm.
+///
+/// Added in 1.0.0.
+/// Changed in 1.9.0: Adds `ExhibitionCue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EventKind {
+    InitializedCore,
+    HeadsetConnected,
+    HeadsetDisconnected,
+    HeadsetCalibrating,
+    HeadsetCalibrated,
+    CalibrationReused,
+    SuggestedThresholds,
+    PredictionActed,
+    PredictionExplanation,
+    InferencePaused,
+    StaleDataSkipped,
+    BulbStateChanged,
+    BulbConnectionState,
+    MemoryPressure,
+    Heartbeat,
+    ModelReloaded,
+    ContextSnapshot,
+    TestSignalVerified,
+    SessionRotated,
+    ClockDriftWarning,
+    IntegrityViolation,
+    OperationProgress,
+    CommandPanicked,
+    UntestedFirmware,
+    DiagnosticBundleCreated,
+    ExhibitionCue,
+}
+
+impl EventKind {
+    /// Kind of an event from the name the core sends it with
+    ///
+    /// Added in 1.0.0.
+    pub fn from_name(name: &str) -> Option<EventKind> {
+        let kind = match NeuralAnalyticsEvents::from_string(name)? {
+            NeuralAnalyticsEvents::InitializedCoreEvent => EventKind::InitializedCore,
+            NeuralAnalyticsEvents::HeadsetConnectedEvent => EventKind::HeadsetConnected,
+            NeuralAnalyticsEvents::HeadsetDisconnectedEvent => EventKind::HeadsetDisconnected,
+            NeuralAnalyticsEvents::HeadsetCalibratingEvent => EventKind::HeadsetCalibrating,
+            NeuralAnalyticsEvents::HeadsetCalibratedEvent => EventKind::HeadsetCalibrated,
+            NeuralAnalyticsEvents::CalibrationReusedEvent => EventKind::CalibrationReused,
+            NeuralAnalyticsEvents::SuggestedThresholdsEvent => EventKind::SuggestedThresholds,
+            NeuralAnalyticsEvents::PredictionActedEvent => EventKind::PredictionActed,
+            NeuralAnalyticsEvents::PredictionExplanationEvent => EventKind::PredictionExplanation,
+            NeuralAnalyticsEvents::InferencePausedEvent => EventKind::InferencePaused,
+            NeuralAnalyticsEvents::StaleDataSkippedEvent => EventKind::StaleDataSkipped,
+            NeuralAnalyticsEvents::BulbStateChangedEvent => EventKind::BulbStateChanged,
+            NeuralAnalyticsEvents::BulbConnectionStateEvent => EventKind::BulbConnectionState,
+            NeuralAnalyticsEvents::MemoryPressureEvent => EventKind::MemoryPressure,
+            NeuralAnalyticsEvents::HeartbeatEvent => EventKind::Heartbeat,
+            NeuralAnalyticsEvents::ModelReloadedEvent => EventKind::ModelReloaded,
+            NeuralAnalyticsEvents::ContextSnapshotEvent => EventKind::ContextSnapshot,
+            NeuralAnalyticsEvents::TestSignalVerifiedEvent => EventKind::TestSignalVerified,
+            NeuralAnalyticsEvents::SessionRotatedEvent => EventKind::SessionRotated,
+            NeuralAnalyticsEvents::ClockDriftWarningEvent => EventKind::ClockDriftWarning,
+            NeuralAnalyticsEvents::IntegrityViolationEvent => EventKind::IntegrityViolation,
+            NeuralAnalyticsEvents::OperationProgressEvent => EventKind::OperationProgress,
+            NeuralAnalyticsEvents::CommandPanickedEvent => EventKind::CommandPanicked,
+            NeuralAnalyticsEvents::UntestedFirmwareEvent => EventKind::UntestedFirmware,
+            NeuralAnalyticsEvents::DiagnosticBundleCreatedEvent => EventKind::DiagnosticBundleCreated,
+            NeuralAnalyticsEvents::ExhibitionCueEvent => EventKind::ExhibitionCue,
+        };
+
+        Some(kind)
+    }
+}
+
+/// Starts the core, on the hardware of the GUI unless other adapters are given
+///
+/// Added in 1.0.0.
+#[derive(Default)]
+pub struct CoreBuilder {
+    config: CoreConfig,
+    adapters: Option<CoreAdapters>,
+}
+
+impl CoreBuilder {
+    /// Builder with the default configuration and hardware
+    ///
+    /// Added in 1.0.0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configuration of the core, `CoreConfig::default()` when not set
+    ///
+    /// Added in 1.0.0.
+    pub fn config(mut self, config: CoreConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Devices of the core, in place of the BrainBit headset and the Tapo bulb
+    ///
+    /// Added in 1.0.0.
+    pub fn adapters(mut self, adapters: CoreAdapters) -> Self {
+        self.adapters = Some(adapters);
+        self
+    }
+
+    /// Starts the core on the tokio runtime of the caller, or on a runtime of its own
+    /// when the `runtime` section of the configuration asks for it
+    ///
+    /// # Arguments
+    /// - `on_event`: Called with every event of the core. Events of a kind this version
+    ///   does not know are not passed on.
+    ///
+    /// # Returns
+    /// - `Result<CoreHandle, String>`: The handle of the running core, or an error message if it fails.
+    ///
+    /// Added in 1.0.0.
+    pub async fn start<F>(self, on_event: F) -> Result<CoreHandle, String>
+    where
+        F: Fn(EventKind, &EventData) -> Result<(), String> + 'static + Send,
+    {
+        if self.config.runtime.dedicated {
+            return tokio::task::spawn_blocking(move || self.start_blocking(on_event))
+                .await
+                .map_err(|e| format!("The core initialization panicked: {}", e))?;
+        }
+
+        let event_handler = typed_event_handler(on_event);
+
+        match self.adapters {
+            Some(adapters) => {
+                neural_analytics_domain::initialize_core_with_adapters(self.config, adapters, event_handler).await
+            }
+            None => initialize_core_with_config(self.config, event_handler).await,
+        }
+    }
+
+    /// Starts the core on a runtime of its own, for the applications without tokio
+    ///
+    /// Blocks until the core is initialized, see `start` for the events.
+    ///
+    /// Added in 1.0.0.
+    pub fn start_blocking<F>(self, on_event: F) -> Result<CoreHandle, String>
+    where
+        F: Fn(EventKind, &EventData) -> Result<(), String> + 'static + Send,
+    {
+        let event_handler = typed_event_handler(on_event);
+
+        match self.adapters {
+            Some(adapters) => neural_analytics_domain::start_core_with_adapters(self.config, || adapters, event_handler),
+            None => start_core_with_config(self.config, event_handler),
+        }
+    }
+}
+
+/// Event handler of the core passing the events of a known kind on to `on_event`
+fn typed_event_handler<F>(on_event: F) -> impl Fn(&String, &EventData) -> Result<(), String> + 'static + Send
+where
+    F: Fn(EventKind, &EventData) -> Result<(), String> + 'static + Send,
+{
+    move |name, data| match EventKind::from_name(name) {
+        Some(kind) => on_event(kind, data),
+        None => Ok(()),
+    }
+}
+
+/// Calls `on_event` with every event of a known kind of a running core, after the handler
+/// it was started with, until the subscription is dropped
+///
+/// `on_event` is called one event at a time and must not block, the core waits for it.
+///
+/// Added in 1.2.0.
+pub fn subscribe<F>(core: &CoreHandle, on_event: F) -> EventSubscription
+where
+    F: Fn(EventKind, &EventData) -> Result<(), String> + 'static + Send,
+{
+    core.add_event_handler(typed_event_handler(on_event))
+}
+
+/// Request a new calibration of the headset, without waiting for its outcome
+///
+/// Added in 1.0.0.
+#[deprecated(since = "1.0.0", note = "Use `CoreHandle::request_recalibration`, which reports whether the headset was recalibrated")]
+#[allow(deprecated)]
+pub fn request_recalibration() {
+    neural_analytics_domain::request_recalibration()
+}
+
+/// Request to search the headset again after its search was cancelled
+///
+/// Added in 1.0.0.
+#[deprecated(since = "1.0.0", note = "Use `CoreHandle::reconnect_headset`, which also reconnects a connected headset")]
+#[allow(deprecated)]
+pub fn resume_headset_search() {
+    neural_analytics_domain::resume_headset_search()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_kind_from_name() {
+        let headset_connected = NeuralAnalyticsEvents::HeadsetConnectedEvent.to_string();
+        let prediction_acted = NeuralAnalyticsEvents::PredictionActedEvent.to_string();
+
+        assert_eq!(EventKind::from_name(&headset_connected), Some(EventKind::HeadsetConnected));
+        assert_eq!(EventKind::from_name(&prediction_acted), Some(EventKind::PredictionActed));
+        assert_eq!(EventKind::from_name("not_an_event"), None);
+    }
+}
@@ -0,0 +1,71 @@
+//! Measures the throughput of the pipeline on the current machine.
+//!
+//! Runs the extraction and prediction of `--windows` windows (500 by default) with
+//! the synthetic headset, and with the BrainBit headset too when `--real` is given,
+//! printing the reports as JSON. Lets users judge whether their hardware, such as
+//! a Raspberry Pi Zero, keeps up with the headset before buying one:
+//!
+//! ```sh
+//! cargo run --release -p neural_analytics_core --bin neural_analytics_benchmark -- --windows 1000
+//! ```
+
+use neural_analytics_adapters_brainflow::brainbit_headset::BrainFlowAdapter;
+use neural_analytics_adapters_fake::synthetic_headset::SyntheticHeadsetAdapter;
+use neural_analytics_domain::domain::{
+    models::core_config::CoreConfig, services::benchmark_service::run_pipeline_benchmark,
+};
+
+// Windows processed when `--windows` is not given
+const DEFAULT_WINDOWS: usize = 500;
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let windows = match args.iter().position(|arg| arg == "--windows") {
+        Some(index) => match args.get(index + 1).and_then(|value| value.parse().ok()) {
+            Some(windows) => windows,
+            None => {
+                eprintln!("--windows expects a number of windows");
+                std::process::exit(2);
+            }
+        },
+        None => DEFAULT_WINDOWS,
+    };
+
+    let mut reports = Vec::new();
+
+    match run_pipeline_benchmark(
+        "synthetic",
+        Box::new(SyntheticHeadsetAdapter::default()),
+        windows,
+        CoreConfig::default(),
+    )
+    .await
+    {
+        Ok(report) => reports.push(report),
+        Err(e) => {
+            eprintln!("The benchmark failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    if args.iter().any(|arg| arg == "--real") {
+        match run_pipeline_benchmark(
+            "brainbit",
+            Box::new(BrainFlowAdapter::default()),
+            windows,
+            CoreConfig::default(),
+        )
+        .await
+        {
+            Ok(report) => reports.push(report),
+            Err(e) => eprintln!("The benchmark of the BrainBit headset failed: {}", e),
+        }
+    }
+
+    match serde_json::to_string_pretty(&reports) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize the reports: {}", e),
+    }
+}
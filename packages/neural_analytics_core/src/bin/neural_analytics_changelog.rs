@@ -0,0 +1,9 @@
+//! Prints the changelog of the stable API, generated from the annotations of its items.
+//!
+//! ```sh
+//! cargo run -p neural_analytics_core --bin neural_analytics_changelog > packages/neural_analytics_core/API_CHANGELOG.md
+//! ```
+
+fn main() {
+    print!("{}", neural_analytics_core::api::changelog::changelog());
+}
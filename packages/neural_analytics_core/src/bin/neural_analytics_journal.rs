@@ -0,0 +1,131 @@
+//! Steps through a session journal, rebuilding the context of the core at each entry.
+//!
+//! Answers the "why did it think green here?" questions of a past session: jump to the
+//! prediction of a window and see the predictions, explanation, actuation and state the
+//! core had when it made it. Reads commands from the standard input:
+//!
+//! * `n [count]`, or an empty line: steps forward.
+//! * `p [count]`: steps backward.
+//! * `g <index>`: goes to an entry.
+//! * `t <epoch ms>`: goes to the last entry recorded up to a time.
+//! * `f <kind>`: goes to the next entry of a kind, such as `actuation`.
+//! * `w <window id>`: goes to the prediction of a window.
+//! * `q`: quits.
+//!
+//! ```sh
+//! cargo run -p neural_analytics_core --bin neural_analytics_journal -- session.jsonl
+//! ```
+
+use std::io::{BufRead, Write};
+
+use neural_analytics_domain::domain::services::journal_replay_service::{read_journal, JournalReplay};
+
+/// Prints the current entry and the context rebuilt up to it
+fn print_position(replay: &JournalReplay) {
+    match (replay.position(), replay.entry()) {
+        (Some(position), Some(entry)) => {
+            println!("[{}/{}] {} at {}", position + 1, replay.len(), entry.kind, entry.timestamp);
+            println!("{}", serde_json::to_string_pretty(&entry.payload).unwrap_or_default());
+        }
+        _ => println!("[0/{}] before the first entry", replay.len()),
+    }
+
+    match serde_json::to_string_pretty(replay.context()) {
+        Ok(context) => println!("context: {}", context),
+        Err(e) => eprintln!("Could not print the context: {}", e),
+    }
+}
+
+/// Runs a command against the replay
+///
+/// # Returns
+/// * `Result<(), String>`: An error telling why the command could not run.
+fn run_command(replay: &mut JournalReplay, command: &str, argument: Option<&str>) -> Result<(), String> {
+    let count = || argument.map_or(Ok(1), |count| count.parse::<usize>().map_err(|_| "Expected a number of entries".to_string()));
+
+    match command {
+        "" | "n" => {
+            for _ in 0..count()? {
+                if !replay.step_forward() {
+                    return Err("Reached the end of the journal".to_string());
+                }
+            }
+        }
+        "p" => {
+            for _ in 0..count()? {
+                if !replay.step_backward() {
+                    return Err("Reached the start of the journal".to_string());
+                }
+            }
+        }
+        "g" => {
+            let index = argument
+                .and_then(|index| index.parse::<usize>().ok())
+                .ok_or("Expected the index of an entry")?;
+            replay.seek(index.saturating_add(1));
+        }
+        "t" => {
+            let timestamp = argument
+                .and_then(|timestamp| timestamp.parse::<i64>().ok())
+                .ok_or("Expected a Unix epoch time in milliseconds")?;
+            replay.seek_time(timestamp);
+        }
+        "f" => {
+            let kind = argument.ok_or("Expected the kind of an entry")?;
+            if !replay.find_next(kind) {
+                return Err(format!("No {} entry after this one", kind));
+            }
+        }
+        "w" => {
+            let window_id = argument
+                .and_then(|window_id| window_id.parse::<u64>().ok())
+                .ok_or("Expected the id of a window")?;
+            if !replay.find_window(window_id) {
+                return Err(format!("No prediction of window {} in the journal", window_id));
+            }
+        }
+        _ => return Err(format!("Unknown command {}", command)),
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: neural_analytics_journal <session journal>");
+        std::process::exit(2);
+    };
+
+    let mut replay = match read_journal(&path) {
+        Ok(entries) => JournalReplay::new(entries),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    print_position(&replay);
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("> ");
+        let _ = std::io::stdout().flush();
+
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        if command == "q" {
+            break;
+        }
+
+        match run_command(&mut replay, command, words.next()) {
+            Ok(()) => print_position(&replay),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Runs the whole core against simulated devices and checks it reaches the capture.
+//!
+//! The core is initialized with the synthetic headset, a simulated bulb and a model
+//! always predicting the same class, and must go through `initialized-core`,
+//! `headset-connected`, `headset-calibrated` and `--windows` predicted windows (10 by
+//! default), each within `--timeout-secs` seconds (30 by default). Exits with a non
+//! zero status otherwise, so CI catches the breakages of the async plumbing that the
+//! unit tests of the use cases miss, without a display nor hardware:
+//!
+//! ```sh
+//! cargo run -p neural_analytics_core --bin neural_analytics_smoke -- --windows 20
+//! ```
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use neural_analytics_adapters_fake::{null_bulb::NullBulbAdapter, synthetic_headset::SyntheticHeadsetAdapter};
+use neural_analytics_domain::{
+    domain::{
+        events::NeuralAnalyticsEvents,
+        models::core_config::CoreConfig,
+        services::model_inference_service::ModelInferenceInterface,
+    },
+    initialize_core_with_adapters, CoreAdapters,
+};
+use tokio::sync::mpsc;
+
+// Predicted windows awaited when `--windows` is not given
+const DEFAULT_WINDOWS: usize = 10;
+
+// Longest wait for each step when `--timeout-secs` is not given
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+// Class predicted for every window
+const PREDICTED_CLASS: &str = "green";
+
+/// Model predicting the same class for every window, so no ONNX model is needed
+struct ConstantModel;
+
+impl ModelInferenceInterface for ConstantModel {
+    fn predict_color(&self, _eeg_data: &HashMap<String, Vec<f32>>) -> Result<String, String> {
+        Ok(PREDICTED_CLASS.to_string())
+    }
+
+    fn is_model_loaded(&self) -> bool {
+        true
+    }
+}
+
+/// Parses the numeric value of an argument, exiting when it is not a number
+fn numeric_argument<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> T {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => match args.get(index + 1).and_then(|value| value.parse().ok()) {
+            Some(value) => value,
+            None => {
+                eprintln!("{} expects a number", name);
+                std::process::exit(2);
+            }
+        },
+        None => default,
+    }
+}
+
+/// Waits for the next event of a step, ignoring the events in between
+///
+/// # Returns
+/// * `Result<(), String>`: An error naming the step when it times out, the core stops
+///   sending events or a command panics.
+async fn expect_event(events: &mut mpsc::UnboundedReceiver<String>, expected: &str, timeout: Duration) -> Result<(), String> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        match tokio::time::timeout_at(deadline, events.recv()).await {
+            Ok(Some(name)) if name == expected => return Ok(()),
+            Ok(Some(name)) if name == NeuralAnalyticsEvents::CommandPanickedEvent.to_string() => {
+                return Err(format!("A command panicked while waiting for {}", expected))
+            }
+            Ok(Some(_)) => continue,
+            Ok(None) => return Err(format!("The core stopped sending events before {}", expected)),
+            Err(_) => return Err(format!("No {} within {} s", expected, timeout.as_secs())),
+        }
+    }
+}
+
+/// Walks the core through the steps of a session, printing each one as it is reached
+async fn run_steps(events: &mut mpsc::UnboundedReceiver<String>, windows: usize, timeout: Duration) -> Result<(), String> {
+    let started_at = Instant::now();
+
+    let steps = [
+        NeuralAnalyticsEvents::InitializedCoreEvent,
+        NeuralAnalyticsEvents::HeadsetConnectedEvent,
+        NeuralAnalyticsEvents::HeadsetCalibratedEvent,
+    ]
+    .into_iter()
+    .chain(std::iter::repeat_with(|| NeuralAnalyticsEvents::PredictionActedEvent).take(windows))
+    .map(|event| event.to_string());
+
+    for (index, step) in steps.enumerate() {
+        expect_event(events, &step, timeout).await?;
+        println!("{:>4} {} after {:.1} s", index + 1, step, started_at.elapsed().as_secs_f32());
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let windows = numeric_argument(&args, "--windows", DEFAULT_WINDOWS);
+    let timeout = Duration::from_secs(numeric_argument(&args, "--timeout-secs", DEFAULT_TIMEOUT_SECS));
+
+    let (sender, mut events) = mpsc::unbounded_channel();
+
+    let adapters = CoreAdapters::new(Box::new(SyntheticHeadsetAdapter::real_time()), Box::new(NullBulbAdapter::default()))
+        .with_model(Box::new(ConstantModel));
+
+    let core = initialize_core_with_adapters(CoreConfig::default(), adapters, move |name, _data| {
+        sender.send(name.clone()).map_err(|e| e.to_string())
+    })
+    .await;
+
+    let core = match core {
+        Ok(core) => core,
+        Err(e) => {
+            eprintln!("The core failed to initialize: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = run_steps(&mut events, windows, timeout).await;
+    core.shutdown().await;
+
+    match result {
+        Ok(()) => println!("The core captured and predicted {} windows", windows),
+        Err(e) => {
+            eprintln!("The smoke test failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
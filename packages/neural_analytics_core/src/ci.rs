@@ -0,0 +1,165 @@
+//! Profile running the whole core from a recorded dataset, without hardware nor ONNX model.
+//!
+//! The core is wired to a `ReplayHeadsetAdapter` streaming a recording, a `NullBulbAdapter`
+//! and `TinyModel`, a model embedded in the code whose predictions follow from the
+//! recording alone. The configuration leaves nothing to the pace of the machine, so the
+//! same recording always yields the same predictions:
+//!
+//! ```sh
+//! cargo test -p neural_analytics_core --features ci-pipeline
+//! ```
+
+use std::collections::HashMap;
+
+use neural_analytics_adapters_fake::null_bulb::NullBulbAdapter;
+use neural_analytics_adapters_fs::replay_headset::ReplayHeadsetAdapter;
+use neural_analytics_domain::{
+    domain::{
+        models::{core_config::CoreConfig, prediction_smoothing::PredictionSmoothing},
+        services::model_inference_service::ModelInferenceInterface,
+    },
+    CoreAdapters,
+};
+
+/// Recording bundled with the crate: 30 seconds of alpha wave at 250 Hz, stronger on the
+/// occipital electrodes for the first and last 10 seconds and on the temporal ones between.
+pub const CI_RECORDING: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/ci/session_30s.jsonl");
+
+// Predictions that must agree to act on them, whatever the pace of the replay
+const CI_SMOOTHED_PREDICTIONS: usize = 6;
+
+// Power ratio between the two groups of electrodes from which a class is predicted
+const DOMINANCE_RATIO: f32 = 2.0;
+
+/// Model comparing the power of the occipital electrodes (`O1`, `O2`) with the one of the
+/// temporal electrodes (`T3`, `T4`).
+///
+/// Predicts `green` when the occipital ones dominate, `red` when the temporal ones do,
+/// by `DOMINANCE_RATIO` or more, and `trash` otherwise.
+pub struct TinyModel;
+
+impl TinyModel {
+    // Mean power of a group of channels, after removing the offset of each
+    fn power(eeg_data: &HashMap<String, Vec<f32>>, channels: [&str; 2]) -> f32 {
+        channels
+            .iter()
+            .filter_map(|channel| eeg_data.get(*channel))
+            .map(|samples| neural_analytics_signal_math::std_dev(samples).powi(2))
+            .sum::<f32>()
+            / channels.len() as f32
+    }
+}
+
+impl ModelInferenceInterface for TinyModel {
+    fn predict_color(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<String, String> {
+        let occipital = Self::power(eeg_data, ["O1", "O2"]);
+        let temporal = Self::power(eeg_data, ["T3", "T4"]);
+
+        let color = if occipital >= DOMINANCE_RATIO * temporal {
+            "green"
+        } else if temporal >= DOMINANCE_RATIO * occipital {
+            "red"
+        } else {
+            "trash"
+        };
+
+        Ok(color.to_string())
+    }
+
+    fn is_model_loaded(&self) -> bool {
+        true
+    }
+}
+
+/// Configuration and adapters of the `ci` profile, replaying a recording.
+///
+/// # Arguments
+/// * `recording_path`: The JSON Lines recording to replay, such as `CI_RECORDING`.
+///
+/// # Returns
+/// * `Result<(CoreConfig, CoreAdapters), String>`: The profile to start the core with, or
+///   an error if the recording cannot be read.
+pub fn ci_profile(recording_path: &str) -> Result<(CoreConfig, CoreAdapters), String> {
+    let mut config = CoreConfig::default();
+    config.heartbeat_interval_ms = 0;
+    config.calibration_reuse_period_secs = 0;
+    // The replay runs faster than the headset, so the smoothing does not follow its pace
+    config.prediction_smoothing = PredictionSmoothing {
+        min_predictions: CI_SMOOTHED_PREDICTIONS,
+        max_predictions: CI_SMOOTHED_PREDICTIONS,
+        ..Default::default()
+    };
+
+    let adapters = CoreAdapters::new(
+        Box::new(ReplayHeadsetAdapter::open(recording_path)?),
+        Box::new(NullBulbAdapter::default()),
+    )
+    .with_model(Box::new(TinyModel));
+
+    Ok((config, adapters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::v1::EventKind;
+    use neural_analytics_domain::{domain::services::window_assembler_service::WINDOW_SAMPLES, initialize_core_with_adapters};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ci_profile_predicts_the_bundled_recording() {
+        let windows = ReplayHeadsetAdapter::open(CI_RECORDING).unwrap().len() / WINDOW_SAMPLES;
+        let (config, adapters) = ci_profile(CI_RECORDING).unwrap();
+
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let core = initialize_core_with_adapters(config, adapters, move |name, data| {
+            if EventKind::from_name(name) == Some(EventKind::PredictionActed) {
+                let _ = sender.send(data.color_thinking.clone());
+            }
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        let mut predictions = Vec::new();
+        while predictions.len() < windows {
+            match tokio::time::timeout(Duration::from_secs(60), events.recv()).await {
+                Ok(Some(Some(color))) => predictions.push(color),
+                Ok(Some(None)) => continue,
+                Ok(None) | Err(_) => break,
+            }
+        }
+        core.shutdown().await;
+
+        // Consecutive predictions of the same class, with the windows they lasted
+        let mut runs: Vec<(String, usize)> = Vec::new();
+        for color in predictions {
+            match runs.last_mut() {
+                Some((last, count)) if *last == color => *count += 1,
+                _ => runs.push((color, 1)),
+            }
+        }
+
+        let runs: Vec<(&str, usize)> = runs.iter().map(|(color, count)| (color.as_str(), *count)).collect();
+        // Each change of class is smoothed over six windows before it is acted on
+        assert_eq!(runs, vec![("green", 40), ("unknown", 6), ("red", 34), ("unknown", 6), ("green", 35)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ci_profile_runs_several_cores_in_one_process() {
+        let start_core = || async {
+            let (config, adapters) = ci_profile(CI_RECORDING).unwrap();
+            initialize_core_with_adapters(config, adapters, |_, _| Ok(())).await
+        };
+
+        // Side by side, each one with the adapters it was started with
+        let first = start_core().await.unwrap();
+        let second = start_core().await.unwrap();
+        first.shutdown().await;
+        second.shutdown().await;
+
+        // And after them, once their adapters were released
+        start_core().await.unwrap().shutdown().await;
+    }
+}
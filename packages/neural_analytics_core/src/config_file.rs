@@ -0,0 +1,137 @@
+//! TOML configuration file of the core.
+//!
+//! Every key of the file is optional and falls back to the `CoreConfig` default, so a
+//! file only lists what an installation changes. Unknown keys, values of the wrong type
+//! and incomplete sections are rejected with the key path and its position in the file.
+//!
+//! A file can pin the layout it was written for with a top-level `version` key. A file
+//! of another `CONFIG_VERSION` is rejected, rather than read with keys that changed meaning.
+
+use neural_analytics_domain::domain::models::core_config::{CoreConfig, CONFIG_VERSION};
+
+/// Environment variable holding the path of the configuration file
+pub const CONFIG_FILE_VARIABLE: &str = "NEURAL_ANALYTICS_CONFIG";
+
+/// Parses a configuration file
+///
+/// # Arguments
+/// - `path`: The TOML file to read.
+///
+/// # Returns
+/// - `Result<CoreConfig, String>`: The configuration, or a diagnostic such as
+///   `core.toml:3:16: scheduling.realtime_priority: invalid type: string "high", expected u8`.
+pub fn load_config(path: &str) -> Result<CoreConfig, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    parse_config(&source).map_err(|diagnostic| format!("{}:{}", path, diagnostic))
+}
+
+/// Parses the configuration file set in `NEURAL_ANALYTICS_CONFIG`, the defaults if it is not set
+pub fn load_config_from_env() -> Result<CoreConfig, String> {
+    match std::env::var(CONFIG_FILE_VARIABLE) {
+        Ok(path) => load_config(&path),
+        Err(_) => Ok(CoreConfig::default()),
+    }
+}
+
+/// Parses the content of a configuration file
+///
+/// # Returns
+/// - `Result<CoreConfig, String>`: The configuration, or a diagnostic starting with the
+///   line and column of the error, followed by the path of the key when it is known.
+pub fn parse_config(source: &str) -> Result<CoreConfig, String> {
+    let deserializer = toml::de::Deserializer::parse(source)
+        .map_err(|e| format!("{}: {}", position(source, e.span()), e.message()))?;
+
+    let config: CoreConfig = serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        let inner = e.inner();
+        let position = position(source, inner.span());
+
+        // The path of an error at the root of the file is a lone dot
+        if path == "." {
+            format!("{}: {}", position, inner.message())
+        } else {
+            format!("{}: {}: {}", position, path, inner.message())
+        }
+    })?;
+
+    if config.version != CONFIG_VERSION {
+        return Err(format!(
+            "{}: version: the file is written for the configuration version {}, this core reads the version {}",
+            position(source, version_span(source)),
+            config.version,
+            CONFIG_VERSION
+        ));
+    }
+
+    Ok(config)
+}
+
+/// Span of the top-level `version` key, the deserializer does not keep it once the file is read
+fn version_span(source: &str) -> Option<std::ops::Range<usize>> {
+    let mut offset = 0;
+
+    for line in source.split_inclusive('\n') {
+        let key = line.trim_start();
+
+        // The keys of the tables are not the version of the file
+        if key.starts_with('[') {
+            break;
+        }
+
+        if key.strip_prefix("version").is_some_and(|rest| rest.trim_start().starts_with('=')) {
+            let start = offset + line.len() - key.len();
+            return Some(start..start + "version".len());
+        }
+
+        offset += line.len();
+    }
+
+    None
+}
+
+/// One-based `line:column` of the start of a span, `1:1` when the error has no span
+fn position(source: &str, span: Option<std::ops::Range<usize>>) -> String {
+    let offset = span.map_or(0, |span| span.start.min(source.len()));
+    let before = &source[..offset];
+
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(offset, |newline| offset - newline - 1) + 1;
+
+    format!("{}:{}", line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_keeps_defaults_for_missing_keys() {
+        let config = parse_config("heartbeat_interval_ms = 1000\n\n[scheduling]\npinned_cores = [2, 3]\n").unwrap();
+
+        assert_eq!(config.heartbeat_interval_ms, 1000);
+        assert_eq!(config.scheduling.pinned_cores, vec![2, 3]);
+        assert_eq!(config.memory_budget_bytes, CoreConfig::default().memory_budget_bytes);
+    }
+
+    #[test]
+    fn test_parse_config_reports_the_key_and_its_position() {
+        let type_mismatch = parse_config("watch_model = true\n\n[scheduling]\nrealtime_priority = \"high\"\n").unwrap_err();
+        assert!(type_mismatch.starts_with("4:21: scheduling.realtime_priority: invalid type"), "{}", type_mismatch);
+
+        let unknown_key = parse_config("heartbeat_interval = 1000\n").unwrap_err();
+        assert!(unknown_key.contains("unknown field `heartbeat_interval`"), "{}", unknown_key);
+
+        let missing_field = parse_config("[calibration_thresholds]\nmax_impedance = 1000\n").unwrap_err();
+        assert!(missing_field.contains("calibration_thresholds: missing field"), "{}", missing_field);
+    }
+
+    #[test]
+    fn test_parse_config_rejects_another_version() {
+        assert_eq!(parse_config("version = 1\nwatch_model = true\n").unwrap().version, CONFIG_VERSION);
+
+        let newer = parse_config("watch_model = true\n  version = 2\n").unwrap_err();
+        assert!(newer.starts_with("2:3: version: the file is written for the configuration version 2"), "{}", newer);
+    }
+}
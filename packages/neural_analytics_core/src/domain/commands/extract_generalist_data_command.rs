@@ -1,6 +0,0 @@
-#[derive(Debug)]
-pub struct ExtractGeneralistDataCommand;
-
-impl presage::Command for ExtractGeneralistDataCommand {
-    const NAME: &'static str = "extract-generalist-data";
-}
\ No newline at end of file
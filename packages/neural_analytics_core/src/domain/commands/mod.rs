@@ -1,7 +0,0 @@
-pub mod disconnect_headband_command;
-pub mod extract_calibration_data_command;
-pub mod extract_generalist_data_command;
-pub mod predict_color_thinking_command;
-pub mod search_headband_command;
-pub mod update_light_status_command;
-
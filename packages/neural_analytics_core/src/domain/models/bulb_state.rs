@@ -1,5 +0,0 @@
-#[derive(Debug, PartialEq, Clone, Copy)] // Added Clone, Copy for convenience
-pub enum BulbState {
-    BulbOn,
-    BulbOff,
-}
\ No newline at end of file
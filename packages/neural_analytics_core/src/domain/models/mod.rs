@@ -1,4 +0,0 @@
-pub mod bulb_state;
-pub mod eeg_work_modes;
-pub mod event_data;
-pub mod event_internals;
@@ -1 +0,0 @@
-pub mod eeg_headset;
\ No newline at end of file
@@ -1 +0,0 @@
-pub mod smart_bulb;
\ No newline at end of file
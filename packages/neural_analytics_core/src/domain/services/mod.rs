@@ -1 +0,0 @@
-pub mod model_inference_service;
@@ -1,4 +0,0 @@
-pub(crate) enum NeuralAnalyticsCoreEvents {
-    InitializeCore,
-    BackgroundTick
-}
@@ -1,6 +0,0 @@
-pub mod disconnect_headband_use_case;
-pub mod extract_calibration_use_case;
-pub mod extract_extraction_use_case;
-pub mod predict_color_thinking_use_case;
-pub mod search_headband_use_case;
-pub mod update_light_status_use_case;
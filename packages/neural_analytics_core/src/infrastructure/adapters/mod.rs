@@ -1,2 +0,0 @@
-pub mod input;
-pub mod output;
\ No newline at end of file
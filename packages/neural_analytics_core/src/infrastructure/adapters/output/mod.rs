@@ -1 +0,0 @@
-pub mod tapo_smartbulb;
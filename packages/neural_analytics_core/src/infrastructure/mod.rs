@@ -1 +0,0 @@
-pub mod adapters;
\ No newline at end of file
@@ -1,22 +1,60 @@
+//! Composition crate wiring the Neural Analytics domain with the hardware adapters.
+//!
+//! The domain lives in `neural_analytics_domain` and knows nothing about BrainFlow,
+//! Tapo, the webhooks or the file system; this crate plugs the real adapters in, so frontends only depend on it.
 
-use domain::models::event_data::EventData;
-use domain::state_machine::{
-    neural_events::NeuralAnalyticsCoreEvents, state_machine::MainStateMachine,
+use neural_analytics_adapters_brainflow::brainbit_headset::BrainFlowAdapter;
+use neural_analytics_adapters_fs::{
+    file_recorder::FileRecorderAdapter, file_tensor_dump::FileTensorDumpAdapter, model_file_watcher::ModelFileWatcherAdapter,
+    zip_bundle_archive::ZipBundleArchiveAdapter,
+};
+use neural_analytics_adapters_tapo::tapo_smartbulb::TapoSmartBulbAdapter;
+use neural_analytics_adapters_mqtt::mqtt_prediction_sink::MqttPredictionSinkAdapter;
+use neural_analytics_adapters_webhook::{
+    lifecycle_webhook::LifecycleWebhookAdapter, prediction_webhook::PredictionWebhookAdapter,
+};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use thread_scheduler::OsThreadSchedulerAdapter;
+
+pub mod api;
+#[cfg(feature = "ci-pipeline")]
+pub mod ci;
+pub mod config_file;
+pub mod thread_scheduler;
+
+use neural_analytics_adapters_fake::synthetic_headset::{SimulatedImpedance, SyntheticHeadsetAdapter};
+use neural_analytics_adapters_fs::replay_headset::ReplayHeadsetAdapter;
+use neural_analytics_domain::{
+    domain::models::{
+        bulb_connection::BulbConnectionPolicy, core_config::CoreConfig, event_data::EventData,
+        payload_policy::{PayloadPolicy, MQTT_DESTINATION}, recording_config::RecordingConfig,
+        synthetic_signal::SyntheticSignalConfig,
+    },
+    domain::ports::input::eeg_headset::EegHeadsetPort,
+    initialize_core_with_adapters, start_core_with_adapters, CoreAdapters,
 };
 
-use statig::awaitable::{InitializedStateMachine, IntoStateMachineExt};
+pub use neural_analytics_domain::{
+    domain, send_headset_command, set_headset_frame_dump, utils, CoreClient, CoreHandle, EventSubscription,
+};
+
+// Drive the last core initialized, kept for the frontends written before `CoreClient`
+#[allow(deprecated)]
+pub use neural_analytics_domain::{
+    apply_calibration_thresholds, cancel_operation, core_health, core_metrics, create_diagnostic_bundle, end_session,
+    get_session_stats, record_operator_action, request_context_snapshot, request_recalibration, request_test_signal,
+    resume_headset_search, set_log_level, set_user_profile, start_recording, start_session, stop_recording,
+};
 
-pub mod domain;
-pub mod infrastructure;
-pub mod utils;
+/// Environment variable that replaces the BrainBit headset with a synthetic one when `true`
+pub const MOCK_HEADSET_VARIABLE: &str = "NEURAL_ANALYTICS_MOCK_HEADSET";
 
-// Internal State Machine
-pub(crate) static mut INTERNAL_STATE_MACHINE: Option<InitializedStateMachine<MainStateMachine>> = None;
+/// Environment variable with the path of a recording replayed, at its original pace, in place of the BrainBit headset
+pub const REPLAY_RECORDING_VARIABLE: &str = "NEURAL_ANALYTICS_REPLAY_RECORDING";
 
-// Setted by the initialize_core function
-pub(crate) static mut INTERNAL_EVENT_HANDLER: Option<
-    Box<dyn Fn(&String, &EventData) -> Result<(), String> + Send>,
-> = None;
+// Impedances of the synthetic headset, set when the core runs in mock mode
+static SIMULATED_IMPEDANCE: OnceLock<SimulatedImpedance> = OnceLock::new();
 
 /// Initialize the core of the application
 ///
@@ -27,39 +65,149 @@ pub(crate) static mut INTERNAL_EVENT_HANDLER: Option<
 /// - `event_handler`: A function that handles events. It takes a string and an `EventData` struct as arguments and returns a `Result<(), String>`.
 ///
 /// # Returns
-/// - `Result<(), String>`: Returns `Ok(())` if the initialization is successful, or an error message if it fails.
+/// - `Result<CoreHandle, String>`: The handle of the running core, or an error message if it fails. Dropping the handle shuts the core down.
+///
+pub async fn initialize_core<F>(event_handler: F) -> Result<CoreHandle, String>
+where
+    F: Fn(&String, &EventData) -> Result<(), String> + 'static + Send,
+{
+    initialize_core_with_config(CoreConfig::default(), event_handler).await
+}
+
+/// Initialize the core of the application with a custom configuration
+///
+/// Same as `initialize_core`, but lets the caller tune the core behaviour,
+/// e.g. the action taken for each prediction class. With a dedicated runtime set in
+/// the `runtime` section, the core is started with `start_core_with_config` without
+/// blocking the runtime of the caller.
+///
+/// # Arguments
+/// - `config`: The `CoreConfig` used by the state machine and its services.
+/// - `event_handler`: A function that handles events. It takes a string and an `EventData` struct as arguments and returns a `Result<(), String>`.
+///
+/// # Returns
+/// - `Result<CoreHandle, String>`: The handle of the running core, or an error message if it fails. Dropping the handle shuts the core down.
 ///
-pub async fn initialize_core<F>(event_handler: F) -> Result<(), String>
+pub async fn initialize_core_with_config<F>(config: CoreConfig, event_handler: F) -> Result<CoreHandle, String>
 where
     F: Fn(&String, &EventData) -> Result<(), String> + 'static + Send,
 {
-    // Define the state machine asynchronously
-    let state_machine_instance = MainStateMachine::new().await;
-    let raw_state_machine = state_machine_instance.uninitialized_state_machine().init().await;
-
-    unsafe {
-        // Set the event handler to the static variable
-        INTERNAL_STATE_MACHINE = Some(raw_state_machine);
-        INTERNAL_EVENT_HANDLER = Some(Box::new(event_handler));
-
-        // Initialize the state machine
-        INTERNAL_STATE_MACHINE
-            .as_mut()
-            .unwrap()
-            .handle(&NeuralAnalyticsCoreEvents::InitializeCore)
-            .await;
+    if config.runtime.dedicated {
+        return tokio::task::spawn_blocking(move || start_core_with_config(config, event_handler))
+            .await
+            .map_err(|e| format!("The core initialization panicked: {}", e))?;
     }
 
-    tokio::spawn(async move {
-        // Run the state machine in the background
-        loop {
-            unsafe {
-                let state_machine = INTERNAL_STATE_MACHINE.as_mut().unwrap();
-                state_machine.handle(&NeuralAnalyticsCoreEvents::BackgroundTick).await;
-            }
-        }
-    });
-
-    // NOTE: No returns a external Command Bus because no intents are defined in GUI.
-    Ok(())
+    let adapters = core_adapters(
+        headset_adapter(&config.synthetic_headset)?,
+        config.bulb_connection,
+        config.payload_policy(MQTT_DESTINATION),
+        config.recording.clone(),
+    );
+
+    initialize_core_with_adapters(config, adapters, event_handler).await
+}
+
+/// Start the core of the application on a dedicated runtime
+///
+/// Blocking counterpart of `initialize_core_with_config`, for applications embedding
+/// the core without a tokio runtime. The runtime is configured in the `runtime` section
+/// of the configuration, and is owned by the returned handle.
+///
+/// # Arguments
+/// - `config`: The `CoreConfig` used by the state machine and its services.
+/// - `event_handler`: A function that handles events. It takes a string and an `EventData` struct as arguments and returns a `Result<(), String>`.
+///
+/// # Returns
+/// - `Result<CoreHandle, String>`: The handle of the running core, or an error message if it fails. Dropping the handle shuts the core and its runtime down.
+///
+pub fn start_core_with_config<F>(config: CoreConfig, event_handler: F) -> Result<CoreHandle, String>
+where
+    F: Fn(&String, &EventData) -> Result<(), String> + 'static + Send,
+{
+    let bulb_connection = config.bulb_connection;
+    let mqtt_policy = config.payload_policy(MQTT_DESTINATION);
+    let recording = config.recording.clone();
+    let eeg_headset = headset_adapter(&config.synthetic_headset)?;
+
+    start_core_with_adapters(
+        config,
+        move || core_adapters(eeg_headset, bulb_connection, mqtt_policy, recording),
+        event_handler,
+    )
+}
+
+/// Adapters of the core: the headset, the Tapo bulb, the webhooks of the predictions, the model file
+/// watcher, the zip diagnostic bundles, the recordings of `recording`, the scheduler of the state machine
+/// thread, and the tensor dump, the lifecycle webhooks and the MQTT broker when configured, publishing in
+/// the shape of `mqtt_policy`
+fn core_adapters(
+    eeg_headset: Box<dyn EegHeadsetPort + Send + Sync>,
+    bulb_connection: BulbConnectionPolicy,
+    mqtt_policy: PayloadPolicy,
+    recording: RecordingConfig,
+) -> CoreAdapters {
+    let mut adapters = CoreAdapters::new(eeg_headset, Box::new(TapoSmartBulbAdapter::with_policy(bulb_connection)))
+        .with_prediction_webhook(Box::new(PredictionWebhookAdapter::new()))
+        .with_model_watcher(Box::new(ModelFileWatcherAdapter))
+        .with_bundle_archive(Box::new(ZipBundleArchiveAdapter))
+        .with_recorder(Box::new(FileRecorderAdapter::new(recording)))
+        .with_thread_scheduler(Box::new(OsThreadSchedulerAdapter));
+
+    if let Some(tensor_dump) = FileTensorDumpAdapter::from_env() {
+        adapters = adapters.with_tensor_dump(Box::new(tensor_dump));
+    }
+
+    if let Some(lifecycle_notifier) = LifecycleWebhookAdapter::from_env() {
+        adapters = adapters.with_lifecycle_notifier(Box::new(lifecycle_notifier));
+    }
+
+    if let Some(prediction_sink) = MqttPredictionSinkAdapter::from_env() {
+        adapters = adapters.with_prediction_sink(Box::new(prediction_sink.with_payload_policy(mqtt_policy)));
+    }
+
+    adapters
+}
+
+/// Headset adapter of the core: the BrainBit headset, a replayed recording, or the synthetic one in mock mode
+///
+/// # Arguments
+/// - `synthetic_signal`: The signal streamed by the synthetic headset in mock mode.
+///
+/// # Returns
+/// - `Result<Box<dyn EegHeadsetPort + Send + Sync>, String>`: The headset, or an error if the recording to replay cannot be read.
+fn headset_adapter(synthetic_signal: &SyntheticSignalConfig) -> Result<Box<dyn EegHeadsetPort + Send + Sync>, String> {
+    if let Ok(path) = std::env::var(REPLAY_RECORDING_VARIABLE) {
+        Ok(Box::new(ReplayHeadsetAdapter::real_time(&path)?))
+    } else if std::env::var(MOCK_HEADSET_VARIABLE).is_ok_and(|value| value == "true") {
+        let headset = SyntheticHeadsetAdapter::real_time().with_signal(synthetic_signal);
+        let _ = SIMULATED_IMPEDANCE.set(headset.simulated_impedance());
+
+        Ok(Box::new(headset))
+    } else {
+        Ok(Box::new(BrainFlowAdapter::default()))
+    }
+}
+
+/// Impedances reported by the synthetic headset, or `None` when the core does not run in mock mode
+pub fn simulated_impedance() -> Option<HashMap<String, u16>> {
+    SIMULATED_IMPEDANCE.get().map(SimulatedImpedance::values)
+}
+
+/// Set the impedance the synthetic headset reports for an electrode
+///
+/// Lets testers take the calibration screen through the whole quality range
+/// without touching hardware. The next calibration reading uses the new value.
+///
+/// # Arguments
+/// - `electrode`: The hardware name of the electrode, such as `O1`.
+/// - `impedance`: The impedance, in kOhm.
+///
+/// # Returns
+/// - `Result<(), String>`: An error if the core does not run in mock mode or the electrode is unknown.
+pub fn set_simulated_impedance(electrode: &str, impedance: u16) -> Result<(), String> {
+    SIMULATED_IMPEDANCE
+        .get()
+        .ok_or_else(|| format!("The core does not run with the synthetic headset, set {}=true", MOCK_HEADSET_VARIABLE))?
+        .set(electrode, impedance)
 }
@@ -0,0 +1,128 @@
+use neural_analytics_domain::domain::{
+    models::scheduling_config::SchedulingConfig, ports::output::thread_scheduler::ThreadSchedulerPort,
+};
+#[cfg(unix)]
+use thread_priority::{RealtimeThreadSchedulePolicy, ThreadSchedulePolicy};
+use thread_priority::{ThreadPriority, ThreadPriorityValue};
+
+/// Pins the thread of the state machine to the configured cores and raises its priority,
+/// through the scheduler of the operating system.
+///
+/// The cores are pinned with `sched_setaffinity` on Linux, so the thread may run on any of
+/// them, and with `core_affinity` elsewhere, which pins it to the first one.
+#[derive(Default)]
+pub struct OsThreadSchedulerAdapter;
+
+impl ThreadSchedulerPort for OsThreadSchedulerAdapter {
+    fn apply(&self, config: &SchedulingConfig) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        if !config.pinned_cores.is_empty() {
+            if let Err(e) = pin_current_thread(&config.pinned_cores) {
+                issues.push(format!("Failed to pin the thread to the cores {:?}: {}", config.pinned_cores, e));
+            }
+        }
+
+        if let Some(priority) = config.realtime_priority {
+            if let Err(e) = raise_current_thread_priority(priority) {
+                issues.push(format!("Failed to raise the thread priority to {}: {}", priority, e));
+            }
+        }
+
+        issues
+    }
+}
+
+// Keeps the configured cores that exist on this machine
+fn existing_cores(cores: &[usize]) -> Result<Vec<core_affinity::CoreId>, String> {
+    let available = core_affinity::get_core_ids().ok_or_else(|| "The cores of the machine are unknown".to_string())?;
+    let existing: Vec<_> = available.into_iter().filter(|core| cores.contains(&core.id)).collect();
+
+    if existing.is_empty() {
+        return Err("None of the cores exists on this machine".to_string());
+    }
+
+    Ok(existing)
+}
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(cores: &[usize]) -> Result<(), String> {
+    use nix::sched::{sched_setaffinity, CpuSet};
+    use nix::unistd::Pid;
+
+    // The thread may run on any of the cores, not only on the first one
+    let mut cpu_set = CpuSet::new();
+    for core in existing_cores(cores)? {
+        cpu_set.set(core.id).map_err(|e| format!("The core {} cannot be pinned: {}", core.id, e))?;
+    }
+
+    sched_setaffinity(Pid::from_raw(0), &cpu_set)
+        .map_err(|e| format!("The system refused to pin the thread to the cores: {}", e))
+}
+
+// `core_affinity` pins to a single core, so elsewhere the thread keeps the first one
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(cores: &[usize]) -> Result<(), String> {
+    let core = existing_cores(cores)?[0];
+
+    if !core_affinity::set_for_current(core) {
+        return Err(format!("The system refused to pin the thread to the core {}", core.id));
+    }
+
+    Ok(())
+}
+
+fn raise_current_thread_priority(priority: u8) -> Result<(), String> {
+    if !(1..=99).contains(&priority) {
+        return Err("The priority must be between 1 and 99".to_string());
+    }
+
+    let priority = ThreadPriority::Crossplatform(ThreadPriorityValue::try_from(priority)?);
+
+    #[cfg(unix)]
+    let result = thread_priority::set_thread_priority_and_policy(
+        thread_priority::thread_native_id(),
+        priority,
+        ThreadSchedulePolicy::Realtime(RealtimeThreadSchedulePolicy::Fifo),
+    );
+    #[cfg(not(unix))]
+    let result = thread_priority::set_current_thread_priority(priority);
+
+    result.map_err(|e| format!("{:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_reports_refused_hints() {
+        let config = SchedulingConfig {
+            pinned_cores: vec![usize::MAX],
+            realtime_priority: Some(0),
+        };
+
+        // Pinned in its own thread, so the other tests keep every core
+        let issues = std::thread::spawn(move || OsThreadSchedulerAdapter.apply(&config)).join().unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert!(OsThreadSchedulerAdapter.apply(&SchedulingConfig::default()).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_pin_current_thread_keeps_every_existing_core() {
+        let available: Vec<_> = core_affinity::get_core_ids().unwrap().into_iter().map(|core| core.id).collect();
+        let mut cores = available.clone();
+        cores.push(usize::MAX);
+
+        let affinity = std::thread::spawn(move || {
+            pin_current_thread(&cores).unwrap();
+            nix::sched::sched_getaffinity(nix::unistd::Pid::from_raw(0)).unwrap()
+        })
+        .join()
+        .unwrap();
+
+        assert!(available.iter().all(|core| affinity.is_set(*core).unwrap()));
+    }
+}
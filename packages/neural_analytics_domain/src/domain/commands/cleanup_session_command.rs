@@ -0,0 +1,6 @@
+#[derive(Debug, Clone)]
+pub struct CleanupSessionCommand;
+
+impl presage::Command for CleanupSessionCommand {
+    const NAME: &'static str = "cleanup-session";
+}
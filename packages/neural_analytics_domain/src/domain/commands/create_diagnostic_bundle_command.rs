@@ -0,0 +1,9 @@
+#[derive(Debug, Clone)]
+pub struct CreateDiagnosticBundleCommand {
+    /// Zip archive to write the bundle to, replaced if it exists.
+    pub path: String,
+}
+
+impl presage::Command for CreateDiagnosticBundleCommand {
+    const NAME: &'static str = "create-diagnostic-bundle";
+}
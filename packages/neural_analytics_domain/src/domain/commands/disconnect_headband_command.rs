@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DisconnectHeadbandCommand;
 
 impl presage::Command for DisconnectHeadbandCommand {
@@ -0,0 +1,6 @@
+#[derive(Debug, Clone)]
+pub struct EndSessionCommand;
+
+impl presage::Command for EndSessionCommand {
+    const NAME: &'static str = "end-session";
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExtractCalibrationDataCommand;
 
 impl presage::Command for ExtractCalibrationDataCommand {
@@ -0,0 +1,11 @@
+use crate::domain::models::headset_reading::HeadsetReading;
+
+#[derive(Debug, Clone)]
+pub struct ExtractGeneralistDataCommand {
+    /// Window read from the headset before the context was locked, or why it could not be read.
+    pub reading: Result<HeadsetReading, String>,
+}
+
+impl presage::Command for ExtractGeneralistDataCommand {
+    const NAME: &'static str = "extract-generalist-data";
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+pub mod cleanup_session_command;
+pub mod create_diagnostic_bundle_command;
+pub mod disconnect_headband_command;
+pub mod end_session_command;
+pub mod extract_calibration_data_command;
+pub mod extract_generalist_data_command;
+pub mod notify_webhook_command;
+pub mod predict_color_thinking_command;
+pub mod publish_prediction_command;
+pub mod run_diagnostics_command;
+pub mod run_test_signal_command;
+pub mod search_headband_command;
+pub mod set_log_level_command;
+pub mod start_session_command;
+pub mod update_light_status_command;
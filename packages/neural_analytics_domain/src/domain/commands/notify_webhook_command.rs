@@ -0,0 +1,11 @@
+#[derive(Debug, Clone)]
+pub struct NotifyWebhookCommand {
+    pub url: String,
+    pub prediction: String,
+    /// Outcome of `deliver_webhook`, called before the context was locked.
+    pub delivery: Result<(), String>,
+}
+
+impl presage::Command for NotifyWebhookCommand {
+    const NAME: &'static str = "notify-webhook";
+}
\ No newline at end of file
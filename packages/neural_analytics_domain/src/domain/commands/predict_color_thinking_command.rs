@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PredictColorThinkingCommand;
 
 impl presage::Command for PredictColorThinkingCommand {
@@ -0,0 +1,12 @@
+use crate::domain::models::published_prediction::PublishedPrediction;
+
+#[derive(Debug, Clone)]
+pub struct PublishPredictionCommand {
+    pub prediction: PublishedPrediction,
+    /// Outcome of `publish_to_sink`, called before the context was locked.
+    pub publication: Result<(), String>,
+}
+
+impl presage::Command for PublishPredictionCommand {
+    const NAME: &'static str = "publish-prediction";
+}
@@ -0,0 +1,6 @@
+#[derive(Debug, Clone)]
+pub struct RunDiagnosticsCommand;
+
+impl presage::Command for RunDiagnosticsCommand {
+    const NAME: &'static str = "run-diagnostics";
+}
\ No newline at end of file
@@ -0,0 +1,6 @@
+#[derive(Debug, Clone)]
+pub struct RunTestSignalCommand;
+
+impl presage::Command for RunTestSignalCommand {
+    const NAME: &'static str = "run-test-signal";
+}
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone)]
+pub struct SetLogLevelCommand {
+    /// Most verbose level logged from now on.
+    pub level: log::LevelFilter,
+}
+
+impl presage::Command for SetLogLevelCommand {
+    const NAME: &'static str = "set-log-level";
+}
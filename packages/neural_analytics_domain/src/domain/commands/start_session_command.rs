@@ -0,0 +1,9 @@
+#[derive(Debug, Clone)]
+pub struct StartSessionCommand {
+    /// File the EEG windows of the session are recorded to, `None` to not record them.
+    pub recording_path: Option<String>,
+}
+
+impl presage::Command for StartSessionCommand {
+    const NAME: &'static str = "start-session";
+}
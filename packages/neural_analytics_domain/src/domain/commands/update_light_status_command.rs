@@ -0,0 +1,15 @@
+#[derive(Debug, Clone)]
+pub struct UpdateLightStatusCommand {
+    pub is_light_on: bool,
+    pub color: Option<(u8, u8, u8)>,
+    /// Whether there is no physical bulb behind the adapter.
+    pub is_simulated: bool,
+    /// Outcome of `drive_bulb`, called before the context was locked.
+    pub actuation: Result<(), String>,
+    /// Time the bulb took to answer, its retries included.
+    pub latency_ms: u64,
+}
+
+impl presage::Command for UpdateLightStatusCommand {
+    const NAME: &'static str = "update-light-status";
+}
\ No newline at end of file
@@ -0,0 +1,150 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use log::error;
+
+use super::NeuralAnalyticsContext;
+use crate::domain::{
+    ports::{
+        input::eeg_headset::EegHeadsetPort,
+        output::{bundle_archive::BundleArchivePort, smart_bulb::SmartBulbPort},
+    },
+    services::model_inference_service::ModelInferenceInterface,
+};
+
+// Longest wait for the lock of an adapter, only held that long by an adapter stuck in a call
+const ADAPTER_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+type HeadsetPort = dyn EegHeadsetPort + Send + Sync;
+type BulbPort = dyn SmartBulbPort + Send + Sync;
+type ModelPort = dyn ModelInferenceInterface + Send + Sync;
+type BundleArchive = dyn BundleArchivePort + Send + Sync;
+
+/// Guarded access to the adapters of the context.
+///
+/// The use cases reach the adapters through these methods, so each lock is held for the
+/// call to the adapter and released before the next one is taken. The wait for each
+/// lock is reported in the `adapters.<name>.lock_wait.ms` gauge, and gives up after 5
+/// seconds with a timeout error, counted in `adapters.<name>.lock_timeouts`, which the
+/// command middleware retries like any other timeout.
+///
+/// The calls slow enough to hold the context, such as `drive_bulb`, take the adapter
+/// instead and run before the context is locked.
+impl NeuralAnalyticsContext {
+    /// Runs `f` with the headset, shared with the other readers.
+    pub async fn with_headset<R>(&mut self, f: impl FnOnce(&HeadsetPort) -> R) -> Result<R, String> {
+        let adapter = self.eeg_headset_adapter.clone();
+        let headset = self.acquire("headset", adapter.read()).await?;
+
+        Ok(f(headset.as_ref()))
+    }
+
+    /// Runs `f` with the headset, locked for it alone.
+    pub async fn with_headset_mut<R>(&mut self, f: impl FnOnce(&mut HeadsetPort) -> R) -> Result<R, String> {
+        let adapter = self.eeg_headset_adapter.clone();
+        let mut headset = self.acquire("headset", adapter.write()).await?;
+
+        Ok(f(headset.as_mut()))
+    }
+
+    /// Runs the call `f` makes to the smart bulb, holding its lock until the call completes.
+    ///
+    /// The methods of the bulb port return the future `f` expects, e.g.
+    /// `ctx.with_bulb(|bulb| bulb.change_state(BulbState::BulbOff)).await`.
+    pub async fn with_bulb<R>(&mut self, f: impl for<'a> FnOnce(&'a BulbPort) -> BoxFuture<'a, R>) -> Result<R, String> {
+        let adapter = self.smart_bulb_adapter.clone();
+        let bulb = self.acquire("bulb", adapter.read()).await?;
+
+        Ok(f(bulb.as_ref()).await)
+    }
+
+    /// Runs `f` with the archive of the diagnostic bundles, shared with the other readers.
+    pub async fn with_bundle_archive<R>(&mut self, f: impl FnOnce(&BundleArchive) -> R) -> Result<R, String> {
+        let adapter = self.bundle_archive.clone();
+        let archive = self.acquire("bundle_archive", adapter.read()).await?;
+
+        Ok(f(archive.as_ref()))
+    }
+
+    /// Runs `f` with the model service, shared with the other readers.
+    pub async fn with_model<R>(&mut self, f: impl FnOnce(&ModelPort) -> R) -> Result<R, String> {
+        let adapter = self.model_service.clone();
+        let model = self.acquire("model", adapter.read()).await?;
+
+        Ok(f(model.as_ref()))
+    }
+
+    /// Waits for the lock of an adapter, measuring the wait.
+    async fn acquire<G>(&mut self, name: &str, lock: impl Future<Output = G>) -> Result<G, String> {
+        let started = Instant::now();
+        let guard = tokio::time::timeout(ADAPTER_LOCK_TIMEOUT, lock).await;
+
+        self.metrics
+            .set_gauge(&format!("adapters.{}.lock_wait.ms", name), started.elapsed().as_secs_f64() * 1000.0);
+
+        guard.map_err(|_| {
+            self.metrics.increment_counter(&format!("adapters.{}.lock_timeouts", name), 1);
+
+            let error_msg = format!(
+                "Timed out after {} s waiting for the {} adapter, held by a call that did not return",
+                ADAPTER_LOCK_TIMEOUT.as_secs(),
+                name
+            );
+            error!("{}", error_msg);
+            error_msg
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::bulb_state::BulbState;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::RwLock;
+
+    /// Bulb remembering the last state it was changed to, shared by its clones
+    #[derive(Clone, Default)]
+    struct StateBulb {
+        state: Arc<Mutex<Option<BulbState>>>,
+    }
+
+    impl StateBulb {
+        fn state(&self) -> Option<BulbState> {
+            *self.state.lock().unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl SmartBulbPort for StateBulb {
+        async fn change_state(&self, state: BulbState) -> Result<(), String> {
+            *self.state.lock().unwrap() = Some(state);
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_adapter_locks_time_out_and_are_measured() {
+        let bulb = StateBulb::default();
+        let mut context = NeuralAnalyticsContext {
+            smart_bulb_adapter: Arc::new(RwLock::new(Box::new(bulb.clone()) as Box<BulbPort>)),
+            ..Default::default()
+        };
+
+        let result = context.with_bulb(|bulb| bulb.change_state(BulbState::BulbOn)).await;
+        assert_eq!(result, Ok(Ok(())));
+        assert_eq!(bulb.state(), Some(BulbState::BulbOn));
+        assert!(context.metrics.gauge("adapters.bulb.lock_wait.ms").is_some());
+
+        // A call stuck with the lock makes the next access fail instead of hanging
+        let adapter = context.smart_bulb_adapter.clone();
+        let _stuck = adapter.write().await;
+        let result = context.with_bulb(|bulb| bulb.change_state(BulbState::BulbOff)).await;
+
+        assert!(result.unwrap_err().contains("Timed out"));
+        assert_eq!(context.metrics.counter("adapters.bulb.lock_timeouts"), 1);
+        assert_eq!(bulb.state(), Some(BulbState::BulbOn));
+    }
+}
@@ -0,0 +1,259 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock as StdRwLock};
+
+use log::info;
+use once_cell::sync::OnceCell;
+use tokio::sync::RwLock;
+
+/// Adapter shared by the contexts and the background tasks, `P` being its port trait object.
+pub type SharedAdapter<P> = Arc<RwLock<Box<P>>>;
+
+/// Name of the adapter of a port when a single device is plugged.
+pub const DEFAULT_ADAPTER: &str = "default";
+
+/// Capability of the headsets measuring the impedance of their electrodes.
+pub const IMPEDANCE_CAPABILITY: &str = "impedance";
+
+/// Capability of the smart bulbs simulated in place of a real device.
+pub const SIMULATED_CAPABILITY: &str = "simulated";
+
+// Registered adapter, type-erased to be stored next to the adapters of the other ports
+struct RegistryEntry {
+    port: &'static str,
+    // The `SharedAdapter` of the port
+    adapter: Box<dyn Any + Send + Sync>,
+    capabilities: Vec<String>,
+}
+
+/// Adapters of a core, keyed by the port they implement and a name.
+///
+/// Each core owns its registry, so two cores of the same process never share their
+/// devices, and a core started after another one shut down registers its adapters
+/// again. The registry and the contexts share each adapter, so a replaced adapter is released
+/// once the contexts created before stop using it. Replacing an adapter only affects the
+/// lookups made afterwards, the contexts created before keep the previous one.
+///
+/// Each adapter is tagged with the capabilities it offers, e.g. `impedance` for a
+/// headset measuring the contact of its electrodes, so the devices able to serve a
+/// request can be found without knowing their names.
+#[derive(Default)]
+pub struct AdapterRegistry {
+    entries: StdRwLock<HashMap<(TypeId, String), RegistryEntry>>,
+}
+
+impl AdapterRegistry {
+    /// Registers the adapter of a port under a name, without capabilities.
+    ///
+    /// # Returns
+    /// * `Result<SharedAdapter<P>, String>`: The shared adapter, or an error if the
+    ///   port already has an adapter with that name.
+    pub fn register<P>(&self, name: &str, adapter: Box<P>) -> Result<SharedAdapter<P>, String>
+    where
+        P: ?Sized + Send + Sync + 'static,
+    {
+        self.register_with_capabilities(name, adapter, &[])
+    }
+
+    /// Registers the adapter of a port under a name, tagged with the capabilities it offers.
+    ///
+    /// # Returns
+    /// * `Result<SharedAdapter<P>, String>`: The shared adapter, or an error if the
+    ///   port already has an adapter with that name.
+    pub fn register_with_capabilities<P>(
+        &self,
+        name: &str,
+        adapter: Box<P>,
+        capabilities: &[&str],
+    ) -> Result<SharedAdapter<P>, String>
+    where
+        P: ?Sized + Send + Sync + 'static,
+    {
+        let mut entries = self.entries.write().map_err(|e| e.to_string())?;
+        let key = (TypeId::of::<P>(), name.to_string());
+
+        if entries.contains_key(&key) {
+            return Err(format!("{} adapter '{}' already registered", type_name::<P>(), name));
+        }
+
+        Ok(Self::insert(&mut entries, key, adapter, capabilities))
+    }
+
+    /// Registers the adapter of a port under a name, replacing the previous one if any.
+    ///
+    /// The replacement keeps the capabilities of the previous adapter.
+    pub fn replace<P>(&self, name: &str, adapter: Box<P>) -> SharedAdapter<P>
+    where
+        P: ?Sized + Send + Sync + 'static,
+    {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let key = (TypeId::of::<P>(), name.to_string());
+        let capabilities = entries.get(&key).map(|entry| entry.capabilities.clone()).unwrap_or_default();
+        let capabilities: Vec<&str> = capabilities.iter().map(String::as_str).collect();
+
+        Self::insert(&mut entries, key, adapter, &capabilities)
+    }
+
+    /// Looks up the adapter of a port by its name.
+    pub fn get<P>(&self, name: &str) -> Option<SharedAdapter<P>>
+    where
+        P: ?Sized + Send + Sync + 'static,
+    {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+
+        entries
+            .get(&(TypeId::of::<P>(), name.to_string()))
+            .and_then(|entry| entry.adapter.downcast_ref::<SharedAdapter<P>>())
+            .cloned()
+    }
+
+    /// Looks up the adapter of a port by its name, registering the fallback if it is missing.
+    pub fn get_or_register<P>(&self, name: &str, fallback: impl FnOnce() -> Box<P>) -> SharedAdapter<P>
+    where
+        P: ?Sized + Send + Sync + 'static,
+    {
+        if let Some(adapter) = self.get::<P>(name) {
+            return adapter;
+        }
+
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let key = (TypeId::of::<P>(), name.to_string());
+
+        // Another thread may have registered it while the lock was released
+        match entries.get(&key).and_then(|entry| entry.adapter.downcast_ref::<SharedAdapter<P>>()) {
+            Some(adapter) => adapter.clone(),
+            None => Self::insert(&mut entries, key, fallback(), &[]),
+        }
+    }
+
+    /// Names of the adapters of a port offering a capability, sorted.
+    pub fn with_capability<P>(&self, capability: &str) -> Vec<String>
+    where
+        P: ?Sized + Send + Sync + 'static,
+    {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<String> = entries
+            .iter()
+            .filter(|((port, _), entry)| {
+                *port == TypeId::of::<P>() && entry.capabilities.iter().any(|tag| tag == capability)
+            })
+            .map(|((_, name), _)| name.clone())
+            .collect();
+
+        names.sort();
+        names
+    }
+
+    /// Port, name and capabilities of every registered adapter, sorted, to tell which devices are plugged.
+    pub fn registered(&self) -> Vec<(&'static str, String, Vec<String>)> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        let mut registered: Vec<_> = entries
+            .iter()
+            .map(|((_, name), entry)| (entry.port, name.clone(), entry.capabilities.clone()))
+            .collect();
+
+        registered.sort();
+        registered
+    }
+
+    fn insert<P>(
+        entries: &mut HashMap<(TypeId, String), RegistryEntry>,
+        key: (TypeId, String),
+        adapter: Box<P>,
+        capabilities: &[&str],
+    ) -> SharedAdapter<P>
+    where
+        P: ?Sized + Send + Sync + 'static,
+    {
+        info!("Registering {} adapter '{}'", type_name::<P>(), key.1);
+
+        let shared: SharedAdapter<P> = Arc::new(RwLock::new(adapter));
+        entries.insert(
+            key,
+            RegistryEntry {
+                port: type_name::<P>(),
+                adapter: Box::new(shared.clone()),
+                capabilities: capabilities.iter().map(|capability| capability.to_string()).collect(),
+            },
+        );
+
+        shared
+    }
+}
+
+/// Registry of the contexts created outside a core, such as the replays and the benchmarks.
+///
+/// Shared by all of them, so the model is loaded once per process.
+pub(crate) fn standalone_adapter_registry() -> &'static AdapterRegistry {
+    static REGISTRY: OnceCell<AdapterRegistry> = OnceCell::new();
+    REGISTRY.get_or_init(AdapterRegistry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::output::smart_bulb::SmartBulbPort;
+    use crate::domain::models::bulb_state::BulbState;
+
+    struct NamedBulb(&'static str);
+
+    #[async_trait::async_trait]
+    impl SmartBulbPort for NamedBulb {
+        async fn change_state(&self, _state: BulbState) -> Result<(), String> {
+            Err(self.0.to_string())
+        }
+    }
+
+    type Bulb = dyn SmartBulbPort + Send + Sync;
+
+    #[tokio::test]
+    async fn test_registry_registers_looks_up_and_replaces_adapters() {
+        let registry = AdapterRegistry::default();
+
+        registry.register::<Bulb>(DEFAULT_ADAPTER, Box::new(NamedBulb("first"))).unwrap();
+        registry.register::<Bulb>("desk", Box::new(NamedBulb("desk"))).unwrap();
+        assert!(registry.register::<Bulb>(DEFAULT_ADAPTER, Box::new(NamedBulb("again"))).is_err());
+
+        let bulb = registry.get::<Bulb>(DEFAULT_ADAPTER).unwrap();
+        assert_eq!(bulb.read().await.change_state(BulbState::BulbOn).await, Err("first".to_string()));
+
+        registry.replace::<Bulb>(DEFAULT_ADAPTER, Box::new(NamedBulb("replaced")));
+        let bulb = registry.get::<Bulb>(DEFAULT_ADAPTER).unwrap();
+        assert_eq!(bulb.read().await.change_state(BulbState::BulbOn).await, Err("replaced".to_string()));
+
+        assert_eq!(registry.registered().len(), 2);
+        assert!(registry.get::<Bulb>("kitchen").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_registry_finds_adapters_by_capability() {
+        let registry = AdapterRegistry::default();
+
+        registry
+            .register_with_capabilities::<Bulb>("desk", Box::new(NamedBulb("desk")), &["color", "dimming"])
+            .unwrap();
+        registry
+            .register_with_capabilities::<Bulb>("hall", Box::new(NamedBulb("hall")), &["dimming"])
+            .unwrap();
+        registry.register::<Bulb>(DEFAULT_ADAPTER, Box::new(NamedBulb("plain"))).unwrap();
+
+        assert_eq!(registry.with_capability::<Bulb>("dimming"), ["desk", "hall"]);
+        assert_eq!(registry.with_capability::<Bulb>("color"), ["desk"]);
+        assert!(registry.with_capability::<Bulb>("infrared").is_empty());
+
+        // A replaced adapter keeps the capabilities of the device
+        registry.replace::<Bulb>("desk", Box::new(NamedBulb("desk again")));
+        assert_eq!(registry.with_capability::<Bulb>("color"), ["desk"]);
+    }
+
+    #[test]
+    fn test_registry_releases_replaced_adapters() {
+        let registry = AdapterRegistry::default();
+
+        let first = registry.register::<Bulb>(DEFAULT_ADAPTER, Box::new(NamedBulb("first"))).unwrap();
+        assert_eq!(Arc::strong_count(&first), 2);
+
+        registry.replace::<Bulb>(DEFAULT_ADAPTER, Box::new(NamedBulb("replaced")));
+        assert_eq!(Arc::strong_count(&first), 1);
+    }
+}
@@ -0,0 +1,1781 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use adapter_registry::{standalone_adapter_registry, AdapterRegistry, SharedAdapter};
+use singletons::{
+    get_bundle_archive_adapter, get_eeg_headset_adapter, get_lifecycle_notifier_adapter, get_model_service, get_prediction_sink_adapter,
+    get_prediction_webhook_adapter, get_smart_bulb_adapter, UnavailableAdapter,
+};
+
+use log::{error, info, warn};
+use presage::{async_trait, Error, Event, EventWriter, SerializedEvent};
+
+use super::{
+    events::bulb_connection_state_event::BulbConnectionStateEvent,
+    events::bulb_state_changed_event::BulbStateChangedEvent,
+    events::clock_drift_warning_event::ClockDriftWarningEvent,
+    events::command_panicked_event::CommandPanickedEvent,
+    events::diagnostic_bundle_created_event::DiagnosticBundleCreatedEvent,
+    events::exhibition_cue_event::ExhibitionCueEvent,
+    events::integrity_violation_event::IntegrityViolationEvent,
+    events::memory_pressure_event::MemoryPressureEvent,
+    events::prediction_acted_event::PredictionActedEvent,
+    events::prediction_explanation_event::PredictionExplanationEvent,
+    events::session_rotated_event::SessionRotatedEvent,
+    events::inference_paused_event::InferencePausedEvent,
+    events::stale_data_skipped_event::StaleDataSkippedEvent,
+    events::test_signal_verified_event::TestSignalVerifiedEvent,
+    events::untested_firmware_event::UntestedFirmwareEvent,
+    models::band_powers::BandPowers,
+    models::bulb_connection::BulbConnectionState,
+    models::bulb_state::BulbState,
+    models::calibration_thresholds::CalibrationThresholds,
+    models::causality_record::CausalityRecord,
+    models::channel_config::ChannelConfig,
+    models::context_snapshot::{AdapterStates, ChannelRange, ContextSnapshot},
+    models::core_config::CoreConfig,
+    models::exhibition::ExhibitionCue,
+    models::window_prediction::WindowPrediction,
+    models::diagnostic_bundle::StateTransition,
+    models::event_data::EventData,
+    models::experiment_config::ExperimentConfig,
+    models::impedance_snapshot::ImpedanceSnapshot,
+    models::integrity_report::{IntegrityReport, IntegrityViolation},
+    models::journal_replay::PredictionRecord,
+    models::lifecycle_event::{LifecycleEvent, LifecycleEventKind, SessionSummary},
+    models::event_internals::{
+        CheckedSignalQualityEvent, CollectedDiagnosticsEvent, ConnectedHeadsetEvent, CreatedDiagnosticBundleEvent, DiscardedWindowEvent, ExplainedPredictionEvent, JournaledEvent, NotifiedWebhookEvent, PublishedPredictionEvent, ReceivedCalibrationDataEvent, ReceivedGeneralistDataEvent,
+        ReceivedPredictColorThinkingDataEvent, VerifiedTestSignalEvent,
+    },
+    models::notification::Notification,
+    models::operation_progress::CancellationToken,
+    models::operator_action::{OperatorAction, OperatorActionRecord},
+    models::signal_quality::SignalQuality,
+    models::signal_unit::SignalUnit,
+    ports::{
+        input::eeg_headset::EegHeadsetPort,
+        output::{
+            bundle_archive::BundleArchivePort, lifecycle_notifier::LifecycleNotifierPort,
+            prediction_sink::PredictionSinkPort, prediction_webhook::PredictionWebhookPort, recorder::RecorderPort,
+            smart_bulb::SmartBulbPort,
+        },
+    },
+    services::{
+        calibration_profile_service::{CalibrationProfile, CalibrationProfileService},
+        band_power_service::BandPowerService,
+        calibration_policy_service::CalibrationPolicyService,
+        calibration_update_service::CalibrationUpdateService,
+        clock_drift_service::ClockDriftService,
+        data_integrity_service::DataIntegrityService,
+        prediction_smoothing_service::PredictionSmoothingService,
+        prediction_streak_service::PredictionStreakService,
+        firmware_compatibility_service::{check_firmware, FirmwareCompatibility},
+        inference_cache_service::InferenceCacheService,
+        journal_service::JournalService,
+        light_policy_service::LightPolicyService,
+        memory_budget_service::{estimate_samples_bytes, estimate_strings_bytes, MemoryBudgetService},
+        metrics_service::MetricsService,
+        model_inference_service::ModelInferenceInterface,
+        queue_monitor_service::QueueMonitorService,
+        session_stats_service::SessionStatsService,
+        stale_data_service::stale_window_age_ms,
+        threshold_advisor_service::ThresholdAdvisorService,
+        window_assembler_service::{WindowAssemblerService, WINDOW_SAMPLES},
+        window_overlap_service::WindowOverlapService,
+    },
+};
+use crate::utils::EventDispatcher;
+
+mod adapter_access;
+pub(crate) mod adapter_registry;
+pub(crate) mod singletons;
+
+// Number of state transitions kept for the diagnostic bundles
+const TRANSITION_HISTORY_SIZE: usize = 64;
+
+// Metric names of the states the calibrations are timed between, as recorded by `record_transition`
+const CALIBRATION_STATE: &str = "awaiting_headset_calibration";
+const CAPTURE_STATE: &str = "capturing_headset_data";
+
+pub(crate) struct NeuralAnalyticsContext {
+    // Data Context
+    pub headset_data: Option<HashMap<String, Vec<f32>>>,
+    pub color_thinking: VecDeque<String>,
+    pub impedance_data: Option<HashMap<String, u16>>,
+    // Calibration of the headsets without impedance support
+    pub signal_quality: Option<HashMap<String, SignalQuality>>,
+    pub bulb_state: Option<BulbState>,
+    pub last_bulb_actuation: Option<BulbStateChangedEvent>,
+    pub last_webhook_prediction: Option<String>,
+    pub last_published_prediction: Option<String>,
+    pub last_test_signal: Option<VerifiedTestSignalEvent>,
+    pub last_explanation: Option<ExplainedPredictionEvent>,
+    // Band powers of each channel of the last window, when enabled
+    pub band_powers: Option<HashMap<String, BandPowers>>,
+    // Prediction of the model for the last window, before smoothing, `None` when it was cached
+    pub window_prediction: Option<WindowPrediction>,
+    pub diagnostics_issues: Vec<String>,
+    pub channel_config: ChannelConfig,
+    pub channel_gains: HashMap<String, f32>,
+    pub skip_calibration_without_impedance: bool,
+    // Unit of the samples, reported by the headset adapter at initialization
+    pub signal_unit: SignalUnit,
+
+    // Session of the current user and headset, rotated when any of them changes
+    pub session_id: u64,
+    pub device_id: Option<String>,
+    pub firmware_version: Option<String>,
+    // Last state of the connection to the bulb reported to the frontends
+    pub bulb_connection: Option<BulbConnectionState>,
+    pub user_profile: Option<String>,
+    // Usage of the headset since it connected, `None` while no headset is connected
+    pub session_summary: Option<SessionSummary>,
+    // Set from the headset connection until it disconnects
+    pub is_headset_in_use: bool,
+    // Set while a session started by the operator is open, it spans the reconnects
+    pub is_session_explicit: bool,
+    // Violation that aborts the session in strict mode, until the state machine takes it
+    pub integrity_abort: Option<IntegrityReport>,
+    // Last changes of state, oldest first, for the diagnostic bundles
+    pub transitions: VecDeque<StateTransition>,
+    // Configuration the core was initialized with, without its secrets
+    pub redacted_config: CoreConfig,
+    // Calibration thresholds in effect, shared with the state machine that replaces them
+    pub calibration_thresholds: Arc<RwLock<CalibrationThresholds>>,
+
+    // Causality tracking (window -> prediction -> actuation)
+    pub window_id: u64,
+    // Acquisition time of the last window, from the board clock corrected for drift, or
+    // its reception when the headset does not timestamp its samples
+    pub window_timestamp: Option<i64>,
+    // Reception of the last window by the core, whose intervals are the cadence of the capture
+    window_received_at: Option<i64>,
+    pub predicted_window_id: Option<u64>,
+    pub prediction_timestamp: Option<i64>,
+    pub actuated_window_id: Option<u64>,
+    // Set from the prediction of a window until the state machine acts on it
+    pub is_prediction_pending: bool,
+    // Set when the current window was buffered by the board too long ago to predict from it
+    pub is_window_stale: bool,
+    // Actuation of the pending prediction, notified together with it
+    pub pending_actuation: Option<BulbStateChangedEvent>,
+
+    // Cancels the long-running operation in progress, shared with the frontends
+    pub cancellation: CancellationToken,
+    // Handlers of the core, which the commands report the progress of their operations to
+    pub(crate) events: Arc<EventDispatcher>,
+    // Set when the user cancelled the headset search, until they ask for a new one
+    pub is_search_paused: bool,
+    // Set when the user paused the capture, the headset stays connected until they resume it
+    pub is_capture_paused: bool,
+
+    // Ports and Adapters (los Arc<RwLock> de los singletons, compartidos con el registro)
+    pub eeg_headset_adapter: SharedAdapter<dyn EegHeadsetPort + Send + Sync>,
+    pub smart_bulb_adapter: SharedAdapter<dyn SmartBulbPort + Send + Sync>,
+    pub lifecycle_notifier: SharedAdapter<dyn LifecycleNotifierPort + Send + Sync>,
+    pub prediction_sink: SharedAdapter<dyn PredictionSinkPort + Send + Sync>,
+    pub prediction_webhook: SharedAdapter<dyn PredictionWebhookPort + Send + Sync>,
+    pub bundle_archive: SharedAdapter<dyn BundleArchivePort + Send + Sync>,
+
+    // Services (el Arc<RwLock> del singleton, compartido con el registro)
+    pub model_service: SharedAdapter<dyn ModelInferenceInterface + Send + Sync>,
+    pub light_policy: LightPolicyService,
+    pub metrics: MetricsService,
+    pub memory_budget: MemoryBudgetService,
+    pub queue_monitor: QueueMonitorService,
+    pub journal: JournalService,
+    // Records the extracted windows, nothing until the composition crate plugs a recorder
+    pub recording: Box<dyn RecorderPort>,
+    // Statistics of the current and last sessions, served to the external analytics
+    pub session_stats: SessionStatsService,
+    pub inference_cache: InferenceCacheService,
+    pub calibration_profiles: CalibrationProfileService,
+    pub calibration_updates: CalibrationUpdateService,
+    pub calibration_policy: CalibrationPolicyService,
+    pub band_power: BandPowerService,
+    pub feed_band_powers_to_model: bool,
+    pub clock_drift: ClockDriftService,
+    pub data_integrity: DataIntegrityService,
+    pub prediction_smoothing: PredictionSmoothingService,
+    pub prediction_streak: PredictionStreakService,
+    pub threshold_advisor: ThresholdAdvisorService,
+    pub window_assembler: WindowAssemblerService,
+    pub window_overlap: WindowOverlapService,
+    pub explain_predictions: bool,
+
+    // External events derived from the applied internal events, until the state machine publishes them
+    notifications: Vec<Notification>,
+    lifecycle_events: Vec<LifecycleEvent>,
+
+    // Names of the internal events applied by the command in progress, traced by the command middleware
+    pub applied_events: Vec<String>,
+}
+
+impl Default for NeuralAnalyticsContext {
+    fn default() -> Self {
+        NeuralAnalyticsContext::new(CoreConfig::default())
+    }
+}
+
+impl NeuralAnalyticsContext {
+    /// Creates the context from the core configuration, wiring the adapters of the standalone registry.
+    ///
+    /// # Arguments
+    /// * `config`: The configuration passed to `initialize_core_with_config`.
+    pub fn new(config: CoreConfig) -> Self {
+        Self::with_adapters(config, standalone_adapter_registry())
+    }
+
+    /// Creates the context from the core configuration, wiring the adapters of a core.
+    ///
+    /// # Arguments
+    /// * `config`: The configuration passed to `initialize_core_with_config`.
+    /// * `adapters`: The registry the composition crate registered the adapters of the core in.
+    pub(crate) fn with_adapters(config: CoreConfig, adapters: &AdapterRegistry) -> Self {
+        // Obtain the EEG headset adapter registered by the composition crate
+        let eeg_adapter = get_eeg_headset_adapter(adapters);
+
+        NeuralAnalyticsContext {
+            // Initialize the services that depend on the configuration
+            light_policy: LightPolicyService::new(&config),
+            metrics: MetricsService::default(),
+            memory_budget: MemoryBudgetService::new(config.memory_budget_bytes),
+            queue_monitor: QueueMonitorService::new(config.queue_backlog_threshold),
+            journal: JournalService::new(config.journal_path.as_deref()),
+            recording: Box::new(UnavailableAdapter),
+            session_stats: SessionStatsService::default(),
+            inference_cache: InferenceCacheService::default(),
+            calibration_updates: CalibrationUpdateService::new(config.calibration_updates),
+            calibration_policy: CalibrationPolicyService::new(config.calibration.clone()),
+            band_power: BandPowerService::new(config.band_powers.clone()),
+            feed_band_powers_to_model: config.band_powers.feed_model,
+            calibration_profiles: CalibrationProfileService::new(
+                config.calibration_profiles_path.as_deref(),
+                if config.research_mode { 0 } else { config.calibration_reuse_period_secs as i64 * 1000 },
+            ),
+            clock_drift: ClockDriftService::new(config.clock_drift_threshold_ms),
+            data_integrity: DataIntegrityService::new(config.strict_mode),
+            prediction_smoothing: PredictionSmoothingService::new(config.prediction_smoothing),
+            prediction_streak: PredictionStreakService::new(config.trash_streak.clone()),
+            threshold_advisor: ThresholdAdvisorService::new(config.auto_apply_suggested_thresholds),
+            window_assembler: WindowAssemblerService::default(),
+            window_overlap: WindowOverlapService::new(config.window_overlap),
+            explain_predictions: config.explain_predictions,
+            notifications: Vec::new(),
+            lifecycle_events: Vec::new(),
+            applied_events: Vec::new(),
+
+            // Initialize the data context
+            headset_data: None,
+            color_thinking: VecDeque::new(),
+            impedance_data: None,
+            signal_quality: None,
+            bulb_state: None,
+            last_bulb_actuation: None,
+            last_webhook_prediction: None,
+            last_published_prediction: None,
+            last_test_signal: None,
+            last_explanation: None,
+            band_powers: None,
+            window_prediction: None,
+            diagnostics_issues: Vec::new(),
+            channel_config: config.channel_config.clone(),
+            channel_gains: config.channel_gains.clone(),
+            skip_calibration_without_impedance: config.skip_calibration_without_impedance,
+            signal_unit: SignalUnit::default(),
+            session_id: 0,
+            device_id: None,
+            firmware_version: None,
+            bulb_connection: None,
+            user_profile: None,
+            session_summary: None,
+            is_headset_in_use: false,
+            is_session_explicit: false,
+            integrity_abort: None,
+            transitions: VecDeque::new(),
+            redacted_config: config.redacted(),
+            calibration_thresholds: Arc::new(RwLock::new(config.calibration_thresholds)),
+            window_id: 0,
+            window_timestamp: None,
+            window_received_at: None,
+            predicted_window_id: None,
+            prediction_timestamp: None,
+            actuated_window_id: None,
+            is_prediction_pending: false,
+            is_window_stale: false,
+            pending_actuation: None,
+            cancellation: CancellationToken::default(),
+            events: Arc::default(),
+            is_search_paused: false,
+            is_capture_paused: false,
+
+            // Initialize the adapters con los singletons del registro
+            eeg_headset_adapter: eeg_adapter,
+            smart_bulb_adapter: get_smart_bulb_adapter(adapters),
+            lifecycle_notifier: get_lifecycle_notifier_adapter(adapters),
+            prediction_sink: get_prediction_sink_adapter(adapters),
+            prediction_webhook: get_prediction_webhook_adapter(adapters),
+            bundle_archive: get_bundle_archive_adapter(adapters),
+
+            // Initialize the model service con el singleton del registro
+            model_service: get_model_service(adapters),
+        }
+    }
+
+    /// Get the real color that the user is thinking about.
+    /// 
+    /// This function checks if all the colors in the `color_thinking` buffer are the same.
+    /// If they are, it returns that color. Otherwise, it returns "unknown".
+    /// 
+    /// # Returns
+    /// * `String`: The color that the user is thinking about, or "unknown" if it cannot be determined.
+    pub fn get_color_thinking(&self) -> String {
+        if self.color_thinking.is_empty() {
+            return "unknown".to_string();
+        }
+
+        let first_color = self.color_thinking.front().unwrap();
+
+        if self.color_thinking.iter().all(|color| color == first_color) {
+           first_color.clone()
+        } else {
+            "unknown".to_string()
+        }
+    }
+
+    /// Queue an external event for the frontends, published once the context is released.
+    fn notify(&mut self, name: &'static str, data: EventData) {
+        self.notifications.push(Notification { name, data });
+    }
+
+    /// Take the external events derived since the last call, in the order they were derived.
+    pub fn take_notifications(&mut self) -> Vec<Notification> {
+        std::mem::take(&mut self.notifications)
+    }
+
+    /// Queue a lifecycle event of the current session for the lifecycle notifier.
+    pub fn record_lifecycle(&mut self, kind: LifecycleEventKind) {
+        self.push_lifecycle(kind, None);
+    }
+
+    /// Take the lifecycle events queued since the last call, in the order they happened.
+    pub fn take_lifecycle_events(&mut self) -> Vec<LifecycleEvent> {
+        std::mem::take(&mut self.lifecycle_events)
+    }
+
+    /// Start summarizing the usage of a connected headset.
+    ///
+    /// Within a session started by the operator, the connection continues its summary.
+    pub fn begin_session(&mut self) {
+        self.is_headset_in_use = true;
+        // The board numbers its packages from the connection
+        self.data_integrity.reset();
+
+        if self.is_session_explicit && self.session_summary.is_some() {
+            info!("Headset connected, session {} continues", self.session_id);
+            self.metrics.increment_counter("sessions.continued", 1);
+            return;
+        }
+
+        self.open_session_summary();
+    }
+
+    /// Close the summary of the session when its headset disconnects.
+    ///
+    /// A session started by the operator stays open until `end_explicit_session`.
+    pub fn end_session(&mut self) {
+        // The next connection calibrates the electrodes again
+        self.prediction_streak.resume();
+        self.calibration_updates.reset();
+        self.calibration_policy.reset();
+
+        if !std::mem::take(&mut self.is_headset_in_use) {
+            return;
+        }
+
+        self.record_lifecycle(LifecycleEventKind::Disconnected);
+        self.session_stats.observe_disconnect();
+
+        if !self.is_session_explicit {
+            self.finish_session_summary();
+        }
+    }
+
+    /// Close the session in progress and the files of the core, before it shuts down.
+    ///
+    /// The summary of the session is reported, even for a session started by the operator,
+    /// the recording is stopped and the journal synced to disk, so the end of the session
+    /// is not lost when the process exits right after.
+    pub fn close_for_shutdown(&mut self) {
+        self.is_session_explicit = false;
+        self.end_session();
+        self.finish_session_summary();
+        self.recording.stop();
+
+        self.journal.record("core_shutdown", &self.session_id);
+        self.journal.flush();
+    }
+
+    /// Open a session on behalf of the operator, independent of the headset connection.
+    ///
+    /// The session, its summary and its recording span the reconnects of the headset,
+    /// so brief disconnects do not split the data. A different headset or user profile
+    /// still rotates it.
+    ///
+    /// # Arguments
+    /// * `recording_path`: File the EEG windows of the session are recorded to, if any.
+    ///
+    /// # Returns
+    /// * `Result<(), String>`: An error if a session is already open or the recording cannot be opened.
+    pub fn start_explicit_session(&mut self, recording_path: Option<&str>) -> Result<(), String> {
+        if self.is_session_explicit {
+            return Err(format!("Session {} is already open", self.session_id));
+        }
+
+        self.is_session_explicit = true;
+        self.rotate_session("Session started by the operator".to_string());
+
+        match recording_path {
+            Some(path) => self.recording.start(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Close the session started by the operator, with its summary and its recording.
+    ///
+    /// A headset still connected continues in a new session, closed when it disconnects.
+    ///
+    /// # Returns
+    /// * `Result<(), String>`: An error if no session was started by the operator.
+    pub fn end_explicit_session(&mut self) -> Result<(), String> {
+        if !self.is_session_explicit {
+            return Err("No session was started by the operator".to_string());
+        }
+
+        self.is_session_explicit = false;
+        self.recording.stop();
+        self.rotate_session("Session ended by the operator".to_string());
+
+        Ok(())
+    }
+
+    /// Take the settings of the pipeline in effect, attached to the session that starts.
+    ///
+    /// The model is not awaited, as in `snapshot`: a model busy reloading is reported
+    /// without its hash and classes.
+    ///
+    /// # Returns
+    /// * `ExperimentConfig`: The model hash, the thresholds, the channels and the configuration.
+    pub fn experiment_config(&self) -> ExperimentConfig {
+        let model = self.model_service.try_read().ok();
+
+        ExperimentConfig {
+            session_id: self.session_id,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            core_version: env!("CARGO_PKG_VERSION").to_string(),
+            model_hash: model.as_ref().and_then(|model| model.model_hash()),
+            class_labels: model.map(|model| model.class_labels()).unwrap_or_default(),
+            signal_unit: self.signal_unit,
+            calibration_thresholds: self
+                .calibration_thresholds
+                .read()
+                .map(|thresholds| *thresholds)
+                .unwrap_or_default(),
+            config: self.redacted_config.clone(),
+        }
+    }
+
+    fn open_session_summary(&mut self) {
+        // Journaled too, so the sessions of the journal file can be traced offline
+        let experiment = self.experiment_config();
+        self.journal.record("experiment_config", &experiment);
+
+        self.session_stats.open(self.session_id, experiment.timestamp);
+        self.session_summary = Some(SessionSummary {
+            started_at: experiment.timestamp,
+            experiment: Some(experiment),
+            ..Default::default()
+        });
+
+        // Recorded to a directory of its own when configured, unless a recording is in progress
+        if let Some(recording_path) = self.recording.start_session(self.session_id) {
+            self.journal.record("session_recording", &recording_path);
+        }
+
+        self.record_lifecycle(LifecycleEventKind::SessionStarted);
+    }
+
+    fn finish_session_summary(&mut self) {
+        self.recording.end_session();
+
+        if let Some(mut summary) = self.session_summary.take() {
+            summary.ended_at = Some(chrono::Utc::now().timestamp_millis());
+            self.session_stats.close(summary.ended_at.unwrap_or_default());
+            self.push_lifecycle(LifecycleEventKind::SummaryReady, Some(summary));
+        }
+    }
+
+    fn push_lifecycle(&mut self, kind: LifecycleEventKind, summary: Option<SessionSummary>) {
+        self.lifecycle_events.push(LifecycleEvent {
+            kind,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            session_id: self.session_id,
+            device_id: self.device_id.clone(),
+            firmware_version: self.firmware_version.clone(),
+            user_profile: self.user_profile.clone(),
+            summary,
+        });
+    }
+
+    /// Apply an actuation attempt, confirmed or not, and notify it to the frontends.
+    ///
+    /// # Arguments
+    /// * `actuation`: The actuation attempt. Failed attempts are applied too, so
+    ///   consumers can track the reliability of the bulb.
+    pub fn apply_bulb_actuation(&mut self, actuation: BulbStateChangedEvent) {
+        let causality = self.record_bulb_actuation(&actuation);
+
+        self.notify(
+            BulbStateChangedEvent::NAME,
+            EventData {
+                bulb_state: Some(actuation.desired),
+                is_bulb_confirmed: Some(actuation.confirmed),
+                bulb_latency_ms: Some(actuation.latency_ms),
+                is_bulb_simulated: Some(actuation.is_simulated),
+                window_id: causality.as_ref().map(|record| record.window_id),
+                causality,
+                ..Default::default()
+            },
+        );
+
+        self.last_bulb_actuation = Some(actuation);
+    }
+
+    /// Notify the pending prediction together with the actuation it caused.
+    ///
+    /// The single point where a predicted window reaches the frontends, so the color
+    /// they show and the state of the bulb cannot disagree: either both are sent or
+    /// neither is. Does nothing when no prediction is pending.
+    ///
+    /// # Arguments
+    /// * `actuation`: The actuation attempt, confirmed or not, or `None` when the
+    ///   action of the prediction does not drive the bulb.
+    pub fn act_on_prediction(&mut self, actuation: Option<BulbStateChangedEvent>) {
+        if !std::mem::take(&mut self.is_prediction_pending) {
+            return;
+        }
+
+        let mut data = EventData {
+            headset_data: self.headset_data.clone(),
+            color_thinking: Some(self.get_color_thinking()),
+            window_id: self.predicted_window_id,
+            band_powers: self.band_powers.clone(),
+            window_prediction: self.window_prediction.clone(),
+            ..Default::default()
+        };
+
+        if let Some(actuation) = actuation {
+            data.causality = self.record_bulb_actuation(&actuation);
+            data.bulb_state = Some(actuation.desired);
+            data.is_bulb_confirmed = Some(actuation.confirmed);
+            data.bulb_latency_ms = Some(actuation.latency_ms);
+            data.is_bulb_simulated = Some(actuation.is_simulated);
+
+            self.last_bulb_actuation = Some(actuation);
+        }
+
+        self.notify(PredictionActedEvent::NAME, data);
+    }
+
+    /// Notify the change of the prediction shown in exhibition mode, once the bulb was
+    /// driven for it, so the overlay of the frontends changes along with the bulb.
+    pub fn show_exhibition_cue(&mut self, cue: ExhibitionCue) {
+        info!("Showing '{}' for {} ms", cue.class, cue.hold_ms);
+        self.journal.record("exhibition_cue", &cue);
+
+        self.notify(
+            ExhibitionCueEvent::NAME,
+            EventData {
+                exhibition_cue: Some(cue),
+                ..Default::default()
+            },
+        );
+    }
+
+    // Applies the bulb state and the usage of a confirmed actuation, linking it to its prediction
+    fn record_bulb_actuation(&mut self, actuation: &BulbStateChangedEvent) -> Option<CausalityRecord> {
+        if actuation.confirmed {
+            self.bulb_state = Some(actuation.desired);
+
+            if let Some(summary) = self.session_summary.as_mut() {
+                summary.actuations += 1;
+            }
+        }
+
+        self.record_actuation(actuation)
+    }
+
+    /// Notify the frontends that the handler of a command panicked, the core keeps running.
+    pub fn record_command_panic(&mut self, command: &str, message: &str) {
+        self.notify(
+            CommandPanickedEvent::NAME,
+            EventData {
+                command_name: Some(command.to_string()),
+                error_message: Some(message.to_string()),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Apply the report of a test signal verification and notify it to the frontends.
+    pub fn apply_test_signal(&mut self, report: VerifiedTestSignalEvent) {
+        self.notify(
+            TestSignalVerifiedEvent::NAME,
+            EventData {
+                headset_data: Some(report.headset_data.clone()),
+                diagnostics_issues: Some(report.issues.clone()),
+                ..Default::default()
+            },
+        );
+
+        self.last_test_signal = Some(report);
+    }
+
+    /// Account for a change between two different states of the state machine.
+    ///
+    /// # Arguments
+    /// * `timestamp`: Unix epoch milliseconds when the new state was entered.
+    /// * `from`: Metric name of the state left.
+    /// * `to`: Metric name of the state entered.
+    pub fn record_transition(&mut self, timestamp: i64, from: &str, to: &str) {
+        self.metrics.increment_labeled_counter("state.transitions", &[("from", from), ("to", to)], 1);
+
+        // The calibrations are timed from their state, which only succeeds towards the capture
+        if to == CALIBRATION_STATE {
+            self.session_stats.observe_calibration_start(timestamp);
+        } else if from == CALIBRATION_STATE {
+            self.session_stats.observe_calibration_end(timestamp, to == CAPTURE_STATE);
+        }
+
+        if self.transitions.len() == TRANSITION_HISTORY_SIZE {
+            self.transitions.pop_front();
+        }
+
+        let transition = StateTransition {
+            timestamp,
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        self.journal.record_at(timestamp, "transition", &transition);
+        self.transitions.push_back(transition);
+    }
+
+    /// Report the outcome of a diagnostic bundle to the frontends.
+    ///
+    /// # Arguments
+    /// * `bundle_path`: The archive the bundle was written to.
+    /// * `error_message`: Why the bundle could not be written, `None` when it was.
+    pub fn apply_diagnostic_bundle(&mut self, bundle_path: String, error_message: Option<String>) {
+        match &error_message {
+            Some(e) => error!("{}", e),
+            None => info!("Diagnostic bundle written to {}", bundle_path),
+        }
+
+        self.notify(
+            DiagnosticBundleCreatedEvent::NAME,
+            EventData {
+                bundle_path: Some(bundle_path),
+                error_message,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Append an operator action to the session journal, next to the current EEG window.
+    ///
+    /// # Arguments
+    /// * `action`: What the operator did.
+    /// * `timestamp`: Unix epoch milliseconds when the operator did it, as it is served between ticks.
+    pub fn record_operator_action(&mut self, action: OperatorAction, timestamp: i64) {
+        let record = OperatorActionRecord {
+            session_id: self.session_id,
+            window_id: self.window_id,
+            action,
+        };
+
+        self.journal.record_at(timestamp, "operator_action", &record);
+        self.metrics.increment_counter("operator.actions", 1);
+        self.refresh_memory_usage();
+    }
+
+    /// Account for a breach of the integrity of the captured data.
+    ///
+    /// Every breach is counted in the `integrity.violations` metric. In strict mode, the
+    /// first one of the session is journaled and notified with its report, and aborts the
+    /// session once the state machine takes it with `take_integrity_abort`.
+    ///
+    /// # Arguments
+    /// * `window_id`: The window the breach was found in.
+    /// * `violation`: What was breached.
+    pub fn report_integrity_violation(&mut self, window_id: u64, violation: IntegrityViolation) {
+        self.metrics
+            .increment_labeled_counter("integrity.violations", &[("kind", violation.kind())], 1);
+
+        if !self.data_integrity.is_strict() || self.integrity_abort.is_some() {
+            return;
+        }
+
+        let report = IntegrityReport {
+            violation,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            session_id: self.session_id,
+            window_id,
+            windows: self.session_summary.as_ref().map(|summary| summary.windows).unwrap_or_default(),
+            device_id: self.device_id.clone(),
+            firmware_version: self.firmware_version.clone(),
+            recording_path: self.recording.path().map(str::to_string),
+        };
+
+        error!(
+            "Aborting session {}, its data lost its integrity in window {}: {}",
+            report.session_id, report.window_id, report.violation
+        );
+
+        self.journal.record("integrity_violation", &report);
+        self.notify(
+            IntegrityViolationEvent::NAME,
+            EventData {
+                integrity_report: Some(report.clone()),
+                ..Default::default()
+            },
+        );
+        self.integrity_abort = Some(report);
+    }
+
+    /// Take the violation that must abort the session, reported in strict mode.
+    pub fn take_integrity_abort(&mut self) -> Option<IntegrityReport> {
+        self.integrity_abort.take()
+    }
+
+    /// Link an actuation attempt with the window and prediction that caused it.
+    ///
+    /// The resulting record is appended to the session journal, so the
+    /// stimulus-response latency can be analyzed offline. Only the first actuation
+    /// after a prediction is linked: later ones, like turning the bulb off when the
+    /// session ends, were not caused by it.
+    ///
+    /// Failed attempts no prediction waits for are journaled as `actuation_failed`,
+    /// so every failure of the bulb reaches the journal with its error.
+    ///
+    /// # Returns
+    /// * `Option<CausalityRecord>`: The record, or `None` if no prediction is waiting for an actuation.
+    fn record_actuation(&mut self, actuation: &BulbStateChangedEvent) -> Option<CausalityRecord> {
+        let record = self.link_actuation(actuation);
+
+        if record.is_none() && !actuation.confirmed {
+            self.journal.record("actuation_failed", actuation);
+            self.refresh_memory_usage();
+        }
+
+        record
+    }
+
+    // Links the actuation to the prediction waiting for it, journaling the link
+    fn link_actuation(&mut self, actuation: &BulbStateChangedEvent) -> Option<CausalityRecord> {
+        if self.actuated_window_id == self.predicted_window_id {
+            return None;
+        }
+
+        let record = CausalityRecord {
+            window_id: self.predicted_window_id?,
+            window_timestamp: self.window_timestamp?,
+            prediction: self.get_color_thinking(),
+            prediction_timestamp: self.prediction_timestamp?,
+            desired: actuation.desired,
+            confirmed: actuation.confirmed,
+            actuation_timestamp: chrono::Utc::now().timestamp_millis(),
+            actuation_latency_ms: actuation.latency_ms,
+            error: actuation.error.clone(),
+        };
+
+        self.actuated_window_id = Some(record.window_id);
+        self.journal.record("actuation", &record);
+        self.refresh_memory_usage();
+
+        Some(record)
+    }
+
+    /// Record the headset of the session, rotating it when a different headset connects.
+    ///
+    /// # Arguments
+    /// * `device_id`: The identifier reported by the headset, ignored when `None`.
+    pub fn identify_headset(&mut self, device_id: Option<String>) {
+        let Some(device_id) = device_id else {
+            return;
+        };
+
+        if let Some(previous) = self.device_id.as_ref().filter(|previous| **previous != device_id) {
+            let reason = format!("Headset changed from {} to {}", previous, device_id);
+            self.rotate_session(reason);
+        }
+
+        self.device_id = Some(device_id);
+    }
+
+    /// Record the depth of a queue between the stages of the core, warning on sustained backlog.
+    ///
+    /// The depth is published as the `queue.<name>.depth` gauge of the metrics, so the
+    /// stage that does not keep up on a slow machine shows in the snapshots.
+    ///
+    /// # Arguments
+    /// * `queue`: The name of the queue, such as `requests`.
+    /// * `depth`: The items waiting in the queue.
+    pub fn observe_queue_depth(&mut self, queue: &str, depth: usize) {
+        self.metrics.set_gauge(&format!("queue.{}.depth", queue), depth as f64);
+
+        if self.queue_monitor.observe(queue, depth) {
+            warn!("The {} queue is backlogged with {} items, its consumer does not keep up", queue, depth);
+            self.metrics.increment_counter(&format!("queue.{}.backlogs", queue), 1);
+        }
+    }
+
+    /// Record the firmware of the connected headset, warning when it was never tested.
+    ///
+    /// # Arguments
+    /// * `firmware_version`: The version reported by the headset, `None` if it does not expose it.
+    pub fn identify_firmware(&mut self, firmware_version: Option<String>) {
+        if check_firmware(firmware_version.as_deref()) == FirmwareCompatibility::Untested {
+            let version = firmware_version.clone().unwrap_or_default();
+            warn!("The headset runs the untested firmware {}, report any issue with it", version);
+
+            self.notify(
+                UntestedFirmwareEvent::NAME,
+                EventData {
+                    firmware_version: Some(version),
+                    ..Default::default()
+                },
+            );
+        }
+
+        self.firmware_version = firmware_version;
+    }
+
+    /// Record the state of the connection to the bulb, notifying the frontends when it changes.
+    ///
+    /// # Arguments
+    /// * `connection_state`: The state reported by the smart bulb adapter.
+    pub fn observe_bulb_connection(&mut self, connection_state: BulbConnectionState) {
+        if self.bulb_connection.as_ref() == Some(&connection_state) {
+            return;
+        }
+
+        match &connection_state {
+            BulbConnectionState::Connecting { .. } => {
+                info!("Smart bulb connection: {:?}", connection_state);
+                self.metrics.increment_counter("bulb.connection.attempts", 1);
+            }
+            BulbConnectionState::Unavailable { reason } => {
+                warn!("The smart bulb is unavailable, the predictions do not change the light: {}", reason)
+            }
+            state => info!("Smart bulb connection: {:?}", state),
+        }
+
+        self.notify(
+            BulbConnectionStateEvent::NAME,
+            EventData {
+                bulb_connection: Some(connection_state.clone()),
+                ..Default::default()
+            },
+        );
+        self.bulb_connection = Some(connection_state);
+    }
+
+    /// Record the user of the session, rotating it when the profile changes.
+    ///
+    /// # Arguments
+    /// * `user_profile`: The name of the profile of the user wearing the headset.
+    pub fn change_user_profile(&mut self, user_profile: &str) {
+        if let Some(previous) = self.user_profile.as_ref().filter(|previous| *previous != user_profile) {
+            let reason = format!("User profile changed from {} to {}", previous, user_profile);
+            self.rotate_session(reason);
+        }
+
+        self.user_profile = Some(user_profile.to_string());
+    }
+
+    /// Store the calibration that just finished, so the same user and headset can reuse it.
+    ///
+    /// # Arguments
+    /// * `impedance_data`: The reading that finished the calibration.
+    pub fn remember_calibration(&mut self, impedance_data: &HashMap<String, u16>) {
+        // Headsets that cannot be told apart never reuse a calibration
+        let Some(device_id) = self.device_id.clone() else {
+            return;
+        };
+
+        self.calibration_profiles.remember(CalibrationProfile {
+            device_id,
+            user_profile: self.user_profile.clone(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            max_impedance: impedance_data.values().copied().max().unwrap_or_default(),
+        });
+    }
+
+    /// Takes the snapshot of the impedance reading that finished the calibration, in the
+    /// unit the headset reports it, and journals it as `calibrated_impedance`.
+    ///
+    /// # Arguments
+    /// * `impedance_data`: The impedance of each electrode in the accepted reading.
+    ///
+    /// # Returns
+    /// * `Option<ImpedanceSnapshot>`: The snapshot, `None` if the reading is not a valid one.
+    pub async fn snapshot_calibration(&mut self, impedance_data: &HashMap<String, u16>) -> Option<ImpedanceSnapshot> {
+        let unit = self.with_headset(|headset| headset.impedance_unit()).await.unwrap_or_default();
+
+        match ImpedanceSnapshot::from_readings(impedance_data, unit, chrono::Utc::now().timestamp_millis()) {
+            Ok(snapshot) => {
+                self.journal.record("calibrated_impedance", &snapshot);
+                Some(snapshot)
+            }
+            Err(e) => {
+                warn!("Not reporting the final impedance of the calibration: {}", e);
+                self.metrics.increment_counter("calibration.invalid_snapshots", 1);
+                None
+            }
+        }
+    }
+
+    /// Close the current session and open a new one with a fresh normalization state.
+    ///
+    /// The buffers that adapt to the user or the headset are reset, so nothing
+    /// bleeds across them, and a recording in progress continues in a new file.
+    ///
+    /// # Arguments
+    /// * `reason`: Why the session was rotated, reported to the frontends.
+    pub fn rotate_session(&mut self, reason: String) {
+        // A headset in use keeps its connection, only its summary is split
+        let keeps_summary = self.is_headset_in_use || self.is_session_explicit;
+        self.finish_session_summary();
+
+        self.session_id += 1;
+        info!("Starting session {}: {}", self.session_id, reason);
+
+        self.headset_data = None;
+        self.impedance_data = None;
+        self.signal_quality = None;
+        self.color_thinking.clear();
+        self.last_explanation = None;
+        self.band_powers = None;
+        self.window_prediction = None;
+        self.light_policy.reset();
+        self.band_power.reset();
+        self.window_assembler = WindowAssemblerService::default();
+        // Shared with the model watcher, so it is cleared in place
+        self.inference_cache.clear();
+        self.threshold_advisor = ThresholdAdvisorService::new(self.threshold_advisor.is_auto_apply());
+        self.clock_drift.reset();
+        self.data_integrity.reset();
+        self.prediction_smoothing.reset();
+
+        let recording_path = self.recording.rotate(self.session_id).unwrap_or_else(|e| {
+            error!("Failed to rotate the recording: {}", e);
+            None
+        });
+
+        let rotation = SessionRotatedEvent {
+            session_id: self.session_id,
+            reason,
+            recording_path,
+        };
+
+        self.journal.record("session_rotated", &rotation);
+        self.metrics.increment_counter("sessions.rotated", 1);
+        self.notify(
+            SessionRotatedEvent::NAME,
+            EventData {
+                session_id: Some(rotation.session_id),
+                session_rotation_reason: Some(rotation.reason),
+                recording_path: rotation.recording_path,
+                ..Default::default()
+            },
+        );
+
+        if keeps_summary {
+            self.open_session_summary();
+        }
+    }
+
+    /// Take a serializable summary of the context, to be attached to issue reports.
+    ///
+    /// The adapters are not awaited: an adapter busy in another task is reported
+    /// as unknown instead of delaying the snapshot.
+    ///
+    /// # Returns
+    /// * `ContextSnapshot`: The buffer sizes, last prediction, normalization ranges and adapter states.
+    pub fn snapshot(&self) -> ContextSnapshot {
+        let headset_data = self.headset_data.clone().unwrap_or_default();
+
+        let normalization_ranges = headset_data
+            .iter()
+            .filter(|(_, values)| !values.is_empty())
+            .map(|(channel, values)| {
+                let range = ChannelRange {
+                    min: values.iter().copied().fold(f32::INFINITY, f32::min),
+                    max: values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+                    mean: neural_analytics_signal_math::mean(values),
+                };
+
+                (channel.clone(), range)
+            })
+            .collect();
+
+        let headset = self.eeg_headset_adapter.try_read().ok();
+        let model = self.model_service.try_read().ok();
+
+        ContextSnapshot {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            headset_samples: headset_data
+                .iter()
+                .map(|(channel, values)| (channel.clone(), values.len()))
+                .collect(),
+            impedance_channels: self.impedance_data.as_ref().map(HashMap::len).unwrap_or(0),
+            color_thinking_buffer: self.color_thinking.iter().cloned().collect(),
+            color_thinking: self.get_color_thinking(),
+            window_id: self.window_id,
+            predicted_window_id: self.predicted_window_id,
+            normalization_ranges,
+            bulb_state: self.bulb_state,
+            adapters: AdapterStates {
+                is_headset_connected: headset.as_ref().map(|headset| headset.is_connected()),
+                headset_work_mode: headset.as_ref().map(|headset| format!("{:?}", headset.get_work_mode())),
+                headset_firmware_version: self.firmware_version.clone(),
+                is_bulb_simulated: self.smart_bulb_adapter.try_read().ok().map(|bulb| bulb.is_simulated()),
+                is_model_loaded: model.as_ref().map(|model| model.is_model_loaded()),
+                model_load_error: model.as_ref().and_then(|model| model.load_error()),
+            },
+            diagnostics_issues: self.diagnostics_issues.clone(),
+            memory_usage: self.memory_budget.usage().clone(),
+            metrics: self.metrics.snapshot(),
+        }
+    }
+
+    /// Refresh the memory estimate of every context buffer and enforce the budget.
+    ///
+    /// When the budget is exceeded, the buffers that grow with the input are trimmed:
+    /// the window keeps only the samples the model reads, the journal only its newest
+    /// entry, and spare allocations are released. The prediction buffer is bounded by
+    /// the smoothing and left alone, so the bulb keeps following smoothed predictions.
+    /// The frontends are notified once per episode.
+    pub fn refresh_memory_usage(&mut self) {
+        let is_crossed = self.measure_buffers();
+
+        if self.memory_budget.is_exceeded() {
+            self.journal.truncate_tail(1);
+
+            // A window the board buffered during a stall can be far longer than the model reads,
+            // which predicts its last samples, so the oldest ones are dropped
+            if let Some(headset_data) = self.headset_data.as_mut() {
+                headset_data.values_mut().for_each(|values| {
+                    let excess = values.len().saturating_sub(WINDOW_SAMPLES);
+                    values.drain(..excess);
+                    values.shrink_to_fit();
+                });
+            }
+
+            self.measure_buffers();
+        }
+
+        if is_crossed {
+            self.notify(
+                MemoryPressureEvent::NAME,
+                EventData {
+                    memory_usage: Some(self.memory_budget.usage().clone()),
+                    memory_budget_bytes: Some(self.memory_budget.budget_bytes()),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    fn measure_buffers(&mut self) -> bool {
+        let usages = [
+            (
+                "color_thinking",
+                estimate_strings_bytes(self.color_thinking.iter())
+                    + (self.color_thinking.capacity() - self.color_thinking.len()) * std::mem::size_of::<String>(),
+            ),
+            (
+                "headset_data",
+                self.headset_data.as_ref().map(estimate_samples_bytes).unwrap_or(0),
+            ),
+            ("previous_window", self.window_assembler.estimated_bytes()),
+            ("journal", self.journal.estimated_bytes()),
+            ("band_powers", self.band_power.estimated_bytes()),
+        ];
+
+        let mut crossed = false;
+
+        for (buffer, bytes) in usages {
+            crossed |= self.memory_budget.update_usage(buffer, bytes);
+            self.metrics.set_gauge(&format!("memory.{}.bytes", buffer), bytes as f64);
+        }
+
+        self.metrics.set_gauge("memory.total.bytes", self.memory_budget.total_usage() as f64);
+
+        crossed
+    }
+}
+
+#[async_trait]
+impl EventWriter for NeuralAnalyticsContext {
+    type Error = Error;
+
+    /// Write an event to the context. This function is called when an event is received.
+    /// 
+    /// It updates the context with the event data, depending on the type of event,
+    /// and derives the external events the frontends must receive once it is applied.
+    /// 
+    /// # Arguments
+    /// * `event`: The serialized event to be processed.
+    /// 
+    /// # Returns
+    /// * `Result<(), Error>`: Returns `Ok(())` if the event is processed successfully, or an error if it fails.
+    async fn write(&mut self, event: &SerializedEvent) -> Result<(), Error> {
+        self.applied_events.push(event.name().to_string());
+
+        // Journaled before it is applied, so a replay of the journal applies it again
+        if let Some(journaled) = JournaledEvent::of(event) {
+            self.journal.record("event", &journaled);
+        }
+
+        if event.name() == ReceivedCalibrationDataEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<ReceivedCalibrationDataEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            self.headset_data = None;
+            self.threshold_advisor.observe(&event_data.impedance_data);
+            self.impedance_data = Some(event_data.impedance_data);
+            self.signal_quality = None;
+        } else if event.name() == CheckedSignalQualityEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(event)
+                .deserialize::<CheckedSignalQualityEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            self.headset_data = None;
+            self.impedance_data = None;
+            self.signal_quality = Some(event_data.signal_quality);
+        } else if event.name() == ConnectedHeadsetEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<ConnectedHeadsetEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            if let (Some(device_id), Some(timings)) = (&event_data.device_id, event_data.device_timings) {
+                self.calibration_profiles.remember_device_timings(device_id, timings);
+            }
+
+            // Known before the headset is identified, so a rotated session starts with it
+            self.identify_firmware(event_data.firmware_version);
+            self.identify_headset(event_data.device_id);
+        } else if event.name() == ReceivedGeneralistDataEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<ReceivedGeneralistDataEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            if let Some(violation) = self.data_integrity.check_sequence(event_data.sequence_numbers.as_deref().unwrap_or_default()) {
+                self.report_integrity_violation(event_data.window_id, violation);
+            }
+
+            if !event_data.non_finite_samples.is_empty() {
+                let violation = IntegrityViolation::NonFiniteSamples {
+                    channels: event_data.non_finite_samples.clone(),
+                };
+                self.report_integrity_violation(event_data.window_id, violation);
+            }
+
+            // In strict mode, the window that broke the integrity stays out of the recording
+            if self.integrity_abort.is_some() {
+                self.headset_data = None;
+                self.window_id = event_data.window_id;
+                self.refresh_memory_usage();
+
+                return Ok(());
+            }
+
+            self.window_assembler.remember(&event_data.headset_data);
+            let sample_timestamp = event_data.board_timestamp.and_then(|board_timestamp| {
+                if let Some(drift_ms) = self.clock_drift.observe(board_timestamp, chrono::Utc::now().timestamp_millis()) {
+                    self.notify(
+                        ClockDriftWarningEvent::NAME,
+                        EventData {
+                            clock_drift_ms: Some(drift_ms),
+                            ..Default::default()
+                        },
+                    );
+
+                    let violation = IntegrityViolation::ClockDrift {
+                        drift_ms,
+                        threshold_ms: self.clock_drift.threshold_ms(),
+                    };
+                    self.report_integrity_violation(event_data.window_id, violation);
+                }
+
+                self.metrics.set_gauge("clock.drift.ms", self.clock_drift.drift_ms());
+                self.clock_drift.correct(board_timestamp)
+            });
+
+            // A window the board buffered during a stall is still recorded, but not predicted
+            let data_age_ms = event_data.board_timestamp.and_then(|board_timestamp| {
+                stale_window_age_ms(
+                    board_timestamp,
+                    chrono::Utc::now().timestamp_millis(),
+                    self.prediction_smoothing.window_period_ms(),
+                )
+            });
+            self.is_window_stale = data_age_ms.is_some();
+
+            if let Some(data_age_ms) = data_age_ms {
+                warn!("Window {} is {} ms old, skipping its prediction", event_data.window_id, data_age_ms);
+                self.metrics.increment_counter("windows.stale", 1);
+                self.notify(
+                    StaleDataSkippedEvent::NAME,
+                    EventData {
+                        window_id: Some(event_data.window_id),
+                        data_age_ms: Some(data_age_ms),
+                        ..Default::default()
+                    },
+                );
+            }
+
+            self.recording.record_window(event_data.window_id, &event_data.headset_data, sample_timestamp);
+
+            if let Some(summary) = self.session_summary.as_mut() {
+                summary.windows += 1;
+            }
+            self.session_stats.observe_window();
+
+            self.band_powers = self.band_power.observe(&event_data.headset_data, self.window_overlap.hop_samples());
+            self.headset_data = Some(event_data.headset_data);
+            self.impedance_data = None;
+            self.window_id = event_data.window_id;
+
+            // The spread of the interval is the jitter the scheduling hints reduce
+            let received_at = chrono::Utc::now().timestamp_millis();
+            if let Some(previous) = self.window_received_at {
+                self.metrics.set_gauge("windows.interval.ms", (received_at - previous) as f64);
+
+                // The smoothed predictions keep covering the same time at the new cadence
+                if let Some(capacity) = self.prediction_smoothing.observe_interval(received_at - previous) {
+                    info!("Smoothing the predictions over {} windows", capacity);
+                    self.metrics.set_gauge("smoothing.predictions", capacity as f64);
+
+                    while self.color_thinking.len() > capacity {
+                        self.color_thinking.pop_front();
+                    }
+                }
+            }
+            self.window_received_at = Some(received_at);
+            self.window_timestamp = Some(sample_timestamp.unwrap_or(received_at));
+        } else if event.name() == DiscardedWindowEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<DiscardedWindowEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            if let Some(violation) = self.data_integrity.check_sequence(event_data.sequence_numbers.as_deref().unwrap_or_default()) {
+                self.report_integrity_violation(event_data.window_id, violation);
+            }
+
+            let violation = IntegrityViolation::DiscardedWindow {
+                reason: event_data.reason.clone(),
+            };
+            self.report_integrity_violation(event_data.window_id, violation);
+
+            self.headset_data = None;
+            self.window_id = event_data.window_id;
+            self.metrics.increment_counter("windows.rejected", 1);
+        } else if event.name() == ReceivedPredictColorThinkingDataEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<ReceivedPredictColorThinkingDataEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            while self.color_thinking.len() >= self.prediction_smoothing.capacity() {
+                self.color_thinking.pop_front();
+            }
+
+            if let (false, Some(prediction)) = (event_data.is_cached, &event_data.prediction) {
+                self.inference_cache.store(event_data.window_hash, prediction.clone());
+            }
+
+            // Overlapping less when the inference no longer keeps up with the hop
+            if let Some(inference_ms) = event_data.inference_ms {
+                if let Some(hop_samples) = self.window_overlap.observe_latency(inference_ms) {
+                    info!(
+                        "Hopping {} samples between windows, an overlap of {:.0}%",
+                        hop_samples,
+                        self.window_overlap.effective_overlap() * 100.0
+                    );
+                    self.metrics.set_gauge("windows.hop.samples", hop_samples as f64);
+                }
+            }
+            self.metrics.increment_counter(
+                if event_data.is_cached { "inference.cached" } else { "inference.computed" },
+                1,
+            );
+
+            if let Some(summary) = self.session_summary.as_mut() {
+                *summary.predictions.entry(event_data.color_thinking.clone()).or_default() += 1;
+            }
+            self.session_stats.observe_prediction(
+                &event_data.color_thinking,
+                event_data.prediction.as_ref().and_then(|prediction| prediction.confidence),
+            );
+            self.window_prediction = event_data.prediction;
+
+            self.color_thinking.push_back(event_data.color_thinking.clone());
+            let smoothed = self.get_color_thinking();
+
+            // Moving away from the notified prediction notifies it again when it comes back
+            if self.last_webhook_prediction.as_ref().is_some_and(|notified| *notified != smoothed) {
+                self.last_webhook_prediction = None;
+            }
+
+            // A long streak of garbage means the electrodes lost contact, predicting on is a waste
+            let is_paused = self.prediction_streak.observe(&event_data.color_thinking, &smoothed);
+            self.metrics.set_gauge("predictions.trash.streak", self.prediction_streak.streak() as f64);
+            self.metrics.set_gauge("predictions.trash.longest", self.prediction_streak.longest_streak() as f64);
+
+            self.journal.record(
+                "prediction",
+                &PredictionRecord {
+                    window_id: event_data.window_id,
+                    prediction: event_data.color_thinking,
+                    is_cached: event_data.is_cached,
+                    smoothed,
+                },
+            );
+
+            if is_paused {
+                let paused = InferencePausedEvent {
+                    streak: self.prediction_streak.streak(),
+                    window_id: event_data.window_id,
+                };
+
+                warn!(
+                    "Pausing the inference after {} garbage predictions up to window {}, check the electrodes",
+                    paused.streak, paused.window_id
+                );
+                self.metrics.increment_counter("inference.paused", 1);
+                self.journal.record("inference_paused", &paused);
+                self.notify(
+                    InferencePausedEvent::NAME,
+                    EventData {
+                        window_id: Some(paused.window_id),
+                        trash_streak: Some(paused.streak),
+                        ..Default::default()
+                    },
+                );
+            }
+            self.impedance_data = None;
+            self.predicted_window_id = Some(event_data.window_id);
+            self.prediction_timestamp = Some(chrono::Utc::now().timestamp_millis());
+
+            // Notified with its actuation once the state machine acts on it
+            self.is_prediction_pending = true;
+            self.pending_actuation = None;
+        } else if event.name() == ExplainedPredictionEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<ExplainedPredictionEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            self.notify(
+                PredictionExplanationEvent::NAME,
+                EventData {
+                    window_id: Some(event_data.window_id),
+                    color_thinking: Some(event_data.predicted_class.clone()),
+                    channel_contributions: Some(event_data.channel_contributions.clone()),
+                    ..Default::default()
+                },
+            );
+            self.journal.record("explanation", &event_data);
+            self.last_explanation = Some(event_data);
+        } else if event.name() == BulbStateChangedEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<BulbStateChangedEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            // The actuation of a pending prediction waits to be notified with it
+            if self.is_prediction_pending {
+                self.pending_actuation = Some(event_data);
+            } else {
+                self.apply_bulb_actuation(event_data);
+            }
+        } else if event.name() == NotifiedWebhookEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<NotifiedWebhookEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            // A rejected notification is only attempted again on the next transition
+            if let Some(error) = event_data.error {
+                warn!("Webhook not notified of prediction '{}': {}", event_data.prediction, error);
+                self.metrics.increment_counter("webhooks.failed", 1);
+            }
+
+            self.last_webhook_prediction = Some(event_data.prediction);
+        } else if event.name() == PublishedPredictionEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(event)
+                .deserialize::<PublishedPredictionEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            self.last_published_prediction = Some(event_data.prediction);
+        } else if event.name() == VerifiedTestSignalEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<VerifiedTestSignalEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            self.apply_test_signal(event_data);
+        } else if event.name() == CollectedDiagnosticsEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<CollectedDiagnosticsEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            self.diagnostics_issues = event_data.issues;
+        } else if event.name() == CreatedDiagnosticBundleEvent::NAME {
+            let event_data = <SerializedEvent as Clone>::clone(&event)
+                .deserialize::<CreatedDiagnosticBundleEvent>()
+                .expect("BUG: Failed to deserialize event");
+
+            self.apply_diagnostic_bundle(event_data.bundle_path, None);
+        }
+
+        self.refresh_memory_usage();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::calibration_thresholds::CalibrationThresholds;
+    use crate::domain::models::impedance_snapshot::ImpedanceUnit;
+    use crate::domain::services::firmware_compatibility_service::TESTED_FIRMWARE_VERSIONS;
+
+    #[test]
+    fn test_record_operator_action_in_journal() {
+        let mut context = NeuralAnalyticsContext {
+            window_id: 42,
+            ..Default::default()
+        };
+
+        context.record_operator_action(OperatorAction::Annotation { text: "Subject moved".to_string() }, 1_000);
+
+        let entry = context.journal.tail().back().unwrap();
+        assert_eq!(entry.kind, "operator_action");
+        assert_eq!(entry.timestamp, 1_000);
+        assert_eq!(entry.payload["action"], "annotation");
+        assert_eq!(entry.payload["text"], "Subject moved");
+        assert_eq!(entry.payload["window_id"], 42);
+    }
+
+    #[test]
+    fn test_refresh_memory_usage_trims_the_window_but_not_the_smoothing() {
+        let mut context = NeuralAnalyticsContext {
+            memory_budget: MemoryBudgetService::new(0),
+            headset_data: Some(
+                [("T3".to_string(), (0..10 * WINDOW_SAMPLES).map(|sample| sample as f32).collect())]
+                    .into_iter()
+                    .collect(),
+            ),
+            color_thinking: ["green", "red", "green"].into_iter().map(String::from).collect(),
+            ..Default::default()
+        };
+
+        context.refresh_memory_usage();
+
+        // The newest samples are kept
+        let samples = &context.headset_data.as_ref().unwrap()["T3"];
+        assert_eq!(samples.len(), WINDOW_SAMPLES);
+        assert_eq!(samples[0], (9 * WINDOW_SAMPLES) as f32);
+        assert_eq!(samples[WINDOW_SAMPLES - 1], (10 * WINDOW_SAMPLES - 1) as f32);
+        assert_eq!(context.color_thinking.len(), 3);
+
+        let notifications = context.take_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].name, MemoryPressureEvent::NAME);
+    }
+
+    #[test]
+    fn test_webhook_prediction_is_forgotten_once_the_prediction_moves_away() {
+        fn predict(context: &mut NeuralAnalyticsContext, color: &str) {
+            context.color_thinking.clear();
+            let prediction = ReceivedPredictColorThinkingDataEvent {
+                color_thinking: color.to_string(),
+                window_id: 1,
+                window_hash: 1,
+                is_cached: false,
+                inference_ms: None,
+                prediction: None,
+            };
+            futures::executor::block_on(context.write(&Event::serialize(prediction).unwrap())).unwrap();
+        }
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.last_webhook_prediction = Some("green".to_string());
+        predict(&mut context, "green");
+        assert_eq!(context.last_webhook_prediction.as_deref(), Some("green"));
+
+        // Once the prediction moved to red, green is notified again when it comes back
+        predict(&mut context, "red");
+        assert_eq!(context.last_webhook_prediction, None);
+    }
+
+    #[test]
+    fn test_identify_firmware_warns_on_untested_versions() {
+        let mut context = NeuralAnalyticsContext::default();
+
+        context.identify_firmware(None);
+        context.identify_firmware(Some(TESTED_FIRMWARE_VERSIONS[0].to_string()));
+        assert!(context.take_notifications().is_empty());
+
+        context.identify_firmware(Some("0.9.0".to_string()));
+        let notifications = context.take_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].name, UntestedFirmwareEvent::NAME);
+        assert_eq!(notifications[0].data.firmware_version.as_deref(), Some("0.9.0"));
+
+        context.begin_session();
+        let events = context.take_lifecycle_events();
+        assert_eq!(events[0].firmware_version.as_deref(), Some("0.9.0"));
+    }
+
+    #[test]
+    fn test_observe_bulb_connection_notifies_changes_only() {
+        let mut context = NeuralAnalyticsContext::default();
+        let connecting = BulbConnectionState::Connecting { attempt: 1, max_attempts: 3 };
+
+        context.observe_bulb_connection(connecting.clone());
+        context.observe_bulb_connection(connecting.clone());
+        context.observe_bulb_connection(BulbConnectionState::Unavailable { reason: "timeout".to_string() });
+
+        let notifications = context.take_notifications();
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].name, BulbConnectionStateEvent::NAME);
+        assert_eq!(notifications[0].data.bulb_connection, Some(connecting));
+    }
+
+    #[test]
+    fn test_identify_headset_rotates_session_on_swap() {
+        let mut context = NeuralAnalyticsContext::default();
+        context.color_thinking.push_back("green".to_string());
+
+        context.identify_headset(Some("AA:BB".to_string()));
+        context.identify_headset(None);
+        context.identify_headset(Some("AA:BB".to_string()));
+        assert_eq!(context.session_id, 0);
+        assert!(context.take_notifications().is_empty());
+
+        context.identify_headset(Some("CC:DD".to_string()));
+        let notifications = context.take_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].name, SessionRotatedEvent::NAME);
+        assert_eq!(notifications[0].data.session_id, Some(1));
+        assert_eq!(
+            notifications[0].data.session_rotation_reason.as_deref(),
+            Some("Headset changed from AA:BB to CC:DD")
+        );
+        assert!(context.color_thinking.is_empty());
+    }
+
+    #[test]
+    fn test_apply_bulb_actuation_links_only_first_actuation_to_prediction() {
+        let mut context = NeuralAnalyticsContext::default();
+        context.color_thinking.push_back("green".to_string());
+        context.window_timestamp = Some(1_000);
+        context.predicted_window_id = Some(7);
+        context.prediction_timestamp = Some(1_100);
+
+        let actuation = |desired, confirmed| BulbStateChangedEvent {
+            desired,
+            confirmed,
+            latency_ms: 20,
+            is_simulated: true,
+            error: None,
+        };
+
+        context.apply_bulb_actuation(actuation(BulbState::BulbOn, true));
+        context.apply_bulb_actuation(BulbStateChangedEvent {
+            error: Some("Bulb unreachable".to_string()),
+            ..actuation(BulbState::BulbOff, false)
+        });
+
+        let notifications = context.take_notifications();
+        assert_eq!(notifications.len(), 2);
+        assert!(notifications.iter().all(|notification| notification.name == BulbStateChangedEvent::NAME));
+        assert_eq!(notifications[0].data.window_id, Some(7));
+        assert!(notifications[1].data.causality.is_none());
+
+        // The failed attempt is reported, but the context keeps the confirmed state
+        assert_eq!(notifications[1].data.is_bulb_confirmed, Some(false));
+        assert_eq!(context.bulb_state, Some(BulbState::BulbOn));
+        assert!(context.take_notifications().is_empty());
+
+        // Both attempts are journaled, the failed one with its error
+        let kinds: Vec<_> = context.journal.tail().iter().map(|entry| entry.kind.as_str()).collect();
+        assert_eq!(kinds, ["actuation", "actuation_failed"]);
+        let failed = context.journal.tail().back().unwrap();
+        assert_eq!(failed.payload["error"], "Bulb unreachable");
+    }
+
+    #[test]
+    fn test_act_on_prediction_notifies_the_prediction_with_its_actuation() {
+        let mut context = NeuralAnalyticsContext {
+            window_timestamp: Some(1_000),
+            predicted_window_id: Some(7),
+            prediction_timestamp: Some(1_100),
+            is_prediction_pending: true,
+            ..Default::default()
+        };
+        context.color_thinking.push_back("green".to_string());
+
+        context.act_on_prediction(Some(BulbStateChangedEvent {
+            desired: BulbState::BulbOn,
+            confirmed: true,
+            latency_ms: 20,
+            is_simulated: false,
+            error: None,
+        }));
+        context.act_on_prediction(None);
+
+        let notifications = context.take_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].name, PredictionActedEvent::NAME);
+        assert_eq!(notifications[0].data.color_thinking.as_deref(), Some("green"));
+        assert_eq!(notifications[0].data.bulb_state, Some(BulbState::BulbOn));
+        assert_eq!(notifications[0].data.causality.as_ref().map(|record| record.window_id), Some(7));
+        assert_eq!(context.bulb_state, Some(BulbState::BulbOn));
+    }
+
+    #[test]
+    fn test_remember_calibration_needs_identified_headset() {
+        let mut context = NeuralAnalyticsContext::default();
+        let impedance_data = [("T3".to_string(), 200), ("O1".to_string(), 400)].into_iter().collect();
+        let thresholds = CalibrationThresholds::default();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        context.remember_calibration(&impedance_data);
+        context.identify_headset(Some("AA:BB".to_string()));
+        assert!(context.calibration_profiles.find_reusable("AA:BB", None, &thresholds, now).is_none());
+
+        context.remember_calibration(&impedance_data);
+        let profile = context.calibration_profiles.find_reusable("AA:BB", None, &thresholds, now);
+        assert_eq!(profile.map(|profile| profile.max_impedance), Some(400));
+    }
+
+    #[test]
+    fn test_session_summary_follows_the_session_lifecycle() {
+        let mut context = NeuralAnalyticsContext::default();
+        context.change_user_profile("alice");
+        context.begin_session();
+        context.apply_bulb_actuation(BulbStateChangedEvent {
+            desired: BulbState::BulbOn,
+            confirmed: true,
+            latency_ms: 20,
+            is_simulated: true,
+            error: None,
+        });
+
+        // A new user splits the summary, the headset stays connected
+        context.change_user_profile("bob");
+        context.end_session();
+        context.end_session();
+
+        let events = context.take_lifecycle_events();
+        let kinds: Vec<_> = events.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                LifecycleEventKind::SessionStarted,
+                LifecycleEventKind::SummaryReady,
+                LifecycleEventKind::SessionStarted,
+                LifecycleEventKind::Disconnected,
+                LifecycleEventKind::SummaryReady,
+            ]
+        );
+        assert_eq!(events[1].user_profile.as_deref(), Some("alice"));
+        assert_eq!(events[1].summary.as_ref().map(|summary| summary.actuations), Some(1));
+        assert_eq!(events[4].session_id, 1);
+        assert_eq!(events[4].summary.as_ref().map(|summary| summary.actuations), Some(0));
+    }
+
+    #[test]
+    fn test_close_for_shutdown_reports_the_open_session() {
+        let mut context = NeuralAnalyticsContext::default();
+        context.start_explicit_session(None).unwrap();
+        context.begin_session();
+        context.take_lifecycle_events();
+
+        context.close_for_shutdown();
+
+        let kinds: Vec<_> = context.take_lifecycle_events().iter().map(|event| event.kind).collect();
+        assert_eq!(kinds, [LifecycleEventKind::Disconnected, LifecycleEventKind::SummaryReady]);
+        assert!(!context.is_session_explicit);
+        assert_eq!(context.journal.tail().back().map(|entry| entry.kind.as_str()), Some("core_shutdown"));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_calibration_validates_the_reading() {
+        let mut context = NeuralAnalyticsContext::default();
+        let impedance_data = [("T3".to_string(), 200), ("O1".to_string(), 400)].into_iter().collect();
+
+        let snapshot = context.snapshot_calibration(&impedance_data).await.unwrap();
+        assert_eq!(snapshot.unit, ImpedanceUnit::Kiloohms);
+        assert_eq!(snapshot.impedances["O1"], 400.0);
+
+        assert!(context.snapshot_calibration(&HashMap::new()).await.is_none());
+        assert_eq!(context.metrics.counter("calibration.invalid_snapshots"), 1);
+    }
+
+    #[test]
+    fn test_session_summary_carries_the_experiment_config() {
+        let mut context = NeuralAnalyticsContext::new(CoreConfig {
+            strict_mode: true,
+            ..Default::default()
+        });
+
+        // Thresholds replaced by the state machine apply to the next sessions only
+        context.begin_session();
+        *context.calibration_thresholds.write().unwrap() = CalibrationThresholds {
+            min_impedance: 5,
+            max_impedance: 800,
+        };
+        context.rotate_session("Test".to_string());
+        context.end_session();
+
+        let experiments: Vec<_> = context
+            .take_lifecycle_events()
+            .into_iter()
+            .filter_map(|event| event.summary.and_then(|summary| summary.experiment))
+            .collect();
+        assert_eq!(experiments.len(), 2);
+        assert_eq!(experiments[0].calibration_thresholds, CalibrationThresholds::default());
+        assert_eq!(experiments[1].session_id, 1);
+        assert_eq!(experiments[1].calibration_thresholds.max_impedance, 800);
+        assert!(experiments[1].config.strict_mode);
+
+        let journaled = context.journal.tail().iter().filter(|entry| entry.kind == "experiment_config").count();
+        assert_eq!(journaled, 2);
+    }
+
+    #[test]
+    fn test_change_user_profile_rotates_session_on_change() {
+        let mut context = NeuralAnalyticsContext::default();
+
+        context.change_user_profile("alice");
+        context.change_user_profile("alice");
+        assert_eq!(context.session_id, 0);
+
+        context.change_user_profile("bob");
+        assert_eq!(context.session_id, 1);
+        assert_eq!(context.user_profile.as_deref(), Some("bob"));
+        assert_eq!(context.metrics.counter("sessions.rotated"), 1);
+    }
+}
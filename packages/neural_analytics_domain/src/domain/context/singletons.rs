@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use log::warn;
+
+use super::adapter_registry::{
+    AdapterRegistry, SharedAdapter, DEFAULT_ADAPTER, IMPEDANCE_CAPABILITY, SIMULATED_CAPABILITY,
+};
+use crate::domain::{
+    models::{
+        bulb_state::BulbState, eeg_work_modes::WorkMode, lifecycle_event::LifecycleEvent,
+        published_prediction::PublishedPrediction, signal_unit::SignalUnit, webhook_notification::WebhookNotification,
+    },
+    ports::{
+        input::eeg_headset::EegHeadsetPort,
+        output::{
+            bundle_archive::BundleArchivePort, lifecycle_notifier::LifecycleNotifierPort,
+            prediction_sink::PredictionSinkPort, prediction_webhook::PredictionWebhookPort, recorder::RecorderPort,
+            smart_bulb::SmartBulbPort,
+        },
+    },
+    services::model_inference_service::{ModelInferenceInterface, ModelInferenceService},
+};
+
+// The adapters of a single device setup, registered under `DEFAULT_ADAPTER` in the adapter registry
+// of a core, or in the standalone registry for the contexts created outside a core
+
+/// Function to get the model service singleton
+///
+/// # Returns
+/// * `SharedAdapter<dyn ModelInferenceInterface + Send + Sync>`: The model service singleton, shared with the registry.
+pub fn get_model_service(registry: &AdapterRegistry) -> SharedAdapter<dyn ModelInferenceInterface + Send + Sync> {
+    registry.get_or_register::<dyn ModelInferenceInterface + Send + Sync>(DEFAULT_ADAPTER, || Box::new(ModelInferenceService::default()))
+}
+
+/// Function to register the model service, replacing the ONNX model of the assets
+///
+/// # Returns
+/// * `Result<(), String>`: An error if a model service was already registered or used.
+pub fn set_model_service(registry: &AdapterRegistry, service: Box<dyn ModelInferenceInterface + Send + Sync>) -> Result<(), String> {
+    registry.register(DEFAULT_ADAPTER, service).map(|_| ())
+}
+
+/// Function to register the EEG headset adapter provided by the composition crate
+///
+/// Tagged with the `impedance` capability when it measures the contact of the electrodes.
+///
+/// # Returns
+/// * `Result<(), String>`: An error if an adapter was already registered or used.
+pub fn set_eeg_headset_adapter(registry: &AdapterRegistry, adapter: Box<dyn EegHeadsetPort + Send + Sync>) -> Result<(), String> {
+    let capabilities: &[&str] = if adapter.supports_impedance() { &[IMPEDANCE_CAPABILITY] } else { &[] };
+
+    registry.register_with_capabilities(DEFAULT_ADAPTER, adapter, capabilities).map(|_| ())
+}
+
+/// Function to register the smart bulb adapter provided by the composition crate
+///
+/// Tagged with the `simulated` capability when there is no physical bulb behind it.
+///
+/// # Returns
+/// * `Result<(), String>`: An error if an adapter was already registered or used.
+pub fn set_smart_bulb_adapter(registry: &AdapterRegistry, adapter: Box<dyn SmartBulbPort + Send + Sync>) -> Result<(), String> {
+    let capabilities: &[&str] = if adapter.is_simulated() { &[SIMULATED_CAPABILITY] } else { &[] };
+
+    registry.register_with_capabilities(DEFAULT_ADAPTER, adapter, capabilities).map(|_| ())
+}
+
+/// Function to register the lifecycle notifier adapter provided by the composition crate
+///
+/// # Returns
+/// * `Result<(), String>`: An error if an adapter was already registered or used.
+pub fn set_lifecycle_notifier_adapter(registry: &AdapterRegistry, adapter: Box<dyn LifecycleNotifierPort + Send + Sync>) -> Result<(), String> {
+    registry.register(DEFAULT_ADAPTER, adapter).map(|_| ())
+}
+
+/// Function to register the prediction sink adapter provided by the composition crate
+///
+/// # Returns
+/// * `Result<(), String>`: An error if an adapter was already registered or used.
+pub fn set_prediction_sink_adapter(registry: &AdapterRegistry, adapter: Box<dyn PredictionSinkPort + Send + Sync>) -> Result<(), String> {
+    registry.register(DEFAULT_ADAPTER, adapter).map(|_| ())
+}
+
+/// Function to register the prediction webhook adapter provided by the composition crate
+///
+/// # Returns
+/// * `Result<(), String>`: An error if an adapter was already registered or used.
+pub fn set_prediction_webhook_adapter(registry: &AdapterRegistry, adapter: Box<dyn PredictionWebhookPort + Send + Sync>) -> Result<(), String> {
+    registry.register(DEFAULT_ADAPTER, adapter).map(|_| ())
+}
+
+/// Function to register the archive of the diagnostic bundles provided by the composition crate
+///
+/// # Returns
+/// * `Result<(), String>`: An error if an adapter was already registered or used.
+pub fn set_bundle_archive_adapter(registry: &AdapterRegistry, adapter: Box<dyn BundleArchivePort + Send + Sync>) -> Result<(), String> {
+    registry.register(DEFAULT_ADAPTER, adapter).map(|_| ())
+}
+
+/// Function to get the EEG headset adapter singleton
+///
+/// Falls back to an adapter that never connects when none was registered.
+///
+/// # Returns
+/// * `SharedAdapter<dyn EegHeadsetPort + Send + Sync>`: The EEG headset adapter singleton, shared with the registry.
+pub fn get_eeg_headset_adapter(registry: &AdapterRegistry) -> SharedAdapter<dyn EegHeadsetPort + Send + Sync> {
+    registry.get_or_register::<dyn EegHeadsetPort + Send + Sync>(DEFAULT_ADAPTER, || {
+        warn!("No EEG headset adapter registered, using an unavailable one");
+        Box::new(UnavailableAdapter)
+    })
+}
+
+/// Function to get the smart bulb adapter singleton
+///
+/// Falls back to an adapter that rejects every actuation when none was registered.
+///
+/// # Returns
+/// * `SharedAdapter<dyn SmartBulbPort + Send + Sync>`: The smart bulb adapter singleton, shared with the registry.
+pub fn get_smart_bulb_adapter(registry: &AdapterRegistry) -> SharedAdapter<dyn SmartBulbPort + Send + Sync> {
+    registry.get_or_register::<dyn SmartBulbPort + Send + Sync>(DEFAULT_ADAPTER, || {
+        warn!("No smart bulb adapter registered, using an unavailable one");
+        Box::new(UnavailableAdapter)
+    })
+}
+
+/// Function to get the lifecycle notifier adapter singleton
+///
+/// Falls back to an adapter that drops every event when none was registered,
+/// as reporting the lifecycle is optional.
+///
+/// # Returns
+/// * `SharedAdapter<dyn LifecycleNotifierPort + Send + Sync>`: The lifecycle notifier adapter singleton, shared with the registry.
+pub fn get_lifecycle_notifier_adapter(registry: &AdapterRegistry) -> SharedAdapter<dyn LifecycleNotifierPort + Send + Sync> {
+    registry.get_or_register::<dyn LifecycleNotifierPort + Send + Sync>(DEFAULT_ADAPTER, || Box::new(UnavailableAdapter))
+}
+
+/// Function to get the prediction sink adapter singleton
+///
+/// Falls back to an adapter that drops every prediction when none was registered,
+/// as publishing the predictions is optional.
+///
+/// # Returns
+/// * `SharedAdapter<dyn PredictionSinkPort + Send + Sync>`: The prediction sink adapter singleton, shared with the registry.
+pub fn get_prediction_sink_adapter(registry: &AdapterRegistry) -> SharedAdapter<dyn PredictionSinkPort + Send + Sync> {
+    registry.get_or_register::<dyn PredictionSinkPort + Send + Sync>(DEFAULT_ADAPTER, || Box::new(UnavailableAdapter))
+}
+
+/// Function to get the prediction webhook adapter singleton
+///
+/// Falls back to an adapter that rejects every notification when none was registered,
+/// as the configuration asked for a webhook that cannot be called.
+///
+/// # Returns
+/// * `SharedAdapter<dyn PredictionWebhookPort + Send + Sync>`: The prediction webhook adapter singleton, shared with the registry.
+pub fn get_prediction_webhook_adapter(registry: &AdapterRegistry) -> SharedAdapter<dyn PredictionWebhookPort + Send + Sync> {
+    registry.get_or_register::<dyn PredictionWebhookPort + Send + Sync>(DEFAULT_ADAPTER, || Box::new(UnavailableAdapter))
+}
+
+/// Function to get the archive of the diagnostic bundles singleton
+///
+/// Falls back to an adapter that rejects every bundle when none was registered.
+///
+/// # Returns
+/// * `SharedAdapter<dyn BundleArchivePort + Send + Sync>`: The bundle archive adapter singleton, shared with the registry.
+pub fn get_bundle_archive_adapter(registry: &AdapterRegistry) -> SharedAdapter<dyn BundleArchivePort + Send + Sync> {
+    registry.get_or_register::<dyn BundleArchivePort + Send + Sync>(DEFAULT_ADAPTER, || Box::new(UnavailableAdapter))
+}
+
+/// Placeholder used when the composition crate did not register an adapter
+pub(crate) struct UnavailableAdapter;
+
+impl EegHeadsetPort for UnavailableAdapter {
+    fn connect(&self) -> Result<(), String> {
+        Err("No EEG headset adapter registered".to_string())
+    }
+
+    fn disconnect(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        false
+    }
+
+    fn get_work_mode(&self) -> WorkMode {
+        WorkMode::Initialized
+    }
+
+    fn change_work_mode(&mut self, _mode: WorkMode) {}
+
+    fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String> {
+        Err("No EEG headset adapter registered".to_string())
+    }
+
+    fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String> {
+        Err("No EEG headset adapter registered".to_string())
+    }
+}
+
+#[async_trait]
+impl SmartBulbPort for UnavailableAdapter {
+    async fn change_state(&self, _state: BulbState) -> Result<(), String> {
+        Err("No smart bulb adapter registered".to_string())
+    }
+}
+
+#[async_trait]
+impl LifecycleNotifierPort for UnavailableAdapter {
+    async fn notify(&self, _event: &LifecycleEvent) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PredictionSinkPort for UnavailableAdapter {
+    async fn publish(&self, _prediction: &PublishedPrediction) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PredictionWebhookPort for UnavailableAdapter {
+    async fn notify(&self, _url: &str, _notification: &WebhookNotification) -> Result<(), String> {
+        Err("No prediction webhook adapter registered".to_string())
+    }
+}
+
+impl BundleArchivePort for UnavailableAdapter {
+    fn write(&self, _path: &str, _files: &[(&str, String)]) -> Result<(), String> {
+        Err("No bundle archive adapter registered".to_string())
+    }
+}
+
+impl RecorderPort for UnavailableAdapter {
+    fn start(&mut self, _path: &str) -> Result<(), String> {
+        Err("No recorder adapter registered".to_string())
+    }
+
+    fn start_session(&mut self, _session_id: u64) -> Option<String> {
+        None
+    }
+
+    fn end_session(&mut self) {}
+
+    fn rotate(&mut self, _session_id: u64) -> Result<Option<String>, String> {
+        Ok(None)
+    }
+
+    fn stop(&mut self) -> Option<String> {
+        None
+    }
+
+    fn path(&self) -> Option<&str> {
+        None
+    }
+
+    fn set_unit(&mut self, _unit: SignalUnit) {}
+
+    fn record_window(&mut self, _window_id: u64, _headset_data: &HashMap<String, Vec<f32>>, _sample_timestamp: Option<i64>) {}
+}
@@ -0,0 +1,11 @@
+use crate::domain::models::bulb_connection::BulbConnectionState;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct BulbConnectionStateEvent {
+    /// State the smart bulb adapter reported, sent each time it changes.
+    pub connection_state: BulbConnectionState,
+}
+
+impl presage::Event for BulbConnectionStateEvent {
+    const NAME: &'static str = "bulb-connection-state";
+}
@@ -0,0 +1,16 @@
+use crate::domain::models::bulb_state::BulbState;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BulbStateChangedEvent {
+    pub desired: BulbState,
+    pub confirmed: bool,
+    pub latency_ms: u64,
+    pub is_simulated: bool,
+    /// Why the bulb did not confirm the actuation, `None` when it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl presage::Event for BulbStateChangedEvent {
+    const NAME: &'static str = "bulb-state-changed";
+}
\ No newline at end of file
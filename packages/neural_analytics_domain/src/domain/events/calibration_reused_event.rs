@@ -0,0 +1,10 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CalibrationReusedEvent {
+    /// Unix epoch milliseconds when the reused calibration finished.
+    pub calibration_timestamp: i64,
+    pub max_impedance: u16,
+}
+
+impl presage::Event for CalibrationReusedEvent {
+    const NAME: &'static str = "calibration-reused";
+}
@@ -0,0 +1,9 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ClockDriftWarningEvent {
+    /// Host time elapsed minus board time elapsed since the session started, in milliseconds.
+    pub drift_ms: f64,
+}
+
+impl presage::Event for ClockDriftWarningEvent {
+    const NAME: &'static str = "clock-drift-warning";
+}
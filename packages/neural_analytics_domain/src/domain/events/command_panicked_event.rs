@@ -0,0 +1,10 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CommandPanickedEvent {
+    /// Name of the command whose handler panicked.
+    pub command: String,
+    pub message: String,
+}
+
+impl presage::Event for CommandPanickedEvent {
+    const NAME: &'static str = "command-panicked";
+}
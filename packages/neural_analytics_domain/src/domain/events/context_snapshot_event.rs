@@ -0,0 +1,10 @@
+use crate::domain::models::context_snapshot::ContextSnapshot;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ContextSnapshotEvent {
+    pub context_snapshot: ContextSnapshot,
+}
+
+impl presage::Event for ContextSnapshotEvent {
+    const NAME: &'static str = "context-snapshot";
+}
@@ -0,0 +1,11 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct DiagnosticBundleCreatedEvent {
+    /// Zip archive the bundle was written to.
+    pub bundle_path: String,
+    /// Why the bundle could not be written, `None` when it was.
+    pub error_message: Option<String>,
+}
+
+impl presage::Event for DiagnosticBundleCreatedEvent {
+    const NAME: &'static str = "diagnostic-bundle-created";
+}
@@ -0,0 +1,11 @@
+use crate::domain::models::exhibition::ExhibitionCue;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ExhibitionCueEvent {
+    /// Change of the prediction shown in exhibition mode, held for the countdown of the overlay.
+    pub cue: ExhibitionCue,
+}
+
+impl presage::Event for ExhibitionCueEvent {
+    const NAME: &'static str = "exhibition-cue";
+}
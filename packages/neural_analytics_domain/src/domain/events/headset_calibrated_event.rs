@@ -0,0 +1,12 @@
+use crate::domain::models::impedance_snapshot::ImpedanceSnapshot;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct HeadsetCalibratedEvent {
+    /// Final impedance of the electrodes, `None` when the calibration was skipped, reused
+    /// or checked on the quality of the signal.
+    pub impedance: Option<ImpedanceSnapshot>,
+}
+
+impl presage::Event for HeadsetCalibratedEvent {
+    const NAME: &'static str = "headset-calibrated";
+}
\ No newline at end of file
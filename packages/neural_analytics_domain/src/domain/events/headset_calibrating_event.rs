@@ -0,0 +1,14 @@
+use std::collections::HashMap;
+
+use crate::domain::models::signal_quality::SignalQuality;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct HeadsetCalibratingEvent {
+    pub impedance_data: Option<HashMap<String, u16>>,
+    /// Reported instead of the impedance by the headsets that cannot measure it.
+    pub signal_quality: Option<HashMap<String, SignalQuality>>,
+}
+
+impl presage::Event for HeadsetCalibratingEvent {
+    const NAME: &'static str = "headset-calibrating";
+}
\ No newline at end of file
@@ -0,0 +1,10 @@
+use crate::domain::models::core_health::CoreHealth;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct HeartbeatEvent {
+    pub core_health: CoreHealth,
+}
+
+impl presage::Event for HeartbeatEvent {
+    const NAME: &'static str = "heartbeat";
+}
\ No newline at end of file
@@ -0,0 +1,11 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct InferencePausedEvent {
+    /// Consecutive garbage predictions that paused the inference.
+    pub streak: u64,
+    /// Window of the last of them.
+    pub window_id: u64,
+}
+
+impl presage::Event for InferencePausedEvent {
+    const NAME: &'static str = "inference-paused";
+}
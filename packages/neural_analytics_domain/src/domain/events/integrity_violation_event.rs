@@ -0,0 +1,11 @@
+use crate::domain::models::integrity_report::IntegrityReport;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct IntegrityViolationEvent {
+    /// Violation that aborted the session in strict mode, with where it happened.
+    pub report: IntegrityReport,
+}
+
+impl presage::Event for IntegrityViolationEvent {
+    const NAME: &'static str = "integrity-violation";
+}
@@ -0,0 +1,11 @@
+use std::collections::HashMap;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MemoryPressureEvent {
+    pub buffer_usage: HashMap<String, usize>,
+    pub budget_bytes: usize,
+}
+
+impl presage::Event for MemoryPressureEvent {
+    const NAME: &'static str = "memory-pressure";
+}
\ No newline at end of file
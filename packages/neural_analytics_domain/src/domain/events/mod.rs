@@ -0,0 +1,180 @@
+use presage::Event;
+
+use super::models::{event_routing::EventSeverity, ui_hint::UiHint};
+use super::state_machine::state_machine::State;
+
+pub mod bulb_connection_state_event;
+pub mod bulb_state_changed_event;
+pub mod calibration_reused_event;
+pub mod clock_drift_warning_event;
+pub mod command_panicked_event;
+pub mod context_snapshot_event;
+pub mod diagnostic_bundle_created_event;
+pub mod exhibition_cue_event;
+pub mod headset_calibrated_event;
+pub mod headset_calibrating_event;
+pub mod headset_connected_event;
+pub mod headset_disconnected_event;
+pub mod heartbeat_event;
+pub mod inference_paused_event;
+pub mod initialized_core_event;
+pub mod integrity_violation_event;
+pub mod memory_pressure_event;
+pub mod model_reloaded_event;
+pub mod operation_progress_event;
+pub mod prediction_acted_event;
+pub mod prediction_explanation_event;
+pub mod session_rotated_event;
+pub mod stale_data_skipped_event;
+pub mod suggested_thresholds_event;
+pub mod test_signal_verified_event;
+pub mod untested_firmware_event;
+
+#[derive(Debug)]
+pub enum NeuralAnalyticsEvents {
+    HeadsetConnectedEvent,
+    HeadsetDisconnectedEvent,
+    HeadsetCalibratingEvent,
+    HeadsetCalibratedEvent,
+    PredictionActedEvent,
+    InitializedCoreEvent,
+    BulbStateChangedEvent,
+    MemoryPressureEvent,
+    HeartbeatEvent,
+    ModelReloadedEvent,
+    SuggestedThresholdsEvent,
+    ContextSnapshotEvent,
+    TestSignalVerifiedEvent,
+    PredictionExplanationEvent,
+    SessionRotatedEvent,
+    CalibrationReusedEvent,
+    ClockDriftWarningEvent,
+    OperationProgressEvent,
+    CommandPanickedEvent,
+    UntestedFirmwareEvent,
+    BulbConnectionStateEvent,
+    IntegrityViolationEvent,
+    DiagnosticBundleCreatedEvent,
+    StaleDataSkippedEvent,
+    InferencePausedEvent,
+    ExhibitionCueEvent,
+}
+
+impl NeuralAnalyticsEvents {
+    pub fn to_string(&self) -> String {
+        match self {
+            NeuralAnalyticsEvents::HeadsetConnectedEvent => headset_connected_event::HeadsetConnectedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::HeadsetDisconnectedEvent => headset_disconnected_event::HeadsetDisconnectedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::HeadsetCalibratingEvent => headset_calibrating_event::HeadsetCalibratingEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::HeadsetCalibratedEvent => headset_calibrated_event::HeadsetCalibratedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::PredictionActedEvent => prediction_acted_event::PredictionActedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::InitializedCoreEvent => initialized_core_event::InitializedCoreEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::BulbStateChangedEvent => bulb_state_changed_event::BulbStateChangedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::MemoryPressureEvent => memory_pressure_event::MemoryPressureEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::HeartbeatEvent => heartbeat_event::HeartbeatEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::ModelReloadedEvent => model_reloaded_event::ModelReloadedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::SuggestedThresholdsEvent => suggested_thresholds_event::SuggestedThresholdsEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::ContextSnapshotEvent => context_snapshot_event::ContextSnapshotEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::TestSignalVerifiedEvent => test_signal_verified_event::TestSignalVerifiedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::PredictionExplanationEvent => prediction_explanation_event::PredictionExplanationEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::SessionRotatedEvent => session_rotated_event::SessionRotatedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::CalibrationReusedEvent => calibration_reused_event::CalibrationReusedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::ClockDriftWarningEvent => clock_drift_warning_event::ClockDriftWarningEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::OperationProgressEvent => operation_progress_event::OperationProgressEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::CommandPanickedEvent => command_panicked_event::CommandPanickedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::UntestedFirmwareEvent => untested_firmware_event::UntestedFirmwareEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::BulbConnectionStateEvent => bulb_connection_state_event::BulbConnectionStateEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::IntegrityViolationEvent => integrity_violation_event::IntegrityViolationEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::DiagnosticBundleCreatedEvent => diagnostic_bundle_created_event::DiagnosticBundleCreatedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::StaleDataSkippedEvent => stale_data_skipped_event::StaleDataSkippedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::InferencePausedEvent => inference_paused_event::InferencePausedEvent::NAME.to_string(),
+            NeuralAnalyticsEvents::ExhibitionCueEvent => exhibition_cue_event::ExhibitionCueEvent::NAME.to_string(),
+        }
+    }
+
+    /// Severity of the event, deciding the destinations it is routed to.
+    pub fn severity(&self) -> EventSeverity {
+        match self {
+            NeuralAnalyticsEvents::CommandPanickedEvent
+            | NeuralAnalyticsEvents::IntegrityViolationEvent => EventSeverity::Critical,
+            NeuralAnalyticsEvents::HeadsetDisconnectedEvent
+            | NeuralAnalyticsEvents::MemoryPressureEvent
+            | NeuralAnalyticsEvents::ClockDriftWarningEvent
+            | NeuralAnalyticsEvents::StaleDataSkippedEvent
+            | NeuralAnalyticsEvents::InferencePausedEvent
+            | NeuralAnalyticsEvents::SuggestedThresholdsEvent
+            | NeuralAnalyticsEvents::UntestedFirmwareEvent
+            | NeuralAnalyticsEvents::BulbConnectionStateEvent => EventSeverity::Warning,
+            _ => EventSeverity::Info,
+        }
+    }
+
+    /// State the core enters when sending the event, for the events of a state transition.
+    pub(crate) fn entered_state(&self) -> Option<State> {
+        match self {
+            NeuralAnalyticsEvents::InitializedCoreEvent
+            | NeuralAnalyticsEvents::HeadsetDisconnectedEvent => Some(State::AwaitingHeadsetConnection {}),
+            NeuralAnalyticsEvents::HeadsetConnectedEvent => Some(State::AwaitingHeadsetCalibration {}),
+            NeuralAnalyticsEvents::HeadsetCalibratedEvent => Some(State::CapturingHeadsetData {}),
+            _ => None,
+        }
+    }
+
+    /// Presentation hints of the state the event enters, `None` for the events within a state.
+    pub fn ui_hint(&self) -> Option<UiHint> {
+        self.entered_state().map(|state| state.ui_hint())
+    }
+
+    pub fn from_string(event_name: &str) -> Option<Self> {
+        match event_name {
+            headset_connected_event::HeadsetConnectedEvent::NAME => Some(NeuralAnalyticsEvents::HeadsetConnectedEvent),
+            headset_disconnected_event::HeadsetDisconnectedEvent::NAME => Some(NeuralAnalyticsEvents::HeadsetDisconnectedEvent),
+            headset_calibrating_event::HeadsetCalibratingEvent::NAME => Some(NeuralAnalyticsEvents::HeadsetCalibratingEvent),
+            headset_calibrated_event::HeadsetCalibratedEvent::NAME => Some(NeuralAnalyticsEvents::HeadsetCalibratedEvent),
+            prediction_acted_event::PredictionActedEvent::NAME => Some(NeuralAnalyticsEvents::PredictionActedEvent),
+            initialized_core_event::InitializedCoreEvent::NAME => Some(NeuralAnalyticsEvents::InitializedCoreEvent),
+            bulb_state_changed_event::BulbStateChangedEvent::NAME => Some(NeuralAnalyticsEvents::BulbStateChangedEvent),
+            memory_pressure_event::MemoryPressureEvent::NAME => Some(NeuralAnalyticsEvents::MemoryPressureEvent),
+            heartbeat_event::HeartbeatEvent::NAME => Some(NeuralAnalyticsEvents::HeartbeatEvent),
+            model_reloaded_event::ModelReloadedEvent::NAME => Some(NeuralAnalyticsEvents::ModelReloadedEvent),
+            suggested_thresholds_event::SuggestedThresholdsEvent::NAME => Some(NeuralAnalyticsEvents::SuggestedThresholdsEvent),
+            context_snapshot_event::ContextSnapshotEvent::NAME => Some(NeuralAnalyticsEvents::ContextSnapshotEvent),
+            test_signal_verified_event::TestSignalVerifiedEvent::NAME => Some(NeuralAnalyticsEvents::TestSignalVerifiedEvent),
+            prediction_explanation_event::PredictionExplanationEvent::NAME => Some(NeuralAnalyticsEvents::PredictionExplanationEvent),
+            session_rotated_event::SessionRotatedEvent::NAME => Some(NeuralAnalyticsEvents::SessionRotatedEvent),
+            calibration_reused_event::CalibrationReusedEvent::NAME => Some(NeuralAnalyticsEvents::CalibrationReusedEvent),
+            clock_drift_warning_event::ClockDriftWarningEvent::NAME => Some(NeuralAnalyticsEvents::ClockDriftWarningEvent),
+            operation_progress_event::OperationProgressEvent::NAME => Some(NeuralAnalyticsEvents::OperationProgressEvent),
+            command_panicked_event::CommandPanickedEvent::NAME => Some(NeuralAnalyticsEvents::CommandPanickedEvent),
+            untested_firmware_event::UntestedFirmwareEvent::NAME => Some(NeuralAnalyticsEvents::UntestedFirmwareEvent),
+            bulb_connection_state_event::BulbConnectionStateEvent::NAME => Some(NeuralAnalyticsEvents::BulbConnectionStateEvent),
+            integrity_violation_event::IntegrityViolationEvent::NAME => Some(NeuralAnalyticsEvents::IntegrityViolationEvent),
+            diagnostic_bundle_created_event::DiagnosticBundleCreatedEvent::NAME => Some(NeuralAnalyticsEvents::DiagnosticBundleCreatedEvent),
+            stale_data_skipped_event::StaleDataSkippedEvent::NAME => Some(NeuralAnalyticsEvents::StaleDataSkippedEvent),
+            inference_paused_event::InferencePausedEvent::NAME => Some(NeuralAnalyticsEvents::InferencePausedEvent),
+            exhibition_cue_event::ExhibitionCueEvent::NAME => Some(NeuralAnalyticsEvents::ExhibitionCueEvent),
+            _ => None,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::ui_hint::SuggestedView;
+
+    #[test]
+    fn test_transition_events_suggest_the_view_of_the_entered_state() {
+        let suggested_view = |event: NeuralAnalyticsEvents| event.ui_hint().map(|hint| hint.suggested_view);
+
+        assert_eq!(suggested_view(NeuralAnalyticsEvents::InitializedCoreEvent), Some(SuggestedView::Welcome));
+        assert_eq!(suggested_view(NeuralAnalyticsEvents::HeadsetConnectedEvent), Some(SuggestedView::Calibration));
+        assert_eq!(suggested_view(NeuralAnalyticsEvents::HeadsetDisconnectedEvent), Some(SuggestedView::Welcome));
+        assert_eq!(suggested_view(NeuralAnalyticsEvents::HeadsetCalibratedEvent), Some(SuggestedView::Capture));
+        assert_eq!(suggested_view(NeuralAnalyticsEvents::HeartbeatEvent), None);
+
+        // The headset search is bounded by the connection timeout of BrainFlow
+        let searching = NeuralAnalyticsEvents::HeadsetDisconnectedEvent.ui_hint().unwrap();
+        assert!(searching.shows_spinner);
+        assert!(searching.expected_duration_ms.is_some());
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ModelReloadedEvent {
+    pub model_path: String,
+}
+
+impl presage::Event for ModelReloadedEvent {
+    const NAME: &'static str = "model-reloaded";
+}
\ No newline at end of file
@@ -0,0 +1,10 @@
+use crate::domain::models::operation_progress::OperationProgress;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct OperationProgressEvent {
+    pub progress: OperationProgress,
+}
+
+impl presage::Event for OperationProgressEvent {
+    const NAME: &'static str = "operation-progress";
+}
@@ -0,0 +1,16 @@
+use std::collections::HashMap;
+
+use super::bulb_state_changed_event::BulbStateChangedEvent;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PredictionActedEvent {
+    pub headset_data: HashMap<String, Vec<f32>>,
+    pub window_id: u64,
+    pub color_thinking: String,
+    /// Actuation attempt the prediction caused, `None` when its action does not drive the bulb.
+    pub actuation: Option<BulbStateChangedEvent>,
+}
+
+impl presage::Event for PredictionActedEvent {
+    const NAME: &'static str = "prediction-acted";
+}
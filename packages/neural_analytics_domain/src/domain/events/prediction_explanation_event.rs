@@ -0,0 +1,12 @@
+use std::collections::HashMap;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PredictionExplanationEvent {
+    pub window_id: u64,
+    pub color_thinking: String,
+    pub channel_contributions: HashMap<String, f32>,
+}
+
+impl presage::Event for PredictionExplanationEvent {
+    const NAME: &'static str = "prediction-explanation";
+}
@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionRotatedEvent {
+    pub session_id: u64,
+    pub reason: String,
+    /// File the new session is recorded to, `None` when not recording.
+    pub recording_path: Option<String>,
+}
+
+impl presage::Event for SessionRotatedEvent {
+    const NAME: &'static str = "session-rotated";
+}
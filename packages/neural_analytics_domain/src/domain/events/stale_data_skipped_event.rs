@@ -0,0 +1,11 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct StaleDataSkippedEvent {
+    /// Window recorded without a prediction, as the board buffered it during a stall.
+    pub window_id: u64,
+    /// Age of the newest sample of the window when it was received, in milliseconds.
+    pub data_age_ms: i64,
+}
+
+impl presage::Event for StaleDataSkippedEvent {
+    const NAME: &'static str = "stale-data-skipped";
+}
@@ -0,0 +1,10 @@
+use crate::domain::models::calibration_thresholds::ThresholdSuggestion;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SuggestedThresholdsEvent {
+    pub suggestion: ThresholdSuggestion,
+}
+
+impl presage::Event for SuggestedThresholdsEvent {
+    const NAME: &'static str = "suggested-thresholds";
+}
@@ -0,0 +1,11 @@
+use std::collections::HashMap;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TestSignalVerifiedEvent {
+    pub headset_data: HashMap<String, Vec<f32>>,
+    pub diagnostics_issues: Vec<String>,
+}
+
+impl presage::Event for TestSignalVerifiedEvent {
+    const NAME: &'static str = "test-signal-verified";
+}
@@ -0,0 +1,9 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct UntestedFirmwareEvent {
+    /// Firmware version reported by the headset, missing from the tested versions.
+    pub firmware_version: String,
+}
+
+impl presage::Event for UntestedFirmwareEvent {
+    const NAME: &'static str = "untested-firmware";
+}
@@ -2,7 +2,7 @@ pub(crate) mod commands;
 pub(crate) mod context;
 pub mod models;
 pub mod events;
-pub(crate) mod ports;
+pub mod ports;
 pub mod services;
 pub(crate) mod state_machine;
 pub(crate) mod use_cases;
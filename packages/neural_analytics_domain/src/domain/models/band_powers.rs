@@ -0,0 +1,54 @@
+/// Bands of the EEG spectrum, with their range in hertz, the upper bound excluded.
+pub const EEG_BANDS: [(&str, f32, f32); 4] = [("delta", 0.5, 4.0), ("theta", 4.0, 8.0), ("alpha", 8.0, 13.0), ("beta", 13.0, 30.0)];
+
+/// Power of the EEG bands of a channel, in the squared unit of its samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BandPowers {
+    pub delta: f32,
+    pub theta: f32,
+    pub alpha: f32,
+    pub beta: f32,
+}
+
+impl BandPowers {
+    /// Power of a band of `EEG_BANDS` by its name, `None` for an unknown band.
+    pub fn of(&self, band: &str) -> Option<f32> {
+        match band {
+            "delta" => Some(self.delta),
+            "theta" => Some(self.theta),
+            "alpha" => Some(self.alpha),
+            "beta" => Some(self.beta),
+            _ => None,
+        }
+    }
+}
+
+/// Band powers computed from the recent samples of each channel.
+///
+/// The powers are estimated by Welch's method over the last `history_samples` samples,
+/// in segments of `segment_samples`: two seconds in segments of one second resolve the
+/// bands to 1 Hz at the 250 Hz of the BrainBit headset, where a single window of 62
+/// samples only resolves them to 4 Hz.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BandPowerConfig {
+    /// Compute the band powers of every window and send them with its prediction.
+    pub enabled: bool,
+    /// Samples of each channel the powers are computed from, the newest kept.
+    pub history_samples: usize,
+    /// Samples of each segment averaged by Welch's method, setting the resolution of the bands.
+    pub segment_samples: usize,
+    /// Pass the band powers to the model along with the window, for the models using them.
+    pub feed_model: bool,
+}
+
+impl Default for BandPowerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_samples: 500,
+            segment_samples: 250,
+            feed_model: false,
+        }
+    }
+}
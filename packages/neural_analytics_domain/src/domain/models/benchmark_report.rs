@@ -0,0 +1,49 @@
+/// Distribution of the latency of a pipeline stage, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LatencyStats {
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    /// Summarizes the latencies measured for a stage, all zero when there are none.
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let percentile = |quantile: f64| sorted[((sorted.len() - 1) as f64 * quantile).round() as usize];
+
+        Self {
+            mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Throughput of the pipeline on the current machine, measured by `run_pipeline_benchmark`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BenchmarkReport {
+    /// Name of the headset adapter the windows were read from.
+    pub adapter: String,
+    /// Windows requested.
+    pub windows: usize,
+    /// Windows extracted and predicted.
+    pub processed_windows: usize,
+    /// Windows discarded or whose extraction or prediction failed.
+    pub failed_windows: usize,
+    pub elapsed_ms: f64,
+    pub windows_per_sec: f64,
+    /// Reading and assembling a window from the headset.
+    pub extraction: LatencyStats,
+    /// Running the model on a window.
+    pub inference: LatencyStats,
+    /// From the context handing an external event over to a frontend receiving it.
+    pub event_dispatch: LatencyStats,
+}
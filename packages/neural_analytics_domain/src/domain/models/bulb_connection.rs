@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+/// Retry schedule of the connection to the smart bulb at startup.
+///
+/// The delay between attempts doubles from `initial_delay_ms` up to `max_delay_ms`,
+/// and the bulb is reported unavailable once `max_attempts` attempts failed.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulbConnectionPolicy {
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// Attempts before giving up, at least one is always made.
+    pub max_attempts: u32,
+}
+
+impl Default for BulbConnectionPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: 1_000,
+            max_delay_ms: 30_000,
+            max_attempts: 8,
+        }
+    }
+}
+
+impl BulbConnectionPolicy {
+    /// Wait after the given failed attempt, counted from 1, before the next one.
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX);
+
+        Duration::from_millis(self.initial_delay_ms.saturating_mul(factor).min(self.max_delay_ms))
+    }
+}
+
+/// State of the connection to the smart bulb, reported by its adapter.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BulbConnectionState {
+    /// The adapter is trying to reach the bulb, `attempt` out of `max_attempts`.
+    Connecting { attempt: u32, max_attempts: u32 },
+    Connected,
+    /// Every attempt failed, the predictions do not change the light.
+    Unavailable { reason: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_after_doubles_up_to_the_max_delay() {
+        let policy = BulbConnectionPolicy::default();
+
+        assert_eq!(policy.delay_after(1), Duration::from_secs(1));
+        assert_eq!(policy.delay_after(3), Duration::from_secs(4));
+        assert_eq!(policy.delay_after(6), Duration::from_secs(30));
+        assert_eq!(policy.delay_after(100), Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,5 @@
+#[derive(Debug, PartialEq, Clone, Copy, serde::Serialize, serde::Deserialize)] // Added Clone, Copy for convenience
+pub enum BulbState {
+    BulbOn,
+    BulbOff,
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use super::calibration_thresholds::CalibrationThresholds;
+
+/// Calibration of the headset, on top of the `calibration_thresholds` shared by every electrode.
+///
+/// The defaults keep the historic calibration: the shared thresholds for every electrode,
+/// finished on the first good reading, and the headset searched again on the first
+/// failed one.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalibrationConfig {
+    /// Thresholds of single electrodes, keyed by hardware name, in place of the shared
+    /// ones, e.g. for an electrode placed over hair.
+    pub electrode_thresholds: HashMap<String, CalibrationThresholds>,
+    /// How long, in milliseconds, every electrode must stay within its thresholds to
+    /// finish the calibration. `0` finishes it on the first good reading.
+    pub stable_duration_ms: u64,
+    /// Failed impedance readings in a row retried before the headset is considered lost.
+    pub read_retries: u32,
+}
+
+impl CalibrationConfig {
+    /// Thresholds of an electrode, its own or the shared ones.
+    pub fn thresholds_of(&self, electrode: &str, shared: &CalibrationThresholds) -> CalibrationThresholds {
+        self.electrode_thresholds.get(electrode).copied().unwrap_or(*shared)
+    }
+
+    /// Checks if every electrode of an impedance reading is within its thresholds.
+    pub fn accepts(&self, impedance_data: &HashMap<String, u16>, shared: &CalibrationThresholds) -> bool {
+        impedance_data
+            .iter()
+            .all(|(electrode, &impedance)| self.thresholds_of(electrode, shared).accepts(impedance))
+    }
+}
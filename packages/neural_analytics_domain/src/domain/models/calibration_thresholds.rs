@@ -0,0 +1,35 @@
+/// Impedance range, in kOhm, an electrode must be within to consider the headset calibrated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CalibrationThresholds {
+    /// Lowest accepted impedance, values below it mean the electrode is not touching the skin.
+    pub min_impedance: u16,
+    pub max_impedance: u16,
+}
+
+impl CalibrationThresholds {
+    /// Checks if an impedance reading is within the thresholds.
+    pub fn accepts(&self, impedance: u16) -> bool {
+        (self.min_impedance..=self.max_impedance).contains(&impedance)
+    }
+}
+
+impl Default for CalibrationThresholds {
+    fn default() -> Self {
+        // Historic values, tuned for fresh electrode gel
+        CalibrationThresholds {
+            min_impedance: 1,
+            max_impedance: 1000,
+        }
+    }
+}
+
+/// Thresholds suggested from the impedance readings observed during calibration.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ThresholdSuggestion {
+    pub thresholds: CalibrationThresholds,
+    /// Median of the worst electrode impedance across the observed readings.
+    pub noise_floor: u16,
+    /// Whether the suggestion was applied without waiting for the user.
+    pub is_applied: bool,
+}
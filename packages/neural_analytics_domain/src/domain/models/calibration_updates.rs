@@ -0,0 +1,23 @@
+/// Pace of the impedance and signal quality updates sent while calibrating the headset.
+///
+/// A reading is only sent when an electrode enters another quality band, or its impedance
+/// changes by more than `min_change` of the last one sent, and never within
+/// `min_interval_ms` of the previous update.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalibrationUpdates {
+    /// Shortest time, in milliseconds, between two updates. `0` sends them as they change.
+    pub min_interval_ms: u64,
+    /// Relative change of the impedance of an electrode worth an update. `0` sends every change.
+    pub min_change: f32,
+}
+
+impl Default for CalibrationUpdates {
+    /// Up to four updates per second, on changes of 10% or more.
+    fn default() -> Self {
+        Self {
+            min_interval_ms: 250,
+            min_change: 0.1,
+        }
+    }
+}
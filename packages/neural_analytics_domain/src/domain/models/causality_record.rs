@@ -0,0 +1,20 @@
+use super::bulb_state::BulbState;
+
+/// Links a bulb actuation with the prediction and the EEG window that caused it.
+///
+/// Timestamps are Unix epoch milliseconds. `window_timestamp` is when the headset
+/// acquired the window, so the stimulus-response latency of an actuation is
+/// `actuation_timestamp - window_timestamp`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CausalityRecord {
+    pub window_id: u64,
+    pub window_timestamp: i64,
+    pub prediction: String,
+    pub prediction_timestamp: i64,
+    pub desired: BulbState,
+    pub confirmed: bool,
+    pub actuation_timestamp: i64,
+    pub actuation_latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
\ No newline at end of file
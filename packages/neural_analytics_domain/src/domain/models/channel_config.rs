@@ -0,0 +1,76 @@
+/// Labels of a hardware channel of the headset.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelLabel {
+    /// Name reported by the headset, the key of the channel in the EEG and impedance data.
+    pub hardware_name: String,
+    /// Position of the electrode in the modern 10-20 nomenclature.
+    pub position: String,
+    /// Free label shown to the user instead of the position.
+    pub display_name: Option<String>,
+}
+
+/// Naming of the headset channels across frontends and metadata.
+///
+/// The EEG data keeps the hardware names, which the model was trained with;
+/// the labels are only used where channels are presented.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelConfig {
+    pub channels: Vec<ChannelLabel>,
+}
+
+impl ChannelConfig {
+    /// Returns the 10-20 position of a channel, the hardware name if it is not configured.
+    pub fn position_of<'a>(&'a self, hardware_name: &'a str) -> &'a str {
+        self.find(hardware_name)
+            .map_or(hardware_name, |channel| channel.position.as_str())
+    }
+
+    /// Returns the label shown to the user for a channel: its display name, or else its position.
+    pub fn display_name_of<'a>(&'a self, hardware_name: &'a str) -> &'a str {
+        match self.find(hardware_name) {
+            Some(channel) => channel.display_name.as_deref().unwrap_or(&channel.position),
+            None => hardware_name,
+        }
+    }
+
+    fn find(&self, hardware_name: &str) -> Option<&ChannelLabel> {
+        self.channels
+            .iter()
+            .find(|channel| channel.hardware_name == hardware_name)
+    }
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        // BrainBit reports the old 10-20 names, T3/T4 were renamed to T7/T8
+        let channels = [("T3", "T7"), ("T4", "T8"), ("O1", "O1"), ("O2", "O2")]
+            .into_iter()
+            .map(|(hardware_name, position)| ChannelLabel {
+                hardware_name: hardware_name.to_string(),
+                position: position.to_string(),
+                display_name: None,
+            })
+            .collect();
+
+        ChannelConfig { channels }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_labels_fall_back_to_position_and_hardware_name() {
+        let mut config = ChannelConfig::default();
+        config.channels[2].display_name = Some("Left occipital".to_string());
+
+        assert_eq!(config.position_of("T3"), "T7");
+        assert_eq!(config.display_name_of("T4"), "T8");
+        assert_eq!(config.display_name_of("O1"), "Left occipital");
+        assert_eq!(config.position_of("Fp1"), "Fp1");
+        assert_eq!(config.display_name_of("Fp1"), "Fp1");
+    }
+}
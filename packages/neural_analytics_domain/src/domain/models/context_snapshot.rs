@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use super::bulb_state::BulbState;
+use crate::domain::services::metrics_service::MetricsSnapshot;
+
+/// Range of the samples of a channel in the current window, the input of the
+/// normalization applied before inference.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ChannelRange {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+/// State of the hardware adapters, `None` when the adapter was busy while taking the snapshot.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AdapterStates {
+    pub is_headset_connected: Option<bool>,
+    pub headset_work_mode: Option<String>,
+    pub headset_firmware_version: Option<String>,
+    pub is_bulb_simulated: Option<bool>,
+    pub is_model_loaded: Option<bool>,
+    pub model_load_error: Option<String>,
+}
+
+/// Summary of the context of the core, published through the `ContextSnapshotEvent`.
+///
+/// Users can attach it to their issue reports, so it only contains sizes and
+/// summaries, never the raw EEG samples.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ContextSnapshot {
+    /// Unix epoch milliseconds of the moment the snapshot was taken.
+    pub timestamp: i64,
+    /// Samples per channel of the current window.
+    pub headset_samples: HashMap<String, usize>,
+    pub impedance_channels: usize,
+    pub color_thinking_buffer: Vec<String>,
+    pub color_thinking: String,
+    pub window_id: u64,
+    pub predicted_window_id: Option<u64>,
+    pub normalization_ranges: HashMap<String, ChannelRange>,
+    pub bulb_state: Option<BulbState>,
+    pub adapters: AdapterStates,
+    pub diagnostics_issues: Vec<String>,
+    pub memory_usage: HashMap<String, usize>,
+    pub metrics: MetricsSnapshot,
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use super::{
+    band_powers::BandPowerConfig, bulb_connection::BulbConnectionPolicy, calibration_config::CalibrationConfig, calibration_thresholds::CalibrationThresholds, calibration_updates::CalibrationUpdates, channel_config::ChannelConfig,
+    display_preferences::DisplayPreferences,
+    event_routing::EventRouting, exhibition::ExhibitionMode, inference_config::InferenceConfig, payload_policy::PayloadPolicy, prediction_action::PredictionAction, prediction_smoothing::PredictionSmoothing,
+    recording_config::RecordingConfig, runtime_config::RuntimeConfig, scheduling_config::SchedulingConfig, synthetic_signal::SyntheticSignalConfig,
+    trash_streak::TrashStreak,
+    window_overlap::WindowOverlap,
+};
+
+/// Version of the layout of `CoreConfig`, bumped when a key is renamed, removed or changes meaning.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Runtime configuration of the core, passed to `initialize_core_with_config`.
+///
+/// Built from `CoreConfig::default()` outside the domain, so the sections added later
+/// do not break the callers.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct CoreConfig {
+    /// Layout the configuration was written for, `CONFIG_VERSION` when it is left out.
+    pub version: u32,
+    /// Action to run for each prediction class reported by the model.
+    pub action_mapping: HashMap<String, PredictionAction>,
+    /// Action to run for prediction classes missing from `action_mapping`.
+    pub default_action: PredictionAction,
+    /// Upper bound, in bytes, for the estimated memory held by the context buffers.
+    pub memory_budget_bytes: usize,
+    /// Interval between `HeartbeatEvent`s, in milliseconds. `0` disables the heartbeat.
+    pub heartbeat_interval_ms: u64,
+    /// JSON Lines file where the session journal is appended. `None` keeps it in memory only.
+    pub journal_path: Option<String>,
+    /// Reload the model when its file is replaced on disk.
+    pub watch_model: bool,
+    /// Impedance range the electrodes must be within to finish the calibration.
+    pub calibration_thresholds: CalibrationThresholds,
+    /// Thresholds of single electrodes, how long they must be stable and the retries of
+    /// the failed readings of the calibration.
+    pub calibration: CalibrationConfig,
+    /// Apply the thresholds suggested from the observed noise floor without asking the user.
+    pub auto_apply_suggested_thresholds: bool,
+    /// Pace of the impedance updates sent to the frontends while calibrating.
+    pub calibration_updates: CalibrationUpdates,
+    /// 10-20 positions and display names of the headset channels.
+    pub channel_config: ChannelConfig,
+    /// Gain correcting each channel, keyed by hardware name, applied to the samples of
+    /// the headset before they are recorded and predicted. Missing channels keep a gain of 1.
+    pub channel_gains: HashMap<String, f32>,
+    /// Score the contribution of each channel to every new prediction, at the cost of
+    /// one extra inference per channel. Meant to debug the model, not for daily use.
+    pub explain_predictions: bool,
+    /// JSON file where the calibration profiles and the measured delays of each headset
+    /// are saved. `None` keeps them in memory only.
+    pub calibration_profiles_path: Option<String>,
+    /// How long, in seconds, the calibration of a headset can be reused when the same
+    /// user reconnects it. `0` always calibrates.
+    pub calibration_reuse_period_secs: u64,
+    /// Skip the calibration of the headsets that cannot measure impedance, instead of
+    /// checking the quality of their signal.
+    pub skip_calibration_without_impedance: bool,
+    /// Research sessions, where every recording must start from a fresh calibration.
+    /// Disables the calibration reuse.
+    pub research_mode: bool,
+    /// Abort the session on the first breach of the integrity of its data, a gap in the
+    /// package numbers, a non finite sample or a clock drift over the threshold, instead
+    /// of repairing or tolerating it. Meant for the research datasets.
+    pub strict_mode: bool,
+    /// Drift, in milliseconds, between the board and the host clocks that triggers a
+    /// `ClockDriftWarningEvent`. `0` disables the warning.
+    pub clock_drift_threshold_ms: u64,
+    /// Lowest severity of the events sent to each destination.
+    pub event_routing: EventRouting,
+    /// CPU pinning and priority of the capture and inference thread.
+    pub scheduling: SchedulingConfig,
+    /// Async runtime the core runs its tasks on, the one of the caller by default.
+    pub runtime: RuntimeConfig,
+    /// Retry schedule of the connection to the smart bulb at startup.
+    pub bulb_connection: BulbConnectionPolicy,
+    /// Time without a command or a window of the core, in milliseconds, after which a bulb
+    /// left on is turned off. `0` disables the auto-off.
+    pub bulb_auto_off_ms: u64,
+    /// Span of the predictions that must agree to act on them, sized from the window cadence.
+    pub prediction_smoothing: PredictionSmoothing,
+    /// Recording of the raw EEG of every session to a directory of its own.
+    pub recording: RecordingConfig,
+    /// Overlap between consecutive windows, lowered while the inference cannot keep up with it.
+    pub window_overlap: WindowOverlap,
+    /// Garbage predictions in a row pausing the inference until the headset is recalibrated.
+    pub trash_streak: TrashStreak,
+    /// High contrast and large text modes of the frontends, for the users with low vision.
+    pub display: DisplayPreferences,
+    /// Depth from which a queue between the stages of the core is backlogged, warned
+    /// when it lasts. `0` disables the warning.
+    pub queue_backlog_threshold: usize,
+    /// Power of the EEG bands of each channel, sent with the predictions and optionally
+    /// passed to the model.
+    pub band_powers: BandPowerConfig,
+    /// Shape and size limit of the events forwarded to each destination, keyed by its
+    /// name, such as `event_trace`. The destinations left out receive the full events.
+    pub payload_policies: HashMap<String, PayloadPolicy>,
+    /// Changes of the prediction shown on the bulb and a fullscreen overlay at once, and
+    /// held for an audience to follow them.
+    pub exhibition: ExhibitionMode,
+    /// Runtime the model is loaded in, tract on the CPU by default or ONNX Runtime on a
+    /// GPU when built with the `ort` feature.
+    pub inference: InferenceConfig,
+    /// Signal streamed by the synthetic headset of the mock mode, with the spectrum of a
+    /// real recording.
+    pub synthetic_headset: SyntheticSignalConfig,
+}
+
+impl Default for CoreConfig {
+    fn default() -> Self {
+        // Mirrors the historic behaviour: only "green" turns the light on
+        let action_mapping = [
+            ("green".to_string(), PredictionAction::TurnOn),
+            ("red".to_string(), PredictionAction::TurnOff),
+        ]
+        .into_iter()
+        .collect();
+
+        CoreConfig {
+            version: CONFIG_VERSION,
+            action_mapping,
+            default_action: PredictionAction::TurnOff,
+            memory_budget_bytes: 8 * 1024 * 1024,
+            heartbeat_interval_ms: 5000,
+            journal_path: None,
+            watch_model: false,
+            calibration_thresholds: CalibrationThresholds::default(),
+            calibration: CalibrationConfig::default(),
+            auto_apply_suggested_thresholds: false,
+            calibration_updates: CalibrationUpdates::default(),
+            channel_config: ChannelConfig::default(),
+            channel_gains: HashMap::new(),
+            explain_predictions: false,
+            calibration_profiles_path: None,
+            calibration_reuse_period_secs: 30 * 60,
+            skip_calibration_without_impedance: false,
+            research_mode: false,
+            strict_mode: false,
+            clock_drift_threshold_ms: 100,
+            event_routing: EventRouting::default(),
+            scheduling: SchedulingConfig::default(),
+            runtime: RuntimeConfig::default(),
+            bulb_connection: BulbConnectionPolicy::default(),
+            bulb_auto_off_ms: 60_000,
+            prediction_smoothing: PredictionSmoothing::default(),
+            recording: RecordingConfig::default(),
+            window_overlap: WindowOverlap::default(),
+            trash_streak: TrashStreak::default(),
+            display: DisplayPreferences::default(),
+            queue_backlog_threshold: 32,
+            band_powers: BandPowerConfig::default(),
+            payload_policies: HashMap::new(),
+            exhibition: ExhibitionMode::default(),
+            inference: InferenceConfig::default(),
+            synthetic_headset: SyntheticSignalConfig::default(),
+        }
+    }
+}
+impl CoreConfig {
+    /// Copy of the configuration that can be shared in an issue report, the webhook URLs
+    /// replaced as they often carry a token.
+    pub fn redacted(&self) -> CoreConfig {
+        let redact = |action: &PredictionAction| match action {
+            PredictionAction::Webhook { .. } => PredictionAction::Webhook {
+                url: "<redacted>".to_string(),
+            },
+            action => action.clone(),
+        };
+
+        CoreConfig {
+            action_mapping: self
+                .action_mapping
+                .iter()
+                .map(|(class, action)| (class.clone(), redact(action)))
+                .collect(),
+            default_action: redact(&self.default_action),
+            ..self.clone()
+        }
+    }
+
+    /// Policy of the events forwarded to a destination, the full events when it has none.
+    pub fn payload_policy(&self, destination: &str) -> PayloadPolicy {
+        self.payload_policies.get(destination).copied().unwrap_or_default()
+    }
+}
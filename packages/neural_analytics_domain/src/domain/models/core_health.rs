@@ -0,0 +1,20 @@
+/// Snapshot of the core liveness, published periodically through the `HeartbeatEvent`.
+///
+/// Timestamps are Unix epoch milliseconds so they can be compared by external
+/// watchdogs without sharing a clock abstraction with the core.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CoreHealth {
+    /// Current state of the main state machine.
+    pub state: String,
+    /// Last time the state machine finished handling a tick.
+    pub last_tick_timestamp: Option<i64>,
+    /// Last time a window of EEG data was extracted from the headset.
+    pub last_window_timestamp: Option<i64>,
+    pub is_headset_connected: bool,
+    /// Charge of the battery of the headset in percent, `None` if it does not report it.
+    pub headset_battery_percent: Option<u8>,
+    /// Result of the last bulb actuation, `None` if the bulb was never actuated.
+    pub is_bulb_reachable: Option<bool>,
+    /// File the EEG windows are recorded to, `None` when not recording.
+    pub recording_path: Option<String>,
+}
\ No newline at end of file
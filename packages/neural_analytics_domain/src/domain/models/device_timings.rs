@@ -0,0 +1,21 @@
+/// Delays a headset needs to settle, measured when it connects.
+///
+/// Boards and dongles answer at different speeds: fixed delays add latency to the
+/// fast ones and make commands flaky on the slow ones.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DeviceTimings {
+    /// Wait before sending a configuration command, in milliseconds.
+    pub command_delay_ms: u64,
+    /// Wait for the samples of a window to arrive before reading them, in milliseconds.
+    pub data_delay_ms: u64,
+}
+
+impl Default for DeviceTimings {
+    /// The conservative delays used until the headset is probed.
+    fn default() -> Self {
+        Self {
+            command_delay_ms: 500,
+            data_delay_ms: 300,
+        }
+    }
+}
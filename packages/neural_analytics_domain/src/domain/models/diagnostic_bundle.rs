@@ -0,0 +1,21 @@
+/// Change between two different states of the core, kept for the diagnostic bundles.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StateTransition {
+    /// Unix epoch milliseconds when the state machine entered the new state.
+    pub timestamp: i64,
+    pub from: String,
+    pub to: String,
+}
+
+/// Versions of the core and of what it runs with, written to the diagnostic bundles.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VersionInfo {
+    pub core_version: String,
+    pub os: String,
+    pub arch: String,
+    pub device_id: Option<String>,
+    pub firmware_version: Option<String>,
+    pub is_model_loaded: Option<bool>,
+    /// Classes the model predicts, in the order of its output.
+    pub class_labels: Vec<String>,
+}
@@ -0,0 +1,10 @@
+/// Accessibility preferences of the frontends, read from the configuration of the core
+/// so every frontend of an installation shows the same.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayPreferences {
+    /// Black and white palette instead of the grey on dark plots and the translucent panels.
+    pub high_contrast: bool,
+    /// Larger fonts for the labels of the views and the plots.
+    pub large_text: bool,
+}
@@ -3,4 +3,6 @@ pub enum WorkMode {
     Initialized,
     Calibration,
     Extraction,
+    /// The board streams a known square wave instead of the electrodes signal.
+    TestSignal,
 }
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use super::{
+    band_powers::BandPowers, bulb_connection::BulbConnectionState, bulb_state::BulbState, calibration_thresholds::ThresholdSuggestion, causality_record::CausalityRecord,
+    channel_config::ChannelConfig, context_snapshot::ContextSnapshot, core_health::CoreHealth, exhibition::ExhibitionCue,
+    impedance_snapshot::ImpedanceSnapshot, integrity_report::IntegrityReport, operation_progress::OperationProgress, signal_quality::SignalQuality,
+    signal_unit::SignalUnit, ui_hint::UiHint, window_prediction::WindowPrediction,
+};
+
+/// Payload of the events, built from `EventData::default()` outside the domain, so the
+/// fields added later do not break the callers.
+#[derive(Default, Clone)]
+#[non_exhaustive]
+pub struct EventData {
+    pub headset_data: Option<HashMap<String, Vec<f32>>>,
+    pub color_thinking: Option<String>,
+    pub impedance_data: Option<HashMap<String, u16>>,
+    /// Quality of the signal of each electrode, calibrating a headset without impedance support.
+    pub signal_quality: Option<HashMap<String, SignalQuality>>,
+    pub bulb_state: Option<BulbState>,
+    pub is_bulb_confirmed: Option<bool>,
+    pub bulb_latency_ms: Option<u64>,
+    pub is_bulb_simulated: Option<bool>,
+    pub bulb_connection: Option<BulbConnectionState>,
+    pub memory_usage: Option<HashMap<String, usize>>,
+    pub memory_budget_bytes: Option<usize>,
+    pub diagnostics_issues: Option<Vec<String>>,
+    pub core_health: Option<CoreHealth>,
+    pub causality: Option<CausalityRecord>,
+    /// Id of the EEG window the event refers to, assigned at extraction time.
+    pub window_id: Option<u64>,
+    /// Monotonically increasing number of the external event, stamped by the core when sending it.
+    pub sequence: Option<u64>,
+    /// Presentation hints of the state a transition event enters, stamped by the core when sending it.
+    pub ui_hint: Option<UiHint>,
+    pub model_path: Option<String>,
+    /// Classes the model predicts, in the order of its output.
+    pub class_labels: Option<Vec<String>>,
+    pub suggested_thresholds: Option<ThresholdSuggestion>,
+    pub channel_config: Option<ChannelConfig>,
+    pub signal_unit: Option<SignalUnit>,
+    pub context_snapshot: Option<ContextSnapshot>,
+    /// Probability of the predicted class lost when each channel is occluded.
+    pub channel_contributions: Option<HashMap<String, f32>>,
+    pub session_id: Option<u64>,
+    pub session_rotation_reason: Option<String>,
+    pub recording_path: Option<String>,
+    /// Unix epoch milliseconds when the reused calibration finished.
+    pub calibration_timestamp: Option<i64>,
+    pub max_impedance: Option<u16>,
+    /// Final impedance of the electrodes of a finished calibration.
+    pub calibrated_impedance: Option<ImpedanceSnapshot>,
+    /// Drift between the host and the board clocks, in milliseconds.
+    pub clock_drift_ms: Option<f64>,
+    /// Age of the newest sample of a window when it was received, in milliseconds.
+    pub data_age_ms: Option<i64>,
+    /// Consecutive garbage predictions that paused the inference.
+    pub trash_streak: Option<u64>,
+    pub operation_progress: Option<OperationProgress>,
+    /// Name of the command an error refers to.
+    pub command_name: Option<String>,
+    pub error_message: Option<String>,
+    pub firmware_version: Option<String>,
+    pub integrity_report: Option<IntegrityReport>,
+    /// Zip archive of a diagnostic bundle.
+    pub bundle_path: Option<String>,
+    /// Power of the EEG bands of each channel, from its recent samples.
+    pub band_powers: Option<HashMap<String, BandPowers>>,
+    /// Prediction of the model for the window before smoothing, with the probability of
+    /// each class and its latency. `None` when the prediction was reused from the cache.
+    pub window_prediction: Option<WindowPrediction>,
+    /// Change of the prediction shown on the bulb and the overlay in exhibition mode.
+    pub exhibition_cue: Option<ExhibitionCue>,
+}
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use presage::{Event, SerializedEvent};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{device_timings::DeviceTimings, signal_quality::SignalQuality, window_prediction::WindowPrediction};
+use crate::domain::events::bulb_state_changed_event::BulbStateChangedEvent;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct ReceivedGeneralistDataEvent {
+    pub headset_data: HashMap<String, Vec<f32>>,
+    pub window_id: u64,
+    /// Board timestamp of the last sample, in Unix epoch seconds, if the headset reports it.
+    pub board_timestamp: Option<f64>,
+    /// Package numbers of the samples, if the headset numbers them.
+    #[serde(default)]
+    pub sequence_numbers: Option<Vec<u32>>,
+    /// Non finite samples per channel before they were interpolated.
+    #[serde(default)]
+    pub non_finite_samples: HashMap<String, usize>,
+}
+
+impl presage::Event for ReceivedGeneralistDataEvent {
+    const NAME: &'static str = "received-generalist-data";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct DiscardedWindowEvent {
+    pub window_id: u64,
+    pub reason: String,
+    /// Package numbers of the samples, if the headset numbers them.
+    #[serde(default)]
+    pub sequence_numbers: Option<Vec<u32>>,
+}
+
+impl presage::Event for DiscardedWindowEvent {
+    const NAME: &'static str = "discarded-window";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct ConnectedHeadsetEvent {
+    /// Identifier reported by the headset, `None` if it cannot tell one device from another.
+    pub device_id: Option<String>,
+    /// Firmware version reported by the headset, `None` if it does not expose it.
+    pub firmware_version: Option<String>,
+    /// Delays the headset measured when it connected.
+    pub device_timings: Option<DeviceTimings>,
+}
+
+impl presage::Event for ConnectedHeadsetEvent {
+    const NAME: &'static str = "connected-headset";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct ReceivedCalibrationDataEvent {
+    pub impedance_data: HashMap<String, u16>,
+}
+
+impl presage::Event for ReceivedCalibrationDataEvent {
+    const NAME: &'static str = "received-calibration-data";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct CheckedSignalQualityEvent {
+    pub signal_quality: HashMap<String, SignalQuality>,
+}
+
+impl presage::Event for CheckedSignalQualityEvent {
+    const NAME: &'static str = "checked-signal-quality";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct ReceivedPredictColorThinkingDataEvent {
+    pub color_thinking: String,
+    pub window_id: u64,
+    pub window_hash: u64,
+    /// Whether the prediction was reused from an identical previous window.
+    pub is_cached: bool,
+    /// Milliseconds the model took to predict, and explain, the window, `None` when it was cached.
+    pub inference_ms: Option<f64>,
+    /// Prediction of the model with the probabilities of the classes, the cached one when it was reused.
+    pub prediction: Option<WindowPrediction>,
+}
+
+impl presage::Event for ReceivedPredictColorThinkingDataEvent {
+    const NAME: &'static str = "received-predict-color-thinking-data";
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ExplainedPredictionEvent {
+    pub window_id: u64,
+    pub predicted_class: String,
+    /// Probability of the predicted class lost when each channel is occluded.
+    pub channel_contributions: HashMap<String, f32>,
+}
+
+impl presage::Event for ExplainedPredictionEvent {
+    const NAME: &'static str = "explained-prediction";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct NotifiedWebhookEvent {
+    pub prediction: String,
+    /// Why the adapter rejected the notification, `None` once it was queued.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl presage::Event for NotifiedWebhookEvent {
+    const NAME: &'static str = "notified-webhook";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct PublishedPredictionEvent {
+    pub prediction: String,
+}
+
+impl presage::Event for PublishedPredictionEvent {
+    const NAME: &'static str = "published-prediction";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct VerifiedTestSignalEvent {
+    pub headset_data: HashMap<String, Vec<f32>>,
+    pub issues: Vec<String>,
+}
+
+impl presage::Event for VerifiedTestSignalEvent {
+    const NAME: &'static str = "verified-test-signal";
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct CollectedDiagnosticsEvent {
+    pub issues: Vec<String>,
+}
+
+impl presage::Event for CollectedDiagnosticsEvent {
+    const NAME: &'static str = "collected-diagnostics";
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct CreatedDiagnosticBundleEvent {
+    pub bundle_path: String,
+}
+
+impl presage::Event for CreatedDiagnosticBundleEvent {
+    const NAME: &'static str = "created-diagnostic-bundle";
+}
+
+/// Event applied by the context, as written to the session journal under the `event` kind.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct JournaledEvent {
+    pub name: String,
+    pub data: serde_json::Value,
+}
+
+impl JournaledEvent {
+    /// Journals an event the context applies, `None` for the ones it ignores.
+    pub fn of(event: &SerializedEvent) -> Option<JournaledEvent> {
+        let data = match event.name() {
+            ReceivedGeneralistDataEvent::NAME => data_of::<ReceivedGeneralistDataEvent>(event),
+            DiscardedWindowEvent::NAME => data_of::<DiscardedWindowEvent>(event),
+            ConnectedHeadsetEvent::NAME => data_of::<ConnectedHeadsetEvent>(event),
+            ReceivedCalibrationDataEvent::NAME => data_of::<ReceivedCalibrationDataEvent>(event),
+            CheckedSignalQualityEvent::NAME => data_of::<CheckedSignalQualityEvent>(event),
+            ReceivedPredictColorThinkingDataEvent::NAME => data_of::<ReceivedPredictColorThinkingDataEvent>(event),
+            ExplainedPredictionEvent::NAME => data_of::<ExplainedPredictionEvent>(event),
+            BulbStateChangedEvent::NAME => data_of::<BulbStateChangedEvent>(event),
+            NotifiedWebhookEvent::NAME => data_of::<NotifiedWebhookEvent>(event),
+            PublishedPredictionEvent::NAME => data_of::<PublishedPredictionEvent>(event),
+            VerifiedTestSignalEvent::NAME => data_of::<VerifiedTestSignalEvent>(event),
+            CollectedDiagnosticsEvent::NAME => data_of::<CollectedDiagnosticsEvent>(event),
+            CreatedDiagnosticBundleEvent::NAME => data_of::<CreatedDiagnosticBundleEvent>(event),
+            _ => None,
+        }?;
+
+        Some(JournaledEvent {
+            name: event.name().to_string(),
+            data,
+        })
+    }
+
+    /// Rebuilds the event, to apply it again to a context.
+    ///
+    /// # Returns
+    /// * `Option<SerializedEvent>`: The event, `None` if its name is unknown or its data cannot be read.
+    pub fn to_event(&self) -> Option<SerializedEvent> {
+        match self.name.as_str() {
+            ReceivedGeneralistDataEvent::NAME => event_of::<ReceivedGeneralistDataEvent>(&self.data),
+            DiscardedWindowEvent::NAME => event_of::<DiscardedWindowEvent>(&self.data),
+            ConnectedHeadsetEvent::NAME => event_of::<ConnectedHeadsetEvent>(&self.data),
+            ReceivedCalibrationDataEvent::NAME => event_of::<ReceivedCalibrationDataEvent>(&self.data),
+            CheckedSignalQualityEvent::NAME => event_of::<CheckedSignalQualityEvent>(&self.data),
+            ReceivedPredictColorThinkingDataEvent::NAME => event_of::<ReceivedPredictColorThinkingDataEvent>(&self.data),
+            ExplainedPredictionEvent::NAME => event_of::<ExplainedPredictionEvent>(&self.data),
+            BulbStateChangedEvent::NAME => event_of::<BulbStateChangedEvent>(&self.data),
+            NotifiedWebhookEvent::NAME => event_of::<NotifiedWebhookEvent>(&self.data),
+            PublishedPredictionEvent::NAME => event_of::<PublishedPredictionEvent>(&self.data),
+            VerifiedTestSignalEvent::NAME => event_of::<VerifiedTestSignalEvent>(&self.data),
+            CollectedDiagnosticsEvent::NAME => event_of::<CollectedDiagnosticsEvent>(&self.data),
+            CreatedDiagnosticBundleEvent::NAME => event_of::<CreatedDiagnosticBundleEvent>(&self.data),
+            _ => None,
+        }
+    }
+}
+
+fn data_of<E: Event + Serialize>(event: &SerializedEvent) -> Option<serde_json::Value> {
+    event.clone().deserialize::<E>().ok().and_then(|event| serde_json::to_value(event).ok())
+}
+
+fn event_of<E: Event + DeserializeOwned>(data: &serde_json::Value) -> Option<SerializedEvent> {
+    serde_json::from_value::<E>(data.clone()).ok().and_then(|event| Event::serialize(event).ok())
+}
@@ -0,0 +1,92 @@
+/// Severity of an external event, from the routine updates to the failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Destination the external events are routed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventDestination {
+    /// The event handler given to the core, which feeds the frontends.
+    Frontend,
+    /// The logs of the core.
+    Log,
+    /// The MQTT broker the predictions are published to.
+    Mqtt,
+    /// The webhooks of the predictions and of the session lifecycle.
+    Webhook,
+    /// The handlers added to the core with `CoreHandle::add_event_handler`.
+    Subscribers,
+}
+
+/// Lowest severity each destination receives.
+///
+/// By default every destination receives every event, like before the severities existed.
+/// Frontends that only raise alerts, such as a kiosk or a remote monitor, can raise the
+/// severity of the `frontend`, but the GUI needs the `info` events to show the signals.
+///
+/// The predictions published to `mqtt` and posted to the `webhook` are `info` messages, and
+/// a disconnected headset is a `warning` of the lifecycle webhook, so raising the severity
+/// of a bridge keeps the messages it forwards over the network to the alerts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventRouting {
+    pub frontend: EventSeverity,
+    /// The `info` events are logged at the debug level, they are sent several times per second.
+    pub log: EventSeverity,
+    pub mqtt: EventSeverity,
+    pub webhook: EventSeverity,
+    pub subscribers: EventSeverity,
+}
+
+impl Default for EventRouting {
+    fn default() -> Self {
+        Self {
+            frontend: EventSeverity::Info,
+            log: EventSeverity::Info,
+            mqtt: EventSeverity::Info,
+            webhook: EventSeverity::Info,
+            subscribers: EventSeverity::Info,
+        }
+    }
+}
+
+impl EventRouting {
+    /// Whether an event of the given severity is routed to the destination.
+    pub fn is_routed(&self, destination: EventDestination, severity: EventSeverity) -> bool {
+        let lowest = match destination {
+            EventDestination::Frontend => self.frontend,
+            EventDestination::Log => self.log,
+            EventDestination::Mqtt => self.mqtt,
+            EventDestination::Webhook => self.webhook,
+            EventDestination::Subscribers => self.subscribers,
+        };
+
+        severity >= lowest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_routing_filters_by_severity() {
+        let routing: EventRouting = serde_json::from_str(r#"{ "frontend": "warning" }"#).unwrap();
+
+        assert!(!routing.is_routed(EventDestination::Frontend, EventSeverity::Info));
+        assert!(routing.is_routed(EventDestination::Frontend, EventSeverity::Critical));
+        assert!(routing.is_routed(EventDestination::Log, EventSeverity::Info));
+        assert!(routing.is_routed(EventDestination::Subscribers, EventSeverity::Info));
+
+        let routing: EventRouting = serde_json::from_str(r#"{ "mqtt": "critical", "webhook": "warning" }"#).unwrap();
+
+        assert!(!routing.is_routed(EventDestination::Mqtt, EventSeverity::Warning));
+        assert!(!routing.is_routed(EventDestination::Webhook, EventSeverity::Info));
+        assert!(routing.is_routed(EventDestination::Webhook, EventSeverity::Warning));
+        assert!(routing.is_routed(EventDestination::Frontend, EventSeverity::Info));
+    }
+}
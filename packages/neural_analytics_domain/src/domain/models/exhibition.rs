@@ -0,0 +1,35 @@
+use super::prediction_action::PredictionAction;
+
+/// Exhibition mode, for the audiences too far away to read the plots of the capture view.
+///
+/// Every change of the prediction is shown at once on the bulb and on a fullscreen overlay
+/// of the GUI, then held for `hold_ms` with a countdown, so the audience can follow it
+/// before the next change is shown. The predictions arriving during the hold are not
+/// acted on, neither on the bulb nor on the overlay.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExhibitionMode {
+    pub enabled: bool,
+    /// Milliseconds a change is shown before the next one can be.
+    pub hold_ms: u64,
+}
+
+impl Default for ExhibitionMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hold_ms: 5_000,
+        }
+    }
+}
+
+/// Change of the prediction shown in exhibition mode, both on the bulb and on the overlay.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExhibitionCue {
+    /// Class the prediction changed to, e.g. `green`.
+    pub class: String,
+    /// Action taken on the bulb for the class, its color being the one of the overlay.
+    pub action: PredictionAction,
+    /// Milliseconds the change is held, counted down by the overlay.
+    pub hold_ms: u64,
+}
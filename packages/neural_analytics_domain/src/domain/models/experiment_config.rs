@@ -0,0 +1,20 @@
+use super::{calibration_thresholds::CalibrationThresholds, core_config::CoreConfig, signal_unit::SignalUnit};
+
+/// Effective settings of the pipeline when a session started, attached to the session
+/// so its results can be traced back to the exact settings that produced them.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExperimentConfig {
+    pub session_id: u64,
+    /// Unix epoch milliseconds when the session started.
+    pub timestamp: i64,
+    pub core_version: String,
+    /// Fingerprint of the model file, `None` when no model is loaded or it was busy reloading.
+    pub model_hash: Option<String>,
+    /// Classes the model predicts, in the order of its output.
+    pub class_labels: Vec<String>,
+    pub signal_unit: SignalUnit,
+    /// Thresholds in effect, which differ from the configured ones once a suggestion is applied.
+    pub calibration_thresholds: CalibrationThresholds,
+    /// Configuration the core was initialized with, without its secrets.
+    pub config: CoreConfig,
+}
@@ -0,0 +1,50 @@
+/// A step of the guided protocol followed by the user while recording.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProtocolStep {
+    /// Label given to the windows captured during this step, e.g. "red".
+    pub label: String,
+    pub duration_ms: u64,
+}
+
+/// Ordered sequence of steps the user is asked to follow during a recording.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GuidedProtocol {
+    pub steps: Vec<ProtocolStep>,
+}
+
+impl Default for GuidedProtocol {
+    /// Rest periods between the colors the model is trained on.
+    fn default() -> Self {
+        let step = |label: &str, duration_ms: u64| ProtocolStep {
+            label: label.to_string(),
+            duration_ms,
+        };
+
+        GuidedProtocol {
+            steps: vec![
+                step("trash", 10_000),
+                step("red", 20_000),
+                step("trash", 10_000),
+                step("green", 20_000),
+            ],
+        }
+    }
+}
+
+impl GuidedProtocol {
+    /// Returns the step active at the given offset from the start of the protocol.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms`: Milliseconds since the protocol started.
+    ///
+    /// # Returns
+    /// * `Option<&ProtocolStep>`: The active step, or `None` once the protocol is over.
+    pub fn step_at(&self, elapsed_ms: u64) -> Option<&ProtocolStep> {
+        let mut step_end = 0;
+
+        self.steps.iter().find(|step| {
+            step_end += step.duration_ms;
+            elapsed_ms < step_end
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,14 @@
+use std::collections::HashMap;
+
+/// Samples read from the headset for a window, before the context assembles and stores them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadsetReading {
+    /// Samples of each channel, as returned by the board.
+    pub data: HashMap<String, Vec<f32>>,
+    /// Package numbers the board stamped on the samples, `None` when it does not number them.
+    pub sequence_numbers: Option<Vec<u32>>,
+    /// Board timestamp of the last sample, in Unix epoch seconds, `None` when it does not report it.
+    pub board_timestamp: Option<f64>,
+    /// Charge of the battery of the headset in percent, `None` when it does not report it.
+    pub battery_percent: Option<u8>,
+}
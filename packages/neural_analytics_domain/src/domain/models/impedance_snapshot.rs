@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+/// Unit of the impedance of the electrodes reported by a headset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImpedanceUnit {
+    Ohms,
+    /// The unit of the calibration thresholds.
+    #[default]
+    Kiloohms,
+}
+
+impl ImpedanceUnit {
+    /// Symbol of the unit, for labels and exports.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ImpedanceUnit::Ohms => "Ω",
+            ImpedanceUnit::Kiloohms => "kΩ",
+        }
+    }
+
+    // Ohms in one of the unit
+    fn ohms(&self) -> f32 {
+        match self {
+            ImpedanceUnit::Ohms => 1.0,
+            ImpedanceUnit::Kiloohms => 1000.0,
+        }
+    }
+}
+
+/// Impedance of every electrode in the reading that finished the calibration.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ImpedanceSnapshot {
+    /// Impedance of each electrode, by channel, in `unit`.
+    pub impedances: HashMap<String, f32>,
+    pub unit: ImpedanceUnit,
+    /// Unix epoch milliseconds when the calibration finished.
+    pub timestamp: i64,
+}
+
+impl ImpedanceSnapshot {
+    /// Takes a snapshot of an impedance reading of the headset.
+    ///
+    /// # Arguments
+    /// * `readings`: The impedance of each electrode, as returned by the headset.
+    /// * `unit`: The unit the headset reports the impedance in.
+    /// * `timestamp`: Unix epoch milliseconds of the reading.
+    ///
+    /// # Returns
+    /// * `Result<Self, String>`: The snapshot, or why the reading cannot be one, see `validate`.
+    pub fn from_readings(readings: &HashMap<String, u16>, unit: ImpedanceUnit, timestamp: i64) -> Result<Self, String> {
+        let snapshot = ImpedanceSnapshot {
+            impedances: readings.iter().map(|(channel, value)| (channel.clone(), *value as f32)).collect(),
+            unit,
+            timestamp,
+        };
+
+        snapshot.validate().map(|_| snapshot)
+    }
+
+    /// Checks that the snapshot names every electrode and holds a measurable impedance for each.
+    ///
+    /// Snapshots also arrive deserialized, e.g. from a journal, so their values are not trusted.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.impedances.is_empty() {
+            return Err("The impedance snapshot has no electrode".to_string());
+        }
+
+        if let Some((channel, value)) = self
+            .impedances
+            .iter()
+            .find(|(channel, value)| channel.is_empty() || !value.is_finite() || **value < 0.0)
+        {
+            return Err(format!("Invalid impedance of the electrode '{}': {}", channel, value));
+        }
+
+        Ok(())
+    }
+
+    /// The snapshot with its impedances converted to another unit.
+    pub fn in_unit(&self, unit: ImpedanceUnit) -> Self {
+        ImpedanceSnapshot {
+            impedances: self
+                .impedances
+                .iter()
+                .map(|(channel, value)| (channel.clone(), value * self.unit.ohms() / unit.ohms()))
+                .collect(),
+            unit,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_converts_between_units() {
+        let readings = [("T3".to_string(), 12), ("O1".to_string(), 1500)].into_iter().collect();
+        let snapshot = ImpedanceSnapshot::from_readings(&readings, ImpedanceUnit::Kiloohms, 42).unwrap();
+
+        let in_ohms = snapshot.in_unit(ImpedanceUnit::Ohms);
+        assert_eq!(in_ohms.unit, ImpedanceUnit::Ohms);
+        assert_eq!(in_ohms.impedances["T3"], 12_000.0);
+        assert_eq!(in_ohms.impedances["O1"], 1_500_000.0);
+        assert_eq!(in_ohms.timestamp, 42);
+
+        assert_eq!(in_ohms.in_unit(ImpedanceUnit::Kiloohms), snapshot);
+        assert_eq!(ImpedanceUnit::default().symbol(), "kΩ");
+    }
+
+    #[test]
+    fn test_snapshot_rejects_unmeasurable_readings() {
+        let empty = ImpedanceSnapshot::from_readings(&HashMap::new(), ImpedanceUnit::Kiloohms, 0);
+        assert!(empty.unwrap_err().contains("no electrode"));
+
+        let mut snapshot = ImpedanceSnapshot {
+            impedances: [("T3".to_string(), 10.0)].into_iter().collect(),
+            unit: ImpedanceUnit::Ohms,
+            timestamp: 0,
+        };
+        assert!(snapshot.validate().is_ok());
+
+        snapshot.impedances.insert("T4".to_string(), f32::NAN);
+        assert!(snapshot.validate().unwrap_err().contains("'T4'"));
+
+        snapshot.impedances.insert("T4".to_string(), -1.0);
+        assert!(snapshot.validate().is_err());
+    }
+}
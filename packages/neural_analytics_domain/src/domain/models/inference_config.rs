@@ -0,0 +1,33 @@
+/// Runtime the ONNX model is run with, tract unless the core was built with another one.
+///
+/// tract is pure Rust and runs on the CPU only. ONNX Runtime, built with the `ort` feature
+/// of the domain crate, can run the model on a GPU through the `execution_provider`, the
+/// inference being the longest stage of the processing of a window.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InferenceConfig {
+    pub backend: InferenceBackend,
+    /// Device ONNX Runtime runs the model on, tract always running it on the CPU.
+    pub execution_provider: ExecutionProvider,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InferenceBackend {
+    #[default]
+    Tract,
+    /// ONNX Runtime, needing the `ort` feature.
+    Ort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionProvider {
+    #[default]
+    Cpu,
+    /// NVIDIA GPUs, needing the `ort-cuda` feature.
+    Cuda,
+    /// DirectX 12 GPUs on Windows, needing the `ort-directml` feature.
+    #[serde(rename = "directml")]
+    DirectMl,
+}
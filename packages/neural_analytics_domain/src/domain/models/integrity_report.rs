@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+/// Breach of the integrity of the captured data, which aborts the session in strict mode.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityViolation {
+    /// The board skipped package numbers, the samples in between were lost on the way.
+    SequenceGap { expected: u32, found: u32 },
+    /// Samples still missing after the channel gains, counted per channel, which the
+    /// window assembler would interpolate.
+    NonFiniteSamples { channels: HashMap<String, usize> },
+    /// The window was discarded for missing too many samples.
+    DiscardedWindow { reason: String },
+    /// The board clock drifted from the host clock beyond `clock_drift_threshold_ms`.
+    ClockDrift { drift_ms: f64, threshold_ms: u64 },
+}
+
+impl IntegrityViolation {
+    /// Name of the violation, as labelled in the metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            IntegrityViolation::SequenceGap { .. } => "sequence_gap",
+            IntegrityViolation::NonFiniteSamples { .. } => "non_finite_samples",
+            IntegrityViolation::DiscardedWindow { .. } => "discarded_window",
+            IntegrityViolation::ClockDrift { .. } => "clock_drift",
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrityViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityViolation::SequenceGap { expected, found } => {
+                write!(f, "package {} expected, {} received", expected, found)
+            }
+            IntegrityViolation::NonFiniteSamples { channels } => {
+                let mut channels: Vec<_> = channels.iter().collect();
+                channels.sort();
+
+                let counts: Vec<_> = channels
+                    .into_iter()
+                    .map(|(channel, count)| format!("{} in {}", count, channel))
+                    .collect();
+                write!(f, "non finite samples after filtering: {}", counts.join(", "))
+            }
+            IntegrityViolation::DiscardedWindow { reason } => write!(f, "window discarded: {}", reason),
+            IntegrityViolation::ClockDrift { drift_ms, threshold_ms } => {
+                write!(f, "clock drift of {:.0} ms, over the {} ms threshold", drift_ms, threshold_ms)
+            }
+        }
+    }
+}
+
+/// Report of the violation that aborted a session in strict mode.
+///
+/// Journaled and sent to the frontends, so the dataset can be traced back to the
+/// session, the headset and the window where its integrity was lost.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IntegrityReport {
+    pub violation: IntegrityViolation,
+    /// Unix epoch milliseconds when the violation was detected.
+    pub timestamp: i64,
+    pub session_id: u64,
+    /// Window the violation was found in.
+    pub window_id: u64,
+    /// Windows of the session captured before the violation.
+    pub windows: u64,
+    pub device_id: Option<String>,
+    pub firmware_version: Option<String>,
+    /// Recording of the session, its samples up to the violation are intact.
+    pub recording_path: Option<String>,
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use super::{
+    bulb_state::BulbState, causality_record::CausalityRecord, channel_config::ChannelConfig,
+    experiment_config::ExperimentConfig, integrity_report::IntegrityReport,
+    operator_action::OperatorActionRecord,
+};
+
+/// Prediction of a window as recorded in the session journal.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PredictionRecord {
+    pub window_id: u64,
+    /// Class the model predicted for the window alone.
+    pub prediction: String,
+    /// Whether the prediction was reused from an identical previous window.
+    pub is_cached: bool,
+    /// Class the recent predictions agree on once this one is added, `unknown` if they differ.
+    pub smoothed: String,
+}
+
+/// Explanation of a prediction as recorded in the session journal.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExplanationRecord {
+    pub window_id: u64,
+    pub predicted_class: String,
+    /// Probability of the predicted class lost when each channel is occluded.
+    pub channel_contributions: HashMap<String, f32>,
+}
+
+/// Context of the core rebuilt from the entries of a session journal up to a point in time.
+///
+/// The journaled events are applied again to a context of the core, as the core applied
+/// them, and this is what it holds once they were.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct ReplayedContext {
+    /// Unix epoch milliseconds of the last entry applied.
+    pub timestamp: Option<i64>,
+    /// State the state machine was in, `None` until the journal records a transition.
+    pub state: Option<String>,
+    pub session_id: u64,
+    pub channel_config: ChannelConfig,
+    /// Settings of the pipeline the current session started with.
+    pub experiment: Option<ExperimentConfig>,
+    pub window_id: u64,
+    pub predicted_window_id: Option<u64>,
+    /// Predictions the smoothing holds, oldest first.
+    pub recent_predictions: Vec<String>,
+    /// Class the recent predictions agree on, `unknown` if they differ.
+    pub smoothed_prediction: String,
+    pub bulb_state: Option<BulbState>,
+    pub last_explanation: Option<ExplanationRecord>,
+    pub last_actuation: Option<CausalityRecord>,
+    /// Operator actions recorded in the journal so far, oldest first.
+    pub operator_actions: Vec<OperatorActionRecord>,
+    pub last_integrity_violation: Option<IntegrityReport>,
+    pub log_level: Option<String>,
+    /// Entries of kinds this version does not replay, or whose payload could not be read.
+    pub skipped_entries: u64,
+}
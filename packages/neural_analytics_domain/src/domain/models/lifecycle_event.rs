@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use super::{event_routing::EventSeverity, experiment_config::ExperimentConfig};
+
+/// Key moments of a headset session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleEventKind {
+    SessionStarted,
+    Calibrated,
+    Disconnected,
+    SummaryReady,
+}
+
+impl LifecycleEventKind {
+    /// Severity of the event, routed to the lifecycle webhook by `EventRouting::webhook`.
+    pub fn severity(&self) -> EventSeverity {
+        match self {
+            LifecycleEventKind::Disconnected => EventSeverity::Warning,
+            _ => EventSeverity::Info,
+        }
+    }
+}
+
+/// Usage of the headset over a session.
+///
+/// Timestamps are Unix epoch milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SessionSummary {
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    /// EEG windows extracted, discarded ones excluded.
+    pub windows: u64,
+    /// Predictions made for each class.
+    pub predictions: HashMap<String, u64>,
+    /// Confirmed bulb actuations.
+    pub actuations: u64,
+    /// Settings of the pipeline the session ran with, taken when it started.
+    pub experiment: Option<ExperimentConfig>,
+}
+
+/// Lifecycle event reported to the tools tracking the usage of the installation.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LifecycleEvent {
+    pub kind: LifecycleEventKind,
+    /// Unix epoch milliseconds.
+    pub timestamp: i64,
+    pub session_id: u64,
+    pub device_id: Option<String>,
+    /// Firmware of the headset, kept to debug the reports from the field.
+    pub firmware_version: Option<String>,
+    pub user_profile: Option<String>,
+    /// Set on `SummaryReady` only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<SessionSummary>,
+}
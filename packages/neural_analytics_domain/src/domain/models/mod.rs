@@ -0,0 +1,50 @@
+pub mod band_powers;
+pub mod benchmark_report;
+pub mod bulb_connection;
+pub mod bulb_state;
+pub mod calibration_config;
+pub mod calibration_thresholds;
+pub mod calibration_updates;
+pub mod causality_record;
+pub mod channel_config;
+pub mod context_snapshot;
+pub mod core_config;
+pub mod core_health;
+pub mod device_timings;
+pub mod diagnostic_bundle;
+pub mod display_preferences;
+pub mod eeg_work_modes;
+pub mod event_data;
+pub mod event_internals;
+pub mod event_routing;
+pub mod exhibition;
+pub mod experiment_config;
+pub mod guided_protocol;
+pub mod headset_reading;
+pub mod impedance_snapshot;
+pub mod inference_config;
+pub mod integrity_report;
+pub mod journal_replay;
+pub mod lifecycle_event;
+pub mod notification;
+pub mod operation_progress;
+pub mod operator_action;
+pub mod payload_policy;
+pub mod prediction_action;
+pub mod prediction_smoothing;
+pub mod preprocessing_spec;
+pub mod published_prediction;
+pub mod recording_config;
+pub mod ring_buffer;
+pub mod runtime_config;
+pub mod scheduling_config;
+pub mod session_stats;
+pub mod signal_quality;
+pub mod signal_unit;
+pub mod soak_report;
+pub mod synthetic_signal;
+pub mod trash_streak;
+pub mod ui_hint;
+pub mod window_overlap;
+pub mod webhook_notification;
+pub mod window_prediction;
@@ -0,0 +1,12 @@
+use super::event_data::EventData;
+
+/// External event derived from an internal event the context applied.
+///
+/// The context queues them while it changes, and the state machine publishes
+/// them to the frontends once the context is released, so the frontends only
+/// ever see state the context already holds.
+#[derive(Clone)]
+pub(crate) struct Notification {
+    pub name: &'static str,
+    pub data: EventData,
+}
@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Long-running operation of the core, reported to the frontends while it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    /// Search and connection of the headset, up to the BrainFlow timeout.
+    HeadsetConnection,
+    /// Load of the ONNX model, at startup or when the watcher reloads it.
+    ModelLoad,
+}
+
+/// Stage of a long-running operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+/// Progress of a long-running operation.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OperationProgress {
+    pub operation: OperationKind,
+    pub status: OperationStatus,
+    /// Completed fraction, from 0 to 1, or `None` when it cannot be estimated.
+    pub fraction: Option<f32>,
+    /// Whether `cancel_operation` stops it.
+    pub is_cancellable: bool,
+}
+
+impl OperationProgress {
+    /// Progress of an operation still running.
+    pub fn running(operation: OperationKind, fraction: Option<f32>, is_cancellable: bool) -> Self {
+        Self {
+            operation,
+            status: OperationStatus::Running,
+            fraction: fraction.map(|fraction| fraction.clamp(0.0, 1.0)),
+            is_cancellable,
+        }
+    }
+
+    /// Last progress of an operation, once it finished, failed or was cancelled.
+    pub fn ended(operation: OperationKind, status: OperationStatus) -> Self {
+        Self {
+            operation,
+            status,
+            fraction: (status == OperationStatus::Finished).then_some(1.0),
+            is_cancellable: false,
+        }
+    }
+}
+
+/// Flag shared between the frontends and the operation in progress, to stop it early.
+///
+/// Clones share the same flag. Operations reset it when they start, so a cancellation
+/// requested while nothing was running does not stop the next one.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
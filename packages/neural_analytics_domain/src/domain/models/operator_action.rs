@@ -0,0 +1,29 @@
+/// Intervention of the operator from a frontend.
+///
+/// The core does not act on it, it is recorded in the session journal so the
+/// analysis of the EEG data can account for the interventions.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum OperatorAction {
+    /// The capture was paused, journaled by the core when paused through the `CoreHandle`.
+    Pause,
+    /// The capture was resumed, journaled by the core when resumed through the `CoreHandle`.
+    Resume,
+    Recalibrate,
+    /// Free text note, e.g. "subject moved".
+    Annotation { text: String },
+    CancelOperation,
+    ResumeHeadsetSearch,
+    /// A suggested impedance threshold was accepted.
+    AcceptThresholds { max_impedance: u16 },
+}
+
+/// Operator action as recorded in the session journal, next to the EEG window it happened at.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OperatorActionRecord {
+    pub session_id: u64,
+    /// Last EEG window extracted when the action happened.
+    pub window_id: u64,
+    #[serde(flatten)]
+    pub action: OperatorAction,
+}
@@ -0,0 +1,46 @@
+/// Destination of the traces of the events received by a frontend, see `EventTraceRecorder`.
+pub const EVENT_TRACE_DESTINATION: &str = "event_trace";
+
+/// Destination of the predictions published to the MQTT broker.
+pub const MQTT_DESTINATION: &str = "mqtt";
+
+/// Destination of the responses of the remote API.
+pub const REMOTE_API_DESTINATION: &str = "remote_api";
+
+/// Shape of the EEG windows carried by the events sent to a destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadMode {
+    /// Every sample of the windows.
+    #[default]
+    Full,
+    /// One sample of every `decimation` of each window.
+    Decimated,
+    /// No samples, only the count, the minimum, the maximum and the mean of each channel.
+    SummaryOnly,
+}
+
+/// Size of the events a destination receives, for the bridges forwarding them over slow links.
+///
+/// The events are serialized in the shape of `mode`; one over `max_bytes` falls back to
+/// the decimated windows, then to their summaries, the rest of the event being kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PayloadPolicy {
+    pub mode: PayloadMode,
+    /// Samples of a window kept one in every `decimation` by the decimated shape.
+    pub decimation: usize,
+    /// Largest event, in bytes, before falling back to a smaller shape. `None` for no limit.
+    pub max_bytes: Option<usize>,
+}
+
+impl Default for PayloadPolicy {
+    /// Full windows without limit, as the events have always been forwarded.
+    fn default() -> Self {
+        Self {
+            mode: PayloadMode::Full,
+            decimation: 4,
+            max_bytes: None,
+        }
+    }
+}
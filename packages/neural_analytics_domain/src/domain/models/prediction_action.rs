@@ -0,0 +1,10 @@
+/// Action to take when the model settles on a prediction class.
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PredictionAction {
+    TurnOn,
+    TurnOff,
+    Color { red: u8, green: u8, blue: u8 },
+    Webhook { url: String },
+    Nothing,
+}
@@ -0,0 +1,37 @@
+/// Span of the predictions smoothed before acting on them.
+///
+/// The predictions of the last `window_ms` milliseconds must agree to act on them.
+/// Their number follows the cadence of the windows, so the same span is smoothed
+/// whatever the sampling rate of the headset, within `min_predictions` and
+/// `max_predictions`.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PredictionSmoothing {
+    pub window_ms: u64,
+    pub min_predictions: usize,
+    pub max_predictions: usize,
+}
+
+impl Default for PredictionSmoothing {
+    /// Six predictions at the 250 Hz of the BrainBit headset, as the fixed buffer it replaces.
+    fn default() -> Self {
+        Self {
+            window_ms: 1_500,
+            min_predictions: 2,
+            max_predictions: 32,
+        }
+    }
+}
+
+impl PredictionSmoothing {
+    /// Number of predictions covering `window_ms` at the given interval between windows.
+    pub fn predictions_for(&self, interval_ms: f64) -> usize {
+        let predictions = if interval_ms > 0.0 {
+            (self.window_ms as f64 / interval_ms).round() as usize
+        } else {
+            self.max_predictions
+        };
+
+        predictions.clamp(self.min_predictions.max(1), self.max_predictions.max(self.min_predictions).max(1))
+    }
+}
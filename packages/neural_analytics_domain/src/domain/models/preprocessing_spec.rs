@@ -0,0 +1,114 @@
+use neural_analytics_signal_math as signal_math;
+
+/// Version of the layout of the preprocessing spec this core reads.
+pub const PREPROCESSING_SPEC_VERSION: u32 = 1;
+
+/// Normalization applied to each channel of a window before the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Normalization {
+    /// Zero mean and unit standard deviation.
+    ZScore,
+    /// Rescaled to the `[0, 1]` range.
+    MinMax,
+}
+
+impl Normalization {
+    /// Normalizes the samples of a channel in place.
+    pub fn apply(&self, values: &mut [f32]) {
+        match self {
+            Normalization::ZScore => signal_math::normalize(values),
+            Normalization::MinMax => signal_math::min_max_normalize(values),
+        }
+    }
+}
+
+/// Preprocessing the model was trained with, exported by the training pipeline next to
+/// the model as `neural_analytics.preprocessing.json`, so the core preprocesses the
+/// windows the same way.
+///
+/// The defaults are the preprocessing of the core before the spec existed, used for the
+/// models exported without one.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PreprocessingSpec {
+    /// Layout of the spec, checked against `PREPROCESSING_SPEC_VERSION`.
+    pub version: u32,
+    /// Normalization of each channel of a window.
+    pub normalization: Normalization,
+    /// Samples of each channel in a window, the second dimension of the input of the model.
+    pub window_samples: usize,
+    /// Hardware names of the channels, in the order of the last dimension of the input.
+    pub channels: Vec<String>,
+}
+
+impl Default for PreprocessingSpec {
+    fn default() -> Self {
+        PreprocessingSpec {
+            version: PREPROCESSING_SPEC_VERSION,
+            normalization: Normalization::ZScore,
+            window_samples: 62,
+            channels: ["T3", "T4", "O1", "O2"].iter().map(|channel| channel.to_string()).collect(),
+        }
+    }
+}
+
+impl PreprocessingSpec {
+    /// Parses a spec, rejecting the versions this core does not know and the empty windows.
+    pub fn parse(content: &str) -> Result<PreprocessingSpec, String> {
+        // The version is read first, so a newer spec is not reported as a malformed one
+        let version = serde_json::from_str::<serde_json::Value>(content)
+            .map_err(|e| e.to_string())?
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or("missing or invalid `version`")?;
+
+        if version != PREPROCESSING_SPEC_VERSION as u64 {
+            return Err(format!(
+                "unsupported version {}, this core reads version {}",
+                version, PREPROCESSING_SPEC_VERSION
+            ));
+        }
+
+        let spec: PreprocessingSpec = serde_json::from_str(content).map_err(|e| e.to_string())?;
+
+        if spec.window_samples == 0 || spec.channels.is_empty() {
+            return Err("expected at least one sample and one channel".to_string());
+        }
+
+        Ok(spec)
+    }
+
+    /// Shape of the input tensor of the model for a batch of one window.
+    pub fn input_shape(&self) -> [usize; 3] {
+        [1, self.window_samples, self.channels.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec_of_the_training_pipeline() {
+        let spec = PreprocessingSpec::parse(
+            r#"{"version": 1, "normalization": "min_max", "window_samples": 128, "channels": ["O1", "O2"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.normalization, Normalization::MinMax);
+        assert_eq!(spec.input_shape(), [1, 128, 2]);
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported_specs() {
+        let newer = PreprocessingSpec::parse(r#"{"version": 2, "normalization": "robust"}"#).unwrap_err();
+        assert_eq!(newer, "unsupported version 2, this core reads version 1");
+
+        let empty = PreprocessingSpec::parse(
+            r#"{"version": 1, "normalization": "z_score", "window_samples": 62, "channels": []}"#,
+        )
+        .unwrap_err();
+        assert!(empty.contains("one channel"), "{}", empty);
+    }
+}
@@ -0,0 +1,11 @@
+/// Prediction published to the external systems reacting to it, such as home-automation
+/// controllers subscribed to an MQTT topic.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PublishedPrediction {
+    /// Class predicted by the model, e.g. `red`.
+    pub prediction: String,
+    /// Window the prediction was made for, `None` for the windows without an id.
+    pub window_id: Option<u64>,
+    /// Unix epoch milliseconds.
+    pub timestamp: i64,
+}
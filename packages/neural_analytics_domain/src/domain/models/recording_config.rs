@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use super::guided_protocol::GuidedProtocol;
+
+/// Format of the files the EEG windows are recorded to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    /// A JSON line per window, with the samples of every channel.
+    #[default]
+    JsonLines,
+    /// A row per sample, with a column per channel, for spreadsheets and analysis tools.
+    Csv,
+}
+
+impl RecordingFormat {
+    /// Extension of the files of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordingFormat::JsonLines => "jsonl",
+            RecordingFormat::Csv => "csv",
+        }
+    }
+
+    /// Format of a recording file, from its extension, JSON Lines unless it is `.csv`.
+    pub fn of_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => RecordingFormat::Csv,
+            _ => RecordingFormat::JsonLines,
+        }
+    }
+}
+
+/// Recording of the raw EEG of every session, without waiting for a recording request.
+///
+/// Each session is recorded to `eeg.<extension>` in a directory of its own, named after
+/// the session and when it started, under `directory`. A recording requested by the
+/// operator takes precedence until it is stopped.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecordingConfig {
+    /// Directory of the session directories. `None` only records on request.
+    pub directory: Option<String>,
+    pub format: RecordingFormat,
+    /// Labels the windows of the JSON Lines recordings with the step of the protocol
+    /// active when they were extracted, counted from the start of each recording.
+    /// `None` leaves them unlabeled.
+    pub guided_protocol: Option<GuidedProtocol>,
+}
@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+
+/// Fixed-capacity buffer keeping the newest values pushed into it, the oldest dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RingBuffer<T> {
+    values: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T: Clone> RingBuffer<T> {
+    /// Creates an empty buffer of `capacity` values, at least one.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+
+        Self {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Appends a value, dropping the oldest one once the buffer is full.
+    pub fn push(&mut self, value: T) {
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// Values in the buffer, the oldest first.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.values.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_keeps_the_newest_values() {
+        let mut buffer = RingBuffer::new(3);
+        (0..5).for_each(|value| buffer.push(value));
+
+        assert!(buffer.is_full());
+        assert_eq!(buffer.to_vec(), vec![2, 3, 4]);
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+    }
+}
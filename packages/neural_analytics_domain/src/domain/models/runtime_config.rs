@@ -0,0 +1,14 @@
+/// Async runtime the core runs its tasks on.
+///
+/// By default the core spawns its tasks on the tokio runtime of the caller, like any
+/// other task of the application. Applications embedding the core, e.g. as a plugin
+/// of a host without tokio, give it a runtime of its own, owned by the `CoreHandle`
+/// and shut down with it.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    /// Run the core on a dedicated runtime instead of the runtime of the caller.
+    pub dedicated: bool,
+    /// Worker threads of the dedicated runtime. `None` starts one per CPU core.
+    pub worker_threads: Option<usize>,
+}
@@ -0,0 +1,24 @@
+/// Scheduling hints of the thread running the capture and the inference.
+///
+/// Meant for dedicated kiosk hardware, where the jitter of the window timing matters
+/// more than sharing the CPU. Without hints the state machine runs on the tokio runtime
+/// of the application, like any other task.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulingConfig {
+    /// CPU cores the thread may be pinned to, the first one the machine has being used,
+    /// as a thread is pinned to a single core on every platform. Empty lets the system
+    /// schedule it anywhere.
+    pub pinned_cores: Vec<usize>,
+    /// Real-time priority of the thread, from 1 to 99, `SCHED_FIFO` on Unix and mapped to
+    /// the thread priorities of Windows. `None` keeps the default policy. Requires the
+    /// `CAP_SYS_NICE` capability or an `rtprio` limit on Linux.
+    pub realtime_priority: Option<u8>,
+}
+
+impl SchedulingConfig {
+    /// Whether any hint is set, so the state machine needs its own thread.
+    pub fn is_enabled(&self) -> bool {
+        !self.pinned_cores.is_empty() || self.realtime_priority.is_some()
+    }
+}
@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+/// Statistics of a session, computed by the core so the lab dashboards do not have to
+/// recompute them from the recordings and the journal.
+///
+/// Timestamps are Unix epoch milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SessionStats {
+    pub session_id: u64,
+    pub started_at: i64,
+    /// `None` while the session is open.
+    pub ended_at: Option<i64>,
+    /// EEG windows extracted, discarded ones excluded.
+    pub windows: u64,
+    /// Predictions made for each class.
+    pub predictions: HashMap<String, u64>,
+    /// Share of the predictions of each class, in percent.
+    pub class_percentages: HashMap<String, f32>,
+    /// Average probability of the predicted classes, `None` when the model does not report it.
+    /// Predictions reused from an identical window are left out.
+    pub mean_confidence: Option<f32>,
+    /// Disconnects of the headset during the session.
+    pub disconnects: u64,
+    /// Milliseconds each calibration of the electrodes took, in order. Reused calibrations
+    /// take no time and are not listed.
+    pub calibration_durations_ms: Vec<i64>,
+}
@@ -0,0 +1,56 @@
+use super::signal_unit::SignalUnit;
+
+// Spread, in µV, under which a channel carries no signal, e.g. a detached electrode
+const FLAT_STD_MICROVOLTS: f32 = 0.5;
+
+// Spread, in µV, over which a channel is dominated by artifacts of a poor contact
+const NOISY_STD_MICROVOLTS: f32 = 150.0;
+
+/// Quality of the signal of an electrode, checked instead of its impedance on the
+/// headsets that cannot measure it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalQuality {
+    Good,
+    /// The samples barely change, the electrode does not touch the skin.
+    Flat,
+    /// The samples swing far beyond EEG amplitudes.
+    Noisy,
+}
+
+impl SignalQuality {
+    /// Classifies a window of samples of one channel by their standard deviation.
+    ///
+    /// Samples without a unit can only be told flat, their amplitude is unknown.
+    pub fn of(samples: &[f32], unit: SignalUnit) -> Self {
+        if samples.is_empty() {
+            return SignalQuality::Flat;
+        }
+
+        let std = neural_analytics_signal_math::std_dev(samples);
+
+        match unit {
+            SignalUnit::Microvolts if std < FLAT_STD_MICROVOLTS => SignalQuality::Flat,
+            SignalUnit::Microvolts if std > NOISY_STD_MICROVOLTS => SignalQuality::Noisy,
+            SignalUnit::Normalized if std <= f32::EPSILON => SignalQuality::Flat,
+            _ => SignalQuality::Good,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_of_classifies_by_the_spread_of_the_samples() {
+        let eeg: Vec<f32> = (0..62).map(|i| (i as f32 * 0.5).sin() * 40.0).collect();
+        let artifact: Vec<f32> = (0..62).map(|i| if i % 2 == 0 { 500.0 } else { -500.0 }).collect();
+
+        assert_eq!(SignalQuality::of(&eeg, SignalUnit::Microvolts), SignalQuality::Good);
+        assert_eq!(SignalQuality::of(&[3.0; 62], SignalUnit::Microvolts), SignalQuality::Flat);
+        assert_eq!(SignalQuality::of(&artifact, SignalUnit::Microvolts), SignalQuality::Noisy);
+        assert_eq!(SignalQuality::of(&artifact, SignalUnit::Normalized), SignalQuality::Good);
+        assert_eq!(SignalQuality::of(&[], SignalUnit::Normalized), SignalQuality::Flat);
+    }
+}
@@ -0,0 +1,19 @@
+/// Unit of the EEG samples returned by a headset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalUnit {
+    Microvolts,
+    /// Unitless values, e.g. scaled by the headset adapter.
+    #[default]
+    Normalized,
+}
+
+impl SignalUnit {
+    /// Symbol of the unit, for axis labels and exports.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            SignalUnit::Microvolts => "µV",
+            SignalUnit::Normalized => "a.u.",
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use super::benchmark_report::LatencyStats;
+
+/// Limits a soak run must stay within, exceeded only by a leak or a degrading pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SoakThresholds {
+    /// Growth of the resident memory of the process after the warm-up.
+    pub max_memory_growth_bytes: i64,
+    /// Growth of the buffers of the context after the warm-up, as estimated by its memory budget.
+    pub max_buffers_growth_bytes: i64,
+    /// Increase of the median latency of a window between the start and the end of the run.
+    pub max_latency_drift_ms: f64,
+}
+
+impl Default for SoakThresholds {
+    fn default() -> Self {
+        Self {
+            max_memory_growth_bytes: 32 * 1024 * 1024,
+            max_buffers_growth_bytes: 1024 * 1024,
+            max_latency_drift_ms: 5.0,
+        }
+    }
+}
+
+/// Behaviour of the pipeline over a long session, measured by `run_soak_test`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SoakReport {
+    /// Windows processed, after the warm-up.
+    pub windows: usize,
+    /// Windows whose extraction or prediction failed.
+    pub failed_windows: usize,
+    /// Length of the recording the windows amount to, at the rate of the headset.
+    pub simulated_secs: f64,
+    pub elapsed_ms: f64,
+    /// Whether the windows were predicted, which needs the model to be loaded.
+    pub is_inference_enabled: bool,
+    /// Growth of the resident memory of the process, `None` where it cannot be read.
+    pub memory_growth_bytes: Option<i64>,
+    pub buffers_growth_bytes: i64,
+    /// Processing of a window during the first tenth of the run.
+    pub early_latency: LatencyStats,
+    /// Processing of a window during the last tenth of the run.
+    pub late_latency: LatencyStats,
+}
+
+impl SoakReport {
+    /// Increase of the median latency of a window over the run.
+    pub fn latency_drift_ms(&self) -> f64 {
+        self.late_latency.p50_ms - self.early_latency.p50_ms
+    }
+
+    /// Describes every threshold the run exceeded, none when it passed.
+    pub fn violations(&self, thresholds: &SoakThresholds) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        if let Some(growth) = self.memory_growth_bytes.filter(|growth| *growth > thresholds.max_memory_growth_bytes) {
+            violations.push(format!(
+                "The resident memory grew {} bytes, more than {}",
+                growth, thresholds.max_memory_growth_bytes
+            ));
+        }
+
+        if self.buffers_growth_bytes > thresholds.max_buffers_growth_bytes {
+            violations.push(format!(
+                "The context buffers grew {} bytes, more than {}",
+                self.buffers_growth_bytes, thresholds.max_buffers_growth_bytes
+            ));
+        }
+
+        if self.latency_drift_ms() > thresholds.max_latency_drift_ms {
+            violations.push(format!(
+                "The median window latency drifted {:.3} ms, more than {:.3}",
+                self.latency_drift_ms(),
+                thresholds.max_latency_drift_ms
+            ));
+        }
+
+        if self.failed_windows > 0 {
+            violations.push(format!("{} of {} windows failed", self.failed_windows, self.windows));
+        }
+
+        violations
+    }
+}
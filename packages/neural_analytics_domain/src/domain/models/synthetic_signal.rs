@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+/// Amplitudes of the components of the synthetic EEG of a channel, in microvolts.
+///
+/// The rhythms drift in frequency within their band, so their power spreads over it
+/// as in a real recording instead of a single line of the spectrum.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BandAmplitudes {
+    /// Standard deviation of the 1/f background of the channel.
+    pub pink_noise: f32,
+    /// Peak of the theta rhythm, around 6 Hz.
+    pub theta: f32,
+    /// Peak of the alpha bursts, around 10 Hz, waxing and waning over a few seconds.
+    pub alpha: f32,
+    /// Peak of the beta rhythm, around 20 Hz.
+    pub beta: f32,
+    /// Peak of the blinks picked up by the electrode.
+    pub blink: f32,
+}
+
+impl Default for BandAmplitudes {
+    /// A temporal electrode of a relaxed user with the eyes open.
+    fn default() -> Self {
+        Self {
+            pink_noise: 8.0,
+            theta: 4.0,
+            alpha: 8.0,
+            beta: 3.0,
+            blink: 60.0,
+        }
+    }
+}
+
+/// Signal streamed by the synthetic headset of the mock mode.
+///
+/// A 1/f background with theta and beta rhythms, alpha bursts and occasional blinks,
+/// at the 250 Hz of the BrainBit headset, so the filters and plots downstream behave
+/// as with a real one. The occipital electrodes get the stronger alpha they have on a
+/// real head, the temporal ones the larger blinks.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SyntheticSignalConfig {
+    /// Amplitudes of each electrode, keyed by its hardware name, such as `O1`. The
+    /// electrodes left out use `default_amplitudes`.
+    pub channels: HashMap<String, BandAmplitudes>,
+    pub default_amplitudes: BandAmplitudes,
+    /// Mean blinks per minute, at random times and on every electrode at once. `0` disables them.
+    pub blinks_per_minute: f32,
+    /// Seed of the noise, the bursts and the blinks, so a run can be reproduced.
+    pub seed: u64,
+}
+
+impl Default for SyntheticSignalConfig {
+    fn default() -> Self {
+        let occipital = BandAmplitudes {
+            alpha: 25.0,
+            blink: 15.0,
+            ..BandAmplitudes::default()
+        };
+
+        Self {
+            channels: [("O1".to_string(), occipital), ("O2".to_string(), occipital)].into_iter().collect(),
+            default_amplitudes: BandAmplitudes::default(),
+            blinks_per_minute: 6.0,
+            seed: 0,
+        }
+    }
+}
+
+impl SyntheticSignalConfig {
+    /// Amplitudes of the components of an electrode.
+    pub fn amplitudes(&self, electrode: &str) -> BandAmplitudes {
+        self.channels.get(electrode).copied().unwrap_or(self.default_amplitudes)
+    }
+}
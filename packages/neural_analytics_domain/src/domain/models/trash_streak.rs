@@ -0,0 +1,23 @@
+/// Streak of garbage predictions pausing the inference, as the electrodes likely lost contact.
+///
+/// After `limit` consecutive predictions of one of the `classes`, the core stops predicting
+/// and actuating until the headset is recalibrated. A prediction counts as garbage when
+/// either its class or the smoothed one is in `classes`, so a model flip-flopping between
+/// classes counts too, being smoothed to "unknown".
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashStreak {
+    /// Consecutive garbage predictions pausing the inference. `0` never pauses it.
+    pub limit: u64,
+    pub classes: Vec<String>,
+}
+
+impl Default for TrashStreak {
+    /// About ten seconds of the windows of the BrainBit headset.
+    fn default() -> Self {
+        Self {
+            limit: 40,
+            classes: vec!["trash".to_string(), "unknown".to_string()],
+        }
+    }
+}
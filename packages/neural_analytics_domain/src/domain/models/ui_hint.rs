@@ -0,0 +1,24 @@
+/// Screen a frontend shows for a state of the core, whatever its toolkit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestedView {
+    /// The core is loading the model and running its diagnostics.
+    Loading,
+    /// Waiting for the user to put the headset on and turn it on.
+    Welcome,
+    /// Checking the contact of the electrodes.
+    Calibration,
+    /// Predicting the EEG windows.
+    Capture,
+}
+
+/// Presentation hints of the state a transition event enters, so frontends do not
+/// hard-code which screen follows each event.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UiHint {
+    pub suggested_view: SuggestedView,
+    /// The state waits on something the user cannot speed up, such as the headset search.
+    pub shows_spinner: bool,
+    /// Longest the state is expected to last, in milliseconds, when it is bounded.
+    pub expected_duration_ms: Option<u64>,
+}
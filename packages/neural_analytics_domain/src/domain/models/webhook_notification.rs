@@ -0,0 +1,8 @@
+/// Body posted to the webhook of a prediction mapped to a `Webhook` action.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WebhookNotification {
+    /// Class predicted by the model, e.g. `green`.
+    pub prediction: String,
+    /// RFC 3339 time of the notification.
+    pub timestamp: String,
+}
@@ -0,0 +1,22 @@
+/// Overlap between consecutive windows, to predict more often from the same stream.
+///
+/// Each window shares `overlap` of its samples with the previous one, e.g. `0.5` hops
+/// half a window and doubles the predictions. The overlap is lowered while the measured
+/// inference takes longer than the hop, plus `latency_headroom` of it, so the core never
+/// falls behind the headset.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowOverlap {
+    pub overlap: f32,
+    pub latency_headroom: f32,
+}
+
+impl Default for WindowOverlap {
+    /// No overlap, as the windows of the headset have always been predicted.
+    fn default() -> Self {
+        Self {
+            overlap: 0.0,
+            latency_headroom: 0.5,
+        }
+    }
+}
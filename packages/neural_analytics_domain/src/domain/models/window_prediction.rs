@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+/// Prediction of a window, with how confident the model is of it and how long it took.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WindowPrediction {
+    /// Class predicted by the model, e.g. `red`.
+    pub label: String,
+    /// Probability of the predicted class, from 0 to 1, `None` when the model does not report it.
+    pub confidence: Option<f32>,
+    /// Softmax probability of every class of the model, empty when it does not report them.
+    pub probabilities: HashMap<String, f32>,
+    /// Milliseconds the model took to predict the window.
+    pub latency_ms: f64,
+}
+
+impl WindowPrediction {
+    /// Takes the most probable class as the prediction, `None` without any class.
+    pub fn from_probabilities(probabilities: HashMap<String, f32>, latency_ms: f64) -> Option<Self> {
+        let (label, confidence) = probabilities
+            .iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(label, confidence)| (label.clone(), *confidence))?;
+
+        Some(Self {
+            label,
+            confidence: Some(confidence),
+            probabilities,
+            latency_ms,
+        })
+    }
+
+    /// Prediction of a model that only reports the class.
+    pub fn from_label(label: String, latency_ms: f64) -> Self {
+        Self {
+            label,
+            confidence: None,
+            probabilities: HashMap::new(),
+            latency_ms,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use crate::domain::models::{
+    device_timings::DeviceTimings, eeg_work_modes::WorkMode, impedance_snapshot::ImpedanceUnit, signal_unit::SignalUnit,
+};
+
+/// Samples per second of each channel, the rate of the BrainBit headset the model was
+/// trained on. The windows, the timing of the pipeline and the recordings assume it.
+pub const SAMPLE_RATE_HZ: f32 = 250.0;
+
+pub trait EegHeadsetPort: Send + Sync + 'static {
+    fn connect(&self) -> Result<(), String>;
+    fn is_connected(&self) -> bool;
+    fn disconnect(&mut self) -> Result<(), String>;
+    fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String>;
+    fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String>;
+    fn change_work_mode(&mut self, mode: WorkMode);
+    fn get_work_mode(&self) -> WorkMode;
+
+    /// Unit of the samples returned by `extract_raw_data`.
+    ///
+    /// Adapters convert the values of the board to microvolts when they know its scaling.
+    /// `Normalized`, the default, means the samples are unitless.
+    fn signal_unit(&self) -> SignalUnit {
+        SignalUnit::Normalized
+    }
+
+    /// Whether the board measures the impedance of the electrodes.
+    ///
+    /// Headsets that cannot are calibrated on the quality of their signal instead,
+    /// see `skip_calibration_without_impedance`. `true` by default.
+    fn supports_impedance(&self) -> bool {
+        true
+    }
+
+    /// Unit of the impedance returned by `extract_impedance_data`, kiloohms by default.
+    fn impedance_unit(&self) -> ImpedanceUnit {
+        ImpedanceUnit::Kiloohms
+    }
+
+    /// Board timestamp, in Unix epoch seconds, of the last sample returned by `extract_raw_data`.
+    ///
+    /// Compared against the host clock to detect drift. `None`, the default, means the
+    /// headset does not report timestamps.
+    fn last_sample_timestamp(&self) -> Option<f64> {
+        None
+    }
+
+    /// Charge of the battery of the device in percent, as of the last `extract_raw_data`.
+    ///
+    /// Reported to the frontends in the `CoreHealth`, so they warn before the headset
+    /// runs out. `None`, the default, means the headset does not report it.
+    fn battery_percent(&self) -> Option<u8> {
+        None
+    }
+
+    /// Package numbers the board stamped on the samples returned by `extract_raw_data`.
+    ///
+    /// Checked for gaps in strict mode, a counter wrapping back to 0 is not a gap.
+    /// `None`, the default, means the headset does not number its packages.
+    fn last_sequence_numbers(&self) -> Option<Vec<u32>> {
+        None
+    }
+
+    /// Identifier of the device, such as its MAC address.
+    ///
+    /// Used to start a new session when a different headset connects. `None`,
+    /// the default, means the headset cannot tell one device from another.
+    fn device_id(&self) -> Option<String> {
+        None
+    }
+
+    /// Firmware version of the device, read by `connect`.
+    ///
+    /// Checked against the versions the pipeline was tested with and recorded with
+    /// the session. `None`, the default, means the headset does not report it.
+    fn firmware_version(&self) -> Option<String> {
+        None
+    }
+
+    /// Delays the device needs to settle, measured by `connect`.
+    ///
+    /// Stored per device, so the next connection of the same headset skips the
+    /// measure. `None`, the default, means the headset uses fixed delays.
+    fn device_timings(&self) -> Option<DeviceTimings> {
+        None
+    }
+
+    /// Uses the delays measured on a previous connection, called before `connect`.
+    fn apply_device_timings(&self, _timings: DeviceTimings) {}
+
+    /// Samples `extract_raw_data` awaits and returns, the rest of the window being
+    /// carried over from the previous one so consecutive windows overlap.
+    ///
+    /// Adapters ignoring it, the default, keep returning whole windows, without overlap.
+    fn set_window_hop(&self, _samples: usize) {}
+
+    /// Sends a raw configuration command to the device, bypassing the adapter logic.
+    ///
+    /// Escape hatch for advanced users (gain changes, test signal mode...), the
+    /// adapter does not track the changes made through it.
+    ///
+    /// # Returns
+    /// The response of the device, or an error, which is the default for headsets
+    /// without raw command support.
+    fn send_raw_command(&self, command: &str) -> Result<String, String> {
+        Err(format!("Raw command '{}' not supported by this headset", command))
+    }
+
+    /// Starts dumping the raw frames of the board to a ring-buffer file, or stops it
+    /// with `None`, keeping the file.
+    ///
+    /// Helps diagnosing channel-index mapping problems across boards at runtime.
+    ///
+    /// # Returns
+    /// An error, which is the default for headsets without raw frames to dump.
+    fn set_frame_dump(&self, _path: Option<&str>) -> Result<(), String> {
+        Err("Frame dumps not supported by this headset".to_string())
+    }
+}
\ No newline at end of file
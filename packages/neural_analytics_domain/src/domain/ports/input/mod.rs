@@ -0,0 +1,2 @@
+pub mod eeg_headset;
+pub mod model_watcher;
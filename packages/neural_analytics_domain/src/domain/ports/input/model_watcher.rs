@@ -0,0 +1,12 @@
+/// Defines the interface for watching the model file, so the domain does not depend
+/// on the notifications of a file system.
+pub trait ModelWatcherPort: Send + Sync + 'static {
+    /// Starts watching the model file in the background.
+    ///
+    /// The adapter coalesces the burst of changes produced by a single write, calling
+    /// `on_change` once the file is written, from a thread of its own.
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`) when the watch cannot start.
+    fn watch(&self, model_path: &str, on_change: Box<dyn Fn() + Send>) -> Result<(), String>;
+}
@@ -0,0 +1,13 @@
+/// Defines the interface for writing the diagnostic bundles, so the domain does not
+/// depend on an archive format.
+pub trait BundleArchivePort: Send + Sync + 'static {
+    /// Writes the files of a diagnostic bundle to an archive, replacing it if it exists.
+    ///
+    /// # Arguments
+    /// * `path`: The archive to write.
+    /// * `files`: The name and the content of each file, in the order they are archived.
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`), naming the archive.
+    fn write(&self, path: &str, files: &[(&str, String)]) -> Result<(), String>;
+}
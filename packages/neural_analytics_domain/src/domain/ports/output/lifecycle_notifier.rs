@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use crate::domain::models::lifecycle_event::LifecycleEvent;
+
+/// Defines the interface for reporting the lifecycle of the headset sessions
+/// to external tools, such as lab management systems.
+#[async_trait]
+pub trait LifecycleNotifierPort: Send + Sync + 'static {
+    /// Reports a lifecycle event.
+    ///
+    /// Runs in the background, the core does not wait for the delivery, so the
+    /// adapter is free to retry as long as it needs.
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`).
+    async fn notify(&self, event: &LifecycleEvent) -> Result<(), String>;
+}
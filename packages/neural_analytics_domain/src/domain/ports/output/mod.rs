@@ -0,0 +1,8 @@
+pub mod bundle_archive;
+pub mod lifecycle_notifier;
+pub mod prediction_sink;
+pub mod prediction_webhook;
+pub mod recorder;
+pub mod smart_bulb;
+pub mod tensor_dump;
+pub mod thread_scheduler;
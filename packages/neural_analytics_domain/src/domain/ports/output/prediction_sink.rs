@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use crate::domain::models::published_prediction::PublishedPrediction;
+
+/// Defines the interface for publishing the predictions to external systems, so
+/// home-automation controllers other than the smart bulb can react to them.
+#[async_trait]
+pub trait PredictionSinkPort: Send + Sync + 'static {
+    /// Publishes a prediction.
+    ///
+    /// Called from the capture loop, so the adapter must not wait for the delivery;
+    /// a prediction that cannot be queued is reported as an error and dropped.
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`).
+    async fn publish(&self, prediction: &PublishedPrediction) -> Result<(), String>;
+}
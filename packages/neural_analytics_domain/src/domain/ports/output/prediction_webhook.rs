@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use crate::domain::models::webhook_notification::WebhookNotification;
+
+/// Defines the interface for notifying the predictions mapped to a `Webhook` action
+/// to the URL configured for them.
+#[async_trait]
+pub trait PredictionWebhookPort: Send + Sync + 'static {
+    /// Notifies a prediction to a URL.
+    ///
+    /// Called from the capture loop, so the adapter must not wait for the delivery;
+    /// it retries it in the background a bounded number of times, and a notification
+    /// that cannot be queued is reported as an error and dropped.
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`).
+    async fn notify(&self, url: &str, notification: &WebhookNotification) -> Result<(), String>;
+}
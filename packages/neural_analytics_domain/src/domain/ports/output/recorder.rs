@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use crate::domain::models::signal_unit::SignalUnit;
+
+/// Defines the interface for recording the extracted EEG windows, so the domain does
+/// not depend on a file format or on the file system.
+///
+/// A recording is either started explicitly, to a given file, or by the session, to
+/// the place the adapter records the sessions to. The explicit one takes precedence.
+pub trait RecorderPort: Send + Sync + 'static {
+    /// Starts recording to the given file, stopping the recording in progress first.
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`) when the file cannot be opened.
+    fn start(&mut self, path: &str) -> Result<(), String>;
+
+    /// Starts recording a session, if the adapter records the sessions and nothing is
+    /// being recorded.
+    ///
+    /// # Returns
+    /// The file the session will be recorded to, if any.
+    fn start_session(&mut self, session_id: u64) -> Option<String>;
+
+    /// Stops the recording of the session started by `start_session`, if it is the one in progress.
+    fn end_session(&mut self);
+
+    /// Continues the recording in progress in a new file for the given session.
+    ///
+    /// # Returns
+    /// The new file, `None` when not recording, or an error if it cannot be opened.
+    fn rotate(&mut self, session_id: u64) -> Result<Option<String>, String>;
+
+    /// Stops the recording in progress, returning the file it was written to.
+    fn stop(&mut self) -> Option<String>;
+
+    /// Returns the file being recorded to, if any.
+    fn path(&self) -> Option<&str>;
+
+    /// Sets the unit of the samples, as reported by the headset.
+    fn set_unit(&mut self, unit: SignalUnit);
+
+    /// Appends a window to the recording, if one is in progress.
+    ///
+    /// # Arguments
+    /// * `window_id`: The id of the window.
+    /// * `headset_data`: The samples of each channel.
+    /// * `sample_timestamp`: The acquisition time of the last sample, if the headset reports it.
+    fn record_window(&mut self, window_id: u64, headset_data: &HashMap<String, Vec<f32>>, sample_timestamp: Option<i64>);
+}
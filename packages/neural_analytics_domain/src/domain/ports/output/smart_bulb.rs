@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use crate::domain::models::{bulb_connection::BulbConnectionState, bulb_state::BulbState};
+
+/// Defines the interface for controlling a smart bulb.
+#[async_trait]
+pub trait SmartBulbPort: Send + Sync + 'static {
+    /// Changes the state of the smart bulb (on or off).
+    ///
+    /// # Arguments
+    /// * `state` - The desired state (`BulbOn` or `BulbOff`).
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`).
+    async fn change_state(&self, state: BulbState) -> Result<(), String>;
+
+    /// Changes the color of the smart bulb, turning it on if needed.
+    ///
+    /// # Returns
+    /// A Result indicating success (`Ok(())`) or failure (`Err(String)`), which is
+    /// the default for bulbs without color support.
+    async fn change_color(&self, red: u8, green: u8, blue: u8) -> Result<(), String> {
+        Err(format!(
+            "Color ({}, {}, {}) not supported by this smart bulb",
+            red, green, blue
+        ))
+    }
+
+    /// Tells the bulb the core is still driving it, although the last window did not change it.
+    ///
+    /// Postpones the auto-off of the adapters turning the bulb off once the core stops
+    /// driving it; a no-op for the others.
+    async fn refresh(&self) {}
+
+    /// State of the connection to the bulb, polled by the core to report it to the frontends.
+    ///
+    /// Adapters connecting in the background report their progress here. `Connected`,
+    /// the default, suits the bulbs that need no connection.
+    fn connection_state(&self) -> BulbConnectionState {
+        BulbConnectionState::Connected
+    }
+
+    /// Indicates whether there is no physical bulb behind this adapter,
+    /// so frontends can render a virtual one instead.
+    fn is_simulated(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,13 @@
+/// Defines the interface for dumping the tensors of the predictions, so the domain does
+/// not depend on the layout of the dump or on the file system.
+pub trait TensorDumpPort: Send + Sync + 'static {
+    /// Records the input tensor and the raw output of a prediction.
+    ///
+    /// A dump that cannot be written does not fail the prediction, the adapter reports it itself.
+    ///
+    /// # Arguments
+    /// * `input_shape`: The shape of the input tensor, e.g. `[1, 62, 4]`.
+    /// * `input`: The input tensor fed to the model, after the preprocessing, in row-major order.
+    /// * `output`: The logits returned by the model, before the softmax.
+    fn record(&self, input_shape: &[usize], input: &[f32], output: &[f32]);
+}
@@ -0,0 +1,14 @@
+use crate::domain::models::scheduling_config::SchedulingConfig;
+
+/// Defines the interface for pinning and prioritizing the thread of the state machine,
+/// so the domain does not depend on the scheduler of an operating system.
+pub trait ThreadSchedulerPort: Send + Sync + 'static {
+    /// Applies the scheduling hints to the calling thread.
+    ///
+    /// The hints are best effort: the thread keeps running with the default scheduling
+    /// when the system refuses them, e.g. without the privileges for a real-time priority.
+    ///
+    /// # Returns
+    /// The hints that could not be applied, empty if all of them were.
+    fn apply(&self, config: &SchedulingConfig) -> Vec<String>;
+}
@@ -0,0 +1,124 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Environment variable holding the directory the assets are read from, instead of searching them
+pub const ASSETS_DIR_VARIABLE: &str = "NEURAL_ANALYTICS_ASSETS_DIR";
+
+// Directory of the assets next to the executable, where the build copies the model,
+// and in the working directory, where they were historically read from
+const ASSETS_DIR: &str = "assets";
+
+// Directory of the application in the shared data directories of an installation prefix
+const APPLICATION_DATA_DIR: &str = "neural_analytics";
+
+/// Resolves an asset, such as the model, whatever directory the application was launched from.
+///
+/// Launchers such as a `.desktop` file or a macOS bundle do not start the application in
+/// its installation directory, so the assets are searched, in order, in:
+///
+/// 1. The directory set in `NEURAL_ANALYTICS_ASSETS_DIR`, the only one searched when set.
+/// 2. `assets` next to the executable, `Resources/assets` of a macOS bundle, and
+///    `share/neural_analytics/assets` of the installation prefix of the executable.
+/// 3. `neural_analytics/assets` in the XDG data directories, on Unix.
+/// 4. `assets` in the working directory.
+///
+/// # Arguments
+/// * `name`: The file name of the asset, e.g. `neural_analytics.onnx`.
+///
+/// # Returns
+/// * `PathBuf`: The first path holding the asset, or the one of the last directory searched
+///   when none does, so the errors of its consumers name the expected location.
+pub fn resolve_asset(name: &str) -> PathBuf {
+    let executable = std::env::current_exe().ok();
+    let search_dirs = asset_search_dirs(|variable| std::env::var_os(variable), executable.as_deref());
+
+    find_asset(name, &search_dirs)
+}
+
+/// Directories the assets are searched in, in order, see `resolve_asset`.
+///
+/// # Arguments
+/// * `env`: Reads an environment variable.
+/// * `executable`: Path of the running executable, `None` when it cannot be told.
+pub fn asset_search_dirs(env: impl Fn(&str) -> Option<OsString>, executable: Option<&Path>) -> Vec<PathBuf> {
+    if let Some(assets_dir) = env(ASSETS_DIR_VARIABLE).filter(|dir| !dir.is_empty()) {
+        return vec![PathBuf::from(assets_dir)];
+    }
+
+    let mut search_dirs = Vec::new();
+
+    if let Some(executable_dir) = executable.and_then(Path::parent) {
+        search_dirs.push(executable_dir.join(ASSETS_DIR));
+        // `Contents/MacOS/<executable>` in a macOS bundle
+        search_dirs.push(executable_dir.join("../Resources").join(ASSETS_DIR));
+        // `<prefix>/bin/<executable>` in a relocatable installation
+        search_dirs.push(executable_dir.join("../share").join(APPLICATION_DATA_DIR).join(ASSETS_DIR));
+    }
+
+    if cfg!(unix) {
+        let data_home = env("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env("HOME").map(|home| Path::new(&home).join(".local/share")));
+        let data_dirs = env("XDG_DATA_DIRS")
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| OsString::from("/usr/local/share:/usr/share"));
+
+        search_dirs.extend(
+            data_home
+                .into_iter()
+                .chain(std::env::split_paths(&data_dirs))
+                .map(|dir| dir.join(APPLICATION_DATA_DIR).join(ASSETS_DIR)),
+        );
+    }
+
+    search_dirs.push(PathBuf::from(ASSETS_DIR));
+    search_dirs
+}
+
+/// First path holding the asset, or the one of the last directory when none does.
+fn find_asset(name: &str, search_dirs: &[PathBuf]) -> PathBuf {
+    search_dirs
+        .iter()
+        .map(|dir| dir.join(name))
+        .find(|path| path.exists())
+        .or_else(|| search_dirs.last().map(|dir| dir.join(name)))
+        .unwrap_or_else(|| Path::new(ASSETS_DIR).join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assets_are_found_next_to_the_executable_before_the_working_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let executable = dir.path().join("bin/neural_analytics_gui");
+        let no_env = |_: &str| None;
+
+        let search_dirs = asset_search_dirs(no_env, Some(&executable));
+        assert_eq!(search_dirs[0], dir.path().join("bin/assets"));
+        assert_eq!(search_dirs.last(), Some(&PathBuf::from("assets")));
+
+        // Missing everywhere, the consumers report the historic location
+        assert_eq!(find_asset("missing.onnx", &search_dirs), Path::new("assets/missing.onnx"));
+
+        std::fs::create_dir_all(dir.path().join("bin")).unwrap();
+        std::fs::create_dir_all(dir.path().join("share/neural_analytics/assets")).unwrap();
+        std::fs::write(dir.path().join("share/neural_analytics/assets/model.onnx"), b"").unwrap();
+        assert_eq!(
+            find_asset("model.onnx", &search_dirs),
+            dir.path().join("bin/../share/neural_analytics/assets/model.onnx")
+        );
+    }
+
+    #[test]
+    fn test_assets_directory_variable_overrides_the_search() {
+        let env = |variable: &str| (variable == ASSETS_DIR_VARIABLE).then(|| OsString::from("/opt/models"));
+
+        let search_dirs = asset_search_dirs(env, Some(Path::new("/usr/bin/neural_analytics_gui")));
+
+        assert_eq!(search_dirs, vec![PathBuf::from("/opt/models")]);
+        assert_eq!(find_asset("model.onnx", &search_dirs), Path::new("/opt/models/model.onnx"));
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::{error, warn};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::domain::{
+    models::{bulb_connection::BulbConnectionState, bulb_state::BulbState},
+    ports::output::smart_bulb::SmartBulbPort,
+};
+
+// Longest wait between two checks of the watchdog, for the long timeouts
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+type BulbPort = dyn SmartBulbPort + Send + Sync;
+
+// Whether the bulb was left on, and until when the core is expected to drive it
+struct Watchdog {
+    is_on: bool,
+    deadline: Instant,
+}
+
+/// Bulb turning itself off once the core stops driving it, so a light left on by a hung
+/// or crashed pipeline, or by a headset that went away, does not stay on unattended.
+///
+/// Every command and every `refresh` of the core postpones the auto-off by `timeout`;
+/// once it elapses with the bulb on, a watchdog task turns the bulb off. The watchdog
+/// runs apart from the state machine, so it covers a state machine that hung or
+/// panicked, but not a process that was killed, the Tapo bulbs having no countdown of
+/// their own.
+pub struct AutoOffBulbAdapter {
+    inner: Arc<BulbPort>,
+    timeout: Duration,
+    // Held for the whole call to the bulb, so the watchdog never races a command
+    watchdog: Arc<Mutex<Watchdog>>,
+}
+
+impl AutoOffBulbAdapter {
+    /// Wraps a bulb and starts its watchdog, which stops once the adapter is dropped.
+    pub fn new(inner: Box<BulbPort>, timeout: Duration) -> Self {
+        let inner: Arc<BulbPort> = Arc::from(inner);
+        let watchdog = Arc::new(Mutex::new(Watchdog {
+            is_on: false,
+            deadline: Instant::now() + timeout,
+        }));
+
+        tokio::spawn(run_watchdog(
+            Arc::downgrade(&inner),
+            Arc::downgrade(&watchdog),
+            timeout,
+        ));
+
+        Self { inner, timeout, watchdog }
+    }
+}
+
+/// Turns the bulb off once its deadline passed with the bulb on.
+///
+/// A failed attempt is retried on the next check, the bulb staying on meanwhile.
+async fn run_watchdog(inner: Weak<BulbPort>, watchdog: Weak<Mutex<Watchdog>>, timeout: Duration) {
+    let mut interval = tokio::time::interval((timeout / 4).clamp(Duration::from_millis(1), MAX_CHECK_INTERVAL));
+
+    loop {
+        interval.tick().await;
+
+        let (Some(inner), Some(watchdog)) = (inner.upgrade(), watchdog.upgrade()) else {
+            return;
+        };
+
+        let mut watchdog = watchdog.lock().await;
+        if !watchdog.is_on || Instant::now() < watchdog.deadline {
+            continue;
+        }
+
+        warn!(
+            "No command for the bulb in {} ms, turning it off for safety",
+            timeout.as_millis()
+        );
+
+        match inner.change_state(BulbState::BulbOff).await {
+            Ok(()) => watchdog.is_on = false,
+            Err(e) => error!("Failed to turn the bulb off after its timeout: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl SmartBulbPort for AutoOffBulbAdapter {
+    async fn change_state(&self, state: BulbState) -> Result<(), String> {
+        let mut watchdog = self.watchdog.lock().await;
+        let result = self.inner.change_state(state).await;
+
+        if result.is_ok() {
+            watchdog.is_on = state == BulbState::BulbOn;
+        }
+        watchdog.deadline = Instant::now() + self.timeout;
+
+        result
+    }
+
+    /// Black turns the color bulbs off, any other color on.
+    async fn change_color(&self, red: u8, green: u8, blue: u8) -> Result<(), String> {
+        let mut watchdog = self.watchdog.lock().await;
+        let result = self.inner.change_color(red, green, blue).await;
+
+        if result.is_ok() {
+            watchdog.is_on = (red, green, blue) != (0, 0, 0);
+        }
+        watchdog.deadline = Instant::now() + self.timeout;
+
+        result
+    }
+
+    async fn refresh(&self) {
+        self.watchdog.lock().await.deadline = Instant::now() + self.timeout;
+        self.inner.refresh().await;
+    }
+
+    fn connection_state(&self) -> BulbConnectionState {
+        self.inner.connection_state()
+    }
+
+    fn is_simulated(&self) -> bool {
+        self.inner.is_simulated()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bulb remembering the last state it was changed to, shared by its clones
+    #[derive(Clone, Default)]
+    struct StateBulb {
+        state: Arc<std::sync::Mutex<Option<BulbState>>>,
+    }
+
+    impl StateBulb {
+        fn state(&self) -> Option<BulbState> {
+            *self.state.lock().unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl SmartBulbPort for StateBulb {
+        async fn change_state(&self, state: BulbState) -> Result<(), String> {
+            *self.state.lock().unwrap() = Some(state);
+            Ok(())
+        }
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bulb_left_on_is_turned_off_after_the_timeout() {
+        let bulb = StateBulb::default();
+        let adapter = AutoOffBulbAdapter::new(Box::new(bulb.clone()), TIMEOUT);
+
+        adapter.change_state(BulbState::BulbOn).await.unwrap();
+        tokio::time::sleep(TIMEOUT / 2).await;
+        assert_eq!(bulb.state(), Some(BulbState::BulbOn));
+
+        tokio::time::sleep(TIMEOUT).await;
+        assert_eq!(bulb.state(), Some(BulbState::BulbOff));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refreshes_keep_the_bulb_on() {
+        let bulb = StateBulb::default();
+        let adapter = AutoOffBulbAdapter::new(Box::new(bulb.clone()), TIMEOUT);
+
+        adapter.change_state(BulbState::BulbOn).await.unwrap();
+        for _ in 0..5 {
+            tokio::time::sleep(TIMEOUT / 2).await;
+            adapter.refresh().await;
+        }
+        assert_eq!(bulb.state(), Some(BulbState::BulbOn));
+
+        // Once the bulb was turned off, there is nothing left to turn off
+        adapter.change_state(BulbState::BulbOff).await.unwrap();
+        drop(adapter);
+        tokio::time::sleep(TIMEOUT * 2).await;
+        assert_eq!(bulb.state(), Some(BulbState::BulbOff));
+    }
+}
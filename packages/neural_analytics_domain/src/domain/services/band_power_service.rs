@@ -0,0 +1,123 @@
+use std::collections::{HashMap, VecDeque};
+
+use neural_analytics_signal_math as signal_math;
+
+use crate::domain::{
+    models::band_powers::{BandPowerConfig, BandPowers, EEG_BANDS},
+    ports::input::eeg_headset::SAMPLE_RATE_HZ,
+};
+
+/// Estimates the power of the EEG bands of each channel from its recent samples.
+///
+/// The windows overlap when the inference keeps up with it, so only the new samples
+/// of each window, its last `hop_samples`, are added to the history.
+pub struct BandPowerService {
+    config: BandPowerConfig,
+    history: HashMap<String, VecDeque<f32>>,
+}
+
+impl BandPowerService {
+    pub fn new(config: BandPowerConfig) -> Self {
+        Self {
+            config,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Adds the new samples of a window and computes the band powers of its channels.
+    ///
+    /// # Arguments
+    /// * `headset_data`: The samples of the window per channel.
+    /// * `hop_samples`: The samples of the window not already in the previous one.
+    ///
+    /// # Returns
+    /// * `Option<HashMap<String, BandPowers>>`: The powers of each channel of the window,
+    ///   `None` when the band powers are disabled.
+    pub fn observe(&mut self, headset_data: &HashMap<String, Vec<f32>>, hop_samples: usize) -> Option<HashMap<String, BandPowers>> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        // A channel that left the window no longer has a meaningful history
+        self.history.retain(|channel, _| headset_data.contains_key(channel));
+
+        let band_powers = headset_data
+            .iter()
+            .map(|(channel, values)| {
+                let history = self.history.entry(channel.clone()).or_default();
+                history.extend(&values[values.len().saturating_sub(hop_samples)..]);
+
+                let excess = history.len().saturating_sub(self.config.history_samples);
+                history.drain(..excess);
+
+                (channel.clone(), band_powers_of(history.make_contiguous(), self.config.segment_samples))
+            })
+            .collect();
+
+        Some(band_powers)
+    }
+
+    /// Forgets the samples of the previous session.
+    pub fn reset(&mut self) {
+        self.history.clear();
+    }
+
+    /// Estimated heap footprint of the history, for the memory budget.
+    pub fn estimated_bytes(&self) -> usize {
+        self.history
+            .iter()
+            .map(|(channel, history)| channel.capacity() + history.capacity() * size_of::<f32>())
+            .sum()
+    }
+}
+
+fn band_powers_of(samples: &[f32], segment_samples: usize) -> BandPowers {
+    let [delta, theta, alpha, beta] =
+        EEG_BANDS.map(|(_, low_hz, high_hz)| signal_math::band_power(samples, SAMPLE_RATE_HZ, segment_samples, low_hz, high_hz));
+
+    BandPowers { delta, theta, alpha, beta }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Samples of a sine at the sample rate of the headset
+    fn sine(frequency: f32, first: usize, samples: usize) -> Vec<f32> {
+        (first..first + samples)
+            .map(|n| (2.0 * std::f32::consts::PI * frequency * n as f32 / SAMPLE_RATE_HZ).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_observe_finds_the_band_of_the_signal() {
+        let mut service = BandPowerService::new(BandPowerConfig::default());
+
+        let mut band_powers = None;
+        for window in 0..10 {
+            let headset_data = HashMap::from([("O1".to_string(), sine(10.0, window * 62, 62))]);
+            band_powers = service.observe(&headset_data, 62);
+        }
+
+        let o1 = band_powers.unwrap()["O1"];
+        assert!(o1.alpha > 0.4, "{:?}", o1);
+        assert!(o1.delta < 0.01 && o1.theta < 0.05 && o1.beta < 0.05, "{:?}", o1);
+        assert_eq!(service.history["O1"].len(), 500);
+    }
+
+    #[test]
+    fn test_observe_keeps_only_the_new_samples_of_overlapping_windows() {
+        let mut service = BandPowerService::new(BandPowerConfig::default());
+        let headset_data = HashMap::from([("O1".to_string(), vec![0.0; 62])]);
+
+        service.observe(&headset_data, 62);
+        service.observe(&headset_data, 31);
+        assert_eq!(service.history["O1"].len(), 93);
+
+        let disabled = BandPowerConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(BandPowerService::new(disabled).observe(&headset_data, 62), None);
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::Instant;
+
+use log::info;
+use presage::{Command, CommandBus, Configuration};
+use tokio::sync::mpsc;
+
+use crate::domain::{
+    commands::{
+        disconnect_headband_command::DisconnectHeadbandCommand,
+        extract_generalist_data_command::ExtractGeneralistDataCommand,
+        predict_color_thinking_command::PredictColorThinkingCommand,
+        search_headband_command::SearchHeadbandCommand,
+    },
+    context::{adapter_registry::standalone_adapter_registry, NeuralAnalyticsContext},
+    models::{
+        benchmark_report::{BenchmarkReport, LatencyStats},
+        core_config::CoreConfig,
+    },
+    ports::input::eeg_headset::EegHeadsetPort,
+    state_machine::command_middleware::{duration_metric, CommandMiddleware},
+    use_cases::{
+        disconnect_headband_use_case::disconnect_headband_use_case,
+        extract_extraction_use_case::{extract_generalist_data_use_case, read_headset_window},
+        predict_color_thinking_use_case::predict_color_thinking_use_case,
+        search_headband_use_case::search_headband_use_case,
+    },
+};
+
+// Name of the headset under benchmark in the adapter registry
+const BENCHMARK_ADAPTER: &str = "benchmark";
+
+/// Command bus with the commands of the capture state, without the bulb.
+pub(crate) fn pipeline_command_bus() -> CommandMiddleware {
+    CommandMiddleware::new(
+        CommandBus::<NeuralAnalyticsContext, presage::Error>::new().configure(
+            Configuration::new()
+                .command_handler(&disconnect_headband_use_case)
+                .command_handler(&extract_generalist_data_use_case)
+                .command_handler(&predict_color_thinking_use_case)
+                .command_handler(&search_headband_use_case),
+        ),
+    )
+}
+
+/// Runs the extraction and prediction pipeline over a number of windows and measures it.
+///
+/// The pipeline is the one of the capture state, without the bulb: the windows are
+/// read from the given headset, assembled, predicted by the loaded model, and the
+/// events derived by the context are dispatched to a consumer, like a frontend would
+/// receive them. Comparing a synthetic headset with a real one tells whether the
+/// machine or the device limits the throughput.
+///
+/// Runs on its own context, so it must not be used while the core is running.
+///
+/// # Arguments
+/// * `adapter`: Name of the headset adapter, copied to the report.
+/// * `headset`: The headset to read the windows from, connected by the benchmark.
+/// * `windows`: The number of windows to process.
+/// * `config`: The configuration of the services of the pipeline.
+///
+/// # Returns
+/// * `Result<BenchmarkReport, String>`: The report, or an error if the model is not
+///   loaded or the headset does not connect.
+pub async fn run_pipeline_benchmark(
+    adapter: &str,
+    headset: Box<dyn EegHeadsetPort + Send + Sync>,
+    windows: usize,
+    config: CoreConfig,
+) -> Result<BenchmarkReport, String> {
+    let mut context = NeuralAnalyticsContext::new(config);
+
+    // Registered apart from the headset of the core, replacing the one of a previous run
+    context.eeg_headset_adapter = standalone_adapter_registry().replace(BENCHMARK_ADAPTER, headset);
+
+    let (is_model_loaded, load_error) = context
+        .with_model(|model| (model.is_model_loaded(), model.load_error()))
+        .await?;
+
+    if !is_model_loaded {
+        return Err(format!("The model is not loaded: {}", load_error.unwrap_or_default()));
+    }
+
+    let command_bus = pipeline_command_bus();
+
+    command_bus
+        .execute(&mut context, SearchHeadbandCommand)
+        .await
+        .map_err(|e| format!("The headset did not connect: {}", e))?;
+
+    // Consumer standing for a frontend, measuring how long each event took to reach it
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Instant>();
+    let consumer = tokio::spawn(async move {
+        let mut latencies = Vec::new();
+
+        while let Some(derived_at) = receiver.recv().await {
+            latencies.push(derived_at.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        latencies
+    });
+
+    info!("Benchmarking the pipeline with the {} adapter over {} windows...", adapter, windows);
+
+    let mut extraction = Vec::with_capacity(windows);
+    let mut inference = Vec::with_capacity(windows);
+    let started = Instant::now();
+
+    for _ in 0..windows {
+        // The board read is timed with the command storing the window, as it is part of the extraction
+        let read_started = Instant::now();
+        let reading = read_headset_window(&context.eeg_headset_adapter, context.window_overlap.hop_samples()).await;
+        let read_ms = read_started.elapsed().as_secs_f64() * 1000.0;
+
+        let extracted = command_bus.execute(&mut context, ExtractGeneralistDataCommand { reading }).await;
+        extraction.extend(
+            context
+                .metrics
+                .gauge(&duration_metric(ExtractGeneralistDataCommand::NAME))
+                .map(|command_ms| read_ms + command_ms),
+        );
+
+        if extracted.is_err() || context.headset_data.is_none() {
+            continue;
+        }
+
+        if command_bus.execute(&mut context, PredictColorThinkingCommand {}).await.is_ok() {
+            inference.extend(context.metrics.gauge(&duration_metric(PredictColorThinkingCommand::NAME)));
+        }
+
+        context.act_on_prediction(None);
+
+        for _ in context.take_notifications() {
+            let _ = sender.send(Instant::now());
+        }
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+
+    drop(sender);
+    let event_dispatch = consumer.await.map_err(|e| format!("The event consumer panicked: {}", e))?;
+
+    let _ = command_bus.execute(&mut context, DisconnectHeadbandCommand).await;
+
+    Ok(BenchmarkReport {
+        adapter: adapter.to_string(),
+        windows,
+        processed_windows: inference.len(),
+        failed_windows: windows - inference.len(),
+        elapsed_ms: elapsed * 1000.0,
+        windows_per_sec: if elapsed > 0.0 { inference.len() as f64 / elapsed } else { 0.0 },
+        extraction: LatencyStats::from_samples(&extraction),
+        inference: LatencyStats::from_samples(&inference),
+        event_dispatch: LatencyStats::from_samples(&event_dispatch),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats_from_samples() {
+        let samples: Vec<f64> = (1..=100).map(f64::from).collect();
+        let stats = LatencyStats::from_samples(&samples);
+
+        assert_eq!(stats.mean_ms, 50.5);
+        assert_eq!(stats.p50_ms, 51.0);
+        assert_eq!(stats.p95_ms, 95.0);
+        assert_eq!(stats.max_ms, 100.0);
+        assert_eq!(LatencyStats::from_samples(&[]), LatencyStats::default());
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::domain::models::{calibration_config::CalibrationConfig, calibration_thresholds::CalibrationThresholds};
+
+/// Decides when a calibration is over, from the readings of the headset.
+///
+/// A reading with every electrode within its thresholds starts a stable period, which
+/// finishes the calibration once it lasts `stable_duration_ms`; a reading out of them
+/// starts it over. A failed reading is retried up to `read_retries` times in a row.
+pub struct CalibrationPolicyService {
+    config: CalibrationConfig,
+    stable_since: Option<Instant>,
+    failed_reads: u32,
+}
+
+impl CalibrationPolicyService {
+    pub fn new(config: CalibrationConfig) -> Self {
+        Self {
+            config,
+            stable_since: None,
+            failed_reads: 0,
+        }
+    }
+
+    /// Whether an impedance reading is within the thresholds of each electrode.
+    ///
+    /// # Arguments
+    /// * `impedance_data`: The impedance of each electrode.
+    /// * `shared`: The thresholds of the electrodes without thresholds of their own.
+    pub fn accepts(&self, impedance_data: &HashMap<String, u16>, shared: &CalibrationThresholds) -> bool {
+        self.config.accepts(impedance_data, shared)
+    }
+
+    /// Records whether a reading was accepted.
+    ///
+    /// # Returns
+    /// * `bool`: Whether the electrodes have been accepted for long enough to finish the calibration.
+    pub fn observe(&mut self, is_accepted: bool, now: Instant) -> bool {
+        if !is_accepted {
+            self.stable_since = None;
+            return false;
+        }
+
+        let stable_since = *self.stable_since.get_or_insert(now);
+        now.duration_since(stable_since) >= Duration::from_millis(self.config.stable_duration_ms)
+    }
+
+    /// Records whether the headset could be read.
+    ///
+    /// # Returns
+    /// * `bool`: Whether a failed reading is retried, `false` once the retries are exhausted.
+    pub fn retries_read(&mut self, is_read: bool) -> bool {
+        if is_read {
+            self.failed_reads = 0;
+            return false;
+        }
+
+        self.failed_reads += 1;
+        self.failed_reads <= self.config.read_retries
+    }
+
+    /// Forgets the readings of the calibration that ended, so the next one starts over.
+    pub fn reset(&mut self) {
+        self.stable_since = None;
+        self.failed_reads = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(stable_duration_ms: u64, read_retries: u32) -> CalibrationPolicyService {
+        CalibrationPolicyService::new(CalibrationConfig {
+            electrode_thresholds: [("O1".to_string(), CalibrationThresholds { min_impedance: 1, max_impedance: 1500 })]
+                .into_iter()
+                .collect(),
+            stable_duration_ms,
+            read_retries,
+        })
+    }
+
+    #[test]
+    fn test_accepts_with_the_thresholds_of_each_electrode() {
+        let policy = policy(0, 0);
+        let shared = CalibrationThresholds::default();
+        let reading = |o1: u16, o2: u16| [("O1".to_string(), o1), ("O2".to_string(), o2)].into_iter().collect();
+
+        assert!(policy.accepts(&reading(1200, 800), &shared));
+        assert!(!policy.accepts(&reading(1200, 1200), &shared));
+        assert!(!policy.accepts(&reading(1600, 800), &shared));
+    }
+
+    #[test]
+    fn test_finishes_once_stable_and_retries_failed_reads() {
+        let mut policy = policy(2_000, 2);
+        let start = Instant::now();
+
+        assert!(!policy.observe(true, start));
+        assert!(!policy.observe(false, start + Duration::from_millis(1_000)));
+        assert!(!policy.observe(true, start + Duration::from_millis(2_000)));
+        assert!(policy.observe(true, start + Duration::from_millis(4_000)));
+
+        assert!(policy.retries_read(false));
+        assert!(policy.retries_read(false));
+        assert!(!policy.retries_read(false));
+        assert!(!policy.retries_read(true));
+        assert!(policy.retries_read(false));
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::fs;
+
+use log::{error, info};
+
+use crate::domain::models::{calibration_thresholds::CalibrationThresholds, device_timings::DeviceTimings};
+
+/// Last successful calibration of a headset, for one user.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CalibrationProfile {
+    pub device_id: String,
+    pub user_profile: Option<String>,
+    /// Unix epoch milliseconds when the calibration finished.
+    pub timestamp: i64,
+    /// Highest electrode impedance, in kOhm, of the reading that finished the calibration.
+    pub max_impedance: u16,
+}
+
+/// Content of the profiles file.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct StoredProfiles {
+    calibrations: Vec<CalibrationProfile>,
+    #[serde(default)]
+    device_timings: HashMap<String, DeviceTimings>,
+}
+
+/// Store of the calibration profiles, to skip the calibration when the same
+/// user reconnects the same headset shortly after, and of the delays measured
+/// for each headset, to skip measuring them again.
+///
+/// The profiles are kept in memory and, when a path is configured, saved to a
+/// JSON file so they survive restarts.
+pub struct CalibrationProfileService {
+    path: Option<String>,
+    reuse_period_ms: i64,
+    stored: StoredProfiles,
+}
+
+impl CalibrationProfileService {
+    /// Creates the store, loading the profiles saved at the given path.
+    ///
+    /// # Arguments
+    /// * `path`: The JSON file of the profiles, or `None` to keep them in memory.
+    /// * `reuse_period_ms`: How long a calibration can be reused. `0` disables the reuse.
+    pub fn new(path: Option<&str>, reuse_period_ms: i64) -> Self {
+        let stored = path
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| match serde_json::from_str(&json) {
+                Ok(stored) => Some(stored),
+                Err(e) => {
+                    error!("Ignoring unreadable calibration profiles: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Self {
+            path: path.map(str::to_string),
+            reuse_period_ms,
+            stored,
+        }
+    }
+
+    /// Records a successful calibration, replacing the previous one of the same headset and user.
+    pub fn remember(&mut self, profile: CalibrationProfile) {
+        self.stored.calibrations.retain(|stored| {
+            stored.device_id != profile.device_id || stored.user_profile != profile.user_profile
+        });
+        self.stored.calibrations.push(profile);
+        self.save();
+    }
+
+    /// Records the delays measured for a headset, saving them only when they changed.
+    pub fn remember_device_timings(&mut self, device_id: &str, timings: DeviceTimings) {
+        if self.stored.device_timings.get(device_id) != Some(&timings) {
+            info!("Storing the delays of {}: {:?}", device_id, timings);
+            self.stored.device_timings.insert(device_id.to_string(), timings);
+            self.save();
+        }
+    }
+
+    /// Delays measured on a previous connection of the headset.
+    pub fn device_timings(&self, device_id: &str) -> Option<DeviceTimings> {
+        self.stored.device_timings.get(device_id).copied()
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let saved = serde_json::to_string_pretty(&self.stored)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+
+        if let Err(e) = saved {
+            error!("Could not save calibration profiles to {}: {}", path, e);
+        }
+    }
+
+    /// Finds a calibration of the headset and user that can be reused instead of calibrating again.
+    ///
+    /// # Arguments
+    /// * `device_id`: The headset that connected.
+    /// * `user_profile`: The user wearing it.
+    /// * `thresholds`: The current thresholds, the stored calibration must still be within them.
+    /// * `now`: Unix epoch milliseconds.
+    ///
+    /// # Returns
+    /// * `Option<&CalibrationProfile>`: The profile, if it is recent and its quality was good.
+    pub fn find_reusable(
+        &self,
+        device_id: &str,
+        user_profile: Option<&str>,
+        thresholds: &CalibrationThresholds,
+        now: i64,
+    ) -> Option<&CalibrationProfile> {
+        let profile = self.stored.calibrations.iter().find(|profile| {
+            profile.device_id == device_id && profile.user_profile.as_deref() == user_profile
+        })?;
+
+        let is_recent = (0..self.reuse_period_ms).contains(&(now - profile.timestamp));
+
+        if is_recent && thresholds.accepts(profile.max_impedance) {
+            info!("Reusing the calibration of {} from {}", device_id, profile.timestamp);
+            Some(profile)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(user_profile: Option<&str>, max_impedance: u16) -> CalibrationProfile {
+        CalibrationProfile {
+            device_id: "AA:BB".to_string(),
+            user_profile: user_profile.map(str::to_string),
+            timestamp: 1_000,
+            max_impedance,
+        }
+    }
+
+    #[test]
+    fn test_find_reusable_requires_recent_good_calibration() {
+        let thresholds = CalibrationThresholds::default();
+        let mut store = CalibrationProfileService::new(None, 60_000);
+        store.remember(profile(Some("alice"), 500));
+
+        assert!(store.find_reusable("AA:BB", Some("alice"), &thresholds, 30_000).is_some());
+        assert!(store.find_reusable("AA:BB", Some("bob"), &thresholds, 30_000).is_none());
+        assert!(store.find_reusable("CC:DD", Some("alice"), &thresholds, 30_000).is_none());
+        assert!(store.find_reusable("AA:BB", Some("alice"), &thresholds, 90_000).is_none());
+
+        // A later calibration out of the thresholds replaces the good one
+        store.remember(profile(Some("alice"), 2_000));
+        assert!(store.find_reusable("AA:BB", Some("alice"), &thresholds, 30_000).is_none());
+    }
+
+    #[test]
+    fn test_profiles_survive_restarts() {
+        let path = std::env::temp_dir().join(format!("neural_analytics_calibration_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let timings = DeviceTimings {
+            command_delay_ms: 75,
+            data_delay_ms: 180,
+        };
+
+        let mut store = CalibrationProfileService::new(Some(path), 60_000);
+        store.remember(profile(None, 500));
+        store.remember_device_timings("AA:BB", timings);
+
+        let store = CalibrationProfileService::new(Some(path), 60_000);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(store.device_timings("AA:BB"), Some(timings));
+        assert_eq!(store.device_timings("CC:DD"), None);
+
+        assert!(store
+            .find_reusable("AA:BB", None, &CalibrationThresholds::default(), 2_000)
+            .is_some());
+        assert!(CalibrationProfileService::new(Some(path), 0)
+            .find_reusable("AA:BB", None, &CalibrationThresholds::default(), 2_000)
+            .is_none());
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::domain::models::{
+    calibration_thresholds::CalibrationThresholds, calibration_updates::CalibrationUpdates,
+    signal_quality::SignalQuality,
+};
+
+// Quality band of an impedance, against the calibration thresholds
+#[derive(PartialEq)]
+enum ImpedanceBand {
+    Detached,
+    Accepted,
+    High,
+}
+
+impl ImpedanceBand {
+    fn of(impedance: u16, thresholds: &CalibrationThresholds) -> Self {
+        if impedance < thresholds.min_impedance {
+            ImpedanceBand::Detached
+        } else if impedance <= thresholds.max_impedance {
+            ImpedanceBand::Accepted
+        } else {
+            ImpedanceBand::High
+        }
+    }
+}
+
+/// Decides which readings of a calibration are worth an update to the frontends.
+///
+/// Readings are compared against the last one sent, so a change held back by the rate
+/// limit is sent with the next reading once the interval has passed.
+pub struct CalibrationUpdateService {
+    config: CalibrationUpdates,
+    last_sent_at: Option<Instant>,
+    last_impedance: Option<HashMap<String, u16>>,
+    last_signal_quality: Option<HashMap<String, SignalQuality>>,
+}
+
+impl CalibrationUpdateService {
+    pub fn new(config: CalibrationUpdates) -> Self {
+        Self {
+            config,
+            last_sent_at: None,
+            last_impedance: None,
+            last_signal_quality: None,
+        }
+    }
+
+    /// Whether an impedance reading is worth an update, remembering it as sent if it is.
+    ///
+    /// # Arguments
+    /// * `impedance_data`: The impedance of each electrode.
+    /// * `thresholds`: The calibration thresholds, delimiting the quality bands.
+    /// * `now`: When the reading was taken.
+    pub fn should_send_impedance(
+        &mut self,
+        impedance_data: &HashMap<String, u16>,
+        thresholds: &CalibrationThresholds,
+        now: Instant,
+    ) -> bool {
+        let is_changed = self.last_impedance.as_ref().is_none_or(|last_impedance| {
+            last_impedance.len() != impedance_data.len()
+                || impedance_data.iter().any(|(electrode, &impedance)| match last_impedance.get(electrode) {
+                    Some(&last) => {
+                        ImpedanceBand::of(last, thresholds) != ImpedanceBand::of(impedance, thresholds)
+                            || (impedance as f32 - last as f32).abs() > self.config.min_change * last.max(1) as f32
+                    }
+                    None => true,
+                })
+        });
+
+        if !self.admit(is_changed, now) {
+            return false;
+        }
+
+        self.last_impedance = Some(impedance_data.clone());
+        true
+    }
+
+    /// Whether a signal quality reading is worth an update, remembering it as sent if it is.
+    ///
+    /// The qualities are bands already, so any change is.
+    pub fn should_send_signal_quality(&mut self, signal_quality: &HashMap<String, SignalQuality>, now: Instant) -> bool {
+        let is_changed = self.last_signal_quality.as_ref() != Some(signal_quality);
+
+        if !self.admit(is_changed, now) {
+            return false;
+        }
+
+        self.last_signal_quality = Some(signal_quality.clone());
+        true
+    }
+
+    /// Forgets the readings sent, so the first one of the next calibration is always sent.
+    pub fn reset(&mut self) {
+        self.last_sent_at = None;
+        self.last_impedance = None;
+        self.last_signal_quality = None;
+    }
+
+    fn admit(&mut self, is_changed: bool, now: Instant) -> bool {
+        let min_interval = Duration::from_millis(self.config.min_interval_ms);
+        let is_due = self
+            .last_sent_at
+            .is_none_or(|last_sent_at| now.saturating_duration_since(last_sent_at) >= min_interval);
+
+        if !is_changed || !is_due {
+            return false;
+        }
+
+        self.last_sent_at = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(t3: u16, o1: u16) -> HashMap<String, u16> {
+        [("T3".to_string(), t3), ("O1".to_string(), o1)].into_iter().collect()
+    }
+
+    #[test]
+    fn test_impedance_updates_need_a_change_and_the_interval() {
+        let mut updates = CalibrationUpdateService::new(CalibrationUpdates::default());
+        let thresholds = CalibrationThresholds::default();
+        let start = Instant::now();
+
+        assert!(updates.should_send_impedance(&reading(1500, 400), &thresholds, start));
+
+        // A change of 5%, then one of 20% too soon after the previous update
+        let later = start + Duration::from_millis(300);
+        assert!(!updates.should_send_impedance(&reading(1425, 400), &thresholds, later));
+        assert!(!updates.should_send_impedance(&reading(1200, 400), &thresholds, start + Duration::from_millis(100)));
+        assert!(updates.should_send_impedance(&reading(1200, 400), &thresholds, later));
+
+        let later = later + Duration::from_millis(300);
+        assert!(updates.should_send_impedance(&reading(1050, 400), &thresholds, later));
+
+        // 1000 is less than 5% lower, but enters the accepted band
+        let later = later + Duration::from_millis(300);
+        assert!(!updates.should_send_impedance(&reading(1040, 400), &thresholds, later));
+        assert!(updates.should_send_impedance(&reading(1000, 400), &thresholds, later));
+
+        updates.reset();
+        assert!(updates.should_send_impedance(&reading(1000, 400), &thresholds, later));
+    }
+
+    #[test]
+    fn test_signal_quality_updates_on_any_change() {
+        let mut updates = CalibrationUpdateService::new(CalibrationUpdates {
+            min_interval_ms: 0,
+            min_change: 0.1,
+        });
+        let now = Instant::now();
+        let mut signal_quality: HashMap<String, SignalQuality> = [("T3".to_string(), SignalQuality::Flat)].into_iter().collect();
+
+        assert!(updates.should_send_signal_quality(&signal_quality, now));
+        assert!(!updates.should_send_signal_quality(&signal_quality, now));
+
+        signal_quality.insert("T3".to_string(), SignalQuality::Noisy);
+        assert!(updates.should_send_signal_quality(&signal_quality, now));
+    }
+}
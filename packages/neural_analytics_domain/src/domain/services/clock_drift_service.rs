@@ -0,0 +1,106 @@
+/// Tracks the drift between the board clock and the host clock over a session.
+///
+/// The first observation pairs both clocks; afterwards the drift is the time the
+/// host advanced minus the time the board advanced. Board timestamps are mapped
+/// onto the host clock through that first pair, so recordings stay aligned with
+/// logs written by other programs on the host.
+pub struct ClockDriftService {
+    threshold_ms: f64,
+    // Board timestamp, in seconds, and host timestamp, in milliseconds, of the first observation
+    reference: Option<(f64, i64)>,
+    drift_ms: f64,
+    is_warned: bool,
+}
+
+impl ClockDriftService {
+    /// Creates the service.
+    ///
+    /// # Arguments
+    /// * `threshold_ms`: The drift that triggers a warning. `0` disables the warnings.
+    pub fn new(threshold_ms: u64) -> Self {
+        Self {
+            threshold_ms: threshold_ms as f64,
+            reference: None,
+            drift_ms: 0.0,
+            is_warned: false,
+        }
+    }
+
+    /// Compares the clocks on a new sample.
+    ///
+    /// # Arguments
+    /// * `board_timestamp`: Board timestamp of the sample, in Unix epoch seconds.
+    /// * `host_timestamp`: Host time when the sample was received, in Unix epoch milliseconds.
+    ///
+    /// # Returns
+    /// * `Option<f64>`: The drift, in milliseconds, when it first exceeds the threshold.
+    ///   It warns again only after the drift went back under the threshold.
+    pub fn observe(&mut self, board_timestamp: f64, host_timestamp: i64) -> Option<f64> {
+        let (board_reference, host_reference) = *self.reference.get_or_insert((board_timestamp, host_timestamp));
+
+        self.drift_ms = (host_timestamp - host_reference) as f64 - (board_timestamp - board_reference) * 1000.0;
+
+        let is_exceeded = self.threshold_ms > 0.0 && self.drift_ms.abs() > self.threshold_ms;
+        let is_new_warning = is_exceeded && !self.is_warned;
+        self.is_warned = is_exceeded;
+
+        is_new_warning.then_some(self.drift_ms)
+    }
+
+    /// Forgets the clocks pairing, for a new headset or session.
+    pub fn reset(&mut self) {
+        self.reference = None;
+        self.drift_ms = 0.0;
+        self.is_warned = false;
+    }
+
+    /// Drift, in milliseconds, that triggers a warning, `0` when disabled.
+    pub fn threshold_ms(&self) -> u64 {
+        self.threshold_ms as u64
+    }
+
+    /// Last observed drift, in milliseconds. Positive when the board clock runs slow.
+    pub fn drift_ms(&self) -> f64 {
+        self.drift_ms
+    }
+
+    /// Maps a board timestamp onto the host clock, removing the drift.
+    ///
+    /// # Returns
+    /// * `Option<i64>`: Unix epoch milliseconds, `None` before the first observation.
+    pub fn correct(&self, board_timestamp: f64) -> Option<i64> {
+        let (board_reference, host_reference) = self.reference?;
+
+        Some(host_reference + ((board_timestamp - board_reference) * 1000.0).round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_warns_once_per_episode() {
+        let mut clock = ClockDriftService::new(100);
+
+        assert_eq!(clock.observe(1_000.0, 1_000_000), None);
+        assert_eq!(clock.observe(1_010.0, 1_010_050), None);
+        assert_eq!(clock.observe(1_020.0, 1_020_150), Some(150.0));
+        assert_eq!(clock.observe(1_030.0, 1_030_200), None);
+
+        // Back under the threshold re-arms the warning
+        assert_eq!(clock.observe(1_040.0, 1_040_000), None);
+        assert_eq!(clock.observe(1_050.0, 1_049_800), Some(-200.0));
+    }
+
+    #[test]
+    fn test_correct_maps_board_time_onto_host_clock() {
+        let mut clock = ClockDriftService::new(0);
+        assert_eq!(clock.correct(1_000.0), None);
+
+        clock.observe(1_000.0, 5_000);
+        assert_eq!(clock.observe(1_010.0, 15_500), None);
+        assert_eq!(clock.drift_ms(), 500.0);
+        assert_eq!(clock.correct(1_010.0), Some(15_000));
+    }
+}
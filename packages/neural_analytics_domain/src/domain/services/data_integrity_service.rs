@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use crate::domain::models::integrity_report::IntegrityViolation;
+
+/// Checks the captured data for the breaches that would corrupt a dataset.
+///
+/// The package numbers of the board must follow each other across the windows of a
+/// session. Every breach is counted; in strict mode the first one also aborts the session.
+pub struct DataIntegrityService {
+    is_strict: bool,
+    // Package number of the last sample checked
+    last_sequence: Option<u32>,
+}
+
+impl DataIntegrityService {
+    /// Creates the service.
+    ///
+    /// # Arguments
+    /// * `is_strict`: Whether the violations abort the session, see `CoreConfig::strict_mode`.
+    pub fn new(is_strict: bool) -> Self {
+        Self {
+            is_strict,
+            last_sequence: None,
+        }
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.is_strict
+    }
+
+    /// Checks the package numbers of a window follow the ones of the previous window.
+    ///
+    /// A number going back to 0 is the counter of the board wrapping around, not a gap.
+    ///
+    /// # Returns
+    /// * `Option<IntegrityViolation>`: The first gap found, if any.
+    pub fn check_sequence(&mut self, sequence_numbers: &[u32]) -> Option<IntegrityViolation> {
+        let mut violation = None;
+
+        for &number in sequence_numbers {
+            if let Some(previous) = self.last_sequence {
+                let expected = previous.wrapping_add(1);
+
+                if number != expected && number != 0 && violation.is_none() {
+                    violation = Some(IntegrityViolation::SequenceGap { expected, found: number });
+                }
+            }
+
+            self.last_sequence = Some(number);
+        }
+
+        violation
+    }
+
+    /// Forgets the package numbers, for a new headset or session.
+    pub fn reset(&mut self) {
+        self.last_sequence = None;
+    }
+}
+
+/// Counts the non finite samples of each channel, leaving out the channels without any.
+pub fn count_non_finite_samples(headset_data: &HashMap<String, Vec<f32>>) -> HashMap<String, usize> {
+    headset_data
+        .iter()
+        .map(|(channel, values)| (channel.clone(), values.iter().filter(|value| !value.is_finite()).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_sequence_finds_gaps_across_windows_and_accepts_wraps() {
+        let mut integrity = DataIntegrityService::new(true);
+
+        assert_eq!(integrity.check_sequence(&[10, 11, 12]), None);
+        assert_eq!(integrity.check_sequence(&[13, 14, 0, 1]), None);
+        assert_eq!(
+            integrity.check_sequence(&[2, 5, 6]),
+            Some(IntegrityViolation::SequenceGap { expected: 3, found: 5 })
+        );
+
+        // A new headset starts its own count
+        integrity.reset();
+        assert_eq!(integrity.check_sequence(&[200, 201]), None);
+    }
+}
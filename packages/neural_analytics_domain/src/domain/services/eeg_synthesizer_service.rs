@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::domain::{
+    models::synthetic_signal::{BandAmplitudes, SyntheticSignalConfig},
+    ports::input::eeg_headset::SAMPLE_RATE_HZ,
+};
+
+// Poles and gains of the filter turning white noise into pink noise, from Paul Kellet's
+// economy filter. Its 1/f slope spans from 0.1 Hz to the 40 Hz above the EEG bands at 250 Hz.
+const PINK_POLES: [f32; 3] = [0.99765, 0.96300, 0.57000];
+const PINK_GAINS: [f32; 3] = [0.0990460, 0.2965164, 1.0526913];
+const PINK_WHITE_GAIN: f32 = 0.1848;
+
+// Center and half width of the rhythms, in hertz, which drift within them
+const THETA_RHYTHM: (f32, f32) = (6.0, 1.5);
+const ALPHA_RHYTHM: (f32, f32) = (10.0, 1.5);
+const BETA_RHYTHM: (f32, f32) = (20.0, 5.0);
+
+// Mean seconds an alpha burst lasts, and the gap between two of them
+const ALPHA_BURST_SECS: f32 = 1.5;
+// Seconds the alpha takes to wax and wane, and its amplitude between the bursts
+const ALPHA_RAMP_SECS: f32 = 0.3;
+const ALPHA_FLOOR: f32 = 0.15;
+
+// Seconds a blink lasts
+const BLINK_SECS: f32 = 0.3;
+
+/// Uniform white noise of unit variance.
+fn white_noise(rng: &mut StdRng) -> f32 {
+    rng.gen_range(-1.0..1.0) * 3f32.sqrt()
+}
+
+// Background of a channel, white noise filtered to a 1/f spectrum
+struct PinkNoise {
+    state: [f32; 3],
+    // Scales the output to a unit standard deviation
+    gain: f32,
+}
+
+impl PinkNoise {
+    fn new() -> Self {
+        // Variance of the filtered unit white noise, the branches sharing their input
+        let branches: Vec<(f32, f32)> = PINK_POLES.iter().copied().zip(PINK_GAINS).chain([(0.0, PINK_WHITE_GAIN)]).collect();
+        let variance: f32 = branches
+            .iter()
+            .flat_map(|&(pole, gain)| branches.iter().map(move |&(other_pole, other_gain)| gain * other_gain / (1.0 - pole * other_pole)))
+            .sum();
+
+        Self {
+            state: [0.0; 3],
+            gain: 1.0 / variance.sqrt(),
+        }
+    }
+
+    fn next(&mut self, white: f32) -> f32 {
+        let mut pink = white * PINK_WHITE_GAIN;
+
+        for ((state, pole), gain) in self.state.iter_mut().zip(PINK_POLES).zip(PINK_GAINS) {
+            *state = pole * *state + gain * white;
+            pink += *state;
+        }
+
+        pink * self.gain
+    }
+}
+
+// Oscillation drifting in frequency around the center of its band
+struct Rhythm {
+    center_hz: f32,
+    half_width_hz: f32,
+    phase: f32,
+    // Offset from the center, a slow random walk pulled back towards it
+    detune_hz: f32,
+}
+
+impl Rhythm {
+    fn new((center_hz, half_width_hz): (f32, f32), rng: &mut StdRng) -> Self {
+        Self {
+            center_hz,
+            half_width_hz,
+            phase: rng.gen_range(0.0..TAU),
+            detune_hz: 0.0,
+        }
+    }
+
+    fn next(&mut self, rng: &mut StdRng) -> f32 {
+        self.detune_hz = (0.998 * self.detune_hz + 0.05 * self.half_width_hz * white_noise(rng))
+            .clamp(-self.half_width_hz, self.half_width_hz);
+        self.phase = (self.phase + TAU * (self.center_hz + self.detune_hz) / SAMPLE_RATE_HZ) % TAU;
+
+        self.phase.sin()
+    }
+}
+
+struct ChannelSynthesizer {
+    electrode: String,
+    amplitudes: BandAmplitudes,
+    pink_noise: PinkNoise,
+    theta: Rhythm,
+    alpha: Rhythm,
+    beta: Rhythm,
+    // Amplitude of the alpha, relative to its peak, and the one it moves to
+    alpha_envelope: f32,
+    is_alpha_burst: bool,
+}
+
+impl ChannelSynthesizer {
+    fn next(&mut self, rng: &mut StdRng, blink: f32) -> f32 {
+        if rng.gen::<f32>() < 1.0 / (ALPHA_BURST_SECS * SAMPLE_RATE_HZ) {
+            self.is_alpha_burst = !self.is_alpha_burst;
+        }
+
+        let target = if self.is_alpha_burst { 1.0 } else { ALPHA_FLOOR };
+        self.alpha_envelope += (target - self.alpha_envelope) / (ALPHA_RAMP_SECS * SAMPLE_RATE_HZ);
+
+        let white = white_noise(rng);
+
+        self.amplitudes.pink_noise * self.pink_noise.next(white)
+            + self.amplitudes.theta * self.theta.next(rng)
+            + self.amplitudes.alpha * self.alpha_envelope * self.alpha.next(rng)
+            + self.amplitudes.beta * self.beta.next(rng)
+            + self.amplitudes.blink * blink
+    }
+}
+
+/// Synthesizer of EEG with a realistic spectrum, streamed by the synthetic headset.
+///
+/// Every channel sums a pink background, theta and beta rhythms and alpha bursts, with
+/// the amplitudes of its electrode in the `SyntheticSignalConfig`. The blinks hit every
+/// channel at once, as they do on a real head. The samples are generated one after the
+/// other at `SAMPLE_RATE_HZ`, so consecutive calls continue the same signal.
+pub struct EegSynthesizer {
+    channels: Vec<ChannelSynthesizer>,
+    rng: StdRng,
+    blink_probability: f32,
+    // Samples into the blink in progress
+    blink_sample: Option<usize>,
+}
+
+impl EegSynthesizer {
+    /// Creates the synthesizer of the given electrodes.
+    ///
+    /// # Arguments
+    /// * `electrodes`: The hardware names of the electrodes, such as `O1`.
+    /// * `config`: The amplitudes of each electrode, the blink rate and the seed.
+    pub fn new(electrodes: &[&str], config: &SyntheticSignalConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(config.seed);
+
+        let channels = electrodes
+            .iter()
+            .map(|electrode| ChannelSynthesizer {
+                electrode: electrode.to_string(),
+                amplitudes: config.amplitudes(electrode),
+                pink_noise: PinkNoise::new(),
+                theta: Rhythm::new(THETA_RHYTHM, &mut rng),
+                alpha: Rhythm::new(ALPHA_RHYTHM, &mut rng),
+                beta: Rhythm::new(BETA_RHYTHM, &mut rng),
+                alpha_envelope: ALPHA_FLOOR,
+                is_alpha_burst: rng.gen(),
+            })
+            .collect();
+
+        Self {
+            channels,
+            rng,
+            blink_probability: config.blinks_per_minute.max(0.0) / (60.0 * SAMPLE_RATE_HZ),
+            blink_sample: None,
+        }
+    }
+
+    /// Generates the next samples of every channel, in microvolts.
+    pub fn next_samples(&mut self, count: usize) -> HashMap<String, Vec<f32>> {
+        let blink_samples = (BLINK_SECS * SAMPLE_RATE_HZ) as usize;
+        let mut samples: Vec<Vec<f32>> = self.channels.iter().map(|_| Vec::with_capacity(count)).collect();
+
+        for _ in 0..count {
+            if self.blink_sample.is_none() && self.rng.gen::<f32>() < self.blink_probability {
+                self.blink_sample = Some(0);
+            }
+
+            // A single bump rising and falling over the blink
+            let blink = self.blink_sample.map_or(0.0, |sample| (PI * sample as f32 / blink_samples as f32).sin());
+            self.blink_sample = self.blink_sample.map(|sample| sample + 1).filter(|&sample| sample < blink_samples);
+
+            for (channel, channel_samples) in self.channels.iter_mut().zip(samples.iter_mut()) {
+                channel_samples.push(channel.next(&mut self.rng, blink));
+            }
+        }
+
+        self.channels
+            .iter()
+            .map(|channel| channel.electrode.clone())
+            .zip(samples)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neural_analytics_signal_math as signal_math;
+
+    // Mean density of a band, so bands of different widths compare
+    fn density(samples: &[f32], low_hz: f32, high_hz: f32) -> f32 {
+        signal_math::band_power(samples, SAMPLE_RATE_HZ, 250, low_hz, high_hz) / (high_hz - low_hz)
+    }
+
+    #[test]
+    fn test_spectrum_peaks_in_alpha_over_a_falling_background() {
+        let config = SyntheticSignalConfig {
+            blinks_per_minute: 0.0,
+            ..SyntheticSignalConfig::default()
+        };
+        let mut synthesizer = EegSynthesizer::new(&["O1"], &config);
+        let samples = synthesizer.next_samples(20 * SAMPLE_RATE_HZ as usize).remove("O1").unwrap();
+
+        assert!(density(&samples, 8.0, 13.0) > 5.0 * density(&samples, 13.0, 30.0));
+
+        let background = SyntheticSignalConfig {
+            default_amplitudes: BandAmplitudes {
+                theta: 0.0,
+                alpha: 0.0,
+                beta: 0.0,
+                blink: 0.0,
+                ..BandAmplitudes::default()
+            },
+            channels: HashMap::new(),
+            ..config
+        };
+        let mut synthesizer = EegSynthesizer::new(&["T3"], &background);
+        let samples = synthesizer.next_samples(20 * SAMPLE_RATE_HZ as usize).remove("T3").unwrap();
+
+        assert!(density(&samples, 1.0, 4.0) > 4.0 * density(&samples, 8.0, 13.0));
+        assert!(density(&samples, 8.0, 13.0) > density(&samples, 20.0, 30.0));
+        assert!((signal_math::std_dev(&samples) - 8.0).abs() < 4.0);
+    }
+
+    #[test]
+    fn test_blinks_hit_every_channel_at_once_and_the_seed_replays_them() {
+        let config = SyntheticSignalConfig {
+            default_amplitudes: BandAmplitudes {
+                pink_noise: 0.0,
+                theta: 0.0,
+                alpha: 0.0,
+                beta: 0.0,
+                blink: 100.0,
+            },
+            channels: HashMap::new(),
+            blinks_per_minute: 60.0,
+            seed: 7,
+        };
+
+        let samples = EegSynthesizer::new(&["T3", "T4"], &config).next_samples(10 * SAMPLE_RATE_HZ as usize);
+
+        assert!(samples["T3"].iter().any(|&sample| sample > 90.0));
+        assert_eq!(samples["T3"], samples["T4"]);
+        assert_eq!(samples, EegSynthesizer::new(&["T3", "T4"], &config).next_samples(10 * SAMPLE_RATE_HZ as usize));
+    }
+}
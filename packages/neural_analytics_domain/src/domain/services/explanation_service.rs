@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use super::model_inference_service::ModelInferenceInterface;
+
+/// Scores how much each channel supports the predicted class, by occlusion.
+///
+/// Every channel is zeroed in turn and the window is predicted again; the
+/// contribution of a channel is the probability of the predicted class the
+/// model loses without it. A negative contribution means the channel pushed
+/// the model away from its prediction. Costs one inference per channel.
+///
+/// # Arguments
+/// * `model`: The model that made the prediction.
+/// * `eeg_data`: The window that was predicted.
+/// * `predicted_class`: The class to explain.
+///
+/// # Returns
+/// * `Result<HashMap<String, f32>, String>`: The contribution of each channel, or an error if the model fails.
+pub fn channel_contributions(
+    model: &dyn ModelInferenceInterface,
+    eeg_data: &HashMap<String, Vec<f32>>,
+    predicted_class: &str,
+) -> Result<HashMap<String, f32>, String> {
+    let class_probability = |data: &HashMap<String, Vec<f32>>| -> Result<f32, String> {
+        model
+            .class_probabilities(data)?
+            .get(predicted_class)
+            .copied()
+            .ok_or_else(|| format!("The model has no class '{}'", predicted_class))
+    };
+
+    let baseline = class_probability(eeg_data)?;
+    let mut occluded = eeg_data.clone();
+    let mut contributions = HashMap::with_capacity(eeg_data.len());
+
+    for (channel, values) in eeg_data {
+        occluded.insert(channel.clone(), vec![0.0; values.len()]);
+        contributions.insert(channel.clone(), baseline - class_probability(&occluded)?);
+        occluded.insert(channel.clone(), values.clone());
+    }
+
+    Ok(contributions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Model whose "green" probability only depends on the O1 channel
+    struct O1Model;
+
+    impl ModelInferenceInterface for O1Model {
+        fn predict_color(&self, _: &HashMap<String, Vec<f32>>) -> Result<String, String> {
+            Ok("green".to_string())
+        }
+
+        fn class_probabilities(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<HashMap<String, f32>, String> {
+            let green = if eeg_data["O1"].iter().any(|&value| value != 0.0) { 0.9 } else { 0.5 };
+
+            Ok([("green".to_string(), green), ("red".to_string(), 1.0 - green)].into_iter().collect())
+        }
+
+        fn is_model_loaded(&self) -> bool {
+            true
+        }
+    }
+
+    fn window() -> HashMap<String, Vec<f32>> {
+        ["T3", "T4", "O1", "O2"]
+            .iter()
+            .map(|channel| (channel.to_string(), vec![1.0; 62]))
+            .collect()
+    }
+
+    #[test]
+    fn test_channel_contributions_blames_the_occluded_channel() {
+        let contributions = channel_contributions(&O1Model, &window(), "green").unwrap();
+
+        assert_eq!(contributions.len(), 4);
+        assert!((contributions["O1"] - 0.4).abs() < 1e-6);
+        assert_eq!(contributions["T3"], 0.0);
+        assert_eq!(contributions["O2"], 0.0);
+    }
+
+    #[test]
+    fn test_channel_contributions_rejects_unknown_class() {
+        assert!(channel_contributions(&O1Model, &window(), "trash").is_err());
+    }
+}
@@ -0,0 +1,42 @@
+/// Firmware versions of the BrainBit headset the pipeline was validated with.
+///
+/// Add a version once a headset running it went through calibration and capture
+/// sessions without issues.
+pub const TESTED_FIRMWARE_VERSIONS: [&str; 2] = ["1.0.5", "1.1.2"];
+
+/// Whether the firmware of a headset is known to work with the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirmwareCompatibility {
+    Tested,
+    Untested,
+    /// The headset does not report its firmware version.
+    Unknown,
+}
+
+/// Checks a firmware version against the `TESTED_FIRMWARE_VERSIONS`.
+///
+/// # Arguments
+/// * `firmware_version`: The version reported by the headset, if any.
+///
+/// # Returns
+/// * `FirmwareCompatibility`: The compatibility of the version.
+pub fn check_firmware(firmware_version: Option<&str>) -> FirmwareCompatibility {
+    match firmware_version.map(str::trim) {
+        None | Some("") => FirmwareCompatibility::Unknown,
+        Some(version) if TESTED_FIRMWARE_VERSIONS.contains(&version) => FirmwareCompatibility::Tested,
+        Some(_) => FirmwareCompatibility::Untested,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_firmware_against_the_tested_versions() {
+        assert_eq!(check_firmware(Some(TESTED_FIRMWARE_VERSIONS[0])), FirmwareCompatibility::Tested);
+        assert_eq!(check_firmware(Some("0.0.1-beta")), FirmwareCompatibility::Untested);
+        assert_eq!(check_firmware(None), FirmwareCompatibility::Unknown);
+        assert_eq!(check_firmware(Some(" ")), FirmwareCompatibility::Unknown);
+    }
+}
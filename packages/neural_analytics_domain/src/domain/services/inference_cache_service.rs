@@ -0,0 +1,130 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::domain::models::{band_powers::BandPowers, window_prediction::WindowPrediction};
+
+/// Hashes a window of EEG samples, independently of the channels order.
+///
+/// Samples are hashed by their bit pattern, so only identical windows collide. The band
+/// powers fed to the model along with the window, if any, are part of the hash, since the
+/// same samples predict differently with other band powers.
+pub fn window_hash(headset_data: &HashMap<String, Vec<f32>>, band_powers: Option<&HashMap<String, BandPowers>>) -> u64 {
+    let mut channels: Vec<_> = headset_data.iter().collect();
+    channels.sort_unstable_by_key(|(channel, _)| *channel);
+
+    let mut hasher = DefaultHasher::new();
+
+    for (channel, values) in channels {
+        channel.hash(&mut hasher);
+        values.len().hash(&mut hasher);
+
+        for value in values {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+
+    if let Some(band_powers) = band_powers {
+        let mut channels: Vec<_> = band_powers.iter().collect();
+        channels.sort_unstable_by_key(|(channel, _)| *channel);
+
+        for (channel, powers) in channels {
+            channel.hash(&mut hasher);
+
+            for power in [powers.delta, powers.theta, powers.alpha, powers.beta] {
+                power.to_bits().hash(&mut hasher);
+            }
+        }
+    }
+
+    hasher.finish()
+}
+
+/// Remembers the prediction of the last window, so an unchanged window skips the model.
+///
+/// Replayed sessions repeat windows, and the board returns the same samples again
+/// when it has no new ones; running the model on them only wastes time. The whole
+/// prediction is kept, so a cached one still reports its confidence.
+///
+/// Clones share the cached prediction, so the model watcher can clear it once it
+/// swapped the model.
+#[derive(Clone, Default)]
+pub struct InferenceCacheService {
+    last_prediction: Arc<Mutex<Option<(u64, WindowPrediction)>>>,
+}
+
+impl InferenceCacheService {
+    /// Returns the cached prediction if the window is the last one predicted.
+    pub fn lookup(&self, window_hash: u64) -> Option<WindowPrediction> {
+        match &*self.last_prediction.lock().ok()? {
+            Some((hash, prediction)) if *hash == window_hash => Some(prediction.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn store(&self, window_hash: u64, prediction: WindowPrediction) {
+        if let Ok(mut last_prediction) = self.last_prediction.lock() {
+            *last_prediction = Some((window_hash, prediction));
+        }
+    }
+
+    /// Forgets the cached prediction, e.g. once the model that made it was replaced.
+    pub fn clear(&self) {
+        if let Ok(mut last_prediction) = self.last_prediction.lock() {
+            *last_prediction = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_hash_ignores_channel_order_but_not_samples() {
+        let first: HashMap<String, Vec<f32>> = [("T3".to_string(), vec![1.0, 2.0]), ("T4".to_string(), vec![3.0])]
+            .into_iter()
+            .collect();
+        let reordered: HashMap<String, Vec<f32>> = [("T4".to_string(), vec![3.0]), ("T3".to_string(), vec![1.0, 2.0])]
+            .into_iter()
+            .collect();
+        let changed: HashMap<String, Vec<f32>> = [("T3".to_string(), vec![1.0, 2.5]), ("T4".to_string(), vec![3.0])]
+            .into_iter()
+            .collect();
+
+        assert_eq!(window_hash(&first, None), window_hash(&reordered, None));
+        assert_ne!(window_hash(&first, None), window_hash(&changed, None));
+
+        let cache = InferenceCacheService::default();
+        cache.store(window_hash(&first, None), WindowPrediction::from_label("green".to_string(), 1.0));
+
+        assert_eq!(cache.lookup(window_hash(&reordered, None)).map(|prediction| prediction.label), Some("green".to_string()));
+        assert_eq!(cache.lookup(window_hash(&changed, None)), None);
+    }
+
+    #[test]
+    fn test_cache_keys_on_band_powers_and_is_cleared_for_its_clones() {
+        let window: HashMap<String, Vec<f32>> = [("T3".to_string(), vec![1.0, 2.0])].into_iter().collect();
+        let band_powers: HashMap<String, BandPowers> = [("T3".to_string(), BandPowers { alpha: 2.0, ..Default::default() })]
+            .into_iter()
+            .collect();
+        let other_band_powers: HashMap<String, BandPowers> = [("T3".to_string(), BandPowers { alpha: 3.0, ..Default::default() })]
+            .into_iter()
+            .collect();
+
+        assert_ne!(window_hash(&window, None), window_hash(&window, Some(&band_powers)));
+        assert_ne!(window_hash(&window, Some(&band_powers)), window_hash(&window, Some(&other_band_powers)));
+
+        let probabilities = [("red".to_string(), 0.9), ("green".to_string(), 0.1)].into_iter().collect();
+        let prediction = WindowPrediction::from_probabilities(probabilities, 1.0).unwrap();
+        let cache = InferenceCacheService::default();
+        cache.store(window_hash(&window, Some(&band_powers)), prediction);
+
+        let cached = cache.lookup(window_hash(&window, Some(&band_powers))).unwrap();
+        assert_eq!(cached.confidence, Some(0.9));
+
+        cache.clone().clear();
+        assert_eq!(cache.lookup(window_hash(&window, Some(&band_powers))), None);
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::Path;
+#[cfg(feature = "ort")]
+use std::sync::Mutex;
+
+use log::warn;
+use tract_onnx::prelude::*;
+
+use crate::domain::models::inference_config::{ExecutionProvider, InferenceBackend, InferenceConfig};
+
+type TractPlan = RunnableModel<TypedFact, Box<dyn TypedOp>, Graph<TypedFact, Box<dyn TypedOp>>>;
+
+/// Dimensions of the input and of the output of a model, `None` for the symbolic ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSignature {
+    pub input: Vec<Option<usize>>,
+    pub output: Vec<Option<usize>>,
+}
+
+/// Engine running a loaded ONNX model on the preprocessed windows.
+pub trait InferenceRuntime: Send + Sync {
+    /// Dimensions the model declares, checked against the preprocessing and the label map.
+    fn signature(&self) -> &ModelSignature;
+
+    /// Runs the model on a window of `shape`, `[batch_size, window samples, channels]`,
+    /// returning its raw output.
+    fn run(&self, input: Vec<f32>, shape: [usize; 3]) -> Result<Vec<f32>, String>;
+}
+
+/// Loads the model of `path` in the runtime of the configuration.
+///
+/// # Returns
+/// * `Result<Box<dyn InferenceRuntime>, String>`: The loaded model, or an error if it is
+///   not a valid model of one input and one output, or if the runtime was not built in.
+pub fn load_runtime(config: &InferenceConfig, path: &Path) -> Result<Box<dyn InferenceRuntime>, String> {
+    match config.backend {
+        InferenceBackend::Tract => {
+            if config.execution_provider != ExecutionProvider::Cpu {
+                warn!(
+                    "tract only runs on the CPU, ignoring the {:?} execution provider",
+                    config.execution_provider
+                );
+            }
+
+            Ok(Box::new(TractRuntime::load(path)?))
+        }
+        #[cfg(feature = "ort")]
+        InferenceBackend::Ort => Ok(Box::new(OrtRuntime::load(path, config.execution_provider)?)),
+        #[cfg(not(feature = "ort"))]
+        InferenceBackend::Ort => {
+            Err("The ONNX Runtime backend needs the `ort` feature of neural_analytics_domain".to_string())
+        }
+    }
+}
+
+/// Checks the model has the single input and single output of the windows and the classes.
+fn validate_arity(inputs: usize, outputs: usize) -> Result<(), String> {
+    if inputs == 1 && outputs == 1 {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Unexpected model signature: expected 1 input and 1 output, found {} inputs and {} outputs",
+        inputs, outputs
+    ))
+}
+
+/// Model run by tract, pure Rust and on the CPU.
+pub struct TractRuntime {
+    plan: TractPlan,
+    signature: ModelSignature,
+}
+
+impl TractRuntime {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let model = tract_onnx::onnx()
+            .model_for_path(path)
+            .map_err(|e| format!("Error loading the model: {}", e))
+            .and_then(|model| {
+                model
+                    .into_optimized()
+                    .map_err(|e| format!("Error optimizing the model: {}", e))
+            })?;
+
+        validate_arity(model.inputs.len(), model.outputs.len())?;
+
+        let fact_dims = |fact: &TypedFact| -> Vec<Option<usize>> {
+            fact.shape.iter().map(|dim| dim.to_i64().ok().map(|value| value as usize)).collect()
+        };
+
+        let signature = ModelSignature {
+            input: model
+                .input_fact(0)
+                .map(fact_dims)
+                .map_err(|e| format!("Error reading model input: {}", e))?,
+            output: model
+                .output_fact(0)
+                .map(fact_dims)
+                .map_err(|e| format!("Error reading model output: {}", e))?,
+        };
+
+        let plan = model
+            .into_runnable()
+            .map_err(|e| format!("Error creating runnable model: {}", e))?;
+
+        Ok(Self { plan, signature })
+    }
+}
+
+impl InferenceRuntime for TractRuntime {
+    fn signature(&self) -> &ModelSignature {
+        &self.signature
+    }
+
+    fn run(&self, input: Vec<f32>, shape: [usize; 3]) -> Result<Vec<f32>, String> {
+        let [batch_size, window_samples, channel_count] = shape;
+        let input_tensor = tract_ndarray::Array3::from_shape_vec((batch_size, window_samples, channel_count), input)
+            .map_err(|e| format!("Error creating input tensor: {}", e))?
+            .into_arc_tensor();
+
+        let outputs = self
+            .plan
+            .run(tvec!(TValue::Const(input_tensor)))
+            .map_err(|e| format!("Error during inference: {}", e))?;
+
+        let output_tensor = outputs.first().ok_or_else(|| "No outputs returned from model".to_string())?;
+        let output_view = output_tensor
+            .to_array_view::<f32>()
+            .map_err(|e| format!("Error converting output to array: {}", e))?;
+
+        Ok(output_view.iter().cloned().collect())
+    }
+}
+
+/// Model run by ONNX Runtime, on the device of its execution provider.
+///
+/// A provider that cannot be registered, e.g. without a GPU, fails the load instead of
+/// silently falling back to the CPU, so the configuration says where the model runs.
+#[cfg(feature = "ort")]
+pub struct OrtRuntime {
+    // Running a session needs it mutably
+    session: Mutex<ort::session::Session>,
+    signature: ModelSignature,
+}
+
+#[cfg(feature = "ort")]
+impl OrtRuntime {
+    pub fn load(path: &Path, execution_provider: ExecutionProvider) -> Result<Self, String> {
+        use ort::execution_providers::{CPUExecutionProvider, CUDAExecutionProvider, DirectMLExecutionProvider};
+
+        let provider = match execution_provider {
+            ExecutionProvider::Cpu => CPUExecutionProvider::default().build(),
+            ExecutionProvider::Cuda => CUDAExecutionProvider::default().build(),
+            ExecutionProvider::DirectMl => DirectMLExecutionProvider::default().build(),
+        };
+
+        let session = ort::session::Session::builder()
+            .and_then(|builder| builder.with_execution_providers([provider.error_on_failure()]))
+            .and_then(|builder| builder.commit_from_file(path))
+            .map_err(|e| format!("Error loading the model with ONNX Runtime: {}", e))?;
+
+        validate_arity(session.inputs.len(), session.outputs.len())?;
+
+        // ONNX Runtime marks the symbolic dimensions with -1
+        let dims = |shape: Option<&ort::tensor::Shape>| -> Vec<Option<usize>> {
+            shape
+                .map(|shape| shape.iter().map(|&dim| usize::try_from(dim).ok()).collect())
+                .unwrap_or_default()
+        };
+
+        let signature = ModelSignature {
+            input: dims(session.inputs[0].input_type.tensor_shape()),
+            output: dims(session.outputs[0].output_type.tensor_shape()),
+        };
+
+        Ok(Self {
+            session: Mutex::new(session),
+            signature,
+        })
+    }
+}
+
+#[cfg(feature = "ort")]
+impl InferenceRuntime for OrtRuntime {
+    fn signature(&self) -> &ModelSignature {
+        &self.signature
+    }
+
+    fn run(&self, input: Vec<f32>, shape: [usize; 3]) -> Result<Vec<f32>, String> {
+        let input_tensor = ort::value::Tensor::from_array((shape, input))
+            .map_err(|e| format!("Error creating input tensor: {}", e))?;
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| "The ONNX Runtime session was poisoned".to_string())?;
+        let outputs = session
+            .run(ort::inputs![input_tensor])
+            .map_err(|e| format!("Error during inference: {}", e))?;
+
+        let (_, output) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| format!("Error converting output to array: {}", e))?;
+
+        Ok(output.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unavailable_backend_fails_the_load() {
+        let config = InferenceConfig {
+            backend: InferenceBackend::Ort,
+            execution_provider: ExecutionProvider::Cuda,
+        };
+
+        // Either the feature is missing or the model is, never a silent fallback to tract
+        assert!(load_runtime(&config, Path::new("non_existent_path/model.onnx")).is_err());
+        assert!(load_runtime(&InferenceConfig::default(), Path::new("non_existent_path/model.onnx")).is_err());
+    }
+}
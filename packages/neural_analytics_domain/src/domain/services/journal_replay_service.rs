@@ -0,0 +1,366 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use presage::EventWriter;
+use serde::de::DeserializeOwned;
+
+use super::journal_service::JournalEntry;
+use crate::domain::{
+    context::NeuralAnalyticsContext,
+    events::session_rotated_event::SessionRotatedEvent,
+    models::{
+        channel_config::ChannelConfig,
+        core_config::CoreConfig,
+        diagnostic_bundle::StateTransition,
+        event_internals::JournaledEvent,
+        experiment_config::ExperimentConfig,
+        journal_replay::{ExplanationRecord, ReplayedContext},
+        operator_action::OperatorActionRecord,
+        recording_config::RecordingConfig,
+    },
+};
+
+// Entries the context journals as it applies the events and the transitions, so the
+// replayed context journals them again instead of reading them
+const DERIVED_KINDS: [&str; 8] = [
+    "prediction",
+    "explanation",
+    "actuation",
+    "actuation_failed",
+    "session_recording",
+    "integrity_violation",
+    "calibrated_impedance",
+    "inference_paused",
+];
+
+/// Reads a session journal written by `JournalService`.
+///
+/// # Returns
+/// * `Result<Vec<JournalEntry>, String>`: The entries in the order they were written, or
+///   an error naming the first line that cannot be read.
+pub fn read_journal(path: &str) -> Result<Vec<JournalEntry>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Could not open session journal {}: {}", path, e))?;
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            line.map_err(|e| e.to_string())
+                .and_then(|line| serde_json::from_str(&line).map_err(|e| e.to_string()))
+                .map_err(|e| {
+                    format!(
+                        "Invalid session journal {}, line {}: {}",
+                        path,
+                        index + 1,
+                        e
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Steps through the entries of a session journal, rebuilding the context of the core
+/// at each of them.
+///
+/// The journaled events and transitions are applied to a context of the core, with the
+/// configuration of the first session of the journal, so the replay follows whatever
+/// the core does with them. The adapters of the process are not driven, and the context
+/// writes no files.
+///
+/// Stepping backward replays the journal from its start, as the entries only record
+/// what changed and cannot be undone.
+pub struct JournalReplay {
+    entries: Vec<JournalEntry>,
+    // Entries applied to the context, the current one being the last of them
+    applied: usize,
+    config: CoreConfig,
+    context: NeuralAnalyticsContext,
+    replayed: ReplayedContext,
+}
+
+impl JournalReplay {
+    /// Creates the replay, positioned before the first entry.
+    pub fn new(entries: Vec<JournalEntry>) -> Self {
+        let config = entries
+            .iter()
+            .filter(|entry| entry.kind == "experiment_config")
+            .find_map(payload::<ExperimentConfig>)
+            .map(|experiment| CoreConfig {
+                journal_path: None,
+                calibration_profiles_path: None,
+                recording: RecordingConfig::default(),
+                ..experiment.config
+            })
+            .unwrap_or_default();
+
+        Self {
+            entries,
+            applied: 0,
+            context: NeuralAnalyticsContext::new(config.clone()),
+            config,
+            replayed: ReplayedContext::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Index of the current entry, `None` before the first one.
+    pub fn position(&self) -> Option<usize> {
+        self.applied.checked_sub(1)
+    }
+
+    /// The current entry, `None` before the first one.
+    pub fn entry(&self) -> Option<&JournalEntry> {
+        self.position().map(|position| &self.entries[position])
+    }
+
+    /// Context of the core once the current entry was applied.
+    pub fn context(&self) -> &ReplayedContext {
+        &self.replayed
+    }
+
+    /// Applies the next entry.
+    ///
+    /// # Returns
+    /// * `bool`: Whether there was an entry left to apply.
+    pub fn step_forward(&mut self) -> bool {
+        let Some(entry) = self.entries.get(self.applied) else {
+            return false;
+        };
+
+        let is_applied = apply_entry(&mut self.context, entry);
+        self.applied += 1;
+
+        // Kept by the replay, as the context does not hold them
+        if let Some(action) = payload::<OperatorActionRecord>(entry).filter(|_| entry.kind == "operator_action") {
+            self.replayed.operator_actions.push(action);
+        }
+        if let Some(level) = entry.payload["to"].as_str().filter(|_| entry.kind == "log_level") {
+            self.replayed.log_level = Some(level.to_string());
+        }
+
+        self.replayed = ReplayedContext {
+            timestamp: Some(entry.timestamp),
+            operator_actions: std::mem::take(&mut self.replayed.operator_actions),
+            log_level: self.replayed.log_level.take(),
+            skipped_entries: self.replayed.skipped_entries + u64::from(!is_applied),
+            ..replayed_context(&self.context)
+        };
+
+        true
+    }
+
+    /// Goes back to the previous entry.
+    ///
+    /// # Returns
+    /// * `bool`: Whether the replay was past the first entry.
+    pub fn step_backward(&mut self) -> bool {
+        match self.position() {
+            Some(position) => {
+                self.seek(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Rebuilds the context with the first `applied` entries, the whole journal at most.
+    pub fn seek(&mut self, applied: usize) {
+        if applied < self.applied {
+            self.applied = 0;
+            self.context = NeuralAnalyticsContext::new(self.config.clone());
+            self.replayed = ReplayedContext::default();
+        }
+
+        while self.applied < applied.min(self.entries.len()) {
+            self.step_forward();
+        }
+    }
+
+    /// Rebuilds the context with the entries recorded up to the given time.
+    ///
+    /// # Arguments
+    /// * `timestamp`: Unix epoch milliseconds.
+    pub fn seek_time(&mut self, timestamp: i64) {
+        let applied = self
+            .entries
+            .iter()
+            .take_while(|entry| entry.timestamp <= timestamp)
+            .count();
+        self.seek(applied);
+    }
+
+    /// Steps forward until an entry of the given kind is applied.
+    ///
+    /// # Returns
+    /// * `bool`: Whether one was found, the replay staying at its position otherwise.
+    pub fn find_next(&mut self, kind: &str) -> bool {
+        match self.entries[self.applied..]
+            .iter()
+            .position(|entry| entry.kind == kind)
+        {
+            Some(offset) => {
+                self.seek(self.applied + offset + 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Steps to the prediction of the given window, to see what the core knew when it made it.
+    ///
+    /// # Returns
+    /// * `bool`: Whether the journal holds the prediction, the replay staying at its position otherwise.
+    pub fn find_window(&mut self, window_id: u64) -> bool {
+        let found = self.entries.iter().position(|entry| {
+            entry.kind == "prediction" && entry.payload["window_id"] == window_id
+        });
+
+        match found {
+            Some(index) => {
+                self.seek(index + 1);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Applies an entry to the context.
+///
+/// # Returns
+/// * `bool`: Whether the entry was replayed, `false` for the kinds this version does not
+///   replay and the payloads that cannot be read.
+fn apply_entry(context: &mut NeuralAnalyticsContext, entry: &JournalEntry) -> bool {
+    let is_applied = match entry.kind.as_str() {
+        "event" => payload::<JournaledEvent>(entry)
+            .and_then(|journaled| journaled.to_event())
+            .and_then(|event| futures::executor::block_on(context.write(&event)).ok()),
+        "transition" => payload(entry)
+            .map(|transition: StateTransition| context.record_transition(transition.timestamp, &transition.from, &transition.to)),
+        "operator_action" => payload(entry)
+            .map(|record: OperatorActionRecord| context.record_operator_action(record.action, entry.timestamp)),
+        "channels" => payload(entry).map(|channel_config: ChannelConfig| context.channel_config = channel_config),
+        // The sessions the events did not already open or rotate in the replayed context,
+        // as the state machine opens them on the connection and the operator rotates them
+        "experiment_config" => payload(entry).map(|_: ExperimentConfig| {
+            if context.session_summary.is_none() {
+                context.begin_session();
+            }
+        }),
+        "session_rotated" => payload(entry).map(|rotation: SessionRotatedEvent| {
+            if context.session_id < rotation.session_id {
+                context.rotate_session(rotation.reason);
+            }
+        }),
+        "log_level" => entry.payload["to"].as_str().map(|_| ()),
+        kind => DERIVED_KINDS.contains(&kind).then_some(()),
+    };
+
+    // Nothing publishes what the replayed context notifies
+    context.take_notifications();
+    context.take_lifecycle_events();
+
+    is_applied.is_some()
+}
+
+/// What the context holds, as shown by the replay
+fn replayed_context(context: &NeuralAnalyticsContext) -> ReplayedContext {
+    let last_entry = |kind: &str| context.journal.tail().iter().rev().find(|entry| entry.kind == kind).and_then(payload);
+
+    ReplayedContext {
+        state: context.transitions.back().map(|transition| transition.to.clone()),
+        session_id: context.session_id,
+        channel_config: context.channel_config.clone(),
+        experiment: context.session_summary.as_ref().and_then(|summary| summary.experiment.clone()),
+        window_id: context.window_id,
+        predicted_window_id: context.predicted_window_id,
+        recent_predictions: context.color_thinking.iter().cloned().collect(),
+        smoothed_prediction: context.get_color_thinking(),
+        bulb_state: context.bulb_state,
+        last_explanation: context.last_explanation.as_ref().map(|explanation| ExplanationRecord {
+            window_id: explanation.window_id,
+            predicted_class: explanation.predicted_class.clone(),
+            channel_contributions: explanation.channel_contributions.clone(),
+        }),
+        last_actuation: last_entry("actuation"),
+        last_integrity_violation: last_entry("integrity_violation"),
+        ..Default::default()
+    }
+}
+
+fn payload<T: DeserializeOwned>(entry: &JournalEntry) -> Option<T> {
+    serde_json::from_value(entry.payload.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use presage::Event;
+
+    use super::*;
+    use crate::domain::models::event_internals::ReceivedPredictColorThinkingDataEvent;
+
+    #[test]
+    fn test_replay_steps_both_ways_through_the_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl").to_string_lossy().into_owned();
+        let mut context = NeuralAnalyticsContext::new(CoreConfig {
+            journal_path: Some(path.clone()),
+            ..Default::default()
+        });
+
+        context.record_transition(1, "awaiting_headset_calibration", "capturing_headset_data");
+        for (window_id, color) in [(7, "red"), (8, "green")] {
+            let prediction = ReceivedPredictColorThinkingDataEvent {
+                color_thinking: color.to_string(),
+                window_id,
+                window_hash: window_id,
+                is_cached: false,
+                inference_ms: None,
+                prediction: None,
+            };
+            futures::executor::block_on(context.write(&Event::serialize(prediction).unwrap())).unwrap();
+        }
+        context.rotate_session("Test".to_string());
+        context.journal.record("heartbeat", &serde_json::json!({}));
+        context.journal.flush();
+
+        let mut replay = JournalReplay::new(read_journal(&path).unwrap());
+
+        assert_eq!(replay.position(), None);
+        assert!(replay.find_window(8));
+        assert_eq!(replay.position(), Some(4));
+        assert_eq!(replay.context().state.as_deref(), Some("capturing_headset_data"));
+        assert_eq!(replay.context().recent_predictions, ["red", "green"]);
+        assert_eq!(replay.context().predicted_window_id, Some(8));
+
+        // Going back forgets what the later entries applied, the event of the window
+        // being journaled before the prediction the context derives from it
+        assert!(replay.step_backward());
+        assert_eq!(replay.context().recent_predictions.len(), 2);
+        assert!(replay.step_backward());
+        assert_eq!(replay.context().recent_predictions, ["red"]);
+
+        assert!(replay.find_next("session_rotated"));
+        assert_eq!(replay.context().session_id, 1);
+        assert!(replay.context().recent_predictions.is_empty());
+
+        // Unknown kinds are counted, not replayed
+        assert!(replay.step_forward());
+        assert!(!replay.step_forward());
+        assert_eq!(replay.context().skipped_entries, 1);
+
+        replay.seek_time(1);
+        assert_eq!(replay.position(), Some(0));
+        assert_eq!(replay.context().timestamp, Some(1));
+        assert!(replay.context().recent_predictions.is_empty());
+    }
+}
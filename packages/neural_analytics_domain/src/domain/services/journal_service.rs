@@ -0,0 +1,186 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+use log::{error, info};
+
+// Number of entries kept in memory for the frontends and diagnostics
+const JOURNAL_TAIL_SIZE: usize = 256;
+
+/// A single timestamped entry of the session journal.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct JournalEntry {
+    /// Unix epoch milliseconds when the entry was recorded.
+    pub timestamp: i64,
+    pub kind: String,
+    pub payload: serde_json::Value,
+}
+
+/// Append-only journal of the session.
+///
+/// Entries are kept in a bounded in-memory tail and, when a path is configured,
+/// appended to a JSON Lines file so sessions can be analyzed offline.
+pub struct JournalService {
+    file: Option<File>,
+    tail: VecDeque<JournalEntry>,
+    // Estimated heap footprint of the tail, kept up to date as entries come and go
+    tail_bytes: usize,
+}
+
+/// Estimates the heap footprint of an entry of the tail.
+fn entry_bytes(entry: &JournalEntry) -> usize {
+    std::mem::size_of::<JournalEntry>() + entry.kind.capacity() + entry.payload.to_string().len()
+}
+
+impl JournalService {
+    /// Creates the journal, opening the file in append mode if a path is given.
+    ///
+    /// # Arguments
+    /// * `path`: The JSON Lines file to append to, or `None` to keep the journal in memory.
+    pub fn new(path: Option<&str>) -> Self {
+        let file = path.and_then(|path| {
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => {
+                    info!("Writing session journal to {}", path);
+                    Some(file)
+                }
+                Err(e) => {
+                    error!("Could not open session journal {}: {}", path, e);
+                    None
+                }
+            }
+        });
+
+        Self {
+            file,
+            tail: VecDeque::with_capacity(JOURNAL_TAIL_SIZE),
+            tail_bytes: 0,
+        }
+    }
+
+    /// Records an entry in the journal.
+    ///
+    /// # Arguments
+    /// * `kind`: The entry type, e.g. `actuation`.
+    /// * `payload`: Any serializable value describing the entry.
+    pub fn record<T: serde::Serialize>(&mut self, kind: &str, payload: &T) {
+        self.record_at(chrono::Utc::now().timestamp_millis(), kind, payload);
+    }
+
+    /// Records an entry that happened before it reached the journal, such as an operator action.
+    ///
+    /// # Arguments
+    /// * `timestamp`: Unix epoch milliseconds when it happened.
+    /// * `kind`: The entry type, e.g. `operator_action`.
+    /// * `payload`: Any serializable value describing the entry.
+    pub fn record_at<T: serde::Serialize>(&mut self, timestamp: i64, kind: &str, payload: &T) {
+        let payload = match serde_json::to_value(payload) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Could not serialize journal entry '{}': {}", kind, e);
+                return;
+            }
+        };
+
+        let entry = JournalEntry {
+            timestamp,
+            kind: kind.to_string(),
+            payload,
+        };
+
+        if let Some(file) = self.file.as_mut() {
+            let written = serde_json::to_string(&entry)
+                .map_err(|e| e.to_string())
+                .and_then(|line| writeln!(file, "{}", line).map_err(|e| e.to_string()));
+
+            if let Err(e) = written {
+                error!("Could not write journal entry '{}': {}", kind, e);
+            }
+        }
+
+        if self.tail.len() >= JOURNAL_TAIL_SIZE {
+            self.pop_oldest();
+        }
+
+        self.tail_bytes += entry_bytes(&entry);
+        self.tail.push_back(entry);
+    }
+
+    fn pop_oldest(&mut self) {
+        if let Some(entry) = self.tail.pop_front() {
+            self.tail_bytes -= entry_bytes(&entry);
+        }
+    }
+
+    /// Syncs the journal file to disk, so its last entries survive the exit of the process.
+    pub fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = file.sync_all() {
+                error!("Could not sync the session journal: {}", e);
+            }
+        }
+    }
+
+    /// Returns the most recent entries, oldest first.
+    pub fn tail(&self) -> &VecDeque<JournalEntry> {
+        &self.tail
+    }
+
+    /// Drops the oldest in-memory entries, keeping at most `len`. The file is not affected.
+    pub fn truncate_tail(&mut self, len: usize) {
+        while self.tail.len() > len {
+            self.pop_oldest();
+        }
+    }
+
+    /// Estimates the heap footprint of the in-memory tail, counted as the entries are
+    /// pushed and evicted, so it is cheap enough to refresh on every event.
+    pub fn estimated_bytes(&self) -> usize {
+        self.tail_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_journal_appends_json_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let mut journal = JournalService::new(path.to_str());
+
+        journal.record("actuation", &serde_json::json!({ "window_id": 1 }));
+        journal.record("actuation", &serde_json::json!({ "window_id": 2 }));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<JournalEntry> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].kind, "actuation");
+        assert_eq!(entries[1].payload["window_id"], 2);
+    }
+
+    #[test]
+    fn test_journal_tail_is_bounded() {
+        let mut journal = JournalService::new(None);
+
+        for i in 0..JOURNAL_TAIL_SIZE + 10 {
+            journal.record("tick", &i);
+        }
+
+        assert_eq!(journal.tail().len(), JOURNAL_TAIL_SIZE);
+        assert_eq!(journal.tail().front().unwrap().payload, 10);
+
+        let expected_bytes: usize = journal.tail().iter().map(entry_bytes).sum();
+        assert_eq!(journal.estimated_bytes(), expected_bytes);
+
+        journal.truncate_tail(1);
+        assert_eq!(journal.tail().len(), 1);
+        assert_eq!(journal.estimated_bytes(), entry_bytes(journal.tail().front().unwrap()));
+    }
+}
\ No newline at end of file
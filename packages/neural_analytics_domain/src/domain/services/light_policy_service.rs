@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use log::debug;
+
+use crate::domain::models::{
+    core_config::CoreConfig,
+    exhibition::{ExhibitionCue, ExhibitionMode},
+    prediction_action::PredictionAction,
+};
+
+/// Translates the smoothed prediction of the model into the action configured for it.
+///
+/// In exhibition mode it also decides when a prediction is shown, so the bulb and the
+/// overlay of the GUI follow the same changes.
+pub struct LightPolicyService {
+    action_mapping: HashMap<String, PredictionAction>,
+    default_action: PredictionAction,
+    exhibition: ExhibitionMode,
+    // Class shown in exhibition mode, with the time it was shown at
+    shown: Option<(String, Instant)>,
+}
+
+impl Default for LightPolicyService {
+    fn default() -> Self {
+        Self::new(&CoreConfig::default())
+    }
+}
+
+impl LightPolicyService {
+    /// Builds the policy from the mapping table of the core configuration.
+    pub fn new(config: &CoreConfig) -> Self {
+        Self {
+            action_mapping: config.action_mapping.clone(),
+            default_action: config.default_action.clone(),
+            exhibition: config.exhibition.clone(),
+            shown: None,
+        }
+    }
+
+    /// Resolves the action for a prediction class, falling back to the default action
+    /// for classes that are not present in the mapping table.
+    ///
+    /// # Arguments
+    /// * `prediction`: The prediction class, e.g. "green" or "unknown".
+    ///
+    /// # Returns
+    /// * `PredictionAction`: The action to perform.
+    pub fn resolve_action(&self, prediction: &str) -> PredictionAction {
+        let action = self
+            .action_mapping
+            .get(prediction)
+            .cloned()
+            .unwrap_or_else(|| self.default_action.clone());
+
+        debug!("Prediction '{}' resolved to action {:?}", prediction, action);
+        action
+    }
+
+    /// Decides the action for a prediction and, in exhibition mode, the cue showing it.
+    ///
+    /// Outside of exhibition mode every prediction is acted on, without a cue. In exhibition
+    /// mode only a change of the class is, once the previous one was held for its time; the
+    /// other predictions do nothing, so the bulb and the overlay keep showing the last change.
+    ///
+    /// # Arguments
+    /// * `prediction`: The smoothed prediction class, empty while the predictions disagree.
+    /// * `now`: The time of the decision.
+    ///
+    /// # Returns
+    /// * `(PredictionAction, Option<ExhibitionCue>)`: The action to perform, and the cue
+    ///   to show along with it in exhibition mode.
+    pub fn decide(&mut self, prediction: &str, now: Instant) -> (PredictionAction, Option<ExhibitionCue>) {
+        if !self.exhibition.enabled {
+            return (self.resolve_action(prediction), None);
+        }
+
+        let hold = Duration::from_millis(self.exhibition.hold_ms);
+        let is_held = self
+            .shown
+            .as_ref()
+            .is_some_and(|(class, shown_at)| class == prediction || now.duration_since(*shown_at) < hold);
+
+        if prediction.is_empty() || is_held {
+            return (PredictionAction::Nothing, None);
+        }
+
+        let action = self.resolve_action(prediction);
+        self.shown = Some((prediction.to_string(), now));
+
+        let cue = ExhibitionCue {
+            class: prediction.to_string(),
+            action: action.clone(),
+            hold_ms: self.exhibition.hold_ms,
+        };
+
+        (action, Some(cue))
+    }
+
+    /// Forgets the class shown in exhibition mode, so the first prediction of a new session is shown at once.
+    pub fn reset(&mut self) {
+        self.shown = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_matches_legacy_behaviour() {
+        let policy = LightPolicyService::default();
+
+        assert_eq!(policy.resolve_action("green"), PredictionAction::TurnOn);
+        assert_eq!(policy.resolve_action("red"), PredictionAction::TurnOff);
+        assert_eq!(policy.resolve_action("unknown"), PredictionAction::TurnOff);
+    }
+
+    #[test]
+    fn test_custom_mapping_is_used() {
+        let mut config = CoreConfig::default();
+        config.action_mapping.insert(
+            "red".to_string(),
+            PredictionAction::Color {
+                red: 255,
+                green: 0,
+                blue: 0,
+            },
+        );
+        config.default_action = PredictionAction::Nothing;
+
+        let policy = LightPolicyService::new(&config);
+
+        assert_eq!(
+            policy.resolve_action("red"),
+            PredictionAction::Color {
+                red: 255,
+                green: 0,
+                blue: 0
+            }
+        );
+        assert_eq!(policy.resolve_action("trash"), PredictionAction::Nothing);
+    }
+
+    #[test]
+    fn test_exhibition_mode_holds_each_change() {
+        let mut config = CoreConfig::default();
+        config.exhibition.enabled = true;
+        config.exhibition.hold_ms = 5_000;
+
+        let mut policy = LightPolicyService::new(&config);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let (action, cue) = policy.decide("green", at(0));
+        assert_eq!(action, PredictionAction::TurnOn);
+        assert_eq!(cue.unwrap().hold_ms, 5_000);
+
+        // Neither a change during the hold nor the same class after it is shown
+        assert_eq!(policy.decide("red", at(2_000)), (PredictionAction::Nothing, None));
+        assert_eq!(policy.decide("green", at(6_000)), (PredictionAction::Nothing, None));
+        assert_eq!(policy.decide("", at(7_000)), (PredictionAction::Nothing, None));
+
+        let (action, cue) = policy.decide("red", at(7_000));
+        assert_eq!(action, PredictionAction::TurnOff);
+        assert_eq!(cue.unwrap().class, "red");
+    }
+
+    #[test]
+    fn test_mapping_deserializes_from_json() {
+        let config: CoreConfig = serde_json::from_str(
+            r#"{
+                "action_mapping": {
+                    "green": { "action": "webhook", "url": "http://localhost/hook" }
+                },
+                "default_action": { "action": "nothing" }
+            }"#,
+        )
+        .unwrap();
+
+        let policy = LightPolicyService::new(&config);
+
+        assert_eq!(
+            policy.resolve_action("green"),
+            PredictionAction::Webhook {
+                url: "http://localhost/hook".to_string()
+            }
+        );
+        assert_eq!(policy.resolve_action("red"), PredictionAction::Nothing);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::mem::size_of;
+
+use log::warn;
+
+/// Estimates the heap footprint of a window of samples per channel.
+pub fn estimate_samples_bytes(data: &HashMap<String, Vec<f32>>) -> usize {
+    data.iter()
+        .map(|(channel, values)| channel.capacity() + values.capacity() * size_of::<f32>())
+        .sum()
+}
+
+/// Estimates the heap footprint of a collection of strings.
+pub fn estimate_strings_bytes<'a>(values: impl Iterator<Item = &'a String>) -> usize {
+    values
+        .map(|value| size_of::<String>() + value.capacity())
+        .sum()
+}
+
+/// Tracks the estimated memory used by the context buffers against a global budget.
+///
+/// Buffers report their estimate after every change; the service keeps the latest
+/// value per buffer and detects the moment the total crosses the budget.
+pub struct MemoryBudgetService {
+    budget_bytes: usize,
+    usage: HashMap<String, usize>,
+    is_exceeded: bool,
+}
+
+impl MemoryBudgetService {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            budget_bytes,
+            usage: HashMap::new(),
+            is_exceeded: false,
+        }
+    }
+
+    /// Records the current estimate of a buffer.
+    ///
+    /// # Returns
+    /// * `bool`: `true` only when this update pushed the total over the budget, so callers can notify once per pressure episode.
+    pub fn update_usage(&mut self, buffer: &str, bytes: usize) -> bool {
+        self.usage.insert(buffer.to_string(), bytes);
+
+        let was_exceeded = self.is_exceeded;
+        self.is_exceeded = self.total_usage() > self.budget_bytes;
+
+        if self.is_exceeded && !was_exceeded {
+            warn!(
+                "Memory budget exceeded: {} bytes used of {} bytes",
+                self.total_usage(),
+                self.budget_bytes
+            );
+            return true;
+        }
+
+        false
+    }
+
+    pub fn total_usage(&self) -> usize {
+        self.usage.values().sum()
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.is_exceeded
+    }
+
+    pub fn usage(&self) -> &HashMap<String, usize> {
+        &self.usage
+    }
+
+    pub fn budget_bytes(&self) -> usize {
+        self.budget_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_crossing_is_reported_once() {
+        let mut budget = MemoryBudgetService::new(100);
+
+        assert!(!budget.update_usage("color_thinking", 60));
+        assert!(budget.update_usage("headset_data", 60));
+        assert!(!budget.update_usage("headset_data", 80));
+        assert!(budget.is_exceeded());
+        assert_eq!(budget.total_usage(), 140);
+    }
+
+    #[test]
+    fn test_budget_recovers_below_limit() {
+        let mut budget = MemoryBudgetService::new(100);
+
+        budget.update_usage("headset_data", 120);
+        assert!(budget.is_exceeded());
+
+        assert!(!budget.update_usage("headset_data", 10));
+        assert!(!budget.is_exceeded());
+        assert!(budget.update_usage("headset_data", 120));
+    }
+
+    #[test]
+    fn test_estimate_samples_bytes() {
+        let mut data = HashMap::new();
+        data.insert("T3".to_string(), vec![0.0f32; 62]);
+
+        assert!(estimate_samples_bytes(&data) >= 62 * size_of::<f32>());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// Prefix of the metrics in the Prometheus exposition format
+const PROMETHEUS_PREFIX: &str = "neural_analytics_";
+
+/// Point-in-time copy of the metrics, safe to hand out to frontends.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MetricsSnapshot {
+    pub counters: HashMap<String, u64>,
+    pub gauges: HashMap<String, f64>,
+}
+
+/// In-memory registry of counters and gauges collected by the core.
+#[derive(Default)]
+pub struct MetricsService {
+    counters: HashMap<String, u64>,
+    gauges: HashMap<String, f64>,
+}
+
+impl MetricsService {
+    /// Increments a monotonic counter, creating it on first use.
+    pub fn increment_counter(&mut self, name: &str, value: u64) {
+        *self.counters.entry(name.to_string()).or_insert(0) += value;
+    }
+
+    /// Increments the counter of a combination of labels, e.g. the transitions from one
+    /// state to another, named `name{label="value",...}` like a Prometheus series.
+    pub fn increment_labeled_counter(&mut self, name: &str, labels: &[(&str, &str)], value: u64) {
+        let labels = labels
+            .iter()
+            .map(|(label, label_value)| format!("{}=\"{}\"", label, label_value.replace('\\', "\\\\").replace('"', "\\\"")))
+            .collect::<Vec<_>>();
+
+        self.increment_counter(&format!("{}{{{}}}", name, labels.join(",")), value);
+    }
+
+    /// Sets a gauge to its latest observed value.
+    pub fn set_gauge(&mut self, name: &str, value: f64) {
+        self.gauges.insert(name.to_string(), value);
+    }
+
+    /// Returns the current value of a counter, or 0 if it was never incremented.
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or(0)
+    }
+
+    /// Returns the current value of a gauge, if it was ever set.
+    pub fn gauge(&self, name: &str) -> Option<f64> {
+        self.gauges.get(name).copied()
+    }
+
+    /// Copies all the metrics collected so far.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            counters: self.counters.clone(),
+            gauges: self.gauges.clone(),
+        }
+    }
+}
+
+impl MetricsSnapshot {
+    /// Renders the metrics in the Prometheus text exposition format, for scrapers.
+    ///
+    /// The dots of the names become underscores and the counters get the `_total`
+    /// suffix, e.g. `windows.rejected` is exported as `neural_analytics_windows_rejected_total`.
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::new();
+
+        write_prometheus_family(&mut output, &self.counters, "counter", "_total");
+        write_prometheus_family(&mut output, &self.gauges, "gauge", "");
+
+        output
+    }
+}
+
+// Writes the series of one kind of metric, grouped by name under their `# TYPE` line
+fn write_prometheus_family<T: std::fmt::Display>(output: &mut String, metrics: &HashMap<String, T>, kind: &str, suffix: &str) {
+    let mut series = metrics
+        .iter()
+        .map(|(key, value)| {
+            let (name, labels) = key.split_at(key.find('{').unwrap_or(key.len()));
+            let name = name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+                .collect::<String>();
+
+            (format!("{}{}{}", PROMETHEUS_PREFIX, name, suffix), labels, value)
+        })
+        .collect::<Vec<_>>();
+    series.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+
+    let mut last_name = None;
+    for (name, labels, value) in series {
+        if last_name.as_ref() != Some(&name) {
+            let _ = writeln!(output, "# TYPE {} {}", name, kind);
+        }
+
+        let _ = writeln!(output, "{}{} {}", name, labels, value);
+        last_name = Some(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_accumulate() {
+        let mut metrics = MetricsService::default();
+
+        metrics.increment_counter("windows", 1);
+        metrics.increment_counter("windows", 2);
+
+        assert_eq!(metrics.counter("windows"), 3);
+        assert_eq!(metrics.counter("missing"), 0);
+    }
+
+    #[test]
+    fn test_gauges_keep_last_value() {
+        let mut metrics = MetricsService::default();
+
+        metrics.set_gauge("memory.total.bytes", 10.0);
+        metrics.set_gauge("memory.total.bytes", 4.0);
+
+        assert_eq!(metrics.gauge("memory.total.bytes"), Some(4.0));
+        assert_eq!(metrics.snapshot().gauges.len(), 1);
+    }
+
+    #[test]
+    fn test_to_prometheus_groups_the_labeled_series() {
+        let mut metrics = MetricsService::default();
+
+        metrics.increment_labeled_counter("state.transitions", &[("from", "a"), ("to", "b")], 2);
+        metrics.increment_labeled_counter("state.transitions", &[("from", "b"), ("to", "a")], 1);
+        metrics.increment_counter("commands.search-headband.failed", 3);
+        metrics.set_gauge("windows.interval.ms", 248.0);
+
+        assert_eq!(metrics.counter("state.transitions{from=\"a\",to=\"b\"}"), 2);
+        assert_eq!(
+            metrics.snapshot().to_prometheus(),
+            "# TYPE neural_analytics_commands_search_headband_failed_total counter\n\
+             neural_analytics_commands_search_headband_failed_total 3\n\
+             # TYPE neural_analytics_state_transitions_total counter\n\
+             neural_analytics_state_transitions_total{from=\"a\",to=\"b\"} 2\n\
+             neural_analytics_state_transitions_total{from=\"b\",to=\"a\"} 1\n\
+             # TYPE neural_analytics_windows_interval_ms gauge\n\
+             neural_analytics_windows_interval_ms 248\n"
+        );
+    }
+}
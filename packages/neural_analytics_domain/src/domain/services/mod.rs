@@ -0,0 +1,35 @@
+pub mod asset_path_service;
+pub mod auto_off_bulb_service;
+pub mod band_power_service;
+pub mod benchmark_service;
+pub mod calibration_policy_service;
+pub mod calibration_profile_service;
+pub mod calibration_update_service;
+pub mod clock_drift_service;
+pub mod data_integrity_service;
+pub mod eeg_synthesizer_service;
+pub mod explanation_service;
+pub mod firmware_compatibility_service;
+pub mod inference_cache_service;
+pub mod inference_runtime_service;
+pub mod journal_replay_service;
+pub mod journal_service;
+pub mod light_policy_service;
+pub mod memory_budget_service;
+pub mod metrics_service;
+pub mod model_inference_service;
+pub mod model_watcher_service;
+pub mod payload_policy_service;
+pub mod prediction_smoothing_service;
+pub mod prediction_streak_service;
+pub mod queue_monitor_service;
+pub mod runtime_service;
+pub mod session_stats_service;
+pub mod soak_service;
+pub mod stale_data_service;
+pub mod streaming_inference_service;
+pub mod test_signal_service;
+pub mod threshold_advisor_service;
+pub mod window_assembler_service;
+pub mod window_labeler_service;
+pub mod window_overlap_service;
@@ -0,0 +1,955 @@
+use log::{info, warn};
+use neural_analytics_signal_math as signal_math;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::{
+    asset_path_service::resolve_asset,
+    inference_runtime_service::{load_runtime, InferenceRuntime, ModelSignature},
+};
+use crate::domain::models::{
+    band_powers::BandPowers, inference_config::InferenceConfig, preprocessing_spec::PreprocessingSpec,
+    window_prediction::WindowPrediction,
+};
+use crate::domain::ports::output::tensor_dump::TensorDumpPort;
+
+/// File name of the model loaded by default, resolved by `resolve_asset`.
+pub const DEFAULT_MODEL_ASSET: &str = "neural_analytics.onnx";
+
+
+/// Classes of the models exported without a label map, in the order of their output.
+const DEFAULT_CLASS_LABELS: [&str; 3] = ["red", "green", "trash"];
+
+/// Extension of the label map exported next to the model, e.g. `neural_analytics.labels.json`.
+const LABEL_MAP_EXTENSION: &str = "labels.json";
+
+/// Extension of the preprocessing spec exported next to the model, e.g. `neural_analytics.preprocessing.json`.
+const PREPROCESSING_SPEC_EXTENSION: &str = "preprocessing.json";
+
+// Trait that defines the interface for the inference service
+pub trait ModelInferenceInterface: Send + Sync + 'static {
+    /// Predicts the color the user is thinking based on EEG data
+    fn predict_color(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<String, String>;
+
+    /// Predicts the color along with the probabilities of the classes and the latency of
+    /// the model, without probabilities for the models that do not report them
+    fn predict_color_detailed(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<WindowPrediction, String> {
+        let started = Instant::now();
+        let color = self.predict_color(eeg_data)?;
+
+        Ok(WindowPrediction::from_label(color, started.elapsed().as_secs_f64() * 1000.0))
+    }
+
+    /// Predicts the color with the band powers of each channel as extra features, for the
+    /// models trained with them. The models that do not use them ignore the band powers.
+    fn predict_color_with_band_powers(
+        &self,
+        eeg_data: &HashMap<String, Vec<f32>>,
+        _band_powers: &HashMap<String, BandPowers>,
+    ) -> Result<String, String> {
+        self.predict_color(eeg_data)
+    }
+
+    /// Returns the probability of every class for the EEG data, used to explain the predictions
+    fn class_probabilities(&self, _eeg_data: &HashMap<String, Vec<f32>>) -> Result<HashMap<String, f32>, String> {
+        Err("The model does not report class probabilities".to_string())
+    }
+
+    /// Predicts the class of a window along with the confidence of the model in it
+    fn predict_window(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<WindowPrediction, String> {
+        let started = Instant::now();
+        let probabilities = self.class_probabilities(eeg_data)?;
+
+        WindowPrediction::from_probabilities(probabilities, started.elapsed().as_secs_f64() * 1000.0)
+            .ok_or_else(|| "The model reported no class probabilities".to_string())
+    }
+
+    /// Predicts a batch of windows, in their order, failing with the first window that fails
+    fn predict_batch(&self, windows: &[HashMap<String, Vec<f32>>]) -> Result<Vec<WindowPrediction>, String> {
+        windows.iter().map(|window| self.predict_window(window)).collect()
+    }
+
+    /// Returns the preprocessing of the model, setting the samples and channels of its windows
+    fn preprocessing(&self) -> PreprocessingSpec {
+        PreprocessingSpec::default()
+    }
+
+    /// Checks if the model is loaded and ready for predictions
+    fn is_model_loaded(&self) -> bool;
+
+    /// Returns the reason why the model could not be loaded, if any
+    fn load_error(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns the classes the model predicts, in the order of its output
+    fn class_labels(&self) -> Vec<String> {
+        default_class_labels()
+    }
+
+    /// Returns the fingerprint of the loaded model file, to trace the results back to it
+    fn model_hash(&self) -> Option<String> {
+        None
+    }
+}
+
+pub struct ModelInferenceService {
+    // The ONNX model, loaded in the runtime of the inference config
+    model: Option<Arc<dyn InferenceRuntime>>,
+    // Runtime the model is loaded in, tract by default
+    inference: InferenceConfig,
+    // Path to the model file
+    model_path: String,
+    // Reason of the last failed load, kept for the diagnostics command
+    load_error: Option<String>,
+    // Classes of the model, read from its label map
+    class_labels: Vec<String>,
+    // Fingerprint of the file the loaded model was read from
+    model_hash: Option<String>,
+    // Dump of the tensors of the predictions, plugged by the composition crate in debug mode
+    tensor_dump: Option<Arc<dyn TensorDumpPort>>,
+    // Preprocessing the model was trained with, read from its spec
+    preprocessing: PreprocessingSpec,
+}
+
+impl Default for ModelInferenceService {
+    fn default() -> Self {
+        // Define the default path to the model
+        let model_path = default_model_path();
+        let mut service = Self {
+            model: None,
+            model_path,
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        // Try to load the model automatically
+        match service.load_model() {
+            Ok(_) => info!("ONNX model successfully loaded"),
+            Err(e) => warn!("Could not load the model automatically: {}", e),
+        }
+
+        service
+    }
+}
+
+/// Path of the model loaded by default, found wherever the application was launched from.
+pub fn default_model_path() -> String {
+    resolve_asset(DEFAULT_MODEL_ASSET).to_string_lossy().into_owned()
+}
+
+impl Drop for ModelInferenceService {
+    fn drop(&mut self) {
+        // Explicitly release resources if necessary
+        if self.model.is_some() {
+            info!("Releasing the model resources");
+            self.model = None;
+        }
+    }
+}
+
+impl ModelInferenceService {
+    // Custom constructor if we need a different path
+    pub fn new(model_path: &str) -> Self {
+        Self::with_inference(model_path, InferenceConfig::default())
+    }
+
+    /// Loads the model of `model_path` in the runtime of the inference config
+    pub fn with_inference(model_path: &str, inference: InferenceConfig) -> Self {
+        let mut service = Self {
+            model: None,
+            model_path: model_path.to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference,
+        };
+
+        // Try to load the model
+        match service.load_model() {
+            Ok(_) => info!("ONNX model successfully loaded from: {}", model_path),
+            Err(e) => warn!("Could not load the model from {}: {}", model_path, e),
+        }
+
+        service
+    }
+
+    /// Dumps the tensors of every prediction, for the model developers to compare the
+    /// preprocessing of the core with the one of the training pipeline
+    pub fn with_tensor_dump(mut self, tensor_dump: Arc<dyn TensorDumpPort>) -> Self {
+        self.tensor_dump = Some(tensor_dump);
+        self
+    }
+
+    /// Loads the ONNX model from the specified path in the runtime of the inference config
+    ///
+    /// The input and output dimensions of the model are validated against the shapes
+    /// expected by `predict_color`, so a wrong model fails here with a detailed
+    /// message instead of at inference time.
+    pub fn load_model(&mut self) -> Result<(), String> {
+        let result = self.try_load_model();
+        self.load_error = result.as_ref().err().cloned();
+        result
+    }
+
+    fn try_load_model(&mut self) -> Result<(), String> {
+        let path = Path::new(&self.model_path);
+
+        if !path.exists() {
+            return Err(format!(
+                "Model file does not exist at path: {}",
+                self.model_path
+            ));
+        }
+
+        let model = load_runtime(&self.inference, path)?;
+
+        let class_labels = load_class_labels(path)?;
+        let preprocessing = load_preprocessing_spec(path)?;
+        Self::validate_signature(model.signature(), &preprocessing, class_labels.len())?;
+
+        let model_bytes = std::fs::read(path).map_err(|e| format!("Error hashing the model: {}", e))?;
+
+        self.model = Some(Arc::from(model));
+        self.class_labels = class_labels;
+        self.preprocessing = preprocessing;
+        self.model_hash = Some(model_fingerprint(&model_bytes));
+        Ok(())
+    }
+
+    /// Checks the input/output dimensions of the model against the expected shapes,
+    /// the input being the windows of the preprocessing spec and the output having one
+    /// probability per class of the label map
+    fn validate_signature(signature: &ModelSignature, preprocessing: &PreprocessingSpec, class_count: usize) -> Result<(), String> {
+        validate_shape("input", &expected_input_shape(preprocessing), &signature.input)?;
+        validate_shape("output", &expected_output_shape(class_count), &signature.output)
+    }
+
+    /// Preprocesses the EEG data before passing it to the model
+    /// This function implements the preprocessing of the spec exported by the training,
+    /// and formats the data into the expected shape [batch_size, window_samples, channels]
+    fn preprocess_data(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<Vec<f32>, String> {
+        // Check that the required channels are present
+        let required_channels = &self.preprocessing.channels;
+        for channel in required_channels.iter() {
+            if !eeg_data.contains_key(channel) {
+                return Err(format!(
+                    "Required channel '{}' not found in EEG data",
+                    channel
+                ));
+            }
+        }
+
+        // Process each channel to obtain the normalized values of a window per channel
+        // Then we organize the data in the format expected by the model [batch_size, window_samples, channels]
+        let expected_samples = self.preprocessing.window_samples;
+        let mut normalized_channels = Vec::new();
+
+        for channel in required_channels.iter() {
+            let channel_data = eeg_data.get(channel).unwrap();
+
+            if channel_data.is_empty() {
+                return Err(format!("Channel '{}' has no data", channel));
+            }
+
+            // Apply the normalization used in training, then resize or truncate to exactly
+            // the samples of a window, windows are completed upstream by the window assembler
+            let mut channel_values = channel_data.clone();
+            self.preprocessing.normalization.apply(&mut channel_values);
+
+            let mut window = vec![0.0; expected_samples];
+            signal_math::fit_window(&channel_values, &mut window);
+
+            // Store the normalized and resized channel data
+            normalized_channels.push(window);
+        }
+
+        // Now we have the channels of the spec with a window of values each
+        // We organize them into a flat vector that will later be reshaped as [1, 62, 4] by default
+        // IMPORTANT: The LSTM model expects data organized as [batch_size, seq_length, input_size]
+        // where seq_length=window_samples (temporal points) and input_size=channels, in the order of the spec
+        // Each temporal entry must contain values from all channels for that time point:
+        // [T3_0, T4_0, O1_0, O2_0, T3_1, T4_1, O1_1, O2_1, ..., T3_61, T4_61, O1_61, O2_61]
+        let channels: Vec<&[f32]> = normalized_channels.iter().map(Vec::as_slice).collect();
+        let mut processed_data = vec![0.0; channels.len() * expected_samples];
+        signal_math::interleave(&channels, &mut processed_data);
+
+        // Log information about the processed data
+        info!(
+            "Preprocessed data: {} channels x {} samples = {} elements",
+            required_channels.len(),
+            expected_samples,
+            processed_data.len()
+        );
+
+        Ok(processed_data)
+    }
+}
+
+impl ModelInferenceInterface for ModelInferenceService {
+    fn predict_color(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<String, String> {
+        let (input, mut output_vec) = self.run_model(eeg_data)?;
+
+        // Only the predictions are dumped, not the extra inferences explaining them
+        if let Some(tensor_dump) = &self.tensor_dump {
+            tensor_dump.record(&self.preprocessing.input_shape(), &input, &output_vec);
+        }
+
+        signal_math::softmax(&mut output_vec);
+
+        // Find the color with the highest probability
+        let max_idx = signal_math::argmax(&output_vec).unwrap_or_default();
+
+        // Return the predicted color, if the index is valid
+        self.class_labels
+            .get(max_idx)
+            .cloned()
+            .ok_or_else(|| format!("Prediction index out of range: {}", max_idx))
+    }
+
+    fn class_probabilities(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<HashMap<String, f32>, String> {
+        let output_vec = self.infer_probabilities(eeg_data)?;
+
+        Ok(self
+            .class_labels
+            .iter()
+            .cloned()
+            .zip(output_vec)
+            .collect())
+    }
+
+    fn predict_color_detailed(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<WindowPrediction, String> {
+        self.predict_window(eeg_data)
+    }
+
+    fn predict_window(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<WindowPrediction, String> {
+        let started = Instant::now();
+        let (input, mut output_vec) = self.run_model(eeg_data)?;
+
+        if let Some(tensor_dump) = &self.tensor_dump {
+            tensor_dump.record(&self.preprocessing.input_shape(), &input, &output_vec);
+        }
+
+        signal_math::softmax(&mut output_vec);
+
+        let probabilities = self.class_labels.iter().cloned().zip(output_vec).collect();
+        WindowPrediction::from_probabilities(probabilities, started.elapsed().as_secs_f64() * 1000.0)
+            .ok_or_else(|| "No probabilities obtained from the model".to_string())
+    }
+
+    fn is_model_loaded(&self) -> bool {
+        self.model.is_some()
+    }
+
+    fn load_error(&self) -> Option<String> {
+        self.load_error.clone()
+    }
+
+    fn class_labels(&self) -> Vec<String> {
+        self.class_labels.clone()
+    }
+
+    fn model_hash(&self) -> Option<String> {
+        self.model_hash.clone()
+    }
+
+    fn preprocessing(&self) -> PreprocessingSpec {
+        self.preprocessing.clone()
+    }
+}
+
+impl ModelInferenceService {
+    /// Runs the model on the window and returns the softmax of its output, in the order of the class labels
+    fn infer_probabilities(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<Vec<f32>, String> {
+        let (_, mut output_vec) = self.run_model(eeg_data)?;
+        signal_math::softmax(&mut output_vec);
+
+        Ok(output_vec)
+    }
+
+    /// Runs the model on the window and returns its input tensor and its raw output
+    fn run_model(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<(Vec<f32>, Vec<f32>), String> {
+        // Check that the model is loaded
+        let model = match &self.model {
+            Some(model) => model.clone(),
+            None => return Err("Model is not loaded. Call load_model first.".to_string()),
+        };
+
+        // Preprocess the data
+        let processed_data = self.preprocess_data(eeg_data)?;
+
+        // Log the actual length of the processed data
+        info!("Processed data length: {}", processed_data.len());
+
+        // We verify that we have exactly a window of samples per channel, 62*4 = 248 elements by default
+        let [batch_size, window_samples, channel_count] = self.preprocessing.input_shape();
+        let expected_elements = window_samples * channel_count;
+        if processed_data.len() != expected_elements {
+            return Err(format!(
+                "Processed data has unexpected length: {} (expected {})",
+                processed_data.len(),
+                expected_elements
+            ));
+        }
+
+        // We process one example at a time
+        info!(
+            "Creating tensor with shape [batch_size={}, {}, {}]",
+            batch_size, window_samples, channel_count
+        );
+
+        // Perform inference in the runtime of the model, the tensor having the shape
+        // [batch_size, window_samples, channels]. The softmax is applied by the callers,
+        // the dump keeps the raw logits
+        let output_vec = model.run(processed_data.clone(), [batch_size, window_samples, channel_count])?;
+
+        if output_vec.is_empty() {
+            return Err("No probabilities obtained from the model".to_string());
+        }
+
+        Ok((processed_data, output_vec))
+    }
+}
+
+/// Classes of the models exported without a label map
+pub fn default_class_labels() -> Vec<String> {
+    DEFAULT_CLASS_LABELS.iter().map(|label| label.to_string()).collect()
+}
+
+/// Input shape expected by the model: [batch_size, window samples, channels] of the spec.
+/// `None` marks a dimension that may be symbolic or take any value.
+fn expected_input_shape(preprocessing: &PreprocessingSpec) -> [Option<usize>; 3] {
+    [None, Some(preprocessing.window_samples), Some(preprocessing.channels.len())]
+}
+
+/// Output shape expected by the model: [batch_size, one probability per class].
+fn expected_output_shape(class_count: usize) -> [Option<usize>; 2] {
+    [None, Some(class_count)]
+}
+
+/// Reads the label map exported next to the model, a JSON list of its classes
+/// in the order of its output.
+///
+/// Models exported before the label maps have none, they predict the default classes.
+fn load_class_labels(model_path: &Path) -> Result<Vec<String>, String> {
+    let labels_path = model_path.with_extension(LABEL_MAP_EXTENSION);
+
+    if !labels_path.exists() {
+        return Ok(default_class_labels());
+    }
+
+    let labels: Vec<String> = std::fs::read_to_string(&labels_path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Invalid label map {}: {}", labels_path.display(), e))?;
+
+    let is_unique = labels.iter().enumerate().all(|(index, label)| !labels[..index].contains(label));
+
+    if labels.is_empty() || !is_unique {
+        return Err(format!(
+            "Invalid label map {}: expected a list of distinct classes, found {:?}",
+            labels_path.display(),
+            labels
+        ));
+    }
+
+    Ok(labels)
+}
+
+/// Reads the preprocessing spec exported next to the model by the training pipeline.
+///
+/// Models exported before the specs have none, they are preprocessed as the core always did.
+fn load_preprocessing_spec(model_path: &Path) -> Result<PreprocessingSpec, String> {
+    let spec_path = model_path.with_extension(PREPROCESSING_SPEC_EXTENSION);
+
+    if !spec_path.exists() {
+        warn!("No preprocessing spec at {}, using the default preprocessing", spec_path.display());
+        return Ok(PreprocessingSpec::default());
+    }
+
+    std::fs::read_to_string(&spec_path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| PreprocessingSpec::parse(&content))
+        .map_err(|e| format!("Invalid preprocessing spec {}: {}", spec_path.display(), e))
+}
+
+/// Fingerprints a model file with the 64-bit FNV-1a hash, stable across builds and platforms
+fn model_fingerprint(bytes: &[u8]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = bytes
+        .iter()
+        .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(PRIME));
+
+    format!("{:016x}", hash)
+}
+
+/// Formats a shape as `[?, 62, 4]`, using `?` for symbolic or unconstrained dimensions
+fn format_shape(dims: &[Option<usize>]) -> String {
+    let dims = dims
+        .iter()
+        .map(|dim| dim.map_or("?".to_string(), |value| value.to_string()))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", dims.join(", "))
+}
+
+/// Validates the rank and the fixed dimensions of a model tensor.
+///
+/// # Arguments
+/// * `name`: The tensor name used in the error message (input/output).
+/// * `expected`: The expected dimensions; `None` accepts any value.
+/// * `found`: The dimensions declared by the model; `None` for symbolic dimensions.
+fn validate_shape(name: &str, expected: &[Option<usize>], found: &[Option<usize>]) -> Result<(), String> {
+    let is_valid = expected.len() == found.len()
+        && expected
+            .iter()
+            .zip(found)
+            .all(|(expected, found)| expected.is_none() || expected == found);
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Model {} shape mismatch: expected {} (rank {}), found {} (rank {})",
+            name,
+            format_shape(expected),
+            expected.len(),
+            format_shape(found),
+            found.len()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::preprocessing_spec::Normalization;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    // Helper function to create test EEG data
+    fn create_test_eeg_data() -> HashMap<String, Vec<f32>> {
+        let mut eeg_data = HashMap::new();
+        // Create valid data for all required channels
+        eeg_data.insert("T3".to_string(), vec![0.1; 62]);
+        eeg_data.insert("T4".to_string(), vec![0.2; 62]);
+        eeg_data.insert("O1".to_string(), vec![0.3; 62]);
+        eeg_data.insert("O2".to_string(), vec![0.4; 62]);
+        eeg_data
+    }
+
+    // Helper to create varied test data with different values
+    fn create_varied_test_eeg_data() -> HashMap<String, Vec<f32>> {
+        let mut eeg_data = HashMap::new();
+        // Creamos valores variados para obtener mejor cobertura en la normalización
+        eeg_data.insert("T3".to_string(), (0..62).map(|i| i as f32 * 0.1).collect());
+        eeg_data.insert("T4".to_string(), (0..62).map(|i| i as f32 * 0.2).collect());
+        eeg_data.insert("O1".to_string(), (0..62).map(|i| i as f32 * 0.3).collect());
+        eeg_data.insert("O2".to_string(), (0..62).map(|i| i as f32 * 0.4).collect());
+        eeg_data
+    }
+
+    // Test for successful model loading
+    #[test]
+    fn test_model_loading() {
+        // Create a mock model file
+        let dir = tempdir().unwrap();
+        let model_path = dir.path().join("test_model.onnx");
+
+        // Just to make the test work, we'll check if the model is not loaded
+        // because we're not actually creating a valid ONNX model
+        let service = ModelInferenceService::new(model_path.to_str().unwrap_or("invalid_path"));
+
+        // Since we didn't create a real model file, it should not be loaded
+        assert!(!service.is_model_loaded());
+    }
+
+    // Test explicit loading with non-existent file
+    #[test]
+    fn test_model_fingerprint_is_the_fnv1a_hash() {
+        assert_eq!(model_fingerprint(b""), "cbf29ce484222325");
+        assert_eq!(model_fingerprint(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_load_model_non_existent_file() {
+        let mut service = ModelInferenceService {
+            model: None,
+            model_path: "non_existent_path/model.onnx".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let result = service.load_model();
+        assert!(result.is_err());
+        let error_msg = result.err().unwrap();
+        assert!(error_msg.contains("Model file does not exist at path"));
+        assert_eq!(service.load_error(), Some(error_msg));
+    }
+
+    // Test the label map next to the model sets its classes, and the legacy models keep the default ones
+    #[test]
+    fn test_load_class_labels_from_label_map() {
+        let dir = tempdir().unwrap();
+        let model_path = dir.path().join("test_model.onnx");
+
+        assert_eq!(load_class_labels(&model_path).unwrap(), default_class_labels());
+
+        std::fs::write(dir.path().join("test_model.labels.json"), r#"["red", "green", "blue", "trash"]"#).unwrap();
+        assert_eq!(load_class_labels(&model_path).unwrap(), vec!["red", "green", "blue", "trash"]);
+
+        std::fs::write(dir.path().join("test_model.labels.json"), r#"["red", "red"]"#).unwrap();
+        assert!(load_class_labels(&model_path).unwrap_err().contains("distinct classes"));
+    }
+
+    // Test the spec next to the model sets its preprocessing, and an unsupported one fails the load
+    #[test]
+    fn test_load_preprocessing_spec_next_to_the_model() {
+        let dir = tempdir().unwrap();
+        let model_path = dir.path().join("test_model.onnx");
+
+        assert_eq!(load_preprocessing_spec(&model_path).unwrap(), PreprocessingSpec::default());
+
+        let spec_path = dir.path().join("test_model.preprocessing.json");
+        std::fs::write(&spec_path, r#"{"version": 1, "normalization": "min_max", "window_samples": 62, "channels": ["O1", "O2", "T3", "T4"]}"#).unwrap();
+        let spec = load_preprocessing_spec(&model_path).unwrap();
+        assert_eq!(spec.normalization, Normalization::MinMax);
+        assert_eq!(spec.channels, vec!["O1", "O2", "T3", "T4"]);
+
+        std::fs::write(&spec_path, r#"{"version": 7}"#).unwrap();
+        assert!(load_preprocessing_spec(&model_path).unwrap_err().contains("unsupported version 7"));
+    }
+
+    // Test the windows are normalized and interleaved as the spec says
+    #[test]
+    fn test_preprocess_data_follows_the_spec() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec {
+                normalization: Normalization::MinMax,
+                window_samples: 3,
+                channels: vec!["O2".to_string(), "T3".to_string()],
+                ..PreprocessingSpec::default()
+            },
+            inference: InferenceConfig::default(),
+        };
+
+        let mut eeg_data = create_test_eeg_data();
+        eeg_data.insert("O2".to_string(), vec![2.0, 4.0, 6.0]);
+        eeg_data.insert("T3".to_string(), vec![10.0, 0.0, 5.0]);
+
+        assert_eq!(service.preprocess_data(&eeg_data).unwrap(), vec![0.0, 1.0, 0.5, 0.0, 1.0, 0.5]);
+    }
+
+    // Test shape validation accepts a symbolic batch dimension
+    #[test]
+    fn test_validate_shape_symbolic_batch() {
+        let result = validate_shape("input", &expected_input_shape(&PreprocessingSpec::default()), &[None, Some(62), Some(4)]);
+        assert!(result.is_ok());
+
+        let result = validate_shape("output", &expected_output_shape(3), &[Some(1), Some(3)]);
+        assert!(result.is_ok());
+    }
+
+    // Test shape validation reports expected vs. found dimensions
+    #[test]
+    fn test_validate_shape_mismatch() {
+        let result = validate_shape("input", &expected_input_shape(&PreprocessingSpec::default()), &[Some(1), Some(4), Some(62)]);
+        assert_eq!(
+            result.err().unwrap(),
+            "Model input shape mismatch: expected [?, 62, 4] (rank 3), found [1, 4, 62] (rank 3)"
+        );
+
+        let result = validate_shape("output", &expected_output_shape(3), &[Some(3)]);
+        assert!(result.err().unwrap().contains("found [3] (rank 1)"));
+    }
+
+    // Test the default constructor
+    #[test]
+    fn test_default_constructor() {
+        let service = ModelInferenceService::default();
+        // El comportamiento dependerá de si existe el archivo por defecto o no
+        // Solo verificamos que la función no falle
+        assert_eq!(service.model_path, default_model_path());
+        assert!(service.model_path.ends_with(DEFAULT_MODEL_ASSET));
+    }
+
+    // Test for data preprocessing with varied data (better coverage)
+    #[test]
+    fn test_preprocess_data_varied() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let eeg_data = create_varied_test_eeg_data();
+        let result = service.preprocess_data(&eeg_data);
+
+        assert!(result.is_ok());
+        let processed_data = result.unwrap();
+        assert_eq!(processed_data.len(), 62 * 4);
+    }
+
+    // Test for data preprocessing - success case
+    #[test]
+    fn test_preprocess_data_success() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let eeg_data = create_test_eeg_data();
+        let result = service.preprocess_data(&eeg_data);
+
+        assert!(result.is_ok());
+        let processed_data = result.unwrap();
+        // Verify size: 62 samples * 4 channels = 248 elements
+        assert_eq!(processed_data.len(), 62 * 4);
+    }
+
+    // Test for data preprocessing - missing channel error
+    #[test]
+    fn test_preprocess_data_missing_channel() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let mut eeg_data = create_test_eeg_data();
+        // Remove a required channel
+        eeg_data.remove("T3");
+
+        let result = service.preprocess_data(&eeg_data);
+        assert!(result.is_err());
+        let error_msg = result.err().unwrap();
+        assert!(error_msg.contains("Required channel 'T3' not found"));
+    }
+
+    // Test for data preprocessing - empty channel data
+    #[test]
+    fn test_preprocess_data_empty_channel() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let mut eeg_data = create_test_eeg_data();
+        // Set an empty channel
+        eeg_data.insert("T3".to_string(), vec![]);
+
+        let result = service.preprocess_data(&eeg_data);
+        assert!(result.is_err());
+        let error_msg = result.err().unwrap();
+        assert!(error_msg.contains("Channel 'T3' has no data"));
+    }
+
+    // Test for prediction with model not loaded
+    #[test]
+    fn test_predict_model_not_loaded() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let eeg_data = create_test_eeg_data();
+        let result = service.predict_color(&eeg_data);
+
+        assert!(result.is_err());
+        let error_msg = result.err().unwrap();
+        assert_eq!(error_msg, "Model is not loaded. Call load_model first.");
+    }
+
+    // Test for short data handling in preprocessing
+    #[test]
+    fn test_preprocess_data_short() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let mut eeg_data = create_test_eeg_data();
+        // Set a channel with fewer elements
+        eeg_data.insert("T3".to_string(), vec![0.1; 30]);
+
+        let result = service.preprocess_data(&eeg_data);
+        assert!(result.is_ok());
+        let processed_data = result.unwrap();
+        // Verify the function handled short data correctly
+        assert_eq!(processed_data.len(), 62 * 4);
+    }
+
+    // Test for long data handling in preprocessing
+    #[test]
+    fn test_preprocess_data_long() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        let mut eeg_data = create_test_eeg_data();
+        // Set a channel with more elements
+        eeg_data.insert("T3".to_string(), vec![0.1; 100]);
+
+        let result = service.preprocess_data(&eeg_data);
+        assert!(result.is_ok());
+        let processed_data = result.unwrap();
+        // Verify the function handled long data correctly
+        assert_eq!(processed_data.len(), 62 * 4);
+    }
+
+    // Test for zero variance data
+    #[test]
+    fn test_preprocess_data_zero_variance() {
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        // Todos los valores son iguales, lo que resultará en varianza cero
+        let mut eeg_data = HashMap::new();
+        eeg_data.insert("T3".to_string(), vec![5.0; 62]);
+        eeg_data.insert("T4".to_string(), vec![5.0; 62]);
+        eeg_data.insert("O1".to_string(), vec![5.0; 62]);
+        eeg_data.insert("O2".to_string(), vec![5.0; 62]);
+
+        let result = service.preprocess_data(&eeg_data);
+        assert!(result.is_ok());
+        // Con varianza cero, la división por (std_dev + 1e-6) debería evitar el NaN
+        let processed_data = result.unwrap();
+        assert_eq!(processed_data.len(), 62 * 4);
+    }
+
+    // Test for predict_color with tensor shape error
+    #[test]
+    fn test_predict_color_tensor_shape_error() {
+        // Simular un modelo cargado para esta prueba
+        struct MockModel;
+
+        impl ModelInferenceInterface for MockModel {
+            fn predict_color(&self, _: &HashMap<String, Vec<f32>>) -> Result<String, String> {
+                // Esta implementación nunca se llamará en la prueba
+                Ok("red".to_string())
+            }
+
+            fn is_model_loaded(&self) -> bool {
+                true
+            }
+        }
+
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        // Crear datos con longitud incorrecta para forzar el error de verificación de longitud
+        let mut eeg_data = create_test_eeg_data();
+        // Manipulamos la estructura interna para forzar un error
+        // En realidad esto no debería suceder con la implementación actual,
+        // pero probamos la condición de error de todos modos
+
+        let result = service.predict_color(&eeg_data);
+        assert!(result.is_err());
+        // El error debe ser por modelo no cargado, no por longitud incorrecta
+        assert_eq!(
+            result.err().unwrap(),
+            "Model is not loaded. Call load_model first."
+        );
+    }
+
+    // Mock test for predict_color (since we can't easily create a real ONNX model)
+    #[test]
+    fn test_predict_color_mock() {
+        // This test is a placeholder for a proper prediction test
+        // A real test would require creating a valid ONNX model, which is complex
+        // Instead, we'll just test the interface as a sanity check
+
+        // In a real test environment, you'd create a test-specific ONNX model
+        // or use dependency injection to mock the model behavior
+
+        let service = ModelInferenceService {
+            model: None,
+            model_path: "dummy_path".to_string(),
+            load_error: None,
+            class_labels: default_class_labels(),
+            model_hash: None,
+            tensor_dump: None,
+            preprocessing: PreprocessingSpec::default(),
+            inference: InferenceConfig::default(),
+        };
+
+        assert!(!service.is_model_loaded());
+    }
+}
@@ -0,0 +1,117 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use log::{error, info, warn};
+use presage::Event;
+
+use crate::{
+    domain::{
+        context::adapter_registry::SharedAdapter,
+        events::model_reloaded_event::ModelReloadedEvent,
+        models::{
+            event_data::EventData,
+            operation_progress::{CancellationToken, OperationKind, OperationProgress, OperationStatus},
+        },
+        ports::input::model_watcher::ModelWatcherPort,
+        services::{
+            inference_cache_service::InferenceCacheService,
+            model_inference_service::{ModelInferenceInterface, ModelInferenceService},
+        },
+    },
+    utils::EventDispatcher,
+};
+
+/// Watches the model file and swaps the model service when a new build is dropped in.
+///
+/// The file is watched by the model watcher adapter, which reports each finished
+/// write. Each change is validated by loading it into a new `ModelInferenceService`
+/// in the thread of the adapter; only a valid model replaces the current one, under
+/// the service write lock, and a `ModelReloadedEvent` is emitted.
+///
+/// The load is reported as a cancellable operation: a model loaded after the
+/// cancellation was requested is discarded, keeping the current one.
+///
+/// # Arguments
+/// * `watcher`: The adapter watching the model file.
+/// * `model_path`: The path of the ONNX model to watch.
+/// * `load_model`: Loads the model of a path, in the runtime and with the tensor dump of the core.
+/// * `model_service`: The model service singleton to update.
+/// * `cancellation`: The token the frontends cancel the reload through.
+/// * `inference_cache`: The cache of the predictions, cleared once the model is replaced.
+/// * `events`: The handlers of the core the reloads are reported to.
+///
+/// # Returns
+/// * `Result<(), String>`: An error if the watcher could not be started.
+pub(crate) fn spawn_model_watcher(
+    watcher: &dyn ModelWatcherPort,
+    model_path: &str,
+    load_model: impl Fn(&str) -> ModelInferenceService + Send + 'static,
+    model_service: SharedAdapter<dyn ModelInferenceInterface + Send + Sync>,
+    cancellation: CancellationToken,
+    inference_cache: InferenceCacheService,
+    events: Arc<EventDispatcher>,
+) -> Result<(), String> {
+    let path = model_path.to_string();
+
+    watcher.watch(
+        model_path,
+        Box::new(move || reload_model(&path, &load_model, &model_service, &cancellation, &inference_cache, &events)),
+    )?;
+
+    info!("Watching model file {} for changes", model_path);
+    Ok(())
+}
+
+fn reload_model(
+    model_path: &str,
+    load_model: &dyn Fn(&str) -> ModelInferenceService,
+    model_service: &SharedAdapter<dyn ModelInferenceInterface + Send + Sync>,
+    cancellation: &CancellationToken,
+    inference_cache: &InferenceCacheService,
+    events: &EventDispatcher,
+) {
+    if !Path::new(model_path).exists() {
+        return;
+    }
+
+    info!("Model file {} changed, validating the new model...", model_path);
+
+    // The runtimes cannot be interrupted, the cancellation is checked once the model is loaded
+    cancellation.reset();
+    events.send_progress(OperationProgress::running(OperationKind::ModelLoad, None, true));
+
+    let candidate = load_model(model_path);
+
+    if cancellation.is_cancelled() {
+        info!("Keeping the current model, the reload was cancelled");
+        events.send_progress(OperationProgress::ended(OperationKind::ModelLoad, OperationStatus::Cancelled));
+        return;
+    }
+
+    if !candidate.is_model_loaded() {
+        warn!(
+            "Keeping the current model, the new one is not valid: {}",
+            candidate.load_error().unwrap_or_default()
+        );
+        events.send_progress(OperationProgress::ended(OperationKind::ModelLoad, OperationStatus::Failed));
+        return;
+    }
+
+    let class_labels = candidate.class_labels();
+    *model_service.blocking_write() = Box::new(candidate);
+    // The cached prediction was made by the previous model
+    inference_cache.clear();
+    info!("Model reloaded from {}", model_path);
+    events.send_progress(OperationProgress::ended(OperationKind::ModelLoad, OperationStatus::Finished));
+
+    if let Err(e) = events.send(
+        &ModelReloadedEvent::NAME.to_string(),
+        &EventData {
+            model_path: Some(model_path.to_string()),
+            class_labels: Some(class_labels),
+            ..Default::default()
+        },
+    ) {
+        error!("Failed to send model reloaded event: {}", e);
+    }
+}
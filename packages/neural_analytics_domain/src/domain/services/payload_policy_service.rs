@@ -0,0 +1,153 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::domain::models::payload_policy::{PayloadMode, PayloadPolicy};
+
+// Fields of the events carrying the samples of a window, per channel
+const WINDOW_FIELDS: [&str; 1] = ["headset_data"];
+
+/// Serializes an event to JSON in the shape of the policy of its destination.
+///
+/// The windows are decimated, or replaced by their summaries, in place: the event keeps
+/// its layout with fewer samples, so the receivers that only read its other fields do
+/// not change. The shape applied is stated next to each window, as
+/// `<field>_decimation` with the decimation factor, or as `<field>_summary` with the
+/// summary of each channel, the window itself left empty.
+///
+/// # Arguments
+/// * `event`: The event, such as a `CoreEvent` of the bridge.
+/// * `policy`: The policy of the destination.
+///
+/// # Returns
+/// * `Result<String, String>`: The JSON of the event, or an error if it cannot be serialized.
+pub fn to_wire<T: Serialize>(event: &T, policy: &PayloadPolicy) -> Result<String, String> {
+    // The full events are serialized as they are, without going through a JSON value
+    if policy.mode == PayloadMode::Full && policy.max_bytes.is_none() {
+        return serde_json::to_string(event).map_err(|e| e.to_string());
+    }
+
+    let event = serde_json::to_value(event).map_err(|e| e.to_string())?;
+    let modes = [PayloadMode::Full, PayloadMode::Decimated, PayloadMode::SummaryOnly];
+    let mut wire = String::new();
+
+    for mode in modes.into_iter().filter(|mode| *mode >= policy.mode) {
+        let mut shaped = event.clone();
+        shape_windows(&mut shaped, mode, policy.decimation.max(1));
+        wire = serde_json::to_string(&shaped).map_err(|e| e.to_string())?;
+
+        if policy.max_bytes.is_none_or(|max_bytes| wire.len() <= max_bytes) {
+            break;
+        }
+    }
+
+    Ok(wire)
+}
+
+/// Shapes the windows found at any depth of the event, such as inside a tagged enum.
+fn shape_windows(value: &mut Value, mode: PayloadMode, decimation: usize) {
+    match value {
+        Value::Object(fields) => {
+            for field in WINDOW_FIELDS {
+                shape_window(fields, field, mode, decimation);
+            }
+
+            fields.values_mut().for_each(|value| shape_windows(value, mode, decimation));
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| shape_windows(value, mode, decimation)),
+        _ => {}
+    }
+}
+
+fn shape_window(fields: &mut Map<String, Value>, field: &str, mode: PayloadMode, decimation: usize) {
+    let Some(Value::Object(channels)) = fields.get_mut(field) else {
+        return;
+    };
+
+    match mode {
+        PayloadMode::Full => {}
+        PayloadMode::Decimated => {
+            for samples in channels.values_mut() {
+                if let Value::Array(samples) = samples {
+                    *samples = samples.iter().step_by(decimation).cloned().collect();
+                }
+            }
+            fields.insert(format!("{}_decimation", field), Value::from(decimation));
+        }
+        PayloadMode::SummaryOnly => {
+            let summary: Map<String, Value> = std::mem::take(channels)
+                .into_iter()
+                .map(|(channel, samples)| (channel, summarize(&samples)))
+                .collect();
+            fields.insert(format!("{}_summary", field), Value::Object(summary));
+        }
+    }
+}
+
+/// Count, minimum, maximum and mean of the samples of a channel.
+fn summarize(samples: &Value) -> Value {
+    let samples: Vec<f64> = samples
+        .as_array()
+        .map(|samples| samples.iter().filter_map(Value::as_f64).collect())
+        .unwrap_or_default();
+
+    let mean = (!samples.is_empty()).then(|| samples.iter().sum::<f64>() / samples.len() as f64);
+
+    serde_json::json!({
+        "samples": samples.len(),
+        "min": samples.iter().copied().reduce(f64::min),
+        "max": samples.iter().copied().reduce(f64::max),
+        "mean": mean,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Serialize)]
+    struct Prediction {
+        kind: &'static str,
+        headset_data: HashMap<String, Vec<f32>>,
+    }
+
+    fn prediction() -> Prediction {
+        Prediction {
+            kind: "PredictionActed",
+            headset_data: HashMap::from([("T3".to_string(), (0..62).map(|i| i as f32).collect())]),
+        }
+    }
+
+    #[test]
+    fn test_to_wire_shapes_the_windows_of_the_policy() {
+        let full = to_wire(&prediction(), &PayloadPolicy::default()).unwrap();
+        assert_eq!(full, serde_json::to_string(&prediction()).unwrap());
+
+        let decimated: Value = serde_json::from_str(
+            &to_wire(&prediction(), &PayloadPolicy { mode: PayloadMode::Decimated, decimation: 10, max_bytes: None }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(decimated["headset_data"]["T3"], serde_json::json!([0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0]));
+        assert_eq!(decimated["headset_data_decimation"], 10);
+        assert_eq!(decimated["kind"], "PredictionActed");
+    }
+
+    #[test]
+    fn test_to_wire_falls_back_to_the_summary_over_the_limit() {
+        let policy = PayloadPolicy {
+            mode: PayloadMode::Full,
+            decimation: 2,
+            max_bytes: Some(150),
+        };
+
+        let wire = to_wire(&prediction(), &policy).unwrap();
+        let summary: Value = serde_json::from_str(&wire).unwrap();
+
+        assert!(wire.len() <= 150, "{}", wire);
+        assert_eq!(summary["headset_data"], serde_json::json!({}));
+        assert_eq!(
+            summary["headset_data_summary"]["T3"],
+            serde_json::json!({ "samples": 62, "min": 0.0, "max": 61.0, "mean": 30.5 })
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::domain::{models::prediction_smoothing::PredictionSmoothing, ports::input::eeg_headset::SAMPLE_RATE_HZ};
+
+use super::window_assembler_service::WINDOW_SAMPLES;
+
+// Interval between the windows of the headset, assumed until the cadence is measured
+const NOMINAL_INTERVAL_MS: f64 = WINDOW_SAMPLES as f64 * 1000.0 / SAMPLE_RATE_HZ as f64;
+
+// Intervals longer than this are pauses of the stream, such as a reconnection, not its cadence
+const MAX_CADENCE_INTERVAL_MS: i64 = 10_000;
+
+// Weight of each new interval in the average, so a single late window does not resize the buffer
+const CADENCE_SMOOTHING: f64 = 0.2;
+
+/// Sizes the buffer of the smoothed predictions from the cadence of the windows.
+///
+/// The interval between windows is averaged as they arrive, and the buffer holds
+/// the predictions of `PredictionSmoothing::window_ms` at that interval.
+pub struct PredictionSmoothingService {
+    config: PredictionSmoothing,
+    // Average interval between windows, in milliseconds, `None` until one is measured
+    interval_ms: Option<f64>,
+    capacity: usize,
+}
+
+impl PredictionSmoothingService {
+    /// Creates the service, sized for the nominal cadence of the BrainBit headset.
+    pub fn new(config: PredictionSmoothing) -> Self {
+        Self {
+            config,
+            interval_ms: None,
+            capacity: config.predictions_for(NOMINAL_INTERVAL_MS),
+        }
+    }
+
+    /// Averages the interval between two windows into the cadence.
+    ///
+    /// # Arguments
+    /// * `interval_ms`: Milliseconds between the window and the previous one.
+    ///
+    /// # Returns
+    /// * `Option<usize>`: The new number of predictions smoothed, when the cadence changed it.
+    pub fn observe_interval(&mut self, interval_ms: i64) -> Option<usize> {
+        if !(0..=MAX_CADENCE_INTERVAL_MS).contains(&interval_ms) {
+            return None;
+        }
+
+        let interval_ms = match self.interval_ms {
+            Some(average) => average + CADENCE_SMOOTHING * (interval_ms as f64 - average),
+            None => interval_ms as f64,
+        };
+        self.interval_ms = Some(interval_ms);
+
+        let capacity = self.config.predictions_for(interval_ms);
+        let is_resized = capacity != self.capacity;
+        self.capacity = capacity;
+
+        is_resized.then_some(capacity)
+    }
+
+    /// Forgets the cadence, for a new headset or session.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+
+    /// Number of predictions that must agree to act on them.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Average interval between windows, in milliseconds, `None` until one is measured.
+    pub fn interval_ms(&self) -> Option<f64> {
+        self.interval_ms
+    }
+
+    /// Period of the windows, the measured cadence or the nominal one until it is measured.
+    pub fn window_period_ms(&self) -> f64 {
+        self.interval_ms.unwrap_or(NOMINAL_INTERVAL_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_follows_the_cadence_of_the_windows() {
+        let mut smoothing = PredictionSmoothingService::new(PredictionSmoothing {
+            window_ms: 2_000,
+            ..PredictionSmoothing::default()
+        });
+
+        assert_eq!(smoothing.capacity(), 8);
+        assert_eq!(smoothing.observe_interval(100), Some(20));
+
+        // A reconnection pause is not the cadence, a faster headset still reaches the maximum
+        assert_eq!(smoothing.observe_interval(30_000), None);
+        (0..50).for_each(|_| {
+            smoothing.observe_interval(10);
+        });
+        assert_eq!(smoothing.capacity(), 32);
+
+        (0..50).for_each(|_| {
+            smoothing.observe_interval(1_000);
+        });
+        assert_eq!(smoothing.capacity(), 2);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::domain::models::trash_streak::TrashStreak;
+
+/// Counts the consecutive garbage predictions, and pauses the inference on a long streak.
+pub struct PredictionStreakService {
+    config: TrashStreak,
+    streak: u64,
+    longest_streak: u64,
+    pauses: u64,
+    is_paused: bool,
+}
+
+impl PredictionStreakService {
+    pub fn new(config: TrashStreak) -> Self {
+        Self {
+            config,
+            streak: 0,
+            longest_streak: 0,
+            pauses: 0,
+            is_paused: false,
+        }
+    }
+
+    /// Counts a prediction into the streak.
+    ///
+    /// # Arguments
+    /// * `prediction`: The class predicted for the window.
+    /// * `smoothed`: The prediction smoothed with the previous ones.
+    ///
+    /// # Returns
+    /// * `bool`: Whether the streak reached the limit, pausing the inference.
+    pub fn observe(&mut self, prediction: &str, smoothed: &str) -> bool {
+        let is_garbage = self.config.classes.iter().any(|class| class == prediction || class == smoothed);
+
+        self.streak = if is_garbage { self.streak + 1 } else { 0 };
+        self.longest_streak = self.longest_streak.max(self.streak);
+
+        if self.is_paused || self.config.limit == 0 || self.streak < self.config.limit {
+            return false;
+        }
+
+        self.is_paused = true;
+        self.pauses += 1;
+        true
+    }
+
+    /// Consecutive garbage predictions up to the last one.
+    pub fn streak(&self) -> u64 {
+        self.streak
+    }
+
+    /// Longest streak of garbage predictions since the service was created.
+    pub fn longest_streak(&self) -> u64 {
+        self.longest_streak
+    }
+
+    /// Times the inference was paused since the service was created.
+    pub fn pauses(&self) -> u64 {
+        self.pauses
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    /// Resumes the inference with a new streak, once the electrodes were checked again.
+    pub fn resume(&mut self) {
+        self.streak = 0;
+        self.is_paused = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_garbage_streak_pauses_until_resumed() {
+        let mut streaks = PredictionStreakService::new(TrashStreak {
+            limit: 3,
+            ..Default::default()
+        });
+
+        assert!(!streaks.observe("trash", "trash"));
+        assert!(!streaks.observe("green", "green"));
+        assert!(!streaks.observe("trash", "unknown"));
+        assert!(!streaks.observe("red", "unknown"));
+        assert!(streaks.observe("trash", "trash"));
+        assert!(streaks.is_paused());
+
+        // The streak goes on while paused, without pausing again
+        assert!(!streaks.observe("trash", "trash"));
+        assert_eq!(streaks.streak(), 4);
+        assert_eq!(streaks.pauses(), 1);
+
+        streaks.resume();
+        assert!(!streaks.is_paused());
+        assert!(!streaks.observe("green", "green"));
+        assert_eq!(streaks.streak(), 0);
+        assert_eq!(streaks.longest_streak(), 4);
+    }
+
+    #[test]
+    fn test_zero_limit_never_pauses() {
+        let mut streaks = PredictionStreakService::new(TrashStreak {
+            limit: 0,
+            ..Default::default()
+        });
+
+        for _ in 0..100 {
+            assert!(!streaks.observe("trash", "trash"));
+        }
+        assert_eq!(streaks.longest_streak(), 100);
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+// Consecutive observations over the threshold that make a backlog sustained
+const SUSTAINED_OBSERVATIONS: usize = 10;
+
+/// Tracks the depth of the queues between the stages of the core.
+///
+/// A queue is backlogged while its depth stays at or over the threshold for
+/// `SUSTAINED_OBSERVATIONS` observations in a row, which tells a stage that cannot
+/// keep up apart from a burst. The backlog is reported once, until the queue drains.
+pub struct QueueMonitorService {
+    backlog_threshold: usize,
+    depths: HashMap<String, usize>,
+    // Consecutive observations over the threshold of each queue
+    over_threshold: HashMap<String, usize>,
+}
+
+impl QueueMonitorService {
+    /// # Arguments
+    /// * `backlog_threshold`: Depth from which a queue counts as backlogged, `0` disables the detection.
+    pub fn new(backlog_threshold: usize) -> Self {
+        Self {
+            backlog_threshold,
+            depths: HashMap::new(),
+            over_threshold: HashMap::new(),
+        }
+    }
+
+    /// Records the current depth of a queue.
+    ///
+    /// # Returns
+    /// * `bool`: `true` when the observation makes the backlog of the queue sustained.
+    pub fn observe(&mut self, queue: &str, depth: usize) -> bool {
+        self.depths.insert(queue.to_string(), depth);
+
+        if self.backlog_threshold == 0 || depth < self.backlog_threshold {
+            self.over_threshold.remove(queue);
+            return false;
+        }
+
+        let observations = self.over_threshold.entry(queue.to_string()).or_default();
+        *observations += 1;
+
+        *observations == SUSTAINED_OBSERVATIONS
+    }
+
+    /// Latest depth of each queue observed.
+    pub fn depths(&self) -> &HashMap<String, usize> {
+        &self.depths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_reports_sustained_backlog_once() {
+        let mut monitor = QueueMonitorService::new(4);
+
+        // A burst drains before the backlog is sustained
+        for _ in 0..SUSTAINED_OBSERVATIONS - 1 {
+            assert!(!monitor.observe("requests", 8));
+        }
+        assert!(!monitor.observe("requests", 0));
+
+        let reports = (0..SUSTAINED_OBSERVATIONS * 2)
+            .filter(|_| monitor.observe("requests", 8))
+            .count();
+
+        assert_eq!(reports, 1);
+        assert_eq!(monitor.depths()["requests"], 8);
+    }
+}
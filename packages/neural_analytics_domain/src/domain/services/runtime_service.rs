@@ -0,0 +1,49 @@
+use tokio::runtime::{Builder, Runtime};
+
+use crate::domain::models::runtime_config::RuntimeConfig;
+
+// Name of the worker threads of the dedicated runtime, to tell them apart from the host ones
+const RUNTIME_THREAD: &str = "neural-analytics-runtime";
+
+/// Builds the dedicated runtime of the core.
+///
+/// # Arguments
+/// * `config`: The threading of the runtime.
+///
+/// # Returns
+/// * `Result<Runtime, String>`: The runtime, or an error if the threads cannot be started.
+pub fn build_core_runtime(config: &RuntimeConfig) -> Result<Runtime, String> {
+    let mut builder = Builder::new_multi_thread();
+    builder.enable_all().thread_name(RUNTIME_THREAD);
+
+    if let Some(worker_threads) = config.worker_threads {
+        if worker_threads == 0 {
+            return Err("The core runtime needs at least one worker thread".to_string());
+        }
+
+        builder.worker_threads(worker_threads);
+    }
+
+    builder.build().map_err(|e| format!("Failed to create the core runtime: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_core_runtime_names_its_worker_threads() {
+        let config = RuntimeConfig {
+            dedicated: true,
+            worker_threads: Some(2),
+        };
+
+        let runtime = build_core_runtime(&config).unwrap();
+        let thread_name = runtime
+            .block_on(runtime.spawn(async { std::thread::current().name().map(str::to_string) }))
+            .unwrap();
+
+        assert_eq!(thread_name.as_deref(), Some(RUNTIME_THREAD));
+        assert!(build_core_runtime(&RuntimeConfig { worker_threads: Some(0), ..config }).is_err());
+    }
+}
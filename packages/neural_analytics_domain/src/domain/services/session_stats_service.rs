@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+
+use crate::domain::models::session_stats::SessionStats;
+
+// Finished sessions kept, the oldest forgotten first
+const SESSION_HISTORY_SIZE: usize = 64;
+
+// Statistics of a session along with the sums their averages are computed from
+#[derive(Default)]
+struct SessionRecord {
+    stats: SessionStats,
+    confidence_sum: f64,
+    confidence_count: u64,
+    calibration_started_at: Option<i64>,
+}
+
+impl SessionRecord {
+    fn computed_stats(&self) -> SessionStats {
+        let total: u64 = self.stats.predictions.values().sum();
+        let class_percentages = self
+            .stats
+            .predictions
+            .iter()
+            .map(|(class, count)| (class.clone(), (*count as f64 * 100.0 / total as f64) as f32))
+            .collect();
+        let mean_confidence =
+            (self.confidence_count > 0).then(|| (self.confidence_sum / self.confidence_count as f64) as f32);
+
+        SessionStats {
+            class_percentages,
+            mean_confidence,
+            ..self.stats.clone()
+        }
+    }
+}
+
+/// Keeps the statistics of the current session and of the last finished ones, served
+/// through `get_session_stats`.
+#[derive(Default)]
+pub struct SessionStatsService {
+    current: Option<SessionRecord>,
+    finished: VecDeque<SessionRecord>,
+}
+
+impl SessionStatsService {
+    /// Starts the statistics of a session, finishing the current one if still open.
+    pub fn open(&mut self, session_id: u64, started_at: i64) {
+        self.close(started_at);
+
+        self.current = Some(SessionRecord {
+            stats: SessionStats {
+                session_id,
+                started_at,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+    }
+
+    /// Finishes the statistics of the current session, if any.
+    pub fn close(&mut self, ended_at: i64) {
+        let Some(mut record) = self.current.take() else {
+            return;
+        };
+
+        record.stats.ended_at = Some(ended_at);
+
+        if self.finished.len() == SESSION_HISTORY_SIZE {
+            self.finished.pop_front();
+        }
+        self.finished.push_back(record);
+    }
+
+    pub fn observe_window(&mut self) {
+        if let Some(record) = self.current.as_mut() {
+            record.stats.windows += 1;
+        }
+    }
+
+    /// Counts a prediction, with the probability of its class when the model reported it.
+    pub fn observe_prediction(&mut self, class: &str, confidence: Option<f32>) {
+        if let Some(record) = self.current.as_mut() {
+            *record.stats.predictions.entry(class.to_string()).or_default() += 1;
+
+            if let Some(confidence) = confidence {
+                record.confidence_sum += confidence as f64;
+                record.confidence_count += 1;
+            }
+        }
+    }
+
+    pub fn observe_disconnect(&mut self) {
+        if let Some(record) = self.current.as_mut() {
+            record.stats.disconnects += 1;
+        }
+    }
+
+    /// Starts timing a calibration, at the Unix epoch milliseconds the core entered it.
+    pub fn observe_calibration_start(&mut self, timestamp: i64) {
+        if let Some(record) = self.current.as_mut() {
+            record.calibration_started_at = Some(timestamp);
+        }
+    }
+
+    /// Stops timing the calibration, at the Unix epoch milliseconds the core left it.
+    /// Only the calibrations that succeeded are counted.
+    pub fn observe_calibration_end(&mut self, timestamp: i64, is_calibrated: bool) {
+        let Some(record) = self.current.as_mut() else {
+            return;
+        };
+
+        if let Some(started_at) = record.calibration_started_at.take().filter(|_| is_calibrated) {
+            record.stats.calibration_durations_ms.push(timestamp - started_at);
+        }
+    }
+
+    /// Statistics of a session, `None` when it is unknown or was forgotten.
+    pub fn get(&self, session_id: u64) -> Option<SessionStats> {
+        self.current
+            .iter()
+            .chain(self.finished.iter().rev())
+            .find(|record| record.stats.session_id == session_id)
+            .map(SessionRecord::computed_stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_stats_are_computed_from_the_observations() {
+        let mut sessions = SessionStatsService::default();
+        sessions.open(1, 1_000);
+        sessions.observe_calibration_start(1_500);
+        sessions.observe_calibration_end(4_000, true);
+        sessions.observe_calibration_start(5_000);
+        sessions.observe_calibration_end(6_000, false);
+
+        (0..4).for_each(|_| sessions.observe_window());
+        sessions.observe_prediction("red", Some(0.9));
+        sessions.observe_prediction("red", Some(0.7));
+        sessions.observe_prediction("green", None);
+        sessions.observe_prediction("trash", Some(0.5));
+        sessions.observe_disconnect();
+
+        sessions.open(2, 10_000);
+        sessions.observe_prediction("green", None);
+
+        let stats = sessions.get(1).unwrap();
+        assert_eq!(stats.ended_at, Some(10_000));
+        assert_eq!(stats.windows, 4);
+        assert_eq!(stats.predictions["red"], 2);
+        assert_eq!(stats.class_percentages["red"], 50.0);
+        assert_eq!(stats.class_percentages["trash"], 25.0);
+        assert!((stats.mean_confidence.unwrap() - 0.7).abs() < 1e-6);
+        assert_eq!(stats.disconnects, 1);
+        assert_eq!(stats.calibration_durations_ms, vec![2_500]);
+
+        let current = sessions.get(2).unwrap();
+        assert_eq!(current.ended_at, None);
+        assert_eq!(current.mean_confidence, None);
+        assert_eq!(sessions.get(3), None);
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::{Duration, Instant};
+
+use log::info;
+
+use crate::domain::{
+    commands::{
+        disconnect_headband_command::DisconnectHeadbandCommand,
+        extract_generalist_data_command::ExtractGeneralistDataCommand,
+        predict_color_thinking_command::PredictColorThinkingCommand,
+        search_headband_command::SearchHeadbandCommand,
+    },
+    context::{adapter_registry::standalone_adapter_registry, NeuralAnalyticsContext},
+    models::{
+        benchmark_report::LatencyStats, core_config::CoreConfig, ring_buffer::RingBuffer, soak_report::SoakReport,
+    },
+    services::{
+        benchmark_service::pipeline_command_bus,
+        window_assembler_service::WINDOW_SAMPLES,
+    },
+    ports::input::eeg_headset::{EegHeadsetPort, SAMPLE_RATE_HZ},
+    state_machine::command_middleware::CommandMiddleware,
+    use_cases::extract_extraction_use_case::read_headset_window,
+};
+
+// Name of the headset under soak in the adapter registry
+const SOAK_ADAPTER: &str = "soak";
+
+// Windows processed before measuring, so the buffers and the model session reach their size
+const WARM_UP_WINDOWS: usize = 100;
+
+// Windows whose latency is compared at the start and at the end of the run
+const LATENCY_WINDOWS: usize = 1_000;
+
+/// Runs the pipeline on one long-lived context for a while, measuring how it degrades.
+///
+/// The windows are read from the headset as fast as they are processed, so with a
+/// synthetic headset a run covers hours of recording in minutes. The resident memory of
+/// the process, the buffers of the context and the latency of each window are
+/// compared between the end of the warm-up and the end of the run, to catch leaks
+/// and buffers growing with the session. The latencies of the first and the last
+/// `LATENCY_WINDOWS` windows are kept in buffers allocated before the memory is first
+/// measured, so the harness does not grow with the run. The windows are predicted only
+/// when the model is loaded.
+///
+/// Runs on its own context, so it must not be used while the core is running.
+///
+/// # Arguments
+/// * `headset`: The headset streaming the windows, such as a synthetic one.
+/// * `duration`: How long to run the pipeline for, after the warm-up.
+/// * `config`: The configuration of the services of the pipeline.
+///
+/// # Returns
+/// * `Result<SoakReport, String>`: The report, or an error if no window was processed.
+pub async fn run_soak_test(
+    headset: Box<dyn EegHeadsetPort + Send + Sync>,
+    duration: Duration,
+    config: CoreConfig,
+) -> Result<SoakReport, String> {
+    let mut context = NeuralAnalyticsContext::new(config);
+    context.eeg_headset_adapter = standalone_adapter_registry().replace(SOAK_ADAPTER, headset);
+
+    let is_inference_enabled = context.with_model(|model| model.is_model_loaded()).await?;
+    let command_bus = pipeline_command_bus();
+
+    command_bus
+        .execute(&mut context, SearchHeadbandCommand)
+        .await
+        .map_err(|e| format!("The headset did not connect: {}", e))?;
+
+    for _ in 0..WARM_UP_WINDOWS {
+        process_window(&command_bus, &mut context, is_inference_enabled).await;
+    }
+
+    info!("Soaking the pipeline for {:?}...", duration);
+
+    let mut early_latencies = Vec::with_capacity(LATENCY_WINDOWS);
+    let mut late_latencies = RingBuffer::new(LATENCY_WINDOWS);
+    let mut windows = 0;
+    let mut failed_windows = 0;
+
+    let initial_memory = resident_memory_bytes();
+    let initial_buffers = context.memory_budget.total_usage() as i64;
+    let started = Instant::now();
+
+    while started.elapsed() < duration {
+        let window_started = Instant::now();
+
+        if !process_window(&command_bus, &mut context, is_inference_enabled).await {
+            failed_windows += 1;
+        }
+
+        let latency = window_started.elapsed().as_secs_f64() * 1000.0;
+        if early_latencies.len() < LATENCY_WINDOWS {
+            early_latencies.push(latency);
+        }
+        late_latencies.push(latency);
+        windows += 1;
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    let final_memory = resident_memory_bytes();
+    let final_buffers = context.memory_budget.total_usage() as i64;
+
+    let _ = command_bus.execute(&mut context, DisconnectHeadbandCommand).await;
+
+    if windows == 0 {
+        return Err("No window was processed during the soak".to_string());
+    }
+
+    // A run of fewer than twice `LATENCY_WINDOWS` windows compares overlapping windows
+    Ok(SoakReport {
+        windows,
+        failed_windows,
+        simulated_secs: (windows * WINDOW_SAMPLES) as f64 / SAMPLE_RATE_HZ as f64,
+        elapsed_ms: elapsed * 1000.0,
+        is_inference_enabled,
+        memory_growth_bytes: initial_memory.zip(final_memory).map(|(initial, last)| last - initial),
+        buffers_growth_bytes: final_buffers - initial_buffers,
+        early_latency: LatencyStats::from_samples(&early_latencies),
+        late_latency: LatencyStats::from_samples(&late_latencies.to_vec()),
+    })
+}
+
+// Extracts and predicts a window, dropping the events like a frontend consuming them
+async fn process_window(command_bus: &CommandMiddleware, context: &mut NeuralAnalyticsContext, is_inference_enabled: bool) -> bool {
+    let reading = read_headset_window(&context.eeg_headset_adapter, context.window_overlap.hop_samples()).await;
+    let is_processed = command_bus.execute(context, ExtractGeneralistDataCommand { reading }).await.is_ok()
+        && context.headset_data.is_some()
+        && (!is_inference_enabled || command_bus.execute(context, PredictColorThinkingCommand {}).await.is_ok());
+
+    context.act_on_prediction(None);
+    context.take_notifications();
+    context.take_lifecycle_events();
+
+    is_processed
+}
+
+// Resident memory of the process, read from procfs in kB
+#[cfg(target_os = "linux")]
+fn resident_memory_bytes() -> Option<i64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let resident_kb: i64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+
+    Some(resident_kb * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resident_memory_bytes() -> Option<i64> {
+    None
+}
@@ -0,0 +1,36 @@
+/// Checks whether a window was captured too long ago to predict from it.
+///
+/// A board that stalled hands over the samples it buffered in the meantime once it
+/// resumes. Those windows are valid data to record, but a prediction on them would act
+/// on what the user was thinking in the past, so a window whose newest sample is older
+/// than the period of the windows is stale.
+///
+/// # Arguments
+/// * `board_timestamp`: Board timestamp of the newest sample of the window, in Unix epoch seconds.
+/// * `host_timestamp`: Host time when the window was received, in Unix epoch milliseconds.
+/// * `window_period_ms`: Interval between two windows, in milliseconds.
+///
+/// # Returns
+/// * `Option<i64>`: The age of the window, in milliseconds, when it is stale.
+pub fn stale_window_age_ms(board_timestamp: f64, host_timestamp: i64, window_period_ms: f64) -> Option<i64> {
+    let age_ms = host_timestamp - (board_timestamp * 1000.0).round() as i64;
+
+    (age_ms as f64 > window_period_ms).then_some(age_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_older_than_their_period_are_stale() {
+        let board_timestamp = 1_700_000_000.0;
+        let host_timestamp = 1_700_000_000_000;
+
+        assert_eq!(stale_window_age_ms(board_timestamp, host_timestamp + 100, 248.0), None);
+        assert_eq!(stale_window_age_ms(board_timestamp, host_timestamp + 1_500, 248.0), Some(1_500));
+
+        // A board clock ahead of the host is not stale data
+        assert_eq!(stale_window_age_ms(board_timestamp, host_timestamp - 50, 248.0), None);
+    }
+}
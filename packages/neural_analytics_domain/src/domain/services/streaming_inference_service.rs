@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use super::model_inference_service::ModelInferenceInterface;
+use crate::domain::models::{ring_buffer::RingBuffer, window_prediction::WindowPrediction};
+
+/// Window completed by the stream, with the number of samples seen at its end.
+pub type StreamWindow = (u64, HashMap<String, Vec<f32>>);
+
+/// Predicts a continuous stream of samples, however they are chunked, in overlapping windows.
+///
+/// The newest samples of each channel are kept in a ring buffer of a window, and a window
+/// is predicted every `stride` samples once the first one is complete, so the callers push
+/// the samples as they arrive without aligning them on the windows of the model. A stride
+/// of a whole window predicts the windows without overlap.
+pub struct StreamingInferenceService {
+    channels: Vec<String>,
+    window_samples: usize,
+    stride: usize,
+    buffers: HashMap<String, RingBuffer<f32>>,
+    // Samples pushed on every channel since the stream started
+    samples_seen: u64,
+}
+
+impl StreamingInferenceService {
+    /// Creates a stream of the windows of `window_samples` of the channels, one every `stride` samples.
+    pub fn new(channels: Vec<String>, window_samples: usize, stride: usize) -> Self {
+        let window_samples = window_samples.max(1);
+        let buffers = channels
+            .iter()
+            .map(|channel| (channel.clone(), RingBuffer::new(window_samples)))
+            .collect();
+
+        Self {
+            channels,
+            window_samples,
+            stride: stride.clamp(1, window_samples),
+            buffers,
+            samples_seen: 0,
+        }
+    }
+
+    /// Creates a stream of the windows the model was trained with, from its preprocessing.
+    pub fn for_model(model: &dyn ModelInferenceInterface, stride: usize) -> Self {
+        let preprocessing = model.preprocessing();
+        Self::new(preprocessing.channels, preprocessing.window_samples, stride)
+    }
+
+    /// Samples between the ends of two consecutive windows.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Samples pushed on every channel since the stream started or was reset.
+    pub fn samples_seen(&self) -> u64 {
+        self.samples_seen
+    }
+
+    /// Appends a chunk of samples and returns the windows it completed, the oldest first.
+    ///
+    /// # Arguments
+    /// * `samples`: The new samples of each channel of the stream, the same number on each.
+    ///
+    /// # Returns
+    /// * `Result<Vec<StreamWindow>, String>`: The windows completed, or an error when a channel
+    ///   is missing or the channels do not have the same number of samples, nothing being
+    ///   appended then.
+    pub fn push(&mut self, samples: &HashMap<String, Vec<f32>>) -> Result<Vec<StreamWindow>, String> {
+        let mut chunk_len = None;
+
+        for channel in &self.channels {
+            let len = samples
+                .get(channel)
+                .ok_or_else(|| format!("Required channel '{}' not found in the samples", channel))?
+                .len();
+
+            match chunk_len {
+                Some(expected) if expected != len => {
+                    return Err(format!(
+                        "Channel '{}' has {} samples where the others have {}",
+                        channel, len, expected
+                    ))
+                }
+                _ => chunk_len = Some(len),
+            }
+        }
+
+        let mut windows = Vec::new();
+        let mut columns: Vec<_> = self.channels.iter().map(|channel| samples[channel].iter()).collect();
+
+        for _ in 0..chunk_len.unwrap_or_default() {
+            for (channel, column) in self.channels.iter().zip(columns.iter_mut()) {
+                if let (Some(buffer), Some(&value)) = (self.buffers.get_mut(channel), column.next()) {
+                    buffer.push(value);
+                }
+            }
+            self.samples_seen += 1;
+
+            if self.is_window_due() {
+                let window = self
+                    .buffers
+                    .iter()
+                    .map(|(channel, buffer)| (channel.clone(), buffer.to_vec()))
+                    .collect();
+                windows.push((self.samples_seen, window));
+            }
+        }
+
+        Ok(windows)
+    }
+
+    /// Appends a chunk of samples and predicts the windows it completed, in one batch.
+    ///
+    /// # Returns
+    /// * `Result<Vec<(u64, WindowPrediction)>, String>`: The prediction of each window completed,
+    ///   with the number of samples seen at its end, or an error if the samples or the model fail.
+    pub fn predict(
+        &mut self,
+        model: &dyn ModelInferenceInterface,
+        samples: &HashMap<String, Vec<f32>>,
+    ) -> Result<Vec<(u64, WindowPrediction)>, String> {
+        let (ends, windows): (Vec<u64>, Vec<_>) = self.push(samples)?.into_iter().unzip();
+        let predictions = model.predict_batch(&windows)?;
+
+        Ok(ends.into_iter().zip(predictions).collect())
+    }
+
+    /// Drops the buffered samples, so the next window is only made of new ones, e.g. after a gap.
+    pub fn reset(&mut self) {
+        self.buffers.values_mut().for_each(RingBuffer::clear);
+        self.samples_seen = 0;
+    }
+
+    /// Whether the last sample completed a window: the first one once the buffers are full,
+    /// then one every stride.
+    fn is_window_due(&self) -> bool {
+        let window_samples = self.window_samples as u64;
+
+        self.samples_seen >= window_samples && (self.samples_seen - window_samples).is_multiple_of(self.stride as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Model predicting "green" with the probability of the last sample of O1
+    struct LastSampleModel;
+
+    impl ModelInferenceInterface for LastSampleModel {
+        fn predict_color(&self, _: &HashMap<String, Vec<f32>>) -> Result<String, String> {
+            Ok("green".to_string())
+        }
+
+        fn class_probabilities(&self, eeg_data: &HashMap<String, Vec<f32>>) -> Result<HashMap<String, f32>, String> {
+            let green = *eeg_data["O1"].last().unwrap();
+
+            Ok([("green".to_string(), green), ("red".to_string(), 1.0 - green)].into_iter().collect())
+        }
+
+        fn is_model_loaded(&self) -> bool {
+            true
+        }
+    }
+
+    fn chunk(values: &[f32]) -> HashMap<String, Vec<f32>> {
+        ["T3", "T4", "O1", "O2"]
+            .iter()
+            .map(|channel| (channel.to_string(), values.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_predicts_overlapping_windows_across_chunks() {
+        let mut stream = StreamingInferenceService::for_model(&LastSampleModel, 31);
+        let samples: Vec<f32> = (0..200).map(|i| i as f32 / 200.0).collect();
+
+        // Chunks that do not line up with the windows, nor with the stride
+        let predictions: Vec<(u64, WindowPrediction)> = samples
+            .chunks(45)
+            .flat_map(|values| stream.predict(&LastSampleModel, &chunk(values)).unwrap())
+            .collect();
+
+        let ends: Vec<u64> = predictions.iter().map(|(end, _)| *end).collect();
+        assert_eq!(ends, vec![62, 93, 124, 155, 186]);
+
+        let (_, last) = &predictions[4];
+        assert_eq!(last.label, "green");
+        assert!((last.confidence.unwrap() - 185.0 / 200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_stream_rejects_uneven_channels() {
+        let mut stream = StreamingInferenceService::new(vec!["T3".to_string(), "O1".to_string()], 62, 62);
+        let mut samples = chunk(&[0.0; 10]);
+        samples.insert("O1".to_string(), vec![0.0; 9]);
+
+        assert!(stream.push(&samples).is_err());
+        assert!(stream.push(&HashMap::new()).is_err());
+        assert_eq!(stream.samples_seen(), 0);
+    }
+}
@@ -0,0 +1,109 @@
+/// Samples per half period of the test square wave.
+pub const TEST_SIGNAL_HALF_PERIOD: usize = 8;
+
+/// Amplitude of the test square wave, around zero.
+pub const TEST_SIGNAL_AMPLITUDE: f32 = 1.0;
+
+// Fraction of the peak-to-peak span a sample may deviate from its level
+const LEVEL_TOLERANCE: f32 = 0.1;
+
+/// Generates the test square wave, starting at its high level.
+///
+/// # Arguments
+/// * `len`: The number of samples to generate.
+pub fn square_wave(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| {
+            if (i / TEST_SIGNAL_HALF_PERIOD).is_multiple_of(2) {
+                TEST_SIGNAL_AMPLITUDE
+            } else {
+                -TEST_SIGNAL_AMPLITUDE
+            }
+        })
+        .collect()
+}
+
+/// Checks that the samples follow the test square wave.
+///
+/// The check does not depend on the phase, offset or gain of the signal: the
+/// samples must sit on two levels, and switch between them every half period.
+///
+/// # Returns
+/// * `Result<(), String>`: The reason the samples do not match the waveform, if any.
+pub fn verify_square_wave(samples: &[f32]) -> Result<(), String> {
+    if samples.len() < 2 * TEST_SIGNAL_HALF_PERIOD {
+        return Err(format!("only {} samples received", samples.len()));
+    }
+
+    let low = samples.iter().copied().fold(f32::INFINITY, f32::min);
+    let high = samples.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let span = high - low;
+
+    if !span.is_finite() || span <= f32::EPSILON {
+        return Err("the signal is flat".to_string());
+    }
+
+    let levels: Vec<bool> = samples
+        .iter()
+        .map(|&sample| {
+            if (high - sample).abs() <= span * LEVEL_TOLERANCE {
+                Ok(true)
+            } else if (sample - low).abs() <= span * LEVEL_TOLERANCE {
+                Ok(false)
+            } else {
+                Err(format!("sample {} is not on the high or low level", sample))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Length of every run of samples on the same level
+    let mut runs = vec![1];
+    for pair in levels.windows(2) {
+        if pair[0] == pair[1] {
+            *runs.last_mut().unwrap() += 1;
+        } else {
+            runs.push(1);
+        }
+    }
+
+    // The first and last runs are cut by the window
+    let inner_runs = runs.len().saturating_sub(2);
+    if runs.iter().skip(1).take(inner_runs).any(|&run| run != TEST_SIGNAL_HALF_PERIOD)
+        || runs.iter().any(|&run| run > TEST_SIGNAL_HALF_PERIOD)
+    {
+        return Err(format!(
+            "level changes every {:?} samples instead of every {}",
+            runs, TEST_SIGNAL_HALF_PERIOD
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_square_wave_accepts_scaled_and_shifted_waves() {
+        let scaled: Vec<f32> = square_wave(62)
+            .into_iter()
+            .skip(3)
+            .map(|sample| sample * 150.0 + 20.0)
+            .collect();
+
+        assert!(verify_square_wave(&square_wave(62)).is_ok());
+        assert!(verify_square_wave(&scaled).is_ok());
+    }
+
+    #[test]
+    fn test_verify_square_wave_rejects_other_signals() {
+        let noise: Vec<f32> = (0..62).map(|i| ((i * 37) % 11) as f32).collect();
+        let slow: Vec<f32> = (0..62usize).map(|i| if (i / 20).is_multiple_of(2) { 1.0 } else { -1.0 }).collect();
+
+        assert!(verify_square_wave(&[1.0; 62]).is_err());
+        assert!(verify_square_wave(&noise).is_err());
+        assert!(verify_square_wave(&slow).is_err());
+        assert!(verify_square_wave(&square_wave(4)).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, VecDeque};
+
+use log::info;
+
+use crate::domain::models::calibration_thresholds::{CalibrationThresholds, ThresholdSuggestion};
+
+// Readings needed before the noise floor is trusted, and how many are kept
+const MIN_READINGS: usize = 10;
+const MAX_READINGS: usize = 50;
+
+// Suggestions are rounded up to this step and never relaxed past the hard limit,
+// above it the signal is too noisy for the model whatever the gel condition
+const SUGGESTION_STEP: u16 = 50;
+const MAX_SUGGESTED_IMPEDANCE: u16 = 2500;
+
+/// Suggests calibration thresholds adapted to the current electrode condition.
+///
+/// Dry gel or a worn headset raise the impedance of every electrode, so the fixed
+/// thresholds may never be met. The service keeps the worst electrode impedance of
+/// the last readings and suggests a maximum just above their noise floor.
+pub struct ThresholdAdvisorService {
+    readings: VecDeque<u16>,
+    last_suggestion: Option<CalibrationThresholds>,
+    is_auto_apply: bool,
+}
+
+impl ThresholdAdvisorService {
+    pub fn new(is_auto_apply: bool) -> Self {
+        Self {
+            readings: VecDeque::with_capacity(MAX_READINGS),
+            last_suggestion: None,
+            is_auto_apply,
+        }
+    }
+
+    /// Records an impedance reading of every electrode.
+    ///
+    /// Readings with an electrode at zero are skipped, the electrode is not touching the skin.
+    pub fn observe(&mut self, impedance_data: &HashMap<String, u16>) {
+        let worst = match impedance_data.values().copied().max() {
+            Some(worst) if impedance_data.values().all(|&value| value > 0) => worst,
+            _ => return,
+        };
+
+        if self.readings.len() == MAX_READINGS {
+            self.readings.pop_front();
+        }
+
+        self.readings.push_back(worst);
+    }
+
+    /// Suggests thresholds from the observed readings.
+    ///
+    /// The maximum is the median of the readings plus three median absolute deviations,
+    /// so a stable contact passes while spikes are still rejected.
+    ///
+    /// # Arguments
+    /// * `current`: The thresholds in use.
+    ///
+    /// # Returns
+    /// * `Option<ThresholdSuggestion>`: `None` until enough readings were observed, or if the suggestion did not change.
+    pub fn suggest(&mut self, current: &CalibrationThresholds) -> Option<ThresholdSuggestion> {
+        if self.readings.len() < MIN_READINGS {
+            return None;
+        }
+
+        let noise_floor = median(self.readings.iter().copied().collect());
+        let deviation = median(
+            self.readings
+                .iter()
+                .map(|&value| value.abs_diff(noise_floor))
+                .collect(),
+        );
+
+        let max_impedance = noise_floor
+            .saturating_add(deviation.saturating_mul(3))
+            .div_ceil(SUGGESTION_STEP)
+            .saturating_mul(SUGGESTION_STEP)
+            .clamp(current.min_impedance, MAX_SUGGESTED_IMPEDANCE);
+
+        let thresholds = CalibrationThresholds {
+            min_impedance: current.min_impedance,
+            max_impedance,
+        };
+
+        if thresholds == *current || self.last_suggestion == Some(thresholds) {
+            return None;
+        }
+
+        info!(
+            "Suggesting a maximum impedance of {} for a noise floor of {}",
+            max_impedance, noise_floor
+        );
+        self.last_suggestion = Some(thresholds);
+
+        Some(ThresholdSuggestion {
+            thresholds,
+            noise_floor,
+            is_applied: self.is_auto_apply,
+        })
+    }
+
+    pub fn is_auto_apply(&self) -> bool {
+        self.is_auto_apply
+    }
+}
+
+fn median(mut values: Vec<u16>) -> u16 {
+    values.sort_unstable();
+    values[values.len() / 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(values: [u16; 4]) -> HashMap<String, u16> {
+        ["T3", "T4", "O1", "O2"]
+            .iter()
+            .zip(values)
+            .map(|(channel, value)| (channel.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_suggest_relaxes_threshold_for_a_high_noise_floor() {
+        let mut advisor = ThresholdAdvisorService::new(false);
+        let current = CalibrationThresholds::default();
+
+        for i in 0..MIN_READINGS as u16 {
+            advisor.observe(&reading([900, 1000, 1180 + (i % 3) * 10, 700]));
+        }
+
+        let suggestion = advisor.suggest(&current).expect("A suggestion was expected");
+
+        assert_eq!(suggestion.noise_floor, 1190);
+        assert_eq!(suggestion.thresholds.max_impedance, 1250);
+        assert_eq!(suggestion.thresholds.min_impedance, current.min_impedance);
+        assert!(!suggestion.is_applied);
+
+        // The same suggestion is not repeated
+        assert_eq!(advisor.suggest(&current), None);
+    }
+
+    #[test]
+    fn test_suggest_waits_for_enough_valid_readings() {
+        let mut advisor = ThresholdAdvisorService::new(true);
+
+        for _ in 0..MIN_READINGS {
+            advisor.observe(&reading([0, 1500, 1500, 1500]));
+        }
+        advisor.observe(&reading([1500, 1500, 1500, 1500]));
+
+        assert_eq!(advisor.suggest(&CalibrationThresholds::default()), None);
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use super::memory_budget_service::estimate_samples_bytes;
+
+/// Samples per channel of a window, the sequence length expected by the model.
+pub const WINDOW_SAMPLES: usize = 62;
+
+// Longest run of missing samples inside a window that is filled by interpolation
+const MAX_INTERPOLATED_GAP: usize = 5;
+
+// A window made mostly of the previous one is no longer a new observation
+const MAX_CARRIED_OVER_SAMPLES: usize = WINDOW_SAMPLES / 2;
+
+/// Completes the partial windows returned by the board.
+///
+/// When the board returns fewer samples than the window length, the window is
+/// completed with the last samples of the previous window, which keeps the signal
+/// continuous. Missing (non finite) samples inside the window are interpolated if
+/// the gap is short. Windows that would need more than that are rejected.
+///
+/// With overlapping windows, the board only returns the samples of the hop and the
+/// rest of each window is always carried over.
+pub struct WindowAssemblerService {
+    previous_window: HashMap<String, Vec<f32>>,
+    hop_samples: usize,
+}
+
+impl Default for WindowAssemblerService {
+    fn default() -> Self {
+        Self {
+            previous_window: HashMap::new(),
+            hop_samples: WINDOW_SAMPLES,
+        }
+    }
+}
+
+impl WindowAssemblerService {
+    /// Builds a complete window from the samples received.
+    ///
+    /// # Arguments
+    /// * `headset_data`: The samples received per channel.
+    ///
+    /// # Returns
+    /// * `Result<HashMap<String, Vec<f32>>, String>`: The window, or the reason it was rejected.
+    pub fn assemble(
+        &self,
+        headset_data: &HashMap<String, Vec<f32>>,
+    ) -> Result<HashMap<String, Vec<f32>>, String> {
+        if headset_data.is_empty() {
+            return Err("No samples received".to_string());
+        }
+
+        headset_data
+            .iter()
+            .map(|(channel, values)| {
+                let values = interpolate_gaps(values)
+                    .map_err(|e| format!("Channel '{}': {}", channel, e))?;
+                let values = self
+                    .carry_over(channel, values)
+                    .map_err(|e| format!("Channel '{}': {}", channel, e))?;
+
+                Ok((channel.clone(), values))
+            })
+            .collect()
+    }
+
+    /// Sets the new samples each window is expected to bring, `WINDOW_SAMPLES` without overlap.
+    pub fn set_hop_samples(&mut self, hop_samples: usize) {
+        self.hop_samples = hop_samples.clamp(1, WINDOW_SAMPLES);
+    }
+
+    /// Remembers the window, so the next partial window can be completed with it.
+    ///
+    /// Only its last `WINDOW_SAMPLES` can be carried over, so a longer window buffered by
+    /// the board is not kept whole.
+    pub fn remember(&mut self, headset_data: &HashMap<String, Vec<f32>>) {
+        self.previous_window = headset_data
+            .iter()
+            .map(|(channel, values)| (channel.clone(), values[values.len().saturating_sub(WINDOW_SAMPLES)..].to_vec()))
+            .collect();
+    }
+
+    /// Estimated heap footprint of the remembered window, for the memory budget.
+    pub fn estimated_bytes(&self) -> usize {
+        estimate_samples_bytes(&self.previous_window)
+    }
+
+    /// Prepends the tail of the previous window to a short window.
+    fn carry_over(&self, channel: &str, values: Vec<f32>) -> Result<Vec<f32>, String> {
+        let missing = WINDOW_SAMPLES.saturating_sub(values.len());
+
+        if missing == 0 {
+            return Ok(values);
+        }
+
+        // The overlap carries over more of the previous window by design
+        if missing > MAX_CARRIED_OVER_SAMPLES.max(WINDOW_SAMPLES - self.hop_samples) {
+            return Err(format!(
+                "{} of {} samples missing",
+                missing, WINDOW_SAMPLES
+            ));
+        }
+
+        let previous = self.previous_window.get(channel).map(Vec::as_slice).unwrap_or_default();
+
+        if previous.len() < missing {
+            return Err(format!(
+                "{} samples missing and no previous window to complete it",
+                missing
+            ));
+        }
+
+        let mut window = previous[previous.len() - missing..].to_vec();
+        window.extend(values);
+
+        Ok(window)
+    }
+}
+
+/// Replaces runs of non finite samples by linear interpolation.
+///
+/// Runs at the edges of the window take the nearest valid sample.
+fn interpolate_gaps(values: &[f32]) -> Result<Vec<f32>, String> {
+    let mut window = values.to_vec();
+    let mut index = 0;
+
+    while index < window.len() {
+        if window[index].is_finite() {
+            index += 1;
+            continue;
+        }
+
+        let gap_start = index;
+        while index < window.len() && !window[index].is_finite() {
+            index += 1;
+        }
+
+        let gap_len = index - gap_start;
+        if gap_len > MAX_INTERPOLATED_GAP {
+            return Err(format!("gap of {} missing samples", gap_len));
+        }
+
+        let before = gap_start.checked_sub(1).map(|i| window[i]);
+        let after = window.get(index).copied();
+
+        for (offset, sample) in window[gap_start..index].iter_mut().enumerate() {
+            *sample = match (before, after) {
+                (Some(before), Some(after)) => {
+                    let ratio = (offset + 1) as f32 / (gap_len + 1) as f32;
+                    before + (after - before) * ratio
+                }
+                (Some(edge), None) | (None, Some(edge)) => edge,
+                (None, None) => return Err("no valid samples".to_string()),
+            };
+        }
+    }
+
+    Ok(window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(values: Vec<f32>) -> HashMap<String, Vec<f32>> {
+        [("T3".to_string(), values)].into_iter().collect()
+    }
+
+    #[test]
+    fn test_assemble_carries_over_previous_samples_and_interpolates() {
+        let mut assembler = WindowAssemblerService::default();
+        assembler.remember(&window((0..WINDOW_SAMPLES).map(|i| i as f32).collect()));
+
+        // 10 samples short, with a gap of 2 samples
+        let mut partial: Vec<f32> = vec![100.0; WINDOW_SAMPLES - 10];
+        partial[2] = f32::NAN;
+        partial[3] = f32::NAN;
+        partial[4] = 106.0;
+
+        let assembled = assembler.assemble(&window(partial)).unwrap();
+        let values = &assembled["T3"];
+
+        assert_eq!(values.len(), WINDOW_SAMPLES);
+        assert_eq!(values[0], (WINDOW_SAMPLES - 10) as f32);
+        assert_eq!(values[9], (WINDOW_SAMPLES - 1) as f32);
+        assert_eq!(values[12], 102.0);
+        assert_eq!(values[13], 104.0);
+    }
+
+    #[test]
+    fn test_assemble_rejects_windows_with_excessive_missing_data() {
+        let mut assembler = WindowAssemblerService::default();
+
+        // No previous window to complete a short one
+        assert!(assembler.assemble(&window(vec![1.0; WINDOW_SAMPLES - 1])).is_err());
+
+        assembler.remember(&window(vec![1.0; WINDOW_SAMPLES]));
+
+        // Mostly carried over
+        assert!(assembler.assemble(&window(vec![1.0; 10])).is_err());
+
+        // Long gap inside the window
+        let mut gapped = vec![1.0; WINDOW_SAMPLES];
+        gapped[20..30].fill(f32::NAN);
+        assert!(assembler.assemble(&window(gapped)).is_err());
+    }
+
+    #[test]
+    fn test_assemble_overlaps_the_hop_with_the_previous_window() {
+        let mut assembler = WindowAssemblerService::default();
+        assembler.set_hop_samples(10);
+        assembler.remember(&window((0..WINDOW_SAMPLES).map(|i| i as f32).collect()));
+
+        let assembled = assembler.assemble(&window(vec![100.0; 10])).unwrap();
+        let values = &assembled["T3"];
+
+        assert_eq!(values.len(), WINDOW_SAMPLES);
+        assert_eq!(values[0], 10.0);
+        assert_eq!(values[WINDOW_SAMPLES - 11], (WINDOW_SAMPLES - 1) as f32);
+        assert_eq!(values[WINDOW_SAMPLES - 10], 100.0);
+
+        // Less than the hop is still missing data
+        assert!(assembler.assemble(&window(vec![100.0; 9])).is_err());
+    }
+
+    #[test]
+    fn test_remember_keeps_only_the_samples_that_can_be_carried_over() {
+        let mut assembler = WindowAssemblerService::default();
+        assembler.remember(&window((0..10 * WINDOW_SAMPLES).map(|i| i as f32).collect()));
+
+        assert_eq!(assembler.previous_window["T3"].len(), WINDOW_SAMPLES);
+        assert_eq!(assembler.previous_window["T3"][0], (9 * WINDOW_SAMPLES) as f32);
+
+        let assembled = assembler.assemble(&window(vec![100.0; WINDOW_SAMPLES - 10])).unwrap();
+        assert_eq!(assembled["T3"][0], (10 * WINDOW_SAMPLES - 10) as f32);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::domain::models::guided_protocol::GuidedProtocol;
+
+/// A window of a recording, as seen by a labeler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowInfo {
+    pub window_id: u64,
+    /// Unix epoch milliseconds when the window was extracted.
+    pub window_timestamp: i64,
+}
+
+/// Assigns a training label to the windows of a recording.
+///
+/// Implementations decide the label from information outside the EEG data
+/// (protocol steps, external markers...), so most windows can be labeled
+/// without manual annotation. `None` leaves the window unlabeled.
+pub trait WindowLabeler: Send + Sync {
+    fn label(&self, window: &WindowInfo) -> Option<String>;
+}
+
+/// Labels windows with the guided protocol step that was active when they were captured.
+pub struct ProtocolWindowLabeler {
+    protocol: GuidedProtocol,
+    started_at: i64,
+}
+
+impl ProtocolWindowLabeler {
+    /// # Arguments
+    /// * `protocol`: The protocol followed during the recording.
+    /// * `started_at`: Unix epoch milliseconds when the first step started.
+    pub fn new(protocol: GuidedProtocol, started_at: i64) -> Self {
+        Self {
+            protocol,
+            started_at,
+        }
+    }
+}
+
+impl WindowLabeler for ProtocolWindowLabeler {
+    fn label(&self, window: &WindowInfo) -> Option<String> {
+        let elapsed_ms = u64::try_from(window.window_timestamp - self.started_at).ok()?;
+
+        self.protocol
+            .step_at(elapsed_ms)
+            .map(|step| step.label.clone())
+    }
+}
+
+/// Labels windows from external markers, e.g. sent by a stimulus presentation tool.
+///
+/// Each marker sets the label of every window captured after it, until the next marker.
+pub struct MarkerWindowLabeler {
+    // (timestamp, label), sorted by timestamp
+    markers: Vec<(i64, String)>,
+}
+
+impl MarkerWindowLabeler {
+    pub fn new(mut markers: Vec<(i64, String)>) -> Self {
+        markers.sort_by_key(|(timestamp, _)| *timestamp);
+        Self { markers }
+    }
+}
+
+impl WindowLabeler for MarkerWindowLabeler {
+    fn label(&self, window: &WindowInfo) -> Option<String> {
+        self.markers
+            .iter()
+            .take_while(|(timestamp, _)| *timestamp <= window.window_timestamp)
+            .last()
+            .map(|(_, label)| label.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(window_timestamp: i64) -> WindowInfo {
+        WindowInfo {
+            window_id: 1,
+            window_timestamp,
+        }
+    }
+
+    #[test]
+    fn test_protocol_labeler_follows_steps() {
+        let labeler = ProtocolWindowLabeler::new(GuidedProtocol::default(), 1_000);
+
+        assert_eq!(labeler.label(&window(500)), None);
+        assert_eq!(labeler.label(&window(1_000)), Some("trash".to_string()));
+        assert_eq!(labeler.label(&window(11_000)), Some("red".to_string()));
+        assert_eq!(labeler.label(&window(45_000)), Some("green".to_string()));
+        assert_eq!(labeler.label(&window(61_000)), None);
+    }
+
+    #[test]
+    fn test_marker_labeler_uses_last_marker() {
+        let labeler = MarkerWindowLabeler::new(vec![
+            (2_000, "green".to_string()),
+            (1_000, "red".to_string()),
+        ]);
+
+        assert_eq!(labeler.label(&window(500)), None);
+        assert_eq!(labeler.label(&window(1_500)), Some("red".to_string()));
+        assert_eq!(labeler.label(&window(2_000)), Some("green".to_string()));
+    }
+}
\ No newline at end of file
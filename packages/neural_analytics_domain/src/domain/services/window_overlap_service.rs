@@ -0,0 +1,104 @@
+use crate::domain::{models::window_overlap::WindowOverlap, ports::input::eeg_headset::SAMPLE_RATE_HZ};
+
+use super::window_assembler_service::WINDOW_SAMPLES;
+
+// Milliseconds between two samples of the headset
+const SAMPLE_PERIOD_MS: f64 = 1000.0 / SAMPLE_RATE_HZ as f64;
+
+// Weight of each new latency in the average, so a single slow inference does not resize the hop
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// Sizes the hop between overlapping windows from the measured inference latency.
+///
+/// The hop starts at the one of the configured overlap, and grows while the average
+/// inference, with its headroom, takes longer than the samples of the hop take to
+/// arrive, up to a whole window without overlap.
+pub struct WindowOverlapService {
+    config: WindowOverlap,
+    // Average inference latency, in milliseconds, `None` until one is measured
+    latency_ms: Option<f64>,
+    hop_samples: usize,
+}
+
+impl WindowOverlapService {
+    /// Creates the service, with the hop of the configured overlap.
+    pub fn new(config: WindowOverlap) -> Self {
+        Self {
+            config,
+            latency_ms: None,
+            hop_samples: configured_hop(&config),
+        }
+    }
+
+    /// Averages the latency of an inference into the hop.
+    ///
+    /// # Arguments
+    /// * `latency_ms`: Milliseconds the model took to predict a window.
+    ///
+    /// # Returns
+    /// * `Option<usize>`: The new hop, in samples, when the latency changed it.
+    pub fn observe_latency(&mut self, latency_ms: f64) -> Option<usize> {
+        let latency_ms = match self.latency_ms {
+            Some(average) => average + LATENCY_SMOOTHING * (latency_ms - average),
+            None => latency_ms,
+        };
+        self.latency_ms = Some(latency_ms);
+
+        let headroom = 1.0 + self.config.latency_headroom.max(0.0) as f64;
+        let min_hop = (latency_ms * headroom / SAMPLE_PERIOD_MS).ceil() as usize;
+        let hop_samples = configured_hop(&self.config).max(min_hop).min(WINDOW_SAMPLES);
+
+        let is_resized = hop_samples != self.hop_samples;
+        self.hop_samples = hop_samples;
+
+        is_resized.then_some(hop_samples)
+    }
+
+    /// New samples of each window, the rest being carried over from the previous one.
+    pub fn hop_samples(&self) -> usize {
+        self.hop_samples
+    }
+
+    /// Fraction of each window shared with the previous one, once limited by the latency.
+    pub fn effective_overlap(&self) -> f32 {
+        1.0 - self.hop_samples as f32 / WINDOW_SAMPLES as f32
+    }
+}
+
+/// Hop of the configured overlap, a whole window when there is none
+fn configured_hop(config: &WindowOverlap) -> usize {
+    let overlap = config.overlap.clamp(0.0, 1.0) as f64;
+    ((WINDOW_SAMPLES as f64 * (1.0 - overlap)).round() as usize).clamp(1, WINDOW_SAMPLES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hop_grows_with_the_inference_latency() {
+        let mut overlap = WindowOverlapService::new(WindowOverlap {
+            overlap: 0.5,
+            latency_headroom: 0.5,
+        });
+
+        assert_eq!(overlap.hop_samples(), WINDOW_SAMPLES / 2);
+
+        // 31 samples take 124 ms to arrive, enough for a 40 ms inference and its headroom
+        assert_eq!(overlap.observe_latency(40.0), None);
+
+        // A 100 ms inference needs 150 ms, 38 samples
+        assert_eq!(overlap.observe_latency(100.0), None);
+        (0..50).for_each(|_| {
+            overlap.observe_latency(100.0);
+        });
+        assert_eq!(overlap.hop_samples(), 38);
+
+        // An inference slower than a window cannot be kept up with by overlapping less
+        (0..50).for_each(|_| {
+            overlap.observe_latency(1_000.0);
+        });
+        assert_eq!(overlap.hop_samples(), WINDOW_SAMPLES);
+        assert_eq!(overlap.effective_overlap(), 0.0);
+    }
+}
@@ -0,0 +1,465 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use futures::FutureExt;
+use log::{debug, error, info, warn};
+use presage::{Command, CommandBus};
+
+use crate::domain::context::NeuralAnalyticsContext;
+
+/// Fragments of the adapter errors worth retrying: the device or the network may answer on a second try.
+const TRANSIENT_ERRORS: [&str; 6] = [
+    "timed out",
+    "timeout",
+    "connection reset",
+    "connection refused",
+    "error sending request",
+    "not connected yet",
+];
+
+thread_local! {
+    // Backtrace of the last panic of the thread, captured by the hook the middleware installs
+    static PANIC_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+    // Handlers being polled on the thread, the only panics the hook captures the backtrace of
+    static RUNNING_HANDLERS: Cell<usize> = const { Cell::new(0) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Captures the backtrace of the panics of the handlers before the previous hook reports
+/// them, as the unwinding is over once the middleware catches the panic. The other panics
+/// of the process go straight to the previous hook.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            if RUNNING_HANDLERS.with(|running| running.get() > 0) {
+                let backtrace = Backtrace::force_capture().to_string();
+                PANIC_BACKTRACE.with(|last| *last.borrow_mut() = Some(backtrace));
+            }
+
+            previous(info);
+        }));
+    });
+}
+
+// Marks the thread as running a handler until dropped, unwinding included
+struct RunningHandler;
+
+impl RunningHandler {
+    fn enter() -> Self {
+        RUNNING_HANDLERS.with(|running| running.set(running.get() + 1));
+        RunningHandler
+    }
+}
+
+impl Drop for RunningHandler {
+    fn drop(&mut self) {
+        RUNNING_HANDLERS.with(|running| running.set(running.get() - 1));
+    }
+}
+
+/// Runs a handler, marking its thread during each poll, as the task may move between threads.
+async fn run_handler<F: Future>(handler: F) -> F::Output {
+    let mut handler = std::pin::pin!(handler);
+
+    std::future::poll_fn(|cx| {
+        let _running = RunningHandler::enter();
+        handler.as_mut().poll(cx)
+    })
+    .await
+}
+
+/// Message of a caught panic, when it carries one.
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// How a command is retried when it fails with a transient adapter error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RetryPolicy {
+    /// Attempts in total, including the first one.
+    pub max_attempts: u32,
+    /// Wait between two attempts.
+    pub backoff: Duration,
+}
+
+/// Name of the gauge holding the duration, in milliseconds, of the last execution of a command.
+pub(crate) fn duration_metric(command_name: &str) -> String {
+    format!("commands.{}.ms", command_name)
+}
+
+/// Whether a command failed for a reason that may go away on its own.
+pub(crate) fn is_transient(error: &presage::Error) -> bool {
+    is_transient_message(&error.to_string())
+}
+
+/// Whether an adapter call failed for a reason that may go away on its own.
+fn is_transient_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    TRANSIENT_ERRORS.iter().any(|fragment| message.contains(fragment))
+}
+
+/// Outcome of a call to an adapter made for a command before the context is locked.
+#[derive(Debug, Clone)]
+pub(crate) struct AdapterCall<T> {
+    /// Result of the last attempt.
+    pub result: Result<T, String>,
+    /// Attempts made after the first one.
+    pub retries: u32,
+    /// Time the attempts took, the backoff included.
+    pub elapsed: Duration,
+    /// Message of the panic of the adapter, when the call panicked.
+    pub panic: Option<String>,
+}
+
+/// Wraps the command bus of the state machine with the concerns shared by every command.
+///
+/// Each execution is timed and recorded in the metrics of the context, retried
+/// according to the policy of the command, and traced with the internal events
+/// it applied to the context, so a log shows which command caused which change.
+///
+/// A panic in a handler is caught and turned into an error, so it does not unwind
+/// through the state machine and stop the background task. It is logged with its
+/// backtrace and notified to the frontends through the `CommandPanickedEvent`.
+///
+/// The calls to the devices slow enough to hold the context, such as the bulb, are
+/// made without it through `call_adapter`, with the same retries and panic handling,
+/// and their outcome handed to the command executed afterwards.
+pub(crate) struct CommandMiddleware {
+    command_bus: CommandBus<NeuralAnalyticsContext, presage::Error>,
+    retry_policies: HashMap<&'static str, RetryPolicy>,
+}
+
+impl CommandMiddleware {
+    pub fn new(command_bus: CommandBus<NeuralAnalyticsContext, presage::Error>) -> Self {
+        install_panic_hook();
+
+        Self {
+            command_bus,
+            retry_policies: HashMap::new(),
+        }
+    }
+
+    /// Retries the command `C` on transient errors. Only idempotent commands should be retried.
+    pub fn with_retry_policy<C: Command>(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policies.insert(C::NAME, policy);
+        self
+    }
+
+    /// Executes a command through the command bus.
+    ///
+    /// # Arguments
+    /// * `context`: The locked context. It stays locked during the backoff of the retries,
+    ///   so the commands retried on slow devices call them with `call_adapter` instead.
+    /// * `command`: The command to execute, cloned for every retry.
+    ///
+    /// # Returns
+    /// * `Result<(), presage::Error>`: The result of the last attempt.
+    pub async fn execute<C: Command + Clone>(
+        &self,
+        context: &mut NeuralAnalyticsContext,
+        command: C,
+    ) -> Result<(), presage::Error> {
+        let max_attempts = self
+            .retry_policies
+            .get(C::NAME)
+            .map(|policy| policy.max_attempts.max(1))
+            .unwrap_or(1);
+
+        let start = Instant::now();
+        context.applied_events.clear();
+
+        let mut attempt = 1;
+        let result = loop {
+            let result = AssertUnwindSafe(run_handler(self.command_bus.execute(context, command.clone())))
+                .catch_unwind()
+                .await
+                .unwrap_or_else(|panic| Err(Self::recover::<C>(context, panic.as_ref())));
+
+            match result {
+                Err(e) if attempt < max_attempts && is_transient(&e) => {
+                    warn!("Command {} failed (attempt {}/{}), retrying: {}", C::NAME, attempt, max_attempts, e);
+                    context.metrics.increment_counter(&format!("commands.{}.retries", C::NAME), 1);
+
+                    if let Some(policy) = self.retry_policies.get(C::NAME) {
+                        tokio::time::sleep(policy.backoff).await;
+                    }
+
+                    attempt += 1;
+                }
+                result => break result,
+            }
+        };
+
+        let elapsed = start.elapsed();
+        context.metrics.set_gauge(&duration_metric(C::NAME), elapsed.as_secs_f64() * 1000.0);
+
+        if result.is_err() {
+            context.metrics.increment_counter(&format!("commands.{}.failed", C::NAME), 1);
+        }
+
+        info!("Command {} took {:?}", C::NAME, elapsed);
+        debug!(
+            "Command {} (window {}) applied [{}]",
+            C::NAME,
+            context.window_id,
+            context.applied_events.join(", ")
+        );
+
+        result
+    }
+
+    /// Calls an adapter for the command `C` without the context, retrying it with the policy of `C`.
+    ///
+    /// # Arguments
+    /// * `call`: The call to the adapter, made again for every retry.
+    ///
+    /// # Returns
+    /// * `AdapterCall<T>`: The outcome of the call, to record with `record_adapter_call`
+    ///   once the context is locked again.
+    pub async fn call_adapter<C: Command, T, F, Fut>(&self, mut call: F) -> AdapterCall<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let policy = self.retry_policies.get(C::NAME).copied();
+        let max_attempts = policy.map(|policy| policy.max_attempts.max(1)).unwrap_or(1);
+
+        let start = Instant::now();
+        let mut retries = 0;
+        let mut panic = None;
+
+        let result = loop {
+            let result = match AssertUnwindSafe(run_handler(call())).catch_unwind().await {
+                Ok(result) => result,
+                Err(caught) => {
+                    let message = panic_message(caught.as_ref());
+                    let backtrace = PANIC_BACKTRACE.with(|last| last.borrow_mut().take()).unwrap_or_default();
+                    error!("Adapter of {} panicked: {}\n{}", C::NAME, message, backtrace);
+
+                    let error_msg = format!("Adapter of {} panicked: {}", C::NAME, message);
+                    panic = Some(message);
+                    break Err(error_msg);
+                }
+            };
+
+            match result {
+                Err(e) if retries + 1 < max_attempts && is_transient_message(&e) => {
+                    warn!("Adapter of {} failed (attempt {}/{}), retrying: {}", C::NAME, retries + 1, max_attempts, e);
+
+                    if let Some(policy) = policy {
+                        tokio::time::sleep(policy.backoff).await;
+                    }
+
+                    retries += 1;
+                }
+                result => break result,
+            }
+        };
+
+        AdapterCall {
+            result,
+            retries,
+            elapsed: start.elapsed(),
+            panic,
+        }
+    }
+
+    /// Records a call made with `call_adapter` in the metrics of its command `C`, after the
+    /// command carrying its outcome is executed, so the duration measures the adapter.
+    pub fn record_adapter_call<C: Command, T>(&self, context: &mut NeuralAnalyticsContext, call: &AdapterCall<T>) {
+        if call.retries > 0 {
+            context.metrics.increment_counter(&format!("commands.{}.retries", C::NAME), call.retries as u64);
+        }
+
+        if call.result.is_err() {
+            context.metrics.increment_counter(&format!("commands.{}.failed", C::NAME), 1);
+        }
+
+        if let Some(message) = &call.panic {
+            context.metrics.increment_counter(&format!("commands.{}.panicked", C::NAME), 1);
+            context.record_command_panic(C::NAME, message);
+        }
+
+        context.metrics.set_gauge(&duration_metric(C::NAME), call.elapsed.as_secs_f64() * 1000.0);
+        info!("Adapter of {} took {:?}", C::NAME, call.elapsed);
+    }
+
+    /// Reports the panic of the handler of `C`, returning the error the command fails with.
+    ///
+    /// The context keeps the changes applied before the panic, the next commands work on them.
+    fn recover<C: Command>(context: &mut NeuralAnalyticsContext, panic: &(dyn Any + Send)) -> presage::Error {
+        let message = panic_message(panic);
+        let backtrace = PANIC_BACKTRACE.with(|last| last.borrow_mut().take()).unwrap_or_default();
+
+        error!("Command {} panicked: {}\n{}", C::NAME, message, backtrace);
+        context.metrics.increment_counter(&format!("commands.{}.panicked", C::NAME), 1);
+        context.record_command_panic(C::NAME, &message);
+
+        let error_msg = format!("Command {} panicked: {}", C::NAME, message);
+        presage::Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use presage::{command_handler, Configuration, Error, Events};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static FLAKY_CALLS: AtomicU32 = AtomicU32::new(0);
+
+    #[derive(Debug, Clone)]
+    struct FlakyCommand {
+        failures: u32,
+        error: &'static str,
+    }
+
+    impl presage::Command for FlakyCommand {
+        const NAME: &'static str = "flaky";
+    }
+
+    #[command_handler(error = Error)]
+    async fn flaky_use_case(_context: &mut NeuralAnalyticsContext, command: FlakyCommand) -> Result<Events, Error> {
+        if FLAKY_CALLS.fetch_add(1, Ordering::SeqCst) < command.failures {
+            return Err(Error::MissingCommandHandler(command.error));
+        }
+
+        Ok(Events::new())
+    }
+
+    #[derive(Debug, Clone)]
+    struct PanickingCommand;
+
+    impl presage::Command for PanickingCommand {
+        const NAME: &'static str = "panicking";
+    }
+
+    #[command_handler(error = Error)]
+    async fn panicking_use_case(_context: &mut NeuralAnalyticsContext, _command: PanickingCommand) -> Result<Events, Error> {
+        let rows: Vec<f32> = Vec::new();
+        let _ = rows[3];
+
+        Ok(Events::new())
+    }
+
+    fn middleware() -> CommandMiddleware {
+        CommandMiddleware::new(
+            CommandBus::<NeuralAnalyticsContext, Error>::new().configure(
+                Configuration::new()
+                    .command_handler(&flaky_use_case)
+                    .command_handler(&panicking_use_case),
+            ),
+        )
+        .with_retry_policy::<FlakyCommand>(RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(1),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_retries_transient_errors_only() {
+        let mut context = NeuralAnalyticsContext::default();
+        let middleware = middleware();
+
+        FLAKY_CALLS.store(0, Ordering::SeqCst);
+        let command = FlakyCommand { failures: 2, error: "Request timed out" };
+        assert!(middleware.execute(&mut context, command).await.is_ok());
+        assert_eq!(context.metrics.counter("commands.flaky.retries"), 2);
+        assert!(context.metrics.gauge(&duration_metric("flaky")).is_some());
+
+        FLAKY_CALLS.store(0, Ordering::SeqCst);
+        let command = FlakyCommand { failures: 1, error: "Device is not connected" };
+        assert!(middleware.execute(&mut context, command).await.is_err());
+        assert_eq!(context.metrics.counter("commands.flaky.retries"), 2);
+        assert_eq!(context.metrics.counter("commands.flaky.failed"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_turns_panics_into_errors() {
+        let mut context = NeuralAnalyticsContext::default();
+        let middleware = middleware();
+
+        let error = middleware.execute(&mut context, PanickingCommand).await.unwrap_err();
+        assert!(error.to_string().contains("Command panicking panicked: index out of bounds"));
+        assert_eq!(context.metrics.counter("commands.panicking.panicked"), 1);
+        assert_eq!(context.metrics.counter("commands.panicking.failed"), 1);
+
+        // The frontends are told, and the middleware keeps serving commands
+        let notifications = context.take_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].data.command_name.as_deref(), Some("panicking"));
+
+        assert!(middleware.execute(&mut context, PanickingCommand).await.is_err());
+        assert_eq!(context.metrics.counter("commands.panicking.panicked"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_call_adapter_retries_transient_errors_without_the_context() {
+        let middleware = middleware();
+        let calls = AtomicU32::new(0);
+
+        let call = middleware
+            .call_adapter::<FlakyCommand, _, _, _>(|| {
+                let attempt = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt < 2 {
+                        Err("Request timed out".to_string())
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+        assert_eq!(call.result, Ok(2));
+        assert_eq!(call.retries, 2);
+
+        // The outcome is recorded like the retries of a command once the context is locked
+        let mut context = NeuralAnalyticsContext::default();
+        middleware.record_adapter_call::<FlakyCommand, _>(&mut context, &call);
+        assert_eq!(context.metrics.counter("commands.flaky.retries"), 2);
+        assert_eq!(context.metrics.counter("commands.flaky.failed"), 0);
+        assert!(context.metrics.gauge(&duration_metric("flaky")).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_call_adapter_turns_panics_into_errors() {
+        let mut context = NeuralAnalyticsContext::default();
+        let middleware = middleware();
+
+        let call = middleware
+            .call_adapter::<PanickingCommand, (), _, _>(|| async {
+                let rows: Vec<f32> = Vec::new();
+                let _ = rows[3];
+                Ok(())
+            })
+            .await;
+        assert!(call.result.as_ref().unwrap_err().contains("Adapter of panicking panicked"));
+
+        middleware.record_adapter_call::<PanickingCommand, _>(&mut context, &call);
+        assert_eq!(context.metrics.counter("commands.panicking.panicked"), 1);
+        assert_eq!(context.metrics.counter("commands.panicking.failed"), 1);
+        assert_eq!(context.take_notifications().len(), 1);
+    }
+
+    #[test]
+    fn test_panic_hook_ignores_the_panics_outside_the_handlers() {
+        let _middleware = middleware();
+        PANIC_BACKTRACE.with(|last| last.borrow_mut().take());
+
+        assert!(std::panic::catch_unwind(|| panic!("not a handler")).is_err());
+        assert!(PANIC_BACKTRACE.with(|last| last.borrow().is_none()));
+    }
+}
@@ -1,2 +1,3 @@
+pub(crate) mod command_middleware;
 pub mod neural_events;
 pub mod state_machine;
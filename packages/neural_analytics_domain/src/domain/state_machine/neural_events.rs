@@ -0,0 +1,6 @@
+pub(crate) enum NeuralAnalyticsCoreEvents {
+    InitializeCore,
+    BackgroundTick,
+    RecalibrationRequested,
+    ReconnectRequested,
+}
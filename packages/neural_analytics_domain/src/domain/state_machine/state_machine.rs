@@ -0,0 +1,2213 @@
+use log::{debug, error, info};
+use presage::{CommandBus, Configuration, Event};
+use statig::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::{
+    domain::{
+        commands::{
+            cleanup_session_command::CleanupSessionCommand,
+            create_diagnostic_bundle_command::CreateDiagnosticBundleCommand,
+            disconnect_headband_command::DisconnectHeadbandCommand,
+            end_session_command::EndSessionCommand,
+            extract_calibration_data_command::ExtractCalibrationDataCommand,
+            extract_generalist_data_command::ExtractGeneralistDataCommand,
+            notify_webhook_command::NotifyWebhookCommand,
+            predict_color_thinking_command::PredictColorThinkingCommand,
+            publish_prediction_command::PublishPredictionCommand,
+            run_diagnostics_command::RunDiagnosticsCommand,
+            run_test_signal_command::RunTestSignalCommand,
+            search_headband_command::SearchHeadbandCommand,
+            set_log_level_command::SetLogLevelCommand,
+            start_session_command::StartSessionCommand,
+            update_light_status_command::UpdateLightStatusCommand,
+        },
+        context::{adapter_registry::AdapterRegistry, NeuralAnalyticsContext},
+        events::{
+            calibration_reused_event::CalibrationReusedEvent,
+            context_snapshot_event::ContextSnapshotEvent,
+            headset_calibrated_event::HeadsetCalibratedEvent,
+            headset_calibrating_event::HeadsetCalibratingEvent,
+            headset_connected_event::HeadsetConnectedEvent,
+            headset_disconnected_event::HeadsetDisconnectedEvent,
+            initialized_core_event::InitializedCoreEvent,
+            suggested_thresholds_event::SuggestedThresholdsEvent,
+        },
+        models::{
+            calibration_thresholds::CalibrationThresholds, context_snapshot::ContextSnapshot,
+            core_config::CoreConfig, core_health::CoreHealth, event_internals::VerifiedTestSignalEvent,
+            event_routing::{EventDestination, EventSeverity},
+            impedance_snapshot::ImpedanceSnapshot, lifecycle_event::LifecycleEventKind, operation_progress::CancellationToken,
+            operator_action::OperatorAction, prediction_action::PredictionAction, published_prediction::PublishedPrediction,
+            session_stats::SessionStats, signal_quality::SignalQuality, ui_hint::{SuggestedView, UiHint},
+        },
+        services::{
+            calibration_update_service::CalibrationUpdateService, inference_cache_service::InferenceCacheService,
+            metrics_service::MetricsSnapshot,
+        },
+        use_cases::{
+            cleanup_session_use_case::cleanup_session_use_case,
+            create_diagnostic_bundle_use_case::create_diagnostic_bundle_use_case,
+            disconnect_headband_use_case::disconnect_headband_use_case,
+            end_session_use_case::end_session_use_case,
+            extract_calibration_use_case::extract_calibration_data_use_case,
+            extract_extraction_use_case::{extract_generalist_data_use_case, read_headset_window},
+            notify_webhook_use_case::{deliver_webhook, is_webhook_due, notify_webhook_use_case},
+            predict_color_thinking_use_case::predict_color_thinking_use_case,
+            publish_prediction_use_case::{is_publication_due, publish_prediction_use_case, publish_to_sink},
+            run_diagnostics_use_case::run_diagnostics_use_case,
+            run_test_signal_use_case::run_test_signal_use_case,
+            search_headband_use_case::{search_headband_use_case, CONNECTION_TIMEOUT},
+            set_log_level_use_case::set_log_level_use_case,
+            start_session_use_case::start_session_use_case,
+            update_light_status_use_case::{drive_bulb, update_light_status_use_case},
+        },
+    },
+    utils::EventDispatcher,
+    EventData,
+};
+
+use super::{
+    command_middleware::{CommandMiddleware, RetryPolicy},
+    neural_events::NeuralAnalyticsCoreEvents,
+};
+
+/// Main state machine - Initializes and holds DI container internally.
+pub(crate) struct MainStateMachine {
+    context: Arc<Mutex<NeuralAnalyticsContext>>,
+    command_bus: CommandMiddleware,
+    // Shared outside of the context lock so the heartbeat keeps flowing if a state hangs
+    health: Arc<RwLock<CoreHealth>>,
+    // Shared with the frontends, so a suggested threshold can be accepted while calibrating
+    calibration_thresholds: Arc<RwLock<CalibrationThresholds>>,
+    // Shared with the frontends, so the operation in progress can be cancelled while it holds the context
+    cancellation: CancellationToken,
+    // Shared with the model watcher, so the predictions of a replaced model are not reused
+    inference_cache: InferenceCacheService,
+    // Lifecycle events handed over to the notifier and not delivered yet
+    lifecycle_backlog: Arc<AtomicUsize>,
+    // Transitions between two different states, counted in the metrics once the context is free
+    pending_transitions: std::sync::Mutex<Vec<(i64, &'static str, &'static str)>>,
+    // Copy of the metrics of the context, read by the exporters without waiting on a state
+    metrics: Arc<RwLock<MetricsSnapshot>>,
+    // Handlers of the core the external events are sent to, shared with its other tasks
+    events: Arc<EventDispatcher>,
+}
+
+// Wait between the checks of a paused headset search, so the loop does not spin
+const PAUSED_SEARCH_POLL: Duration = Duration::from_millis(200);
+
+// Wait between the checks of a paused capture, so the loop does not spin
+const PAUSED_CAPTURE_POLL: Duration = Duration::from_millis(200);
+
+#[state_machine(
+    initial = "State::initialize_application()",
+    on_transition = "Self::on_transition",
+    state(derive(Debug))
+)]
+impl MainStateMachine {
+    /// Creates a new instance of the MainStateMachine asynchronously,
+    /// building the necessary DI container.
+    ///
+    /// The commands that drive an output device are retried on transient errors,
+    /// as repeating them leaves the device in the same state.
+    ///
+    /// # Arguments
+    /// * `config`: The configuration of the core.
+    /// * `events`: The handlers of the core, which the state machine and its commands send their events to.
+    /// * `adapters`: The registry of the core, holding the adapters its context drives.
+    pub async fn new(config: CoreConfig, events: Arc<EventDispatcher>, adapters: &AdapterRegistry) -> Self {
+        debug!("Initializate state machine...");
+
+        let bus = CommandBus::<NeuralAnalyticsContext, presage::Error>::new().configure(
+            Configuration::new()
+                .command_handler(&cleanup_session_use_case)
+                .command_handler(&create_diagnostic_bundle_use_case)
+                .command_handler(&disconnect_headband_use_case)
+                .command_handler(&end_session_use_case)
+                .command_handler(&extract_calibration_data_use_case)
+                .command_handler(&extract_generalist_data_use_case)
+                .command_handler(&notify_webhook_use_case)
+                .command_handler(&predict_color_thinking_use_case)
+                .command_handler(&publish_prediction_use_case)
+                .command_handler(&run_diagnostics_use_case)
+                .command_handler(&run_test_signal_use_case)
+                .command_handler(&search_headband_use_case)
+                .command_handler(&set_log_level_use_case)
+                .command_handler(&start_session_use_case)
+                .command_handler(&update_light_status_use_case),
+        );
+
+        let command_bus = CommandMiddleware::new(bus)
+            .with_retry_policy::<UpdateLightStatusCommand>(RetryPolicy {
+                max_attempts: 3,
+                backoff: Duration::from_millis(100),
+            });
+
+        let mut context = NeuralAnalyticsContext::with_adapters(config, adapters);
+        context.events = events.clone();
+        let calibration_thresholds = context.calibration_thresholds.clone();
+        let cancellation = context.cancellation.clone();
+        let inference_cache = context.inference_cache.clone();
+
+        Self {
+            context: Arc::new(Mutex::new(context)),
+            command_bus,
+            health: Arc::new(RwLock::new(CoreHealth::default())),
+            calibration_thresholds,
+            cancellation,
+            inference_cache,
+            lifecycle_backlog: Arc::new(AtomicUsize::new(0)),
+            pending_transitions: std::sync::Mutex::new(Vec::new()),
+            metrics: Arc::new(RwLock::new(MetricsSnapshot::default())),
+            events,
+        }
+    }
+
+    /// Initialization state for the Neural Analytics system.
+    /// This state initializes all necessary hardware components
+    /// and prepares the system for operation.
+    ///
+    /// # State Flow
+    /// - Executes `RunDiagnosticsCommand` to collect load problems (e.g. model shape mismatches)
+    /// - Emits `InitializedCoreEvent` with the diagnostics issues, the channel labels, the signal unit and the classes of the model upon initialization
+    /// - Transitions to `awaiting_headset_connection` state
+    #[state]
+    #[allow(unused_variables)]
+    async fn initialize_application(
+        &mut self,
+        event: &NeuralAnalyticsCoreEvents,
+    ) -> Response<State> {
+        // Initialization state - Detailed logging
+        debug!("Executing state: initialize_application");
+
+        let (diagnostics_issues, channel_config, signal_unit, class_labels) = {
+            let mut ctx = self.context.lock().await;
+
+            if let Err(e) = self.command_bus.execute(&mut ctx, RunDiagnosticsCommand).await {
+                error!("Failed to run diagnostics: {:?}", e);
+            }
+
+            // The journal carries the channel labels, so offline analysis names them like the frontends
+            let channel_config = ctx.channel_config.clone();
+            ctx.journal.record("channels", &channel_config);
+
+            // The recordings and the plots carry the unit the headset reports its samples in
+            let signal_unit = ctx.with_headset(|headset| headset.signal_unit()).await.unwrap_or(ctx.signal_unit);
+            ctx.signal_unit = signal_unit;
+            ctx.recording.set_unit(signal_unit);
+
+            // The frontends present every class the model predicts, not only red and green
+            let class_labels = ctx.with_model(|model| model.class_labels()).await.unwrap_or_default();
+
+            (ctx.diagnostics_issues.clone(), channel_config, signal_unit, class_labels)
+        };
+
+        if let Err(e) = self.events.send(
+            &InitializedCoreEvent::NAME.to_string(),
+            &EventData {
+                diagnostics_issues: Some(diagnostics_issues),
+                channel_config: Some(channel_config),
+                signal_unit: Some(signal_unit),
+                class_labels: Some(class_labels),
+                ..Default::default()
+            },
+        ) {
+            error!("Failed to send initialized core event: {}", e);
+            debug!("Repeating state: initialize_application due to error");
+            return Transition(State::initialize_application());
+        }
+
+        debug!("Transitioning to state: awaiting_headset_connection");
+
+        // Direct transition to the next state
+        Transition(State::awaiting_headset_connection())
+    }
+
+    /// State that waits for a headset to connect to the system.
+    /// This state continuously polls for available headsets
+    /// and transitions to the calibration state when a connection is established.
+    ///
+    /// # State Flow
+    /// - Executes `SearchHeadbandCommand` to find connected devices
+    /// - Emits either `HeadsetConnectedEvent` or `HeadsetDisconnectedEvent`
+    /// - On connection success, transitions to `awaiting_headset_calibration`, or straight to
+    ///   `capturing_headset_data` when a recent calibration of the same user and headset is reused
+    /// - On connection failure, remains in `awaiting_headset_connection`
+    /// - On cancellation, remains in `awaiting_headset_connection` without searching until
+    ///   the search is resumed
+    #[state]
+    #[allow(unused_variables)]
+    async fn awaiting_headset_connection(
+        &mut self,
+        event: &NeuralAnalyticsCoreEvents,
+    ) -> Response<State> {
+        debug!("Executing state: awaiting_headset_connection");
+
+        if self.context.lock().await.is_search_paused {
+            tokio::time::sleep(PAUSED_SEARCH_POLL).await;
+            return Transition(State::awaiting_headset_connection());
+        }
+
+        debug!("Disconnecting headset...");
+
+        let disconnect_result = {
+            let mut ctx = self.context.lock().await;
+            self.command_bus
+                .execute(&mut ctx, DisconnectHeadbandCommand)
+                .await
+        };
+
+        info!("Searching for headset...");
+
+        let search_result = {
+            let mut ctx = self.context.lock().await;
+            ctx.metrics.increment_counter("headset.connection.attempts", 1);
+
+            let result = self.command_bus
+                .execute(&mut ctx, SearchHeadbandCommand)
+                .await;
+
+            // A cancelled search waits for the user to ask for a new one
+            ctx.is_search_paused = result.is_err() && ctx.cancellation.is_cancelled();
+            result
+        };
+
+        match search_result {
+            Ok(_) => {
+                // Headset connected
+                info!("Headset correctly connected");
+                {
+                    let mut ctx = self.context.lock().await;
+                    ctx.metrics.increment_counter("headset.connections", 1);
+                    ctx.begin_session();
+                }
+
+                // A swapped headset rotates the session, announced before the connection
+                self.publish_notifications().await;
+
+                if let Err(e) = self.events.send(
+                    &HeadsetConnectedEvent::NAME.to_string(),
+                    &EventData::default(),
+                ) {
+                    error!("Failed to send headset connected event: {}", e);
+
+                    Transition(State::awaiting_headset_connection())
+                } else if self.reuse_calibration().await {
+                    debug!("Transitioning to state: capturing_headset_data");
+                    Transition(State::capturing_headset_data())
+                } else {
+                    debug!("Transitioning to state: awaiting_headset_calibration");
+                    Transition(State::awaiting_headset_calibration())
+                }
+            }
+            Err(_) => {
+                // Headset disconnected
+                info!("Headset not connected");
+
+                if let Err(e) = self.events.send(
+                    &HeadsetDisconnectedEvent::NAME.to_string(),
+                    &EventData::default(),
+                ) {
+                    error!("Failed to send headset disconnected event: {}", e);
+                }
+
+                Transition(State::awaiting_headset_connection())
+            }
+        }
+    }
+
+    /// State that handles the headset calibration process.
+    /// This state verifies that the headset's impedance levels are
+    /// within acceptable ranges before allowing data capture.
+    ///
+    /// # State Flow
+    /// - Executes `ExtractCalibrationDataCommand` to obtain impedance data
+    /// - Analyzes impedance values to determine if calibration is acceptable
+    /// - If calibration fails due to connection issues, retries the reading up to the `read_retries` of
+    ///   the `CalibrationConfig`, then returns to `awaiting_headset_connection`
+    /// - On `ReconnectRequested`, emits `HeadsetDisconnectedEvent` and returns to `awaiting_headset_connection`
+    /// - If impedance values are out of the calibration thresholds, the shared ones or those of their
+    ///   electrode, or were within them for less than `stable_duration_ms`, emits `HeadsetCalibratingEvent`
+    ///   and remains in this state
+    /// - Emits `SuggestedThresholdsEvent` when the observed noise floor calls for other thresholds,
+    ///   applying them first if `auto_apply_suggested_thresholds` is set
+    /// - If impedance values are acceptable, stores the calibration profile and transitions to `capturing_headset_data`
+    /// - Headsets without impedance support are calibrated on the quality of their signal, emitting
+    ///   `HeadsetCalibratingEvent` until every electrode is good, or skip the calibration when
+    ///   `skip_calibration_without_impedance` is set
+    #[state(superstate = "headset_session")]
+    #[allow(unused_variables)]
+    async fn awaiting_headset_calibration(
+        &mut self,
+        event: &NeuralAnalyticsCoreEvents,
+    ) -> Response<State> {
+        // Send debug message
+        debug!("Executing state: awaiting_headset_calibration");
+
+        if matches!(event, NeuralAnalyticsCoreEvents::ReconnectRequested) {
+            info!("Reconnection requested, searching the headset again...");
+
+            if let Err(e) = self.events.send(
+                &HeadsetDisconnectedEvent::NAME.to_string(),
+                &EventData::default(),
+            ) {
+                error!("Failed to send headset disconnected event: {}", e);
+            }
+
+            return Transition(State::awaiting_headset_connection());
+        }
+
+        if self.skips_calibration().await {
+            info!("Impedance not supported by the headset, skipping the calibration");
+            return self.finish_calibration(None).await;
+        }
+
+        // Get calibration data from internal context
+        let calibration_result = {
+            let mut ctx = self.context.lock().await;
+            self.command_bus
+                .execute(&mut ctx, ExtractCalibrationDataCommand)
+                .await
+        };
+
+        // A failed reading is retried before the headset is considered lost
+        let is_retried = self.context.lock().await.calibration_policy.retries_read(calibration_result.is_ok());
+        if is_retried {
+            return Transition(State::awaiting_headset_calibration());
+        }
+
+        if calibration_result.is_err() {
+            if let Err(e) = self.events.send(
+                &HeadsetDisconnectedEvent::NAME.to_string(),
+                &EventData::default(),
+            ) {
+                error!("Failed to send headset disconnected event: {}", e);
+            }
+
+            return Transition(State::awaiting_headset_connection());
+        }
+
+        // Get impedance data from internal context
+        let (impedance_data, signal_quality) = {
+            let mut ctx = self.context.lock().await;
+            (ctx.impedance_data.clone(), ctx.signal_quality.take())
+        };
+
+        if let Some(signal_quality) = signal_quality {
+            if signal_quality.is_empty() || signal_quality.values().any(|quality| *quality != SignalQuality::Good) {
+                if !self.should_send_calibration_update(|updates| updates.should_send_signal_quality(&signal_quality, Instant::now())).await {
+                    return Transition(State::awaiting_headset_calibration());
+                }
+
+                if let Err(e) = self.events.send(
+                    &HeadsetCalibratingEvent::NAME.to_string(),
+                    &EventData {
+                        signal_quality: Some(signal_quality),
+                        ..Default::default()
+                    },
+                ) {
+                    error!("Failed to send headset calibrating event: {}", e);
+                }
+
+                return Transition(State::awaiting_headset_calibration());
+            }
+        }
+
+        if let Some(data) = &impedance_data {
+            let thresholds = self.calibration_thresholds();
+            let (is_accepted, is_stable) = {
+                let mut ctx = self.context.lock().await;
+                let is_accepted = ctx.calibration_policy.accepts(data, &thresholds);
+                (is_accepted, ctx.calibration_policy.observe(is_accepted, Instant::now()))
+            };
+
+            // Out of the thresholds, or within them for less than the stable duration
+            if !is_stable {
+                let suggestion = if is_accepted {
+                    None
+                } else {
+                    let mut ctx = self.context.lock().await;
+                    ctx.threshold_advisor.suggest(&thresholds)
+                };
+
+                if let Some(suggestion) = suggestion {
+                    if suggestion.is_applied {
+                        self.set_calibration_thresholds(suggestion.thresholds);
+                    }
+
+                    if let Err(e) = self.events.send(
+                        &SuggestedThresholdsEvent::NAME.to_string(),
+                        &EventData {
+                            suggested_thresholds: Some(suggestion),
+                            ..Default::default()
+                        },
+                    ) {
+                        error!("Failed to send suggested thresholds event: {}", e);
+                    }
+                }
+
+                let is_update_due = self
+                    .should_send_calibration_update(|updates| updates.should_send_impedance(data, &thresholds, Instant::now()))
+                    .await;
+
+                if is_update_due {
+                    if let Err(e) = self.events.send(
+                        &HeadsetCalibratingEvent::NAME.to_string(),
+                        &EventData {
+                            impedance_data: Some(data.clone()),
+                            ..Default::default()
+                        },
+                    ) {
+                        error!("Failed to send headset calibrating event: {}", e);
+                    }
+                }
+
+                return Transition(State::awaiting_headset_calibration());
+            }
+
+            let mut ctx = self.context.lock().await;
+            ctx.calibration_policy.reset();
+            ctx.remember_calibration(data);
+
+            let snapshot = ctx.snapshot_calibration(data).await;
+            drop(ctx);
+
+            return self.finish_calibration(snapshot).await;
+        }
+
+        self.finish_calibration(None).await
+    }
+
+    // Announces the calibrated headset, with the final impedance of its electrodes, and starts capturing
+    async fn finish_calibration(&self, impedance: Option<ImpedanceSnapshot>) -> Response<State> {
+        {
+            let mut ctx = self.context.lock().await;
+            ctx.record_lifecycle(LifecycleEventKind::Calibrated);
+            // A recalibration shows its first reading right away
+            ctx.calibration_updates.reset();
+        }
+
+        if let Err(e) = self.events.send(
+            &HeadsetCalibratedEvent::NAME.to_string(),
+            &EventData {
+                calibrated_impedance: impedance,
+                ..Default::default()
+            },
+        ) {
+            error!("Failed to send headset calibrated event: {}", e);
+        }
+
+        Transition(State::capturing_headset_data())
+    }
+
+    // Whether a calibration reading is worth an update to the frontends, counting the ones held back
+    async fn should_send_calibration_update(&self, decide: impl FnOnce(&mut CalibrationUpdateService) -> bool) -> bool {
+        let mut ctx = self.context.lock().await;
+        let is_update_due = decide(&mut ctx.calibration_updates);
+
+        if !is_update_due {
+            ctx.metrics.increment_counter("calibration.updates.suppressed", 1);
+        }
+
+        is_update_due
+    }
+
+    // Whether the headset cannot measure impedance and its calibration is skipped
+    async fn skips_calibration(&self) -> bool {
+        let mut ctx = self.context.lock().await;
+
+        ctx.skip_calibration_without_impedance
+            && !ctx.with_headset(|headset| headset.supports_impedance()).await.unwrap_or(true)
+    }
+
+    /// State for capturing and processing neural data from the headset.
+    /// This state continuously retrieves EEG data, runs it through the
+    /// machine learning model for color prediction, and controls output devices.
+    ///
+    /// # State Flow
+    /// - Reads the raw EEG data with `read_headset_window`, before locking the context, and
+    ///   stores it with `ExtractGeneralistDataCommand`
+    /// - If data extraction fails, returns to `awaiting_headset_connection`
+    /// - If the window is discarded for missing samples, waits for the next one
+    /// - In strict mode, if the data lost its integrity, aborts the session and pauses the
+    ///   headset search in `awaiting_headset_connection` until the operator resumes it
+    /// - If the inference was paused on a long streak of garbage predictions, records the
+    ///   window without predicting nor actuating, until the headset is recalibrated
+    /// - Runs `PredictColorThinkingCommand` to process the data
+    /// - Runs the action configured for the prediction in `CoreConfig` (bulb on/off, color, webhook)
+    ///   and publishes it, releasing the context while the devices are called and locking it
+    ///   again to record their outcome
+    /// - Acts on the prediction in the context with its actuation attempt, confirmed or not
+    /// - Continues in this state in a loop to capture more data
+    /// - On `RecalibrationRequested`, returns to `awaiting_headset_calibration` keeping the session,
+    ///   resuming the inference if it was paused
+    /// - On `ReconnectRequested`, emits `HeadsetDisconnectedEvent` and returns to `awaiting_headset_connection`
+    /// - While the user paused the capture, stays in this state without extracting nor predicting,
+    ///   the headset kept connected
+    ///
+    /// The context derives `PredictionActedEvent`, carrying the window, the prediction and
+    /// the actuation together, and the other external events from what it applied,
+    /// published by `publish_notifications` after the tick.
+    #[state(superstate = "headset_session")]
+    async fn capturing_headset_data(
+        &mut self,
+        event: &NeuralAnalyticsCoreEvents,
+    ) -> Response<State> {
+        if matches!(event, NeuralAnalyticsCoreEvents::RecalibrationRequested) {
+            info!("Recalibration requested, checking the electrodes again...");
+            self.context.lock().await.prediction_streak.resume();
+            return Transition(State::awaiting_headset_calibration());
+        }
+
+        if matches!(event, NeuralAnalyticsCoreEvents::ReconnectRequested) {
+            info!("Reconnection requested, searching the headset again...");
+
+            if let Err(e) = self.events.send(
+                &HeadsetDisconnectedEvent::NAME.to_string(),
+                &EventData::default(),
+            ) {
+                error!("Failed to send headset disconnected event: {}", e);
+            }
+
+            return Transition(State::awaiting_headset_connection());
+        }
+
+        let (is_capture_paused, headset, hop_samples) = {
+            let ctx = self.context.lock().await;
+            (ctx.is_capture_paused, ctx.eeg_headset_adapter.clone(), ctx.window_overlap.hop_samples())
+        };
+
+        if is_capture_paused {
+            tokio::time::sleep(PAUSED_CAPTURE_POLL).await;
+            return Transition(State::capturing_headset_data());
+        }
+
+        // The board is read before the context is locked, so waiting for the samples does
+        // not hold the frontend requests and the background tasks using the context
+        let reading = read_headset_window(&headset, hop_samples).await;
+        let battery_percent = reading.as_ref().ok().and_then(|reading| reading.battery_percent);
+
+        // The window is stored, predicted and its action decided under a single lock of the context
+        let mut ctx = self.context.lock().await;
+
+        let extracted = self
+            .command_bus
+            .execute(&mut ctx, ExtractGeneralistDataCommand { reading })
+            .await
+            .map(|_| ctx.headset_data.is_some() && !ctx.is_window_stale && !ctx.prediction_streak.is_paused());
+
+        let is_aborted = self.abort_on_integrity_violation(&mut ctx).await;
+
+        match extracted {
+            Ok(true) if !is_aborted => {}
+            // The window was discarded for missing too many samples, was stale, or the inference
+            // is paused, wait for the next one
+            Ok(false) if !is_aborted => return Transition(State::capturing_headset_data()),
+            // The headset was lost, or the session aborted in strict mode
+            _ => {
+                if let Err(e) = self.events.send(
+                    &HeadsetDisconnectedEvent::NAME.to_string(),
+                    &EventData::default(),
+                ) {
+                    error!("Failed to send headset disconnected event: {}", e);
+                }
+
+                return Transition(State::awaiting_headset_connection());
+            }
+        };
+
+        self.update_health(|health| {
+            health.last_window_timestamp = Some(chrono::Utc::now().timestamp_millis());
+            health.headset_battery_percent = battery_percent;
+        });
+
+        let prediction_result = self
+            .command_bus
+            .execute(&mut ctx, PredictColorThinkingCommand {})
+            .await;
+
+        if let Err(e) = prediction_result {
+            error!("Failed to predict color thinking: {:?}", e);
+
+            if e.to_string().contains("has no data") {
+                if let Err(e) = self.events.send(
+                    &HeadsetDisconnectedEvent::NAME.to_string(),
+                    &EventData::default(),
+                ) {
+                    error!("Failed to send headset disconnected event: {}", e);
+                }
+
+                return Transition(State::awaiting_headset_connection());
+            } else {
+                return Transition(State::capturing_headset_data());
+            }
+        }
+
+        let color_prediction = ctx.get_color_thinking();
+        let (prediction_action, exhibition_cue) = ctx.light_policy.decide(&color_prediction, Instant::now());
+
+        // Resolve the configured action for the prediction, the devices are driven once the context is released
+        let mut webhook_url = None;
+        let light_status = match prediction_action {
+            _ if color_prediction.is_empty() => None,
+            PredictionAction::TurnOn => Some((true, None)),
+            PredictionAction::TurnOff => Some((false, None)),
+            PredictionAction::Color { red, green, blue } => Some((true, Some((red, green, blue)))),
+            // The predictions are routine messages, a webhook routed to the alerts only is skipped
+            PredictionAction::Webhook { url }
+                if !self.events.routing().is_routed(EventDestination::Webhook, EventSeverity::Info) =>
+            {
+                debug!("Skipping the webhook of '{}', the predictions are not routed to the webhooks", url);
+                None
+            }
+            PredictionAction::Webhook { url } => {
+                if is_webhook_due(&ctx, &color_prediction) {
+                    webhook_url = Some(url);
+                } else {
+                    debug!("Prediction '{}' already notified, skipping webhook", color_prediction);
+                }
+                None
+            }
+            PredictionAction::Nothing => None,
+        };
+
+        // The prediction sink is told of every prediction, whatever its action on the bulb,
+        // unless the routine messages are not routed to the broker
+        let published_prediction = (!color_prediction.is_empty()
+            && self.events.routing().is_routed(EventDestination::Mqtt, EventSeverity::Info)
+            && is_publication_due(&ctx, &color_prediction))
+        .then(|| PublishedPrediction {
+            prediction: color_prediction.clone(),
+            window_id: ctx.predicted_window_id,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+
+        let bulb = ctx.smart_bulb_adapter.clone();
+        let webhook = ctx.prediction_webhook.clone();
+        let sink = ctx.prediction_sink.clone();
+
+        // The bulb, retried with its backoff, the webhook and the sink are called without the
+        // context, so a slow device does not hold the frontend requests and the background tasks
+        drop(ctx);
+
+        let light_call = match light_status {
+            Some((is_light_on, color)) => {
+                let call = self
+                    .command_bus
+                    .call_adapter::<UpdateLightStatusCommand, _, _, _>(|| drive_bulb(&bulb, is_light_on, color))
+                    .await;
+                let is_simulated = bulb.read().await.is_simulated();
+
+                Some((is_light_on, color, is_simulated, call))
+            }
+            None => {
+                // The windows leaving the bulb as it is still postpone its auto-off
+                bulb.read().await.refresh().await;
+                None
+            }
+        };
+
+        let webhook_call = match webhook_url {
+            Some(url) => {
+                let call = self
+                    .command_bus
+                    .call_adapter::<NotifyWebhookCommand, _, _, _>(|| deliver_webhook(&webhook, &url, &color_prediction))
+                    .await;
+
+                Some((url, call))
+            }
+            None => None,
+        };
+
+        let publish_call = match published_prediction {
+            Some(prediction) => {
+                let call = self
+                    .command_bus
+                    .call_adapter::<PublishPredictionCommand, _, _, _>(|| publish_to_sink(&sink, &prediction))
+                    .await;
+
+                Some((prediction, call))
+            }
+            None => None,
+        };
+
+        // The outcomes are stored under a second, brief lock of the context
+        let mut ctx = self.context.lock().await;
+
+        if let Some((url, call)) = webhook_call {
+            let command = NotifyWebhookCommand {
+                url,
+                prediction: color_prediction.clone(),
+                delivery: call.result.clone(),
+            };
+
+            if let Err(e) = self.command_bus.execute(&mut ctx, command).await {
+                error!("Failed to notify webhook: {:?}", e);
+            }
+            self.command_bus.record_adapter_call::<NotifyWebhookCommand, _>(&mut ctx, &call);
+        }
+
+        let actuation = match light_call {
+            Some((is_light_on, color, is_simulated, call)) => {
+                // Failed attempts are reported too, so consumers can track reliability
+                let command = UpdateLightStatusCommand {
+                    is_light_on,
+                    color,
+                    is_simulated,
+                    actuation: call.result.clone(),
+                    latency_ms: call.elapsed.as_millis() as u64,
+                };
+
+                if let Err(e) = self.command_bus.execute(&mut ctx, command).await {
+                    error!("Failed to update light status: {:?}", e);
+                }
+                self.command_bus.record_adapter_call::<UpdateLightStatusCommand, _>(&mut ctx, &call);
+
+                ctx.pending_actuation.take()
+            }
+            None => None,
+        };
+
+        if let Some((prediction, call)) = publish_call {
+            let command = PublishPredictionCommand {
+                prediction,
+                publication: call.result.clone(),
+            };
+
+            if let Err(e) = self.command_bus.execute(&mut ctx, command).await {
+                error!("Failed to publish the prediction: {:?}", e);
+            }
+            self.command_bus.record_adapter_call::<PublishPredictionCommand, _>(&mut ctx, &call);
+        }
+
+        let is_bulb_confirmed = actuation.as_ref().map(|actuation| actuation.confirmed);
+
+        // The prediction and its actuation reach the frontends in one event
+        ctx.act_on_prediction(actuation);
+
+        // The overlay follows the bulb, driven above by the same decision
+        if let Some(cue) = exhibition_cue {
+            ctx.show_exhibition_cue(cue);
+        }
+        drop(ctx);
+
+        if let Some(is_bulb_confirmed) = is_bulb_confirmed {
+            self.update_health(|health| health.is_bulb_reachable = Some(is_bulb_confirmed));
+        }
+
+        Transition(State::capturing_headset_data())
+    }
+
+    /// Superstate grouping the states that run with a connected headset.
+    ///
+    /// # State Flow
+    /// - Its exit action runs `cleanup_session` on every exit path of the session,
+    ///   while the transitions between its states (or to themselves) keep the session alive
+    #[superstate(exit_action = "cleanup_session")]
+    #[allow(unused_variables)]
+    async fn headset_session(&mut self, event: &NeuralAnalyticsCoreEvents) -> Response<State> {
+        Handled
+    }
+
+    /// Aborts the session whose data lost its integrity, reported by the context in strict mode.
+    ///
+    /// The recording stops at the last intact window and the headset is disconnected, with
+    /// its search paused, so the next session starts only when the operator resumes it.
+    ///
+    /// # Returns
+    /// * `bool`: Whether the session was aborted.
+    async fn abort_on_integrity_violation(&self, ctx: &mut NeuralAnalyticsContext) -> bool {
+        if ctx.take_integrity_abort().is_none() {
+            return false;
+        }
+
+        if ctx.is_session_explicit {
+            if let Err(e) = ctx.end_explicit_session() {
+                error!("Failed to end the session: {}", e);
+            }
+        } else {
+            ctx.recording.stop();
+        }
+
+        ctx.is_search_paused = true;
+
+        if let Err(e) = self.command_bus.execute(ctx, DisconnectHeadbandCommand).await {
+            error!("Failed to disconnect the headset: {:?}", e);
+        }
+
+        true
+    }
+
+    /// Leaves the devices idle when the headset session ends.
+    ///
+    /// Executes `CleanupSessionCommand`, whose bulb actuation the context notifies if the bulb was turned off,
+    /// and closes the summary of the session, unless the operator started it with `StartSessionCommand`.
+    #[action]
+    async fn cleanup_session(&mut self) {
+        debug!("Executing action: cleanup_session");
+
+        let mut ctx = self.context.lock().await;
+
+        if let Err(e) = self.command_bus.execute(&mut ctx, CleanupSessionCommand).await {
+            error!("Failed to clean up the session: {:?}", e);
+        }
+
+        ctx.end_session();
+    }
+}
+
+impl MainStateMachine {
+    /// Returns the shared health snapshot published by the heartbeat.
+    pub fn health(&self) -> Arc<RwLock<CoreHealth>> {
+        self.health.clone()
+    }
+
+    /// Records that the state machine finished handling a tick in the given state.
+    pub fn record_tick(&self, state: &State) {
+        let is_headset_connected = matches!(
+            state,
+            State::AwaitingHeadsetCalibration {} | State::CapturingHeadsetData {}
+        );
+
+        self.update_health(|health| {
+            health.state = format!("{:?}", state);
+            health.last_tick_timestamp = Some(chrono::Utc::now().timestamp_millis());
+            health.is_headset_connected = is_headset_connected;
+        });
+    }
+
+    /// Returns a summary of the context, for debugging purposes.
+    pub async fn context_snapshot(&self) -> ContextSnapshot {
+        self.context.lock().await.snapshot()
+    }
+
+    /// Publishes the summary of the context through the `ContextSnapshotEvent`.
+    pub async fn publish_context_snapshot(&self) {
+        let context_snapshot = self.context_snapshot().await;
+
+        if let Err(e) = self.events.send(
+            &ContextSnapshotEvent::NAME.to_string(),
+            &EventData {
+                context_snapshot: Some(context_snapshot),
+                ..Default::default()
+            },
+        ) {
+            error!("Failed to send context snapshot event: {}", e);
+        }
+    }
+
+    /// Verifies the pipeline with the test signal of the headset, reported through the
+    /// `TestSignalVerifiedEvent` once the notifications are published.
+    ///
+    /// Runs between ticks: the next calibration or capture tick switches the headset back
+    /// to its own work mode.
+    pub async fn verify_test_signal(&self) {
+        let mut ctx = self.context.lock().await;
+
+        if let Err(e) = self.command_bus.execute(&mut ctx, RunTestSignalCommand).await {
+            ctx.apply_test_signal(VerifiedTestSignalEvent {
+                headset_data: Default::default(),
+                issues: vec![e.to_string()],
+            });
+        }
+    }
+
+    /// Returns the statistics of a session, `None` when it is unknown or was forgotten.
+    pub async fn session_stats(&self, session_id: u64) -> Option<SessionStats> {
+        self.context.lock().await.session_stats.get(session_id)
+    }
+
+    /// Changes the profile of the user wearing the headset, starting a new session if it differs.
+    pub async fn set_user_profile(&self, user_profile: &str) {
+        self.context.lock().await.change_user_profile(user_profile);
+    }
+
+    /// Skips the calibration when the same user reconnects the same headset shortly
+    /// after a good calibration, emitting `CalibrationReusedEvent` and `HeadsetCalibratedEvent`.
+    ///
+    /// # Returns
+    /// - `bool`: Whether the calibration was reused.
+    async fn reuse_calibration(&self) -> bool {
+        let thresholds = self.calibration_thresholds();
+        let profile = {
+            let ctx = self.context.lock().await;
+
+            ctx.device_id.as_deref().and_then(|device_id| {
+                ctx.calibration_profiles
+                    .find_reusable(
+                        device_id,
+                        ctx.user_profile.as_deref(),
+                        &thresholds,
+                        chrono::Utc::now().timestamp_millis(),
+                    )
+                    .cloned()
+            })
+        };
+
+        let Some(profile) = profile else {
+            return false;
+        };
+
+        self.context.lock().await.record_lifecycle(LifecycleEventKind::Calibrated);
+
+        if let Err(e) = self.events.send(
+            &CalibrationReusedEvent::NAME.to_string(),
+            &EventData {
+                calibration_timestamp: Some(profile.timestamp),
+                max_impedance: Some(profile.max_impedance),
+                ..Default::default()
+            },
+        ) {
+            error!("Failed to send calibration reused event: {}", e);
+        }
+
+        if let Err(e) = self.events.send(
+            &HeadsetCalibratedEvent::NAME.to_string(),
+            &EventData::default(),
+        ) {
+            error!("Failed to send headset calibrated event: {}", e);
+        }
+
+        true
+    }
+
+    /// Publishes the external events the context derived since the last call.
+    ///
+    /// The context is released before the frontends handle them, as their handlers may
+    /// take a while, and the health mirrors the recording file, which a rotation changes.
+    /// The lifecycle events are handed to the lifecycle notifier in the background, in order.
+    pub async fn publish_notifications(&self) {
+        let (notifications, lifecycle_events, lifecycle_notifier, recording_path) = {
+            let mut ctx = self.context.lock().await;
+            let bulb_connection = ctx
+                .with_bulb(|smart_bulb| Box::pin(async move { smart_bulb.connection_state() }))
+                .await;
+            if let Ok(bulb_connection) = bulb_connection {
+                ctx.observe_bulb_connection(bulb_connection);
+            }
+
+            let notifications = ctx.take_notifications();
+
+            let transitions = self.pending_transitions.lock().map(|mut transitions| std::mem::take(&mut *transitions));
+            for (timestamp, from, to) in transitions.unwrap_or_default() {
+                ctx.record_transition(timestamp, from, to);
+            }
+
+            ctx.observe_queue_depth("notifications", notifications.len());
+
+            match self.metrics.write() {
+                Ok(mut metrics) => *metrics = ctx.metrics.snapshot(),
+                Err(e) => error!("Failed to share the metrics: {}", e),
+            }
+            ctx.observe_queue_depth("lifecycle", self.lifecycle_backlog.load(Ordering::SeqCst));
+
+            (
+                notifications,
+                ctx.take_lifecycle_events(),
+                ctx.lifecycle_notifier.clone(),
+                ctx.recording.path().map(str::to_string),
+            )
+        };
+
+        self.update_health(|health| health.recording_path = recording_path);
+
+        let routing = self.events.routing();
+        let lifecycle_events: Vec<_> = lifecycle_events
+            .into_iter()
+            .filter(|event| routing.is_routed(EventDestination::Webhook, event.kind.severity()))
+            .collect();
+
+        if !lifecycle_events.is_empty() {
+            let lifecycle_backlog = self.lifecycle_backlog.clone();
+            lifecycle_backlog.fetch_add(lifecycle_events.len(), Ordering::SeqCst);
+
+            tokio::spawn(async move {
+                let notifier = lifecycle_notifier.read().await;
+
+                for event in lifecycle_events {
+                    if let Err(e) = notifier.notify(&event).await {
+                        error!("Failed to notify the {:?} lifecycle event: {}", event.kind, e);
+                    }
+
+                    lifecycle_backlog.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        }
+
+        for notification in notifications {
+            if let Err(e) = self.events.send(&notification.name.to_string(), &notification.data) {
+                error!("Failed to send {} event: {}", notification.name, e);
+            }
+        }
+    }
+
+    /// Starts recording the extracted EEG windows to the given file.
+    pub async fn start_recording(&self, path: &str) {
+        if let Err(e) = self.context.lock().await.recording.start(path) {
+            error!("Failed to start recording: {}", e);
+        }
+    }
+
+    /// Stops the recording in progress, if any.
+    pub async fn stop_recording(&self) {
+        self.context.lock().await.recording.stop();
+    }
+
+    /// Opens a session on behalf of the operator, spanning the reconnects of the headset.
+    pub async fn start_session(&self, recording_path: Option<String>) {
+        let mut ctx = self.context.lock().await;
+
+        if let Err(e) = self.command_bus.execute(&mut ctx, StartSessionCommand { recording_path }).await {
+            error!("Failed to start the session: {:?}", e);
+        }
+    }
+
+    /// Closes the session opened by `start_session`.
+    pub async fn end_session(&self) {
+        let mut ctx = self.context.lock().await;
+
+        if let Err(e) = self.command_bus.execute(&mut ctx, EndSessionCommand).await {
+            error!("Failed to end the session: {:?}", e);
+        }
+    }
+
+    /// Closes the session and the files of the core, before the devices are released.
+    pub async fn close_for_shutdown(&self) {
+        self.context.lock().await.close_for_shutdown();
+    }
+
+    /// Records the depth of the queue of the frontend requests waiting for the loop.
+    pub async fn observe_request_queue(&self, depth: usize) {
+        self.context.lock().await.observe_queue_depth("requests", depth);
+    }
+
+    /// Records an action of the operator in the session journal.
+    pub async fn record_operator_action(&self, action: OperatorAction, timestamp: i64) {
+        self.context.lock().await.record_operator_action(action, timestamp);
+    }
+
+    /// Changes the level of the logs, to debug a session without restarting the core.
+    pub async fn set_log_level(&self, level: log::LevelFilter) {
+        let mut ctx = self.context.lock().await;
+
+        if let Err(e) = self.command_bus.execute(&mut ctx, SetLogLevelCommand { level }).await {
+            error!("Failed to set the log level: {:?}", e);
+        }
+    }
+
+    /// Searches the headset again after the user cancelled the search.
+    pub async fn resume_headset_search(&self) {
+        self.context.lock().await.is_search_paused = false;
+    }
+
+    /// Pauses the capture, keeping the headset connected, and journals the pause.
+    pub async fn pause_capture(&self) {
+        let mut ctx = self.context.lock().await;
+
+        if !ctx.is_capture_paused {
+            info!("Capture paused");
+            ctx.is_capture_paused = true;
+            ctx.record_operator_action(OperatorAction::Pause, chrono::Utc::now().timestamp_millis());
+        }
+    }
+
+    /// Resumes the capture paused by the user, and journals it.
+    pub async fn resume_capture(&self) {
+        let mut ctx = self.context.lock().await;
+
+        if ctx.is_capture_paused {
+            info!("Capture resumed");
+            ctx.is_capture_paused = false;
+            ctx.record_operator_action(OperatorAction::Resume, chrono::Utc::now().timestamp_millis());
+        }
+    }
+
+    /// Writes a diagnostic bundle for an issue report, reported through the
+    /// `DiagnosticBundleCreatedEvent` once the notifications are published.
+    ///
+    /// # Arguments
+    /// - `path`: The zip archive to write, `neural_analytics_diagnostics_<timestamp>.zip` in the
+    ///   working directory by default.
+    pub async fn create_diagnostic_bundle(&self, path: Option<String>) {
+        let path = path.unwrap_or_else(|| {
+            format!(
+                "neural_analytics_diagnostics_{}.zip",
+                chrono::Utc::now().format("%Y%m%d_%H%M%S")
+            )
+        });
+        let mut ctx = self.context.lock().await;
+
+        if let Err(e) = self
+            .command_bus
+            .execute(&mut ctx, CreateDiagnosticBundleCommand { path: path.clone() })
+            .await
+        {
+            // The use case reports its failures as the message of the error
+            let error_message = match e {
+                presage::Error::MissingCommandHandler(message) => message.to_string(),
+                e => e.to_string(),
+            };
+
+            ctx.apply_diagnostic_bundle(path, Some(error_message));
+        }
+    }
+
+    /// Returns the context, so the core can close its files if the state machine hangs on shutdown.
+    pub fn shared_context(&self) -> Arc<Mutex<NeuralAnalyticsContext>> {
+        self.context.clone()
+    }
+
+    /// Returns the inference cache of the context, so the model watcher can clear it on a reload.
+    pub fn shared_inference_cache(&self) -> InferenceCacheService {
+        self.inference_cache.clone()
+    }
+
+    /// Returns the cancellation token of the long-running operations, so they can be cancelled
+    /// from outside the state machine.
+    pub fn shared_cancellation(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Returns the copy of the metrics refreshed as the notifications are published, so they
+    /// can be exported while a state holds the context.
+    pub fn shared_metrics(&self) -> Arc<RwLock<MetricsSnapshot>> {
+        self.metrics.clone()
+    }
+
+    /// Counts the changes of state, the re-entries of a polling state are not transitions.
+    fn on_transition(&mut self, source: &State, target: &State) {
+        let (from, to) = (source.metric_name(), target.metric_name());
+
+        if from != to {
+            if let Ok(mut transitions) = self.pending_transitions.lock() {
+                transitions.push((chrono::Utc::now().timestamp_millis(), from, to));
+            }
+        }
+    }
+
+    /// Returns the shared calibration thresholds, so they can be replaced from outside the state machine.
+    pub fn shared_calibration_thresholds(&self) -> Arc<RwLock<CalibrationThresholds>> {
+        self.calibration_thresholds.clone()
+    }
+
+    fn calibration_thresholds(&self) -> CalibrationThresholds {
+        self.calibration_thresholds
+            .read()
+            .map(|thresholds| *thresholds)
+            .unwrap_or_default()
+    }
+
+    fn set_calibration_thresholds(&self, thresholds: CalibrationThresholds) {
+        match self.calibration_thresholds.write() {
+            Ok(mut current) => *current = thresholds,
+            Err(e) => error!("Failed to update calibration thresholds: {}", e),
+        }
+    }
+
+    fn update_health(&self, update: impl FnOnce(&mut CoreHealth)) {
+        match self.health.write() {
+            Ok(mut health) => update(&mut health),
+            Err(e) => error!("Failed to update core health: {}", e),
+        }
+    }
+}
+
+impl State {
+    /// Name of the state in the labels of the metrics.
+    pub(crate) fn metric_name(&self) -> &'static str {
+        match self {
+            State::InitializeApplication {} => "initialize_application",
+            State::AwaitingHeadsetConnection {} => "awaiting_headset_connection",
+            State::AwaitingHeadsetCalibration {} => "awaiting_headset_calibration",
+            State::CapturingHeadsetData {} => "capturing_headset_data",
+        }
+    }
+
+    /// Presentation hints of the state, sent with the events that enter it.
+    pub(crate) fn ui_hint(&self) -> UiHint {
+        let (suggested_view, shows_spinner, expected_duration) = match self {
+            State::InitializeApplication {} => (SuggestedView::Loading, true, None),
+            State::AwaitingHeadsetConnection {} => (SuggestedView::Welcome, true, Some(CONNECTION_TIMEOUT)),
+            State::AwaitingHeadsetCalibration {} => (SuggestedView::Calibration, false, None),
+            State::CapturingHeadsetData {} => (SuggestedView::Capture, false, None),
+        };
+
+        UiHint {
+            suggested_view,
+            shows_spinner,
+            expected_duration_ms: expected_duration.map(|duration| duration.as_millis() as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        events::{exhibition_cue_event::ExhibitionCueEvent, inference_paused_event::InferencePausedEvent},
+        models::{
+            bulb_state::BulbState, calibration_config::CalibrationConfig, eeg_work_modes::WorkMode, signal_unit::SignalUnit,
+            trash_streak::TrashStreak,
+        },
+        ports::{input::eeg_headset::EegHeadsetPort, output::smart_bulb::SmartBulbPort},
+        services::{
+            calibration_policy_service::CalibrationPolicyService,
+            light_policy_service::LightPolicyService,
+            model_inference_service::ModelInferenceInterface, prediction_streak_service::PredictionStreakService,
+            window_assembler_service::WINDOW_SAMPLES,
+        },
+    };
+    use mockall::{mock, predicate::*};
+    use presage::Command;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+    use tokio::test;
+
+    // Mocks para los tests
+    mock! {
+        EegHeadsetAdapter {}
+        impl EegHeadsetPort for EegHeadsetAdapter {
+            fn connect(&self) -> Result<(), String>;
+            fn disconnect(&mut self) -> Result<(), String>;
+            fn is_connected(&self) -> bool;
+            fn get_work_mode(&self) -> WorkMode;
+            fn change_work_mode(&mut self, mode: WorkMode);
+            fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String>;
+            fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String>;
+        }
+    }
+
+    mock! {
+        ImpedancelessHeadsetAdapter {}
+        impl EegHeadsetPort for ImpedancelessHeadsetAdapter {
+            fn connect(&self) -> Result<(), String>;
+            fn disconnect(&mut self) -> Result<(), String>;
+            fn is_connected(&self) -> bool;
+            fn get_work_mode(&self) -> WorkMode;
+            fn change_work_mode(&mut self, mode: WorkMode);
+            fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String>;
+            fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String>;
+            fn supports_impedance(&self) -> bool;
+            fn signal_unit(&self) -> SignalUnit;
+        }
+    }
+
+    mock! {
+        SmartBulbAdapter {}
+        #[async_trait::async_trait]
+        impl SmartBulbPort for SmartBulbAdapter {
+            async fn change_state(&self, state: BulbState) -> Result<(), String>;
+        }
+    }
+
+    mock! {
+        ModelService {}
+        impl ModelInferenceInterface for ModelService {
+            fn predict_color(&self, data: &HashMap<String, Vec<f32>>) -> Result<String, String>;
+            fn is_model_loaded(&self) -> bool;
+        }
+    }
+
+    /// Bulb failing a seeded share of the actuations with a timeout, recording whether each failed
+    #[derive(Clone)]
+    struct FlakyBulb {
+        failure_rate: f64,
+        rng: Arc<std::sync::Mutex<rand::rngs::StdRng>>,
+        attempts: Arc<std::sync::Mutex<Vec<bool>>>,
+        state: Arc<std::sync::Mutex<Option<BulbState>>>,
+    }
+
+    impl FlakyBulb {
+        fn new(failure_rate: f64, seed: u64) -> Self {
+            Self {
+                failure_rate,
+                rng: Arc::new(std::sync::Mutex::new(rand::SeedableRng::seed_from_u64(seed))),
+                attempts: Arc::default(),
+                state: Arc::default(),
+            }
+        }
+
+        /// Whether each actuation attempted so far failed, oldest first
+        fn attempts(&self) -> Vec<bool> {
+            self.attempts.lock().unwrap().clone()
+        }
+
+        fn state(&self) -> Option<BulbState> {
+            *self.state.lock().unwrap()
+        }
+
+        fn actuate(&self, state: BulbState) -> Result<(), String> {
+            let is_failed = rand::Rng::gen_bool(&mut *self.rng.lock().unwrap(), self.failure_rate);
+            self.attempts.lock().unwrap().push(is_failed);
+
+            if is_failed {
+                return Err("Request timed out (injected failure)".to_string());
+            }
+
+            *self.state.lock().unwrap() = Some(state);
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SmartBulbPort for FlakyBulb {
+        async fn change_state(&self, state: BulbState) -> Result<(), String> {
+            self.actuate(state)
+        }
+
+        async fn change_color(&self, _red: u8, _green: u8, _blue: u8) -> Result<(), String> {
+            self.actuate(BulbState::BulbOn)
+        }
+    }
+
+    /// Helper para crear el adaptador compartido de EegHeadsetPort
+    fn create_static_eeg_mock<T>(
+        mock: T,
+    ) -> Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
+    where
+        T: EegHeadsetPort + Send + Sync + 'static,
+    {
+        Arc::new(RwLock::new(
+            Box::new(mock) as Box<dyn EegHeadsetPort + Send + Sync>
+        ))
+    }
+
+    /// Helper para crear el adaptador compartido de SmartBulbPort
+    fn create_static_bulb_mock<T>(
+        mock: T,
+    ) -> Arc<RwLock<Box<dyn SmartBulbPort + Send + Sync>>>
+    where
+        T: SmartBulbPort + Send + Sync + 'static,
+    {
+        Arc::new(RwLock::new(
+            Box::new(mock) as Box<dyn SmartBulbPort + Send + Sync>
+        ))
+    }
+
+    /// Helper para crear el adaptador compartido de ModelInferenceInterface
+    fn create_static_model_mock<T>(
+        mock: T,
+    ) -> Arc<RwLock<Box<dyn ModelInferenceInterface + Send + Sync>>>
+    where
+        T: ModelInferenceInterface + Send + Sync + 'static,
+    {
+        Arc::new(RwLock::new(
+            Box::new(mock) as Box<dyn ModelInferenceInterface + Send + Sync>
+        ))
+    }
+
+    // Helper para crear una máquina de estados para pruebas con mocks configurados
+    async fn create_test_state_machine(
+        eeg_mock: MockEegHeadsetAdapter,
+        bulb_mock: MockSmartBulbAdapter,
+        model_mock: MockModelService,
+    ) -> MainStateMachine {
+        let mut context = NeuralAnalyticsContext::default();
+
+        // Crear referencias estáticas para los mocks
+        context.eeg_headset_adapter = create_static_eeg_mock(eeg_mock);
+        context.smart_bulb_adapter = create_static_bulb_mock(bulb_mock);
+        context.model_service = create_static_model_mock(model_mock);
+
+        // Creamos la máquina de estados con el contexto mockeado
+        let bus = CommandBus::<NeuralAnalyticsContext, presage::Error>::new().configure(
+            Configuration::new()
+                .command_handler(&cleanup_session_use_case)
+                .command_handler(&create_diagnostic_bundle_use_case)
+                .command_handler(&disconnect_headband_use_case)
+                .command_handler(&extract_calibration_data_use_case)
+                .command_handler(&extract_generalist_data_use_case)
+                .command_handler(&notify_webhook_use_case)
+                .command_handler(&predict_color_thinking_use_case)
+                .command_handler(&publish_prediction_use_case)
+                .command_handler(&run_diagnostics_use_case)
+                .command_handler(&run_test_signal_use_case)
+                .command_handler(&search_headband_use_case)
+                .command_handler(&set_log_level_use_case)
+                .command_handler(&update_light_status_use_case),
+        );
+
+        let calibration_thresholds = context.calibration_thresholds.clone();
+        let cancellation = context.cancellation.clone();
+        let events = context.events.clone();
+        let inference_cache = context.inference_cache.clone();
+
+        MainStateMachine {
+            context: Arc::new(Mutex::new(context)),
+            command_bus: CommandMiddleware::new(bus),
+            health: Arc::new(std::sync::RwLock::new(CoreHealth::default())),
+            calibration_thresholds,
+            lifecycle_backlog: Arc::new(AtomicUsize::new(0)),
+            pending_transitions: std::sync::Mutex::new(Vec::new()),
+            metrics: Arc::new(std::sync::RwLock::new(MetricsSnapshot::default())),
+            cancellation,
+            inference_cache,
+            events,
+        }
+    }
+
+    // #[test]
+    // async fn test_initialize_application_state_transition() {
+    //     // Arrange
+    //     let eeg_mock = MockEegHeadsetAdapter::new();
+    //     let bulb_mock = MockSmartBulbAdapter::new();
+    //     let model_mock = MockModelService::new();
+
+    //     // Configuramos el entorno para que send_event tenga éxito
+    //     let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+    //     // Act - Ejecutar el estado de inicialización
+    //     let result = state_machine
+    //         .initialize_application(&NeuralAnalyticsCoreEvents::InitializeCore)
+    //         .await;
+
+    //     // Assert - Verificar que transitamos al estado de espera de conexión
+    //     if let Response::Transition(State::AwaitingHeadsetConnection { .. }) = result {
+    //         // Transición exitosa
+    //         assert!(true);
+    //     } else {
+    //         panic!("Expected transition to awaiting_headset_connection state");
+    //     }
+    // }
+
+    // #[test]
+    // async fn test_awaiting_headset_connection_success() {
+    //     // Arrange
+    //     let mut eeg_mock = MockEegHeadsetAdapter::new();
+    //     eeg_mock.expect_disconnect().returning(|| Ok(()));
+    //     eeg_mock.expect_is_connected().returning(|| false); // No conectado inicialmente
+    //     eeg_mock.expect_connect().returning(|| Ok(())); // Conexión exitosa
+    //     eeg_mock.expect_is_connected().returning(|| true); // Conectado después
+
+    //     let bulb_mock = MockSmartBulbAdapter::new();
+    //     let model_mock = MockModelService::new();
+
+    //     let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+    //     // Act
+    //     let result = state_machine
+    //         .awaiting_headset_connection(&NeuralAnalyticsCoreEvents::BackgroundTick)
+    //         .await;
+
+    //     // Assert - Verificar que transitamos al estado de calibración
+    //     if let Response::Transition(State::AwaitingHeadsetCalibration { .. }) = result {
+    //         // Transición exitosa
+    //         assert!(true);
+    //     } else {
+    //         panic!("Expected transition to awaiting_headset_calibration state");
+    //     }
+    // }
+
+    #[test]
+    async fn test_awaiting_headset_connection_failure() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+        eeg_mock.expect_disconnect().returning(|| Ok(()));
+        eeg_mock.expect_is_connected().returning(|| false);
+        eeg_mock
+            .expect_connect()
+            .returning(|| Err("Connection failed".to_string()));
+
+        let bulb_mock = MockSmartBulbAdapter::new();
+        let model_mock = MockModelService::new();
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Act
+        let result = state_machine
+            .awaiting_headset_connection(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert - Verificar que permanecemos en el mismo estado
+        if let Response::Transition(State::AwaitingHeadsetConnection { .. }) = result {
+            // Se mantiene en el mismo estado (esperado)
+            assert!(true);
+        } else {
+            panic!("Expected to remain in awaiting_headset_connection state");
+        }
+    }
+
+    #[test]
+    async fn test_awaiting_headset_calibration_success() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        let mut impedance_data = HashMap::new();
+        impedance_data.insert("sensor1".to_string(), 100);
+        impedance_data.insert("sensor2".to_string(), 100);
+
+        eeg_mock
+            .expect_extract_impedance_data()
+            .returning(move || Ok(impedance_data.clone()));
+
+        eeg_mock.expect_is_connected().returning(|| true);
+
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Calibration);
+
+        let bulb_mock = MockSmartBulbAdapter::new();
+        let model_mock = MockModelService::new();
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Configurar los datos de impedancia en el contexto
+        {
+            let mut ctx = state_machine.context.lock().await;
+            let mut data = HashMap::new();
+            data.insert("sensor1".to_string(), 100);
+            data.insert("sensor2".to_string(), 100);
+            ctx.impedance_data = Some(data);
+        }
+
+        // Act
+        let result = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert - Verificar que transitamos al estado de captura de datos
+        if let Response::Transition(State::CapturingHeadsetData { .. }) = result {
+            // Transición exitosa
+            assert!(true);
+        } else {
+            panic!("Expected transition to capturing_headset_data state");
+        }
+    }
+
+    #[test]
+    async fn test_awaiting_headset_calibration_needs_more_calibration() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        let mut impedance_data = HashMap::new();
+        impedance_data.insert("sensor1".to_string(), 2000); // Valor muy alto, requiere más calibración
+        impedance_data.insert("sensor2".to_string(), 100);
+
+        eeg_mock
+            .expect_extract_impedance_data()
+            .returning(move || Ok(impedance_data.clone()));
+
+        eeg_mock.expect_is_connected().returning(|| true);
+
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Calibration);
+
+        let bulb_mock = MockSmartBulbAdapter::new();
+        let model_mock = MockModelService::new();
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Configurar los datos de impedancia en el contexto
+        {
+            let mut ctx = state_machine.context.lock().await;
+            let mut data = HashMap::new();
+            data.insert("sensor1".to_string(), 2000);
+            data.insert("sensor2".to_string(), 100);
+            ctx.impedance_data = Some(data);
+        }
+
+        // Act
+        let result = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert - Verificar que permanecemos en el mismo estado
+        if let Response::Transition(State::AwaitingHeadsetCalibration { .. }) = result {
+            // Se mantiene en el estado de calibración (esperado)
+            assert!(true);
+        } else {
+            panic!("Expected to remain in awaiting_headset_calibration state");
+        }
+    }
+
+    #[test]
+    async fn test_awaiting_headset_calibration_uses_shared_thresholds() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        let mut impedance_data = HashMap::new();
+        impedance_data.insert("sensor1".to_string(), 2000);
+        impedance_data.insert("sensor2".to_string(), 1800);
+
+        eeg_mock
+            .expect_extract_impedance_data()
+            .returning(move || Ok(impedance_data.clone()));
+
+        eeg_mock.expect_is_connected().returning(|| true);
+
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Calibration);
+
+        let bulb_mock = MockSmartBulbAdapter::new();
+        let model_mock = MockModelService::new();
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Relax the thresholds as if the user accepted a suggestion
+        *state_machine.shared_calibration_thresholds().write().unwrap() = CalibrationThresholds {
+            min_impedance: 1,
+            max_impedance: 2500,
+        };
+
+        // Act
+        let result = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert
+        if let Response::Transition(State::CapturingHeadsetData { .. }) = result {
+            assert!(true);
+        } else {
+            panic!("Expected transition to capturing_headset_data state");
+        }
+    }
+
+    #[test]
+    async fn test_awaiting_headset_calibration_checks_signal_without_impedance() {
+        // Arrange: a detached electrode first, then a good contact
+        let mut eeg_mock = MockImpedancelessHeadsetAdapter::new();
+        let windows = std::sync::atomic::AtomicUsize::new(0);
+
+        eeg_mock.expect_is_connected().returning(|| true);
+        eeg_mock.expect_supports_impedance().returning(|| false);
+        eeg_mock.expect_signal_unit().returning(|| SignalUnit::Microvolts);
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+        eeg_mock.expect_extract_raw_data().returning(move || {
+            let is_detached = windows.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0;
+            let o1 = (0..62).map(|i| if is_detached { 0.0 } else { (i as f32).sin() * 30.0 }).collect();
+
+            Ok(HashMap::from([("O1".to_string(), o1)]))
+        });
+
+        let mut state_machine = create_test_state_machine(
+            MockEegHeadsetAdapter::new(),
+            MockSmartBulbAdapter::new(),
+            MockModelService::new(),
+        )
+        .await;
+        state_machine.context.lock().await.eeg_headset_adapter = create_static_eeg_mock(eeg_mock);
+
+        // Act
+        let first = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+        let second = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert
+        assert!(matches!(first, Response::Transition(State::AwaitingHeadsetCalibration {})));
+        assert!(matches!(second, Response::Transition(State::CapturingHeadsetData {})));
+    }
+
+    #[test]
+    async fn test_awaiting_headset_calibration_fails() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        eeg_mock
+            .expect_extract_impedance_data()
+            .returning(|| Err("Failed to extract impedance data".to_string()));
+
+        eeg_mock.expect_is_connected().returning(|| true);
+
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Calibration);
+
+        let bulb_mock = MockSmartBulbAdapter::new();
+        let model_mock = MockModelService::new();
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Act
+        let result = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert - Verificar que volvemos al estado de espera de conexión
+        if let Response::Transition(State::AwaitingHeadsetConnection { .. }) = result {
+            // Transición al estado de conexión (esperado)
+            assert!(true);
+        } else {
+            panic!("Expected transition to awaiting_headset_connection state");
+        }
+    }
+
+    #[test]
+    async fn test_awaiting_headset_calibration_retries_a_failed_reading() {
+        // Arrange: one retry, then the headset is considered lost
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        eeg_mock
+            .expect_extract_impedance_data()
+            .returning(|| Err("Failed to extract impedance data".to_string()));
+        eeg_mock.expect_is_connected().returning(|| true);
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Calibration);
+
+        let mut state_machine =
+            create_test_state_machine(eeg_mock, MockSmartBulbAdapter::new(), MockModelService::new()).await;
+        state_machine.context.lock().await.calibration_policy = CalibrationPolicyService::new(CalibrationConfig {
+            read_retries: 1,
+            ..Default::default()
+        });
+
+        // Act
+        let first = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+        let second = state_machine
+            .awaiting_headset_calibration(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert
+        assert!(matches!(first, Response::Transition(State::AwaitingHeadsetCalibration {})));
+        assert!(matches!(second, Response::Transition(State::AwaitingHeadsetConnection {})));
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_success() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        let mut raw_data = HashMap::new();
+        raw_data.insert("sensor1".to_string(), vec![1.0, 2.0, 3.0]);
+        raw_data.insert("sensor2".to_string(), vec![4.0, 5.0, 6.0]);
+
+        eeg_mock
+            .expect_extract_raw_data()
+            .returning(move || Ok(raw_data.clone()));
+
+        eeg_mock.expect_is_connected().returning(|| true);
+
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+
+        let mut bulb_mock = MockSmartBulbAdapter::new();
+        bulb_mock
+            .expect_change_state()
+            .with(eq(BulbState::BulbOn))
+            .returning(|_| Ok(()));
+
+        let mut model_mock = MockModelService::new();
+        model_mock
+            .expect_predict_color()
+            .returning(|_| Ok("green".to_string()));
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Configurar datos en el contexto
+        {
+            let mut ctx = state_machine.context.lock().await;
+            let mut data = HashMap::new();
+            data.insert("sensor1".to_string(), vec![1.0, 2.0, 3.0]);
+            data.insert("sensor2".to_string(), vec![4.0, 5.0, 6.0]);
+            ctx.headset_data = Some(data);
+        }
+
+        // Act
+        let result = state_machine
+            .capturing_headset_data(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert - Verificar que permanecemos en el mismo estado
+        if let Response::Transition(State::CapturingHeadsetData { .. }) = result {
+            // Se mantiene en el mismo estado (esperado)
+            assert!(true);
+        } else {
+            panic!("Expected to remain in capturing_headset_data state");
+        }
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_extraction_fails() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        eeg_mock
+            .expect_extract_raw_data()
+            .returning(|| Err("Failed to extract data".to_string()));
+
+        eeg_mock.expect_is_connected().returning(|| true);
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+
+        let bulb_mock = MockSmartBulbAdapter::new();
+        let model_mock = MockModelService::new();
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Act
+        let result = state_machine
+            .capturing_headset_data(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert - Verificar que volvemos al estado de espera de conexión
+        if let Response::Transition(State::AwaitingHeadsetConnection { .. }) = result {
+            // Transición al estado de conexión (esperado)
+            assert!(true);
+        } else {
+            panic!("Expected transition to awaiting_headset_connection state");
+        }
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_prediction_fails() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+
+        let mut raw_data = HashMap::new();
+        raw_data.insert("sensor1".to_string(), vec![1.0; WINDOW_SAMPLES]);
+
+        eeg_mock
+            .expect_extract_raw_data()
+            .returning(move || Ok(raw_data.clone()));
+
+        eeg_mock.expect_is_connected().returning(|| true);
+
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+
+        let bulb_mock = MockSmartBulbAdapter::new();
+
+        let mut model_mock = MockModelService::new();
+        model_mock
+            .expect_predict_color()
+            .returning(|_| Err("Model has no data".to_string()));
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+
+        // Configurar datos en el contexto
+        {
+            let mut ctx = state_machine.context.lock().await;
+            let mut data = HashMap::new();
+            data.insert("sensor1".to_string(), vec![1.0, 2.0, 3.0]);
+            ctx.headset_data = Some(data);
+        }
+
+        // Act
+        let result = state_machine
+            .capturing_headset_data(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert - Verificar que volvemos al estado de espera de conexión
+        if let Response::Transition(State::AwaitingHeadsetConnection { .. }) = result {
+            // Transición al estado de conexión (esperado)
+            assert!(true);
+        } else {
+            panic!("Expected transition to awaiting_headset_connection state");
+        }
+    }
+
+    #[test]
+    async fn test_context_snapshot_summarizes_context() {
+        // Arrange
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+        eeg_mock.expect_is_connected().return_const(true);
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+
+        let mut model_mock = MockModelService::new();
+        model_mock.expect_is_model_loaded().return_const(true);
+
+        let state_machine =
+            create_test_state_machine(eeg_mock, MockSmartBulbAdapter::new(), model_mock).await;
+
+        {
+            let mut ctx = state_machine.context.lock().await;
+            ctx.headset_data = Some([("T3".to_string(), vec![-2.0, 4.0, 1.0])].into_iter().collect());
+            ctx.color_thinking.push_back("red".to_string());
+            ctx.window_id = 7;
+        }
+
+        // Act
+        let snapshot = state_machine.context_snapshot().await;
+
+        // Assert
+        assert_eq!(snapshot.headset_samples["T3"], 3);
+        assert_eq!(snapshot.color_thinking, "red");
+        assert_eq!(snapshot.window_id, 7);
+        assert_eq!(snapshot.normalization_ranges["T3"].min, -2.0);
+        assert_eq!(snapshot.normalization_ranges["T3"].max, 4.0);
+        assert_eq!(snapshot.normalization_ranges["T3"].mean, 1.0);
+        assert_eq!(snapshot.adapters.is_headset_connected, Some(true));
+        assert_eq!(snapshot.adapters.headset_work_mode.as_deref(), Some("Extraction"));
+        assert_eq!(snapshot.adapters.is_model_loaded, Some(true));
+        assert!(serde_json::to_string(&snapshot).is_ok());
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_recalibration_requested() {
+        // Arrange - No device access is expected
+        let mut state_machine = create_test_state_machine(
+            MockEegHeadsetAdapter::new(),
+            MockSmartBulbAdapter::new(),
+            MockModelService::new(),
+        )
+        .await;
+
+        // Act
+        let result = state_machine
+            .capturing_headset_data(&NeuralAnalyticsCoreEvents::RecalibrationRequested)
+            .await;
+
+        // Assert
+        assert!(matches!(
+            result,
+            Response::Transition(State::AwaitingHeadsetCalibration {})
+        ));
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_reconnect_requested() {
+        // Arrange - The headset is searched again by the next state, not here
+        let mut state_machine = create_test_state_machine(
+            MockEegHeadsetAdapter::new(),
+            MockSmartBulbAdapter::new(),
+            MockModelService::new(),
+        )
+        .await;
+
+        // Act
+        let result = state_machine
+            .capturing_headset_data(&NeuralAnalyticsCoreEvents::ReconnectRequested)
+            .await;
+
+        // Assert
+        assert!(matches!(
+            result,
+            Response::Transition(State::AwaitingHeadsetConnection {})
+        ));
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_paused_skips_extraction() {
+        // Arrange - No extraction is expected while paused
+        let mut state_machine = create_test_state_machine(
+            MockEegHeadsetAdapter::new(),
+            MockSmartBulbAdapter::new(),
+            MockModelService::new(),
+        )
+        .await;
+        state_machine.pause_capture().await;
+
+        // Act
+        let result = state_machine
+            .capturing_headset_data(&NeuralAnalyticsCoreEvents::BackgroundTick)
+            .await;
+
+        // Assert
+        assert!(matches!(
+            result,
+            Response::Transition(State::CapturingHeadsetData {})
+        ));
+
+        state_machine.resume_capture().await;
+        assert!(!state_machine.context.lock().await.is_capture_paused);
+    }
+
+    #[test]
+    async fn test_record_tick_updates_health() {
+        // Arrange
+        let state_machine = create_test_state_machine(
+            MockEegHeadsetAdapter::new(),
+            MockSmartBulbAdapter::new(),
+            MockModelService::new(),
+        )
+        .await;
+
+        // Act
+        state_machine.record_tick(&State::CapturingHeadsetData {});
+
+        // Assert
+        let health = state_machine.health();
+        let health = health.read().unwrap();
+        assert_eq!(health.state, "CapturingHeadsetData");
+        assert!(health.is_headset_connected);
+        assert!(health.last_tick_timestamp.is_some());
+        assert!(health.last_window_timestamp.is_none());
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_retries_a_flaky_bulb() {
+        // Arrange - A new window each time, all predicted green, and a bulb failing half of the actuations
+        let windows = std::sync::atomic::AtomicU64::new(0);
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+        eeg_mock.expect_is_connected().returning(|| true);
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+        eeg_mock.expect_extract_raw_data().returning(move || {
+            let window = windows.fetch_add(1, std::sync::atomic::Ordering::SeqCst) as f32;
+            Ok([("T3".to_string(), vec![window; WINDOW_SAMPLES])].into_iter().collect())
+        });
+
+        let mut model_mock = MockModelService::new();
+        model_mock.expect_predict_color().returning(|_| Ok("green".to_string()));
+        model_mock.expect_is_model_loaded().returning(|| true);
+
+        let bulb = FlakyBulb::new(0.5, 7);
+
+        // The retry policies of the core, not the bare bus of the other tests
+        let mut state_machine = MainStateMachine::new(
+            CoreConfig::default(),
+            Arc::default(),
+            crate::domain::context::adapter_registry::standalone_adapter_registry(),
+        )
+        .await;
+        {
+            let mut ctx = state_machine.context.lock().await;
+            ctx.eeg_headset_adapter = create_static_eeg_mock(eeg_mock);
+            ctx.smart_bulb_adapter = create_static_bulb_mock(bulb.clone());
+            ctx.model_service = create_static_model_mock(model_mock);
+        }
+
+        // Act & Assert - Every window is retried up to 3 times, and acted on once at most
+        for _ in 0..10 {
+            let attempts = bulb.attempts().len();
+
+            state_machine
+                .capturing_headset_data(&NeuralAnalyticsCoreEvents::BackgroundTick)
+                .await;
+
+            let new_attempts = &bulb.attempts()[attempts..];
+            assert!((1..=3).contains(&new_attempts.len()));
+            assert!(new_attempts[..new_attempts.len() - 1].iter().all(|is_failed| *is_failed));
+            assert!(new_attempts.iter().filter(|is_failed| !**is_failed).count() <= 1);
+        }
+
+        let ctx = state_machine.context.lock().await;
+        let failed_attempts = bulb.attempts().iter().filter(|is_failed| **is_failed).count() as u64;
+        let failed_commands = ctx.metrics.counter(&format!("commands.{}.failed", UpdateLightStatusCommand::NAME));
+
+        assert!(failed_attempts > 0);
+        assert_eq!(ctx.metrics.counter(&format!("commands.{}.retries", UpdateLightStatusCommand::NAME)), failed_attempts - failed_commands);
+        assert_eq!(bulb.state(), Some(BulbState::BulbOn));
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_pauses_on_a_trash_streak() {
+        // Arrange - A new window each time, all predicted trash
+        let windows = std::sync::atomic::AtomicU64::new(0);
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+        eeg_mock.expect_is_connected().returning(|| true);
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+        eeg_mock.expect_extract_raw_data().returning(move || {
+            let window = windows.fetch_add(1, std::sync::atomic::Ordering::SeqCst) as f32;
+            Ok([("T3".to_string(), vec![window; WINDOW_SAMPLES])].into_iter().collect())
+        });
+
+        let mut model_mock = MockModelService::new();
+        model_mock.expect_predict_color().times(3).returning(|_| Ok("trash".to_string()));
+        model_mock.expect_is_model_loaded().returning(|| true);
+
+        let mut bulb_mock = MockSmartBulbAdapter::new();
+        bulb_mock.expect_change_state().times(3).returning(|_| Ok(()));
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+        state_machine.context.lock().await.prediction_streak = PredictionStreakService::new(TrashStreak {
+            limit: 3,
+            ..Default::default()
+        });
+
+        // Act - The windows after the third one are neither predicted nor acted on
+        for _ in 0..6 {
+            state_machine
+                .capturing_headset_data(&NeuralAnalyticsCoreEvents::BackgroundTick)
+                .await;
+        }
+
+        // Assert
+        {
+            let mut ctx = state_machine.context.lock().await;
+            let notifications = ctx.take_notifications();
+
+            assert!(ctx.prediction_streak.is_paused());
+            assert_eq!(ctx.metrics.counter("inference.paused"), 1);
+            assert_eq!(
+                notifications
+                    .iter()
+                    .filter(|notification| notification.name == InferencePausedEvent::NAME)
+                    .map(|notification| notification.data.trash_streak)
+                    .collect::<Vec<_>>(),
+                vec![Some(3)]
+            );
+        }
+
+        state_machine
+            .capturing_headset_data(&NeuralAnalyticsCoreEvents::RecalibrationRequested)
+            .await;
+        assert!(!state_machine.context.lock().await.prediction_streak.is_paused());
+    }
+
+    #[test]
+    async fn test_capturing_headset_data_holds_each_change_in_exhibition_mode() {
+        // Arrange - A new window each time, the first ones predicted green and the next ones red
+        let windows = std::sync::atomic::AtomicU64::new(0);
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+        eeg_mock.expect_is_connected().returning(|| true);
+        eeg_mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+        eeg_mock.expect_extract_raw_data().returning(move || {
+            let window = windows.fetch_add(1, std::sync::atomic::Ordering::SeqCst) as f32;
+            Ok([("T3".to_string(), vec![window; WINDOW_SAMPLES])].into_iter().collect())
+        });
+
+        let predictions = std::sync::atomic::AtomicU64::new(0);
+        let mut model_mock = MockModelService::new();
+        model_mock.expect_predict_color().returning(move |_| {
+            let prediction = predictions.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(if prediction < 2 { "green" } else { "red" }.to_string())
+        });
+        model_mock.expect_is_model_loaded().returning(|| true);
+
+        // Only the first change reaches the bulb, the next one being held
+        let mut bulb_mock = MockSmartBulbAdapter::new();
+        bulb_mock.expect_change_state().times(1).with(eq(BulbState::BulbOn)).returning(|_| Ok(()));
+
+        let mut state_machine = create_test_state_machine(eeg_mock, bulb_mock, model_mock).await;
+        let mut config = CoreConfig::default();
+        config.exhibition.enabled = true;
+        config.exhibition.hold_ms = 60_000;
+        state_machine.context.lock().await.light_policy = LightPolicyService::new(&config);
+
+        // Act
+        for _ in 0..6 {
+            state_machine
+                .capturing_headset_data(&NeuralAnalyticsCoreEvents::BackgroundTick)
+                .await;
+        }
+
+        // Assert - The overlay is shown along with the bulb, once
+        let notifications = state_machine.context.lock().await.take_notifications();
+        let cues: Vec<_> = notifications
+            .iter()
+            .filter(|notification| notification.name == ExhibitionCueEvent::NAME)
+            .filter_map(|notification| notification.data.exhibition_cue.clone())
+            .collect();
+
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].class, "green");
+        assert_eq!(cues[0].hold_ms, 60_000);
+    }
+
+    #[test]
+    async fn test_transitions_are_counted_per_pair_of_states() {
+        // Arrange
+        let mut state_machine = create_test_state_machine(
+            MockEegHeadsetAdapter::new(),
+            MockSmartBulbAdapter::new(),
+            MockModelService::new(),
+        )
+        .await;
+
+        // Act - The re-entries of the headset search are not transitions
+        state_machine.on_transition(&State::AwaitingHeadsetConnection {}, &State::AwaitingHeadsetConnection {});
+        state_machine.on_transition(&State::CapturingHeadsetData {}, &State::AwaitingHeadsetConnection {});
+        state_machine.on_transition(&State::CapturingHeadsetData {}, &State::AwaitingHeadsetConnection {});
+        state_machine.publish_notifications().await;
+
+        // Assert
+        let metrics = state_machine.shared_metrics();
+        let metrics = metrics.read().unwrap();
+        let transitions = metrics.counters.keys().filter(|name| name.starts_with("state.transitions")).count();
+
+        assert_eq!(transitions, 1);
+        assert_eq!(
+            metrics.counters["state.transitions{from=\"capturing_headset_data\",to=\"awaiting_headset_connection\"}"],
+            2
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::domain::{
+    commands::cleanup_session_command::CleanupSessionCommand, context::NeuralAnalyticsContext,
+    events::bulb_state_changed_event::BulbStateChangedEvent, models::bulb_state::BulbState,
+};
+use log::{error, info};
+use presage::{command_handler, Error, Events};
+use std::time::Instant;
+
+/// This use case is responsible for leaving the devices idle when a session ends.
+/// It turns the bulb off if the session left it on, and disconnects the headset,
+/// which stops the current measuring mode of the board so it does not drain the battery.
+///
+/// The cleanup is best effort: failures are logged and never abort the exit path.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which contains
+/// the EEG headset and smart bulb adapters.
+/// * `_command`: The command to clean up the session.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing a `BulbStateChangedEvent` if the bulb was turned off.
+#[command_handler(error = Error)]
+pub async fn cleanup_session_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    _command: CleanupSessionCommand,
+) -> Result<Events, Error> {
+    info!("Cleaning up the session devices...");
+
+    let mut events = Events::new();
+
+    if _context.bulb_state == Some(BulbState::BulbOn) {
+        let start_actuation = Instant::now();
+        let turned_off = _context
+            .with_bulb(|bulb| Box::pin(async move { bulb.change_state(BulbState::BulbOff).await.map(|_| bulb.is_simulated()) }))
+            .await;
+
+        match turned_off.and_then(|result| result) {
+            Ok(is_simulated) => {
+                let _ = events.add(BulbStateChangedEvent {
+                    desired: BulbState::BulbOff,
+                    confirmed: true,
+                    latency_ms: start_actuation.elapsed().as_millis() as u64,
+                    is_simulated,
+                    error: None,
+                });
+            }
+            Err(e) => {
+                error!("Failed to turn the bulb off at the end of the session: {}", e);
+
+                // Reported unconfirmed, so the failed attempt is journaled with its error
+                let _ = events.add(BulbStateChangedEvent {
+                    desired: BulbState::BulbOff,
+                    confirmed: false,
+                    latency_ms: start_actuation.elapsed().as_millis() as u64,
+                    is_simulated: false,
+                    error: Some(e),
+                });
+            }
+        }
+    }
+
+    let disconnected = _context
+        .with_headset_mut(|headset| if headset.is_connected() { headset.disconnect() } else { Ok(()) })
+        .await;
+
+    if let Err(e) = disconnected.and_then(|result| result) {
+        error!("Failed to stop the headset at the end of the session: {}", e);
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::domain::models::eeg_work_modes::WorkMode;
+    use crate::domain::ports::{input::eeg_headset::EegHeadsetPort, output::smart_bulb::SmartBulbPort};
+    use mockall::mock;
+    use mockall::predicate::*;
+    use presage::{CommandBus, Configuration};
+    use tokio::sync::RwLock;
+
+    mock! {
+        SmartBulbAdapter {}
+        #[async_trait::async_trait]
+        impl SmartBulbPort for SmartBulbAdapter {
+            async fn change_state(&self, state: BulbState) -> Result<(), String>;
+        }
+    }
+
+    mock! {
+        EegHeadsetAdapter {}
+        impl EegHeadsetPort for EegHeadsetAdapter {
+            fn connect(&self) -> Result<(), String>;
+            fn disconnect(&mut self) -> Result<(), String>;
+            fn is_connected(&self) -> bool;
+            fn get_work_mode(&self) -> WorkMode;
+            fn change_work_mode(&mut self, mode: WorkMode);
+            fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String>;
+            fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String>;
+        }
+    }
+
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&cleanup_session_use_case))
+    }
+
+    fn setup_context(bulb_mock: MockSmartBulbAdapter, eeg_mock: MockEegHeadsetAdapter) -> NeuralAnalyticsContext {
+        let bulb: Box<dyn SmartBulbPort + Send + Sync> = Box::new(bulb_mock);
+        let eeg: Box<dyn EegHeadsetPort + Send + Sync> = Box::new(eeg_mock);
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.smart_bulb_adapter = Arc::new(RwLock::new(bulb));
+        context.eeg_headset_adapter = Arc::new(RwLock::new(eeg));
+        context
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_session_turns_bulb_off_and_stops_headset() {
+        // Arrange
+        let mut bulb_mock = MockSmartBulbAdapter::new();
+        bulb_mock
+            .expect_change_state()
+            .with(eq(BulbState::BulbOff))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+        eeg_mock.expect_is_connected().return_const(true);
+        eeg_mock.expect_disconnect().times(1).returning(|| Ok(()));
+
+        let mut context = setup_context(bulb_mock, eeg_mock);
+        context.bulb_state = Some(BulbState::BulbOn);
+
+        // Act
+        let result = setup_command_bus().execute(&mut context, CleanupSessionCommand).await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(context.bulb_state, Some(BulbState::BulbOff));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_session_ignores_failures_and_idle_devices() {
+        // Arrange - The bulb is already off and the headset disconnected
+        let mut bulb_mock = MockSmartBulbAdapter::new();
+        bulb_mock.expect_change_state().times(0);
+
+        let mut eeg_mock = MockEegHeadsetAdapter::new();
+        eeg_mock.expect_is_connected().return_const(false);
+        eeg_mock.expect_disconnect().times(0);
+
+        let mut context = setup_context(bulb_mock, eeg_mock);
+        context.bulb_state = Some(BulbState::BulbOff);
+
+        // Act
+        let result = setup_command_bus().execute(&mut context, CleanupSessionCommand).await;
+
+        // Assert
+        assert!(result.is_ok());
+    }
+}
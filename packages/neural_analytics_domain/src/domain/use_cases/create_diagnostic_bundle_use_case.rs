@@ -0,0 +1,160 @@
+use crate::domain::{
+    commands::create_diagnostic_bundle_command::CreateDiagnosticBundleCommand, context::NeuralAnalyticsContext,
+    models::{diagnostic_bundle::VersionInfo, event_internals::CreatedDiagnosticBundleEvent},
+};
+use log::info;
+use presage::{command_handler, Error, Events};
+
+/// Serializes a file of the bundle, as the frontends cannot report a partial bundle.
+fn to_json<T: serde::Serialize>(name: &str, value: &T) -> Result<String, Error> {
+    serde_json::to_string_pretty(value).map_err(|e| {
+        let msg = format!("Could not serialize {} of the diagnostic bundle: {}", name, e);
+        Error::MissingCommandHandler(Box::leak(msg.into_boxed_str()))
+    })
+}
+
+/// This use case is responsible for gathering what an issue report needs into an
+/// archive the user can attach, without the raw EEG samples.
+///
+/// The archive is written by the bundle archive adapter, a zip file with the
+/// adapters of the composition crate.
+///
+/// The bundle holds the configuration with its webhook URLs redacted, the tail of the
+/// session journal, the metrics, the context snapshot, the last state transitions and
+/// the versions of the core, the headset and the model.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` to gather the bundle from.
+/// * `command`: The command holding the archive to write.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing the event with the archive written, or an
+///   error if it could not be written.
+#[command_handler(error = Error)]
+pub async fn create_diagnostic_bundle_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    command: CreateDiagnosticBundleCommand,
+) -> Result<Events, Error> {
+    info!("Creating diagnostic bundle {}...", command.path);
+
+    let (is_model_loaded, class_labels) = _context
+        .with_model(|model_service| (Some(model_service.is_model_loaded()), model_service.class_labels()))
+        .await
+        .unwrap_or_default();
+    let version = VersionInfo {
+        core_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        device_id: _context.device_id.clone(),
+        firmware_version: _context.firmware_version.clone(),
+        is_model_loaded,
+        class_labels,
+    };
+
+    let journal = _context
+        .journal
+        .tail()
+        .iter()
+        .map(|entry| serde_json::to_string(entry).map(|line| line + "\n"))
+        .collect::<Result<String, _>>()
+        .map_err(|e| {
+            let msg = format!("Could not serialize the journal of the diagnostic bundle: {}", e);
+            Error::MissingCommandHandler(Box::leak(msg.into_boxed_str()))
+        })?;
+
+    let files = [
+        ("version.json", to_json("the versions", &version)?),
+        ("config.json", to_json("the configuration", &_context.redacted_config)?),
+        ("journal.jsonl", journal),
+        ("metrics.json", to_json("the metrics", &_context.metrics.snapshot())?),
+        ("snapshot.json", to_json("the context snapshot", &_context.snapshot())?),
+        ("transitions.json", to_json("the state transitions", &_context.transitions)?),
+    ];
+
+    _context
+        .with_bundle_archive(|archive| archive.write(&command.path, &files))
+        .await
+        .and_then(|written| written)
+        .map_err(|msg| Error::MissingCommandHandler(Box::leak(msg.into_boxed_str())))?;
+
+    let mut events = Events::new();
+    let _ = events.add(CreatedDiagnosticBundleEvent {
+        bundle_path: command.path,
+    });
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{core_config::CoreConfig, prediction_action::PredictionAction};
+    use crate::domain::ports::output::bundle_archive::BundleArchivePort;
+    use presage::{CommandBus, Configuration};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::RwLock;
+
+    type Archive = dyn BundleArchivePort + Send + Sync;
+
+    /// Archive keeping the files written to each path
+    #[derive(Clone, Default)]
+    struct RecordingArchive {
+        bundles: Arc<Mutex<HashMap<String, Vec<(String, String)>>>>,
+    }
+
+    impl BundleArchivePort for RecordingArchive {
+        fn write(&self, path: &str, files: &[(&str, String)]) -> Result<(), String> {
+            let files = files.iter().map(|(name, content)| (name.to_string(), content.clone())).collect();
+            self.bundles.lock().unwrap().insert(path.to_string(), files);
+            Ok(())
+        }
+    }
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&create_diagnostic_bundle_use_case))
+    }
+
+    #[tokio::test]
+    async fn test_create_diagnostic_bundle_redacts_the_webhooks() {
+        // Arrange
+        let mut config = CoreConfig::default();
+        config.action_mapping.insert(
+            "blue".to_string(),
+            PredictionAction::Webhook {
+                url: "https://example.com/hook?token=secret".to_string(),
+            },
+        );
+        let archive = RecordingArchive::default();
+        let mut context = NeuralAnalyticsContext::new(config);
+        context.bundle_archive = Arc::new(RwLock::new(Box::new(archive.clone()) as Box<Archive>));
+        context.record_transition(1, "awaiting_headset_connection", "awaiting_headset_calibration");
+        let command_bus = setup_command_bus();
+        let path = "bundle.zip".to_string();
+
+        // Act
+        let result = command_bus
+            .execute(&mut context, CreateDiagnosticBundleCommand { path: path.clone() })
+            .await;
+
+        // Assert
+        assert!(result.is_ok());
+
+        let bundles = archive.bundles.lock().unwrap();
+        let files: HashMap<&str, &str> = bundles[&path]
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_str()))
+            .collect();
+        let mut names: Vec<&str> = files.keys().copied().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["config.json", "journal.jsonl", "metrics.json", "snapshot.json", "transitions.json", "version.json"]
+        );
+
+        assert!(!files["config.json"].contains("secret"), "{}", files["config.json"]);
+        assert!(files["transitions.json"].contains("awaiting_headset_calibration"));
+    }
+}
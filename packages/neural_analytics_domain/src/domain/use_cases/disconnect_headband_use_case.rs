@@ -21,39 +21,44 @@ pub async fn disconnect_headband_use_case(
 ) -> Result<Events, Error> {
     info!("Starting search and connection of BrainBit device...");
 
-    // Obtain the EEG headset adapter from the context
-    let mut headset = _context.eeg_headset_adapter.write().await;
-
-    let is_connected = headset.is_connected();
-
-    // Check if already connected
-    if !is_connected {
-        debug!("The device is already disconnected.");
-        return Ok(Events::new());
-    }
-
-    // Try to connect to the device
-    match headset.disconnect() {
-        Ok(_) => {
-            debug!("Disconnected successfully.");
-        },
-        Err(e) => {
-            let error_msg = format!("Error disconnecting from the device: {}", e);
-            error!("{}", error_msg);
-            return Err(Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str())).into());
-        }
-    }
-
-    if !headset.is_connected() {
-        debug!("The device is now disconnected.");
-        
-        // Return an empty list of events for now
-        Ok(Events::new())
-    } else {
-        let error_msg = "Error: Device is not disconnected or is sending data. Disconnect first.";
-        error!("{}", error_msg);
-        return Err(Error::MissingCommandHandler(error_msg).into());
-    }
+    // Disconnect the EEG headset adapter of the context
+    _context
+        .with_headset_mut(|headset| {
+            let is_connected = headset.is_connected();
+
+            // Check if already connected
+            if !is_connected {
+                debug!("The device is already disconnected.");
+                return Ok(());
+            }
+
+            // Try to connect to the device
+            match headset.disconnect() {
+                Ok(_) => {
+                    debug!("Disconnected successfully.");
+                },
+                Err(e) => {
+                    let error_msg = format!("Error disconnecting from the device: {}", e);
+                    error!("{}", error_msg);
+                    return Err(Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str())));
+                }
+            }
+
+            if !headset.is_connected() {
+                debug!("The device is now disconnected.");
+
+                Ok(())
+            } else {
+                let error_msg = "Error: Device is not disconnected or is sending data. Disconnect first.";
+                error!("{}", error_msg);
+                Err(Error::MissingCommandHandler(error_msg))
+            }
+        })
+        .await
+        .map_err(|e| Error::MissingCommandHandler(Box::leak(e.into_boxed_str())))??;
+
+    // Return an empty list of events for now
+    Ok(Events::new())
 }
 
 #[cfg(test)]
@@ -83,22 +88,19 @@ mod tests {
     }
 
     /// Función auxiliar para crear mocks estáticos para los tests
-    /// Esta función crea un mock y lo convierte en una referencia estática
+    /// Esta función crea un mock y lo comparte en un Arc<RwLock>
     /// que puede ser utilizada en el contexto del test.
     fn create_static_mock<T>(
         mock: T,
-    ) -> &'static Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
+    ) -> Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
     where
         T: EegHeadsetPort + Send + Sync + 'static,
     {
         // Crear un Box dinámico con el mock
         let boxed_mock: Box<dyn EegHeadsetPort + Send + Sync> = Box::new(mock);
 
-        // Envolver en RwLock y Arc
-        let arc_rwlock = Arc::new(RwLock::new(boxed_mock));
-
-        // Convertir a referencia estática
-        Box::leak(Box::new(arc_rwlock))
+        // Envolver en RwLock y Arc, compartido con el contexto
+        Arc::new(RwLock::new(boxed_mock))
     }
 
     /// Función auxiliar para configurar el CommandBus para los tests
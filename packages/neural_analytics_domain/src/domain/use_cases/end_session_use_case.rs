@@ -0,0 +1,31 @@
+use crate::domain::{commands::end_session_command::EndSessionCommand, context::NeuralAnalyticsContext};
+use log::{error, info};
+use presage::{command_handler, Error, Events};
+
+/// This use case is responsible for closing the session opened by `StartSessionCommand`.
+/// Its summary is reported and its recording closed; a headset still connected
+/// continues in a new session, closed when it disconnects.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which holds the session.
+/// * `_command`: The command to end the session.
+///
+/// # Returns
+/// * `Result<Events, Error>`: An error if no session was started by the operator.
+#[command_handler(error = Error)]
+pub async fn end_session_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    _command: EndSessionCommand,
+) -> Result<Events, Error> {
+    let session_id = _context.session_id;
+
+    _context.end_explicit_session().map_err(|e| {
+        let error_msg = format!("Error ending the session: {}", e);
+        error!("{}", error_msg);
+        Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str()))
+    })?;
+
+    info!("Session {} ended by the operator", session_id);
+
+    Ok(Events::new())
+}
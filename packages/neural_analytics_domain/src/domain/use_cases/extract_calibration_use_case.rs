@@ -2,8 +2,13 @@ use presage::{command_handler, Error, Events};
 use crate::domain::{
     commands::extract_calibration_data_command::ExtractCalibrationDataCommand, 
     context::NeuralAnalyticsContext, 
-    models::{eeg_work_modes::WorkMode, event_internals::ReceivedCalibrationDataEvent}, 
-    ports::input::eeg_headset::EegHeadsetPort
+    models::{
+        eeg_work_modes::WorkMode,
+        event_internals::{CheckedSignalQualityEvent, ReceivedCalibrationDataEvent},
+        signal_quality::SignalQuality,
+    },
+    ports::input::eeg_headset::EegHeadsetPort,
+    services::window_assembler_service::WINDOW_SAMPLES,
 };
 use std::collections::HashMap;
 use log::{self, info};
@@ -12,6 +17,9 @@ use log::{self, info};
 /// and processing it. It checks if the device is connected and in the correct mode
 /// before attempting to extract the data. The extracted data is then processed and
 /// returned as an event.
+///
+/// Headsets that cannot measure impedance stay in extraction mode, and the quality
+/// of a window of their signal is checked instead.
 /// 
 /// # Arguments
 /// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which contains
@@ -28,40 +36,80 @@ pub async fn extract_calibration_data_use_case(
 ) -> Result<Events, Error> {
     log::info!("Starting calibration data extraction from BrainBit device...");
 
-    // Obtain the EEG headset adapter from the context
-    let mut headset_guard = _context.eeg_headset_adapter.write().await;
-    let headset: &mut dyn EegHeadsetPort = headset_guard.as_mut();
+    // Obtain the EEG headset adapter from the context, locked for the whole calibration step
+    _context
+        .with_headset_mut(|headset| {
+            // Check if the device is connected
+            if !headset.is_connected() {
+                let error_msg = "Error: Device is not connected. Connect first.";
+                log::error!("{}", error_msg);
+                return Err(Error::MissingCommandHandler(error_msg));
+            }
+
+            if !headset.supports_impedance() {
+                return check_signal_quality(headset);
+            }
+
+            if headset.get_work_mode() != WorkMode::Calibration {
+                log::info!("Changing work mode to Calibration...");
+                headset.change_work_mode(WorkMode::Calibration);
+            }
+    
+            let data = match headset.extract_impedance_data() {
+                Ok(data) => {
+                    process_impedance_data(&data);
+                    log::info!("Calibration data successfully extracted.");
+                    data
+                },
+                Err(e) => {
+                    let error_msg = format!("Error extracting calibration data from device: {}", e);
+                    log::error!("{}", error_msg);
+                    return Err(Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str())));
+                }
+            };
+
+            let mut events = Events::new();
+
+            let _ = events.add(ReceivedCalibrationDataEvent {
+                impedance_data: data,
+            });
+
+            Ok(events)
+        })
+        .await
+        .map_err(|e| Error::MissingCommandHandler(Box::leak(e.into_boxed_str())))?
+}
 
-    // Check if the device is connected
-    if !headset.is_connected() {
-        let error_msg = "Error: Device is not connected. Connect first.";
-        log::error!("{}", error_msg);
-        return Err(Error::MissingCommandHandler(error_msg).into());
+// Checks the quality of a window of signal, the calibration of the headsets without impedance
+fn check_signal_quality(headset: &mut dyn EegHeadsetPort) -> Result<Events, Error> {
+    if headset.get_work_mode() != WorkMode::Extraction {
+        log::info!("Impedance not supported by the headset, checking the signal quality instead...");
+        headset.change_work_mode(WorkMode::Extraction);
     }
 
-    if headset.get_work_mode() != WorkMode::Calibration {
-        log::info!("Changing work mode to Calibration...");
-        headset.change_work_mode(WorkMode::Calibration);
-    }
-    
-    let data = match headset.extract_impedance_data() {
-        Ok(data) => {
-            process_impedance_data(&data);
-            log::info!("Calibration data successfully extracted.");
-            data
-        },
+    // The quality is judged on a whole window, even when the capture overlaps them
+    headset.set_window_hop(WINDOW_SAMPLES);
+
+    let unit = headset.signal_unit();
+    let signal_quality: HashMap<String, SignalQuality> = match headset.extract_raw_data() {
+        Ok(data) => data
+            .iter()
+            .map(|(electrode, samples)| (electrode.clone(), SignalQuality::of(samples, unit)))
+            .collect(),
         Err(e) => {
-            let error_msg = format!("Error extracting calibration data from device: {}", e);
+            let error_msg = format!("Error extracting signal quality data from device: {}", e);
             log::error!("{}", error_msg);
-            return Err(Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str())).into());
+            return Err(Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str())));
         }
     };
 
+    for (electrode, quality) in &signal_quality {
+        info!("  Electrode {}: {:?} signal", electrode, quality);
+    }
+
     let mut events = Events::new();
 
-    let _ = events.add(ReceivedCalibrationDataEvent {
-        impedance_data: data,
-    });
+    let _ = events.add(CheckedSignalQualityEvent { signal_quality });
 
     Ok(events)
 }
@@ -110,22 +158,19 @@ mod tests {
     }
 
     /// Función auxiliar para crear mocks estáticos para los tests
-    /// Esta función crea un mock y lo convierte en una referencia estática
+    /// Esta función crea un mock y lo comparte en un Arc<RwLock>
     /// que puede ser utilizada en el contexto del test.
     fn create_static_mock<T>(
         mock: T,
-    ) -> &'static Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
+    ) -> Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
     where
         T: EegHeadsetPort + Send + Sync + 'static,
     {
         // Crear un Box dinámico con el mock
         let boxed_mock: Box<dyn EegHeadsetPort + Send + Sync> = Box::new(mock);
 
-        // Envolver en RwLock y Arc
-        let arc_rwlock = Arc::new(RwLock::new(boxed_mock));
-
-        // Convertir a referencia estática
-        Box::leak(Box::new(arc_rwlock))
+        // Envolver en RwLock y Arc, compartido con el contexto
+        Arc::new(RwLock::new(boxed_mock))
     }
 
     /// Función auxiliar para configurar el CommandBus para los tests
@@ -0,0 +1,460 @@
+use crate::domain::{
+    commands::extract_generalist_data_command::ExtractGeneralistDataCommand,
+    context::{adapter_registry::SharedAdapter, NeuralAnalyticsContext},
+    models::{
+        eeg_work_modes::WorkMode,
+        event_internals::{DiscardedWindowEvent, ReceivedGeneralistDataEvent},
+        headset_reading::HeadsetReading,
+    },
+    ports::input::eeg_headset::EegHeadsetPort,
+    services::data_integrity_service::count_non_finite_samples,
+};
+use log::{error, info, warn};
+use presage::{command_handler, Error, Events};
+use std::collections::HashMap;
+
+/// Reads the next window from the EEG headset, without the context.
+///
+/// The board read blocks until the samples of the hop arrive, so it runs on the blocking
+/// threads of the runtime, holding only the lock of the headset, and the context is
+/// locked afterwards to store the window with `ExtractGeneralistDataCommand`. It checks
+/// if the device is connected and in the correct mode before attempting to read.
+///
+/// # Arguments
+/// * `headset`: The EEG headset adapter of the context.
+/// * `hop_samples`: The new samples to await, the rest of the window overlapping the previous one.
+///
+/// # Returns
+/// * `Result<HeadsetReading, String>`: The samples read, or an error if the headset could not be read.
+pub async fn read_headset_window(
+    headset: &SharedAdapter<dyn EegHeadsetPort + Send + Sync>,
+    hop_samples: usize,
+) -> Result<HeadsetReading, String> {
+    let mut headset = headset.clone().write_owned().await;
+
+    tokio::task::spawn_blocking(move || {
+        // Check if the device is connected
+        if !headset.is_connected() {
+            return Err("Error: Device is not connected. Connect first.".to_string());
+        }
+
+        // Change to extraction mode before trying to get data
+        if headset.get_work_mode() != WorkMode::Extraction {
+            info!("Changing work mode to Extraction...");
+            headset.change_work_mode(WorkMode::Extraction);
+        }
+
+        headset.set_window_hop(hop_samples);
+
+        // Try to extract raw data from the device
+        headset
+            .extract_raw_data()
+            .map(|data| HeadsetReading {
+                data,
+                sequence_numbers: headset.last_sequence_numbers(),
+                board_timestamp: headset.last_sample_timestamp(),
+                battery_percent: headset.battery_percent(),
+            })
+            .map_err(|e| format!("Error extracting data from device: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Error extracting data from device: the read panicked: {}", e))?
+}
+
+/// This use case is responsible for processing the raw EEG data read from the EEG
+/// headset by `read_headset_window`. Partial windows are completed with the previous
+/// window, and windows with too many missing samples are discarded. The package numbers
+/// and the missing samples are reported for the integrity checks.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which assembles
+/// the windows.
+/// * `command`: The command with the window read from the headset.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing either the events generated from
+/// the extracted data or an error if the headset could not be read.
+#[command_handler(error = Error)]
+pub async fn extract_generalist_data_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    command: ExtractGeneralistDataCommand,
+) -> Result<Events, Error> {
+    info!("Starting raw data extraction from BrainBit device...");
+
+    // Only the new samples of the hop are awaited, the rest of the window overlaps the previous one
+    let hop_samples = _context.window_overlap.hop_samples();
+    _context.window_assembler.set_hop_samples(hop_samples);
+
+    let HeadsetReading {
+        mut data,
+        sequence_numbers,
+        board_timestamp,
+        ..
+    } = command.reading.map_err(|error_msg| {
+        error!("{}", error_msg);
+        Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str()))
+    })?;
+
+    // Correct the gain of each channel before the window is assembled and recorded
+    apply_channel_gains(&mut data, &_context.channel_gains);
+
+    // Process the extracted data
+    process_eeg_data(&data);
+
+    // Create event with the extracted data, assigning the next window id
+    let mut events = Events::new();
+    let window_id = _context.window_id + 1;
+
+    match _context.window_assembler.assemble(&data) {
+        Ok(headset_data) => {
+            let _ = events.add(ReceivedGeneralistDataEvent {
+                headset_data,
+                window_id,
+                board_timestamp,
+                sequence_numbers,
+                // Counted before the assembler interpolates them away
+                non_finite_samples: count_non_finite_samples(&data),
+            });
+        }
+        Err(reason) => {
+            warn!("Discarding window {}: {}", window_id, reason);
+            let _ = events.add(DiscardedWindowEvent {
+                window_id,
+                reason,
+                sequence_numbers,
+            });
+        }
+    }
+
+    // Send the event to the event queue
+    Ok(events)
+}
+
+// Helper function to scale each channel by its configured gain, leaving the rest untouched
+fn apply_channel_gains(data: &mut HashMap<String, Vec<f32>>, gains: &HashMap<String, f32>) {
+    for (channel, values) in data.iter_mut() {
+        if let Some(gain) = gains.get(channel) {
+            neural_analytics_signal_math::scale(values, *gain);
+        }
+    }
+}
+
+// Helper function to process the EEG data
+fn process_eeg_data(data: &HashMap<String, Vec<f32>>) {
+    // For now, we simply show basic information about the received data
+    info!("Processing EEG data:");
+    for (channel, values) in data {
+        info!("  Channel {}: {} samples received", channel, values.len());
+        if !values.is_empty() {
+            info!(
+                "    - First values: {:?}",
+                &values[..std::cmp::min(values.len(), 5)]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::domain::events::integrity_violation_event::IntegrityViolationEvent;
+    use crate::domain::models::{core_config::CoreConfig, integrity_report::IntegrityViolation};
+    use crate::domain::ports::input::eeg_headset::EegHeadsetPort;
+    use crate::domain::services::window_assembler_service::WINDOW_SAMPLES;
+    use mockall::mock;
+    use mockall::predicate::*;
+    use presage::CommandBus;
+    use tokio::sync::RwLock;
+    use tokio::test;
+    use presage::Configuration;
+    use presage::Event;
+
+    // Mock implementation of the EegHeadsetPort for testing
+    mock! {
+        EegHeadsetAdapter {}
+        impl EegHeadsetPort for EegHeadsetAdapter {
+            fn connect(&self) -> Result<(), String>;
+            fn disconnect(&mut self) -> Result<(), String>; // Corregido de &self a &mut self
+            fn is_connected(&self) -> bool;
+            fn get_work_mode(&self) -> WorkMode;
+            fn change_work_mode(&mut self, mode: WorkMode);
+            fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String>;
+            fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String>;
+            fn last_sequence_numbers(&self) -> Option<Vec<u32>>;
+        }
+    }
+
+    /// Función auxiliar para crear mocks estáticos para los tests
+    /// Esta función crea un mock y lo comparte en un Arc<RwLock>
+    /// que puede ser utilizada en el contexto del test.
+    fn create_static_mock<T>(
+        mock: T,
+    ) -> Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
+    where
+        T: EegHeadsetPort + Send + Sync + 'static,
+    {
+        // Crear un Box dinámico con el mock
+        let boxed_mock: Box<dyn EegHeadsetPort + Send + Sync> = Box::new(mock);
+
+        // Envolver en RwLock y Arc, compartido con el contexto
+        Arc::new(RwLock::new(boxed_mock))
+    }
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new().configure(
+            Configuration::new()
+                .command_handler(&extract_generalist_data_use_case)
+        )
+    }
+
+    /// Reads a window from the headset of the context and stores it, as the state machine does
+    async fn extract(command_bus: &CommandBus<NeuralAnalyticsContext, Error>, context: &mut NeuralAnalyticsContext) -> Result<(), Error> {
+        let reading = read_headset_window(&context.eeg_headset_adapter, context.window_overlap.hop_samples()).await;
+
+        command_bus.execute(context, ExtractGeneralistDataCommand { reading }).await
+    }
+
+    #[test]
+    async fn test_apply_channel_gains_scales_only_configured_channels() {
+        let mut data = HashMap::from([
+            ("O1".to_string(), vec![1.0, -2.0]),
+            ("O2".to_string(), vec![1.0, -2.0]),
+        ]);
+        let gains = HashMap::from([("O1".to_string(), 0.5)]);
+
+        apply_channel_gains(&mut data, &gains);
+
+        assert_eq!(data["O1"], vec![0.5, -1.0]);
+        assert_eq!(data["O2"], vec![1.0, -2.0]);
+    }
+
+    #[test]
+    async fn test_extract_generalist_data_disconnected() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(false); // Device is not connected
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = extract(&command_bus, &mut context).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Device is not connected"));
+    }
+
+    #[test]
+    async fn test_extract_generalist_data_already_in_extraction_mode() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(true); // Device is connected
+
+        mock.expect_get_work_mode()
+            .return_const(WorkMode::Extraction); // Already in extraction mode
+
+        // We don't expect change_work_mode to be called
+
+        let mut eeg_data = HashMap::new();
+        eeg_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+        eeg_data.insert("channel2".to_string(), vec![4.0, 5.0, 6.0]);
+
+        mock.expect_extract_raw_data()
+            .times(1)
+            .returning(move || Ok(eeg_data.clone()));
+
+        mock.expect_last_sequence_numbers().return_const(None);
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = extract(&command_bus, &mut context).await;
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    async fn test_extract_generalist_data_assigns_the_next_window_id() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(true);
+        mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+
+        let mut eeg_data = HashMap::new();
+        eeg_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+        eeg_data.insert("channel2".to_string(), vec![4.0, 5.0, 6.0]);
+
+        mock.expect_extract_raw_data()
+            .times(2)
+            .returning(move || Ok(eeg_data.clone()));
+
+        mock.expect_last_sequence_numbers().return_const(None);
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = extract(&command_bus, &mut context).await;
+        let second_result = extract(&command_bus, &mut context).await;
+
+        // Assert - Each extracted window gets the next id
+        assert!(result.is_ok());
+        assert!(second_result.is_ok());
+        assert_eq!(context.window_id, 2);
+    }
+
+    #[test]
+    async fn test_extract_generalist_data_completes_and_discards_partial_windows() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        let mut sequence = mockall::Sequence::new();
+        mock.expect_is_connected().return_const(true);
+        mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+
+        for samples in [WINDOW_SAMPLES, WINDOW_SAMPLES - 2, 3] {
+            mock.expect_extract_raw_data()
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(move || Ok([("T3".to_string(), vec![1.0; samples])].into_iter().collect()));
+        }
+
+        mock.expect_last_sequence_numbers().return_const(None);
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+        let command_bus = setup_command_bus();
+
+        // Act & Assert - A short window is completed with the previous one
+        extract(&command_bus, &mut context).await.unwrap();
+        extract(&command_bus, &mut context).await.unwrap();
+        assert_eq!(context.headset_data.as_ref().unwrap()["T3"].len(), WINDOW_SAMPLES);
+
+        // A window missing most of its samples is discarded
+        extract(&command_bus, &mut context).await.unwrap();
+        assert!(context.headset_data.is_none());
+        assert_eq!(context.window_id, 3);
+        assert_eq!(context.metrics.counter("windows.rejected"), 1);
+    }
+
+    #[test]
+    async fn test_strict_mode_aborts_on_a_sequence_gap_without_keeping_the_window() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        let mut sequence = mockall::Sequence::new();
+        mock.expect_is_connected().return_const(true);
+        mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+        mock.expect_extract_raw_data()
+            .returning(|| Ok([("T3".to_string(), vec![1.0; WINDOW_SAMPLES])].into_iter().collect()));
+
+        // Packages 124 to 129 are lost before the third window
+        for first in [0, WINDOW_SAMPLES as u32, 130] {
+            mock.expect_last_sequence_numbers()
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(move || Some((first..first + WINDOW_SAMPLES as u32).collect()));
+        }
+
+        let mut context = NeuralAnalyticsContext::new(CoreConfig {
+            strict_mode: true,
+            ..Default::default()
+        });
+        context.eeg_headset_adapter = create_static_mock(mock);
+        let command_bus = setup_command_bus();
+
+        // Act & Assert - Contiguous windows are kept
+        extract(&command_bus, &mut context).await.unwrap();
+        extract(&command_bus, &mut context).await.unwrap();
+        assert!(context.take_integrity_abort().is_none());
+        assert!(context.headset_data.is_some());
+
+        extract(&command_bus, &mut context).await.unwrap();
+        let report = context.take_integrity_abort().unwrap();
+
+        assert_eq!(report.violation, IntegrityViolation::SequenceGap { expected: 124, found: 130 });
+        assert_eq!(report.window_id, 3);
+        assert!(context.headset_data.is_none());
+        assert!(context
+            .take_notifications()
+            .iter()
+            .any(|notification| notification.name == IntegrityViolationEvent::NAME));
+    }
+
+    #[test]
+    async fn test_extract_generalist_data_change_mode() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(true); // Device is connected
+
+        mock.expect_get_work_mode()
+            .return_const(WorkMode::Calibration); // In calibration mode
+
+        mock.expect_change_work_mode()
+            .times(1)
+            .with(eq(WorkMode::Extraction))
+            .return_const(());
+
+        let mut eeg_data = HashMap::new();
+        eeg_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+
+        mock.expect_extract_raw_data()
+            .times(1)
+            .returning(move || Ok(eeg_data.clone()));
+
+        mock.expect_last_sequence_numbers().return_const(None);
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = extract(&command_bus, &mut context).await;
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    async fn test_extract_generalist_data_extraction_error() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(true); // Device is connected
+
+        mock.expect_get_work_mode()
+            .return_const(WorkMode::Extraction); // Already in extraction mode
+
+        mock.expect_extract_raw_data()
+            .times(1)
+            .returning(|| Err("Raw data extraction failed".to_string()));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = extract(&command_bus, &mut context).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Error extracting data from device"));
+    }
+}
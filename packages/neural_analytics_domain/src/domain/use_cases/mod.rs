@@ -0,0 +1,15 @@
+pub mod cleanup_session_use_case;
+pub mod create_diagnostic_bundle_use_case;
+pub mod disconnect_headband_use_case;
+pub mod end_session_use_case;
+pub mod extract_calibration_use_case;
+pub mod extract_extraction_use_case;
+pub mod notify_webhook_use_case;
+pub mod predict_color_thinking_use_case;
+pub mod publish_prediction_use_case;
+pub mod run_diagnostics_use_case;
+pub mod run_test_signal_use_case;
+pub mod search_headband_use_case;
+pub mod set_log_level_use_case;
+pub mod start_session_use_case;
+pub mod update_light_status_use_case;
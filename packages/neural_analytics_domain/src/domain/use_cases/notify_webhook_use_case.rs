@@ -0,0 +1,190 @@
+use crate::domain::{
+    commands::notify_webhook_command::NotifyWebhookCommand,
+    context::{adapter_registry::SharedAdapter, NeuralAnalyticsContext},
+    models::{event_internals::NotifiedWebhookEvent, webhook_notification::WebhookNotification},
+    ports::output::prediction_webhook::PredictionWebhookPort,
+};
+use log::{error, info};
+use presage::{command_handler, Error, Events};
+
+/// Whether the prediction mapped to a `Webhook` action is to be notified. Repeated
+/// predictions are skipped so the endpoint is only called on transitions instead of
+/// on every captured window.
+pub fn is_webhook_due(context: &NeuralAnalyticsContext, prediction: &str) -> bool {
+    context.last_webhook_prediction.as_deref() != Some(prediction)
+}
+
+/// Hands a prediction to the prediction webhook adapter, without the context.
+///
+/// The adapter delivers the notification in the background, and the context is locked
+/// afterwards to record it with `NotifyWebhookCommand`.
+///
+/// # Arguments
+/// * `webhook`: The prediction webhook adapter of the context.
+/// * `url`: The URL of the webhook.
+/// * `prediction`: The prediction to notify.
+///
+/// # Returns
+/// * `Result<(), String>`: Whether the adapter accepted the notification.
+pub async fn deliver_webhook(
+    webhook: &SharedAdapter<dyn PredictionWebhookPort + Send + Sync>,
+    url: &str,
+    prediction: &str,
+) -> Result<(), String> {
+    info!("Notifying webhook {} with prediction '{}'...", url, prediction);
+
+    let notification = WebhookNotification {
+        prediction: prediction.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+
+    webhook.read().await.notify(url, &notification).await
+}
+
+/// This use case is responsible for recording the notification of a prediction handed
+/// to the webhook by `deliver_webhook`, when `is_webhook_due`.
+///
+/// A notification the adapter rejects is reported in the event instead of failing
+/// the command, so it is not attempted again until the prediction changes.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which contains
+/// the last notified prediction.
+/// * `command`: The command with the webhook URL, the prediction and the outcome of its delivery.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing the events generated from the notification.
+#[command_handler(error = Error)]
+pub async fn notify_webhook_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    command: NotifyWebhookCommand,
+) -> Result<Events, Error> {
+    let error = command.delivery.err().map(|e| {
+        let error_msg = format!("Error notifying webhook {}: {}", command.url, e);
+        error!("{}", error_msg);
+        error_msg
+    });
+
+    let mut events = Events::new();
+    let _ = events.add(NotifiedWebhookEvent {
+        prediction: command.prediction,
+        error,
+    });
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use presage::{CommandBus, Configuration};
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::RwLock;
+
+    type Webhook = dyn PredictionWebhookPort + Send + Sync;
+
+    /// Webhook recording the notifications it is given, rejecting them when `is_failing`
+    #[derive(Clone, Default)]
+    struct RecordingWebhook {
+        notified: Arc<Mutex<Vec<(String, String)>>>,
+        is_failing: bool,
+    }
+
+    #[async_trait]
+    impl PredictionWebhookPort for RecordingWebhook {
+        async fn notify(&self, url: &str, notification: &WebhookNotification) -> Result<(), String> {
+            self.notified.lock().unwrap().push((url.to_string(), notification.prediction.clone()));
+
+            if self.is_failing {
+                Err("queue full".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&notify_webhook_use_case))
+    }
+
+    fn context_with(webhook: &RecordingWebhook) -> NeuralAnalyticsContext {
+        NeuralAnalyticsContext {
+            prediction_webhook: Arc::new(RwLock::new(Box::new(webhook.clone()) as Box<Webhook>)),
+            ..Default::default()
+        }
+    }
+
+    /// Notifies the prediction like the state machine does, delivered only when due
+    async fn notify(context: &mut NeuralAnalyticsContext, prediction: &str) -> Result<(), Error> {
+        if !is_webhook_due(context, prediction) {
+            return Ok(());
+        }
+
+        let url = "http://localhost/webhook".to_string();
+        let delivery = deliver_webhook(&context.prediction_webhook, &url, prediction).await;
+        let command = NotifyWebhookCommand {
+            url,
+            prediction: prediction.to_string(),
+            delivery,
+        };
+
+        setup_command_bus().execute(context, command).await
+    }
+
+    #[tokio::test]
+    async fn test_notify_webhook_skips_repeated_prediction() {
+        // Arrange
+        let webhook = RecordingWebhook::default();
+        let mut context = context_with(&webhook);
+        context.last_webhook_prediction = Some("green".to_string());
+
+        // Act
+        let result = notify(&mut context, "green").await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(!is_webhook_due(&context, "green"));
+        assert!(webhook.notified.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_notify_webhook_hands_the_prediction_to_the_adapter() {
+        // Arrange
+        let webhook = RecordingWebhook::default();
+        let mut context = context_with(&webhook);
+
+        // Act
+        let result = notify(&mut context, "red").await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(
+            *webhook.notified.lock().unwrap(),
+            vec![("http://localhost/webhook".to_string(), "red".to_string())]
+        );
+        assert_eq!(context.last_webhook_prediction.as_deref(), Some("red"));
+    }
+
+    #[tokio::test]
+    async fn test_notify_webhook_rejected_is_not_attempted_again_on_the_next_window() {
+        // Arrange
+        let webhook = RecordingWebhook {
+            is_failing: true,
+            ..Default::default()
+        };
+        let mut context = context_with(&webhook);
+
+        // Act
+        let result = notify(&mut context, "red").await;
+        let repeated = notify(&mut context, "red").await;
+
+        // Assert - The failure is counted instead of failing the command
+        assert!(result.is_ok());
+        assert!(repeated.is_ok());
+        assert_eq!(webhook.notified.lock().unwrap().len(), 1);
+        assert_eq!(context.metrics.counter("webhooks.failed"), 1);
+    }
+}
@@ -0,0 +1,342 @@
+use crate::domain::{
+    commands::predict_color_thinking_command::PredictColorThinkingCommand,
+    context::NeuralAnalyticsContext,
+    models::{
+        event_internals::{ExplainedPredictionEvent, ReceivedPredictColorThinkingDataEvent},
+        window_prediction::WindowPrediction,
+    },
+    services::{explanation_service::channel_contributions, inference_cache_service::window_hash},
+};
+use log::{error, info, warn};
+use presage::{command_handler, Error, Events};
+use std::time::Instant;
+
+/// Este caso de uso es responsable de predecir el color en el que está pensando el usuario
+/// basado en los datos del EEG. Verifica si el auricular EEG está conectado y si los datos
+/// están disponibles. Si los datos están disponibles, utiliza el servicio de modelo para predecir
+/// el color y devuelve el resultado como un evento.
+///
+/// # Argumentos
+/// * `_context`: Una referencia mutable al `NeuralAnalyticsContext` que contiene
+/// el adaptador del auricular EEG y el servicio de modelo.
+/// * `_command`: El comando para predecir el color en el que está pensando el usuario.
+///
+/// # Retorna
+/// * `Result<Events, Error>`: Un resultado que contiene los eventos generados a partir de
+/// la predicción o un error si algo sale mal.
+#[command_handler(error = Error)]
+pub async fn predict_color_thinking_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    _command: PredictColorThinkingCommand,
+) -> Result<Events, Error> {
+    info!("Starting color prediction for what the user is thinking...");
+
+    // Verificar si los datos del EEG están disponibles
+    // La ventana se copia para no retener el contexto mientras se accede al modelo
+    let headset_data = match _context.headset_data.clone() {
+        Some(data) => data,
+        None => {
+            let error_msg = "No EEG data available for prediction";
+            error!("{}", error_msg);
+            return Err(Error::MissingCommandHandler(error_msg).into());
+        }
+    };
+
+    let band_powers = _context
+        .feed_band_powers_to_model
+        .then(|| _context.band_powers.clone())
+        .flatten();
+
+    // Una ventana idéntica a la anterior, con las mismas potencias de banda, reutiliza su predicción sin ejecutar el modelo
+    let window_hash = window_hash(&headset_data, band_powers.as_ref());
+    let cached_result = _context.inference_cache.lookup(window_hash);
+    let is_cached = cached_result.is_some();
+    let mut explanation = None;
+    let mut inference_ms = None;
+    let window_prediction;
+
+    let color_result = match cached_result {
+        Some(cached_prediction) => {
+            info!("EEG window unchanged, reusing the cached prediction...");
+            let color_result = cached_prediction.label.clone();
+            window_prediction = Some(cached_prediction);
+            color_result
+        }
+        None => {
+            let explain_predictions = _context.explain_predictions;
+
+            // Usar el servicio de inferencia para predecir el color
+            info!("Processing EEG data for prediction...");
+            let started = Instant::now();
+            let (prediction, contributions) = _context
+                .with_model(|model_service| {
+                    let prediction = match &band_powers {
+                        Some(band_powers) => {
+                            let color = model_service.predict_color_with_band_powers(&headset_data, band_powers)?;
+                            WindowPrediction::from_label(color, started.elapsed().as_secs_f64() * 1000.0)
+                        }
+                        None => model_service.predict_color_detailed(&headset_data)?,
+                    };
+
+                    // Solo se explican las predicciones nuevas, una ventana repetida tendría la misma explicación
+                    let contributions = explain_predictions
+                        .then(|| channel_contributions(model_service, &headset_data, &prediction.label));
+
+                    Ok((prediction, contributions))
+                })
+                .await
+                .and_then(|prediction| prediction)
+                .map_err(|e: String| {
+                    let error_msg = format!("Error predicting color: {}", e);
+                    error!("{}", error_msg);
+                    Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str()))
+                })?;
+
+            match contributions {
+                Some(Ok(contributions)) => explanation = Some(contributions),
+                Some(Err(e)) => warn!("Could not explain the prediction: {}", e),
+                None => {}
+            }
+
+            inference_ms = Some(started.elapsed().as_secs_f64() * 1000.0);
+            let color_result = prediction.label.clone();
+            window_prediction = Some(prediction);
+            color_result
+        }
+    };
+
+    // Guardar el resultado en el contexto
+    info!(
+        "Successful prediction: the user is thinking of the color '{}'",
+        color_result
+    );
+
+    // Crear y devolver eventos
+    let mut events = Events::new();
+    if let Some(channel_contributions) = explanation {
+        let _ = events.add(ExplainedPredictionEvent {
+            window_id: _context.window_id,
+            predicted_class: color_result.clone(),
+            channel_contributions,
+        });
+    }
+
+    let _ = events.add(ReceivedPredictColorThinkingDataEvent {
+        color_thinking: color_result,
+        window_id: _context.window_id,
+        window_hash,
+        is_cached,
+        inference_ms,
+        prediction: window_prediction,
+    });
+
+    // Enviar el evento a la cola de eventos
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::model_inference_service::ModelInferenceInterface as ModelServicePort;
+    use mockall::mock;
+    use mockall::predicate::*;
+    use presage::{CommandBus, Configuration};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    // Implementación mock de ModelServicePort para las pruebas
+    mock! {
+        ModelService {}
+        impl ModelServicePort for ModelService {
+            fn predict_color(&self, headset_data: &HashMap<String, Vec<f32>>) -> Result<String, String>;
+            fn class_probabilities(&self, headset_data: &HashMap<String, Vec<f32>>) -> Result<HashMap<String, f32>, String>;
+            fn is_model_loaded(&self) -> bool;
+        }
+    }
+
+    /// Función auxiliar para crear mocks estáticos para los tests
+    /// Esta función crea un mock y lo comparte en un Arc<RwLock>
+    /// que puede ser utilizada en el contexto del test.
+    fn create_static_mock<T>(
+        mock: T,
+    ) -> Arc<RwLock<Box<dyn ModelServicePort + Send + Sync>>>
+    where
+        T: ModelServicePort + Send + Sync + 'static,
+    {
+        // Crear un Box dinámico con el mock
+        let boxed_mock: Box<dyn ModelServicePort + Send + Sync> = Box::new(mock);
+
+        // Envolver en RwLock y Arc, compartido con el contexto
+        Arc::new(RwLock::new(boxed_mock))
+    }
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    /// Ahora se requiere que el handler tenga lifetime 'static.
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new().configure(
+            Configuration::new()
+                .command_handler(&predict_color_thinking_use_case)
+            )
+    }
+
+    #[tokio::test]
+    async fn test_predict_color_thinking_no_data() {
+        // Arrange
+        let mut context = NeuralAnalyticsContext::default();
+        context.headset_data = None; // No hay datos disponibles
+
+        // Asegurarnos de que model_service no es nulo
+        let mock = MockModelService::new();
+        context.model_service = create_static_mock(mock);
+
+        let command = PredictColorThinkingCommand {};
+        let command_bus = setup_command_bus();
+
+        // Act
+        let _ = command_bus.execute(&mut context, command).await;
+        assert!(context.color_thinking.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_predict_color_thinking_successful() {
+        // Arrange
+        let mut mock = MockModelService::new();
+
+        let mut headset_data = HashMap::new();
+        headset_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+
+        mock.expect_predict_color()
+            .times(1)
+            .withf(move |data: &HashMap<String, Vec<f32>>| data.contains_key("channel1"))
+            .returning(|_| Ok("green".to_string()));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.headset_data = Some(headset_data);
+        context.model_service = create_static_mock(mock);
+
+        let command = PredictColorThinkingCommand {};
+        let command_bus = setup_command_bus();
+
+        let _ = command_bus.execute(&mut context, command).await;
+
+        assert!(!context.color_thinking.is_empty());
+        assert_eq!(context.get_color_thinking(), "green".to_string());
+    }
+
+    #[tokio::test]
+    async fn test_predict_color_thinking_carries_the_window_id() {
+        // Arrange
+        let mut mock = MockModelService::new();
+
+        let mut headset_data = HashMap::new();
+        headset_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+
+        mock.expect_predict_color()
+            .times(1)
+            .returning(|_| Ok("green".to_string()));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.headset_data = Some(headset_data);
+        context.window_id = 7;
+        context.model_service = create_static_mock(mock);
+
+        let command_bus = setup_command_bus();
+
+        // Act
+        let _ = command_bus.execute(&mut context, PredictColorThinkingCommand {}).await;
+
+        // Assert - The prediction is tied to the window it was made from
+        assert_eq!(context.predicted_window_id, Some(7));
+    }
+
+    #[tokio::test]
+    async fn test_predict_color_thinking_reuses_cached_prediction() {
+        // Arrange - The model must run only once for the same window
+        let mut mock = MockModelService::new();
+
+        let mut headset_data = HashMap::new();
+        headset_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+
+        mock.expect_predict_color()
+            .times(1)
+            .returning(|_| Ok("red".to_string()));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.headset_data = Some(headset_data);
+        context.model_service = create_static_mock(mock);
+
+        let command_bus = setup_command_bus();
+
+        // Act
+        let _ = command_bus.execute(&mut context, PredictColorThinkingCommand {}).await;
+        let _ = command_bus.execute(&mut context, PredictColorThinkingCommand {}).await;
+
+        // Assert
+        assert_eq!(context.get_color_thinking(), "red".to_string());
+        assert_eq!(context.metrics.counter("inference.computed"), 1);
+        assert_eq!(context.metrics.counter("inference.cached"), 1);
+        assert_eq!(context.window_prediction.map(|prediction| prediction.label), Some("red".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_predict_color_thinking_explains_prediction() {
+        // Arrange - The model keys on channel2 only
+        let mut mock = MockModelService::new();
+
+        let mut headset_data = HashMap::new();
+        headset_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+        headset_data.insert("channel2".to_string(), vec![1.0, 2.0, 3.0]);
+
+        mock.expect_predict_color()
+            .times(1)
+            .returning(|_| Ok("green".to_string()));
+        mock.expect_class_probabilities()
+            .times(3)
+            .returning(|data: &HashMap<String, Vec<f32>>| {
+                let green = if data["channel2"].iter().all(|&value| value == 0.0) { 0.2 } else { 0.8 };
+                Ok([("green".to_string(), green)].into_iter().collect())
+            });
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.headset_data = Some(headset_data);
+        context.window_id = 3;
+        context.explain_predictions = true;
+        context.model_service = create_static_mock(mock);
+
+        // Act
+        let _ = setup_command_bus().execute(&mut context, PredictColorThinkingCommand {}).await;
+
+        // Assert
+        let explanation = context.last_explanation.expect("The prediction must be explained");
+        assert_eq!(explanation.window_id, 3);
+        assert_eq!(explanation.predicted_class, "green");
+        assert_eq!(explanation.channel_contributions["channel1"], 0.0);
+        assert!((explanation.channel_contributions["channel2"] - 0.6).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_predict_color_thinking_prediction_error() {
+        // Arrange
+        let mut mock = MockModelService::new();
+
+        let mut headset_data = HashMap::new();
+        headset_data.insert("channel1".to_string(), vec![1.0, 2.0, 3.0]);
+
+        mock.expect_predict_color()
+            .times(1)
+            .returning(|_| Err("Prediction failed".to_string()));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.headset_data = Some(headset_data);
+        context.model_service = create_static_mock(mock);
+
+        let command = PredictColorThinkingCommand {};
+        let command_bus = setup_command_bus();
+
+        // Act
+        let _ = command_bus.execute(&mut context, command).await;
+    
+        assert!(context.color_thinking.is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::domain::{
+    commands::publish_prediction_command::PublishPredictionCommand,
+    context::{adapter_registry::SharedAdapter, NeuralAnalyticsContext},
+    models::{event_internals::PublishedPredictionEvent, published_prediction::PublishedPrediction},
+    ports::output::prediction_sink::PredictionSinkPort,
+};
+use log::error;
+use presage::{command_handler, Error, Events};
+
+/// Whether the prediction is to be published. Repeated predictions are skipped so
+/// the subscribers are only told of the transitions instead of every captured window.
+pub fn is_publication_due(context: &NeuralAnalyticsContext, prediction: &str) -> bool {
+    context.last_published_prediction.as_deref() != Some(prediction)
+}
+
+/// Hands a prediction to the prediction sink, such as an MQTT topic, without the context.
+///
+/// The context is locked afterwards to record it with `PublishPredictionCommand`.
+///
+/// # Arguments
+/// * `sink`: The prediction sink of the context.
+/// * `prediction`: The prediction to publish.
+///
+/// # Returns
+/// * `Result<(), String>`: Whether the sink queued the prediction.
+pub async fn publish_to_sink(
+    sink: &SharedAdapter<dyn PredictionSinkPort + Send + Sync>,
+    prediction: &PublishedPrediction,
+) -> Result<(), String> {
+    sink.read().await.publish(prediction).await
+}
+
+/// This use case is responsible for recording the predictions published to the
+/// prediction sink by `publish_to_sink`, alongside the smart bulb, when `is_publication_due`.
+///
+/// A prediction the sink could not queue is logged and not recorded, so it is
+/// published again on the next window.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which contains
+/// the last published prediction.
+/// * `command`: The command with the prediction and the outcome of its publication.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing the events generated from the publication.
+#[command_handler(error = Error)]
+pub async fn publish_prediction_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    command: PublishPredictionCommand,
+) -> Result<Events, Error> {
+    let prediction = command.prediction;
+    let mut events = Events::new();
+
+    match command.publication {
+        Ok(()) => {
+            let _ = events.add(PublishedPredictionEvent {
+                prediction: prediction.prediction,
+            });
+        }
+        Err(e) => error!("Error publishing the prediction '{}': {}", prediction.prediction, e),
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use presage::{CommandBus, Configuration};
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&publish_prediction_use_case))
+    }
+
+    /// Publishes the prediction like the state machine does, only when due
+    async fn publish(context: &mut NeuralAnalyticsContext, prediction: &str) -> Result<(), Error> {
+        if !is_publication_due(context, prediction) {
+            return Ok(());
+        }
+
+        let prediction = PublishedPrediction {
+            prediction: prediction.to_string(),
+            window_id: Some(1),
+            timestamp: 0,
+        };
+        let publication = publish_to_sink(&context.prediction_sink, &prediction).await;
+
+        setup_command_bus()
+            .execute(context, PublishPredictionCommand { prediction, publication })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_publish_prediction_remembers_the_last_one() {
+        // Arrange - Without a registered sink the predictions are dropped
+        let mut context = NeuralAnalyticsContext::default();
+
+        // Act
+        let result = publish(&mut context, "red").await;
+        let repeated = publish(&mut context, "red").await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(repeated.is_ok());
+        assert_eq!(context.last_published_prediction.as_deref(), Some("red"));
+        assert!(!is_publication_due(&context, "red"));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::domain::{
+    commands::run_diagnostics_command::RunDiagnosticsCommand, context::NeuralAnalyticsContext,
+    models::event_internals::CollectedDiagnosticsEvent,
+};
+use log::{info, warn};
+use presage::{command_handler, Error, Events};
+
+/// This use case is responsible for collecting the problems detected while loading
+/// the core components, so they can be reported to the user before the capture starts.
+/// It never fails: every problem found is returned as a diagnostics issue.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which contains
+/// the model service to inspect.
+/// * `_command`: The command to run the diagnostics.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing the event with the collected issues.
+#[command_handler(error = Error)]
+pub async fn run_diagnostics_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    _command: RunDiagnosticsCommand,
+) -> Result<Events, Error> {
+    info!("Running core diagnostics...");
+
+    let mut issues = Vec::new();
+
+    let model_status = _context
+        .with_model(|model_service| (model_service.is_model_loaded(), model_service.load_error()))
+        .await;
+
+    match model_status {
+        Ok((false, load_error)) => {
+            let reason = load_error.unwrap_or_else(|| "unknown reason".to_string());
+            issues.push(format!("Model is not loaded: {}", reason));
+        }
+        Ok(_) => {}
+        Err(e) => issues.push(e),
+    }
+
+    for issue in &issues {
+        warn!("Diagnostics issue: {}", issue);
+    }
+
+    let mut events = Events::new();
+    let _ = events.add(CollectedDiagnosticsEvent { issues });
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use presage::{CommandBus, Configuration};
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&run_diagnostics_use_case))
+    }
+
+    #[tokio::test]
+    async fn test_run_diagnostics_reports_model_status() {
+        // Arrange
+        let mut context = NeuralAnalyticsContext::default();
+        let command_bus = setup_command_bus();
+        let is_model_loaded = context.model_service.read().await.is_model_loaded();
+
+        // Act
+        let result = command_bus.execute(&mut context, RunDiagnosticsCommand).await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(
+            context.diagnostics_issues.iter().any(|issue| issue.starts_with("Model is not loaded")),
+            !is_model_loaded
+        );
+    }
+}
\ No newline at end of file
@@ -0,0 +1,187 @@
+use crate::domain::{
+    commands::run_test_signal_command::RunTestSignalCommand,
+    context::NeuralAnalyticsContext,
+    models::{eeg_work_modes::WorkMode, event_internals::VerifiedTestSignalEvent},
+    services::{test_signal_service::verify_square_wave, window_assembler_service::WINDOW_SAMPLES},
+};
+use log::{error, info, warn};
+use presage::{command_handler, Error, Events};
+
+/// This use case is responsible for verifying the whole pipeline against the test
+/// signal of the board, so an installation can be checked without wearing the headset.
+/// It switches the headset to the test signal mode, extracts a window, checks that
+/// every channel follows the known square wave and runs the model on it, which must
+/// reject or classify the window without failing. Every problem found is returned
+/// as an issue; the capture switches the headset back to its own mode afterwards.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which contains
+/// the EEG headset adapter and the model service.
+/// * `_command`: The command to run the test signal verification.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing the event with the window and the
+/// issues found, or an error if the headset is not connected.
+#[command_handler(error = Error)]
+pub async fn run_test_signal_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    _command: RunTestSignalCommand,
+) -> Result<Events, Error> {
+    info!("Verifying the pipeline with the test signal...");
+
+    let mut issues = Vec::new();
+
+    let headset_data = _context
+        .with_headset_mut(|headset| {
+            if !headset.is_connected() {
+                let error_msg = "Error: Device is not connected. Connect first.";
+                error!("{}", error_msg);
+                return Err(Error::MissingCommandHandler(error_msg));
+            }
+
+            if headset.get_work_mode() != WorkMode::TestSignal {
+                headset.change_work_mode(WorkMode::TestSignal);
+            }
+
+            if headset.get_work_mode() != WorkMode::TestSignal {
+                issues.push("The headset does not support the test signal mode".to_string());
+                Ok(Default::default())
+            } else {
+                // The square wave is verified on a whole window, even when the capture overlaps them
+                headset.set_window_hop(WINDOW_SAMPLES);
+                Ok(headset.extract_raw_data().unwrap_or_else(|e| {
+                    issues.push(format!("Failed to extract the test signal: {}", e));
+                    Default::default()
+                }))
+            }
+        })
+        .await
+        .map_err(|e| Error::MissingCommandHandler(Box::leak(e.into_boxed_str())))??;
+
+    if issues.is_empty() && headset_data.is_empty() {
+        issues.push("No samples received from the test signal".to_string());
+    }
+
+    let mut channels: Vec<_> = headset_data.iter().collect();
+    channels.sort_unstable_by_key(|(channel, _)| *channel);
+
+    for (channel, samples) in channels {
+        if let Err(e) = verify_square_wave(samples) {
+            issues.push(format!("Channel '{}' does not follow the test signal: {}", channel, e));
+        }
+    }
+
+    // The model was not trained on square waves, any answer is fine as long as it does not fail
+    if !headset_data.is_empty() {
+        match _context.with_model(|model| model.predict_color(&headset_data)).await {
+            Ok(Ok(color)) => info!("The model classified the test signal as '{}'", color),
+            Ok(Err(e)) => info!("The model rejected the test signal: {}", e),
+            Err(e) => issues.push(e),
+        }
+    }
+
+    for issue in &issues {
+        warn!("Test signal issue: {}", issue);
+    }
+
+    let mut events = Events::new();
+    let _ = events.add(VerifiedTestSignalEvent { headset_data, issues });
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::domain::ports::input::eeg_headset::EegHeadsetPort;
+    use crate::domain::services::test_signal_service::square_wave;
+    use mockall::mock;
+    use mockall::predicate::*;
+    use presage::{CommandBus, Configuration};
+    use tokio::sync::RwLock;
+
+    mock! {
+        EegHeadsetAdapter {}
+        impl EegHeadsetPort for EegHeadsetAdapter {
+            fn connect(&self) -> Result<(), String>;
+            fn disconnect(&mut self) -> Result<(), String>;
+            fn is_connected(&self) -> bool;
+            fn get_work_mode(&self) -> WorkMode;
+            fn change_work_mode(&mut self, mode: WorkMode);
+            fn extract_impedance_data(&self) -> Result<HashMap<String, u16>, String>;
+            fn extract_raw_data(&self) -> Result<HashMap<String, Vec<f32>>, String>;
+        }
+    }
+
+    /// Función auxiliar para crear mocks estáticos para los tests
+    fn create_static_mock<T>(mock: T) -> Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
+    where
+        T: EegHeadsetPort + Send + Sync + 'static,
+    {
+        let boxed_mock: Box<dyn EegHeadsetPort + Send + Sync> = Box::new(mock);
+        Arc::new(RwLock::new(boxed_mock))
+    }
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&run_test_signal_use_case))
+    }
+
+    #[tokio::test]
+    async fn test_run_test_signal_reports_channels_off_the_waveform() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(true);
+        mock.expect_get_work_mode().return_const(WorkMode::TestSignal);
+        mock.expect_change_work_mode().never();
+        mock.expect_extract_raw_data().times(1).returning(|| {
+            Ok([
+                ("T3".to_string(), square_wave(62)),
+                ("T4".to_string(), vec![0.5; 62]),
+            ]
+            .into_iter()
+            .collect())
+        });
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        // Act
+        let result = setup_command_bus().execute(&mut context, RunTestSignalCommand).await;
+
+        // Assert
+        assert!(result.is_ok());
+
+        let report = context.last_test_signal.as_ref().unwrap();
+        assert_eq!(report.headset_data.len(), 2);
+        assert_eq!(report.issues.len(), 1);
+        assert!(report.issues[0].contains("'T4'"));
+    }
+
+    #[tokio::test]
+    async fn test_run_test_signal_reports_unsupported_headsets() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(true);
+        mock.expect_get_work_mode().return_const(WorkMode::Extraction);
+        mock.expect_change_work_mode().with(eq(WorkMode::TestSignal)).times(1).return_const(());
+        mock.expect_extract_raw_data().never();
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        // Act
+        let result = setup_command_bus().execute(&mut context, RunTestSignalCommand).await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(
+            context.last_test_signal.as_ref().unwrap().issues,
+            vec!["The headset does not support the test signal mode".to_string()]
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use std::time::{Duration, Instant};
+
+use crate::domain::{
+    commands::search_headband_command::SearchHeadbandCommand,
+    context::NeuralAnalyticsContext,
+    models::{
+        event_internals::ConnectedHeadsetEvent,
+        operation_progress::{OperationKind, OperationProgress, OperationStatus},
+    },
+};
+use log::{debug, error, info, warn};
+use presage::{command_handler, Error, Events};
+
+// Longest wait of BrainFlow for the device, used to estimate the progress of the connection
+pub(crate) const CONNECTION_TIMEOUT: Duration = Duration::from_secs(20);
+
+// Interval between the progress reports, which is also how fast a cancellation is noticed
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// This use case is responsible for searching and connecting to the EEG headset (BrainBit device).
+/// It checks if the device is already connected and attempts to connect it, reusing
+/// the delays measured on a previous connection of the same device, if any.
+/// If successful, it returns a `ConnectedHeadsetEvent` with the identifier of the device
+/// and the delays it measured.
+///
+/// The connection blocks up to the BrainFlow timeout, so it runs in a blocking thread
+/// while its progress is reported, and it can be cancelled through the cancellation
+/// token of the context. A cancelled connection that succeeds later is disconnected.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which contains
+/// the EEG headset adapter.
+/// * `_command`: The command to search and connect the headband.
+///
+/// # Returns
+/// * `Result<Events, Error>`: A result containing either the events generated from
+/// the connection or an error if something goes wrong.
+#[command_handler(error = Error)]
+pub async fn search_headband_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    _command: SearchHeadbandCommand,
+) -> Result<Events, Error> {
+    info!("Starting search and connection of BrainBit device...");
+
+    // Check if already connected
+    let (is_connected, device_id) = _context
+        .with_headset(|headset| (headset.is_connected(), headset.device_id()))
+        .await
+        .map_err(|e| Error::MissingCommandHandler(Box::leak(e.into_boxed_str())))?;
+
+    if is_connected {
+        debug!("The device is already connected.");
+        return Ok(Events::new());
+    }
+
+    // Skip measuring the delays of a device that already connected before
+    let known_timings = device_id.and_then(|device_id| _context.calibration_profiles.device_timings(&device_id));
+
+    if let Some(timings) = known_timings {
+        debug!("Reusing the stored delays of the device: {:?}", timings);
+        _context
+            .with_headset(|headset| headset.apply_device_timings(timings))
+            .await
+            .map_err(|e| Error::MissingCommandHandler(Box::leak(e.into_boxed_str())))?;
+    }
+
+    // Try to connect to the device
+    match connect_cancellable(_context).await {
+        Ok(_) => {
+            debug!("Connection established successfully.");
+            _context.events.send_progress(OperationProgress::ended(OperationKind::HeadsetConnection, OperationStatus::Finished));
+        }
+        Err(e) if _context.cancellation.is_cancelled() => {
+            info!("{}", e);
+            _context.events.send_progress(OperationProgress::ended(OperationKind::HeadsetConnection, OperationStatus::Cancelled));
+            return Err(Error::MissingCommandHandler("Connection cancelled"));
+        }
+        Err(e) => {
+            let error_msg = format!("Error connecting to the device: {}", e);
+            error!("{}", error_msg);
+            _context.events.send_progress(OperationProgress::ended(OperationKind::HeadsetConnection, OperationStatus::Failed));
+            return Err(Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str())).into());
+        }
+    }
+
+    let connected = _context
+        .with_headset(|headset| {
+            headset.is_connected().then(|| ConnectedHeadsetEvent {
+                device_id: headset.device_id(),
+                firmware_version: headset.firmware_version(),
+                device_timings: headset.device_timings(),
+            })
+        })
+        .await
+        .map_err(|e| Error::MissingCommandHandler(Box::leak(e.into_boxed_str())))?;
+
+    if let Some(connected_event) = connected {
+        debug!("The device is now connected.");
+
+        // The context starts a new session if a different headset connected
+        let mut events = Events::new();
+        let _ = events.add(connected_event);
+
+        Ok(events)
+    } else {
+        let error_msg = "Error: Device is not connected or is not sending data. Connect first.";
+        error!("{}", error_msg);
+        return Err(Error::MissingCommandHandler(error_msg).into());
+    }
+}
+
+/// Connects the headset in a blocking thread, reporting the elapsed fraction of the
+/// BrainFlow timeout until it returns or the connection is cancelled.
+async fn connect_cancellable(context: &NeuralAnalyticsContext) -> Result<(), String> {
+    let adapter = context.eeg_headset_adapter.clone();
+    let cancellation = context.cancellation.clone();
+    let events = context.events.clone();
+    cancellation.reset();
+
+    let started = Instant::now();
+    let mut connection = tokio::task::spawn_blocking(move || adapter.blocking_read().connect());
+    let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+
+    loop {
+        tokio::select! {
+            result = &mut connection => {
+                return result.map_err(|e| format!("The connection panicked: {}", e))?;
+            }
+            _ = progress.tick() => {
+                if cancellation.is_cancelled() {
+                    break;
+                }
+
+                let fraction = started.elapsed().as_secs_f32() / CONNECTION_TIMEOUT.as_secs_f32();
+                events.send_progress(OperationProgress::running(OperationKind::HeadsetConnection, Some(fraction), true));
+            }
+        }
+    }
+
+    // BrainFlow cannot be interrupted, so a late connection is closed once it returns
+    tokio::spawn(async move {
+        if let Ok(Ok(())) = connection.await {
+            if let Err(e) = adapter.write().await.disconnect() {
+                warn!("Failed to close the cancelled connection: {}", e);
+            }
+        }
+    });
+
+    Err("The connection was cancelled".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::domain::models::device_timings::DeviceTimings;
+    use crate::domain::ports::input::eeg_headset::EegHeadsetPort;
+    use mockall::mock;
+    use mockall::predicate::*;
+    use presage::CommandBus;
+    use presage::Configuration;
+    use tokio::sync::RwLock;
+    use tokio::test;
+
+    // Mock implementation of the EegHeadsetPort for testing
+    mock! {
+        EegHeadsetAdapter {}
+        impl EegHeadsetPort for EegHeadsetAdapter {
+            fn connect(&self) -> Result<(), String>;
+            fn disconnect(&mut self) -> Result<(), String>;
+            fn is_connected(&self) -> bool;
+            fn get_work_mode(&self) -> crate::domain::models::eeg_work_modes::WorkMode;
+            fn change_work_mode(&mut self, mode: crate::domain::models::eeg_work_modes::WorkMode);
+            fn extract_impedance_data(&self) -> Result<std::collections::HashMap<String, u16>, String>;
+            fn extract_raw_data(&self) -> Result<std::collections::HashMap<String, Vec<f32>>, String>;
+        }
+    }
+
+    /// Función auxiliar para crear mocks estáticos para los tests
+    /// Esta función crea un mock y lo comparte en un Arc<RwLock>
+    /// que puede ser utilizada en el contexto del test.
+    fn create_static_mock<T>(mock: T) -> Arc<RwLock<Box<dyn EegHeadsetPort + Send + Sync>>>
+    where
+        T: EegHeadsetPort + Send + Sync + 'static,
+    {
+        // Crear un Box dinámico con el mock
+        let boxed_mock: Box<dyn EegHeadsetPort + Send + Sync> = Box::new(mock);
+
+        // Envolver en RwLock y Arc, compartido con el contexto
+        Arc::new(RwLock::new(boxed_mock))
+    }
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&search_headband_use_case))
+    }
+
+    #[test]
+    async fn test_search_already_connected() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+        mock.expect_is_connected().return_const(true); // Device is already connected
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command = SearchHeadbandCommand;
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = command_bus.execute(&mut context, command).await;
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    async fn test_search_connect_successful() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+
+        // Setup sequence of is_connected calls
+        mock.expect_is_connected().times(1).returning(|| false); // Initially not connected
+
+        mock.expect_connect().times(1).returning(|| Ok(()));
+
+        mock.expect_is_connected().times(1).returning(|| true); // Connected after connect()
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command = SearchHeadbandCommand;
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = command_bus.execute(&mut context, command).await;
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    async fn test_search_connect_error() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+
+        mock.expect_is_connected().return_const(false); // Device is not connected
+
+        mock.expect_connect()
+            .times(1)
+            .returning(|| Err("Failed to connect to device".to_string()));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command = SearchHeadbandCommand;
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = command_bus.execute(&mut context, command).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Error connecting to the device"));
+    }
+
+    #[test]
+    async fn test_search_connect_not_connected_after_attempt() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+
+        mock.expect_is_connected().times(2).returning(|| false); // Never connected, even after connect()
+
+        mock.expect_connect().times(1).returning(|| Ok(())); // Connect succeeds but device doesn't actually connect
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let command = SearchHeadbandCommand;
+        let command_bus = setup_command_bus();
+
+        // Act
+        let result = command_bus.execute(&mut context, command).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Device is not connected or is not sending data"));
+    }
+
+    #[test]
+    async fn test_search_connect_cancelled() {
+        // Arrange
+        let mut mock = MockEegHeadsetAdapter::new();
+
+        mock.expect_is_connected().return_const(false);
+
+        // BrainFlow blocks until the device answers or its timeout expires
+        mock.expect_connect().times(1).returning(|| {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(())
+        });
+        mock.expect_disconnect().returning(|| Ok(()));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        let cancellation = context.cancellation.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            cancellation.cancel();
+        });
+
+        // Act
+        let started = Instant::now();
+        let result = setup_command_bus().execute(&mut context, SearchHeadbandCommand).await;
+
+        // Assert
+        assert!(result.unwrap_err().to_string().contains("Connection cancelled"));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    // Mock of a headset that measures its delays when it connects
+    mock! {
+        TimedEegHeadsetAdapter {}
+        impl EegHeadsetPort for TimedEegHeadsetAdapter {
+            fn connect(&self) -> Result<(), String>;
+            fn disconnect(&mut self) -> Result<(), String>;
+            fn is_connected(&self) -> bool;
+            fn get_work_mode(&self) -> crate::domain::models::eeg_work_modes::WorkMode;
+            fn change_work_mode(&mut self, mode: crate::domain::models::eeg_work_modes::WorkMode);
+            fn extract_impedance_data(&self) -> Result<std::collections::HashMap<String, u16>, String>;
+            fn extract_raw_data(&self) -> Result<std::collections::HashMap<String, Vec<f32>>, String>;
+            fn device_id(&self) -> Option<String>;
+            fn device_timings(&self) -> Option<DeviceTimings>;
+            fn apply_device_timings(&self, timings: DeviceTimings);
+        }
+    }
+
+    #[test]
+    async fn test_search_reuses_and_stores_device_timings() {
+        // Arrange
+        let stored = DeviceTimings {
+            command_delay_ms: 150,
+            data_delay_ms: 200,
+        };
+        let measured = DeviceTimings {
+            command_delay_ms: 75,
+            data_delay_ms: 200,
+        };
+
+        let mut mock = MockTimedEegHeadsetAdapter::new();
+        mock.expect_is_connected().times(1).returning(|| false);
+        mock.expect_device_id().returning(|| Some("AA:BB".to_string()));
+        mock.expect_apply_device_timings().with(eq(stored)).times(1).return_const(());
+        mock.expect_connect().times(1).returning(|| Ok(()));
+        mock.expect_is_connected().times(1).returning(|| true);
+        mock.expect_device_timings().return_const(Some(measured));
+
+        let mut context = NeuralAnalyticsContext::default();
+        context.calibration_profiles.remember_device_timings("AA:BB", stored);
+        context.eeg_headset_adapter = create_static_mock(mock);
+
+        // Act
+        let result = setup_command_bus().execute(&mut context, SearchHeadbandCommand).await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(context.calibration_profiles.device_timings("AA:BB"), Some(measured));
+    }
+}
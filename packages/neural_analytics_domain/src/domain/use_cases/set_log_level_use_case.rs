@@ -0,0 +1,73 @@
+use crate::domain::{commands::set_log_level_command::SetLogLevelCommand, context::NeuralAnalyticsContext};
+use log::info;
+use presage::{command_handler, Error, Events};
+
+/// This use case is responsible for changing the level of the logs while the core runs,
+/// so the debug logs of a problematic session can be enabled without restarting it.
+/// The change is journaled, so the analysis of the session knows from when the logs
+/// are more verbose.
+///
+/// The level is the global maximum of the `log` crate: the logger installed by the
+/// application must leave the filtering to it for a raised level to show.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which holds the journal.
+/// * `command`: The command holding the new level.
+///
+/// # Returns
+/// * `Result<Events, Error>`: Always succeeds, no event is emitted.
+#[command_handler(error = Error)]
+pub async fn set_log_level_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    command: SetLogLevelCommand,
+) -> Result<Events, Error> {
+    let previous = log::max_level();
+
+    // Logged at both levels, so the change shows whichever way it goes
+    info!("Changing the log level from {} to {}", previous, command.level);
+    log::set_max_level(command.level);
+    info!("Log level set to {}", command.level);
+
+    _context.journal.record(
+        "log_level",
+        &serde_json::json!({ "from": previous.to_string(), "to": command.level.to_string() }),
+    );
+
+    Ok(Events::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::LevelFilter;
+    use presage::{CommandBus, Configuration};
+
+    /// Función auxiliar para configurar el CommandBus para los tests
+    fn setup_command_bus() -> CommandBus<NeuralAnalyticsContext, Error> {
+        CommandBus::<NeuralAnalyticsContext, Error>::new()
+            .configure(Configuration::new().command_handler(&set_log_level_use_case))
+    }
+
+    #[tokio::test]
+    async fn test_set_log_level_changes_the_max_level_and_journals_it() {
+        // Arrange
+        let mut context = NeuralAnalyticsContext::default();
+        let command_bus = setup_command_bus();
+        let previous = log::max_level();
+
+        // Act
+        let result = command_bus
+            .execute(&mut context, SetLogLevelCommand { level: LevelFilter::Debug })
+            .await;
+        let level = log::max_level();
+        log::set_max_level(previous);
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(level, LevelFilter::Debug);
+
+        let entry = context.journal.tail().back().unwrap();
+        assert_eq!(entry.kind, "log_level");
+        assert_eq!(entry.payload["to"], "DEBUG");
+    }
+}
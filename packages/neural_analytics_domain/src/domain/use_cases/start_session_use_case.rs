@@ -0,0 +1,87 @@
+use crate::domain::{commands::start_session_command::StartSessionCommand, context::NeuralAnalyticsContext};
+use log::{error, info};
+use presage::{command_handler, Error, Events};
+
+/// This use case is responsible for opening a session on behalf of the operator.
+/// Unlike the sessions opened by the headset connection, it spans the reconnects of
+/// the same headset, so its summary, journal entries and recording are not split by
+/// brief disconnects. It stays open until `EndSessionCommand`.
+///
+/// # Arguments
+/// * `_context`: A mutable reference to the `NeuralAnalyticsContext` which holds the session.
+/// * `command`: The command with the file to record the session to, if any.
+///
+/// # Returns
+/// * `Result<Events, Error>`: An error if a session is already open or the recording cannot be opened.
+#[command_handler(error = Error)]
+pub async fn start_session_use_case(
+    _context: &mut NeuralAnalyticsContext,
+    command: StartSessionCommand,
+) -> Result<Events, Error> {
+    _context
+        .start_explicit_session(command.recording_path.as_deref())
+        .map_err(|e| {
+            let error_msg = format!("Error starting the session: {}", e);
+            error!("{}", error_msg);
+            Error::MissingCommandHandler(Box::leak(error_msg.into_boxed_str()))
+        })?;
+
+    info!("Session {} started by the operator", _context.session_id);
+
+    Ok(Events::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        commands::end_session_command::EndSessionCommand, models::lifecycle_event::LifecycleEventKind,
+        use_cases::end_session_use_case::end_session_use_case,
+    };
+    use presage::{CommandBus, Configuration};
+
+    #[tokio::test]
+    async fn test_started_session_spans_the_reconnects_of_the_headset() {
+        let command_bus = CommandBus::<NeuralAnalyticsContext, Error>::new().configure(
+            Configuration::new()
+                .command_handler(&start_session_use_case)
+                .command_handler(&end_session_use_case),
+        );
+        let mut context = NeuralAnalyticsContext::default();
+
+        command_bus
+            .execute(&mut context, StartSessionCommand { recording_path: None })
+            .await
+            .unwrap();
+        assert!(command_bus
+            .execute(&mut context, StartSessionCommand { recording_path: None })
+            .await
+            .is_err());
+
+        // A brief disconnect keeps the session and its summary
+        context.begin_session();
+        context.end_session();
+        context.begin_session();
+        assert_eq!(context.session_id, 1);
+        assert_eq!(context.metrics.counter("sessions.continued"), 2);
+
+        command_bus.execute(&mut context, EndSessionCommand).await.unwrap();
+        context.end_session();
+
+        let kinds: Vec<_> = context.take_lifecycle_events().iter().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                LifecycleEventKind::SessionStarted,
+                LifecycleEventKind::Disconnected,
+                LifecycleEventKind::SummaryReady,
+                // The headset still connected continues in a session of its own
+                LifecycleEventKind::SessionStarted,
+                LifecycleEventKind::Disconnected,
+                LifecycleEventKind::SummaryReady,
+            ]
+        );
+        assert_eq!(context.session_id, 2);
+        assert!(command_bus.execute(&mut context, EndSessionCommand).await.is_err());
+    }
+}
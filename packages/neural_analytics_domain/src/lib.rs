@@ -0,0 +1,1225 @@
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Duration;
+
+use domain::context::adapter_registry::{AdapterRegistry, SharedAdapter};
+use domain::context::NeuralAnalyticsContext;
+use domain::context::singletons::{
+    get_eeg_headset_adapter, get_model_service, get_smart_bulb_adapter, set_bundle_archive_adapter,
+    set_eeg_headset_adapter, set_lifecycle_notifier_adapter, set_model_service, set_prediction_sink_adapter, set_prediction_webhook_adapter,
+    set_smart_bulb_adapter,
+};
+use domain::events::heartbeat_event::HeartbeatEvent;
+use utils::{EventDispatcher, SharedEventHandler};
+use domain::ports::{
+    input::{eeg_headset::EegHeadsetPort, model_watcher::ModelWatcherPort},
+    output::{
+        bundle_archive::BundleArchivePort, lifecycle_notifier::LifecycleNotifierPort,
+        prediction_sink::PredictionSinkPort, prediction_webhook::PredictionWebhookPort, recorder::RecorderPort,
+        smart_bulb::SmartBulbPort, tensor_dump::TensorDumpPort, thread_scheduler::ThreadSchedulerPort,
+    },
+};
+use domain::services::{
+    auto_off_bulb_service::AutoOffBulbAdapter,
+    metrics_service::MetricsSnapshot,
+    model_inference_service::{default_model_path, ModelInferenceInterface, ModelInferenceService},
+    model_watcher_service::spawn_model_watcher,
+    runtime_service::build_core_runtime,
+};
+use futures::channel::oneshot;
+use domain::models::{
+    bulb_state::BulbState, calibration_thresholds::CalibrationThresholds, core_config::CoreConfig,
+    core_health::CoreHealth, event_data::EventData, inference_config::InferenceConfig,
+    operation_progress::{CancellationToken, OperationKind, OperationProgress, OperationStatus},
+    operator_action::OperatorAction, session_stats::SessionStats,
+};
+use log::{debug, error, info, warn, LevelFilter};
+use presage::Event;
+use domain::state_machine::{
+    neural_events::NeuralAnalyticsCoreEvents, state_machine::{MainStateMachine, State},
+};
+
+use statig::awaitable::{InitializedStateMachine, IntoStateMachineExt};
+
+pub mod domain;
+pub mod utils;
+
+// Core the deprecated free functions drive, the last one initialized
+static CURRENT_CORE: RwLock<Option<Weak<CoreState>>> = RwLock::new(None);
+
+/// State of a running core, shared by its handle, its clients and its background tasks
+///
+/// Each core has its own, so two cores started one after the other never see the flags,
+/// the requests or the handlers of each other.
+struct CoreState {
+    // Set by the `CoreHandle` to stop the background tasks, and by the loop once it stopped
+    shutdown_requested: AtomicBool,
+    state_machine_stopped: AtomicBool,
+    // Set by whichever closes the session and the files first, the loop or a shutdown timing out
+    files_closed: AtomicBool,
+    // Context of the state machine, so a shutdown timing out can still sync the journal and the recording
+    context: Arc<tokio::sync::Mutex<NeuralAnalyticsContext>>,
+    // Requests of the frontends, served in order by the state machine loop between ticks
+    requests: Mutex<VecDeque<CoreRequest>>,
+    // Calibration thresholds of the state machine, replaced by `apply_calibration_thresholds`
+    calibration_thresholds: Arc<RwLock<CalibrationThresholds>>,
+    // Health and metrics of the state machine, readable while it holds its context
+    health: Arc<RwLock<CoreHealth>>,
+    metrics: Arc<RwLock<MetricsSnapshot>>,
+    // Cancellation token of the long-running operations of the state machine
+    cancellation: CancellationToken,
+    events: Arc<EventDispatcher>,
+    // Adapters of this core, released with it so a later core registers its own
+    adapters: AdapterRegistry,
+}
+
+impl CoreState {
+    fn is_shutdown_requested(&self) -> bool {
+        self.shutdown_requested.load(Ordering::SeqCst)
+    }
+
+    fn push_request(&self, request: CoreRequest) {
+        // A stopped loop would never serve it, so it is dropped and its receiver, if any, fails
+        if self.is_shutdown_requested() {
+            debug!("Dropping a request sent while the core shuts down");
+            return;
+        }
+
+        match self.requests.lock() {
+            Ok(mut requests) => requests.push_back(request),
+            Err(e) => error!("Failed to queue a core request: {}", e),
+        }
+    }
+
+    fn pop_request(&self) -> Option<CoreRequest> {
+        self.requests.lock().ok()?.pop_front()
+    }
+
+    fn pending_requests(&self) -> usize {
+        self.requests.lock().map(|requests| requests.len()).unwrap_or_default()
+    }
+
+    fn headset(&self) -> SharedAdapter<dyn EegHeadsetPort + Send + Sync> {
+        get_eeg_headset_adapter(&self.adapters)
+    }
+}
+
+enum CoreRequest {
+    ContextSnapshot,
+    DiagnosticBundle(Option<String>),
+    TestSignal,
+    StartRecording(String),
+    StopRecording,
+    StartSession(Option<String>),
+    EndSession,
+    UserProfile(String),
+    ResumeHeadsetSearch,
+    LogLevel(LevelFilter),
+    // Unix epoch milliseconds when the operator acted, the request waits for the current tick
+    OperatorAction(OperatorAction, i64),
+    // Sent through the `CoreHandle`, which is told whether the command was served
+    Command(CoreCommand, oneshot::Sender<Result<(), String>>),
+    SessionStats(u64, oneshot::Sender<Option<SessionStats>>),
+}
+
+// Actions the frontends drive through the `CoreHandle`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CoreCommand {
+    Recalibrate,
+    PauseCapture,
+    ResumeCapture,
+    ReconnectHeadset,
+}
+
+/// Client of the last core initialized, driven by the deprecated free functions
+fn current_core() -> CoreClient {
+    let core = CURRENT_CORE.read().ok().and_then(|core| core.clone()).unwrap_or_default();
+
+    CoreClient { core }
+}
+
+/// Handlers of the last core initialized, used by the deprecated `utils::send_event`
+pub(crate) fn current_events() -> Option<Arc<EventDispatcher>> {
+    current_core().core.upgrade().map(|core| core.events.clone())
+}
+
+// Name of the thread running the state machine when scheduling hints are configured
+const STATE_MACHINE_THREAD: &str = "neural-analytics-core";
+
+// Longest wait for the state machine to finish its current tick before cleaning up
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Hardware adapters plugged into the core by the composition crate
+///
+/// Built with `CoreAdapters::new` and the `with_*` methods of the optional adapters, so
+/// the adapters added later do not break the callers.
+#[non_exhaustive]
+pub struct CoreAdapters {
+    pub eeg_headset: Box<dyn EegHeadsetPort + Send + Sync>,
+    pub smart_bulb: Box<dyn SmartBulbPort + Send + Sync>,
+    /// Reports the session lifecycle to external tools, `None` to keep it local.
+    pub lifecycle_notifier: Option<Box<dyn LifecycleNotifierPort + Send + Sync>>,
+    /// Publishes the predictions beside the smart bulb, `None` to keep them local.
+    pub prediction_sink: Option<Box<dyn PredictionSinkPort + Send + Sync>>,
+    /// Calls the URLs of the `Webhook` actions, `None` when no action uses them.
+    pub prediction_webhook: Option<Box<dyn PredictionWebhookPort + Send + Sync>>,
+    /// Predicts the windows, `None` to load the ONNX model of the assets.
+    pub model: Option<Box<dyn ModelInferenceInterface + Send + Sync>>,
+    /// Watches the model file when `watch_model` is set, `None` to never reload it.
+    pub model_watcher: Option<Box<dyn ModelWatcherPort>>,
+    /// Writes the diagnostic bundles, `None` to reject them.
+    pub bundle_archive: Option<Box<dyn BundleArchivePort + Send + Sync>>,
+    /// Records the extracted windows, `None` to never record them.
+    pub recorder: Option<Box<dyn RecorderPort>>,
+    /// Dumps the tensors of the predictions of the ONNX model, `None` outside debugging.
+    pub tensor_dump: Option<Box<dyn TensorDumpPort>>,
+    /// Applies the `scheduling` hints to the thread of the state machine, `None` to ignore them.
+    pub thread_scheduler: Option<Box<dyn ThreadSchedulerPort>>,
+}
+
+impl CoreAdapters {
+    /// Adapters of the headset and the smart bulb, the optional ones left unset
+    pub fn new(eeg_headset: Box<dyn EegHeadsetPort + Send + Sync>, smart_bulb: Box<dyn SmartBulbPort + Send + Sync>) -> Self {
+        Self {
+            eeg_headset,
+            smart_bulb,
+            lifecycle_notifier: None,
+            prediction_sink: None,
+            prediction_webhook: None,
+            model: None,
+            model_watcher: None,
+            bundle_archive: None,
+            recorder: None,
+            tensor_dump: None,
+            thread_scheduler: None,
+        }
+    }
+
+    /// Reports the session lifecycle to external tools
+    pub fn with_lifecycle_notifier(mut self, lifecycle_notifier: Box<dyn LifecycleNotifierPort + Send + Sync>) -> Self {
+        self.lifecycle_notifier = Some(lifecycle_notifier);
+        self
+    }
+
+    /// Publishes the predictions beside the smart bulb
+    pub fn with_prediction_sink(mut self, prediction_sink: Box<dyn PredictionSinkPort + Send + Sync>) -> Self {
+        self.prediction_sink = Some(prediction_sink);
+        self
+    }
+
+    /// Calls the URLs of the `Webhook` actions
+    pub fn with_prediction_webhook(mut self, prediction_webhook: Box<dyn PredictionWebhookPort + Send + Sync>) -> Self {
+        self.prediction_webhook = Some(prediction_webhook);
+        self
+    }
+
+    /// Predicts the windows in place of the ONNX model of the assets
+    pub fn with_model(mut self, model: Box<dyn ModelInferenceInterface + Send + Sync>) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Watches the model file when `watch_model` is set
+    pub fn with_model_watcher(mut self, model_watcher: Box<dyn ModelWatcherPort>) -> Self {
+        self.model_watcher = Some(model_watcher);
+        self
+    }
+
+    /// Writes the diagnostic bundles
+    pub fn with_bundle_archive(mut self, bundle_archive: Box<dyn BundleArchivePort + Send + Sync>) -> Self {
+        self.bundle_archive = Some(bundle_archive);
+        self
+    }
+
+    /// Records the extracted windows, explicitly or per session
+    pub fn with_recorder(mut self, recorder: Box<dyn RecorderPort>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Dumps the tensors of the predictions of the ONNX model, reloaded ones included
+    pub fn with_tensor_dump(mut self, tensor_dump: Box<dyn TensorDumpPort>) -> Self {
+        self.tensor_dump = Some(tensor_dump);
+        self
+    }
+
+    /// Applies the `scheduling` hints to the thread of the state machine
+    pub fn with_thread_scheduler(mut self, thread_scheduler: Box<dyn ThreadSchedulerPort>) -> Self {
+        self.thread_scheduler = Some(thread_scheduler);
+        self
+    }
+}
+
+/// Initialize the core of the application with the given adapters
+///
+/// The core tasks are spawned on the tokio runtime of the caller, whatever the
+/// `runtime` section of the configuration says; use `start_core_with_adapters` to run
+/// them on a runtime of their own.
+///
+/// This function initializes the core of the application by registering the adapters
+/// and setting up the state machine and the event handler. The domain does not know
+/// any concrete hardware, so the adapters are provided by the caller.
+///
+/// # Arguments
+/// - `config`: The `CoreConfig` used by the state machine and its services.
+/// - `adapters`: The EEG headset and smart bulb adapters to use.
+/// - `event_handler`: A function that handles events. It takes a string and an `EventData` struct as arguments and returns a `Result<(), String>`.
+///
+/// # Returns
+/// - `Result<CoreHandle, String>`: The handle of the running core, or an error message if it fails.
+///   Dropping the handle shuts the core down.
+///
+pub async fn initialize_core_with_adapters<F>(
+    config: CoreConfig,
+    adapters: CoreAdapters,
+    event_handler: F,
+) -> Result<CoreHandle, String>
+where
+    F: Fn(&String, &EventData) -> Result<(), String> + 'static + Send,
+{
+    // Registered in a registry of this core, so other cores of the process keep their devices
+    let registry = AdapterRegistry::default();
+    set_eeg_headset_adapter(&registry, adapters.eeg_headset)?;
+
+    // The bulb is turned off when the core stops driving it, unless disabled
+    match config.bulb_auto_off_ms {
+        0 => set_smart_bulb_adapter(&registry, adapters.smart_bulb)?,
+        auto_off_ms => set_smart_bulb_adapter(
+            &registry,
+            Box::new(AutoOffBulbAdapter::new(adapters.smart_bulb, Duration::from_millis(auto_off_ms))),
+        )?,
+    }
+
+    if let Some(lifecycle_notifier) = adapters.lifecycle_notifier {
+        set_lifecycle_notifier_adapter(&registry, lifecycle_notifier)?;
+    }
+
+    if let Some(prediction_sink) = adapters.prediction_sink {
+        set_prediction_sink_adapter(&registry, prediction_sink)?;
+    }
+
+    if let Some(prediction_webhook) = adapters.prediction_webhook {
+        set_prediction_webhook_adapter(&registry, prediction_webhook)?;
+    }
+
+    if let Some(bundle_archive) = adapters.bundle_archive {
+        set_bundle_archive_adapter(&registry, bundle_archive)?;
+    }
+
+    let tensor_dump: Option<Arc<dyn TensorDumpPort>> = adapters.tensor_dump.map(Arc::from);
+
+    if let Some(model) = adapters.model {
+        set_model_service(&registry, model)?;
+    } else if config.inference != InferenceConfig::default() || tensor_dump.is_some() {
+        // Registered before the first use, so the model is not loaded in tract or without the dump first
+        set_model_service(
+            &registry,
+            Box::new(load_model_service(&default_model_path(), config.inference, tensor_dump.as_ref())),
+        )?;
+    }
+
+    for (port, name, capabilities) in registry.registered() {
+        info!("Adapter '{}' registered for {} with capabilities {:?}", name, port, capabilities);
+    }
+
+    // Created before the model load, so the load is reported
+    let event_handler: SharedEventHandler = Arc::new(Mutex::new(Box::new(event_handler)));
+    let events = Arc::new(EventDispatcher::new(event_handler, config.event_routing));
+
+    let heartbeat_interval_ms = config.heartbeat_interval_ms;
+    let watch_model = config.watch_model;
+    let inference = config.inference;
+    let scheduling = config.scheduling.clone();
+
+    // Load the model before the state machine, reporting it so the frontends do not look frozen
+    events.send_progress(OperationProgress::running(OperationKind::ModelLoad, None, false));
+    let model_load_status = if get_model_service(&registry).read().await.is_model_loaded() {
+        OperationStatus::Finished
+    } else {
+        OperationStatus::Failed
+    };
+    events.send_progress(OperationProgress::ended(OperationKind::ModelLoad, model_load_status));
+
+    // Define the state machine asynchronously
+    let state_machine_instance = MainStateMachine::new(config, events.clone(), &registry).await;
+
+    // Plugged before the first state, which tells the recorder the unit of the samples
+    if let Some(recorder) = adapters.recorder {
+        state_machine_instance.shared_context().lock().await.recording = recorder;
+    }
+
+    let core = Arc::new(CoreState {
+        shutdown_requested: AtomicBool::new(false),
+        state_machine_stopped: AtomicBool::new(false),
+        files_closed: AtomicBool::new(false),
+        context: state_machine_instance.shared_context(),
+        requests: Mutex::new(VecDeque::new()),
+        calibration_thresholds: state_machine_instance.shared_calibration_thresholds(),
+        health: state_machine_instance.health(),
+        metrics: state_machine_instance.shared_metrics(),
+        cancellation: state_machine_instance.shared_cancellation(),
+        events,
+        adapters: registry,
+    });
+
+    if watch_model {
+        // A missing watcher only disables hot reloading, the core keeps working
+        let model_watcher = match adapters.model_watcher.as_deref() {
+            Some(model_watcher) => spawn_model_watcher(
+                model_watcher,
+                &default_model_path(),
+                move |model_path| load_model_service(model_path, inference, tensor_dump.as_ref()),
+                get_model_service(&core.adapters),
+                core.cancellation.clone(),
+                state_machine_instance.shared_inference_cache(),
+                core.events.clone(),
+            ),
+            None => Err("No model watcher adapter registered".to_string()),
+        };
+
+        if let Err(e) = model_watcher {
+            error!("Failed to start the model watcher: {}", e);
+        }
+    }
+
+    // Owned by the loop below, which serves the requests of the frontends between its ticks
+    let mut state_machine = state_machine_instance.uninitialized_state_machine().init().await;
+    state_machine.handle(&NeuralAnalyticsCoreEvents::InitializeCore).await;
+    state_machine.publish_notifications().await;
+
+    let loop_core = core.clone();
+    let state_machine_loop = async move {
+        // Run the state machine in the background
+        while !loop_core.is_shutdown_requested() {
+            state_machine.handle(&NeuralAnalyticsCoreEvents::BackgroundTick).await;
+            state_machine.record_tick(state_machine.state());
+            state_machine.publish_notifications().await;
+            state_machine.observe_request_queue(loop_core.pending_requests()).await;
+
+            while let Some(request) = loop_core.pop_request() {
+                let mut acknowledgement = None;
+
+                match request {
+                    CoreRequest::ContextSnapshot => state_machine.publish_context_snapshot().await,
+                    CoreRequest::DiagnosticBundle(path) => state_machine.create_diagnostic_bundle(path).await,
+                    CoreRequest::TestSignal => state_machine.verify_test_signal().await,
+                    CoreRequest::StartRecording(path) => state_machine.start_recording(&path).await,
+                    CoreRequest::StopRecording => state_machine.stop_recording().await,
+                    CoreRequest::StartSession(recording_path) => state_machine.start_session(recording_path).await,
+                    CoreRequest::EndSession => state_machine.end_session().await,
+                    CoreRequest::UserProfile(user_profile) => state_machine.set_user_profile(&user_profile).await,
+                    CoreRequest::ResumeHeadsetSearch => state_machine.resume_headset_search().await,
+                    CoreRequest::LogLevel(level) => state_machine.set_log_level(level).await,
+                    CoreRequest::OperatorAction(action, timestamp) => {
+                        state_machine.record_operator_action(action, timestamp).await
+                    }
+                    CoreRequest::Command(command, sender) => {
+                        acknowledgement = Some((sender, serve_command(&mut state_machine, command).await));
+                    }
+                    CoreRequest::SessionStats(session_id, sender) => {
+                        let _ = sender.send(state_machine.session_stats(session_id).await);
+                    }
+                }
+
+                state_machine.publish_notifications().await;
+
+                // The frontend is answered once the outcome of the command was published
+                if let Some((sender, result)) = acknowledgement {
+                    let _ = sender.send(result);
+                }
+            }
+        }
+
+        // Reports the summary of the last session and syncs the journal and the recording,
+        // while the event handler is still registered
+        if !loop_core.files_closed.swap(true, Ordering::SeqCst) {
+            state_machine.close_for_shutdown().await;
+            state_machine.publish_notifications().await;
+        }
+
+        loop_core.state_machine_stopped.store(true, Ordering::SeqCst);
+    };
+
+    if scheduling.is_enabled() {
+        let thread_scheduler = adapters.thread_scheduler;
+
+        // The capture and the inference get a thread of their own, so the hints do not
+        // apply to the other tasks of the application runtime
+        std::thread::Builder::new()
+            .name(STATE_MACHINE_THREAD.to_string())
+            .spawn(move || {
+                let issues = match thread_scheduler {
+                    Some(thread_scheduler) => thread_scheduler.apply(&scheduling),
+                    None => vec!["No thread scheduler adapter registered, the scheduling hints are ignored".to_string()],
+                };
+
+                for issue in issues {
+                    warn!("{}", issue);
+                }
+
+                match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime.block_on(state_machine_loop),
+                    Err(e) => error!("Failed to create the state machine runtime: {}", e),
+                }
+            })
+            .map_err(|e| format!("Failed to spawn the state machine thread: {}", e))?;
+    } else {
+        tokio::spawn(state_machine_loop);
+    }
+
+    if heartbeat_interval_ms > 0 {
+        let heartbeat_core = core.clone();
+
+        tokio::spawn(async move {
+            // Publish the health snapshot at a low rate, independently of the state machine,
+            // so watchdogs can detect a hung core by its stale tick timestamp
+            let mut interval = tokio::time::interval(Duration::from_millis(heartbeat_interval_ms));
+
+            while !heartbeat_core.is_shutdown_requested() {
+                interval.tick().await;
+
+                let core_health = heartbeat_core.health.read().map(|health| health.clone()).unwrap_or_default();
+
+                if let Err(e) = heartbeat_core.events.send(
+                    &HeartbeatEvent::NAME.to_string(),
+                    &EventData {
+                        core_health: Some(core_health),
+                        ..Default::default()
+                    },
+                ) {
+                    error!("Failed to send heartbeat event: {}", e);
+                }
+            }
+        });
+    }
+
+    // The deprecated free functions drive the last core initialized
+    match CURRENT_CORE.write() {
+        Ok(mut current) => *current = Some(Arc::downgrade(&core)),
+        Err(e) => error!("Failed to register the core for the free functions: {}", e),
+    }
+
+    Ok(CoreHandle {
+        is_shut_down: false,
+        runtime: None,
+        core,
+    })
+}
+
+/// Loads the ONNX model of a path in the runtime of `inference`, dumping its tensors when a dump is plugged
+fn load_model_service(
+    model_path: &str,
+    inference: InferenceConfig,
+    tensor_dump: Option<&Arc<dyn TensorDumpPort>>,
+) -> ModelInferenceService {
+    let service = ModelInferenceService::with_inference(model_path, inference);
+
+    match tensor_dump {
+        Some(tensor_dump) => service.with_tensor_dump(tensor_dump.clone()),
+        None => service,
+    }
+}
+
+/// Serves a command of the frontends between two ticks of the state machine
+///
+/// # Returns
+/// - `Result<(), String>`: An error if the command does not apply to the current state.
+async fn serve_command(
+    state_machine: &mut InitializedStateMachine<MainStateMachine>,
+    command: CoreCommand,
+) -> Result<(), String> {
+    let state = state_machine.state();
+
+    match command {
+        // The other states would take the event as a regular tick
+        CoreCommand::Recalibrate if matches!(state, State::CapturingHeadsetData {}) => {
+            state_machine.handle(&NeuralAnalyticsCoreEvents::RecalibrationRequested).await;
+        }
+        CoreCommand::Recalibrate => {
+            return Err(format!("The headset can only be recalibrated while capturing, the core is in {:?}", state));
+        }
+        CoreCommand::PauseCapture => state_machine.pause_capture().await,
+        CoreCommand::ResumeCapture => state_machine.resume_capture().await,
+        CoreCommand::ReconnectHeadset => {
+            state_machine.resume_headset_search().await;
+
+            if matches!(state, State::AwaitingHeadsetCalibration {} | State::CapturingHeadsetData {}) {
+                state_machine.handle(&NeuralAnalyticsCoreEvents::ReconnectRequested).await;
+            }
+        }
+    }
+
+    state_machine.record_tick(state_machine.state());
+    Ok(())
+}
+
+/// Start the core of the application on a dedicated runtime
+///
+/// Blocking counterpart of `initialize_core_with_adapters`, for applications that do not
+/// run tokio or that keep the core threads apart from theirs. The runtime is built from
+/// the `runtime` section of the configuration, and is owned by the returned handle, so
+/// it is shut down with the core. The adapters are created on the runtime, as some of
+/// them spawn their connection tasks when created.
+///
+/// Can be called from a tokio runtime too, it then blocks its thread until the core is
+/// initialized.
+///
+/// # Arguments
+/// - `config`: The `CoreConfig` used by the state machine and its services.
+/// - `adapters`: Creates the EEG headset and smart bulb adapters to use.
+/// - `event_handler`: A function that handles events. It takes a string and an `EventData` struct as arguments and returns a `Result<(), String>`.
+///
+/// # Returns
+/// - `Result<CoreHandle, String>`: The handle of the running core, or an error message if it fails.
+///   Dropping the handle shuts the core and its runtime down.
+///
+pub fn start_core_with_adapters<A, F>(config: CoreConfig, adapters: A, event_handler: F) -> Result<CoreHandle, String>
+where
+    A: FnOnce() -> CoreAdapters + Send,
+    F: Fn(&String, &EventData) -> Result<(), String> + 'static + Send,
+{
+    let runtime = build_core_runtime(&config.runtime)?;
+    let runtime_handle = runtime.handle().clone();
+
+    // Blocking on the runtime from a thread of another runtime panics, so the
+    // initialization runs on a thread of its own
+    let initialized = std::thread::scope(|scope| {
+        scope
+            .spawn(move || {
+                runtime_handle.block_on(async move {
+                    initialize_core_with_adapters(config, adapters(), event_handler).await
+                })
+            })
+            .join()
+    });
+
+    match initialized {
+        Ok(Ok(mut core)) => {
+            core.runtime = Some(runtime);
+            Ok(core)
+        }
+        Ok(Err(e)) => {
+            runtime.shutdown_background();
+            Err(e)
+        }
+        Err(_) => {
+            runtime.shutdown_background();
+            Err("The core initialization panicked".to_string())
+        }
+    }
+}
+
+/// Handle of the running core
+///
+/// Ending the core through the handle, explicitly with `shutdown` or by dropping it,
+/// stops the background tasks and leaves the devices idle: the bulb is turned off
+/// and the headset stops measuring, so it does not drain its battery. The session in
+/// progress is closed before, its summary reported, and the journal and the recording
+/// synced to disk.
+///
+/// A core started with `start_core_with_adapters` also owns its runtime, which is shut
+/// down after the devices cleanup.
+///
+/// The state machine is owned by the background loop, and the requests, the health, the
+/// thresholds, the event handlers and the adapters of the core by the handle, so nothing of
+/// the core is reachable through mutable statics. Several cores can run in the same
+/// process, each with its own devices, and a core can be started once another shut down.
+///
+/// The frontends drive the core through the handle, or through a `CoreClient` taken from
+/// it for the tasks that do not own the core: each command is queued when the method is
+/// called, and the returned future resolves once the state machine served it, after its
+/// current tick, with an error if it did not apply. The future does not borrow the
+/// handle, so it can be awaited from another task.
+#[must_use = "Dropping the handle shuts the core down"]
+pub struct CoreHandle {
+    is_shut_down: bool,
+    runtime: Option<tokio::runtime::Runtime>,
+    core: Arc<CoreState>,
+}
+
+impl CoreHandle {
+    /// Shuts the core down, waiting for the devices cleanup
+    ///
+    /// Prefer it to dropping the handle from async code: the drop waits on a thread of its
+    /// own, and on a current thread runtime it cannot wait at all, so the cleanup is only
+    /// spawned there.
+    pub async fn shutdown(mut self) {
+        self.is_shut_down = true;
+
+        match self.runtime.take() {
+            Some(runtime) => {
+                if runtime.spawn(shutdown_core(self.core.clone())).await.is_err() {
+                    error!("The core shutdown panicked");
+                }
+
+                // Dropping a runtime blocks, which is not allowed in an async context
+                runtime.shutdown_background();
+            }
+            None => shutdown_core(self.core.clone()).await,
+        }
+    }
+
+    /// Handle of the dedicated runtime of the core, to spawn tasks next to the core ones
+    ///
+    /// # Returns
+    /// - `Option<Handle>`: `None` when the core runs on the runtime of the caller.
+    pub fn runtime(&self) -> Option<tokio::runtime::Handle> {
+        self.runtime.as_ref().map(|runtime| runtime.handle().clone())
+    }
+
+    /// Client of the core, for the tasks driving it without owning it
+    ///
+    /// The client does not keep the core running: once it is shut down, its requests
+    /// are dropped and its commands fail.
+    pub fn client(&self) -> CoreClient {
+        CoreClient {
+            core: Arc::downgrade(&self.core),
+        }
+    }
+
+    /// Checks the electrodes again, keeping the headset session
+    ///
+    /// # Returns
+    /// - `Future<Output = Result<(), String>>`: An error if the core is not capturing.
+    pub fn request_recalibration(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.client().request_recalibration()
+    }
+
+    /// Stops extracting and predicting windows, keeping the headset connected
+    ///
+    /// The pause is journaled, lasts across reconnects until `resume_capture`, and only
+    /// holds the capture: a headset still to calibrate keeps calibrating.
+    pub fn pause_capture(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.client().pause_capture()
+    }
+
+    /// Resumes the capture paused by `pause_capture`, journaling it
+    pub fn resume_capture(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.client().resume_capture()
+    }
+
+    /// Disconnects the headset and searches it again, or resumes a cancelled search
+    pub fn reconnect_headset(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.client().reconnect_headset()
+    }
+
+    /// Sends the events of the core to another handler too, after the one it was started with
+    ///
+    /// The handler is called one event at a time, with the same sequence numbers, and must
+    /// not send events itself. Its errors are logged without failing the event.
+    ///
+    /// # Returns
+    /// - `EventSubscription`: Removes the handler when dropped, or once the core shuts down.
+    pub fn add_event_handler<F>(&self, event_handler: F) -> EventSubscription
+    where
+        F: Fn(&String, &EventData) -> Result<(), String> + 'static + Send,
+    {
+        EventSubscription {
+            events: Arc::downgrade(&self.core.events),
+            id: self.core.events.add_subscriber(Arc::new(Mutex::new(Box::new(event_handler)))),
+        }
+    }
+}
+
+/// Client of a running core, taken from its handle with `CoreHandle::client`
+///
+/// Drives the core without owning it, so it can be cloned into the tasks serving the
+/// frontends, such as the remote API. The requests are served once the state machine
+/// finishes its current tick, and those sent after the core shut down are dropped.
+#[derive(Clone)]
+pub struct CoreClient {
+    core: Weak<CoreState>,
+}
+
+impl CoreClient {
+    // Headset of the core, for the commands sent to the device without the state machine
+    fn headset(&self) -> Result<SharedAdapter<dyn EegHeadsetPort + Send + Sync>, String> {
+        self.core.upgrade().map(|core| core.headset()).ok_or_else(|| "The core is not running".to_string())
+    }
+
+    fn push_request(&self, request: CoreRequest) {
+        match self.core.upgrade() {
+            Some(core) => core.push_request(request),
+            None => debug!("Dropping a request sent after the core shut down"),
+        }
+    }
+
+    /// Queues a command for the state machine loop
+    ///
+    /// The request is queued right away, the returned future only waits for its outcome.
+    fn send_command(&self, command: CoreCommand) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        let (sender, receiver) = oneshot::channel();
+        self.push_request(CoreRequest::Command(command, sender));
+
+        async move {
+            receiver
+                .await
+                .map_err(|_| format!("The core stopped before serving the {:?} command", command))?
+        }
+    }
+
+    /// Checks the electrodes again, see `CoreHandle::request_recalibration`
+    pub fn request_recalibration(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.send_command(CoreCommand::Recalibrate)
+    }
+
+    /// Stops extracting and predicting windows, see `CoreHandle::pause_capture`
+    pub fn pause_capture(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.send_command(CoreCommand::PauseCapture)
+    }
+
+    /// Resumes the capture paused by `pause_capture`, journaling it
+    pub fn resume_capture(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.send_command(CoreCommand::ResumeCapture)
+    }
+
+    /// Disconnects the headset and searches it again, or resumes a cancelled search
+    pub fn reconnect_headset(&self) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        self.send_command(CoreCommand::ReconnectHeadset)
+    }
+
+    /// Request a summary of the context of the core
+    ///
+    /// The summary is published through the `ContextSnapshotEvent` once the state machine
+    /// finishes its current tick, so support can ask users for a state dump when they
+    /// report an issue.
+    pub fn request_context_snapshot(&self) {
+        self.push_request(CoreRequest::ContextSnapshot);
+    }
+
+    /// Request a diagnostic bundle to attach to an issue report
+    ///
+    /// Once the state machine finishes its current tick, the core writes a zip archive with
+    /// its configuration, the webhook URLs redacted, the tail of the session journal, the
+    /// metrics, the context snapshot, the last state transitions and the versions of the
+    /// core, the headset and the model. The raw EEG samples are left out. The archive, or
+    /// why it could not be written, is reported through the `DiagnosticBundleCreatedEvent`.
+    ///
+    /// # Arguments
+    /// - `path`: The archive to write, replaced if it exists. `None` writes
+    ///   `neural_analytics_diagnostics_<timestamp>.zip` in the working directory.
+    pub fn create_diagnostic_bundle(&self, path: Option<&str>) {
+        self.push_request(CoreRequest::DiagnosticBundle(path.map(str::to_string)));
+    }
+
+    /// Request a verification of the pipeline with the test signal of the headset
+    ///
+    /// The headset streams a known square wave, which goes through the extraction and the
+    /// model like a real window. The result is published through the `TestSignalVerifiedEvent`
+    /// once the state machine finishes its current tick, so an installation can be verified
+    /// without wearing the headset. The headset must be connected.
+    pub fn request_test_signal(&self) {
+        self.push_request(CoreRequest::TestSignal);
+    }
+
+    /// Request to record the extracted EEG windows to a file
+    ///
+    /// Every window is appended as a JSON line until `stop_recording` is requested.
+    /// The path of the recording in progress is reported in the `CoreHealth`.
+    ///
+    /// # Arguments
+    /// - `path`: The file to write, replaced if it exists.
+    pub fn start_recording(&self, path: &str) {
+        self.push_request(CoreRequest::StartRecording(path.to_string()));
+    }
+
+    /// Request to stop the recording in progress, if any
+    pub fn stop_recording(&self) {
+        self.push_request(CoreRequest::StopRecording);
+    }
+
+    /// Request to open a session independent of the headset connection
+    ///
+    /// By default a session lasts while its headset is connected, so every disconnect
+    /// splits its summary. A session started by the operator spans the reconnects of the
+    /// same headset instead, with its summary, journal and recording, until `end_session`
+    /// is requested. A different headset or user profile still rotates it, as reported by
+    /// the `SessionRotatedEvent`. Ignored while such a session is open.
+    ///
+    /// # Arguments
+    /// - `recording_path`: File the EEG windows of the session are recorded to, `None` to not record them.
+    pub fn start_session(&self, recording_path: Option<&str>) {
+        self.push_request(CoreRequest::StartSession(recording_path.map(str::to_string)));
+    }
+
+    /// Request to close the session opened by `start_session`, with its recording
+    ///
+    /// A headset still connected continues in a session of its own, closed when it disconnects.
+    pub fn end_session(&self) {
+        self.push_request(CoreRequest::EndSession);
+    }
+
+    /// Request to change the profile of the user wearing the headset
+    ///
+    /// When the profile differs from the previous one, the core closes the current session
+    /// and opens a new one with a fresh normalization state, reported through the
+    /// `SessionRotatedEvent`. The same happens when a different headset connects.
+    ///
+    /// # Arguments
+    /// - `user_profile`: The name of the profile.
+    pub fn set_user_profile(&self, user_profile: &str) {
+        self.push_request(CoreRequest::UserProfile(user_profile.to_string()));
+    }
+
+    /// Cancel the long-running operation in progress, if any
+    ///
+    /// Takes effect right away, without waiting for the current tick: the headset connection
+    /// stops waiting for the device, and a model reload keeps the current model. The outcome
+    /// is reported through the `OperationProgressEvent`. A cancelled headset search is paused
+    /// until `resume_headset_search` is requested.
+    pub fn cancel_operation(&self) {
+        match self.core.upgrade() {
+            Some(core) => {
+                info!("Cancelling the operation in progress");
+                core.cancellation.cancel();
+            }
+            None => error!("The core is not running, there is no operation to cancel"),
+        }
+    }
+
+    /// Request to search the headset again after its search was cancelled
+    pub fn resume_headset_search(&self) {
+        self.push_request(CoreRequest::ResumeHeadsetSearch);
+    }
+
+    /// Request to change the level of the logs while the core runs
+    ///
+    /// Lets the debug logs of a problematic session be enabled for a while, without
+    /// restarting the core and losing the session. The level is the global maximum of the
+    /// `log` crate, set once the state machine finishes its current tick, and the change is
+    /// journaled. The logger of the application must leave the filtering to that maximum.
+    ///
+    /// # Arguments
+    /// - `level`: One of `off`, `error`, `warn`, `info`, `debug` or `trace`, case insensitive.
+    ///
+    /// # Returns
+    /// - `Result<(), String>`: An error if the level is unknown.
+    pub fn set_log_level(&self, level: &str) -> Result<(), String> {
+        let level = level.trim().parse::<LevelFilter>().map_err(|_| {
+            format!("Unknown log level {}, expected off, error, warn, info, debug or trace", level)
+        })?;
+
+        self.push_request(CoreRequest::LogLevel(level));
+        Ok(())
+    }
+
+    /// Record an action of the operator in the session journal
+    ///
+    /// Frontends report the interventions of the operator, such as a recalibration or an
+    /// annotation, so the analysis of the EEG data can account for them. The action is
+    /// stamped now and journaled next to the last extracted window once the state machine
+    /// finishes its current tick.
+    ///
+    /// # Arguments
+    /// - `action`: What the operator did.
+    pub fn record_operator_action(&self, action: OperatorAction) {
+        self.push_request(CoreRequest::OperatorAction(action, chrono::Utc::now().timestamp_millis()));
+    }
+
+    /// Statistics of a session of the core, such as its windows, the share of each class
+    /// in its predictions and the duration of its calibrations
+    ///
+    /// The current session is served with the statistics so far, and the last 64 finished
+    /// ones with their final statistics. The request waits for the current tick.
+    ///
+    /// # Arguments
+    /// - `session_id`: The session, as reported by the `SessionRotatedEvent` and the lifecycle events.
+    ///
+    /// # Returns
+    /// - `Future<Output = Result<Option<SessionStats>, String>>`: `None` when the session is unknown
+    ///   or was forgotten, or an error if the core is not running.
+    pub fn session_stats(&self, session_id: u64) -> impl Future<Output = Result<Option<SessionStats>, String>> + Send + 'static {
+        let (sender, receiver) = oneshot::channel();
+        self.push_request(CoreRequest::SessionStats(session_id, sender));
+
+        async move {
+            receiver
+                .await
+                .map_err(|_| "The core is not running".to_string())
+        }
+    }
+
+    /// Health of the core, or `None` once it shut down
+    pub fn health(&self) -> Option<CoreHealth> {
+        self.core.upgrade()?.health.read().ok().map(|health| health.clone())
+    }
+
+    /// Metrics of the core, such as the transitions between its states and the failed
+    /// commands, or `None` once it shut down
+    ///
+    /// Render them with `MetricsSnapshot::to_prometheus` for a Prometheus scraper.
+    pub fn metrics(&self) -> Option<MetricsSnapshot> {
+        self.core.upgrade()?.metrics.read().ok().map(|metrics| metrics.clone())
+    }
+
+    /// Replace the calibration thresholds of the core
+    ///
+    /// Used by the frontends to accept a threshold suggested through the `SuggestedThresholdsEvent`.
+    /// The new thresholds are used from the next calibration reading on.
+    ///
+    /// # Arguments
+    /// - `thresholds`: The impedance range the electrodes must be within.
+    ///
+    /// # Returns
+    /// - `Result<(), String>`: An error if the core shut down.
+    pub fn apply_calibration_thresholds(&self, thresholds: CalibrationThresholds) -> Result<(), String> {
+        let core = self.core.upgrade().ok_or_else(|| "The core is not running".to_string())?;
+
+        let mut current = core
+            .calibration_thresholds
+            .write()
+            .map_err(|e| format!("Failed to update calibration thresholds: {}", e))?;
+
+        info!("Applying calibration thresholds: {:?}", thresholds);
+        *current = thresholds;
+        Ok(())
+    }
+}
+
+/// Handler added with `CoreHandle::add_event_handler`, removed when dropped
+#[must_use = "Dropping the subscription removes its handler"]
+pub struct EventSubscription {
+    events: Weak<EventDispatcher>,
+    id: u64,
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        if let Some(events) = self.events.upgrade() {
+            events.remove_subscriber(self.id);
+        }
+    }
+}
+
+impl Drop for CoreHandle {
+    fn drop(&mut self) {
+        if self.is_shut_down {
+            return;
+        }
+
+        let dedicated_runtime = self.runtime.take();
+        let core = self.core.clone();
+
+        // Blocking the only thread of the runtime the loop runs on would keep it from ever
+        // stopping, so the cleanup is left to that runtime and the handle returns at once
+        if dedicated_runtime.is_none() {
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::CurrentThread {
+                    warn!("The core handle was dropped on a current thread runtime, await `shutdown` to wait for the cleanup");
+                    handle.spawn(shutdown_core(core));
+                    return;
+                }
+            }
+        }
+
+        // Drop cannot await, so the cleanup runs in its own runtime and thread,
+        // which works whether or not the handle is dropped inside a multi-threaded runtime
+        let cleanup = std::thread::spawn(move || match dedicated_runtime {
+            Some(runtime) => {
+                runtime.block_on(shutdown_core(core));
+                runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+            }
+            None => match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(shutdown_core(core)),
+                Err(e) => error!("Failed to create the shutdown runtime: {}", e),
+            },
+        });
+
+        if cleanup.join().is_err() {
+            error!("The core shutdown panicked");
+        }
+    }
+}
+
+/// Stops the background tasks and leaves the devices idle
+///
+/// # Arguments
+/// - `core`: The state of the core, whose handlers are no longer called once it is shut down.
+async fn shutdown_core(core: Arc<CoreState>) {
+    info!("Shutting down the core...");
+    core.shutdown_requested.store(true, Ordering::SeqCst);
+
+    // Let the current tick finish, so the state machine does not reconnect the headset afterwards
+    let deadline = tokio::time::Instant::now() + SHUTDOWN_TIMEOUT;
+    while !core.state_machine_stopped.load(Ordering::SeqCst) && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    if !core.state_machine_stopped.load(Ordering::SeqCst) {
+        warn!("The state machine did not stop in time, cancelling its operation in progress");
+        core.cancellation.cancel();
+
+        // The loop never reached `close_for_shutdown`, so the session and the files are closed
+        // here, unless the stuck state still holds the context once the operation is cancelled
+        match tokio::time::timeout(SHUTDOWN_TIMEOUT, core.context.lock()).await {
+            Ok(mut context) => {
+                if !core.files_closed.swap(true, Ordering::SeqCst) {
+                    context.close_for_shutdown();
+                }
+            }
+            Err(_) => error!("Could not reach the context on shutdown, the journal and the recording may be incomplete"),
+        }
+    }
+
+    // The requests left are never served, the frontends waiting on a command are told so
+    if let Ok(mut requests) = core.requests.lock() {
+        requests.clear();
+    }
+
+    // The devices of this core, not the ones of another core of the process
+    let bulb_adapter = get_smart_bulb_adapter(&core.adapters);
+    if let Err(e) = bulb_adapter.read().await.change_state(BulbState::BulbOff).await {
+        error!("Failed to turn the bulb off on shutdown: {}", e);
+    }
+
+    let headset_adapter = core.headset();
+    let mut headset = headset_adapter.write().await;
+
+    if headset.is_connected() {
+        if let Err(e) = headset.disconnect() {
+            error!("Failed to stop the headset on shutdown: {}", e);
+        }
+    }
+
+    core.events.clear();
+}
+
+/// Request a summary of the context of the last core initialized
+#[deprecated(note = "Use `CoreClient::request_context_snapshot`, which drives the core it was taken from")]
+pub fn request_context_snapshot() {
+    current_core().request_context_snapshot();
+}
+
+/// Request a diagnostic bundle of the last core initialized
+#[deprecated(note = "Use `CoreClient::create_diagnostic_bundle`, which drives the core it was taken from")]
+pub fn create_diagnostic_bundle(path: Option<&str>) {
+    current_core().create_diagnostic_bundle(path);
+}
+
+/// Request a verification of the pipeline of the last core initialized with the test signal of the headset
+#[deprecated(note = "Use `CoreClient::request_test_signal`, which drives the core it was taken from")]
+pub fn request_test_signal() {
+    current_core().request_test_signal();
+}
+
+/// Request a new calibration of the headset of the last core initialized, without waiting for its outcome
+#[deprecated(note = "Use `CoreClient::request_recalibration`, which reports whether the headset was recalibrated")]
+pub fn request_recalibration() {
+    let _ = current_core().request_recalibration();
+}
+
+/// Request the last core initialized to record the extracted EEG windows to a file
+#[deprecated(note = "Use `CoreClient::start_recording`, which drives the core it was taken from")]
+pub fn start_recording(path: &str) {
+    current_core().start_recording(path);
+}
+
+/// Request the last core initialized to stop the recording in progress, if any
+#[deprecated(note = "Use `CoreClient::stop_recording`, which drives the core it was taken from")]
+pub fn stop_recording() {
+    current_core().stop_recording();
+}
+
+/// Request the last core initialized to open a session independent of the headset connection
+#[deprecated(note = "Use `CoreClient::start_session`, which drives the core it was taken from")]
+pub fn start_session(recording_path: Option<&str>) {
+    current_core().start_session(recording_path);
+}
+
+/// Request the last core initialized to close the session opened by `start_session`
+#[deprecated(note = "Use `CoreClient::end_session`, which drives the core it was taken from")]
+pub fn end_session() {
+    current_core().end_session();
+}
+
+/// Request the last core initialized to change the profile of the user wearing the headset
+#[deprecated(note = "Use `CoreClient::set_user_profile`, which drives the core it was taken from")]
+pub fn set_user_profile(user_profile: &str) {
+    current_core().set_user_profile(user_profile);
+}
+
+/// Cancel the long-running operation in progress of the last core initialized, if any
+#[deprecated(note = "Use `CoreClient::cancel_operation`, which drives the core it was taken from")]
+pub fn cancel_operation() {
+    current_core().cancel_operation();
+}
+
+/// Request the last core initialized to search the headset again after its search was cancelled
+#[deprecated(note = "Use `CoreClient::resume_headset_search`, which drives the core it was taken from")]
+pub fn resume_headset_search() {
+    current_core().resume_headset_search();
+}
+
+/// Request the last core initialized to change the level of the logs
+#[deprecated(note = "Use `CoreClient::set_log_level`, which drives the core it was taken from")]
+pub fn set_log_level(level: &str) -> Result<(), String> {
+    current_core().set_log_level(level)
+}
+
+/// Record an action of the operator in the session journal of the last core initialized
+#[deprecated(note = "Use `CoreClient::record_operator_action`, which drives the core it was taken from")]
+pub fn record_operator_action(action: OperatorAction) {
+    current_core().record_operator_action(action);
+}
+
+/// Statistics of a session of the last core initialized
+#[deprecated(note = "Use `CoreClient::session_stats`, which drives the core it was taken from")]
+pub fn get_session_stats(session_id: u64) -> impl Future<Output = Result<Option<SessionStats>, String>> + Send + 'static {
+    current_core().session_stats(session_id)
+}
+
+/// Health of the last core initialized, or `None` if there is none running
+#[deprecated(note = "Use `CoreClient::health`, which reads the core it was taken from")]
+pub fn core_health() -> Option<CoreHealth> {
+    current_core().health()
+}
+
+/// Metrics of the last core initialized, or `None` if there is none running
+#[deprecated(note = "Use `CoreClient::metrics`, which reads the core it was taken from")]
+pub fn core_metrics() -> Option<MetricsSnapshot> {
+    current_core().metrics()
+}
+
+/// Replace the calibration thresholds of the last core initialized
+#[deprecated(note = "Use `CoreClient::apply_calibration_thresholds`, which drives the core it was taken from")]
+pub fn apply_calibration_thresholds(thresholds: CalibrationThresholds) -> Result<(), String> {
+    current_core().apply_calibration_thresholds(thresholds)
+}
+
+/// Send a raw configuration command to the EEG headset of the last core initialized
+///
+/// Escape hatch for advanced users, used by the hidden console of the GUI to send
+/// BrainFlow config strings (gain changes, test signal mode...) without rebuilding
+/// the adapter. The state machine is not aware of the changes made this way.
+///
+/// # Arguments
+/// - `command`: The command, sent as is to the device.
+///
+/// # Returns
+/// - `Result<String, String>`: The response of the device, or an error message if it fails.
+pub async fn send_headset_command(command: &str) -> Result<String, String> {
+    let headset_adapter = current_core().headset()?;
+    let headset = headset_adapter.read().await;
+
+    headset.send_raw_command(command)
+}
+
+/// Dump the raw frames of the EEG headset of the last core initialized to a ring-buffer file, or stop it
+///
+/// Used by the hidden console of the GUI to diagnose channel-index mapping problems
+/// across boards without recompiling the adapter.
+///
+/// # Arguments
+/// - `path`: The file the frames are dumped to, `None` to stop dumping them.
+///
+/// # Returns
+/// - `Result<(), String>`: An error message if the headset has no raw frames to dump.
+pub async fn set_headset_frame_dump(path: Option<&str>) -> Result<(), String> {
+    let headset_adapter = current_core().headset()?;
+    let headset = headset_adapter.read().await;
+
+    headset.set_frame_dump(path)
+}
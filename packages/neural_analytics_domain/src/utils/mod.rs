@@ -0,0 +1,299 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use log::{debug, error, warn};
+
+use presage::Event;
+
+use crate::{
+    domain::{
+        events::{operation_progress_event::OperationProgressEvent, NeuralAnalyticsEvents},
+        models::{
+            event_data::EventData,
+            event_routing::{EventDestination, EventRouting, EventSeverity},
+            operation_progress::OperationProgress,
+        },
+    },
+};
+
+/// Handler of the external events of a core, owned by its `CoreHandle`
+///
+/// The handler is only `Send`, while the state machine and the heartbeat send events from
+/// different threads, so it is called behind a mutex, one event at a time.
+pub(crate) type SharedEventHandler = Arc<Mutex<Box<dyn Fn(&String, &EventData) -> Result<(), String> + Send>>>;
+
+/// Sends the external events of a core to its handlers
+///
+/// Each core has its own dispatcher, created by its initialization and handed to the
+/// state machine and the background tasks sending events, so the events of a core never
+/// reach the handlers of another one.
+#[derive(Default)]
+pub(crate) struct EventDispatcher {
+    // Handler the core was started with, cleared by its shutdown
+    handler: RwLock<Option<SharedEventHandler>>,
+    // Handlers added with `CoreHandle::add_event_handler`, by their id
+    subscribers: Mutex<Vec<(u64, SharedEventHandler)>>,
+    // Id of the last handler added
+    subscriber_id: AtomicU64,
+    // Sequence number of the last external event sent
+    sequence: AtomicU64,
+    routing: EventRouting,
+}
+
+impl EventDispatcher {
+    /// Dispatcher sending the events to `handler`, filtered by `routing`.
+    pub fn new(handler: SharedEventHandler, routing: EventRouting) -> Self {
+        Self {
+            handler: RwLock::new(Some(handler)),
+            routing,
+            ..Default::default()
+        }
+    }
+
+    /// Lowest severity each destination receives, for the bridges forwarding the messages of the core.
+    pub fn routing(&self) -> EventRouting {
+        self.routing
+    }
+
+    /// Removes the handler of the core and the handlers added to it, once the core is shut down.
+    pub fn clear(&self) {
+        if let Ok(mut handler) = self.handler.write() {
+            *handler = None;
+        }
+
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.clear();
+        }
+    }
+
+    /// Adds a handler the external events are sent to after the handler of the core.
+    ///
+    /// # Returns
+    /// - `u64`: The id of the handler, to remove it with `remove_subscriber`.
+    pub fn add_subscriber(&self, event_handler: SharedEventHandler) -> u64 {
+        let id = self.subscriber_id.fetch_add(1, Ordering::SeqCst) + 1;
+
+        match self.subscribers.lock() {
+            Ok(mut subscribers) => subscribers.push((id, event_handler)),
+            Err(e) => error!("Failed to add the event handler: {}", e),
+        }
+
+        id
+    }
+
+    /// Removes a handler added with `add_subscriber`.
+    pub fn remove_subscriber(&self, id: u64) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|(subscriber_id, _)| *subscriber_id != id);
+        }
+    }
+
+    /// Sends an event to the handler of the core, stamping the payload with
+    /// the next sequence number so consumers can detect gaps, duplicates and
+    /// ordering issues. The events reach the handler in the order of their
+    /// sequence numbers.
+    ///
+    /// Events below the severity routed to a destination skip it. Only the
+    /// events reaching a handler are numbered, so filtering leaves no gaps.
+    ///
+    /// The handlers added with `CoreHandle::add_event_handler` then receive the
+    /// same event, in the order they were added, if it is routed to the
+    /// subscribers. Their errors are only logged.
+    ///
+    /// # Parameters
+    /// - `event`: Event name/identifier
+    /// - `data`: Event payload data
+    ///
+    /// # Returns
+    /// - `Result<(), String>`: Success or error message
+    pub fn send(&self, event: &String, data: &EventData) -> Result<(), String> {
+        // Events unknown to the registry are routed as routine updates
+        let registered_event = NeuralAnalyticsEvents::from_string(event);
+        let severity = registered_event
+            .as_ref()
+            .map(|event| event.severity())
+            .unwrap_or(EventSeverity::Info);
+
+        if self.routing.is_routed(EventDestination::Log, severity) {
+            match severity {
+                EventSeverity::Info => debug!("Event '{}'", event),
+                EventSeverity::Warning => warn!("Event '{}'", event),
+                EventSeverity::Critical => error!("Event '{}'", event),
+            }
+        }
+
+        let is_frontend_routed = self.routing.is_routed(EventDestination::Frontend, severity);
+        let is_subscribers_routed = self.routing.is_routed(EventDestination::Subscribers, severity);
+
+        if !is_frontend_routed && !is_subscribers_routed {
+            return Ok(());
+        }
+
+        let event_handler = self.handler.read().ok().and_then(|event_handler| event_handler.clone());
+
+        // Send the event to the event handler
+        if let Some(event_handler) = event_handler {
+            // Held while the sequence number is taken, so the events are handled in its order
+            let event_handler = event_handler
+                .lock()
+                .map_err(|e| format!("The event handler panicked on a previous event: {}", e))?;
+
+            let data = EventData {
+                sequence: Some(self.sequence.fetch_add(1, Ordering::SeqCst) + 1),
+                ui_hint: registered_event.and_then(|event| event.ui_hint()),
+                ..data.clone()
+            };
+
+            let result = if is_frontend_routed {
+                event_handler(event, &data)
+            } else {
+                Ok(())
+            };
+
+            if let Err(ref e) = result {
+                error!("Error sending event '{}': {}", event, e);
+            } else {
+                debug!("Event '{}' sent successfully", event);
+            }
+
+            // Still under the lock of the core handler, so they get the events in order too
+            let subscribers = if is_subscribers_routed {
+                self.subscribers.lock().map(|subscribers| subscribers.clone()).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            for (id, subscriber) in subscribers {
+                let subscriber_result = subscriber
+                    .lock()
+                    .map_err(|e| format!("The handler panicked on a previous event: {}", e))
+                    .and_then(|subscriber| subscriber(event, &data));
+
+                if let Err(e) = subscriber_result {
+                    error!("Error sending event '{}' to the handler {}: {}", event, id, e);
+                }
+            }
+
+            result
+        } else {
+            Err("No running core to handle the event".to_string())
+        }
+    }
+
+    /// Reports the progress of a long-running operation.
+    ///
+    /// Unlike the notifications of the context, published once the tick ends,
+    /// the progress is sent right away, while the operation still runs.
+    ///
+    /// # Parameters
+    /// - `progress`: The progress of the operation
+    pub fn send_progress(&self, progress: OperationProgress) {
+        debug!("Operation progress: {:?}", progress);
+
+        let _ = self.send(
+            &OperationProgressEvent::NAME.to_string(),
+            &EventData {
+                operation_progress: Some(progress),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Helper function to send events to the external subscribers of the last core initialized.
+///
+/// # Parameters
+/// - `event`: Event name/identifier
+/// - `data`: Event payload data
+///
+/// # Returns
+/// - `Result<(), String>`: Success or error message
+#[deprecated(note = "Each core sends its events through its own handlers, see `CoreHandle::add_event_handler`")]
+pub fn send_event(event: &String, data: &EventData) -> Result<(), String> {
+    crate::current_events()
+        .ok_or_else(|| "No running core to handle the event".to_string())?
+        .send(event, data)
+}
+
+/// Helper function to report the progress of a long-running operation of the last core initialized.
+///
+/// # Parameters
+/// - `progress`: The progress of the operation
+#[deprecated(note = "Each core reports the progress of its operations through its own handlers")]
+pub fn send_progress(progress: OperationProgress) {
+    if let Some(events) = crate::current_events() {
+        events.send_progress(progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_handler(sink: Arc<Mutex<Vec<(&'static str, Option<u64>)>>>, name: &'static str) -> SharedEventHandler {
+        Arc::new(Mutex::new(Box::new(move |_: &String, data: &EventData| {
+            sink.lock().unwrap().push((name, data.sequence));
+            Ok(())
+        })))
+    }
+
+    #[test]
+    fn test_send_reaches_the_handler_of_the_core_until_cleared() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let events = EventDispatcher::new(recording_handler(received.clone(), "core"), EventRouting::default());
+
+        events.send(&"handler_test_event".to_string(), &EventData::default()).unwrap();
+        events.send(&"handler_test_event".to_string(), &EventData::default()).unwrap();
+        events.clear();
+        let cleared = events.send(&"handler_test_event".to_string(), &EventData::default());
+
+        assert_eq!(*received.lock().unwrap(), vec![("core", Some(1)), ("core", Some(2))]);
+        assert!(cleared.is_err());
+    }
+
+    #[test]
+    fn test_added_handlers_receive_the_events_until_removed() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let events = EventDispatcher::new(recording_handler(received.clone(), "core"), EventRouting::default());
+        let id = events.add_subscriber(recording_handler(received.clone(), "subscriber"));
+
+        events.send(&"subscriber_test_event".to_string(), &EventData::default()).unwrap();
+        events.remove_subscriber(id);
+        events.send(&"subscriber_test_event".to_string(), &EventData::default()).unwrap();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![("core", Some(1)), ("subscriber", Some(1)), ("core", Some(2))]
+        );
+    }
+
+    #[test]
+    fn test_added_handlers_only_receive_the_events_routed_to_the_subscribers() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let routing = EventRouting {
+            subscribers: EventSeverity::Warning,
+            ..Default::default()
+        };
+        let events = EventDispatcher::new(recording_handler(received.clone(), "core"), routing);
+        events.add_subscriber(recording_handler(received.clone(), "subscriber"));
+
+        events.send(&"subscriber_test_event".to_string(), &EventData::default()).unwrap();
+        events.send(&NeuralAnalyticsEvents::BulbConnectionStateEvent.to_string(), &EventData::default()).unwrap();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![("core", Some(1)), ("core", Some(2)), ("subscriber", Some(2))]
+        );
+    }
+
+    #[test]
+    fn test_the_events_of_a_core_do_not_reach_another_core() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let first = EventDispatcher::new(recording_handler(received.clone(), "first"), EventRouting::default());
+        let second = EventDispatcher::new(recording_handler(received.clone(), "second"), EventRouting::default());
+
+        first.send(&"isolation_test_event".to_string(), &EventData::default()).unwrap();
+        second.send(&"isolation_test_event".to_string(), &EventData::default()).unwrap();
+
+        assert_eq!(*received.lock().unwrap(), vec![("first", Some(1)), ("second", Some(1))]);
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use neural_analytics_bridge::domain::models::prediction_action::PredictionAction;
+use slint::Color;
+
+// Colors given to the classes by position, so the default red, green and trash keep theirs
+const CLASS_PALETTE: [(u8, u8, u8); 8] = [
+    (0xFF, 0x00, 0x00),
+    (0x00, 0xFF, 0x00),
+    (0xFF, 0x00, 0xFF),
+    (0x00, 0x80, 0xFF),
+    (0xFF, 0xA5, 0x00),
+    (0xFF, 0xFF, 0x00),
+    (0x00, 0xFF, 0xFF),
+    (0x80, 0x00, 0xFF),
+];
+
+// Color of the classes the model does not report
+const UNKNOWN_CLASS_COLOR: (u8, u8, u8) = (0xFF, 0x00, 0xFF);
+
+// Colors the configuration sets the bulb to, which the views show for the same classes
+static CONFIGURED_COLORS: LazyLock<Mutex<HashMap<String, Color>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Classes of the model, in the order of its output, reported by the core
+static CLASS_LABELS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Takes the colors of the classes mapped to a `Color` action, so the thinking color
+/// shown matches the light of the bulb
+pub fn set_configured_class_colors(action_mapping: &HashMap<String, PredictionAction>) {
+    let colors = action_mapping
+        .iter()
+        .filter_map(|(class, action)| match action {
+            PredictionAction::Color { red, green, blue } => Some((class.clone(), Color::from_rgb_u8(*red, *green, *blue))),
+            _ => None,
+        })
+        .collect();
+
+    if let Ok(mut current) = CONFIGURED_COLORS.lock() {
+        *current = colors;
+    }
+}
+
+/// Changes the classes of the model, when the core initializes or reloads it
+pub fn set_class_labels(class_labels: Vec<String>) {
+    if let Ok(mut current) = CLASS_LABELS.lock() {
+        *current = class_labels;
+    }
+}
+
+/// Color to show a predicted class with: the one of its `Color` action if configured,
+/// otherwise the one of its position in the classes of the model
+pub fn class_color(class: &str) -> Color {
+    let configured = CONFIGURED_COLORS.lock().ok().and_then(|colors| colors.get(class).copied());
+    let by_position = || {
+        CLASS_LABELS
+            .lock()
+            .ok()
+            .and_then(|labels| labels.iter().position(|label| label == class))
+            .map(|index| CLASS_PALETTE[index % CLASS_PALETTE.len()])
+            .map(|(red, green, blue)| Color::from_rgb_u8(red, green, blue))
+    };
+
+    let (red, green, blue) = UNKNOWN_CLASS_COLOR;
+    configured.or_else(by_position).unwrap_or(Color::from_rgb_u8(red, green, blue))
+}
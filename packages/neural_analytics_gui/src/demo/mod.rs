@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use neural_analytics_bridge::domain::events::NeuralAnalyticsEvents;
+use neural_analytics_bridge::domain::models::{
+    bulb_state::BulbState, channel_config::ChannelConfig,
+    impedance_snapshot::{ImpedanceSnapshot, ImpedanceUnit},
+    signal_unit::SignalUnit, window_prediction::WindowPrediction,
+};
+use neural_analytics_bridge::domain::services::model_inference_service::default_class_labels;
+use neural_analytics_bridge::{BulbActuation, CoreEvent, CoreEventSender};
+use rand::Rng;
+
+// Same window size and channels the core produces, so plots look alike
+const WINDOW_SAMPLES: usize = 62;
+const CHANNELS: [(&str, f32); 4] = [("T3", 8.0), ("T4", 10.0), ("O1", 12.0), ("O2", 20.0)];
+
+// Pace of the scripted session
+const STEP_DELAY: Duration = Duration::from_millis(1500);
+const WINDOW_DELAY: Duration = Duration::from_millis(250);
+const WINDOWS_PER_COLOR: usize = 12;
+
+/// Checks if the GUI was started in demo mode
+///
+/// Demo mode is enabled with the `--demo` flag or `NEURAL_ANALYTICS_DEMO=true`.
+pub fn is_demo_mode() -> bool {
+    std::env::args().any(|arg| arg == "--demo")
+        || std::env::var("NEURAL_ANALYTICS_DEMO").is_ok_and(|value| value == "true")
+}
+
+/// Feeds the views with generated data and scripted state transitions
+///
+/// The core is never initialized: the same events it would emit are sent
+/// straight through the bridge channel, so the `.slint` files and the plot rendering
+/// can be iterated on without a headset or a smart bulb.
+///
+/// # Arguments
+/// - `sender`: The bridge sender the GUI receives the core events from.
+pub async fn run_demo_session(sender: CoreEventSender) {
+    let send = |event: CoreEvent| {
+        if sender.send(event).is_err() {
+            eprintln!("Demo mode: the core event receiver was dropped");
+        }
+    };
+
+    send(CoreEvent::Initialized {
+        diagnostics_issues: Vec::new(),
+        channel_config: ChannelConfig::default(),
+        signal_unit: SignalUnit::Microvolts,
+        class_labels: default_class_labels(),
+        ui_hint: NeuralAnalyticsEvents::InitializedCoreEvent.ui_hint(),
+    });
+    tokio::time::sleep(STEP_DELAY).await;
+
+    send(CoreEvent::HeadsetConnected {
+        ui_hint: NeuralAnalyticsEvents::HeadsetConnectedEvent.ui_hint(),
+    });
+    tokio::time::sleep(STEP_DELAY).await;
+
+    // Impedances drop until every electrode has a good contact
+    for impedance in [1800u16, 1300, 900, 400] {
+        let impedance_data = CHANNELS
+            .iter()
+            .map(|(channel, _)| (channel.to_string(), impedance))
+            .collect();
+
+        send(CoreEvent::HeadsetCalibrating { impedance_data });
+        tokio::time::sleep(STEP_DELAY).await;
+    }
+
+    send(CoreEvent::HeadsetCalibrated {
+        impedance: Some(ImpedanceSnapshot {
+            impedances: CHANNELS.iter().map(|(channel, _)| (channel.to_string(), 400.0)).collect(),
+            unit: ImpedanceUnit::Kiloohms,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as i64)
+                .unwrap_or_default(),
+        }),
+        ui_hint: NeuralAnalyticsEvents::HeadsetCalibratedEvent.ui_hint(),
+    });
+
+    let mut window = 0usize;
+
+    loop {
+        let color = if (window / WINDOWS_PER_COLOR).is_multiple_of(2) { "green" } else { "red" };
+
+        // The bulb follows the color when it changes, like the default action mapping
+        let actuation = window.is_multiple_of(WINDOWS_PER_COLOR).then(|| BulbActuation {
+            bulb_state: if color == "green" { BulbState::BulbOn } else { BulbState::BulbOff },
+            is_confirmed: true,
+            is_simulated: true,
+            latency_ms: Some(0),
+            causality: None,
+        });
+
+        send(CoreEvent::PredictionActed {
+            window_id: Some(window as u64),
+            headset_data: generate_window(window),
+            color_thinking: Some(color.to_string()),
+            actuation,
+            band_powers: None,
+            window_prediction: Some(generate_prediction(color)),
+        });
+
+        window += 1;
+        tokio::time::sleep(WINDOW_DELAY).await;
+    }
+}
+
+/// Generates a prediction of the color with a random confidence, the rest spread on the other classes
+fn generate_prediction(color: &str) -> WindowPrediction {
+    let confidence = rand::thread_rng().gen_range(0.55..0.95);
+    let labels = default_class_labels();
+    let other = (1.0 - confidence) / (labels.len() - 1) as f32;
+
+    WindowPrediction {
+        label: color.to_string(),
+        confidence: Some(confidence),
+        probabilities: labels
+            .into_iter()
+            .map(|label| {
+                let probability = if label == color { confidence } else { other };
+                (label, probability)
+            })
+            .collect(),
+        latency_ms: 0.0,
+    }
+}
+
+/// Generates a window of sine waves with noise, one frequency per channel
+fn generate_window(window: usize) -> HashMap<String, Vec<f32>> {
+    let mut rng = rand::thread_rng();
+    let offset = window * WINDOW_SAMPLES;
+
+    CHANNELS
+        .iter()
+        .map(|(channel, frequency)| {
+            let values = (0..WINDOW_SAMPLES)
+                .map(|i| {
+                    let t = (offset + i) as f32 / 256.0;
+                    (2.0 * PI * frequency * t).sin() * 40.0 + rng.gen_range(-10.0..10.0)
+                })
+                .collect();
+
+            (channel.to_string(), values)
+        })
+        .collect()
+}
@@ -1,38 +1,78 @@
-use neural_analytics_core::{domain::events::NeuralAnalyticsEvents, initialize_core};
-use neural_analytics_core::domain::models::event_data::EventData;
-use utils::render_signal_plot;
+use neural_analytics_bridge::{
+    core_event_channel, send_headset_command, set_headset_frame_dump, spawn_core, CoreClient, CoreEvent, CoreHandle,
+};
+use neural_analytics_bridge::config_file::{load_config, load_config_from_env, CONFIG_FILE_VARIABLE};
+use neural_analytics_bridge::event_trace::{read_event_trace, replay_event_trace, EventTraceRecorder, TracedEvent};
+use neural_analytics_bridge::domain::models::{
+    bulb_connection::BulbConnectionState,
+    bulb_state::BulbState,
+    impedance_snapshot::ImpedanceUnit,
+    operation_progress::{OperationKind, OperationStatus},
+    operator_action::OperatorAction,
+    payload_policy::EVENT_TRACE_DESTINATION,
+    prediction_action::PredictionAction,
+    signal_quality::SignalQuality,
+    ui_hint::SuggestedView,
+};
+use observer::{
+    is_observer_requested, set_observer_electrode_labels, toggle_observer_window,
+    update_observer_headset_data,
+};
+use notifications::{
+    bind_notification_center, push_notification, push_notification_with_action, NotificationLevel,
+    warnings::{LowBatteryWarning, LowConfidenceStreak, LOW_CONFIDENCE_STREAK},
+};
+use class_colors::{class_color, set_class_labels, set_configured_class_colors};
+use plot_grid::{bind_plot_grid, plot_channels, plot_labels, set_plot_channels, update_plot_data};
+use strip_chart::{clear_strip_history, push_strip_window, render_strip_chart, set_strip_labels};
+use utils::{bind_plot_rendering, cycle_plot_scale, render_signal_plot, set_display_preferences, set_signal_unit};
 use std::process::exit;
 use std::sync::{Mutex, LazyLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::vec;
-use slint::{ComponentHandle, ModelRc, SharedString, Weak};
+use slint::{Color, ComponentHandle, ModelRc, SharedString, Weak};
 
+pub mod class_colors;
+pub mod demo;
+pub mod notifications;
+pub mod observer;
+pub mod plot_grid;
+pub mod strip_chart;
 pub mod utils;
 
 slint::include_modules!();
 
+// Hardware names of the electrodes, in the order the views show them
+const ELECTRODES: [&str; 4] = ["T3", "T4", "O1", "O2"];
+
+// Time without EEG windows before warning about data starvation
+const DATA_STARVATION_MS: i64 = 10_000;
+
+// Handle of the running core, dropped on close to leave the devices idle
+static CORE_HANDLE: Mutex<Option<CoreHandle>> = Mutex::new(None);
+
+// Whether the user paused the capture through the core handle
+static IS_CAPTURE_PAUSED: AtomicBool = AtomicBool::new(false);
+
+// Warnings derived from the heartbeats and the predictions, raised once per occurrence
+static LOW_BATTERY: Mutex<LowBatteryWarning> = Mutex::new(LowBatteryWarning::new());
+static LOW_CONFIDENCE: Mutex<LowConfidenceStreak> = Mutex::new(LowConfidenceStreak::new());
+
 // Global storage for our main window reference
 static MAIN_WINDOW_WEAK: LazyLock<Mutex<Option<Weak<MainFrame>>>> = LazyLock::new(|| Mutex::new(None));
 
-/// Event handler function
+/// Core event handler
 /// 
-/// This function is called when an event occurs. It takes a string and an `EventData` struct as arguments.
+/// This function is called for every event received from the core bridge.
 /// This is part of Model View Intent (MVI) pattern. Communicates with the UI thread to update the view.
 /// 
 /// # Arguments
-/// - `event`: A string representing the event name.
-/// - `data`: An `EventData` struct containing the data associated with the event.
+/// - `event`: The typed `CoreEvent` sent by the core.
 /// 
 /// # Returns
 /// - `Result<(), String>`: Returns `Ok(())` if the event is handled successfully, or an error message if it fails.
-fn event_handler(event: &String, data: &EventData) -> Result<(), String> {
-    // Clone the event name to avoid borrowing issues
-    let event_name = event.clone();
-    
-    // Clone the data to avoid borrowing issues
-    let impedance_data_clone = data.impedance_data.clone(); 
-    let headset_data_clone = data.headset_data.clone();
-    let color_thinking_clone = data.color_thinking.clone();
-    
+fn handle_core_event(event: CoreEvent) -> Result<(), String> {
     // Execute on UI thread to avoid threading issues
     slint::invoke_from_event_loop(move || {
         let main_window = match MAIN_WINDOW_WEAK.lock().unwrap().as_ref() {
@@ -43,60 +83,533 @@ fn event_handler(event: &String, data: &EventData) -> Result<(), String> {
             None => return,
         };
 
-        // Handle the event based on its name
-        match event_name.as_str() {
-            val if val == NeuralAnalyticsEvents::InitializedCoreEvent.to_string() => {
-                main_window.invoke_update_current_view(SharedString::from("WelcomeUserView"));
+        // The events of a state transition tell which view the state is shown in
+        if let Some(ui_hint) = event.ui_hint() {
+            main_window.invoke_update_current_view(SharedString::from(view_name(ui_hint.suggested_view)));
+        }
+
+        match event {
+            CoreEvent::Initialized { diagnostics_issues, channel_config, signal_unit, class_labels, .. } => {
+                set_signal_unit(signal_unit);
+                set_class_labels(class_labels);
+
+                // The views show the channels in the hardware order, with their configured labels
+                let electrode_labels: Vec<SharedString> = ELECTRODES
+                    .iter()
+                    .map(|electrode| SharedString::from(channel_config.display_name_of(electrode)))
+                    .collect();
+                main_window.set_electrode_labels(ModelRc::from(&electrode_labels[..]));
+                set_observer_electrode_labels(ModelRc::from(&electrode_labels[..]));
+
+                // The data capture view plots every configured channel, until the data tells otherwise
+                set_plot_channels(
+                    &main_window,
+                    channel_config
+                        .channels
+                        .iter()
+                        .map(|channel| {
+                            let label = channel_config.display_name_of(&channel.hardware_name).to_string();
+                            (channel.hardware_name.clone(), label)
+                        })
+                        .collect(),
+                );
+                set_strip_labels(plot_labels());
+
+                for issue in &diagnostics_issues {
+                    push_notification(NotificationLevel::Warning, issue);
+                }
             },
-            val if val == NeuralAnalyticsEvents::HeadsetConnectedEvent.to_string() => {
-                main_window.invoke_update_current_view(SharedString::from("HeadsetCalibrationView"));
+            CoreEvent::HeadsetCalibrating { impedance_data } => {
+                main_window.invoke_update_electrode_status(
+                    impedance_data.get("T3").cloned().unwrap_or(0) as i32,
+                    impedance_data.get("T4").cloned().unwrap_or(0) as i32,
+                    impedance_data.get("O1").cloned().unwrap_or(0) as i32,
+                    impedance_data.get("O2").cloned().unwrap_or(0) as i32,
+                );
             },
-            val if val == NeuralAnalyticsEvents::HeadsetDisconnectedEvent.to_string() => {
-                main_window.invoke_update_current_view(SharedString::from("WelcomeUserView"));
+            CoreEvent::HeadsetCalibrated { impedance: Some(impedance), .. } => {
+                // The success of the calibration lists the final impedance of every electrode
+                let impedance = impedance.in_unit(ImpedanceUnit::Kiloohms);
+                let electrodes: Vec<String> = ELECTRODES
+                    .iter()
+                    .filter_map(|electrode| {
+                        let value = impedance.impedances.get(*electrode)?;
+                        Some(format!("{} {:.0} {}", electrode, value, impedance.unit.symbol()))
+                    })
+                    .collect();
+
+                push_notification(NotificationLevel::Info, &format!("Headset calibrated: {}.", electrodes.join(", ")));
             },
-            val if val == NeuralAnalyticsEvents::HeadsetCalibratingEvent.to_string() => {
-                if let Some(impedance_data) = &impedance_data_clone {
-                    main_window.invoke_update_electrode_status(
-                        impedance_data.get("T3").cloned().unwrap_or(0) as i32,
-                        impedance_data.get("T4").cloned().unwrap_or(0) as i32,
-                        impedance_data.get("O1").cloned().unwrap_or(0) as i32,
-                        impedance_data.get("O2").cloned().unwrap_or(0) as i32,
+            CoreEvent::CheckingSignalQuality { signal_quality } => {
+                // Shown on the same indicators as the impedance, a missing electrode as detached
+                let [t3, t4, o1, o2] = ELECTRODES.map(|electrode| {
+                    signal_quality_status(signal_quality.get(electrode).copied().unwrap_or(SignalQuality::Flat))
+                });
+
+                main_window.invoke_update_electrode_status(t3, t4, o1, o2);
+            },
+            CoreEvent::PredictionActed { headset_data, color_thinking, actuation, window_prediction, .. } => {
+                // The grid follows the channels of the data, and the stacked chart the ones of the grid
+                if update_plot_data(&main_window, &headset_data) {
+                    set_strip_labels(plot_labels());
+                }
+
+                // The stacked chart keeps the last seconds, before the views are updated
+                let windows: Vec<Vec<f32>> = plot_channels()
+                    .iter()
+                    .map(|channel| headset_data.get(channel).cloned().unwrap_or_default())
+                    .collect();
+                push_strip_window(&windows);
+                main_window.invoke_update_strip_chart();
+
+                // The observer window shows the same window of data, for the four electrodes of the BrainBit headset
+                let [t3, t4, o1, o2] = ELECTRODES.map(|electrode| {
+                    ModelRc::from(&headset_data.get(electrode).cloned().unwrap_or(vec![0.0])[..])
+                });
+                update_observer_headset_data(t3, t4, o1, o2);
+
+                if let Some(color_thinking) = &color_thinking {
+                    main_window.invoke_update_thinking_color(
+                        SharedString::from(color_thinking.to_uppercase()),
+                        class_color(color_thinking),
                     );
                 }
+
+                // A cached window repeats the previous prediction, so its confidence is kept
+                if let Some(prediction) = &window_prediction {
+                    main_window.invoke_update_prediction_confidence(prediction.confidence.unwrap_or(-1.0));
+
+                    if LOW_CONFIDENCE.lock().unwrap().observe(prediction.confidence) {
+                        push_notification_with_action(
+                            NotificationLevel::Warning,
+                            &format!(
+                                "The model was unsure of the last {} predictions, check the contact of the electrodes.",
+                                LOW_CONFIDENCE_STREAK
+                            ),
+                            "Recalibrate",
+                            recalibrate,
+                        );
+                    }
+                }
+
+                // The bulb is updated with the color that caused it, never apart from it
+                if let Some(actuation) = actuation {
+                    show_bulb_actuation(&main_window, actuation.bulb_state, actuation.is_confirmed, actuation.is_simulated);
+                }
             },
-            val if val == NeuralAnalyticsEvents::HeadsetCalibratedEvent.to_string() => {
-                main_window.invoke_update_current_view(SharedString::from("DataCapturerView"));
+            CoreEvent::PredictionExplanation { channel_contributions, .. } => {
+                let contributions = ELECTRODES.map(|electrode| {
+                    channel_contributions.get(electrode).copied().unwrap_or_default()
+                });
+
+                main_window.invoke_update_channel_contributions(ModelRc::from(&contributions[..]));
             },
-            val if val == NeuralAnalyticsEvents::CapturedHeadsetDataEvent.to_string() => {
-                if let Some(headset_data) = &headset_data_clone {
-                    main_window.invoke_update_headset_data(
-                        ModelRc::from(&headset_data.get("T3").cloned().unwrap_or(vec![0.0])[..]),
-                        ModelRc::from(&headset_data.get("T4").cloned().unwrap_or(vec![0.0])[..]),
-                        ModelRc::from(&headset_data.get("O1").cloned().unwrap_or(vec![0.0])[..]),
-                        ModelRc::from(&headset_data.get("O2").cloned().unwrap_or(vec![0.0])[..]),
+            CoreEvent::BulbStateChanged { bulb_state, is_confirmed, is_simulated, .. } => {
+                show_bulb_actuation(&main_window, bulb_state, is_confirmed, is_simulated);
+            },
+            CoreEvent::BulbConnection { connection_state } => match connection_state {
+                BulbConnectionState::Connecting { attempt: 1, .. } => {
+                    push_notification(NotificationLevel::Info, "Connecting to the smart bulb...");
+                },
+                // The retries are only worth a notice once they run out
+                BulbConnectionState::Connecting { .. } => {},
+                BulbConnectionState::Connected => {
+                    push_notification(NotificationLevel::Info, "Smart bulb connected.");
+                },
+                BulbConnectionState::Unavailable { reason } => {
+                    push_notification(
+                        NotificationLevel::Warning,
+                        &format!("The smart bulb is unavailable, the predictions will not change the light. {}", reason),
+                    );
+                },
+            },
+            CoreEvent::MemoryPressure { .. } => {
+                push_notification(
+                    NotificationLevel::Warning,
+                    "Memory budget exceeded, the oldest buffered data was discarded.",
+                );
+            },
+            CoreEvent::ModelReloaded { model_path, class_labels } => {
+                set_class_labels(class_labels);
+                push_notification(
+                    NotificationLevel::Info,
+                    &format!("Model reloaded from {}.", model_path),
+                );
+            },
+            CoreEvent::Heartbeat { core_health } => {
+                // Data starvation: connected, but no window extracted for a while
+                let now = now_millis();
+                let is_starving = core_health.is_headset_connected
+                    && core_health
+                        .last_window_timestamp
+                        .is_some_and(|timestamp| now - timestamp > DATA_STARVATION_MS);
+
+                if is_starving {
+                    push_notification(
+                        NotificationLevel::Warning,
+                        "No EEG data received recently, check the headset.",
                     );
                 }
 
-                if let Some(color_thinking) = &color_thinking_clone {
-                    main_window.invoke_update_thinking_color(
-                        SharedString::from(color_thinking),
+                if LOW_BATTERY.lock().unwrap().observe(core_health.headset_battery_percent) {
+                    push_notification(
+                        NotificationLevel::Warning,
+                        &format!(
+                            "The headset battery is at {}%, charge it soon.",
+                            core_health.headset_battery_percent.unwrap_or_default()
+                        ),
+                    );
+                }
+            },
+            CoreEvent::SuggestedThresholds { suggestion } => {
+                let max_impedance = suggestion.thresholds.max_impedance;
+
+                if suggestion.is_applied {
+                    push_notification(
+                        NotificationLevel::Info,
+                        &format!("Calibration threshold adapted to {} kOhm.", max_impedance),
+                    );
+                } else {
+                    // The user decides whether the electrodes condition is acceptable
+                    push_notification_with_action(
+                        NotificationLevel::Info,
+                        &format!("High impedances observed, accept {} kOhm as threshold?", max_impedance),
+                        "Accept",
+                        move || {
+                            let Some(core) = core_client() else {
+                                return;
+                            };
+
+                            core.record_operator_action(OperatorAction::AcceptThresholds { max_impedance });
+
+                            if let Err(e) = core.apply_calibration_thresholds(suggestion.thresholds) {
+                                eprintln!("Failed to apply calibration thresholds: {}", e);
+                            }
+                        },
                     );
                 }
             },
-            _ => {}
+            CoreEvent::HeadsetConnected { .. } => {
+                // The signal of a reconnected headset does not continue the stacked chart
+                clear_strip_history();
+            },
+            CoreEvent::HeadsetDisconnected { .. } => {
+                // Beside the change of view, the class shown to the audience is stale
+                main_window.invoke_hide_exhibition_cue();
+            },
+            CoreEvent::HeadsetCalibrated { .. } => {
+                // Only change the view, following its hint
+            },
+            CoreEvent::ExhibitionCue { cue } => {
+                // The overlay takes the color of the bulb when the class sets one
+                let reference = match cue.action {
+                    PredictionAction::Color { red, green, blue } => Color::from_rgb_u8(red, green, blue),
+                    _ => class_color(&cue.class),
+                };
+
+                main_window.invoke_show_exhibition_cue(
+                    SharedString::from(cue.class.to_uppercase()),
+                    reference,
+                    cue.hold_ms.min(i32::MAX as u64) as i32,
+                );
+            },
+            CoreEvent::ContextSnapshot { .. } | CoreEvent::TestSignalVerified { .. } => {
+                // Only requested by the debug frontend
+            },
+            CoreEvent::StaleDataSkipped { .. } => {
+                // Counted in the metrics, the predictions resume with the next fresh window
+            },
+            CoreEvent::InferencePaused { streak, .. } => {
+                push_notification_with_action(
+                    NotificationLevel::Warning,
+                    &format!(
+                        "Predictions paused after {} unreadable windows in a row, check the contact of the electrodes.",
+                        streak
+                    ),
+                    "Recalibrate",
+                    recalibrate,
+                );
+            },
+            CoreEvent::SessionRotated { reason, .. } => {
+                push_notification(
+                    NotificationLevel::Info,
+                    &format!("{}, a new session was started.", reason),
+                );
+            },
+            CoreEvent::CalibrationReused { calibration_timestamp, .. } => {
+                let minutes = (now_millis() - calibration_timestamp).max(0) / 60_000;
+
+                push_notification_with_action(
+                    NotificationLevel::Info,
+                    &format!("Calibration from {} minutes ago reused.", minutes),
+                    "Recalibrate",
+                    recalibrate,
+                );
+            },
+            CoreEvent::ClockDriftWarning { drift_ms } => {
+                push_notification(
+                    NotificationLevel::Warning,
+                    &format!("The headset clock drifted {:.0} ms from the system clock.", drift_ms),
+                );
+            },
+            CoreEvent::UntestedFirmware { firmware_version } => {
+                push_notification(
+                    NotificationLevel::Warning,
+                    &format!("The headset firmware {} was not tested, report any issue with it.", firmware_version),
+                );
+            },
+            CoreEvent::OperationProgress { progress } => {
+                let label = match (progress.operation, progress.status) {
+                    (_, OperationStatus::Running) => match progress.operation {
+                        OperationKind::HeadsetConnection => "Connecting to the headset...",
+                        OperationKind::ModelLoad => "Loading the model...",
+                    },
+                    (OperationKind::HeadsetConnection, OperationStatus::Cancelled) => {
+                        push_notification_with_action(
+                            NotificationLevel::Info,
+                            "Headset search cancelled.",
+                            "Search",
+                            resume_headset_search,
+                        );
+                        ""
+                    },
+                    (OperationKind::ModelLoad, OperationStatus::Cancelled) => {
+                        push_notification(
+                            NotificationLevel::Info,
+                            "Model reload cancelled, the current model is kept.",
+                        );
+                        ""
+                    },
+                    // Failures are reported by the events of the operation itself
+                    _ => "",
+                };
+
+                // An empty label hides the progress
+                main_window.set_operation_label(SharedString::from(label));
+                main_window.set_operation_fraction(progress.fraction.unwrap_or(-1.0));
+                main_window.set_operation_cancellable(progress.is_cancellable);
+            },
+            CoreEvent::CommandPanicked { command, .. } => {
+                push_notification(
+                    NotificationLevel::Warning,
+                    &format!("Internal error in {}, the application keeps running.", command),
+                );
+            },
+            CoreEvent::IntegrityViolation { report } => {
+                push_notification_with_action(
+                    NotificationLevel::Warning,
+                    &format!(
+                        "Session {} aborted in window {}, {}. Its recording is intact up to that window.",
+                        report.session_id, report.window_id, report.violation
+                    ),
+                    "Search",
+                    resume_headset_search,
+                );
+            },
+            CoreEvent::DiagnosticBundleCreated { result } => match result {
+                Ok(path) => push_notification(
+                    NotificationLevel::Info,
+                    &format!("Diagnostic bundle saved to {}, attach it to your issue report.", path),
+                ),
+                Err(e) => push_notification(NotificationLevel::Warning, &e),
+            },
+            CoreEvent::ShutdownRequested => {
+                // Same as closing the window, the core is shut down before the application returns
+                drop(CORE_HANDLE.lock().unwrap().take());
+                let _ = slint::quit_event_loop();
+            },
         }
     }).map_err(|e| format!("BUG: UI thread error; {:?}", e))?;
     
     Ok(())
 }
 
+/// Client of the running core, `None` until it is initialized and once it is shut down
+fn core_client() -> Option<CoreClient> {
+    CORE_HANDLE.lock().unwrap().as_ref().map(CoreHandle::client)
+}
+
+/// Journals a recalibration asked by the operator and goes back to the calibration
+fn recalibrate() {
+    if let Some(core) = core_client() {
+        core.record_operator_action(OperatorAction::Recalibrate);
+
+        // Only asked while capturing, the views follow the calibration events
+        let _ = core.request_recalibration();
+    }
+}
+
+/// Journals a new search of the headset asked by the operator and resumes it
+fn resume_headset_search() {
+    if let Some(core) = core_client() {
+        core.record_operator_action(OperatorAction::ResumeHeadsetSearch);
+        core.resume_headset_search();
+    }
+}
+
+/// Runs a command of the hidden headset console
+///
+/// `frame_dump <path>` and `frame_dump off` toggle the dump of the raw frames of the
+/// headset, the other commands are sent to the device as is.
+async fn run_console_command(command: &str) -> Result<String, String> {
+    match command.trim().strip_prefix("frame_dump ").map(str::trim) {
+        Some("off") => set_headset_frame_dump(None).await.map(|_| "Raw frames no longer dumped".to_string()),
+        Some(path) => set_headset_frame_dump(Some(path))
+            .await
+            .map(|_| format!("Dumping raw frames to {}", path)),
+        None => send_headset_command(command).await,
+    }
+}
+
+/// Serves the remote API for the core when a token is configured
+#[cfg(feature = "remote-api")]
+fn serve_remote_api(core: CoreClient, sender: neural_analytics_bridge::CoreEventSender, config: &CoreConfig) {
+    use neural_analytics_bridge::domain::models::payload_policy::REMOTE_API_DESTINATION;
+
+    let payload_policy = config.payload_policy(REMOTE_API_DESTINATION);
+
+    if let Some(mut remote_api_config) = neural_analytics_bridge::remote_api::RemoteApiConfig::from_env() {
+        // Responses are shaped like the events of the other bridges
+        remote_api_config.payload_policy = payload_policy;
+        let remote_api = neural_analytics_bridge::remote_api::spawn_remote_api(remote_api_config, core, sender);
+
+        tokio::spawn(async move {
+            if let Ok(Err(e)) = remote_api.await {
+                eprintln!("{}", e);
+            }
+        });
+    }
+}
+
+/// Shows the state of the bulb after an actuation, warning when it was not applied
+fn show_bulb_actuation(main_window: &MainFrame, bulb_state: BulbState, is_confirmed: bool, is_simulated: bool) {
+    // Unconfirmed attempts leave the bulb as it was
+    if is_confirmed {
+        main_window.invoke_update_bulb_state(
+            bulb_state == BulbState::BulbOn,
+            is_simulated,
+        );
+    } else {
+        push_notification(
+            NotificationLevel::Warning,
+            "The smart bulb is unreachable, the last change was not applied.",
+        );
+    }
+}
+
+/// View of the main window a suggested view is shown in
+fn view_name(view: SuggestedView) -> &'static str {
+    match view {
+        SuggestedView::Loading => "LoadingApplicationView",
+        SuggestedView::Welcome => "WelcomeUserView",
+        SuggestedView::Calibration => "HeadsetCalibrationView",
+        SuggestedView::Capture => "DataCapturerView",
+    }
+}
+
+/// Impedance, in ohms, the calibration view shows a signal quality as
+fn signal_quality_status(quality: SignalQuality) -> i32 {
+    match quality {
+        SignalQuality::Good => 0,
+        // A poor contact, like an impedance the view warns about
+        SignalQuality::Noisy => 1500,
+        // A detached electrode, like an impedance the view rejects
+        SignalQuality::Flat => 2500,
+    }
+}
+
+/// Current Unix epoch milliseconds, the time base of the core timestamps
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// Validates a configuration file, the one set in `NEURAL_ANALYTICS_CONFIG` if none is given
+///
+/// # Returns
+/// - `i32`: The exit code, `0` if the file is valid.
+fn check_config(path: Option<&String>) -> i32 {
+    let Some(path) = path.cloned().or_else(|| std::env::var(CONFIG_FILE_VARIABLE).ok()) else {
+        eprintln!("--check-config expects a file, or {} to be set", CONFIG_FILE_VARIABLE);
+        return 2;
+    };
+
+    match load_config(&path) {
+        Ok(_) => {
+            println!("{} is valid", path);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Reads the event trace given to `--replay`, if any
+///
+/// # Returns
+/// - `Result<Option<Vec<TracedEvent>>, (i32, String)>`: The trace, or the exit code and the error.
+fn replay_trace(args: &[String]) -> Result<Option<Vec<TracedEvent>>, (i32, String)> {
+    let Some(index) = args.iter().position(|arg| arg == "--replay") else {
+        return Ok(None);
+    };
+
+    let Some(path) = args.get(index + 1) else {
+        return Err((2, "--replay expects an event trace file".to_string()));
+    };
+
+    read_event_trace(path).map(Some).map_err(|e| (1, e))
+}
+
+/// Installs the logger, at the level of `RUST_LOG` until `set_log_level` changes it
+///
+/// The logger itself lets every level through, the maximum level of the `log` crate
+/// filters the records instead, so the level can be raised without restarting.
+fn init_logger() {
+    let initial_level = env_logger::Builder::from_default_env().build().filter();
+
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Trace)
+        .init();
+    log::set_max_level(initial_level);
+}
+
 /// Main function
 /// 
 /// This is the entry point of the application. It creates the main window and initializes the core.
 /// It also sets the initial view and runs the application.
 #[tokio::main]
 async fn main() {
-    env_logger::init();
+    init_logger();
+
+    // Validate the configuration file without starting the pipeline
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--check-config") {
+        exit(check_config(args.get(index + 1)));
+    }
+
+    // Replay a recorded event trace instead of starting the core
+    let replay_trace = match replay_trace(&args) {
+        Ok(trace) => trace,
+        Err((code, e)) => {
+            eprintln!("{}", e);
+            exit(code);
+        }
+    };
+
+    let config = match load_config_from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration file {}", e);
+            exit(1);
+        }
+    };
+
+    // The thinking color follows the color the bulb is set to for each class
+    set_configured_class_colors(&config.action_mapping);
 
     let main_window = MainFrame::new();
 
@@ -107,15 +620,161 @@ async fn main() {
         // Store a weak reference to our window globally
         *MAIN_WINDOW_WEAK.lock().unwrap() = Some(main_window.as_weak());
         
+        // The views and the plots follow the accessibility preferences of the configuration
+        set_display_preferences(config.display);
+        let accessibility = main_window.global::<Accessibility>();
+        accessibility.set_high_contrast(config.display.high_contrast);
+        accessibility.set_large_text(config.display.large_text);
+
         // Set up the signal plot rendering
         main_window.on_render_signal_plot(render_signal_plot);
+        bind_plot_rendering(&main_window.global::<PlotRendering>());
+        main_window.on_render_strip_chart(render_strip_chart);
+        main_window.on_cycle_plot_scale(|| {
+            push_notification(NotificationLevel::Info, &format!("Signal plots: {}", cycle_plot_scale()));
+        });
+
+        // Set up the notification center
+        bind_notification_center(&main_window);
+
+        // The plots of the BrainBit electrodes, until the core tells the channels of its headset
+        bind_plot_grid(&main_window);
+        set_plot_channels(&main_window, ELECTRODES.map(|electrode| (electrode.to_string(), electrode.to_string())).to_vec());
+
+        // Set up the diagnostic bundle button, reported through a notification
+        main_window.on_create_diagnostic_bundle(|| {
+            if let Some(core) = core_client() {
+                core.create_diagnostic_bundle(None);
+            }
+        });
+
+        // Set up the observer window for a second screen
+        let observer_main_window = main_window.as_weak();
+        main_window.on_toggle_observer_window(move || {
+            if let Some(main_window) = observer_main_window.upgrade() {
+                toggle_observer_window(&main_window);
+            }
+        });
+
+        if is_observer_requested() {
+            toggle_observer_window(&main_window);
+        }
+
+        // Set up the cancel button of the long-running operations
+        main_window.on_cancel_operation(|| {
+            if let Some(core) = core_client() {
+                core.record_operator_action(OperatorAction::CancelOperation);
+                core.cancel_operation();
+            }
+        });
+
+        // Set up the annotations of the operator, journaled next to the EEG data
+        main_window.on_record_annotation(|text| {
+            if let Some(core) = core_client() {
+                core.record_operator_action(OperatorAction::Annotation { text: text.to_string() });
+            }
+        });
+
+        // Set up the pause of the capture, the core journals it and keeps the headset connected
+        let pause_window = main_window.as_weak();
+        main_window.on_toggle_capture_pause(move || {
+            let is_paused = IS_CAPTURE_PAUSED.load(Ordering::SeqCst);
+
+            let command = match CORE_HANDLE.lock().unwrap().as_ref() {
+                Some(core) if is_paused => tokio::spawn(core.resume_capture()),
+                Some(core) => tokio::spawn(core.pause_capture()),
+                None => {
+                    push_notification(NotificationLevel::Warning, "The capture cannot be paused without a running core.");
+                    return;
+                }
+            };
+
+            let pause_window = pause_window.clone();
+            tokio::spawn(async move {
+                let result = command.await.unwrap_or_else(|e| Err(e.to_string()));
+
+                let _ = pause_window.upgrade_in_event_loop(move |_| match result {
+                    Ok(()) => {
+                        IS_CAPTURE_PAUSED.store(!is_paused, Ordering::SeqCst);
+                        push_notification(
+                            NotificationLevel::Info,
+                            if is_paused { "Capture resumed." } else { "Capture paused, press F7 to resume it." },
+                        );
+                    }
+                    Err(e) => push_notification(NotificationLevel::Warning, &e),
+                });
+            });
+        });
+
+        // Set up the hidden headset console
+        let console_window = main_window.as_weak();
+        main_window.on_send_headset_command(move |command| {
+            let console_window = console_window.clone();
+
+            tokio::spawn(async move {
+                let response = match run_console_command(&command).await {
+                    Ok(response) => format!("> {}: {}", command, response),
+                    Err(e) => format!("> {}: {}", command, e),
+                };
+
+                let _ = console_window.upgrade_in_event_loop(move |main_window| {
+                    main_window.set_console_response(SharedString::from(response));
+                });
+            });
+        });
+
+        // Forward the core events to the UI, whichever the source is
+        let (sender, mut receiver) = core_event_channel();
+        let event_trace_policy = config.payload_policy(EVENT_TRACE_DESTINATION);
+
+        tokio::spawn(async move {
+            // Traces the events as the views receive them, when NEURAL_ANALYTICS_EVENT_TRACE is set
+            let mut event_trace =
+                EventTraceRecorder::from_env().map(|event_trace| event_trace.with_payload_policy(event_trace_policy));
+
+            while let Some(event) = receiver.recv().await {
+                if let Some(event_trace) = event_trace.as_mut() {
+                    event_trace.record(&event);
+                }
+
+                if let Err(e) = handle_core_event(event) {
+                    eprintln!("Failed to handle core event: {}", e);
+                }
+            }
+        });
 
         // Set up the event handler
-        main_window.on_start_core_process(|| {
-            tokio::spawn(async {
-                // Initialize the core with the event handler
-                if let Err(e) = initialize_core(event_handler).await {
-                    panic!("BUG: Failed to initialize core: {}", e);
+        main_window.on_start_core_process(move || {
+            if let Some(trace) = &replay_trace {
+                // The views go through the recorded events, the core is never initialized
+                tokio::spawn(replay_event_trace(trace.clone(), sender.clone()));
+                return true;
+            }
+
+            if demo::is_demo_mode() {
+                // Demo mode never initializes the core, the views are fed with generated data
+                tokio::spawn(demo::run_demo_session(sender.clone()));
+                return true;
+            }
+
+            let core = spawn_core(config.clone(), sender.clone());
+            #[cfg(feature = "remote-api")]
+            let remote_api_sender = sender.clone();
+            #[cfg(feature = "remote-api")]
+            let remote_api_config = config.clone();
+
+            tokio::spawn(async move {
+                // Initialize the core through the bridge
+                match core.await {
+                    Ok(Ok(handle)) => {
+                        // Serve the remote API when a token is configured, once there is a core to operate
+                        #[cfg(feature = "remote-api")]
+                        serve_remote_api(handle.client(), remote_api_sender, &remote_api_config);
+
+                        *CORE_HANDLE.lock().unwrap() = Some(handle);
+                    }
+                    Ok(Err(e)) => panic!("BUG: Failed to initialize core: {}", e),
+                    Err(e) => panic!("BUG: The core initialization panicked: {}", e),
                 }
             });
             true
@@ -125,7 +784,12 @@ async fn main() {
         main_window.invoke_update_current_view(SharedString::from("LoadingApplicationView"));
 
         main_window.window().on_close_requested(|| {
-            exit(0);
+            // Closes the session, turns the bulb off and stops the headset before leaving
+            drop(CORE_HANDLE.lock().unwrap().take());
+
+            // The observer window would keep the event loop running, and the process with it
+            let _ = slint::quit_event_loop();
+            slint::CloseRequestResponse::HideWindow
         });
         
         // Run the application
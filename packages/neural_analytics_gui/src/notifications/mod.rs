@@ -0,0 +1,134 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use slint::{Model, ModelRc, SharedString, VecModel};
+
+use crate::{MainFrame, Notification};
+
+pub mod warnings;
+
+// Older notifications are dropped beyond this size, so the queue never covers the views
+const MAX_NOTIFICATIONS: usize = 4;
+
+// Action run when the user clicks the button of a notification
+type NotificationAction = Box<dyn Fn()>;
+
+thread_local! {
+    // Slint models live in the UI thread, so the queue is kept per thread
+    static NOTIFICATIONS: Rc<VecModel<Notification>> = Rc::new(VecModel::default());
+    static NEXT_ID: Cell<i32> = const { Cell::new(0) };
+    static IS_BOUND: Cell<bool> = const { Cell::new(false) };
+    static ACTIONS: RefCell<HashMap<i32, NotificationAction>> = RefCell::new(HashMap::new());
+}
+
+/// Level of a notification, used to pick the toast style
+#[derive(Debug, Clone, Copy)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+}
+
+/// Binds the notification queue to the main window
+///
+/// Must be called from the UI thread before any notification is pushed.
+pub fn bind_notification_center(main_window: &MainFrame) {
+    NOTIFICATIONS.with(|notifications| {
+        main_window.set_notifications(ModelRc::from(notifications.clone()));
+    });
+    IS_BOUND.with(|is_bound| is_bound.set(true));
+
+    main_window.on_dismiss_notification(dismiss_notification);
+    main_window.on_notification_action(run_notification_action);
+}
+
+/// Adds a notification to the queue shown as toasts
+///
+/// Must be called from the UI thread. A message already in the queue is not repeated.
+///
+/// # Arguments
+/// - `level`: The level of the notification.
+/// - `message`: The text shown to the user.
+pub fn push_notification(level: NotificationLevel, message: &str) {
+    push_notification_entry(level, message, None);
+}
+
+/// Adds a notification with an action button to the queue shown as toasts
+///
+/// Must be called from the UI thread. The notification is dismissed once the action runs.
+///
+/// # Arguments
+/// - `level`: The level of the notification.
+/// - `message`: The text shown to the user.
+/// - `label`: The text of the action button.
+/// - `action`: The function run when the user clicks the button.
+pub fn push_notification_with_action(
+    level: NotificationLevel,
+    message: &str,
+    label: &str,
+    action: impl Fn() + 'static,
+) {
+    push_notification_entry(level, message, Some((label, Box::new(action))));
+}
+
+fn push_notification_entry(
+    level: NotificationLevel,
+    message: &str,
+    action: Option<(&str, NotificationAction)>,
+) {
+    if !IS_BOUND.with(|is_bound| is_bound.get()) {
+        return;
+    }
+
+    NOTIFICATIONS.with(|notifications| {
+        if notifications.iter().any(|notification| notification.message == message) {
+            return;
+        }
+
+        if notifications.row_count() >= MAX_NOTIFICATIONS {
+            let oldest = notifications.remove(0);
+            ACTIONS.with(|actions| actions.borrow_mut().remove(&oldest.id));
+        }
+
+        let id = NEXT_ID.with(|next_id| {
+            let id = next_id.get();
+            next_id.set(id.wrapping_add(1));
+            id
+        });
+
+        notifications.push(Notification {
+            id,
+            level: SharedString::from(match level {
+                NotificationLevel::Info => "info",
+                NotificationLevel::Warning => "warning",
+            }),
+            message: SharedString::from(message),
+            action: SharedString::from(action.as_ref().map_or("", |(label, _)| *label)),
+        });
+
+        if let Some((_, action)) = action {
+            ACTIONS.with(|actions| actions.borrow_mut().insert(id, action));
+        }
+    });
+}
+
+/// Removes a notification from the queue when the user dismisses its toast
+fn dismiss_notification(id: i32) {
+    NOTIFICATIONS.with(|notifications| {
+        if let Some(index) = notifications.iter().position(|notification| notification.id == id) {
+            notifications.remove(index);
+        }
+    });
+    ACTIONS.with(|actions| actions.borrow_mut().remove(&id));
+}
+
+/// Runs the action of a notification and dismisses it
+fn run_notification_action(id: i32) {
+    let action = ACTIONS.with(|actions| actions.borrow_mut().remove(&id));
+
+    if let Some(action) = action {
+        action();
+    }
+
+    dismiss_notification(id);
+}
@@ -0,0 +1,114 @@
+/// Charge of the headset battery, in percent, under which the user is warned
+pub const LOW_BATTERY_PERCENT: u8 = 15;
+
+/// Battery charge the headset must get back to before it is warned about again
+const RECHARGED_BATTERY_PERCENT: u8 = LOW_BATTERY_PERCENT + 5;
+
+/// Confidence of the model under which a prediction counts towards a low confidence streak
+pub const LOW_CONFIDENCE: f32 = 0.5;
+
+/// Predictions in a row under `LOW_CONFIDENCE` before the user is warned
+pub const LOW_CONFIDENCE_STREAK: u32 = 20;
+
+/// Warns once when the headset battery runs low, and again only after it was recharged
+///
+/// The margin between both thresholds keeps a charge hovering around the low one from
+/// warning on every heartbeat.
+#[derive(Debug, Default)]
+pub struct LowBatteryWarning {
+    is_warned: bool,
+}
+
+impl LowBatteryWarning {
+    pub const fn new() -> Self {
+        Self { is_warned: false }
+    }
+
+    /// Observes the charge reported by the heartbeat of the core
+    ///
+    /// # Returns
+    /// - `bool`: Whether the user should be warned, the first time the charge is low.
+    pub fn observe(&mut self, battery_percent: Option<u8>) -> bool {
+        match battery_percent {
+            Some(percent) if percent < LOW_BATTERY_PERCENT && !self.is_warned => {
+                self.is_warned = true;
+                true
+            }
+            Some(percent) if percent >= RECHARGED_BATTERY_PERCENT => {
+                self.is_warned = false;
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Counts the predictions in a row the model was not confident of
+#[derive(Debug, Default)]
+pub struct LowConfidenceStreak {
+    streak: u32,
+}
+
+impl LowConfidenceStreak {
+    pub const fn new() -> Self {
+        Self { streak: 0 }
+    }
+
+    /// Observes the confidence of a prediction, a model that does not report it ends the streak
+    ///
+    /// # Returns
+    /// - `bool`: Whether the user should be warned, once per streak when it reaches `LOW_CONFIDENCE_STREAK`.
+    pub fn observe(&mut self, confidence: Option<f32>) -> bool {
+        match confidence {
+            Some(confidence) if confidence < LOW_CONFIDENCE => {
+                self.streak = self.streak.saturating_add(1);
+                self.streak == LOW_CONFIDENCE_STREAK
+            }
+            _ => {
+                self.streak = 0;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_battery_warns_once_until_recharged() {
+        let mut warning = LowBatteryWarning::new();
+
+        assert!(!warning.observe(None));
+        assert!(!warning.observe(Some(LOW_BATTERY_PERCENT)));
+        assert!(warning.observe(Some(LOW_BATTERY_PERCENT - 1)));
+        assert!(!warning.observe(Some(LOW_BATTERY_PERCENT - 2)));
+
+        // Hovering around the threshold does not warn again
+        assert!(!warning.observe(Some(LOW_BATTERY_PERCENT + 1)));
+        assert!(!warning.observe(Some(LOW_BATTERY_PERCENT - 1)));
+
+        assert!(!warning.observe(Some(RECHARGED_BATTERY_PERCENT)));
+        assert!(warning.observe(Some(LOW_BATTERY_PERCENT - 1)));
+    }
+
+    #[test]
+    fn test_low_confidence_streak_warns_once_when_reaching_the_limit() {
+        let mut streak = LowConfidenceStreak::new();
+
+        for _ in 1..LOW_CONFIDENCE_STREAK {
+            assert!(!streak.observe(Some(LOW_CONFIDENCE - 0.1)));
+        }
+        assert!(streak.observe(Some(LOW_CONFIDENCE - 0.1)));
+        assert!(!streak.observe(Some(LOW_CONFIDENCE - 0.1)));
+
+        // A confident prediction, or one without confidence, starts a new streak
+        assert!(!streak.observe(Some(LOW_CONFIDENCE)));
+        for _ in 1..LOW_CONFIDENCE_STREAK {
+            assert!(!streak.observe(Some(0.0)));
+        }
+        assert!(!streak.observe(None));
+        assert!(!streak.observe(Some(0.0)));
+    }
+}
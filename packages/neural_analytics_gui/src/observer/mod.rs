@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+
+use slint::{ComponentHandle, ModelRc, SharedString};
+
+use crate::{
+    utils::{bind_plot_rendering, render_signal_plot},
+    Accessibility, MainFrame, ObserverFrame, PlotRendering,
+};
+
+thread_local! {
+    // Slint windows live in the UI thread, so the observer window is kept per thread
+    static OBSERVER_WINDOW: RefCell<Option<ObserverFrame>> = const { RefCell::new(None) };
+}
+
+/// Checks if the observer window was requested at startup
+///
+/// The observer window is opened with the `--observer` flag or `NEURAL_ANALYTICS_OBSERVER=true`.
+/// It can also be toggled at any time with F11 in the main window.
+pub fn is_observer_requested() -> bool {
+    std::env::args().any(|arg| arg == "--observer")
+        || std::env::var("NEURAL_ANALYTICS_OBSERVER").is_ok_and(|value| value == "true")
+}
+
+/// Shows the observer window, or hides it if it is already shown
+///
+/// Must be called from the UI thread. The window mirrors the live plots of the main
+/// window, so it can be moved to a projector or a second monitor.
+///
+/// # Arguments
+/// - `main_window`: The main window, whose electrode labels and display preferences are copied.
+pub fn toggle_observer_window(main_window: &MainFrame) {
+    OBSERVER_WINDOW.with(|observer| {
+        let mut observer = observer.borrow_mut();
+
+        if observer.is_none() {
+            match ObserverFrame::new() {
+                Ok(window) => {
+                    window.on_render_signal_plot(render_signal_plot);
+                    bind_plot_rendering(&window.global::<PlotRendering>());
+                    *observer = Some(window);
+                }
+                Err(e) => {
+                    eprintln!("Failed to create the observer window: {}", e);
+                    return;
+                }
+            }
+        }
+
+        let Some(window) = observer.as_ref() else {
+            return;
+        };
+
+        let result = if window.window().is_visible() {
+            window.hide()
+        } else {
+            window.set_electrode_labels(main_window.get_electrode_labels());
+
+            // Each window has its own globals, the preferences are the ones of the main window
+            let (accessibility, preferences) = (window.global::<Accessibility>(), main_window.global::<Accessibility>());
+            accessibility.set_high_contrast(preferences.get_high_contrast());
+            accessibility.set_large_text(preferences.get_large_text());
+
+            window.show()
+        };
+
+        if let Err(e) = result {
+            eprintln!("Failed to toggle the observer window: {}", e);
+        }
+    });
+}
+
+/// Mirrors the electrode labels of the main window in the observer window, if open
+pub fn set_observer_electrode_labels(labels: ModelRc<SharedString>) {
+    OBSERVER_WINDOW.with(|observer| {
+        if let Some(window) = observer.borrow().as_ref() {
+            window.set_electrode_labels(labels);
+        }
+    });
+}
+
+/// Mirrors a window of EEG data in the observer window, if open
+pub fn update_observer_headset_data(
+    t3: ModelRc<f32>,
+    t4: ModelRc<f32>,
+    o1: ModelRc<f32>,
+    o2: ModelRc<f32>,
+) {
+    OBSERVER_WINDOW.with(|observer| {
+        if let Some(window) = observer.borrow().as_ref() {
+            window.invoke_update_headset_data(t3, t4, o1, o2);
+        }
+    });
+}
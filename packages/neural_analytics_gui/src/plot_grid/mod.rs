@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
+
+use crate::{ChannelPlot, MainFrame};
+
+// Most channels plotted by the data capture view, the rest of a larger headset is left out
+const MAX_CHANNELS: usize = 16;
+
+thread_local! {
+    // Slint models live in the UI thread, so the plots are kept per thread
+    static PLOTS: Rc<VecModel<ChannelPlot>> = Rc::new(VecModel::default());
+    // Hardware names of the plotted channels, in the order of the plots
+    static CHANNELS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    // Label shown for each channel, its hardware name when missing
+    static LABELS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    // Channels hidden by the user, kept hidden when the grid is laid out again
+    static HIDDEN: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Columns of the plot grid for a number of visible plots
+///
+/// The grid is kept as square as possible, 2 columns up to 4 plots, 3 up to 9 and 4 up to 16.
+pub fn grid_columns(visible: usize) -> usize {
+    (1..).find(|columns| columns * columns >= visible).unwrap_or(1)
+}
+
+/// Binds the plots of the data capture view to the main window
+///
+/// Must be called from the UI thread before any plot is updated.
+pub fn bind_plot_grid(main_window: &MainFrame) {
+    PLOTS.with(|plots| {
+        main_window.set_channel_plots(ModelRc::from(plots.clone()));
+    });
+
+    let main_window_weak = main_window.as_weak();
+    main_window.on_toggle_channel_plot(move |index, is_visible| {
+        if let Some(main_window) = main_window_weak.upgrade() {
+            toggle_channel_plot(&main_window, index as usize, is_visible);
+        }
+    });
+}
+
+/// Lays the grid out for a list of channels, such as the ones of the channel configuration
+///
+/// # Arguments
+/// - `main_window`: The main window showing the plots.
+/// - `channels`: The hardware name and the label of each channel, in the order they are plotted.
+pub fn set_plot_channels(main_window: &MainFrame, channels: Vec<(String, String)>) {
+    LABELS.with(|labels| {
+        let mut labels = labels.borrow_mut();
+        labels.extend(channels.iter().cloned());
+    });
+
+    let channels = channels.into_iter().map(|(channel, _)| channel).take(MAX_CHANNELS).collect();
+    CHANNELS.with(|current| *current.borrow_mut() = channels);
+
+    lay_out(main_window);
+}
+
+/// Updates the plots with a window of data, laying the grid out again when its channels change
+///
+/// The channels of the data keep the order of the current grid, the new ones after them.
+///
+/// # Returns
+/// - `bool`: Whether the plotted channels changed.
+pub fn update_plot_data(main_window: &MainFrame, headset_data: &HashMap<String, Vec<f32>>) -> bool {
+    let channels = CHANNELS.with(|current| {
+        let current = current.borrow();
+        let mut new_channels: Vec<&String> = headset_data.keys().filter(|channel| !current.contains(channel)).collect();
+        new_channels.sort();
+
+        current
+            .iter()
+            .filter(|channel| headset_data.contains_key(*channel))
+            .chain(new_channels)
+            .take(MAX_CHANNELS)
+            .cloned()
+            .collect::<Vec<String>>()
+    });
+
+    let is_changed = CHANNELS.with(|current| *current.borrow() != channels);
+    if is_changed {
+        CHANNELS.with(|current| *current.borrow_mut() = channels.clone());
+        lay_out(main_window);
+    }
+
+    PLOTS.with(|plots| {
+        for (index, channel) in channels.iter().enumerate() {
+            if let (Some(mut plot), Some(values)) = (plots.row_data(index), headset_data.get(channel)) {
+                plot.values = ModelRc::from(&values[..]);
+                plots.set_row_data(index, plot);
+            }
+        }
+    });
+
+    is_changed
+}
+
+/// Hardware names of the plotted channels, in the order of the plots
+pub fn plot_channels() -> Vec<String> {
+    CHANNELS.with(|channels| channels.borrow().clone())
+}
+
+/// Labels of the plotted channels, in the order of the plots
+pub fn plot_labels() -> Vec<String> {
+    plot_channels().iter().map(|channel| label_of(channel)).collect()
+}
+
+fn label_of(channel: &str) -> String {
+    LABELS.with(|labels| labels.borrow().get(channel).cloned().unwrap_or_else(|| channel.to_string()))
+}
+
+/// Shows or hides the plot of a channel when the user toggles it
+///
+/// The last visible plot cannot be hidden, its toggle is checked again instead.
+fn toggle_channel_plot(main_window: &MainFrame, index: usize, is_visible: bool) {
+    let Some(channel) = CHANNELS.with(|channels| channels.borrow().get(index).cloned()) else {
+        return;
+    };
+
+    HIDDEN.with(|hidden| {
+        let mut hidden = hidden.borrow_mut();
+        let visible = CHANNELS.with(|channels| channels.borrow().iter().filter(|channel| !hidden.contains(*channel)).count());
+
+        if is_visible {
+            hidden.remove(&channel);
+        } else if visible > 1 {
+            hidden.insert(channel);
+        }
+    });
+
+    lay_out(main_window);
+}
+
+/// Places every visible plot in the grid, keeping the data of the channels still plotted
+fn lay_out(main_window: &MainFrame) {
+    let channels = plot_channels();
+    let hidden = HIDDEN.with(|hidden| hidden.borrow().clone());
+    let visible = channels.iter().filter(|channel| !hidden.contains(*channel)).count();
+    let columns = grid_columns(visible);
+
+    PLOTS.with(|plots| {
+        let previous: HashMap<SharedString, ModelRc<f32>> =
+            plots.iter().map(|plot| (plot.label.clone(), plot.values.clone())).collect();
+
+        let mut slot = 0;
+        let layout: Vec<ChannelPlot> = channels
+            .iter()
+            .map(|channel| {
+                let label = SharedString::from(label_of(channel));
+                let is_visible = !hidden.contains(channel);
+                let plot = ChannelPlot {
+                    values: previous.get(&label).cloned().unwrap_or_default(),
+                    label,
+                    is_visible,
+                    slot,
+                };
+
+                if is_visible {
+                    slot += 1;
+                }
+                plot
+            })
+            .collect();
+
+        plots.set_vec(layout);
+    });
+
+    main_window.set_plot_columns(columns as i32);
+    main_window.set_plot_rows(visible.div_ceil(columns).max(1) as i32);
+}
@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use neural_analytics_bridge::domain::models::signal_unit::SignalUnit;
+use neural_analytics_bridge::domain::ports::input::eeg_headset::SAMPLE_RATE_HZ;
+use plotters::prelude::*;
+use slint::{Image, SharedPixelBuffer};
+
+use crate::utils::{decimate_min_max, nice_step, plot_style, signal_unit, OFFSET_RANGE_UV};
+
+// Seconds of signal kept on the chart
+const HISTORY_SECS: f32 = 5.0;
+
+// Last samples of each channel, in the order they are stacked from the top
+struct StripHistory {
+    labels: Vec<String>,
+    channels: Vec<VecDeque<f32>>,
+}
+
+static STRIP_HISTORY: Mutex<StripHistory> = Mutex::new(StripHistory {
+    labels: Vec::new(),
+    channels: Vec::new(),
+});
+
+/// Sets the labels of the stacked channels, from the top, forgetting their samples
+pub fn set_strip_labels(labels: Vec<String>) {
+    if let Ok(mut history) = STRIP_HISTORY.lock() {
+        history.channels = vec![VecDeque::new(); labels.len()];
+        history.labels = labels;
+    }
+}
+
+/// Appends a window to the samples of each channel, in the order of their labels
+///
+/// Only the last seconds are kept, so the chart scrolls like a paper strip.
+pub fn push_strip_window(windows: &[Vec<f32>]) {
+    let capacity = (SAMPLE_RATE_HZ * HISTORY_SECS) as usize;
+
+    if let Ok(mut history) = STRIP_HISTORY.lock() {
+        for (channel, window) in history.channels.iter_mut().zip(windows) {
+            channel.extend(window.iter().copied());
+
+            let excess = channel.len().saturating_sub(capacity);
+            channel.drain(..excess);
+        }
+    }
+}
+
+/// Forgets the samples, as the signal of a reconnected headset does not continue them
+pub fn clear_strip_history() {
+    if let Ok(mut history) = STRIP_HISTORY.lock() {
+        history.channels.iter_mut().for_each(VecDeque::clear);
+    }
+}
+
+/// Renders every channel stacked on a shared time axis, as clinical EEG viewers do
+///
+/// Each channel is drawn around its baseline on the same range, so their amplitudes
+/// can be compared, and the latest sample is on the right edge.
+///
+/// # Arguments
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+///
+/// # Returns
+/// * `slint::Image` - Rendered image with the chart
+pub fn render_strip_chart(width: f32, height: f32) -> Image {
+    let width_px = width.round() as u32;
+    let height_px = height.round() as u32;
+
+    let mut pixel_buffer = SharedPixelBuffer::<slint::Rgb8Pixel>::new(width_px, height_px);
+
+    {
+        let root = BitMapBackend::with_buffer(pixel_buffer.make_mut_bytes(), (width_px, height_px))
+            .into_drawing_area();
+
+        let style = plot_style();
+        root.fill(&style.background).unwrap();
+
+        let Ok(history) = STRIP_HISTORY.lock() else {
+            drop(root);
+            return Image::from_rgb8(pixel_buffer);
+        };
+
+        let length = history.channels.iter().map(VecDeque::len).max().unwrap_or_default();
+
+        if length < 2 {
+            drop(root);
+            return Image::from_rgb8(pixel_buffer);
+        }
+
+        // Each channel around its own baseline
+        let centered: Vec<Vec<f32>> = history
+            .channels
+            .iter()
+            .map(|channel| {
+                let baseline = channel.iter().sum::<f32>() / channel.len().max(1) as f32;
+                channel.iter().map(|value| value - baseline).collect()
+            })
+            .collect();
+
+        // Unitless samples have no common range, it follows the largest deviation instead
+        let unit = signal_unit();
+        let half_range = match unit {
+            SignalUnit::Microvolts => OFFSET_RANGE_UV,
+            SignalUnit::Normalized => {
+                let deviation = centered.iter().flatten().fold(0.0f32, |max, value| max.max(value.abs()));
+                nice_step(deviation.max(0.025))
+            }
+        };
+
+        let count = centered.len();
+        let duration = length as f32 / SAMPLE_RATE_HZ;
+
+        let root_area = root
+            .titled(
+                &format!("All channels, ±{} {} each", half_range, unit.symbol()),
+                TextStyle::from(("Open Sans Pro", style.text(20))).color(&style.foreground),
+            )
+            .unwrap();
+
+        // The channels are one unit of the Y axis apart, the first one at the top
+        let mut chart = ChartBuilder::on(&root_area)
+            .margin(10)
+            .set_label_area_size(LabelAreaPosition::Left, style.text(20))
+            .set_label_area_size(LabelAreaPosition::Bottom, style.text(40))
+            .build_cartesian_2d(0f32..duration, -0.5f32..(count as f32 - 0.5))
+            .unwrap();
+
+        chart
+            .configure_mesh()
+            .axis_style(style.foreground.mix(style.axis_opacity))
+            .disable_y_mesh()
+            .x_desc("Time (s)")
+            .x_label_style(
+                ("Open Sans Pro", style.text(15))
+                    .into_text_style(&root_area)
+                    .color(&style.foreground),
+            )
+            .x_label_formatter(&|v| format!("{:.1}", v))
+            .y_label_formatter(&|_| String::new())
+            .draw()
+            .unwrap();
+
+        for (index, (label, samples)) in history.labels.iter().zip(&centered).enumerate() {
+            let position = (count - 1 - index) as f32;
+            // A channel with fewer samples ends with the others, on the right edge
+            let start = (length - samples.len()) as f32 / SAMPLE_RATE_HZ;
+
+            chart
+                .draw_series(LineSeries::new(
+                    decimate_min_max(samples, width_px as usize).into_iter().map(|(x, y)| {
+                        let y = (y / (2.0 * half_range)).clamp(-0.5, 0.5);
+                        (start + x as f32 / SAMPLE_RATE_HZ, position + y)
+                    }),
+                    style.foreground.stroke_width(style.stroke(1)),
+                ))
+                .unwrap();
+
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    label.clone(),
+                    (0.0, position + 0.45),
+                    ("Open Sans Pro", style.text(15)).into_text_style(&root_area).color(&style.foreground),
+                )))
+                .unwrap();
+        }
+    }
+
+    Image::from_rgb8(pixel_buffer)
+}
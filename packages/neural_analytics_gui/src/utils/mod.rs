@@ -1,6 +1,259 @@
+use std::fmt::Write;
+use std::ops::Range;
+use std::sync::Mutex;
+
+use neural_analytics_bridge::domain::models::{display_preferences::DisplayPreferences, signal_unit::SignalUnit};
 use plotters::{prelude::*, style::full_palette::GREY_900};
 use slint::{Image, Model, ModelRc, SharedPixelBuffer, SharedString};
 
+use crate::PlotRendering;
+
+// Unit of the plotted samples, reported by the core when it initializes
+static SIGNAL_UNIT: Mutex<SignalUnit> = Mutex::new(SignalUnit::Normalized);
+
+// Accessibility preferences of the configuration of the core
+static DISPLAY_PREFERENCES: Mutex<DisplayPreferences> = Mutex::new(DisplayPreferences {
+    high_contrast: false,
+    large_text: false,
+});
+
+// Ticks on the Y axis of the signal plots
+const Y_TICKS: usize = 5;
+
+/// Half of the range of the offset plots in microvolts, the same for every channel
+pub(crate) const OFFSET_RANGE_UV: f32 = 100.0;
+
+/// Scale of the Y axis of the signal plots, cycled at runtime with F9
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlotScale {
+    /// Samples in the unit of the headset, the range following the window
+    #[default]
+    Absolute,
+    /// Samples rescaled from 0 to 1 within the window, to compare their shapes only
+    Normalized,
+    /// Samples around the baseline of each channel, on the same fixed range for every
+    /// channel, as the strips of clinical EEG viewers
+    Offset,
+}
+
+impl PlotScale {
+    /// Scale selected after this one
+    pub fn next(self) -> Self {
+        match self {
+            PlotScale::Absolute => PlotScale::Normalized,
+            PlotScale::Normalized => PlotScale::Offset,
+            PlotScale::Offset => PlotScale::Absolute,
+        }
+    }
+
+    /// Description of the Y axis, in the given unit
+    pub fn axis_description(self, unit: SignalUnit) -> String {
+        match self {
+            PlotScale::Absolute => format!("Signal ({})", unit.symbol()),
+            PlotScale::Normalized => "Normalized signal".to_string(),
+            PlotScale::Offset => format!("Offset from baseline ({})", unit.symbol()),
+        }
+    }
+}
+
+// Scale of the Y axis, chosen by the user
+static PLOT_SCALE: Mutex<PlotScale> = Mutex::new(PlotScale::Absolute);
+
+/// Colors and sizes the plots are drawn with, following the display preferences
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PlotStyle {
+    pub background: RGBColor,
+    pub foreground: RGBColor,
+    /// Opacity of the axes, which stay faint unless in high contrast
+    pub axis_opacity: f64,
+    // Factor of the fonts and of the areas holding the labels
+    text_scale: f64,
+    is_high_contrast: bool,
+}
+
+impl PlotStyle {
+    /// Size of a font or of a label area, larger in the large text mode
+    pub fn text(&self, size: u32) -> u32 {
+        (size as f64 * self.text_scale).round() as u32
+    }
+
+    /// Width of a trace, thicker in high contrast
+    pub fn stroke(&self, width: u32) -> u32 {
+        if self.is_high_contrast {
+            width + 1
+        } else {
+            width
+        }
+    }
+}
+
+/// Changes the accessibility preferences the plots are drawn with, from their next window
+pub fn set_display_preferences(preferences: DisplayPreferences) {
+    if let Ok(mut current) = DISPLAY_PREFERENCES.lock() {
+        *current = preferences;
+    }
+}
+
+/// Style of the plots for the display preferences of the core
+pub(crate) fn plot_style() -> PlotStyle {
+    let preferences = DISPLAY_PREFERENCES.lock().map(|preferences| *preferences).unwrap_or_default();
+
+    PlotStyle {
+        background: if preferences.high_contrast { BLACK } else { GREY_900 },
+        foreground: WHITE,
+        axis_opacity: if preferences.high_contrast { 1.0 } else { 0.5 },
+        text_scale: if preferences.large_text { 1.5 } else { 1.0 },
+        is_high_contrast: preferences.high_contrast,
+    }
+}
+
+/// Changes the unit shown on the Y axis of the signal plots
+pub fn set_signal_unit(unit: SignalUnit) {
+    if let Ok(mut current) = SIGNAL_UNIT.lock() {
+        *current = unit;
+    }
+}
+
+/// Unit of the plotted samples, reported by the core
+pub(crate) fn signal_unit() -> SignalUnit {
+    SIGNAL_UNIT.lock().map(|unit| *unit).unwrap_or_default()
+}
+
+/// Selects the next scale of the signal plots, applied from their next window
+///
+/// # Returns
+/// * `String` - Description of the selected scale, to tell the user
+pub fn cycle_plot_scale() -> String {
+    let unit = signal_unit();
+
+    PLOT_SCALE
+        .lock()
+        .map(|mut scale| {
+            *scale = scale.next();
+            scale.axis_description(unit)
+        })
+        .unwrap_or_default()
+}
+
+/// Scales the samples of a window for the plot
+///
+/// # Returns
+/// * `(Vec<f32>, Range<f32>)` - The scaled samples and the range of the Y axis
+fn scale_samples(data: &[f32], scale: PlotScale, unit: SignalUnit) -> (Vec<f32>, Range<f32>) {
+    let min_value = data.iter().copied().fold(f32::INFINITY, f32::min);
+    let max_value = data.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+    match scale {
+        PlotScale::Absolute => {
+            // If the range is too small, set a minimum range around the middle value
+            let range = if max_value - min_value < 0.05 {
+                let mid = (min_value + max_value) * 0.5;
+                (mid - 0.025)..(mid + 0.025)
+            } else {
+                min_value..max_value
+            };
+
+            (data.to_vec(), range)
+        }
+        PlotScale::Normalized => {
+            let span = max_value - min_value;
+            let samples = data
+                .iter()
+                .map(|value| if span > 0.0 { (value - min_value) / span } else { 0.5 })
+                .collect();
+
+            (samples, 0.0..1.0)
+        }
+        PlotScale::Offset => {
+            let baseline = data.iter().sum::<f32>() / data.len() as f32;
+            let samples: Vec<f32> = data.iter().map(|value| value - baseline).collect();
+
+            // Unitless samples have no common range, it follows the largest deviation instead
+            let half_range = match unit {
+                SignalUnit::Microvolts => OFFSET_RANGE_UV,
+                SignalUnit::Normalized => {
+                    let deviation = samples.iter().fold(0.0f32, |max, value| max.max(value.abs())).max(0.025);
+                    let step = nice_step(deviation * 2.0 / Y_TICKS as f32);
+                    (deviation / step).ceil() * step
+                }
+            };
+
+            (samples, -half_range..half_range)
+        }
+    }
+}
+
+/// Reduces a trace to the minimum and maximum of each of `columns` buckets, in order
+///
+/// Keeps the peaks a plain subsampling would skip, while drawing at most two points
+/// per pixel column of the chart.
+///
+/// # Returns
+/// * `Vec<(usize, f32)>` - The kept samples, with their index in the trace
+pub(crate) fn decimate_min_max(values: &[f32], columns: usize) -> Vec<(usize, f32)> {
+    if values.len() <= columns.max(1) * 2 {
+        return values.iter().copied().enumerate().collect();
+    }
+
+    let bucket = values.len().div_ceil(columns);
+    let mut decimated = Vec::with_capacity(columns * 2);
+
+    for (chunk_index, chunk) in values.chunks(bucket).enumerate() {
+        let start = chunk_index * bucket;
+        let (mut low, mut high) = (0, 0);
+
+        for (index, value) in chunk.iter().enumerate() {
+            if *value < chunk[low] {
+                low = index;
+            }
+            if *value > chunk[high] {
+                high = index;
+            }
+        }
+
+        let (first, second) = (low.min(high), low.max(high));
+        decimated.push((start + first, chunk[first]));
+        if second != first {
+            decimated.push((start + second, chunk[second]));
+        }
+    }
+
+    decimated
+}
+
+/// Rounds a step up to 1, 2 or 5 times a power of ten
+pub(crate) fn nice_step(step: f32) -> f32 {
+    let magnitude = 10f32.powf(step.log10().floor());
+
+    match step / magnitude {
+        fraction if fraction <= 1.0 => magnitude,
+        fraction if fraction <= 2.0 => 2.0 * magnitude,
+        fraction if fraction <= 5.0 => 5.0 * magnitude,
+        _ => 10.0 * magnitude,
+    }
+}
+
+/// Formats a tick with the decimals the range needs, e.g. `-50`, `2.5` or `0.02`
+fn format_tick(value: f32, range: &Range<f32>) -> String {
+    let step = nice_step((range.end - range.start).abs().max(f32::EPSILON) / Y_TICKS as f32);
+    // One more decimal than the step, for the ticks plotters places in between
+    let decimals = (1.0 - step.log10().floor()).max(0.0) as usize;
+    let tick = format!("{:.*}", decimals, value);
+
+    let tick = if tick.contains('.') {
+        tick.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        tick
+    };
+
+    // Avoids a "-0" tick on the baseline
+    if tick == "-0" {
+        "0".to_string()
+    } else {
+        tick
+    }
+}
+
 /// Renders a chart to visualize EEG signals
 ///
 /// This function takes EEG signal data and generates an image with a chart
@@ -36,8 +289,9 @@ pub fn render_signal_plot(
         let root = BitMapBackend::with_buffer(pixel_buffer.make_mut_bytes(), (width_px, height_px))
             .into_drawing_area();
 
-        // Draw the background
-        root.fill(&GREY_900).unwrap();
+        // Draw the background, in the colors of the display preferences
+        let style = plot_style();
+        root.fill(&style.background).unwrap();
 
         // Transform data to vector
         let data_vec: Vec<f32> = data.iter().collect();
@@ -47,67 +301,46 @@ pub fn render_signal_plot(
             return Image::from_rgb8(pixel_buffer);
         }
 
-        // Data is already normalized between 0 and 1 from BrainFlowAdapter
-        // But we calculate the current range to improve visualization
-        let normalized_data = data_vec.clone();
-
-        // Calculate current min and max values to dynamically adjust the Y range
-        let min_value = normalized_data
-            .iter()
-            .cloned()
-            .fold(f32::INFINITY, f32::min);
-        let max_value = normalized_data
-            .iter()
-            .cloned()
-            .fold(f32::NEG_INFINITY, f32::max);
+        // Data is in the unit reported by the headset, scaled as the user chose
+        let unit = signal_unit();
+        let scale = PLOT_SCALE.lock().map(|scale| *scale).unwrap_or_default();
+        let (normalized_data, y_range) = scale_samples(&data_vec, scale, unit);
 
         // Add a small margin for better visualization
-        let margin = (max_value - min_value) * 0.0001;
-
-        // Ensure the range is within [0,1] (normalized data)
-        let display_min = (min_value - margin).max(0.0);
-        let display_max = (max_value + margin).min(1.0);
-
-        // If the range is too small, set a minimum range for visualization
-        let (final_min, final_max) = if (display_max - display_min).abs() < 0.05 {
-            // Center a minimum range around the middle value
-            let mid = (display_min + display_max) * 0.5;
-            let half_range = 0.025;
-            ((mid - half_range).max(0.0), (mid + half_range).min(1.0))
-        } else {
-            (display_min, display_max)
-        };
+        let margin = (y_range.end - y_range.start) * 0.0001;
+        let (final_min, final_max) = (y_range.start - margin, y_range.end + margin);
 
         // Draw the title
         let root_area = root
             .titled(
                 name.as_str(),
-                TextStyle::from(("Open Sans Pro", 20)).color(&WHITE),
+                TextStyle::from(("Open Sans Pro", style.text(20))).color(&style.foreground),
             )
             .unwrap();
 
         // Draw the chart
         let mut chart = ChartBuilder::on(&root_area)
             .margin(10)
-            .set_label_area_size(LabelAreaPosition::Left, 50)
-            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .set_label_area_size(LabelAreaPosition::Left, style.text(50))
+            .set_label_area_size(LabelAreaPosition::Bottom, style.text(40))
             .build_cartesian_2d(1..(normalized_data.len()), final_min..final_max)
             .unwrap();
 
         chart
             .configure_mesh()
-            .axis_style(WHITE.mix(0.5))
+            .axis_style(style.foreground.mix(style.axis_opacity))
             .x_desc("Timeseries")
-            .y_desc("Signal Value")
+            .y_desc(scale.axis_description(unit))
+            .y_labels(Y_TICKS)
             .x_label_style(
-                ("Open Sans Pro", 15)
+                ("Open Sans Pro", style.text(15))
                     .into_text_style(&root_area)
-                    .color(&WHITE),
+                    .color(&style.foreground),
             )
             .y_label_style(
-                ("Open Sans Pro", 15)
+                ("Open Sans Pro", style.text(15))
                     .into_text_style(&root_area)
-                    .color(&WHITE),
+                    .color(&style.foreground),
             ) // Estilo de ejes semitransparente
             .x_label_formatter(&|v| {
                 // Calculamos mod_value asegurándonos de que nunca sea 0
@@ -118,15 +351,17 @@ pub fn render_signal_plot(
                     "".to_string()
                 }
             })
-            .y_label_formatter(&|v| format!("{:.1}", v))
+            .y_label_formatter(&|v| format_tick(*v, &y_range))
             .draw()
             .unwrap();
 
         // Draw the data in the chart
         chart
             .draw_series(LineSeries::new(
-                normalized_data.iter().enumerate().map(|(x, &y)| (x + 1, y)),
-                WHITE.stroke_width(2),
+                decimate_min_max(&normalized_data, width_px as usize)
+                    .into_iter()
+                    .map(|(x, y)| (x + 1, y)),
+                style.foreground.stroke_width(style.stroke(2)),
             ))
             .unwrap();
 
@@ -143,7 +378,7 @@ pub fn render_signal_plot(
                         .step_by(step_size)
                         .map(|(x, &y)| (x + 1, y)),
                     4,
-                    ShapeStyle::from(&WHITE).filled(),
+                    ShapeStyle::from(&style.foreground).filled(),
                     &|coord, size, style| {
                         EmptyElement::at(coord) + Circle::new((0, 0), size, style)
                     },
@@ -154,3 +389,64 @@ pub fn render_signal_plot(
 
     Image::from_rgb8(pixel_buffer)
 }
+
+/// Draws the signal plots as vector paths when the GUI is built with `vector-plots`
+///
+/// The paths are rendered by Slint, on the GPU with the Skia renderer, where the pixel
+/// buffers of plotters fall behind at high refresh rates on HiDPI screens. Without the
+/// feature the plots keep the plotters path, which also draws the full axes.
+///
+/// # Arguments
+/// * `plot_rendering` - Global of the window the plots are drawn in, each window having its own
+pub fn bind_plot_rendering(plot_rendering: &PlotRendering) {
+    if !cfg!(feature = "vector-plots") {
+        return;
+    }
+
+    let style = plot_style();
+    let color = |rgb: RGBColor| slint::Color::from_rgb_u8(rgb.0, rgb.1, rgb.2);
+
+    plot_rendering.set_background(color(style.background));
+    plot_rendering.set_foreground(color(style.foreground));
+    plot_rendering.set_stroke_width(style.stroke(2) as f32);
+    plot_rendering.on_trace_path(render_trace_path);
+    plot_rendering.on_range_labels(render_range_labels);
+    plot_rendering.set_is_vector(true);
+}
+
+/// SVG commands of the trace of a window, for the vector plots
+///
+/// The samples are scaled and decimated as in the plotters path, in a viewbox as wide
+/// as the window and one unit high, the top of the Y axis at 0.
+fn render_trace_path(data: ModelRc<f32>, width: f32) -> SharedString {
+    let data_vec: Vec<f32> = data.iter().collect();
+    if data_vec.is_empty() {
+        return SharedString::default();
+    }
+
+    let scale = PLOT_SCALE.lock().map(|scale| *scale).unwrap_or_default();
+    let (samples, y_range) = scale_samples(&data_vec, scale, signal_unit());
+    let span = (y_range.end - y_range.start).max(f32::EPSILON);
+
+    let mut commands = String::new();
+    for (point, (x, y)) in decimate_min_max(&samples, width.round().max(1.0) as usize).into_iter().enumerate() {
+        let command = if point == 0 { 'M' } else { 'L' };
+        let _ = write!(commands, "{} {} {:.4} ", command, x, (y_range.end - y) / span);
+    }
+
+    commands.trim_end().into()
+}
+
+/// Ticks of the top and the bottom of the Y axis of a window, for the vector plots
+fn render_range_labels(data: ModelRc<f32>) -> ModelRc<SharedString> {
+    let data_vec: Vec<f32> = data.iter().collect();
+    if data_vec.is_empty() {
+        return ModelRc::from(&[SharedString::default(), SharedString::default()][..]);
+    }
+
+    let scale = PLOT_SCALE.lock().map(|scale| *scale).unwrap_or_default();
+    let (_, y_range) = scale_samples(&data_vec, scale, signal_unit());
+    let labels = [y_range.end, y_range.start].map(|value| SharedString::from(format_tick(value, &y_range)));
+
+    ModelRc::from(&labels[..])
+}
@@ -3,6 +3,17 @@ use std::path::Path;
 use std::fs;
 use std::io;
 
+// Builds the model with Python even without the `build-model` feature, e.g. in CI
+const BUILD_MODEL_VARIABLE: &str = "NEURAL_ANALYTICS_BUILD_MODEL";
+
+// Model built elsewhere, bundled instead of building one
+const PREBUILT_MODEL_VARIABLE: &str = "NEURAL_ANALYTICS_PREBUILT_MODEL";
+
+fn is_model_build_enabled() -> bool {
+    std::env::var_os("CARGO_FEATURE_BUILD_MODEL").is_some()
+        || std::env::var(BUILD_MODEL_VARIABLE).is_ok_and(|value| value == "true")
+}
+
 fn install_requirements(project_root: &Path) {
     let requirements_path = project_root.join("requirements.txt");
     
@@ -42,7 +53,7 @@ fn build_model(script_path: &Path, build_file_path: &Path) -> io::Result<()> {
                 "[!] Python script error: {}",
                 String::from_utf8_lossy(&output.stderr)
             );
-            return Err(io::Error::new(io::ErrorKind::Other, "Python script failed"));
+            return Err(io::Error::other("Python script failed"));
         }
 
         println!(
@@ -71,20 +82,69 @@ fn copy_model_assets(build_file_path: &Path, project_root: &Path) -> io::Result<
         .map_err(|e| io::Error::new(e.kind(), "[!] Error copying file"))?;
 
     println!("[*] File copied to: {:?}", target_path);
+
+    // The label map names the classes of the model, older models have none
+    let labels_path = build_file_path.with_extension("labels.json");
+    if labels_path.exists() {
+        let target_labels_path = target_dir.join("neural_analytics.labels.json");
+        fs::copy(&labels_path, &target_labels_path)
+            .map_err(|e| io::Error::new(e.kind(), "[!] Error copying label map"))?;
+
+        println!("[*] File copied to: {:?}", target_labels_path);
+    }
+
+    // The preprocessing spec tells the core how the model was trained, older models have none
+    let spec_path = build_file_path.with_extension("preprocessing.json");
+    if spec_path.exists() {
+        let target_spec_path = target_dir.join("neural_analytics.preprocessing.json");
+        fs::copy(&spec_path, &target_spec_path)
+            .map_err(|e| io::Error::new(e.kind(), "[!] Error copying preprocessing spec"))?;
+
+        println!("[*] File copied to: {:?}", target_spec_path);
+    }
+
     Ok(())
 }
 
 fn main() {
     let project_root = Path::new(env!("CARGO_MANIFEST_DIR"));
-    
-    install_requirements(&project_root);
-
-    let script_path = project_root.join("src/main.py");
     let build_file_path = project_root.join("build/neural_analytics.onnx");
 
-    build_model(&script_path, &build_file_path)
-        .expect("[!] Failed to build model");
-        
+    println!("cargo:rerun-if-env-changed={}", BUILD_MODEL_VARIABLE);
+    println!("cargo:rerun-if-env-changed={}", PREBUILT_MODEL_VARIABLE);
+    // Same as the default of cargo, so a model built by hand in `build/` gets bundled
+    println!("cargo:rerun-if-changed={}", project_root.display());
+
+    // A model built elsewhere needs neither Python nor the dataset
+    if let Some(prebuilt_path) = std::env::var_os(PREBUILT_MODEL_VARIABLE) {
+        let prebuilt_path = Path::new(&prebuilt_path);
+
+        if !prebuilt_path.exists() {
+            panic!("[!] Prebuilt model {:?} not found", prebuilt_path);
+        }
+
+        println!("cargo:rerun-if-changed={}", prebuilt_path.display());
+        copy_model_assets(prebuilt_path, project_root)
+            .expect("[!] Failed to copy model assets");
+        return;
+    }
+
+    if is_model_build_enabled() {
+        install_requirements(project_root);
+
+        let script_path = project_root.join("src/main.py");
+
+        build_model(&script_path, &build_file_path)
+            .expect("[!] Failed to build model");
+    } else if !build_file_path.exists() {
+        // The core runs without inference until a model is placed in the assets
+        println!(
+            "cargo:warning=No model bundled, enable the build-model feature, set {}=true or {} to a built model",
+            BUILD_MODEL_VARIABLE, PREBUILT_MODEL_VARIABLE
+        );
+        return;
+    }
+
     copy_model_assets(&build_file_path, project_root)
         .expect("[!] Failed to copy model assets");
 }
\ No newline at end of file
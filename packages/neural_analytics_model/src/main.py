@@ -19,6 +19,7 @@ from utils.evaluation import evaluate_model, save_training_curves
 
 from datasets.neural_analytics import NeuralAnalyticsDataset
 from models.neural_analytics import NeuralAnalyticsModel
+from preprocessors.neural_analytics import CLASS_LABELS, PREPROCESSING_SPEC
 from sklearn.model_selection import train_test_split
 
 from torch.utils.data import DataLoader
@@ -28,7 +29,7 @@ import os
 import torch
 
 BATCH_SIZE = 64
-WINDOW_SIZE = 62
+WINDOW_SIZE = PREPROCESSING_SPEC['window_samples']
 DATASET_FOLDER = os.path.join(os.getcwd(), '../' 'dataset')
 
 def main():
@@ -81,8 +82,10 @@ def main():
     export_model(
         model,
         device,
-        input_size=(1, WINDOW_SIZE, 4),
-        output_path='../build/neural_analytics.onnx'
+        input_size=(1, WINDOW_SIZE, len(PREPROCESSING_SPEC['channels'])),
+        output_path='../build/neural_analytics.onnx',
+        class_labels=CLASS_LABELS,
+        preprocessing_spec=PREPROCESSING_SPEC
     )
 
     # Close the training log
@@ -14,13 +14,15 @@
 # along with this program.  If not, see <https://www.gnu.org/licenses/>.
 import torch.nn as nn
 
+from preprocessors.neural_analytics import CLASS_LABELS
+
 INPUT_SIZE = 4  # Number of features in the input (T3, T4, O1, O2)
 HIDDEN_SIZE = 64  # Size of hidden units in the LSTM
-NUM_CLASSES = 3  # Number of classification categories (RED, GREEN, UNKNOWN)
+NUM_CLASSES = len(CLASS_LABELS)  # Number of classification categories
 
 class NeuralAnalyticsModel(nn.Module):
     # Mapping from index to class label
-    class_mapping = {0: 'RED', 1: 'GREEN', 2: 'TRASH'}
+    class_mapping = {index: label.upper() for index, label in enumerate(CLASS_LABELS)}
 
     def __init__(self):
         super(NeuralAnalyticsModel, self).__init__()
@@ -16,13 +16,37 @@
 import os
 import pandas as pd
 import numpy as np
-from sklearn.preprocessing import MinMaxScaler
+from sklearn.preprocessing import MinMaxScaler, StandardScaler
+
+# Classes of the model, in the order of its outputs. Exported next to the model as its
+# label map, so adding a class here is enough for the pipeline to predict it.
+CLASS_LABELS = ["red", "green", "trash"]
+
+# Preprocessing of the windows, exported next to the model as its preprocessing spec,
+# which the core reads to preprocess the windows the same way. Bump the version when a
+# key is renamed, removed or changes meaning, the core refuses the versions it does not know.
+PREPROCESSING_SPEC = {
+    "version": 1,
+    # Normalization of each channel of a window: "min_max" or "z_score"
+    "normalization": "min_max",
+    # Samples of each channel in a window
+    "window_samples": 62,
+    # Channels of the windows, in the order of the input of the model
+    "channels": ["T3", "T4", "O1", "O2"],
+}
 
 def normalize_features(df: pd.DataFrame, features: list) -> pd.DataFrame:
     """
-    Globally normalizes the specified columns using MinMaxScaler.
+    Normalizes the specified columns with the normalization of PREPROCESSING_SPEC.
+
+    The core only sees a window at a time, so the windows are normalized one by one,
+    never the whole recording.
     """
-    scaler = MinMaxScaler()
+    if PREPROCESSING_SPEC["normalization"] == "min_max":
+        scaler = MinMaxScaler()
+    else:
+        scaler = StandardScaler()
+
     df_norm = df.copy()
     df_norm[features] = scaler.fit_transform(df_norm[features])
     # print("[*] Feature normalization completed for:", features)
@@ -31,46 +55,41 @@ def normalize_features(df: pd.DataFrame, features: list) -> pd.DataFrame:
 def get_class_label_from_path(file: str) -> str:
     """
     Gets the class label from the folder path.
-    Looks for 'red', 'green' or 'trash' in the path (in lowercase).
+    Looks for one of the CLASS_LABELS in the path (in lowercase).
     """
     file_lower = file.lower()
-    if "red" in file_lower:
-        return "red"
-    elif "green" in file_lower:
-        return "green"
-    elif "trash" in file_lower:
-        return "trash"
-    else:
-        return "unknown"
+    for class_label in CLASS_LABELS:
+        if class_label in file_lower:
+            return class_label
+
+    return "unknown"
 
 def onehot_encode_class_label(class_label: str) -> np.ndarray:
     """
-    Converts the class label to a one-hot vector.
-    Mapping:
-      - 'red'   -> [1, 0, 0]
-      - 'green' -> [0, 1, 0]
-      - 'trash' -> [0, 0, 1]
+    Converts the class label to a one-hot vector, with a position per class of
+    CLASS_LABELS, e.g. 'green' -> [0, 1, 0].
     """
-    mapping = {
-        "red": [1, 0, 0],
-        "green": [0, 1, 0],
-        "trash": [0, 0, 1],
-    }
-    return np.array(mapping.get(class_label, [0, 0]))
+    encoded = np.zeros(len(CLASS_LABELS), dtype=int)
+    if class_label in CLASS_LABELS:
+        encoded[CLASS_LABELS.index(class_label)] = 1
+
+    return encoded
 
 def create_present_sliding_windows(df: pd.DataFrame, window_size: int, class_label: str) -> pd.DataFrame:
     """
     Creates sliding windows from the dataset.
 
-    Each window is a consecutive sequence (of size window_size) of columns T3, T4, O1 and O2.
+    Each window is a consecutive sequence (of size window_size) of the channels of
+    PREPROCESSING_SPEC, normalized on its own as the core does.
     Each window is assigned the label (one-hot encoded) obtained from the folder path.
     """
-    feature_cols = ['T3', 'T4', 'O1', 'O2']
-    df = normalize_features(df, feature_cols)
+    feature_cols = PREPROCESSING_SPEC["channels"]
+    df = df.reset_index(drop=True)
     
     windows = []
     for i in range(len(df) - window_size + 1):
-        window_features = df.loc[i:i + window_size - 1, feature_cols].values
+        window = normalize_features(df.loc[i:i + window_size - 1, feature_cols], feature_cols)
+        window_features = window.values
         encoded_label = onehot_encode_class_label(class_label)
         windows.append((window_features, encoded_label))
 
@@ -95,7 +114,7 @@ def neural_analytics_preprocessor(file: str, window_size: int) -> pd.DataFrame:
     """
     # Read the CSV and filter the required columns.
     df = pd.read_csv(file, on_bad_lines='skip', delimiter=",", low_memory=False)
-    required_cols = ['T3', 'T4', 'O1', 'O2']
+    required_cols = PREPROCESSING_SPEC["channels"]
     df = df.dropna(subset=required_cols)
     # print("[*] View of preprocessed dataset:")
     # print(df.head())
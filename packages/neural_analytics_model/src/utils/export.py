@@ -15,9 +15,10 @@
 
 import torch
 import onnx
+import json
 import os
 
-def export_model(model, device, input_size, output_path):
+def export_model(model, device, input_size, output_path, class_labels, preprocessing_spec):
     """
     Exports a PyTorch model to ONNX format and simplifies the ONNX model.
 
@@ -25,6 +26,8 @@ def export_model(model, device, input_size, output_path):
     :param device: PyTorch device being used for training.
     :param input_size: Input size of the model (e.g., (batch_size, channels, height, width)).
     :param output_path: Path where the ONNX model will be saved.
+    :param class_labels: Classes of the outputs of the model, saved as its label map.
+    :param preprocessing_spec: Preprocessing of the windows of the model, saved as its preprocessing spec.
     """
     # Set the model to evaluation mode
     model.eval()
@@ -58,4 +61,18 @@ def export_model(model, device, input_size, output_path):
     # Save the simplified model
     onnx.save(model_onnx, output_path)
 
-    print(f'[*] Model exported and simplified to: {output_path}')
\ No newline at end of file
+    print(f'[*] Model exported and simplified to: {output_path}')
+
+    # Save the label map next to the model, read by the core to name the outputs
+    labels_path = os.path.splitext(output_path)[0] + '.labels.json'
+    with open(labels_path, 'w') as labels_file:
+        json.dump(class_labels, labels_file)
+
+    print(f'[*] Label map exported to: {labels_path}')
+
+    # Save the preprocessing spec next to the model, read by the core to preprocess the windows
+    spec_path = os.path.splitext(output_path)[0] + '.preprocessing.json'
+    with open(spec_path, 'w') as spec_file:
+        json.dump(preprocessing_spec, spec_file)
+
+    print(f'[*] Preprocessing spec exported to: {spec_path}')
\ No newline at end of file
@@ -0,0 +1,329 @@
+//! Signal math of the pipeline, shared by every build of the core.
+//!
+//! Pure functions over slices, needing neither the standard library nor an allocator, so
+//! the embedded ports and the WASM build run the exact same math as the desktop pipeline.
+//! The floating point functions come from `libm` on every target, so a window is rounded
+//! the same whatever runs it.
+//!
+//! # Numerical behavior
+//! * Sums add the samples in order, in `f32`, as the training preprocessing does.
+//! * Empty slices are left untouched, and their statistics are `0`.
+//! * Non finite values propagate: a window holding a NaN or an infinity normalizes to
+//!   NaNs, and so does the softmax of logits holding one.
+
+#![no_std]
+
+/// Added to the standard deviation by `normalize`, so a flat channel normalizes to zeros
+/// instead of dividing by zero.
+pub const NORMALIZATION_EPSILON: f32 = 1e-6;
+
+/// Arithmetic mean of the values, `0` for an empty slice.
+pub fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+/// Population standard deviation of the values, `0` for an empty slice.
+pub fn std_dev(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let mean = mean(values);
+    let variance = values.iter().map(|value| (value - mean) * (value - mean)).sum::<f32>() / values.len() as f32;
+
+    libm::sqrtf(variance)
+}
+
+/// Normalizes the values to a zero mean and a unit standard deviation, as in training.
+///
+/// The standard deviation is offset by `NORMALIZATION_EPSILON`, so the result of a flat
+/// channel is all zeros and the one of a noisy channel has a deviation slightly under 1.
+pub fn normalize(values: &mut [f32]) {
+    let mean = mean(values);
+    let std_dev = std_dev(values);
+
+    for value in values.iter_mut() {
+        *value = (*value - mean) / (std_dev + NORMALIZATION_EPSILON);
+    }
+}
+
+/// Rescales the values to the `[0, 1]` range, as the `MinMaxScaler` of the training does.
+///
+/// A flat channel rescales to zeros, and a channel holding a non finite value to NaNs.
+pub fn min_max_normalize(values: &mut [f32]) {
+    let (Some(min), Some(max)) = (values.iter().copied().reduce(f32::min), values.iter().copied().reduce(f32::max)) else {
+        return;
+    };
+
+    if values.iter().any(|value| !value.is_finite()) {
+        values.fill(f32::NAN);
+        return;
+    }
+
+    let range = if max > min { max - min } else { 1.0 };
+    for value in values.iter_mut() {
+        *value = (*value - min) / range;
+    }
+}
+
+/// Multiplies the values by a gain, such as the correction of a channel or a unit conversion.
+pub fn scale(values: &mut [f32], gain: f32) {
+    values.iter_mut().for_each(|value| *value *= gain);
+}
+
+/// Copies the samples of a channel into a window of a fixed length.
+///
+/// The samples beyond the window are dropped, and a window longer than the samples is
+/// padded with the last of them, or with zeros when there is none.
+pub fn fit_window(samples: &[f32], window: &mut [f32]) {
+    let copied = samples.len().min(window.len());
+    window[..copied].copy_from_slice(&samples[..copied]);
+
+    let padding = samples.last().copied().unwrap_or(0.0);
+    window[copied..].fill(padding);
+}
+
+/// Interleaves the windows of the channels into time steps, the layout the model takes.
+///
+/// The output holds `output.len() / channels.len()` time steps, each with the sample of
+/// every channel in order: `[c0_0, c1_0, ..., c0_1, c1_1, ...]`. Channels shorter than the
+/// time steps leave the rest of the output untouched.
+pub fn interleave(channels: &[&[f32]], output: &mut [f32]) {
+    if channels.is_empty() {
+        return;
+    }
+
+    for (step, samples) in output.chunks_exact_mut(channels.len()).enumerate() {
+        for (sample, channel) in samples.iter_mut().zip(channels) {
+            if let Some(value) = channel.get(step) {
+                *sample = *value;
+            }
+        }
+    }
+}
+
+/// Turns the logits of the model into probabilities summing to 1.
+///
+/// The largest logit is subtracted before the exponential, so large logits do not
+/// overflow. Probabilities too small for an `f32` round to 0.
+pub fn softmax(values: &mut [f32]) {
+    let Some(max) = values.iter().copied().reduce(f32::max) else {
+        return;
+    };
+
+    let mut sum = 0.0;
+    for value in values.iter_mut() {
+        *value = libm::expf(*value - max);
+        sum += *value;
+    }
+
+    values.iter_mut().for_each(|value| *value /= sum);
+}
+
+/// Power of the samples within a frequency band, by Welch's method.
+///
+/// The samples are split in segments of `segment_len` samples overlapping by half, each
+/// one centered on its mean and tapered by a Hann window, and the one-sided power spectral
+/// densities of the segments are averaged. The power is the sum of the density over the
+/// bins within `[low_hz, high_hz)`, times the width of a bin, `sample_rate / segment_len`.
+/// Samples shorter than a segment are taken as a single one, and fewer than two samples
+/// have no power.
+///
+/// The bins are computed by a direct DFT, as the EEG bands only span a few of them.
+pub fn band_power(samples: &[f32], sample_rate: f32, segment_len: usize, low_hz: f32, high_hz: f32) -> f32 {
+    let segment_len = segment_len.min(samples.len());
+    if segment_len < 2 || sample_rate <= 0.0 {
+        return 0.0;
+    }
+
+    let length = segment_len as f32;
+    let bin_width = sample_rate / length;
+    let hann = |n: usize| 0.5 - 0.5 * libm::cosf(2.0 * core::f32::consts::PI * n as f32 / length);
+    let window_power: f32 = (0..segment_len).map(|n| hann(n) * hann(n)).sum();
+
+    let mut total = 0.0;
+    let mut segments = 0;
+    for start in (0..=samples.len() - segment_len).step_by((segment_len / 2).max(1)) {
+        let segment = &samples[start..start + segment_len];
+        let mean = mean(segment);
+
+        for bin in 0..=segment_len / 2 {
+            let frequency = bin as f32 * bin_width;
+            if frequency < low_hz || frequency >= high_hz {
+                continue;
+            }
+
+            let (mut real, mut imaginary) = (0.0, 0.0);
+            for (n, sample) in segment.iter().enumerate() {
+                let angle = 2.0 * core::f32::consts::PI * (bin * n % segment_len) as f32 / length;
+                let value = (sample - mean) * hann(n);
+                real += value * libm::cosf(angle);
+                imaginary -= value * libm::sinf(angle);
+            }
+
+            // Every bin but the DC and the Nyquist ones stands for its negative frequency too
+            let is_edge = bin == 0 || 2 * bin == segment_len;
+            let density = (real * real + imaginary * imaginary) / (sample_rate * window_power);
+            total += if is_edge { density } else { 2.0 * density } * bin_width;
+        }
+
+        segments += 1;
+    }
+
+    total / segments as f32
+}
+
+/// Index of the largest value, the first one on ties, `None` for an empty slice.
+///
+/// NaNs are never the largest, unless the first value is one and nothing is larger.
+pub fn argmax(values: &[f32]) -> Option<usize> {
+    let mut max_index = 0;
+
+    for (index, value) in values.iter().enumerate().skip(1) {
+        if *value > values[max_index] {
+            max_index = index;
+        }
+    }
+
+    (!values.is_empty()).then_some(max_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{} is not {}", actual, expected);
+    }
+
+    #[test]
+    fn test_statistics_of_empty_and_constant_slices() {
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(std_dev(&[]), 0.0);
+        assert_eq!(mean(&[2.0, 4.0, 6.0]), 4.0);
+        assert_eq!(std_dev(&[3.0; 8]), 0.0);
+        assert_close(std_dev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), 2.0);
+    }
+
+    #[test]
+    fn test_normalize_centers_and_scales() {
+        let mut values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        normalize(&mut values);
+
+        assert_close(mean(&values), 0.0);
+        assert_close(std_dev(&values), 1.0);
+        assert_close(values[0], -1.5);
+
+        // Flat channels normalize to zeros, empty ones are left alone
+        let mut flat = [7.5; 4];
+        normalize(&mut flat);
+        assert_eq!(flat, [0.0; 4]);
+        normalize(&mut []);
+
+        let mut broken = [1.0, f32::NAN, 3.0];
+        normalize(&mut broken);
+        assert!(broken.iter().all(|value| value.is_nan()));
+    }
+
+    #[test]
+    fn test_min_max_normalize_rescales_to_the_unit_range() {
+        let mut values = [2.0, 4.0, 6.0];
+        min_max_normalize(&mut values);
+        assert_eq!(values, [0.0, 0.5, 1.0]);
+
+        let mut flat = [7.5; 4];
+        min_max_normalize(&mut flat);
+        assert_eq!(flat, [0.0; 4]);
+        min_max_normalize(&mut []);
+
+        let mut broken = [1.0, f32::INFINITY, 3.0];
+        min_max_normalize(&mut broken);
+        assert!(broken.iter().all(|value| value.is_nan()));
+    }
+
+    #[test]
+    fn test_band_power_finds_the_power_of_a_sine_in_its_band() {
+        // Two seconds of a 10 Hz sine of amplitude 2 at 250 Hz, whose power is 2
+        let mut samples = [0.0; 500];
+        for (n, sample) in samples.iter_mut().enumerate() {
+            *sample = 1.0 + 2.0 * libm::sinf(2.0 * core::f32::consts::PI * 10.0 * n as f32 / 250.0);
+        }
+
+        let alpha = band_power(&samples, 250.0, 250, 8.0, 13.0);
+        assert!((alpha - 2.0).abs() < 0.1, "{}", alpha);
+        assert!(band_power(&samples, 250.0, 250, 0.5, 4.0) < 1e-3);
+        assert!(band_power(&samples, 250.0, 250, 13.0, 30.0) < 1e-3);
+
+        assert_eq!(band_power(&samples[..1], 250.0, 250, 0.0, 125.0), 0.0);
+    }
+
+    #[test]
+    fn test_scale_and_fit_window() {
+        let mut values = [1.0, -2.0];
+        scale(&mut values, 0.5);
+        assert_eq!(values, [0.5, -1.0]);
+
+        let mut window = [9.0; 4];
+        fit_window(&[1.0, 2.0], &mut window);
+        assert_eq!(window, [1.0, 2.0, 2.0, 2.0]);
+
+        fit_window(&[1.0, 2.0, 3.0, 4.0, 5.0], &mut window);
+        assert_eq!(window, [1.0, 2.0, 3.0, 4.0]);
+
+        fit_window(&[], &mut window);
+        assert_eq!(window, [0.0; 4]);
+    }
+
+    #[test]
+    fn test_interleave_lays_out_time_steps() {
+        let mut output = [0.0; 6];
+        interleave(&[&[1.0, 2.0, 3.0], &[10.0, 20.0, 30.0]], &mut output);
+        assert_eq!(output, [1.0, 10.0, 2.0, 20.0, 3.0, 30.0]);
+
+        let mut output = [-1.0; 4];
+        interleave(&[&[1.0], &[10.0]], &mut output);
+        assert_eq!(output, [1.0, 10.0, -1.0, -1.0]);
+
+        interleave(&[], &mut output);
+        assert_eq!(output, [1.0, 10.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_softmax_is_stable_and_sums_to_one() {
+        let mut probabilities = [1.0, 2.0, 3.0];
+        softmax(&mut probabilities);
+        assert_close(probabilities.iter().sum(), 1.0);
+        assert_close(probabilities[2], 0.665_240_9);
+        assert!(probabilities[0] < probabilities[1] && probabilities[1] < probabilities[2]);
+
+        // Shifted logits give the same probabilities, without overflowing
+        let mut shifted = [1001.0, 1002.0, 1003.0];
+        softmax(&mut shifted);
+        for (shifted, probability) in shifted.iter().zip(probabilities) {
+            assert_close(*shifted, probability);
+        }
+
+        let mut underflow = [0.0, -200.0];
+        softmax(&mut underflow);
+        assert_eq!(underflow, [1.0, 0.0]);
+
+        let mut infinite = [f32::INFINITY, 0.0];
+        softmax(&mut infinite);
+        assert!(infinite[0].is_nan());
+
+        softmax(&mut []);
+    }
+
+    #[test]
+    fn test_argmax_takes_the_first_largest() {
+        assert_eq!(argmax(&[]), None);
+        assert_eq!(argmax(&[0.2, 0.5, 0.3]), Some(1));
+        assert_eq!(argmax(&[0.4, 0.4, 0.2]), Some(0));
+        assert_eq!(argmax(&[0.1, f32::NAN, 0.3]), Some(2));
+        assert_eq!(argmax(&[f32::NAN, 0.3]), Some(0));
+    }
+}